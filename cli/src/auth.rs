@@ -0,0 +1,70 @@
+//! The token commands are sent with. The services reject anonymous calls
+//! to anything but public reads and sign-up, so every call carries a token
+//! naming who made it.
+
+use crate::failed;
+use anyhow::{Context, Result, bail};
+use common::auth::{self, Role};
+use proto::user::{LoginRequest, user_service_client::UserServiceClient};
+use std::env;
+use tonic::metadata::{Ascii, MetadataValue};
+use tonic::service::Interceptor;
+use tonic::transport::Channel;
+use tonic::{Request, Status};
+
+/// Subject of the tokens the CLI signs itself.
+const CLI_SUBJECT: &str = "cli";
+
+/// Adds the `authorization` header to every call of a client.
+#[derive(Clone)]
+pub struct Credentials {
+    header: MetadataValue<Ascii>,
+}
+
+impl Credentials {
+    fn bearer(token: &str) -> Result<Self> {
+        Ok(Self {
+            header: format!("Bearer {}", token)
+                .parse()
+                .context("The token isn't a valid header value")?,
+        })
+    }
+
+    /// Credentials from, in order: `token`, a login of `login` with the
+    /// password in `ADMIN_PASSWORD`, or an admin token signed with
+    /// `JWT_SECRET` for operators holding it, e.g. to create the first admin.
+    pub async fn resolve(token: Option<&str>, login: Option<&str>, users: Channel) -> Result<Self> {
+        if let Some(token) = token {
+            return Self::bearer(token);
+        }
+
+        if let Some(identifier) = login {
+            let password = env::var("ADMIN_PASSWORD")
+                .context("ADMIN_PASSWORD must be set to sign in with --login")?;
+            let response = UserServiceClient::new(users)
+                .login(LoginRequest {
+                    identifier: identifier.to_string(),
+                    password,
+                })
+                .await
+                .map_err(failed)?
+                .into_inner();
+            return Self::bearer(&response.token);
+        }
+
+        if env::var("JWT_SECRET").is_ok_and(|secret| !secret.is_empty()) {
+            return Self::bearer(&auth::issue_token(CLI_SUBJECT, Role::Admin, None)?);
+        }
+
+        bail!("Sign in with --token or --login, or set JWT_SECRET")
+    }
+}
+
+impl Interceptor for Credentials {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        request
+            .metadata_mut()
+            .insert("authorization", self.header.clone());
+        Ok(request)
+    }
+}
@@ -0,0 +1,38 @@
+use crate::{Services, failed};
+use anyhow::Result;
+use clap::Subcommand;
+use proto::product::{UpdateInventoryRequest, product_service_client::ProductServiceClient};
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Add units to a product's stock, or take them out with a negative
+    /// change, e.g. after a stock count.
+    Adjust {
+        product_id: String,
+        #[arg(allow_negative_numbers = true)]
+        change: i32,
+    },
+}
+
+pub async fn run(command: Command, services: &Services) -> Result<()> {
+    let mut products = ProductServiceClient::new(services.authenticated(&services.product));
+
+    match command {
+        Command::Adjust { product_id, change } => {
+            let response = products
+                .update_inventory(UpdateInventoryRequest {
+                    product_id: product_id.clone(),
+                    quantity_change: change,
+                })
+                .await
+                .map_err(failed)?
+                .into_inner();
+            println!(
+                "Stock of {} is now {}",
+                product_id, response.new_stock_quantity
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,111 @@
+mod auth;
+mod inventory;
+mod orders;
+mod products;
+mod users;
+
+use anyhow::{Result, anyhow};
+use auth::Credentials;
+use clap::{Parser, Subcommand};
+use common::client::ServiceEndpoint;
+use common::error;
+use common::tls;
+use tonic::Status;
+use tonic::service::interceptor::InterceptedService;
+use tonic::transport::Channel;
+
+/// Operator tasks against the running services, e.g. creating an admin or
+/// importing a catalog, instead of SQL against their database.
+#[derive(Parser)]
+#[command(name = "cli", version)]
+struct Cli {
+    /// Token to call the services with, e.g. an admin's login token.
+    #[arg(long, env = "ADMIN_TOKEN", global = true, hide_env_values = true)]
+    token: Option<String>,
+
+    /// Username or email to sign in as, with the password in ADMIN_PASSWORD.
+    #[arg(long, env = "ADMIN_LOGIN", global = true)]
+    login: Option<String>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Manage accounts.
+    #[command(subcommand)]
+    User(users::Command),
+    /// Manage the catalog.
+    #[command(subcommand)]
+    Product(products::Command),
+    /// Manage orders.
+    #[command(subcommand)]
+    Order(orders::Command),
+    /// Manage stock.
+    #[command(subcommand)]
+    Inventory(inventory::Command),
+}
+
+/// Channels to the services, at the URLs the services themselves read,
+/// e.g. `PRODUCT_SERVICE_URL`.
+pub struct Services {
+    pub user: Channel,
+    pub product: Channel,
+    pub order: Channel,
+    pub credentials: Credentials,
+}
+
+pub type Authenticated = InterceptedService<Channel, Credentials>;
+
+impl Services {
+    async fn connect(cli: &Cli) -> Result<Self> {
+        let internal_tls = tls::client_config()?;
+        let channel = |name: &str, prefix: &str, default_url: &str| {
+            ServiceEndpoint::from_env(name, prefix, default_url)
+                .with_tls(internal_tls.clone())
+                .primary_channel()
+        };
+        let user = channel("user", "USER_SERVICE", "http://127.0.0.1:50051")?;
+        let product = channel("product", "PRODUCT_SERVICE", "http://127.0.0.1:50052")?;
+        let order = channel("order", "ORDER_SERVICE", "http://127.0.0.1:50053")?;
+        let credentials =
+            Credentials::resolve(cli.token.as_deref(), cli.login.as_deref(), user.clone()).await?;
+
+        Ok(Self {
+            user,
+            product,
+            order,
+            credentials,
+        })
+    }
+
+    /// `channel` sending the credentials with every call.
+    pub fn authenticated(&self, channel: &Channel) -> Authenticated {
+        InterceptedService::new(channel.clone(), self.credentials.clone())
+    }
+}
+
+/// A failed call as an error naming its reason, e.g.
+/// `PRODUCT_NOT_FOUND: Product not found`.
+pub fn failed(status: Status) -> anyhow::Error {
+    match error::error_info(&status) {
+        Some(info) => anyhow!("{}: {}", info.reason, status.message()),
+        None => anyhow!("{:?}: {}", status.code(), status.message()),
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenvy::dotenv().ok();
+
+    let cli = Cli::parse();
+    let services = Services::connect(&cli).await?;
+
+    match cli.command {
+        Command::User(command) => users::run(command, &services).await,
+        Command::Product(command) => products::run(command, &services).await,
+        Command::Order(command) => orders::run(command, &services).await,
+        Command::Inventory(command) => inventory::run(command, &services).await,
+    }
+}
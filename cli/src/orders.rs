@@ -0,0 +1,35 @@
+use crate::{Services, failed};
+use anyhow::Result;
+use clap::Subcommand;
+use proto::order::{CancelOrderRequest, order_service_client::OrderServiceClient};
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Cancel an order and put its items back in stock.
+    Cancel {
+        order_id: String,
+        /// Kept in the order's history.
+        #[arg(long, default_value = "")]
+        reason: String,
+    },
+}
+
+pub async fn run(command: Command, services: &Services) -> Result<()> {
+    let mut orders = OrderServiceClient::new(services.authenticated(&services.order));
+
+    match command {
+        Command::Cancel { order_id, reason } => {
+            orders
+                .cancel_order(CancelOrderRequest {
+                    order_id: order_id.clone(),
+                    user_id: String::new(),
+                    reason,
+                })
+                .await
+                .map_err(failed)?;
+            println!("Cancelled order {}", order_id);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,150 @@
+use crate::{Services, failed};
+use anyhow::{Context, Result, bail};
+use clap::Subcommand;
+use proto::product::{
+    AddProductRequest, ProductType, PublishProductRequest,
+    product_service_client::ProductServiceClient,
+};
+use serde::Deserialize;
+use std::path::PathBuf;
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Add the products of a CSV file with a header row. `name` and `price`
+    /// are required; the other columns, `description`, `stock_quantity`,
+    /// `category`, `warranty_months`, `product_type` (PHYSICAL or DIGITAL),
+    /// `allow_backorder`, `tax_class` and `weight_grams`, may be left out
+    /// or empty.
+    Import {
+        file: PathBuf,
+        /// Seller the products are listed by; the platform's own otherwise.
+        #[arg(long, default_value = "")]
+        seller_id: String,
+        /// Publish the products once added; they are drafts otherwise.
+        #[arg(long)]
+        publish: bool,
+    },
+}
+
+/// A row of an import file.
+#[derive(Debug, Deserialize)]
+struct ProductRow {
+    name: String,
+    price: String,
+    description: Option<String>,
+    stock_quantity: Option<i32>,
+    category: Option<String>,
+    warranty_months: Option<i32>,
+    product_type: Option<String>,
+    allow_backorder: Option<bool>,
+    tax_class: Option<String>,
+    weight_grams: Option<i32>,
+}
+
+impl ProductRow {
+    fn to_request(&self, seller_id: &str) -> Result<AddProductRequest> {
+        let product_type = match self.product_type.as_deref() {
+            None => ProductType::Physical,
+            Some(name) => ProductType::from_str_name(&name.to_ascii_uppercase())
+                .with_context(|| format!("Unknown product type {:?}", name))?,
+        };
+
+        Ok(AddProductRequest {
+            name: self.name.clone(),
+            description: self.description.clone().unwrap_or_default(),
+            price: self.price.clone(),
+            stock_quantity: self.stock_quantity.unwrap_or_default(),
+            category: self.category.clone().unwrap_or_default(),
+            warranty_months: self.warranty_months.unwrap_or_default(),
+            product_type: product_type as i32,
+            allow_backorder: self.allow_backorder.unwrap_or_default(),
+            available_from: 0,
+            seller_id: seller_id.to_string(),
+            tax_class: self.tax_class.clone().unwrap_or_default(),
+            weight_grams: self.weight_grams.unwrap_or_default(),
+        })
+    }
+}
+
+/// Reads every row of `file` before any is imported, so a malformed file
+/// adds nothing. Rows are numbered by their line in the file.
+fn read_rows(file: &PathBuf, seller_id: &str) -> Result<Vec<(u64, AddProductRequest)>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_path(file)
+        .with_context(|| format!("Can't read {}", file.display()))?;
+
+    let headers = reader.headers()?.clone();
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let line = record.position().map_or(0, |position| position.line());
+        let request = record
+            .deserialize::<ProductRow>(Some(&headers))
+            .map_err(anyhow::Error::from)
+            .and_then(|row| row.to_request(seller_id))
+            .with_context(|| format!("Line {}", line))?;
+        rows.push((line, request));
+    }
+    Ok(rows)
+}
+
+pub async fn run(command: Command, services: &Services) -> Result<()> {
+    let mut products = ProductServiceClient::new(services.authenticated(&services.product));
+
+    match command {
+        Command::Import {
+            file,
+            seller_id,
+            publish,
+        } => {
+            let rows = read_rows(&file, &seller_id)?;
+
+            // Rows the services refuse are reported and skipped, so a file
+            // of just those can be imported again once they are fixed
+            let mut failures = 0;
+            for (line, request) in &rows {
+                let name = request.name.clone();
+                let result = async {
+                    let product_id = products
+                        .add_product(request.clone())
+                        .await
+                        .map_err(failed)?
+                        .into_inner()
+                        .product_id;
+                    if publish {
+                        products
+                            .publish_product(PublishProductRequest {
+                                product_id: product_id.clone(),
+                            })
+                            .await
+                            .map_err(|status| {
+                                failed(status).context(format!("Added {} as a draft", product_id))
+                            })?;
+                    }
+                    anyhow::Ok(product_id)
+                }
+                .await;
+
+                match result {
+                    Ok(product_id) => println!("Line {}: added {} ({})", line, name, product_id),
+                    Err(e) => {
+                        failures += 1;
+                        eprintln!("Line {}: {:#}", line, e);
+                    }
+                }
+            }
+
+            println!(
+                "Imported {} of {} products",
+                rows.len() - failures,
+                rows.len()
+            );
+            if failures > 0 {
+                bail!("{} rows were not imported", failures);
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use crate::{Services, failed};
+use anyhow::Result;
+use clap::Subcommand;
+use proto::user::{CreateAdminRequest, user_service_client::UserServiceClient};
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Create an account that signs in as an admin.
+    CreateAdmin {
+        #[arg(long)]
+        username: String,
+        #[arg(long)]
+        email: String,
+        /// At least 12 characters.
+        #[arg(long, env = "NEW_ADMIN_PASSWORD", hide_env_values = true)]
+        password: String,
+    },
+}
+
+pub async fn run(command: Command, services: &Services) -> Result<()> {
+    let mut users = UserServiceClient::new(services.authenticated(&services.user));
+
+    match command {
+        Command::CreateAdmin {
+            username,
+            email,
+            password,
+        } => {
+            let response = users
+                .create_admin(CreateAdminRequest {
+                    username: username.clone(),
+                    email,
+                    password,
+                })
+                .await
+                .map_err(failed)?
+                .into_inner();
+            println!("Created admin {} ({})", username, response.user_id);
+        }
+    }
+
+    Ok(())
+}
@@ -1,19 +1,497 @@
+use std::collections::HashMap;
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
 use std::path::PathBuf;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let proto_dir = PathBuf::from("../proto");
+    let descriptor_path = PathBuf::from(env::var("OUT_DIR")?).join("descriptors.bin");
 
     tonic_build::configure()
         .build_server(true)
         .build_client(true)
         .out_dir("../proto/src/")
+        .file_descriptor_set_path(&descriptor_path)
         .compile_protos(
             &[
                 proto_dir.join("product.proto").to_str().unwrap(),
                 proto_dir.join("user.proto").to_str().unwrap(),
                 proto_dir.join("order.proto").to_str().unwrap(),
+                proto_dir.join("notification.proto").to_str().unwrap(),
+                proto_dir.join("ops.proto").to_str().unwrap(),
+                proto_dir.join("slo.proto").to_str().unwrap(),
+                proto_dir.join("recall.proto").to_str().unwrap(),
+                proto_dir.join("warranty.proto").to_str().unwrap(),
+                proto_dir.join("reporting.proto").to_str().unwrap(),
+                proto_dir.join("review.proto").to_str().unwrap(),
+                proto_dir.join("settings.proto").to_str().unwrap(),
+                proto_dir.join("payment.proto").to_str().unwrap(),
+                proto_dir.join("shipping.proto").to_str().unwrap(),
+                proto_dir.join("tax.proto").to_str().unwrap(),
+                proto_dir.join("webhook.proto").to_str().unwrap(),
+                proto_dir.join("events.proto").to_str().unwrap(),
+                proto_dir.join("search.proto").to_str().unwrap(),
             ],
             &[proto_dir.to_str().unwrap()],
         )?;
+
+    let descriptors = fs::read(&descriptor_path)?;
+    let validation = generate_validation(&descriptors)?;
+    fs::write("../proto/src/validation.rs", validation)?;
     Ok(())
 }
+
+// Field numbers of the (validate.*) options declared in proto/validate.proto
+const MIN_LEN: u32 = 51001;
+const MAX_LEN: u32 = 51002;
+const PATTERN: u32 = 51003;
+const GT: u32 = 51004;
+const GTE: u32 = 51005;
+const DECIMAL: u32 = 51006;
+
+// FieldDescriptorProto label and type values
+const LABEL_REPEATED: u64 = 3;
+const TYPE_DOUBLE: u64 = 1;
+const TYPE_STRING: u64 = 9;
+const TYPE_MESSAGE: u64 = 11;
+const TYPE_BYTES: u64 = 12;
+const TYPE_ENUM: u64 = 14;
+
+/// A raw protobuf field value. prost drops unknown fields, which is where
+/// custom options live, so descriptors are walked at the wire level.
+enum Wire<'a> {
+    Varint(u64),
+    Fixed64(u64),
+    Fixed32,
+    Len(&'a [u8]),
+}
+
+fn read_varint(buf: &[u8], pos: &mut usize) -> Result<u64, String> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *buf.get(*pos).ok_or("truncated varint")?;
+        *pos += 1;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err("varint too long".to_string())
+}
+
+fn read_fields(buf: &[u8]) -> Result<Vec<(u32, Wire<'_>)>, String> {
+    let mut fields = Vec::new();
+    let mut pos = 0;
+    while pos < buf.len() {
+        let key = read_varint(buf, &mut pos)?;
+        let number = (key >> 3) as u32;
+        let value = match key & 7 {
+            0 => Wire::Varint(read_varint(buf, &mut pos)?),
+            1 => {
+                let bytes = buf.get(pos..pos + 8).ok_or("truncated fixed64")?;
+                pos += 8;
+                Wire::Fixed64(u64::from_le_bytes(bytes.try_into().unwrap()))
+            }
+            2 => {
+                let len = read_varint(buf, &mut pos)? as usize;
+                let bytes = buf.get(pos..pos + len).ok_or("truncated field")?;
+                pos += len;
+                Wire::Len(bytes)
+            }
+            5 => {
+                pos += 4;
+                Wire::Fixed32
+            }
+            wire_type => return Err(format!("unsupported wire type {}", wire_type)),
+        };
+        fields.push((number, value));
+    }
+    Ok(fields)
+}
+
+fn string_field(fields: &[(u32, Wire<'_>)], number: u32) -> String {
+    fields
+        .iter()
+        .find_map(|(n, v)| match v {
+            Wire::Len(bytes) if *n == number => Some(String::from_utf8_lossy(bytes).into_owned()),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+fn varint_field(fields: &[(u32, Wire<'_>)], number: u32) -> Option<u64> {
+    fields.iter().find_map(|(n, v)| match v {
+        Wire::Varint(value) if *n == number => Some(*value),
+        _ => None,
+    })
+}
+
+fn double_field(fields: &[(u32, Wire<'_>)], number: u32) -> Option<f64> {
+    fields.iter().find_map(|(n, v)| match v {
+        Wire::Fixed64(bits) if *n == number => Some(f64::from_bits(*bits)),
+        _ => None,
+    })
+}
+
+fn len_fields<'a>(fields: &'a [(u32, Wire<'a>)], number: u32) -> impl Iterator<Item = &'a [u8]> {
+    fields.iter().filter_map(move |(n, v)| match v {
+        Wire::Len(bytes) if *n == number => Some(*bytes),
+        _ => None,
+    })
+}
+
+#[derive(Default)]
+struct Rules {
+    min_len: Option<u64>,
+    max_len: Option<u64>,
+    pattern: Option<String>,
+    gt: Option<f64>,
+    gte: Option<f64>,
+    decimal: bool,
+}
+
+struct Field {
+    name: String,
+    repeated: bool,
+    field_type: u64,
+    type_name: String,
+    rules: Rules,
+}
+
+struct Method {
+    path: String,
+    input_type: String,
+}
+
+/// Fields of every message, keyed by fully qualified name (e.g. `.user.RegisterRequest`).
+type Messages = HashMap<String, Vec<Field>>;
+
+/// Parses the messages and the unary-input methods of every service.
+fn parse_descriptors(buf: &[u8]) -> Result<(Messages, Vec<Method>), String> {
+    let mut messages = HashMap::new();
+    let mut methods = Vec::new();
+
+    for file in len_fields(&read_fields(buf)?, 1) {
+        let file = read_fields(file)?;
+        let package = string_field(&file, 2);
+
+        for message in len_fields(&file, 4) {
+            let message = read_fields(message)?;
+            let mut fields = Vec::new();
+            for field in len_fields(&message, 2) {
+                let field = read_fields(field)?;
+                let options = match len_fields(&field, 8).next() {
+                    Some(options) => read_fields(options)?,
+                    None => Vec::new(),
+                };
+                let pattern = string_field(&options, PATTERN);
+                fields.push(Field {
+                    name: string_field(&field, 1),
+                    repeated: varint_field(&field, 4) == Some(LABEL_REPEATED),
+                    field_type: varint_field(&field, 5).unwrap_or_default(),
+                    type_name: string_field(&field, 6),
+                    rules: Rules {
+                        min_len: varint_field(&options, MIN_LEN),
+                        max_len: varint_field(&options, MAX_LEN),
+                        pattern: (!pattern.is_empty()).then_some(pattern),
+                        gt: double_field(&options, GT),
+                        gte: double_field(&options, GTE),
+                        decimal: varint_field(&options, DECIMAL) == Some(1),
+                    },
+                });
+            }
+            messages.insert(
+                format!(".{}.{}", package, string_field(&message, 1)),
+                fields,
+            );
+        }
+
+        for service in len_fields(&file, 6) {
+            let service = read_fields(service)?;
+            let service_name = string_field(&service, 1);
+            for method in len_fields(&service, 2) {
+                let method = read_fields(method)?;
+                // Client-streaming requests can't be buffered and checked up front
+                if varint_field(&method, 5) == Some(1) {
+                    continue;
+                }
+                methods.push(Method {
+                    path: format!("/{}.{}/{}", package, service_name, string_field(&method, 1)),
+                    input_type: string_field(&method, 2),
+                });
+            }
+        }
+    }
+
+    Ok((messages, methods))
+}
+
+fn has_rules(rules: &Rules) -> bool {
+    rules.min_len.is_some()
+        || rules.max_len.is_some()
+        || rules.pattern.is_some()
+        || rules.gt.is_some()
+        || rules.gte.is_some()
+        || rules.decimal
+}
+
+/// Whether a message, or any message it contains, carries rules.
+fn needs_validation(messages: &Messages, name: &str, seen: &mut Vec<String>) -> bool {
+    if seen.iter().any(|s| s == name) {
+        return false;
+    }
+    seen.push(name.to_string());
+
+    messages.get(name).is_some_and(|fields| {
+        fields.iter().any(|f| {
+            has_rules(&f.rules)
+                || (f.field_type == TYPE_MESSAGE && needs_validation(messages, &f.type_name, seen))
+        })
+    })
+}
+
+/// `.user.RegisterRequest` -> `crate::user::RegisterRequest`
+fn rust_path(type_name: &str) -> String {
+    format!(
+        "crate::{}",
+        type_name.trim_start_matches('.').replace('.', "::")
+    )
+}
+
+fn rust_field(name: &str) -> String {
+    match name {
+        "type" | "ref" | "match" | "move" | "in" | "fn" | "use" | "mod" | "loop" => {
+            format!("r#{}", name)
+        }
+        _ => name.to_string(),
+    }
+}
+
+fn generate_validation(descriptors: &[u8]) -> Result<String, Box<dyn std::error::Error>> {
+    let (messages, methods) = parse_descriptors(descriptors)?;
+
+    let mut validated: Vec<&String> = messages
+        .keys()
+        .filter(|name| needs_validation(&messages, name, &mut Vec::new()))
+        .collect();
+    validated.sort();
+
+    let mut out = String::new();
+    writeln!(
+        out,
+        "// Generated by common/build.rs from the (validate.*) field options. Do not edit."
+    )?;
+    writeln!(out)?;
+    writeln!(out, "use crate::rules::{{self, Validate}};")?;
+
+    let mut patterns = 0;
+    for name in &validated {
+        let mut body = String::new();
+        let mut statics = String::new();
+
+        for field in &messages[*name] {
+            let access = format!("self.{}", rust_field(&field.name));
+            let rules = &field.rules;
+            let is_len = field.repeated || field.field_type == TYPE_STRING;
+
+            if field.repeated || field.field_type == TYPE_BYTES {
+                if let Some(min) = rules.min_len {
+                    writeln!(
+                        body,
+                        "        rules::min_items({:?}, {}.len(), {})?;",
+                        field.name, access, min
+                    )?;
+                }
+                if let Some(max) = rules.max_len {
+                    writeln!(
+                        body,
+                        "        rules::max_items({:?}, {}.len(), {})?;",
+                        field.name, access, max
+                    )?;
+                }
+            } else if is_len {
+                if let Some(min) = rules.min_len {
+                    writeln!(
+                        body,
+                        "        rules::min_len({:?}, &{}, {})?;",
+                        field.name, access, min
+                    )?;
+                }
+                if let Some(max) = rules.max_len {
+                    writeln!(
+                        body,
+                        "        rules::max_len({:?}, &{}, {})?;",
+                        field.name, access, max
+                    )?;
+                }
+            } else if rules.min_len.is_some() || rules.max_len.is_some() {
+                return Err(format!(
+                    "{}.{}: min_len/max_len need a string or repeated field",
+                    name, field.name
+                )
+                .into());
+            }
+
+            if let Some(pattern) = &rules.pattern {
+                if field.field_type != TYPE_STRING || field.repeated {
+                    return Err(
+                        format!("{}.{}: pattern needs a string field", name, field.name).into(),
+                    );
+                }
+                regex::Regex::new(pattern)
+                    .map_err(|e| format!("{}.{}: invalid pattern: {}", name, field.name, e))?;
+                writeln!(
+                    statics,
+                    "static PATTERN_{}: std::sync::LazyLock<regex::Regex> =\n    std::sync::LazyLock::new(|| regex::Regex::new({:?}).unwrap());",
+                    patterns, pattern
+                )?;
+                writeln!(
+                    body,
+                    "        rules::pattern({:?}, &{}, &PATTERN_{})?;",
+                    field.name, access, patterns
+                )?;
+                patterns += 1;
+            }
+
+            if rules.decimal {
+                if field.field_type != TYPE_STRING || field.repeated {
+                    return Err(
+                        format!("{}.{}: decimal needs a string field", name, field.name).into(),
+                    );
+                }
+                writeln!(
+                    body,
+                    "        rules::decimal({:?}, &{})?;",
+                    field.name, access
+                )?;
+                // Bounds compare as decimals, not through a float
+                if let Some(gt) = rules.gt {
+                    writeln!(
+                        body,
+                        "        rules::decimal_gt({:?}, &{}, {:?})?;",
+                        field.name,
+                        access,
+                        gt.to_string()
+                    )?;
+                }
+                if let Some(gte) = rules.gte {
+                    writeln!(
+                        body,
+                        "        rules::decimal_gte({:?}, &{}, {:?})?;",
+                        field.name,
+                        access,
+                        gte.to_string()
+                    )?;
+                }
+            }
+
+            let numeric = !matches!(
+                field.field_type,
+                TYPE_STRING | TYPE_BYTES | TYPE_MESSAGE | TYPE_ENUM
+            );
+            if (rules.gt.is_some() || rules.gte.is_some()) && !rules.decimal {
+                if !numeric || field.repeated {
+                    return Err(
+                        format!("{}.{}: gt/gte need a numeric field", name, field.name).into(),
+                    );
+                }
+                let value = if field.field_type == TYPE_DOUBLE {
+                    access.clone()
+                } else {
+                    format!("{} as f64", access)
+                };
+                if let Some(gt) = rules.gt {
+                    writeln!(
+                        body,
+                        "        rules::gt({:?}, {}, {:?})?;",
+                        field.name, value, gt
+                    )?;
+                }
+                if let Some(gte) = rules.gte {
+                    writeln!(
+                        body,
+                        "        rules::gte({:?}, {}, {:?})?;",
+                        field.name, value, gte
+                    )?;
+                }
+            }
+
+            if field.field_type == TYPE_MESSAGE && validated.contains(&&field.type_name) {
+                if field.repeated {
+                    writeln!(
+                        body,
+                        "        for item in &{} {{\n            item.validate()?;\n        }}",
+                        access
+                    )?;
+                } else {
+                    writeln!(
+                        body,
+                        "        if let Some(value) = &{} {{\n            value.validate()?;\n        }}",
+                        access
+                    )?;
+                }
+            }
+        }
+
+        writeln!(out)?;
+        write!(out, "{}", statics)?;
+        if !statics.is_empty() {
+            writeln!(out)?;
+        }
+        writeln!(out, "impl Validate for {} {{", rust_path(name))?;
+        writeln!(out, "    fn validate(&self) -> Result<(), String> {{")?;
+        write!(out, "{}", body)?;
+        writeln!(out, "        Ok(())")?;
+        writeln!(out, "    }}")?;
+        writeln!(out, "}}")?;
+    }
+
+    let checked: Vec<&Method> = methods
+        .iter()
+        .filter(|m| validated.contains(&&m.input_type))
+        .collect();
+
+    writeln!(out)?;
+    writeln!(
+        out,
+        "/// Whether the request message of `path` carries rules."
+    )?;
+    writeln!(out, "pub fn has_rules(path: &str) -> bool {{")?;
+    if checked.is_empty() {
+        writeln!(out, "    let _ = path;")?;
+        writeln!(out, "    false")?;
+    } else {
+        let paths: Vec<String> = checked.iter().map(|m| format!("{:?}", m.path)).collect();
+        writeln!(
+            out,
+            "    matches!(\n        path,\n        {}\n    )",
+            paths.join("\n            | ")
+        )?;
+    }
+    writeln!(out, "}}")?;
+
+    writeln!(out)?;
+    writeln!(
+        out,
+        "/// Decodes and validates the request message of `path`; `None` when the method has no rules."
+    )?;
+    writeln!(
+        out,
+        "pub fn validate_request(path: &str, message: &[u8]) -> Option<Result<(), String>> {{"
+    )?;
+    writeln!(out, "    match path {{")?;
+    for method in &checked {
+        writeln!(
+            out,
+            "        {:?} => {{\n            Some(rules::decode_and_validate::<{}>(message))\n        }}",
+            method.path,
+            rust_path(&method.input_type)
+        )?;
+    }
+    writeln!(out, "        _ => None,")?;
+    writeln!(out, "    }}")?;
+    writeln!(out, "}}")?;
+
+    Ok(out)
+}
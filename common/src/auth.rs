@@ -0,0 +1,251 @@
+//! Bearer tokens: issued by the user service at login, and read by the
+//! services that scope what a caller may change.
+
+use crate::telemetry::PropagateContext;
+use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use serde::{Deserialize, Serialize};
+use std::env;
+use tonic::metadata::{MetadataMap, MetadataValue};
+use tonic::service::Interceptor;
+use tonic::{Request, Status};
+
+const DEFAULT_JWT_SECRET: &str = "your-secret-key-change-in-production";
+const TOKEN_EXPIRATION_HOURS: i64 = 24;
+const EMAIL_TOKEN_EXPIRATION_DAYS: i64 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Customer,
+    Seller,
+    Admin,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Customer => "CUSTOMER",
+            Role::Seller => "SELLER",
+            Role::Admin => "ADMIN",
+        }
+    }
+
+    pub fn parse(role: &str) -> Self {
+        match role {
+            "SELLER" => Role::Seller,
+            "ADMIN" => Role::Admin,
+            _ => Role::Customer,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Claims {
+    pub sub: String, // user_id
+    pub exp: i64,    // expiration time
+    pub iat: i64,    // issued at
+    #[serde(default)]
+    pub role: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seller_id: Option<String>,
+}
+
+/// Signing secret shared by every service, from `JWT_SECRET`.
+fn secret() -> String {
+    env::var("JWT_SECRET").unwrap_or_else(|_| DEFAULT_JWT_SECRET.to_string())
+}
+
+pub fn issue_token(
+    user_id: &str,
+    role: Role,
+    seller_id: Option<&str>,
+) -> Result<String, jsonwebtoken::errors::Error> {
+    let now = chrono::Utc::now().timestamp();
+    let claims = Claims {
+        sub: user_id.to_string(),
+        exp: now + (TOKEN_EXPIRATION_HOURS * 3600),
+        iat: now,
+        role: role.as_str().to_string(),
+        seller_id: seller_id.map(str::to_string),
+    };
+
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(secret().as_bytes()),
+    )
+}
+
+pub fn decode_token(token: &str) -> Result<Claims, jsonwebtoken::errors::Error> {
+    let token_data = decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(secret().as_bytes()),
+        &Validation::default(),
+    )?;
+
+    Ok(token_data.claims)
+}
+
+/// Claims of an email token. They share no fields with `Claims`' `sub`, so
+/// neither kind of token decodes as the other.
+#[derive(Debug, Serialize, Deserialize)]
+struct EmailClaims {
+    email: String,
+    exp: i64,
+    iat: i64,
+}
+
+/// Issues a token vouching for an email address, e.g. the one a guest checked
+/// out with, which lets them claim their orders once they register.
+pub fn issue_email_token(email: &str) -> Result<String, jsonwebtoken::errors::Error> {
+    let now = chrono::Utc::now().timestamp();
+    let claims = EmailClaims {
+        email: email.to_string(),
+        exp: now + (EMAIL_TOKEN_EXPIRATION_DAYS * 24 * 3600),
+        iat: now,
+    };
+
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(secret().as_bytes()),
+    )
+}
+
+/// The email address an email token vouches for.
+pub fn decode_email_token(token: &str) -> Result<String, jsonwebtoken::errors::Error> {
+    let token_data = decode::<EmailClaims>(
+        token,
+        &DecodingKey::from_secret(secret().as_bytes()),
+        &Validation::default(),
+    )?;
+
+    Ok(token_data.claims.email)
+}
+
+/// Who a request comes from, as far as changing the catalog goes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Caller {
+    /// An admin token: operators, and other services calling on their own
+    /// behalf with `ServiceCredentials`.
+    Platform,
+    /// A user signed in as the seller they own, limited to its own
+    /// products; still the owner of their own profile and orders.
+    Seller { user_id: String, seller_id: String },
+    /// A customer, who changes no products.
+    Customer(String),
+    /// No token at all; only public reads and sign-up are open to it.
+    Anonymous,
+}
+
+fn sign_in_first() -> Status {
+    Status::unauthenticated("Sign in first")
+}
+
+// Handlers return these errors as-is, so they stay `Status` like theirs.
+#[allow(clippy::result_large_err)]
+impl Caller {
+    /// The caller behind the request's `authorization: Bearer ..` header,
+    /// `Anonymous` without one. A token that is present but invalid or
+    /// expired is rejected.
+    pub fn from_metadata(metadata: &MetadataMap) -> Result<Self, Status> {
+        let Some(header) = metadata.get("authorization") else {
+            return Ok(Caller::Anonymous);
+        };
+        let token = header
+            .to_str()
+            .ok()
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .ok_or_else(|| Status::unauthenticated("Malformed authorization header"))?;
+        let claims =
+            decode_token(token).map_err(|_| Status::unauthenticated("Invalid or expired token"))?;
+
+        Ok(match (Role::parse(&claims.role), claims.seller_id) {
+            (Role::Admin, _) => Caller::Platform,
+            (Role::Seller, Some(seller_id)) => Caller::Seller {
+                user_id: claims.sub,
+                seller_id,
+            },
+            _ => Caller::Customer(claims.sub),
+        })
+    }
+
+    /// How the caller appears as the actor of a change, e.g. `user:<id>`.
+    pub fn actor(&self) -> String {
+        match self {
+            Caller::Platform => "platform".to_string(),
+            Caller::Seller { seller_id, .. } => format!("seller:{}", seller_id),
+            Caller::Customer(user_id) => format!("user:{}", user_id),
+            Caller::Anonymous => "anonymous".to_string(),
+        }
+    }
+
+    pub fn seller_id(&self) -> Option<&str> {
+        match self {
+            Caller::Seller { seller_id, .. } => Some(seller_id),
+            _ => None,
+        }
+    }
+
+    /// The user behind the token, for customers and sellers.
+    pub fn user_id(&self) -> Option<&str> {
+        match self {
+            Caller::Customer(user_id) | Caller::Seller { user_id, .. } => Some(user_id),
+            _ => None,
+        }
+    }
+
+    /// Fails unless the caller is the customer `user_id` or the platform.
+    pub fn require_owner(&self, user_id: &str) -> Result<(), Status> {
+        match self {
+            Caller::Platform => Ok(()),
+            Caller::Customer(caller_id)
+            | Caller::Seller {
+                user_id: caller_id, ..
+            } if caller_id == user_id => Ok(()),
+            Caller::Anonymous => Err(sign_in_first()),
+            _ => Err(Status::permission_denied(
+                "Only the owner can access this order",
+            )),
+        }
+    }
+
+    /// Fails unless the caller is the platform itself.
+    pub fn require_platform(&self) -> Result<(), Status> {
+        match self {
+            Caller::Platform => Ok(()),
+            Caller::Anonymous => Err(sign_in_first()),
+            _ => Err(Status::permission_denied(
+                "Only the platform can perform this action",
+            )),
+        }
+    }
+}
+
+/// Subject prefix of the tokens services sign for their own calls.
+const SERVICE_SUBJECT_PREFIX: &str = "service:";
+
+/// Whether `claims` are those of a token a service signed for its own
+/// calls with `ServiceCredentials`.
+pub fn is_service(claims: &Claims) -> bool {
+    Role::parse(&claims.role) == Role::Admin && claims.sub.starts_with(SERVICE_SUBJECT_PREFIX)
+}
+
+/// Credentials of calls a service makes on its own behalf, e.g. the order
+/// service reserving stock: an admin token naming the calling service,
+/// signed with the shared secret, plus the trace context `PropagateContext`
+/// adds. Calls made for a customer forward the customer's token instead.
+#[derive(Debug, Clone, Copy)]
+pub struct ServiceCredentials(pub &'static str);
+
+impl Interceptor for ServiceCredentials {
+    fn call(&mut self, request: Request<()>) -> Result<Request<()>, Status> {
+        let mut request = PropagateContext.call(request)?;
+        let subject = format!("{}{}", SERVICE_SUBJECT_PREFIX, self.0);
+        let token = issue_token(&subject, Role::Admin, None)
+            .map_err(|e| Status::internal(format!("Can't sign a service token: {}", e)))?;
+        let header = MetadataValue::try_from(format!("Bearer {}", token))
+            .map_err(|_| Status::internal("Tokens are valid header values"))?;
+        request.metadata_mut().insert("authorization", header);
+        Ok(request)
+    }
+}
@@ -0,0 +1,139 @@
+//! Shared read-through cache in Redis, for lookups every instance of a
+//! service serves over and over, e.g. the products an order is priced from.
+//! Unlike `response_cache` it's shared by the instances, so a write
+//! invalidating an entry does so for all of them.
+//!
+//! Values are protobuf messages. The cache never fails a call: a Redis error
+//! is logged and the read falls through to the database, and an entry an
+//! invalidation missed still expires with its TTL.
+
+use anyhow::{Context, Result};
+use prost::Message;
+use redis::aio::ConnectionManager;
+use std::env;
+use std::time::Duration;
+use tracing::{info, warn};
+
+pub struct Cache {
+    /// `None` when no Redis is configured: every read is a miss.
+    redis: Option<ConnectionManager>,
+    /// Prefix of every key, e.g. `product`.
+    namespace: &'static str,
+}
+
+impl Cache {
+    /// Caches in the Redis at `REDIS_URL`; disabled when it's unset.
+    pub async fn from_env(namespace: &'static str) -> Result<Self> {
+        let url = match env::var("REDIS_URL") {
+            Ok(url) if !url.is_empty() => url,
+            _ => return Ok(Self::disabled(namespace)),
+        };
+        let client = redis::Client::open(url).context("Invalid REDIS_URL")?;
+        let redis = ConnectionManager::new(client)
+            .await
+            .context("Failed to connect to Redis")?;
+        info!(namespace, "Caching in Redis");
+        Ok(Self {
+            redis: Some(redis),
+            namespace,
+        })
+    }
+
+    pub fn disabled(namespace: &'static str) -> Self {
+        Self {
+            redis: None,
+            namespace,
+        }
+    }
+
+    fn key(&self, key: &str) -> String {
+        format!("{}:{}", self.namespace, key)
+    }
+
+    pub async fn get<M: Message + Default>(&self, key: &str) -> Option<M> {
+        self.get_many(&[key]).await.pop().flatten()
+    }
+
+    /// The cached value of each key, in order.
+    pub async fn get_many<M: Message + Default>(&self, keys: &[&str]) -> Vec<Option<M>> {
+        let Some(redis) = &self.redis else {
+            return keys.iter().map(|_| None).collect();
+        };
+        if keys.is_empty() {
+            return Vec::new();
+        }
+
+        let values: Vec<Option<Vec<u8>>> = match redis::cmd("MGET")
+            .arg(keys.iter().map(|k| self.key(k)).collect::<Vec<_>>())
+            .query_async(&mut redis.clone())
+            .await
+        {
+            Ok(values) => values,
+            Err(e) => {
+                warn!(namespace = self.namespace, "Cache read failed: {}", e);
+                return keys.iter().map(|_| None).collect();
+            }
+        };
+
+        values
+            .into_iter()
+            .map(|value| {
+                // An entry that doesn't decode is treated as missing and
+                // overwritten by the read falling through
+                value.and_then(|bytes| M::decode(bytes.as_slice()).ok())
+            })
+            .collect()
+    }
+
+    pub async fn set<M: Message>(&self, key: &str, value: &M, ttl: Duration) {
+        self.set_many(&[(key, value, ttl)]).await;
+    }
+
+    /// Caches each value under its key for its TTL, in one round trip.
+    pub async fn set_many<M: Message>(&self, entries: &[(&str, &M, Duration)]) {
+        let Some(redis) = &self.redis else {
+            return;
+        };
+        let mut pipe = redis::pipe();
+        for (key, value, ttl) in entries {
+            if ttl.is_zero() {
+                continue;
+            }
+            pipe.cmd("SET")
+                .arg(self.key(key))
+                .arg(value.encode_to_vec())
+                .arg("PX")
+                .arg(ttl.as_millis() as u64)
+                .ignore();
+        }
+        if pipe.is_empty() {
+            return;
+        }
+        if let Err(e) = pipe.query_async::<()>(&mut redis.clone()).await {
+            warn!(namespace = self.namespace, "Cache write failed: {}", e);
+        }
+    }
+
+    /// Drops the entries of `keys`, after a write changed what they hold.
+    pub async fn invalidate(&self, keys: &[&str]) {
+        let Some(redis) = &self.redis else {
+            return;
+        };
+        if keys.is_empty() {
+            return;
+        }
+
+        let result = redis::cmd("DEL")
+            .arg(keys.iter().map(|k| self.key(k)).collect::<Vec<_>>())
+            .query_async::<()>(&mut redis.clone())
+            .await;
+        if let Err(e) = result {
+            warn!(
+                namespace = self.namespace,
+                ?keys,
+                "Cache invalidation failed, entries expire with their TTL: {}",
+                e
+            );
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! Panic recovery. A handler that panics would otherwise take the
+//! connection down with it, failing every call multiplexed on it;
+//! `CatchPanicLayer` turns the panic into an INTERNAL status for that call
+//! alone, logged with its request ID and counted in `grpc_panics_total`.
+
+use crate::error::AppError;
+use crate::metrics::Metrics;
+use http::{Request, Response};
+use pin_project::pin_project;
+use prometheus::{IntCounterVec, Opts};
+use std::any::Any;
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tonic::Status;
+use tonic::body::BoxBody;
+use tower::{Layer, Service};
+
+#[derive(Clone)]
+pub struct CatchPanicLayer {
+    panics: IntCounterVec,
+}
+
+impl CatchPanicLayer {
+    /// Counts panics in `metrics`, by method.
+    pub fn new(metrics: &Metrics) -> Self {
+        let panics = IntCounterVec::new(
+            Opts::new("grpc_panics_total", "RPCs whose handler panicked"),
+            &["method"],
+        )
+        .expect("Valid metric");
+        metrics
+            .registry()
+            .register(Box::new(panics.clone()))
+            .expect("Metric registered once");
+        Self { panics }
+    }
+}
+
+impl<S> Layer<S> for CatchPanicLayer {
+    type Service = CatchPanic<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        CatchPanic {
+            inner: service,
+            panics: self.panics.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct CatchPanic<S> {
+    inner: S,
+    panics: IntCounterVec,
+}
+
+impl<S> Service<Request<BoxBody>> for CatchPanic<S>
+where
+    S: Service<Request<BoxBody>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = ResponseFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<BoxBody>) -> Self::Future {
+        let path = req.uri().path().to_owned();
+
+        match panic::catch_unwind(AssertUnwindSafe(|| self.inner.call(req))) {
+            Ok(future) => ResponseFuture {
+                future: Some(future),
+                panicked: None,
+                path,
+                panics: self.panics.clone(),
+            },
+            Err(payload) => ResponseFuture {
+                future: None,
+                panicked: Some(payload),
+                path,
+                panics: self.panics.clone(),
+            },
+        }
+    }
+}
+
+#[pin_project]
+pub struct ResponseFuture<F> {
+    /// `None` when the call itself panicked, with the payload in `panicked`.
+    #[pin]
+    future: Option<F>,
+    panicked: Option<Box<dyn Any + Send>>,
+    path: String,
+    panics: IntCounterVec,
+}
+
+impl<F, E> Future for ResponseFuture<F>
+where
+    F: Future<Output = Result<Response<BoxBody>, E>>,
+{
+    type Output = Result<Response<BoxBody>, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        let mut future = this.future;
+        let payload = match future.as_mut().as_pin_mut() {
+            Some(inner) => match panic::catch_unwind(AssertUnwindSafe(|| inner.poll(cx))) {
+                Ok(poll) => return poll,
+                Err(payload) => {
+                    future.set(None);
+                    payload
+                }
+            },
+            None => this
+                .panicked
+                .take()
+                .expect("ResponseFuture polled after completion"),
+        };
+
+        this.panics.with_label_values(&[this.path.as_str()]).inc();
+        // Logged, with the request ID, as the status is made
+        let status: Status =
+            AppError::Internal(format!("{} panicked: {}", this.path, message(&*payload))).into();
+        Poll::Ready(Ok(status.into_http()))
+    }
+}
+
+/// The message a panic was raised with, when it was raised with one.
+fn message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("Box<dyn Any>")
+}
@@ -0,0 +1,223 @@
+use crate::resilience::{CircuitBreaker, CircuitBreakerConfig, CircuitBreakerLayer, RetryPolicy};
+use dashmap::DashMap;
+use std::env;
+use std::fmt::Debug;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tonic::Status;
+use tonic::transport::{Channel, ClientTlsConfig, Endpoint};
+use tower::Layer;
+use tracing::{info, warn};
+
+#[derive(Debug, Clone)]
+pub struct CanaryConfig {
+    pub url: String,
+    /// Share of requests (0-100) routed to the canary.
+    pub percent: u8,
+}
+
+/// Where a single downstream call should be sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Route<'a> {
+    Primary(&'a str),
+    Canary { canary: &'a str, primary: &'a str },
+}
+
+/// Time allowed to establish a connection to a downstream service.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// Interval of the HTTP/2 pings that detect a dead connection while idle.
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// A downstream service address with an optional canary deployment.
+#[derive(Debug)]
+pub struct ServiceEndpoint {
+    name: String,
+    primary: String,
+    canary: Option<CanaryConfig>,
+    counter: AtomicU64,
+    channels: DashMap<String, Channel>,
+    tls: Option<ClientTlsConfig>,
+    circuit_breaker: CircuitBreakerLayer,
+    retry: RetryPolicy,
+}
+
+impl ServiceEndpoint {
+    pub fn new(name: impl Into<String>, primary: impl Into<String>) -> Self {
+        let name = name.into();
+        Self {
+            circuit_breaker: CircuitBreakerLayer::new(&name, CircuitBreakerConfig::default()),
+            name,
+            primary: primary.into(),
+            canary: None,
+            counter: AtomicU64::new(0),
+            channels: DashMap::new(),
+            tls: None,
+            retry: RetryPolicy::default(),
+        }
+    }
+
+    pub fn with_canary(mut self, url: impl Into<String>, percent: u8) -> Self {
+        self.canary = Some(CanaryConfig {
+            url: url.into(),
+            percent: percent.min(100),
+        });
+        self
+    }
+
+    /// Calls the service over TLS, see `tls::client_config`; `None` keeps
+    /// plaintext.
+    pub fn with_tls(mut self, tls: Option<ClientTlsConfig>) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    pub fn with_circuit_breaker(mut self, config: CircuitBreakerConfig) -> Self {
+        self.circuit_breaker = CircuitBreakerLayer::new(&self.name, config);
+        self
+    }
+
+    pub fn with_retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
+    /// Reads `<PREFIX>_URL`, `<PREFIX>_CANARY_URL` and `<PREFIX>_CANARY_PERCENT`,
+    /// e.g. `PRODUCT_SERVICE_URL` and `PRODUCT_SERVICE_CANARY_URL`.
+    pub fn from_env(name: &str, prefix: &str, default_url: &str) -> Self {
+        let primary =
+            env::var(format!("{}_URL", prefix)).unwrap_or_else(|_| default_url.to_string());
+        let endpoint = Self::new(name, primary);
+
+        match env::var(format!("{}_CANARY_URL", prefix)) {
+            Ok(url) if !url.is_empty() => {
+                let percent = env::var(format!("{}_CANARY_PERCENT", prefix))
+                    .ok()
+                    .and_then(|p| p.parse().ok())
+                    .unwrap_or(0);
+                endpoint.with_canary(url, percent)
+            }
+            _ => endpoint,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// `channel` behind this service's circuit breaker, which every channel
+    /// guarded here shares.
+    pub fn guard(&self, channel: Channel) -> CircuitBreaker<Channel> {
+        self.circuit_breaker.layer(channel)
+    }
+
+    /// How calls to this service that are safe to repeat are retried, see
+    /// `resilience::retry`.
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry
+    }
+
+    pub fn primary_url(&self) -> &str {
+        &self.primary
+    }
+
+    /// Channel to `url`, one of this endpoint's targets. Channels are created
+    /// on first use and shared by every later call; they connect lazily and
+    /// reconnect by themselves once a connection drops.
+    pub fn channel(&self, url: &str) -> Result<Channel, tonic::transport::Error> {
+        if let Some(channel) = self.channels.get(url) {
+            return Ok(channel.clone());
+        }
+
+        let mut endpoint = Endpoint::from_shared(url.to_string())?
+            .connect_timeout(CONNECT_TIMEOUT)
+            .http2_keep_alive_interval(KEEP_ALIVE_INTERVAL)
+            .keep_alive_while_idle(true);
+        if let Some(tls) = &self.tls {
+            endpoint = endpoint.tls_config(tls.clone())?;
+        }
+        let channel = endpoint.connect_lazy();
+        Ok(self
+            .channels
+            .entry(url.to_string())
+            .or_insert(channel)
+            .clone())
+    }
+
+    pub fn primary_channel(&self) -> Result<Channel, tonic::transport::Error> {
+        self.channel(&self.primary)
+    }
+
+    /// Picks the target for the next call. Selection is a round-robin over
+    /// 100 slots, so the split is exact rather than probabilistic.
+    pub fn route(&self) -> Route<'_> {
+        match &self.canary {
+            Some(canary) if canary.percent > 0 => {
+                let slot = self.counter.fetch_add(1, Ordering::Relaxed) % 100;
+                if slot < canary.percent as u64 {
+                    Route::Canary {
+                        canary: &canary.url,
+                        primary: &self.primary,
+                    }
+                } else {
+                    Route::Primary(&self.primary)
+                }
+            }
+            _ => Route::Primary(&self.primary),
+        }
+    }
+}
+
+/// Runs a read-only call over the channel of the routed endpoint. Calls
+/// routed to the canary are mirrored to the primary and the two results are
+/// compared and logged; the canary's result is returned.
+pub async fn call_with_canary<T, F, Fut>(
+    endpoint: &ServiceEndpoint,
+    method: &str,
+    call: F,
+) -> Result<T, Status>
+where
+    T: PartialEq + Debug,
+    F: Fn(Channel) -> Fut,
+    Fut: Future<Output = Result<T, Status>>,
+{
+    let invalid_url =
+        |e| Status::internal(format!("Invalid {} service URL: {}", endpoint.name(), e));
+
+    match endpoint.route() {
+        Route::Primary(url) => call(endpoint.channel(url).map_err(invalid_url)?).await,
+        Route::Canary { canary, primary } => {
+            let (canary_result, primary_result) = tokio::join!(
+                call(endpoint.channel(canary).map_err(invalid_url)?),
+                call(endpoint.channel(primary).map_err(invalid_url)?)
+            );
+            compare_results(endpoint.name(), method, &primary_result, &canary_result);
+            canary_result
+        }
+    }
+}
+
+fn compare_results<T: PartialEq + Debug>(
+    service: &str,
+    method: &str,
+    primary: &Result<T, Status>,
+    canary: &Result<T, Status>,
+) {
+    let matched = match (primary, canary) {
+        (Ok(p), Ok(c)) => p == c,
+        (Err(p), Err(c)) => p.code() == c.code(),
+        _ => false,
+    };
+
+    if matched {
+        info!(service, method, "Canary result matches primary");
+    } else {
+        warn!(
+            service,
+            method,
+            primary = ?primary,
+            canary = ?canary,
+            "Canary result differs from primary"
+        );
+    }
+}
@@ -0,0 +1,338 @@
+//! Errors RPCs fail with. A request that can't be carried out fails with
+//! the gRPC code that says why, NOT_FOUND, INVALID_ARGUMENT,
+//! FAILED_PRECONDITION and so on, rather than an OK response with
+//! `success = false`, so retries and monitoring see the failure. The status
+//! carries a `google.rpc.ErrorInfo` detail whose reason, e.g.
+//! `ORDER_NOT_FOUND`, clients can branch on instead of parsing the message.
+
+use crate::request_id;
+use prost::Message;
+use std::collections::HashMap;
+use std::fmt;
+use tonic::{Code, Status};
+use tracing::{debug, error, warn};
+
+/// `ErrorInfo.domain` of every error raised here.
+pub const DOMAIN: &str = "e-commerce-rs";
+
+const ERROR_INFO_TYPE_URL: &str = "type.googleapis.com/google.rpc.ErrorInfo";
+
+/// `google.rpc.ErrorInfo`: why a request failed, in machine-readable form.
+#[derive(Clone, PartialEq, Message)]
+pub struct ErrorInfo {
+    /// UPPER_SNAKE_CASE cause, unique within the domain.
+    #[prost(string, tag = "1")]
+    pub reason: String,
+    #[prost(string, tag = "2")]
+    pub domain: String,
+    #[prost(map = "string, string", tag = "3")]
+    pub metadata: HashMap<String, String>,
+}
+
+/// `google.protobuf.Any`
+#[derive(Clone, PartialEq, Message)]
+struct Any {
+    #[prost(string, tag = "1")]
+    type_url: String,
+    #[prost(bytes = "vec", tag = "2")]
+    value: Vec<u8>,
+}
+
+/// `google.rpc.Status`, the payload of the `grpc-status-details-bin` trailer.
+#[derive(Clone, PartialEq, Message)]
+struct RpcStatus {
+    #[prost(int32, tag = "1")]
+    code: i32,
+    #[prost(string, tag = "2")]
+    message: String,
+    #[prost(message, repeated, tag = "3")]
+    details: Vec<Any>,
+}
+
+/// A status of `code` with an ErrorInfo detail of `reason`.
+pub fn with_reason(code: Code, reason: &str, message: impl Into<String>) -> Status {
+    let message = message.into();
+    let info = ErrorInfo {
+        reason: reason.to_string(),
+        domain: DOMAIN.to_string(),
+        metadata: HashMap::new(),
+    };
+    let details = RpcStatus {
+        code: code as i32,
+        message: message.clone(),
+        details: vec![Any {
+            type_url: ERROR_INFO_TYPE_URL.to_string(),
+            value: info.encode_to_vec(),
+        }],
+    };
+    Status::with_details(code, message, details.encode_to_vec().into())
+}
+
+/// The resource the request names doesn't exist.
+pub fn not_found(reason: &str, message: impl Into<String>) -> Status {
+    with_reason(Code::NotFound, reason, message)
+}
+
+/// The request is wrong whatever the state of the system, e.g. a malformed
+/// date; retrying it unchanged fails again.
+pub fn invalid_argument(reason: &str, message: impl Into<String>) -> Status {
+    with_reason(Code::InvalidArgument, reason, message)
+}
+
+/// The system isn't in a state that allows the request, e.g. the order was
+/// already shipped or the stock ran out.
+pub fn failed_precondition(reason: &str, message: impl Into<String>) -> Status {
+    with_reason(Code::FailedPrecondition, reason, message)
+}
+
+/// What the request would create exists already.
+pub fn already_exists(reason: &str, message: impl Into<String>) -> Status {
+    with_reason(Code::AlreadyExists, reason, message)
+}
+
+/// The caller's credentials were not accepted.
+pub fn unauthenticated(reason: &str, message: impl Into<String>) -> Status {
+    with_reason(Code::Unauthenticated, reason, message)
+}
+
+/// The ErrorInfo a status carries, if any.
+pub fn error_info(status: &Status) -> Option<ErrorInfo> {
+    let details = RpcStatus::decode(status.details()).ok()?;
+    details
+        .details
+        .into_iter()
+        .find(|any| any.type_url == ERROR_INFO_TYPE_URL)
+        .and_then(|any| ErrorInfo::decode(any.value.as_slice()).ok())
+}
+
+/// Whether a call was refused for a reason the caller can act on, rather
+/// than failing: the codes handlers answer domain errors with.
+pub fn is_refusal(status: &Status) -> bool {
+    matches!(
+        status.code(),
+        Code::NotFound | Code::InvalidArgument | Code::FailedPrecondition | Code::AlreadyExists
+    )
+}
+
+/// Failures of the work behind a handler, e.g. a query or a call to another
+/// service, by what they mean for the caller. Handlers convert with
+/// `.map_err(AppError::from)?` instead of formatting a status at every call
+/// site.
+///
+/// The messages of `Unavailable` and `Internal` are details for the logs,
+/// which may name tables or quote SQL: the status a caller gets says only
+/// what went wrong and the request ID the details were logged under.
+#[derive(Debug)]
+pub enum AppError {
+    /// What the request names doesn't exist.
+    NotFound(String),
+    /// The request breaks a rule of the data, e.g. a check constraint.
+    Validation(String),
+    /// The request collides with existing data, e.g. a unique key.
+    Conflict(String),
+    /// A dependency is down or overloaded; retrying later may succeed.
+    Unavailable(String),
+    Internal(String),
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::NotFound(message)
+            | AppError::Validation(message)
+            | AppError::Conflict(message)
+            | AppError::Unavailable(message)
+            | AppError::Internal(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+/// SQLSTATEs of the constraint violations a request can cause.
+const UNIQUE_VIOLATION: &str = "23505";
+const FOREIGN_KEY_VIOLATION: &str = "23503";
+const CHECK_VIOLATION: &str = "23514";
+
+impl From<sqlx::Error> for AppError {
+    fn from(e: sqlx::Error) -> Self {
+        match &e {
+            sqlx::Error::RowNotFound => AppError::NotFound("Record not found".to_string()),
+            sqlx::Error::Database(db) => match db.code().as_deref() {
+                Some(UNIQUE_VIOLATION) => {
+                    debug!("Unique violation: {}", db.message());
+                    AppError::Conflict("Conflicts with an existing record".to_string())
+                }
+                Some(FOREIGN_KEY_VIOLATION | CHECK_VIOLATION) => {
+                    debug!("Constraint violation: {}", db.message());
+                    AppError::Validation("Violates a constraint of the data".to_string())
+                }
+                _ => AppError::Internal(format!("Database error: {}", e)),
+            },
+            sqlx::Error::PoolTimedOut | sqlx::Error::PoolClosed | sqlx::Error::Io(_) => {
+                AppError::Unavailable(format!("Database unavailable: {}", e))
+            }
+            _ => AppError::Internal(format!("Database error: {}", e)),
+        }
+    }
+}
+
+impl From<anyhow::Error> for AppError {
+    fn from(e: anyhow::Error) -> Self {
+        match e.downcast::<sqlx::Error>() {
+            Ok(e) => e.into(),
+            Err(e) => AppError::Internal(e.to_string()),
+        }
+    }
+}
+
+impl From<AppError> for Status {
+    fn from(e: AppError) -> Self {
+        match e {
+            AppError::NotFound(message) => not_found("NOT_FOUND", message),
+            AppError::Validation(message) => invalid_argument("VALIDATION_FAILED", message),
+            AppError::Conflict(message) => already_exists("CONFLICT", message),
+            AppError::Unavailable(details) => {
+                warn!("Unavailable: {}", details);
+                Status::unavailable(withheld("Temporarily unavailable, retry later"))
+            }
+            AppError::Internal(details) => {
+                error!("Internal error: {}", details);
+                Status::internal(withheld("Internal error"))
+            }
+        }
+    }
+}
+
+/// `message` with the ID of the request whose logs hold the details.
+fn withheld(message: &str) -> String {
+    match request_id::current() {
+        Some(id) => format!("{} (request ID {})", message, id),
+        None => message.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::error::{DatabaseError, ErrorKind};
+    use std::borrow::Cow;
+
+    /// A database error raised by Postgres with SQLSTATE `code`.
+    #[derive(Debug)]
+    struct PgError {
+        code: &'static str,
+        message: &'static str,
+    }
+
+    impl fmt::Display for PgError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(self.message)
+        }
+    }
+
+    impl std::error::Error for PgError {}
+
+    impl DatabaseError for PgError {
+        fn message(&self) -> &str {
+            self.message
+        }
+
+        fn code(&self) -> Option<Cow<'_, str>> {
+            Some(Cow::Borrowed(self.code))
+        }
+
+        fn as_error(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+            self
+        }
+
+        fn as_error_mut(&mut self) -> &mut (dyn std::error::Error + Send + Sync + 'static) {
+            self
+        }
+
+        fn into_error(self: Box<Self>) -> Box<dyn std::error::Error + Send + Sync + 'static> {
+            self
+        }
+
+        fn kind(&self) -> ErrorKind {
+            ErrorKind::Other
+        }
+    }
+
+    const SQL: &str = "relation \"orders_secret\" does not exist at SELECT * FROM orders_secret";
+
+    fn database_error(code: &'static str) -> AppError {
+        sqlx::Error::Database(Box::new(PgError { code, message: SQL })).into()
+    }
+
+    fn reason(status: &Status) -> String {
+        error_info(status).expect("status has an ErrorInfo").reason
+    }
+
+    #[test]
+    fn sqlx_errors_map_by_what_they_mean() {
+        assert!(matches!(
+            AppError::from(sqlx::Error::RowNotFound),
+            AppError::NotFound(_)
+        ));
+        assert!(matches!(database_error("23505"), AppError::Conflict(_)));
+        assert!(matches!(database_error("23503"), AppError::Validation(_)));
+        assert!(matches!(database_error("23514"), AppError::Validation(_)));
+        assert!(matches!(database_error("42P01"), AppError::Internal(_)));
+        assert!(matches!(
+            AppError::from(sqlx::Error::PoolTimedOut),
+            AppError::Unavailable(_)
+        ));
+        assert!(matches!(
+            AppError::from(sqlx::Error::ColumnNotFound("id".to_string())),
+            AppError::Internal(_)
+        ));
+    }
+
+    #[test]
+    fn constraint_violations_keep_the_sql_out_of_their_message() {
+        for code in ["23505", "23503", "23514"] {
+            let message = database_error(code).to_string();
+            assert!(!message.contains("orders_secret"), "{}", message);
+        }
+    }
+
+    #[test]
+    fn statuses_carry_their_code_and_reason() {
+        let status = Status::from(AppError::NotFound("Order not found".to_string()));
+        assert_eq!(status.code(), Code::NotFound);
+        assert_eq!(reason(&status), "NOT_FOUND");
+        assert_eq!(status.message(), "Order not found");
+
+        let status = Status::from(AppError::Validation("Bad data".to_string()));
+        assert_eq!(status.code(), Code::InvalidArgument);
+        assert_eq!(reason(&status), "VALIDATION_FAILED");
+
+        let status = Status::from(AppError::Conflict("Taken".to_string()));
+        assert_eq!(status.code(), Code::AlreadyExists);
+        assert_eq!(reason(&status), "CONFLICT");
+        assert_eq!(error_info(&status).unwrap().domain, DOMAIN);
+    }
+
+    #[test]
+    fn internal_and_unavailable_statuses_withhold_the_details() {
+        let status = Status::from(database_error("42P01"));
+        assert_eq!(status.code(), Code::Internal);
+        assert!(!status.message().contains("orders_secret"));
+        assert!(!status.message().contains("SELECT"));
+
+        let unavailable = AppError::Unavailable(format!("Database unavailable: {}", SQL));
+        let status = Status::from(unavailable);
+        assert_eq!(status.code(), Code::Unavailable);
+        assert!(!status.message().contains("orders_secret"));
+    }
+
+    #[test]
+    fn error_info_is_read_back_from_a_status() {
+        let status = with_reason(Code::FailedPrecondition, "ORDER_SHIPPED", "Too late");
+        let info = error_info(&status).unwrap();
+        assert_eq!(info.reason, "ORDER_SHIPPED");
+        assert_eq!(info.domain, DOMAIN);
+        assert!(error_info(&Status::internal("no details")).is_none());
+    }
+}
@@ -0,0 +1,417 @@
+//! Event bus. Services announce changes as typed events (see
+//! `proto/events.proto`) rather than calling each other: `emit` writes an
+//! event to the outbox in the transaction making the change, the outbox
+//! relay publishes it through an `EventPublisher`, and services interested
+//! in it run an `EventHandler` over an `EventConsumer`.
+//!
+//! Two brokers are supported, picked by `EVENT_BUS`: Kafka (`KAFKA_BROKERS`)
+//! and NATS (`NATS_URL`). Core NATS keeps no messages, so a NATS consumer only sees events
+//! published while it's subscribed; Kafka consumers resume from their
+//! group's committed offsets.
+
+use crate::outbox::{self, OutboxEvent};
+use anyhow::{Context as _, Result, anyhow, bail};
+use prost::Message;
+use proto::events::{Envelope, OrderCreated, ProductChanged, StockChanged, UserRegistered};
+use rdkafka::ClientConfig;
+use rdkafka::Message as _;
+use rdkafka::consumer::{CommitMode, Consumer, StreamConsumer};
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::util::Timeout;
+use sqlx::PgExecutor;
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio_stream::{StreamExt, StreamMap};
+use tracing::{error, info, warn};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long a consumer waits for events before polling again.
+const POLL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Events a consumer polls at once.
+const MAX_BATCH: usize = 100;
+
+/// Attempts at handling an event before it's logged and skipped.
+const HANDLER_ATTEMPTS: u32 = 3;
+
+/// A typed event, published on `TOPIC` keyed by `key`.
+pub trait Event: Message + Default {
+    const TOPIC: &'static str;
+    /// Its `event_type` in the envelope.
+    const TYPE: &'static str;
+
+    /// Id of the entity the event is about.
+    fn key(&self) -> &str;
+}
+
+impl Event for UserRegistered {
+    const TOPIC: &'static str = "users";
+    const TYPE: &'static str = "UserRegistered";
+
+    fn key(&self) -> &str {
+        &self.user_id
+    }
+}
+
+impl Event for OrderCreated {
+    const TOPIC: &'static str = "orders";
+    const TYPE: &'static str = "OrderCreated";
+
+    fn key(&self) -> &str {
+        &self.order_id
+    }
+}
+
+impl Event for ProductChanged {
+    const TOPIC: &'static str = "products";
+    const TYPE: &'static str = "ProductChanged";
+
+    fn key(&self) -> &str {
+        &self.product_id
+    }
+}
+
+impl Event for StockChanged {
+    const TOPIC: &'static str = "stock";
+    const TYPE: &'static str = "StockChanged";
+
+    fn key(&self) -> &str {
+        &self.product_id
+    }
+}
+
+/// Writes `event` to the outbox for publishing; pass the transaction making
+/// the change it announces.
+pub async fn emit<'e, E: Event>(
+    executor: impl PgExecutor<'e>,
+    event: &E,
+) -> Result<(), sqlx::Error> {
+    outbox::enqueue(
+        executor,
+        &OutboxEvent {
+            aggregate_type: E::TOPIC,
+            aggregate_id: event.key(),
+            event_type: E::TYPE,
+            payload: &event.encode_to_vec(),
+        },
+    )
+    .await
+}
+
+/// The event in `envelope`, or `None` when it holds another type.
+pub fn decode<E: Event>(envelope: &Envelope) -> Result<Option<E>> {
+    if envelope.event_type != E::TYPE {
+        return Ok(None);
+    }
+    let event = E::decode(envelope.payload.as_slice())
+        .with_context(|| format!("Malformed {} event {}", E::TYPE, envelope.event_id))?;
+    Ok(Some(event))
+}
+
+/// Publishes events to the broker. `Ok` means the broker accepted it.
+#[tonic::async_trait]
+pub trait EventPublisher: Send + Sync {
+    async fn publish(&self, topic: &str, envelope: &Envelope) -> Result<()>;
+}
+
+/// An event received by a consumer.
+#[derive(Debug, Clone)]
+pub struct Delivery {
+    pub topic: String,
+    pub envelope: Envelope,
+}
+
+/// Receives the events of the topics it subscribed to.
+#[tonic::async_trait]
+pub trait EventConsumer: Send + Sync {
+    /// The next events, or none when `POLL_TIMEOUT` passed without any.
+    async fn poll(&self) -> Result<Vec<Delivery>>;
+
+    /// Acknowledges the events polled so far, so they aren't delivered to
+    /// the group again.
+    async fn commit(&self) -> Result<()>;
+}
+
+/// Reacts to events.
+#[tonic::async_trait]
+pub trait EventHandler: Send + Sync {
+    async fn handle(&self, delivery: &Delivery) -> Result<()>;
+}
+
+/// Spawns a background task feeding the events `consumer` receives to
+/// `handler`. An event the handler keeps failing is logged and skipped, so
+/// one bad event doesn't hold up the topic.
+pub fn spawn_consumer(consumer: Arc<dyn EventConsumer>, handler: Arc<dyn EventHandler>) {
+    tokio::spawn(async move {
+        loop {
+            let deliveries = match consumer.poll().await {
+                Ok(deliveries) => deliveries,
+                Err(e) => {
+                    warn!("Failed to poll events: {:#}", e);
+                    tokio::time::sleep(POLL_TIMEOUT).await;
+                    continue;
+                }
+            };
+            if deliveries.is_empty() {
+                continue;
+            }
+
+            for delivery in &deliveries {
+                handle_with_retries(handler.as_ref(), delivery).await;
+            }
+
+            if let Err(e) = consumer.commit().await {
+                warn!("Failed to commit events: {:#}", e);
+            }
+        }
+    });
+}
+
+async fn handle_with_retries(handler: &dyn EventHandler, delivery: &Delivery) {
+    let mut backoff = Duration::from_secs(1);
+    for attempt in 1..=HANDLER_ATTEMPTS {
+        match handler.handle(delivery).await {
+            Ok(()) => return,
+            Err(e) if attempt < HANDLER_ATTEMPTS => {
+                warn!(
+                    event_id = %delivery.envelope.event_id,
+                    event_type = %delivery.envelope.event_type,
+                    "Failed to handle event, retrying: {:#}", e
+                );
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+            }
+            Err(e) => error!(
+                event_id = %delivery.envelope.event_id,
+                event_type = %delivery.envelope.event_type,
+                topic = %delivery.topic,
+                "Skipped event after {} attempts: {:#}", HANDLER_ATTEMPTS, e
+            ),
+        }
+    }
+}
+
+/// The publisher `EVENT_BUS` names, `None` when it's unset.
+pub async fn publisher_from_env() -> Result<Option<Arc<dyn EventPublisher>>> {
+    Ok(match bus_from_env()? {
+        Some(Bus::Kafka(brokers)) => Some(Arc::new(KafkaPublisher::new(&brokers)?)),
+        Some(Bus::Nats(url)) => Some(Arc::new(NatsPublisher::connect(&url).await?)),
+        None => None,
+    })
+}
+
+/// A consumer of `topics` for the `group` named, shared by the instances of
+/// a service so each event is handled by one of them; `None` when
+/// `EVENT_BUS` is unset.
+pub async fn consumer_from_env(
+    group: &str,
+    topics: &[&str],
+) -> Result<Option<Arc<dyn EventConsumer>>> {
+    Ok(match bus_from_env()? {
+        Some(Bus::Kafka(brokers)) => Some(Arc::new(KafkaConsumer::new(&brokers, group, topics)?)),
+        Some(Bus::Nats(url)) => Some(Arc::new(NatsConsumer::connect(&url, group, topics).await?)),
+        None => None,
+    })
+}
+
+enum Bus {
+    Kafka(String),
+    Nats(String),
+}
+
+fn bus_from_env() -> Result<Option<Bus>> {
+    match env::var("EVENT_BUS").unwrap_or_default().as_str() {
+        "" => Ok(None),
+        "kafka" => Ok(Some(Bus::Kafka(
+            env::var("KAFKA_BROKERS").unwrap_or_else(|_| "localhost:9092".to_string()),
+        ))),
+        "nats" => Ok(Some(Bus::Nats(
+            env::var("NATS_URL").unwrap_or_else(|_| "nats://localhost:4222".to_string()),
+        ))),
+        other => bail!("Unknown EVENT_BUS {:?}, expected kafka or nats", other),
+    }
+}
+
+/// Publishes to Kafka. Records are keyed by the event's key, so its events
+/// share a partition and stay in order; the producer is idempotent, so a
+/// retried send doesn't write a record twice.
+pub struct KafkaPublisher {
+    producer: FutureProducer,
+}
+
+impl KafkaPublisher {
+    pub fn new(brokers: &str) -> Result<Self> {
+        let producer = ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .set("enable.idempotence", "true")
+            .set("acks", "all")
+            .set(
+                "message.timeout.ms",
+                REQUEST_TIMEOUT.as_millis().to_string(),
+            )
+            .create()
+            .context("Failed to create Kafka producer")?;
+        Ok(Self { producer })
+    }
+}
+
+#[tonic::async_trait]
+impl EventPublisher for KafkaPublisher {
+    async fn publish(&self, topic: &str, envelope: &Envelope) -> Result<()> {
+        let payload = envelope.encode_to_vec();
+        self.producer
+            .send(
+                FutureRecord::to(topic).key(&envelope.key).payload(&payload),
+                Timeout::After(REQUEST_TIMEOUT),
+            )
+            .await
+            .map_err(|(e, _)| anyhow!("Kafka refused the record: {}", e))?;
+        Ok(())
+    }
+}
+
+/// Consumes from Kafka as a member of a consumer group. Offsets are
+/// committed by `commit` only, so events polled but not committed are
+/// delivered again after a restart or rebalance.
+pub struct KafkaConsumer {
+    consumer: StreamConsumer,
+}
+
+impl KafkaConsumer {
+    pub fn new(brokers: &str, group: &str, topics: &[&str]) -> Result<Self> {
+        let consumer: StreamConsumer = ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .set("group.id", group)
+            .set("enable.auto.commit", "false")
+            .set("auto.offset.reset", "earliest")
+            .create()
+            .context("Failed to create Kafka consumer")?;
+        consumer
+            .subscribe(topics)
+            .context("Failed to subscribe to Kafka topics")?;
+        info!(group, ?topics, "Subscribed to Kafka");
+        Ok(Self { consumer })
+    }
+}
+
+#[tonic::async_trait]
+impl EventConsumer for KafkaConsumer {
+    async fn poll(&self) -> Result<Vec<Delivery>> {
+        let mut deliveries = Vec::new();
+        // Waits for the first event only; whatever else has already arrived
+        // comes along in the same batch
+        let mut wait = POLL_TIMEOUT;
+        while deliveries.len() < MAX_BATCH {
+            let message = match tokio::time::timeout(wait, self.consumer.recv()).await {
+                Ok(message) => message?,
+                Err(_) => break,
+            };
+            wait = Duration::ZERO;
+
+            let envelope = Envelope::decode(message.payload().unwrap_or_default());
+            match envelope {
+                Ok(envelope) => deliveries.push(Delivery {
+                    topic: message.topic().to_string(),
+                    envelope,
+                }),
+                Err(e) => warn!(
+                    topic = message.topic(),
+                    partition = message.partition(),
+                    offset = message.offset(),
+                    "Skipped malformed record: {}",
+                    e
+                ),
+            }
+        }
+        Ok(deliveries)
+    }
+
+    async fn commit(&self) -> Result<()> {
+        self.consumer
+            .commit_consumer_state(CommitMode::Async)
+            .context("Failed to commit Kafka offsets")
+    }
+}
+
+/// Publishes to NATS, each topic a subject.
+pub struct NatsPublisher {
+    client: async_nats::Client,
+}
+
+impl NatsPublisher {
+    pub async fn connect(url: &str) -> Result<Self> {
+        let client = async_nats::connect(url)
+            .await
+            .with_context(|| format!("Failed to connect to NATS at {}", url))?;
+        Ok(Self { client })
+    }
+}
+
+#[tonic::async_trait]
+impl EventPublisher for NatsPublisher {
+    async fn publish(&self, topic: &str, envelope: &Envelope) -> Result<()> {
+        self.client
+            .publish(topic.to_string(), envelope.encode_to_vec().into())
+            .await?;
+        // Core NATS acknowledges nothing, so a publish is as done as it
+        // gets once written to the server
+        self.client.flush().await?;
+        Ok(())
+    }
+}
+
+/// Consumes from NATS as a member of a queue group, so each event goes to
+/// one consumer of the group. Core NATS doesn't redeliver, so `commit` has
+/// nothing to do.
+pub struct NatsConsumer {
+    subscriptions: Mutex<StreamMap<String, async_nats::Subscriber>>,
+}
+
+impl NatsConsumer {
+    pub async fn connect(url: &str, group: &str, topics: &[&str]) -> Result<Self> {
+        let client = async_nats::connect(url)
+            .await
+            .with_context(|| format!("Failed to connect to NATS at {}", url))?;
+        let mut subscriptions = StreamMap::new();
+        for topic in topics {
+            let subscriber = client
+                .queue_subscribe(topic.to_string(), group.to_string())
+                .await?;
+            subscriptions.insert(topic.to_string(), subscriber);
+        }
+        info!(group, ?topics, "Subscribed to NATS");
+        Ok(Self {
+            subscriptions: Mutex::new(subscriptions),
+        })
+    }
+}
+
+#[tonic::async_trait]
+impl EventConsumer for NatsConsumer {
+    async fn poll(&self) -> Result<Vec<Delivery>> {
+        let mut subscriptions = self.subscriptions.lock().await;
+        let mut deliveries = Vec::new();
+        let mut wait = POLL_TIMEOUT;
+        while deliveries.len() < MAX_BATCH {
+            let (topic, message) = match tokio::time::timeout(wait, subscriptions.next()).await {
+                Ok(Some(next)) => next,
+                Ok(None) => bail!("NATS subscriptions closed"),
+                Err(_) => break,
+            };
+            wait = Duration::ZERO;
+
+            match Envelope::decode(message.payload) {
+                Ok(envelope) => deliveries.push(Delivery { topic, envelope }),
+                Err(e) => warn!(topic = %topic, "Skipped malformed event: {}", e),
+            }
+        }
+        Ok(deliveries)
+    }
+
+    async fn commit(&self) -> Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+//! Client addresses. The proxy in front of the services appends the address
+//! it received a request from to `x-forwarded-for`; the entries before it are
+//! whatever the client sent, so only the last one can be trusted.
+
+use std::net::SocketAddr;
+use tonic::transport::server::{TcpConnectInfo, TlsConnectInfo};
+
+pub const HEADER: &str = "x-forwarded-for";
+
+/// The entry the proxy added to an `x-forwarded-for` value: the last one,
+/// trimmed; `None` when it's empty.
+pub fn trusted_hop(forwarded_for: &str) -> Option<&str> {
+    forwarded_for
+        .rsplit(',')
+        .next()
+        .map(str::trim)
+        .filter(|hop| !hop.is_empty())
+}
+
+/// The client address given the request's `x-forwarded-for` and the peer
+/// it came from: the trusted hop for proxied requests, the peer otherwise.
+fn resolve(forwarded_for: Option<&str>, peer: Option<SocketAddr>) -> Option<String> {
+    forwarded_for
+        .and_then(trusted_hop)
+        .map(str::to_string)
+        .or_else(|| peer.map(|addr| addr.ip().to_string()))
+}
+
+/// Client address of a request reaching a tower layer.
+pub fn client_address<B>(req: &http::Request<B>) -> Option<String> {
+    let forwarded_for = req.headers().get(HEADER).and_then(|v| v.to_str().ok());
+    let extensions = req.extensions();
+    let peer = extensions
+        .get::<TcpConnectInfo>()
+        .and_then(TcpConnectInfo::remote_addr)
+        .or_else(|| {
+            extensions
+                .get::<TlsConnectInfo<TcpConnectInfo>>()
+                .and_then(|info| info.get_ref().remote_addr())
+        });
+    resolve(forwarded_for, peer)
+}
+
+/// Client address of a request reaching a handler.
+pub fn grpc_client_address<T>(request: &tonic::Request<T>) -> Option<String> {
+    let forwarded_for = request.metadata().get(HEADER).and_then(|v| v.to_str().ok());
+    resolve(forwarded_for, request.remote_addr())
+}
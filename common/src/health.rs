@@ -0,0 +1,127 @@
+//! `grpc.health.v1.Health` backed by readiness checks: a service reports
+//! SERVING only while its database answers and the services it can't work
+//! without report SERVING themselves, so load balancers and probes take an
+//! instance out of rotation when it couldn't handle requests anyway.
+
+use sqlx::PgPool;
+use std::time::Duration;
+use tonic::server::NamedService;
+use tonic::transport::Channel;
+use tonic_health::ServingStatus;
+use tonic_health::pb::HealthCheckRequest;
+use tonic_health::pb::health_check_response;
+use tonic_health::pb::health_client::HealthClient;
+use tonic_health::pb::health_server::{Health, HealthServer};
+use tracing::{info, warn};
+
+/// Path prefix of the health RPCs, for layers that must let probes through.
+pub const PATH_PREFIX: &str = "/grpc.health.v1.Health/";
+
+/// Time a single check, a query or a downstream call, may take.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+pub struct HealthCheck {
+    pool: PgPool,
+    services: Vec<&'static str>,
+    downstream: Vec<(String, Channel)>,
+}
+
+impl HealthCheck {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            services: Vec::new(),
+            downstream: Vec::new(),
+        }
+    }
+
+    /// Reports the health of `S` along with that of the server as a whole.
+    pub fn with_service<S: NamedService>(mut self) -> Self {
+        self.services.push(S::NAME);
+        self
+    }
+
+    /// Requires `name`, reached over `channel`, to be serving.
+    pub fn with_downstream(mut self, name: impl Into<String>, channel: Channel) -> Self {
+        self.downstream.push((name.into(), channel));
+        self
+    }
+
+    /// Why the service isn't ready, if it isn't.
+    async fn check(&self) -> Result<(), String> {
+        match tokio::time::timeout(CHECK_TIMEOUT, sqlx::query("SELECT 1").execute(&self.pool)).await
+        {
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) => return Err(format!("Database error: {}", e)),
+            Err(_) => return Err("Database timed out".to_string()),
+        }
+
+        for (name, channel) in &self.downstream {
+            let mut client = HealthClient::new(channel.clone());
+            let request = HealthCheckRequest {
+                service: String::new(),
+            };
+            match tokio::time::timeout(CHECK_TIMEOUT, client.check(request)).await {
+                Ok(Ok(response))
+                    if response.get_ref().status()
+                        == health_check_response::ServingStatus::Serving => {}
+                Ok(Ok(_)) => return Err(format!("{} service is not serving", name)),
+                Ok(Err(status)) => {
+                    return Err(format!(
+                        "{} service health check failed: {}",
+                        name,
+                        status.message()
+                    ));
+                }
+                Err(_) => return Err(format!("{} service timed out", name)),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks now and then every `interval`, and returns the health service
+    /// reporting the outcome.
+    pub async fn start(self, interval: Duration) -> HealthServer<impl Health> {
+        let (mut reporter, service) = tonic_health::server::health_reporter();
+
+        let mut ticker = tokio::time::interval(interval);
+        ticker.tick().await;
+        let mut last = self.report(&mut reporter, None).await;
+
+        tokio::spawn(async move {
+            loop {
+                ticker.tick().await;
+                last = self.report(&mut reporter, Some(last)).await;
+            }
+        });
+
+        service
+    }
+
+    async fn report(
+        &self,
+        reporter: &mut tonic_health::server::HealthReporter,
+        last: Option<ServingStatus>,
+    ) -> ServingStatus {
+        let result = self.check().await;
+        let status = match result {
+            Ok(()) => ServingStatus::Serving,
+            Err(_) => ServingStatus::NotServing,
+        };
+        // Logged on changes only, not on every check while unhealthy
+        if last != Some(status) {
+            match result {
+                Ok(()) => info!("Ready"),
+                Err(reason) => warn!("Not ready: {}", reason),
+            }
+        }
+
+        // "" is the server as a whole
+        for name in std::iter::once("").chain(self.services.iter().copied()) {
+            reporter.set_service_status(name, status).await;
+        }
+
+        status
+    }
+}
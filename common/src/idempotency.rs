@@ -0,0 +1,866 @@
+//! Idempotent retries. A client that may retry a mutating call, after a
+//! timeout say, sends an `idempotency-key` with it; `IdempotencyLayer` runs
+//! the first call with a key and answers repeats of it with the stored
+//! response instead of running the call again. Keys are scoped to the method
+//! and the caller, i.e. the user its token names or, for callers without
+//! one, the client address, and a key reused for a different request is
+//! refused. Calls without a key are passed through untouched.
+//!
+//! Responses are kept in an `IdempotencyStore`: `MemoryStore` for a single
+//! instance, `PostgresStore` or `RedisStore` when repeats may reach another
+//! instance; `store_from_env` picks one by `IDEMPOTENCY_STORE`.
+
+use crate::auth;
+use crate::error::{self, AppError};
+use crate::forwarded;
+use anyhow::{Context as _, anyhow};
+use bytes::Bytes;
+use dashmap::DashMap;
+use dashmap::mapref::entry::Entry;
+use http::header::AUTHORIZATION;
+use http::{HeaderMap, HeaderName, HeaderValue, Request, Response};
+use http_body::Frame;
+use http_body_util::{BodyExt, Full, StreamBody};
+use prost::Message;
+use redis::aio::ConnectionManager;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::env;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tonic::body::BoxBody;
+use tonic::{Code, Status};
+use tower::{Layer, Service};
+use tracing::{debug, info, warn};
+
+pub const HEADER: &str = "idempotency-key";
+
+/// Longest key accepted; UUIDs and the like fit with room to spare.
+const MAX_KEY_LEN: usize = 255;
+
+/// How long a completed call's response answers repeats by default.
+const DEFAULT_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// How long a claim holds without a response, so a call whose instance died
+/// mid-way can be retried after a while rather than never.
+const CLAIM_LEASE: Duration = Duration::from_secs(60);
+
+const DEFAULT_MAX_ENTRIES: usize = 10_000;
+
+/// Interval of the sweep deleting expired keys from Postgres.
+const POSTGRES_EVICTION_INTERVAL: Duration = Duration::from_secs(3600);
+
+#[derive(Clone, PartialEq, Message)]
+struct Header {
+    #[prost(string, tag = "1")]
+    name: String,
+    #[prost(bytes = "vec", tag = "2")]
+    value: Vec<u8>,
+}
+
+/// A unary response as stored: its headers, message and trailers.
+#[derive(Clone, PartialEq, Message)]
+pub struct StoredResponse {
+    #[prost(message, repeated, tag = "1")]
+    headers: Vec<Header>,
+    #[prost(bytes = "vec", tag = "2")]
+    body: Vec<u8>,
+    /// Empty for trailers-only responses, which carry the status in the
+    /// headers.
+    #[prost(message, repeated, tag = "3")]
+    trailers: Vec<Header>,
+}
+
+fn to_headers(map: &HeaderMap) -> Vec<Header> {
+    map.iter()
+        .map(|(name, value)| Header {
+            name: name.to_string(),
+            value: value.as_bytes().to_vec(),
+        })
+        .collect()
+}
+
+fn to_header_map(headers: &[Header]) -> HeaderMap {
+    headers
+        .iter()
+        .filter_map(|header| {
+            let name = HeaderName::from_bytes(header.name.as_bytes()).ok()?;
+            let value = HeaderValue::from_bytes(&header.value).ok()?;
+            Some((name, value))
+        })
+        .collect()
+}
+
+impl StoredResponse {
+    fn new(headers: &HeaderMap, body: Bytes, trailers: Option<&HeaderMap>) -> Self {
+        Self {
+            headers: to_headers(headers),
+            body: body.to_vec(),
+            trailers: trailers.map(to_headers).unwrap_or_default(),
+        }
+    }
+
+    fn to_response(&self) -> Response<BoxBody> {
+        let mut frames = vec![Ok::<_, Status>(Frame::data(Bytes::from(self.body.clone())))];
+        if !self.trailers.is_empty() {
+            frames.push(Ok(Frame::trailers(to_header_map(&self.trailers))));
+        }
+
+        let mut response = Response::new(tonic::body::boxed(StreamBody::new(tokio_stream::iter(
+            frames,
+        ))));
+        *response.headers_mut() = to_header_map(&self.headers);
+        response
+    }
+}
+
+/// What claiming a key found.
+pub enum Claim {
+    /// The key is this call's to run.
+    Acquired,
+    /// A call claimed the key before: still running unless it has a
+    /// response.
+    Held {
+        fingerprint: Vec<u8>,
+        response: Option<StoredResponse>,
+    },
+}
+
+/// Where claimed keys and their responses are kept. Expired keys are free
+/// to claim again.
+#[tonic::async_trait]
+pub trait IdempotencyStore: Send + Sync {
+    /// Claims `key` for a request with `fingerprint` for `lease`, unless it's
+    /// held already.
+    async fn claim(
+        &self,
+        key: &str,
+        fingerprint: &[u8],
+        lease: Duration,
+    ) -> Result<Claim, AppError>;
+
+    /// Stores the response of the call holding `key`, answering repeats for
+    /// `ttl`.
+    async fn complete(
+        &self,
+        key: &str,
+        response: &StoredResponse,
+        ttl: Duration,
+    ) -> Result<(), AppError>;
+
+    /// Gives up the claim on `key`, so a retry runs the call again.
+    async fn release(&self, key: &str) -> Result<(), AppError>;
+}
+
+struct MemoryEntry {
+    fingerprint: Vec<u8>,
+    response: Option<StoredResponse>,
+    expires_at: Instant,
+}
+
+/// Keys held in this process, for services running a single instance.
+pub struct MemoryStore {
+    max_entries: usize,
+    entries: DashMap<String, MemoryEntry>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self {
+            max_entries: DEFAULT_MAX_ENTRIES,
+            entries: DashMap::new(),
+        }
+    }
+
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+}
+
+impl Default for MemoryStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[tonic::async_trait]
+impl IdempotencyStore for MemoryStore {
+    async fn claim(
+        &self,
+        key: &str,
+        fingerprint: &[u8],
+        lease: Duration,
+    ) -> Result<Claim, AppError> {
+        let now = Instant::now();
+        if self.entries.len() >= self.max_entries && !self.entries.contains_key(key) {
+            self.entries.retain(|_, entry| entry.expires_at > now);
+            if self.entries.len() >= self.max_entries {
+                return Err(AppError::Unavailable(
+                    "Idempotency key store is full".to_string(),
+                ));
+            }
+        }
+
+        let claimed = MemoryEntry {
+            fingerprint: fingerprint.to_vec(),
+            response: None,
+            expires_at: now + lease,
+        };
+        match self.entries.entry(key.to_string()) {
+            Entry::Occupied(entry) if entry.get().expires_at > now => Ok(Claim::Held {
+                fingerprint: entry.get().fingerprint.clone(),
+                response: entry.get().response.clone(),
+            }),
+            Entry::Occupied(mut entry) => {
+                entry.insert(claimed);
+                Ok(Claim::Acquired)
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(claimed);
+                Ok(Claim::Acquired)
+            }
+        }
+    }
+
+    async fn complete(
+        &self,
+        key: &str,
+        response: &StoredResponse,
+        ttl: Duration,
+    ) -> Result<(), AppError> {
+        if let Some(mut entry) = self.entries.get_mut(key) {
+            entry.response = Some(response.clone());
+            entry.expires_at = Instant::now() + ttl;
+        }
+        Ok(())
+    }
+
+    async fn release(&self, key: &str) -> Result<(), AppError> {
+        self.entries.remove(key);
+        Ok(())
+    }
+}
+
+/// Keys held in the `idempotency_keys` table, shared by every instance.
+#[derive(Clone)]
+pub struct PostgresStore {
+    pool: PgPool,
+}
+
+impl PostgresStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Deletes the expired keys every `interval`.
+    pub fn spawn_eviction(&self, interval: Duration) {
+        let pool = self.pool.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match sqlx::query("DELETE FROM idempotency_keys WHERE expires_at <= NOW()")
+                    .execute(&pool)
+                    .await
+                {
+                    Ok(result) if result.rows_affected() > 0 => {
+                        debug!(evicted = result.rows_affected(), "Evicted idempotency keys");
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!("Idempotency key eviction failed: {}", e),
+                }
+            }
+        });
+    }
+}
+
+#[tonic::async_trait]
+impl IdempotencyStore for PostgresStore {
+    async fn claim(
+        &self,
+        key: &str,
+        fingerprint: &[u8],
+        lease: Duration,
+    ) -> Result<Claim, AppError> {
+        // The key may expire between the insert and the select; the next
+        // round claims it then
+        loop {
+            let claimed: Option<(String,)> = sqlx::query_as(
+                r#"
+                INSERT INTO idempotency_keys (key, fingerprint, expires_at)
+                VALUES ($1, $2, NOW() + $3 * INTERVAL '1 second')
+                ON CONFLICT (key) DO UPDATE
+                SET fingerprint = EXCLUDED.fingerprint,
+                    response = NULL,
+                    expires_at = EXCLUDED.expires_at,
+                    created_at = CURRENT_TIMESTAMP
+                WHERE idempotency_keys.expires_at <= NOW()
+                RETURNING key
+                "#,
+            )
+            .bind(key)
+            .bind(fingerprint)
+            .bind(lease.as_secs_f64())
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(AppError::from)?;
+            if claimed.is_some() {
+                return Ok(Claim::Acquired);
+            }
+
+            let held: Option<(Vec<u8>, Option<Vec<u8>>)> = sqlx::query_as(
+                "SELECT fingerprint, response FROM idempotency_keys WHERE key = $1 AND expires_at > NOW()",
+            )
+            .bind(key)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(AppError::from)?;
+            if let Some((fingerprint, response)) = held {
+                let response = response
+                    .map(|encoded| StoredResponse::decode(encoded.as_slice()))
+                    .transpose()
+                    .map_err(|e| AppError::Internal(format!("Corrupt stored response: {}", e)))?;
+                return Ok(Claim::Held {
+                    fingerprint,
+                    response,
+                });
+            }
+        }
+    }
+
+    async fn complete(
+        &self,
+        key: &str,
+        response: &StoredResponse,
+        ttl: Duration,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            "UPDATE idempotency_keys SET response = $2, expires_at = NOW() + $3 * INTERVAL '1 second' WHERE key = $1",
+        )
+        .bind(key)
+        .bind(response.encode_to_vec())
+        .bind(ttl.as_secs_f64())
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::from)?;
+        Ok(())
+    }
+
+    async fn release(&self, key: &str) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM idempotency_keys WHERE key = $1 AND response IS NULL")
+            .bind(key)
+            .execute(&self.pool)
+            .await
+            .map_err(AppError::from)?;
+        Ok(())
+    }
+}
+
+/// A key as kept in Redis: the request it was claimed for, and the response
+/// once the call completed.
+#[derive(Clone, PartialEq, Message)]
+struct RedisEntry {
+    #[prost(bytes = "vec", tag = "1")]
+    fingerprint: Vec<u8>,
+    #[prost(message, optional, tag = "2")]
+    response: Option<StoredResponse>,
+}
+
+/// Keys held in Redis, shared by every instance. A key is claimed with
+/// `SET NX` and expires with its lease or TTL, so nothing needs evicting.
+#[derive(Clone)]
+pub struct RedisStore {
+    redis: ConnectionManager,
+}
+
+impl RedisStore {
+    /// Keeps the keys in the Redis at `url`.
+    pub async fn connect(url: &str) -> anyhow::Result<Self> {
+        let client = redis::Client::open(url).context("Invalid REDIS_URL")?;
+        let redis = ConnectionManager::new(client)
+            .await
+            .context("Failed to connect to Redis")?;
+        Ok(Self { redis })
+    }
+
+    fn key(key: &str) -> String {
+        format!("idempotency:{}", key)
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<RedisEntry>, AppError> {
+        let encoded: Option<Vec<u8>> = redis::cmd("GET")
+            .arg(Self::key(key))
+            .query_async(&mut self.redis.clone())
+            .await
+            .map_err(redis_error)?;
+        encoded
+            .map(|encoded| RedisEntry::decode(encoded.as_slice()))
+            .transpose()
+            .map_err(|e| AppError::Internal(format!("Corrupt stored response: {}", e)))
+    }
+}
+
+fn redis_error(e: redis::RedisError) -> AppError {
+    warn!("Idempotency key store error: {}", e);
+    AppError::Unavailable("Idempotency key store is unavailable".to_string())
+}
+
+fn millis(duration: Duration) -> u64 {
+    (duration.as_millis() as u64).max(1)
+}
+
+#[tonic::async_trait]
+impl IdempotencyStore for RedisStore {
+    async fn claim(
+        &self,
+        key: &str,
+        fingerprint: &[u8],
+        lease: Duration,
+    ) -> Result<Claim, AppError> {
+        let claimed = RedisEntry {
+            fingerprint: fingerprint.to_vec(),
+            response: None,
+        };
+        // The key may expire between the set and the get; the next round
+        // claims it then
+        loop {
+            let set: Option<String> = redis::cmd("SET")
+                .arg(Self::key(key))
+                .arg(claimed.encode_to_vec())
+                .arg("NX")
+                .arg("PX")
+                .arg(millis(lease))
+                .query_async(&mut self.redis.clone())
+                .await
+                .map_err(redis_error)?;
+            if set.is_some() {
+                return Ok(Claim::Acquired);
+            }
+
+            if let Some(held) = self.get(key).await? {
+                return Ok(Claim::Held {
+                    fingerprint: held.fingerprint,
+                    response: held.response,
+                });
+            }
+        }
+    }
+
+    async fn complete(
+        &self,
+        key: &str,
+        response: &StoredResponse,
+        ttl: Duration,
+    ) -> Result<(), AppError> {
+        // Only the call holding the key completes it, so nothing else writes
+        // it in between; a lease that ran out leaves nothing to complete
+        let Some(mut entry) = self.get(key).await? else {
+            return Ok(());
+        };
+        entry.response = Some(response.clone());
+        redis::cmd("SET")
+            .arg(Self::key(key))
+            .arg(entry.encode_to_vec())
+            .arg("XX")
+            .arg("PX")
+            .arg(millis(ttl))
+            .query_async::<()>(&mut self.redis.clone())
+            .await
+            .map_err(redis_error)
+    }
+
+    async fn release(&self, key: &str) -> Result<(), AppError> {
+        match self.get(key).await? {
+            Some(entry) if entry.response.is_none() => redis::cmd("DEL")
+                .arg(Self::key(key))
+                .query_async::<()>(&mut self.redis.clone())
+                .await
+                .map_err(redis_error),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The store `IDEMPOTENCY_STORE` names: `postgres` (the default) in `pool`,
+/// `redis` in the Redis at `REDIS_URL`, or `memory` for a single instance.
+pub async fn store_from_env(pool: &PgPool) -> anyhow::Result<Arc<dyn IdempotencyStore>> {
+    match env::var("IDEMPOTENCY_STORE").unwrap_or_default().as_str() {
+        "" | "postgres" => {
+            let store = PostgresStore::new(pool.clone());
+            store.spawn_eviction(POSTGRES_EVICTION_INTERVAL);
+            Ok(Arc::new(store))
+        }
+        "redis" => {
+            let url = env::var("REDIS_URL").context("REDIS_URL must be set")?;
+            let store = RedisStore::connect(&url).await?;
+            info!("Keeping idempotency keys in Redis");
+            Ok(Arc::new(store))
+        }
+        "memory" => Ok(Arc::new(MemoryStore::new())),
+        other => Err(anyhow!("Unknown IDEMPOTENCY_STORE: {}", other)),
+    }
+}
+
+/// Who the caller is, so one caller's key can't replay another's response:
+/// the user of a valid bearer token, as the rate limiter has it, so a key
+/// holds across the tokens a user signs in with. Credentials that don't
+/// decode are hashed instead, and callers without any are told apart by
+/// their client address, see `forwarded::client_address`. `None` when
+/// there's no telling who the caller is.
+fn caller<B>(req: &Request<B>) -> Option<String> {
+    let Some(value) = req.headers().get(AUTHORIZATION) else {
+        return forwarded::client_address(req).map(|ip| format!("ip:{}", ip));
+    };
+    let claims = value
+        .to_str()
+        .ok()
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .and_then(|token| auth::decode_token(token).ok());
+    Some(match claims {
+        Some(claims) => format!("user:{}", claims.sub),
+        None => hex::encode(Sha256::digest(value.as_bytes())),
+    })
+}
+
+/// Whether a response is the outcome of the call, which repeats get too:
+/// a success or a refusal. Failures that may pass, e.g. UNAVAILABLE, aren't,
+/// so a retry runs the call again.
+fn is_outcome(status: Option<&Status>) -> bool {
+    status.is_some_and(|status| status.code() == Code::Ok || error::is_refusal(status))
+}
+
+#[derive(Clone)]
+pub struct IdempotencyLayer {
+    store: Arc<dyn IdempotencyStore>,
+    ttl: Duration,
+}
+
+impl IdempotencyLayer {
+    pub fn new(store: Arc<dyn IdempotencyStore>) -> Self {
+        Self {
+            store,
+            ttl: DEFAULT_TTL,
+        }
+    }
+
+    /// Answers repeats of a completed call for `ttl` after it.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+}
+
+impl<S> Layer<S> for IdempotencyLayer {
+    type Service = IdempotencyService<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        IdempotencyService {
+            inner: service,
+            store: self.store.clone(),
+            ttl: self.ttl,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct IdempotencyService<S> {
+    inner: S,
+    store: Arc<dyn IdempotencyStore>,
+    ttl: Duration,
+}
+
+impl<S> Service<Request<BoxBody>> for IdempotencyService<S>
+where
+    S: Service<Request<BoxBody>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: Send,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<BoxBody>) -> Self::Future {
+        let mut inner = self.inner.clone();
+
+        let Some(key) = req.headers().get(HEADER) else {
+            return Box::pin(inner.call(req));
+        };
+        let key = match key.to_str() {
+            Ok(key) if !key.is_empty() && key.len() <= MAX_KEY_LEN => key,
+            _ => {
+                let status = error::invalid_argument(
+                    "INVALID_IDEMPOTENCY_KEY",
+                    format!("{} must be 1 to {} ASCII characters", HEADER, MAX_KEY_LEN),
+                );
+                return Box::pin(async move { Ok(status.into_http()) });
+            }
+        };
+        // Anonymous callers would otherwise share their keys, and replay
+        // each other's responses
+        let Some(caller) = caller(&req) else {
+            let status = error::invalid_argument(
+                "IDEMPOTENCY_KEY_UNSCOPED",
+                format!("{} needs a signed-in caller or a client address", HEADER),
+            );
+            return Box::pin(async move { Ok(status.into_http()) });
+        };
+        let path = req.uri().path().to_owned();
+        let scope = format!("{} {} {}", path, caller, key);
+        let store = self.store.clone();
+        let ttl = self.ttl;
+
+        Box::pin(async move {
+            // Buffer the (unary) request so repeats can be told apart from
+            // other requests under the same key
+            let (parts, body) = req.into_parts();
+            let body = match body.collect().await {
+                Ok(collected) => collected.to_bytes(),
+                Err(status) => return Ok(status.into_http()),
+            };
+            let fingerprint = Sha256::digest(&body).to_vec();
+
+            match store.claim(&scope, &fingerprint, CLAIM_LEASE).await {
+                Ok(Claim::Acquired) => {}
+                Ok(Claim::Held {
+                    fingerprint: held, ..
+                }) if held != fingerprint => {
+                    let status = error::invalid_argument(
+                        "IDEMPOTENCY_KEY_REUSED",
+                        "Idempotency key was used for a different request",
+                    );
+                    return Ok(status.into_http());
+                }
+                Ok(Claim::Held {
+                    response: Some(response),
+                    ..
+                }) => {
+                    debug!(path = %path, "Replaying response of idempotency key");
+                    return Ok(response.to_response());
+                }
+                Ok(Claim::Held { response: None, .. }) => {
+                    let status = error::with_reason(
+                        Code::Aborted,
+                        "REQUEST_IN_PROGRESS",
+                        "A request with this idempotency key is still in progress",
+                    );
+                    return Ok(status.into_http());
+                }
+                Err(e) => return Ok(Status::from(e).into_http()),
+            }
+
+            let req = Request::from_parts(parts, tonic::body::boxed(Full::new(body)));
+            let response = match inner.call(req).await {
+                Ok(response) => response,
+                Err(e) => {
+                    release(store.as_ref(), &scope).await;
+                    return Err(e);
+                }
+            };
+
+            let (parts, body) = response.into_parts();
+            let collected = match body.collect().await {
+                Ok(collected) => collected,
+                Err(status) => {
+                    release(store.as_ref(), &scope).await;
+                    return Ok(status.into_http());
+                }
+            };
+            let trailers = collected.trailers().cloned();
+            let stored =
+                StoredResponse::new(&parts.headers, collected.to_bytes(), trailers.as_ref());
+
+            // Trailers-only responses carry the status in the headers
+            let status = Status::from_header_map(&parts.headers)
+                .or_else(|| trailers.as_ref().and_then(Status::from_header_map));
+            if is_outcome(status.as_ref()) {
+                if let Err(e) = store.complete(&scope, &stored, ttl).await {
+                    warn!(path = %path, "Failed to store idempotent response: {}", e);
+                }
+            } else {
+                release(store.as_ref(), &scope).await;
+            }
+
+            Ok(stored.to_response())
+        })
+    }
+}
+
+async fn release(store: &dyn IdempotencyStore, key: &str) {
+    if let Err(e) = store.release(key).await {
+        warn!("Failed to release idempotency key: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::Role;
+    use std::convert::Infallible;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const PATH: &str = "/order.OrderService/CreateOrder";
+
+    /// A service answering each call with its number, with status `code`.
+    #[derive(Clone)]
+    struct Counter {
+        calls: Arc<AtomicUsize>,
+        code: Code,
+    }
+
+    impl Service<Request<BoxBody>> for Counter {
+        type Response = Response<BoxBody>;
+        type Error = Infallible;
+        type Future = std::future::Ready<Result<Response<BoxBody>, Infallible>>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, _req: Request<BoxBody>) -> Self::Future {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            let mut response = Response::new(tonic::body::boxed(Full::new(Bytes::from(format!(
+                "call {}",
+                call
+            )))));
+            Status::new(self.code, "")
+                .add_header(response.headers_mut())
+                .unwrap();
+            std::future::ready(Ok(response))
+        }
+    }
+
+    fn service(code: Code) -> (IdempotencyService<Counter>, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Counter {
+            calls: calls.clone(),
+            code,
+        };
+        let layer = IdempotencyLayer::new(Arc::new(MemoryStore::new()));
+        (layer.layer(counter), calls)
+    }
+
+    /// A request with `key` and `body`, plus `headers`.
+    fn request(
+        key: &str,
+        body: &'static str,
+        headers: &[(&'static str, String)],
+    ) -> Request<BoxBody> {
+        let mut req = Request::new(tonic::body::boxed(Full::new(Bytes::from(body))));
+        *req.uri_mut() = PATH.parse().unwrap();
+        req.headers_mut()
+            .insert(HEADER, HeaderValue::from_str(key).unwrap());
+        for (name, value) in headers {
+            req.headers_mut()
+                .insert(*name, HeaderValue::from_str(value).unwrap());
+        }
+        req
+    }
+
+    fn bearer(user_id: &str) -> (&'static str, String) {
+        let token = auth::issue_token(user_id, Role::Customer, None).unwrap();
+        ("authorization", format!("Bearer {}", token))
+    }
+
+    fn from(ip: &str) -> (&'static str, String) {
+        (forwarded::HEADER, ip.to_string())
+    }
+
+    /// What `req` is answered with: the body, or the error's status.
+    async fn send(
+        service: &mut IdempotencyService<Counter>,
+        req: Request<BoxBody>,
+    ) -> Result<String, Status> {
+        let response = service.call(req).await.unwrap();
+        match Status::from_header_map(response.headers()) {
+            Some(status) if status.code() != Code::Ok => Err(status),
+            _ => {
+                let body = response.into_body().collect().await.unwrap().to_bytes();
+                Ok(String::from_utf8(body.to_vec()).unwrap())
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn repeats_are_answered_with_the_first_response() {
+        let (mut service, calls) = service(Code::Ok);
+        let ann = [bearer("ann")];
+
+        let first = send(&mut service, request("k1", "order", &ann)).await;
+        let repeat = send(&mut service, request("k1", "order", &ann)).await;
+        assert_eq!(first.unwrap(), "call 1");
+        assert_eq!(repeat.unwrap(), "call 1");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn a_key_reused_for_another_request_is_refused() {
+        let (mut service, calls) = service(Code::Ok);
+        let ann = [bearer("ann")];
+
+        send(&mut service, request("k1", "order", &ann))
+            .await
+            .unwrap();
+        let status = send(&mut service, request("k1", "other order", &ann))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::InvalidArgument);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn keys_are_scoped_to_the_user() {
+        let (mut service, calls) = service(Code::Ok);
+
+        let ann = send(&mut service, request("k1", "order", &[bearer("ann")])).await;
+        let bob = send(&mut service, request("k1", "order", &[bearer("bob")])).await;
+        assert_eq!(ann.unwrap(), "call 1");
+        assert_eq!(bob.unwrap(), "call 2");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn anonymous_keys_are_scoped_to_the_client_address() {
+        let (mut service, calls) = service(Code::Ok);
+
+        let first = send(&mut service, request("k1", "order", &[from("10.0.0.1")])).await;
+        let other = send(&mut service, request("k1", "order", &[from("10.0.0.2")])).await;
+        let repeat = send(&mut service, request("k1", "order", &[from("10.0.0.1")])).await;
+        assert_eq!(first.unwrap(), "call 1");
+        assert_eq!(other.unwrap(), "call 2");
+        assert_eq!(repeat.unwrap(), "call 1");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn anonymous_keys_without_a_client_address_are_refused() {
+        let (mut service, calls) = service(Code::Ok);
+
+        let status = send(&mut service, request("k1", "order", &[]))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::InvalidArgument);
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn failures_that_may_pass_are_run_again() {
+        let (mut service, calls) = service(Code::Unavailable);
+        let ann = [bearer("ann")];
+
+        send(&mut service, request("k1", "order", &ann))
+            .await
+            .unwrap_err();
+        send(&mut service, request("k1", "order", &ann))
+            .await
+            .unwrap_err();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
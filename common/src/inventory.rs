@@ -0,0 +1,98 @@
+use sqlx::{PgConnection, PgPool};
+use std::collections::HashMap;
+
+/// Atomically takes `quantity` units of a product. The decrement only applies
+/// while enough stock is left and the product is published and neither
+/// deleted nor quarantined, so concurrent orders can't oversell. Digital
+/// products have no stock to take and pass with their level unchanged.
+/// Returns the new stock level and whether any stock was taken, or `None`
+/// when the decrement was refused.
+pub async fn decrement_stock(
+    conn: &mut PgConnection,
+    product_id: &str,
+    quantity: i32,
+) -> Result<Option<(i32, bool)>, sqlx::Error> {
+    sqlx::query_as(
+        "UPDATE products
+         SET stock_quantity = CASE WHEN product_type = 'DIGITAL' THEN stock_quantity ELSE stock_quantity - $1 END,
+             updated_at = CURRENT_TIMESTAMP
+         WHERE id = $2 AND (product_type = 'DIGITAL' OR stock_quantity >= $1)
+           AND deleted_at IS NULL AND status = 'PUBLISHED'
+           AND NOT EXISTS (
+               SELECT 1 FROM product_quarantines q
+               WHERE q.product_id = products.id AND q.lot_number IS NULL AND q.released_at IS NULL
+           )
+         RETURNING stock_quantity, product_type <> 'DIGITAL'",
+    )
+    .bind(quantity)
+    .bind(product_id)
+    .fetch_optional(conn)
+    .await
+}
+
+/// Current sellable stock per product, read without locking. Unpublished,
+/// deleted and quarantined products report zero, digital ones `i32::MAX`,
+/// and unknown ids are left out. Only a preview: `decrement_stock` remains
+/// the authoritative check.
+pub async fn sellable_stock(
+    db: &PgPool,
+    product_ids: &[String],
+) -> Result<HashMap<String, i32>, sqlx::Error> {
+    let rows: Vec<(String, i32)> = sqlx::query_as(
+        "SELECT id,
+                CASE WHEN deleted_at IS NOT NULL OR status <> 'PUBLISHED' OR EXISTS (
+                    SELECT 1 FROM product_quarantines q
+                    WHERE q.product_id = products.id AND q.lot_number IS NULL AND q.released_at IS NULL
+                ) THEN 0
+                WHEN product_type = 'DIGITAL' THEN 2147483647
+                ELSE stock_quantity END
+         FROM products WHERE id = ANY($1)",
+    )
+    .bind(product_ids)
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows.into_iter().collect())
+}
+
+/// How a product takes orders beyond its stock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backorder {
+    /// Not released yet: every unit is ordered ahead of its release.
+    Preorder,
+    /// Released and backorderable: units past the stock are owed.
+    Allowed,
+}
+
+/// Backorder terms of the sellable products among `product_ids`. Products
+/// that only sell from stock, and those not sellable at all, are left out.
+pub async fn backorder_terms(
+    db: &PgPool,
+    product_ids: &[String],
+) -> Result<HashMap<String, Backorder>, sqlx::Error> {
+    let rows: Vec<(String, bool)> = sqlx::query_as(
+        "SELECT id, COALESCE(available_from > NOW(), FALSE)
+         FROM products
+         WHERE id = ANY($1) AND (allow_backorder OR available_from > NOW())
+           AND deleted_at IS NULL AND status = 'PUBLISHED'
+           AND NOT EXISTS (
+               SELECT 1 FROM product_quarantines q
+               WHERE q.product_id = products.id AND q.lot_number IS NULL AND q.released_at IS NULL
+           )",
+    )
+    .bind(product_ids)
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(id, preorder)| {
+            let terms = if preorder {
+                Backorder::Preorder
+            } else {
+                Backorder::Allowed
+            };
+            (id, terms)
+        })
+        .collect())
+}
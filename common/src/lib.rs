@@ -1,2 +1,31 @@
+pub mod auth;
+pub mod cache;
+pub mod captcha;
+pub mod catch_panic;
+pub mod client;
+pub mod error;
+pub mod events;
+pub mod forwarded;
+pub mod health;
+pub mod idempotency;
+pub mod inventory;
 pub mod logging;
-pub mod ratelimit;
\ No newline at end of file
+pub mod metrics;
+pub mod order_events;
+pub mod outbox;
+pub mod pagination;
+pub mod pricing;
+pub mod public_id;
+pub mod ratelimit;
+pub mod redact;
+pub mod request_id;
+pub mod resilience;
+pub mod response_cache;
+pub mod sandbox;
+pub mod server;
+pub mod settings;
+pub mod slo;
+pub mod switches;
+pub mod telemetry;
+pub mod tls;
+pub mod validation;
\ No newline at end of file
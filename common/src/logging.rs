@@ -1,12 +1,15 @@
+use bytes::Bytes;
 use http::{Request, Response};
+use http_body::{Body, Frame, SizeHint};
 use pin_project::pin_project;
 use std::future::Future;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 use std::time::Instant;
 use tonic::body::BoxBody;
+use tonic::{Code, Status};
 use tower::{Layer, Service};
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 #[derive(Clone)]
 pub struct LoggingLayer;
@@ -52,9 +55,57 @@ where
 
         ResponseFuture {
             future,
-            start,
-            path,
-            method: method.to_string(),
+            completion: Some(Completion {
+                start,
+                path,
+                method: method.to_string(),
+                done: false,
+            }),
+        }
+    }
+}
+
+/// The completion log line of a call, written once its gRPC status is known:
+/// from the headers when the handler failed, from the trailers at the end of
+/// the body otherwise. A call dropped before either, e.g. because the client
+/// went away, is logged as cancelled.
+struct Completion {
+    start: Instant,
+    path: String,
+    method: String,
+    done: bool,
+}
+
+impl Completion {
+    fn log(&mut self, status: &Status) {
+        self.done = true;
+        let duration = self.start.elapsed();
+
+        if status.code() == Code::Ok {
+            info!(
+                method = %self.method,
+                path = %self.path,
+                grpc_code = ?status.code(),
+                duration_ms = %duration.as_millis(),
+                "gRPC request completed"
+            );
+        } else {
+            warn!(
+                method = %self.method,
+                path = %self.path,
+                grpc_code = ?status.code(),
+                grpc_message = %status.message(),
+                duration_ms = %duration.as_millis(),
+                "gRPC request completed"
+            );
+        }
+    }
+}
+
+impl Drop for Completion {
+    fn drop(&mut self) {
+        if !self.done {
+            self.log(&Status::cancelled("Dropped before its status was sent"));
         }
     }
 }
@@ -63,9 +114,7 @@ where
 pub struct ResponseFuture<F> {
     #[pin]
     future: F,
-    start: Instant,
-    path: String,
-    method: String,
+    completion: Option<Completion>,
 }
 
 impl<F, E> Future for ResponseFuture<F>
@@ -79,31 +128,83 @@ where
 
         match this.future.poll(cx) {
             Poll::Ready(result) => {
-                let duration = this.start.elapsed();
-
-                match &result {
-                    Ok(response) => {
-                        info!(
-                            method = %this.method,
-                            path = %this.path,
-                            status = %response.status(),
-                            duration_ms = %duration.as_millis(),
-                            "gRPC request completed"
-                        );
-                    }
-                    Err(_) => {
+                let mut completion = this
+                    .completion
+                    .take()
+                    .expect("ResponseFuture polled after completion");
+
+                match result {
+                    Ok(response) => match Status::from_header_map(response.headers()) {
+                        Some(status) => {
+                            completion.log(&status);
+                            Poll::Ready(Ok(response))
+                        }
+                        None => Poll::Ready(Ok(response.map(|body| {
+                            tonic::body::boxed(LoggedBody {
+                                inner: body,
+                                completion: Some(completion),
+                            })
+                        }))),
+                    },
+                    Err(e) => {
+                        completion.done = true;
                         error!(
-                            method = %this. method,
-                            path = %this.path,
-                            duration_ms = %duration.as_millis(),
+                            method = %completion.method,
+                            path = %completion.path,
+                            duration_ms = %completion.start.elapsed().as_millis(),
                             "gRPC request failed"
                         );
+                        Poll::Ready(Err(e))
                     }
                 }
-
-                Poll::Ready(result)
             }
             Poll::Pending => Poll::Pending,
         }
     }
 }
+
+/// A response body that logs the call's completion when the trailers with
+/// its status go by.
+#[pin_project]
+struct LoggedBody {
+    #[pin]
+    inner: BoxBody,
+    completion: Option<Completion>,
+}
+
+impl Body for LoggedBody {
+    type Data = Bytes;
+    type Error = Status;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let this = self.project();
+        let frame = match this.inner.poll_frame(cx) {
+            Poll::Ready(frame) => frame,
+            Poll::Pending => return Poll::Pending,
+        };
+
+        let status = match &frame {
+            Some(Ok(frame)) => frame.trailers_ref().and_then(Status::from_header_map),
+            Some(Err(status)) => Some(status.clone()),
+            None => None,
+        };
+        if let Some(status) = status
+            && let Some(mut completion) = this.completion.take()
+        {
+            completion.log(&status);
+        }
+
+        Poll::Ready(frame)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
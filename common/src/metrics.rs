@@ -0,0 +1,240 @@
+//! Prometheus metrics. `MetricsLayer` counts every RPC, its failures by gRPC
+//! code and its latency, labelled by method; `serve` exposes them, and any
+//! gauges a service registers itself, as `GET /metrics` on a port of its own.
+
+use axum::Router;
+use axum::extract::State;
+use axum::http::{StatusCode, header};
+use axum::response::IntoResponse;
+use axum::routing::get;
+use http::{Request, Response};
+use pin_project::pin_project;
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry, TextEncoder,
+};
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Instant;
+use tonic::body::BoxBody;
+use tower::{Layer, Service};
+use tracing::{error, info};
+
+/// Latency buckets in seconds, from 5ms to 10s.
+const LATENCY_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// A service's metrics registry with the per-RPC metrics in it.
+pub struct Metrics {
+    registry: Registry,
+    requests: IntCounterVec,
+    errors: IntCounterVec,
+    latency: HistogramVec,
+}
+
+impl Metrics {
+    /// Metrics of `service`, which every series is labelled with.
+    pub fn new(service: &str) -> Arc<Self> {
+        let registry = Registry::new_custom(
+            None,
+            Some([("service".to_string(), service.to_string())].into()),
+        )
+        .expect("Valid metrics registry labels");
+
+        let requests = IntCounterVec::new(
+            Opts::new("grpc_requests_total", "RPCs received"),
+            &["method"],
+        )
+        .expect("Valid metric");
+        let errors = IntCounterVec::new(
+            Opts::new("grpc_errors_total", "RPCs that failed, by gRPC code"),
+            &["method", "code"],
+        )
+        .expect("Valid metric");
+        let latency = HistogramVec::new(
+            HistogramOpts::new("grpc_request_duration_seconds", "RPC latency")
+                .buckets(LATENCY_BUCKETS.to_vec()),
+            &["method"],
+        )
+        .expect("Valid metric");
+
+        for collector in [
+            Box::new(requests.clone()) as Box<dyn prometheus::core::Collector>,
+            Box::new(errors.clone()),
+            Box::new(latency.clone()),
+        ] {
+            registry
+                .register(collector)
+                .expect("Metric registered once");
+        }
+
+        Arc::new(Self {
+            registry,
+            requests,
+            errors,
+            latency,
+        })
+    }
+
+    /// The registry, for a service to add metrics of its own to.
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    fn record(&self, method: &str, code: Option<&str>, elapsed: f64) {
+        self.requests.with_label_values(&[method]).inc();
+        self.latency.with_label_values(&[method]).observe(elapsed);
+        if let Some(code) = code {
+            self.errors.with_label_values(&[method, code]).inc();
+        }
+    }
+
+    /// The registry in the Prometheus text format.
+    fn render(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        // Writing to a Vec only fails on malformed metrics, which the
+        // constructors above rule out
+        let _ = TextEncoder::new().encode(&self.registry.gather(), &mut buffer);
+        buffer
+    }
+}
+
+/// gRPC code of a failed response, e.g. "NotFound"; `None` when it succeeded.
+/// Handlers that return an error answer with the status in the headers;
+/// streams that fail after their first message aren't seen here.
+fn failure_code<B>(response: &Response<B>) -> Option<String> {
+    let code = response
+        .headers()
+        .get("grpc-status")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<i32>().ok())
+        .map(tonic::Code::from)
+        .unwrap_or(tonic::Code::Ok);
+    (code != tonic::Code::Ok).then(|| format!("{:?}", code))
+}
+
+#[derive(Clone)]
+pub struct MetricsLayer {
+    metrics: Arc<Metrics>,
+}
+
+impl MetricsLayer {
+    pub fn new(metrics: Arc<Metrics>) -> Self {
+        Self { metrics }
+    }
+}
+
+impl<S> Layer<S> for MetricsLayer {
+    type Service = MetricsMiddleware<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        MetricsMiddleware {
+            inner: service,
+            metrics: self.metrics.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct MetricsMiddleware<S> {
+    inner: S,
+    metrics: Arc<Metrics>,
+}
+
+impl<S> Service<Request<BoxBody>> for MetricsMiddleware<S>
+where
+    S: Service<Request<BoxBody>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = ResponseFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<BoxBody>) -> Self::Future {
+        let path = req.uri().path().to_owned();
+
+        ResponseFuture {
+            future: self.inner.call(req),
+            start: Instant::now(),
+            path,
+            metrics: self.metrics.clone(),
+        }
+    }
+}
+
+#[pin_project]
+pub struct ResponseFuture<F> {
+    #[pin]
+    future: F,
+    start: Instant,
+    path: String,
+    metrics: Arc<Metrics>,
+}
+
+impl<F, E> Future for ResponseFuture<F>
+where
+    F: Future<Output = Result<Response<BoxBody>, E>>,
+{
+    type Output = Result<Response<BoxBody>, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        match this.future.poll(cx) {
+            Poll::Ready(result) => {
+                let code = match &result {
+                    Ok(response) => failure_code(response),
+                    // The connection failed before a status was sent
+                    Err(_) => Some("Unknown".to_string()),
+                };
+                this.metrics.record(
+                    this.path,
+                    code.as_deref(),
+                    this.start.elapsed().as_secs_f64(),
+                );
+                Poll::Ready(result)
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+async fn handle(State(metrics): State<Arc<Metrics>>) -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        [(
+            header::CONTENT_TYPE,
+            TextEncoder::new().format_type().to_string(),
+        )],
+        metrics.render(),
+    )
+}
+
+/// Serves `GET /metrics` on `addr` until the process exits.
+pub async fn serve(addr: SocketAddr, metrics: Arc<Metrics>) -> anyhow::Result<()> {
+    let app = Router::new()
+        .route("/metrics", get(handle))
+        .with_state(metrics);
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    info!("Metrics listener on {}", addr);
+    axum::serve(listener, app).await?;
+
+    Ok(())
+}
+
+/// Serves the metrics on `addr` in the background.
+pub fn spawn(addr: SocketAddr, metrics: Arc<Metrics>) {
+    tokio::spawn(async move {
+        if let Err(e) = serve(addr, metrics).await {
+            error!("Metrics listener failed: {}", e);
+        }
+    });
+}
@@ -0,0 +1,133 @@
+//! Order timeline: every change to an order is recorded in `order_events`,
+//! in the transaction making the change, so support can tell who changed an
+//! order and when. Any service that changes orders records through here.
+//! The order service also delivers events to webhooks from this table, and
+//! every event is published to the message broker through the outbox.
+
+use crate::error::AppError;
+use sqlx::PgExecutor;
+use tonic::Status;
+
+/// Aggregate type, and so topic, order events are published under.
+pub const AGGREGATE_TYPE: &str = "order";
+
+/// Actor of changes made by a service on its own, e.g. `system:payment`.
+pub fn system_actor(service: &str) -> String {
+    format!("system:{}", service)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventType {
+    Created,
+    StatusChanged,
+    Cancelled,
+    Refunded,
+    AddressChanged,
+    ItemsCancelled,
+    ItemsShipped,
+    Claimed,
+    DeliveryEstimateChanged,
+}
+
+impl EventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventType::Created => "CREATED",
+            EventType::StatusChanged => "STATUS_CHANGED",
+            EventType::Cancelled => "CANCELLED",
+            EventType::Refunded => "REFUNDED",
+            EventType::AddressChanged => "ADDRESS_CHANGED",
+            EventType::ItemsCancelled => "ITEMS_CANCELLED",
+            EventType::ItemsShipped => "ITEMS_SHIPPED",
+            EventType::Claimed => "CLAIMED",
+            EventType::DeliveryEstimateChanged => "DELIVERY_ESTIMATE_CHANGED",
+        }
+    }
+}
+
+/// A change to an order. Statuses are left empty for changes that keep it.
+#[derive(Debug, Clone, Copy)]
+pub struct OrderEvent<'a> {
+    pub order_id: &'a str,
+    pub event_type: EventType,
+    pub from_status: Option<&'a str>,
+    pub to_status: Option<&'a str>,
+    pub actor: &'a str,
+    /// Why, as given by whoever made the change.
+    pub reason: Option<&'a str>,
+    /// What changed beyond the status, e.g. the amount refunded.
+    pub details: Option<&'a str>,
+}
+
+impl<'a> OrderEvent<'a> {
+    pub fn new(order_id: &'a str, event_type: EventType, actor: &'a str) -> Self {
+        Self {
+            order_id,
+            event_type,
+            from_status: None,
+            to_status: None,
+            actor,
+            reason: None,
+            details: None,
+        }
+    }
+
+    pub fn status(mut self, from: Option<&'a str>, to: &'a str) -> Self {
+        self.from_status = from;
+        self.to_status = Some(to);
+        self
+    }
+
+    /// Sets the reason; empty reasons are left out.
+    pub fn reason(mut self, reason: &'a str) -> Self {
+        self.reason = (!reason.is_empty()).then_some(reason);
+        self
+    }
+
+    pub fn details(mut self, details: &'a str) -> Self {
+        self.details = Some(details);
+        self
+    }
+}
+
+/// Records an event, and queues it for publishing in the outbox; pass the
+/// transaction making the change.
+pub async fn record<'e>(
+    executor: impl PgExecutor<'e>,
+    event: &OrderEvent<'_>,
+) -> Result<(), Status> {
+    let payload = serde_json::json!({
+        "order_id": event.order_id,
+        "event_type": event.event_type.as_str(),
+        "from_status": event.from_status,
+        "to_status": event.to_status,
+        "actor": event.actor,
+        "reason": event.reason,
+        "details": event.details,
+    });
+
+    // One statement, as the executor may only be good for one
+    sqlx::query(
+        "WITH event AS (
+             INSERT INTO order_events (order_id, event_type, from_status, to_status, actor, reason, details)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             RETURNING order_id, event_type
+         )
+         INSERT INTO outbox (aggregate_type, aggregate_id, event_type, payload)
+         SELECT $8, order_id, event_type, $9 FROM event",
+    )
+    .bind(event.order_id)
+    .bind(event.event_type.as_str())
+    .bind(event.from_status)
+    .bind(event.to_status)
+    .bind(event.actor)
+    .bind(event.reason)
+    .bind(event.details)
+    .bind(AGGREGATE_TYPE)
+    .bind(payload.to_string().into_bytes())
+    .execute(executor)
+    .await
+    .map_err(AppError::from)?;
+
+    Ok(())
+}
@@ -0,0 +1,286 @@
+//! Transactional outbox. A service publishes a domain event by writing it
+//! to `outbox` in the transaction making the change, so the event exists
+//! if and only if the change does; `OutboxRelay` then publishes the events
+//! to the message broker. Delivery is at least once: an event whose publish
+//! succeeded but wasn't marked yet is published again, so consumers dedupe
+//! on the event id. Events of one aggregate, e.g. one order, are published
+//! in the order they were written. Typed events go in through
+//! `events::emit`.
+
+use crate::error::AppError;
+use crate::events::{self, EventPublisher};
+use anyhow::{Result, anyhow};
+use proto::events::Envelope;
+use sqlx::{PgExecutor, PgPool};
+use std::collections::HashSet;
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+use tonic::Status;
+use tracing::{debug, info, warn};
+
+/// Events published per sweep.
+const BATCH_SIZE: i64 = 100;
+
+/// How long published events are kept, for replaying them by hand.
+const RETENTION_DAYS: i32 = 7;
+
+/// Advisory lock held by the relay sweeping: a single relay at a time keeps
+/// each aggregate's events in order, whichever instances run one.
+const RELAY_LOCK: i64 = 0x6f75_7462_6f78;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A domain event, e.g. an order's `CREATED`.
+#[derive(Debug, Clone, Copy)]
+pub struct OutboxEvent<'a> {
+    /// Kind of the entity that changed, e.g. `order`; the topic it's
+    /// published to.
+    pub aggregate_type: &'a str,
+    /// Which one; events of one aggregate are published in order.
+    pub aggregate_id: &'a str,
+    pub event_type: &'a str,
+    pub payload: &'a [u8],
+}
+
+/// Writes `event` to the outbox; pass the transaction making the change.
+pub async fn enqueue<'e>(
+    executor: impl PgExecutor<'e>,
+    event: &OutboxEvent<'_>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO outbox (aggregate_type, aggregate_id, event_type, payload)
+         VALUES ($1, $2, $3, $4)",
+    )
+    .bind(event.aggregate_type)
+    .bind(event.aggregate_id)
+    .bind(event.event_type)
+    .bind(event.payload)
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// An event read back from the outbox for publishing.
+#[derive(Debug, sqlx::FromRow)]
+pub struct OutboxMessage {
+    /// Increases with every event; consumers dedupe on it.
+    pub id: i64,
+    pub aggregate_type: String,
+    pub aggregate_id: String,
+    pub event_type: String,
+    pub payload: Vec<u8>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// The message broker events are published to. A publish returning `Ok`
+/// must have been accepted by the broker.
+#[tonic::async_trait]
+pub trait Publisher: Send + Sync {
+    async fn publish(&self, message: &OutboxMessage) -> Result<()>;
+}
+
+/// Publishes to the log only, for development without a broker.
+pub struct LogPublisher;
+
+#[tonic::async_trait]
+impl Publisher for LogPublisher {
+    async fn publish(&self, message: &OutboxMessage) -> Result<()> {
+        info!(
+            id = message.id,
+            topic = %message.aggregate_type,
+            key = %message.aggregate_id,
+            event_type = %message.event_type,
+            "Published event"
+        );
+        Ok(())
+    }
+}
+
+/// Publishes through an HTTP bridge to the broker, e.g. a Kafka REST proxy
+/// or a NATS HTTP gateway: each event is POSTed to `<url>/<topic>` with the
+/// payload as body and its id, key and type in headers.
+pub struct HttpPublisher {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl HttpPublisher {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into().trim_end_matches('/').to_string(),
+            client: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .expect("Failed to build outbox HTTP client"),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl Publisher for HttpPublisher {
+    async fn publish(&self, message: &OutboxMessage) -> Result<()> {
+        let response = self
+            .client
+            .post(format!("{}/{}", self.url, message.aggregate_type))
+            .header("content-type", "application/octet-stream")
+            .header("x-event-id", message.id.to_string())
+            .header("x-event-key", &message.aggregate_id)
+            .header("x-event-type", &message.event_type)
+            .body(message.payload.clone())
+            .send()
+            .await?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(anyhow!("Broker answered {}", response.status()))
+        }
+    }
+}
+
+/// Publishes to the event bus, each event in an `Envelope` on the topic
+/// named by its aggregate type and keyed by its aggregate id.
+pub struct BusPublisher {
+    bus: Arc<dyn EventPublisher>,
+}
+
+impl BusPublisher {
+    pub fn new(bus: Arc<dyn EventPublisher>) -> Self {
+        Self { bus }
+    }
+}
+
+#[tonic::async_trait]
+impl Publisher for BusPublisher {
+    async fn publish(&self, message: &OutboxMessage) -> Result<()> {
+        let envelope = Envelope {
+            event_id: message.id.to_string(),
+            event_type: message.event_type.clone(),
+            key: message.aggregate_id.clone(),
+            occurred_at: message.created_at.timestamp(),
+            payload: message.payload.clone(),
+        };
+        self.bus.publish(&message.aggregate_type, &envelope).await
+    }
+}
+
+/// The event bus `EVENT_BUS` names, else the HTTP bridge
+/// `OUTBOX_PUBLISH_URL` names, logging only when neither is set.
+pub async fn publisher_from_env() -> Result<Arc<dyn Publisher>> {
+    if let Some(bus) = events::publisher_from_env().await? {
+        return Ok(Arc::new(BusPublisher::new(bus)));
+    }
+    Ok(match env::var("OUTBOX_PUBLISH_URL") {
+        Ok(url) if !url.is_empty() => Arc::new(HttpPublisher::new(url)),
+        _ => Arc::new(LogPublisher),
+    })
+}
+
+/// Publishes the events written to the outbox.
+pub struct OutboxRelay {
+    db: PgPool,
+    publisher: Arc<dyn Publisher>,
+}
+
+impl OutboxRelay {
+    pub fn new(db: PgPool, publisher: Arc<dyn Publisher>) -> Self {
+        Self { db, publisher }
+    }
+
+    /// Publishes a batch of pending events, oldest first; returns how many
+    /// were published. An event that fails holds back the later events of
+    /// its aggregate until a later sweep publishes it.
+    pub async fn run(&self) -> Result<usize, Status> {
+        let mut tx = self.db.begin().await.map_err(AppError::from)?;
+
+        let (locked,): (bool,) = sqlx::query_as("SELECT pg_try_advisory_xact_lock($1)")
+            .bind(RELAY_LOCK)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(AppError::from)?;
+        if !locked {
+            return Ok(0);
+        }
+
+        let messages = sqlx::query_as::<_, OutboxMessage>(
+            "SELECT id, aggregate_type, aggregate_id, event_type, payload, created_at
+             FROM outbox
+             WHERE published_at IS NULL
+             ORDER BY id
+             LIMIT $1",
+        )
+        .bind(BATCH_SIZE)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+
+        let mut held_back = HashSet::new();
+        let mut published = 0;
+        for message in &messages {
+            let aggregate = (&message.aggregate_type, &message.aggregate_id);
+            if held_back.contains(&aggregate) {
+                continue;
+            }
+
+            match self.publisher.publish(message).await {
+                Ok(()) => {
+                    sqlx::query("UPDATE outbox SET published_at = CURRENT_TIMESTAMP WHERE id = $1")
+                        .bind(message.id)
+                        .execute(&mut *tx)
+                        .await
+                        .map_err(AppError::from)?;
+                    published += 1;
+                }
+                Err(e) => {
+                    warn!(
+                        id = message.id,
+                        event_type = %message.event_type,
+                        "Failed to publish event: {}", e
+                    );
+                    sqlx::query(
+                        "UPDATE outbox SET attempts = attempts + 1, last_error = $2 WHERE id = $1",
+                    )
+                    .bind(message.id)
+                    .bind(e.to_string())
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(AppError::from)?;
+                    held_back.insert(aggregate);
+                }
+            }
+        }
+
+        let pruned = sqlx::query(
+            "DELETE FROM outbox
+             WHERE published_at < CURRENT_TIMESTAMP - make_interval(days => $1)",
+        )
+        .bind(RETENTION_DAYS)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+        if pruned.rows_affected() > 0 {
+            debug!(pruned = pruned.rows_affected(), "Pruned published events");
+        }
+
+        tx.commit().await.map_err(AppError::from)?;
+
+        Ok(published)
+    }
+
+    /// Spawns a background task that sweeps every `interval`.
+    pub fn spawn(self, interval: Duration) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match self.run().await {
+                    Ok(0) => {}
+                    Ok(published) => debug!("Published {} events", published),
+                    Err(e) => warn!("Outbox sweep failed: {}", e),
+                }
+            }
+        });
+    }
+}
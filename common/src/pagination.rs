@@ -0,0 +1,148 @@
+use sqlx::{PgPool, Postgres, QueryBuilder};
+
+/// Page size used when a request asks for none or for more than `MAX_PAGE_SIZE`.
+pub const DEFAULT_PAGE_SIZE: i32 = 10;
+pub const MAX_PAGE_SIZE: i32 = 100;
+
+/// Offset pagination with the defaults shared by every list RPC: pages start
+/// at 1 and page sizes outside 1..=MAX_PAGE_SIZE fall back to DEFAULT_PAGE_SIZE.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageRequest {
+    pub page: i32,
+    pub page_size: i32,
+}
+
+impl PageRequest {
+    pub fn new(page: i32, page_size: i32) -> Self {
+        Self {
+            page: page.max(1),
+            page_size: if page_size <= 0 || page_size > MAX_PAGE_SIZE {
+                DEFAULT_PAGE_SIZE
+            } else {
+                page_size
+            },
+        }
+    }
+
+    pub fn limit(&self) -> i64 {
+        self.page_size as i64
+    }
+
+    pub fn offset(&self) -> i64 {
+        (self.page as i64 - 1) * self.page_size as i64
+    }
+
+    /// Appends ` LIMIT .. OFFSET ..` for this page.
+    pub fn push_limit_offset(&self, query: &mut QueryBuilder<'_, Postgres>) {
+        query
+            .push(" LIMIT ")
+            .push_bind(self.limit())
+            .push(" OFFSET ")
+            .push_bind(self.offset());
+    }
+
+    /// Appends ` LIMIT ..` one row over the page size, for `next_page_token`,
+    /// and this page's ` OFFSET ..` unless the query resumes after a cursor.
+    pub fn push_lookahead_limit(&self, query: &mut QueryBuilder<'_, Postgres>, after_cursor: bool) {
+        query.push(" LIMIT ").push_bind(self.limit() + 1);
+        if !after_cursor {
+            query.push(" OFFSET ").push_bind(self.offset());
+        }
+    }
+}
+
+/// Runs a `SELECT COUNT(*) ...` query, typically built with the same filter
+/// function as the page query so the two can't disagree.
+pub async fn fetch_count(
+    mut query: QueryBuilder<'_, Postgres>,
+    db: &PgPool,
+) -> Result<i32, sqlx::Error> {
+    let count: i64 = query.build_query_scalar().fetch_one(db).await?;
+    Ok(i32::try_from(count).unwrap_or(i32::MAX))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+/// Keyset position after the last row of a page: the value of the sort
+/// column and the row id, which breaks ties between equal sort values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cursor {
+    pub key: String,
+    pub id: String,
+}
+
+impl Cursor {
+    pub fn new(key: impl Into<String>, id: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            id: id.into(),
+        }
+    }
+
+    /// Encodes the cursor as an opaque page token for clients.
+    pub fn encode(&self) -> String {
+        format!("{}\n{}", self.key, self.id)
+            .bytes()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    /// Decodes a page token; `None` when it wasn't produced by `encode`.
+    pub fn decode(token: &str) -> Option<Self> {
+        if !token.len().is_multiple_of(2) {
+            return None;
+        }
+        let bytes = (0..token.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(token.get(i..i + 2)?, 16).ok())
+            .collect::<Option<Vec<u8>>>()?;
+        let text = String::from_utf8(bytes).ok()?;
+        let (key, id) = text.split_once('\n')?;
+        Some(Self::new(key, id))
+    }
+
+    /// Appends ` AND (key_column, id_column) > (key, id)` (`<` for descending
+    /// order), selecting the rows after this cursor. The key is cast to
+    /// `key_type` so it compares as the column does; the query must already
+    /// have a WHERE clause and be ordered by the same two columns.
+    pub fn push_after(
+        &self,
+        query: &mut QueryBuilder<'_, Postgres>,
+        key_column: &str,
+        key_type: &str,
+        id_column: &str,
+        order: SortOrder,
+    ) {
+        let op = match order {
+            SortOrder::Asc => ">",
+            SortOrder::Desc => "<",
+        };
+        query
+            .push(format!(
+                " AND ({}, {}) {} (CAST(",
+                key_column, id_column, op
+            ))
+            .push_bind(self.key.clone())
+            .push(format!(" AS {}), ", key_type))
+            .push_bind(self.id.clone())
+            .push(")");
+    }
+}
+
+/// For cursor pages fetched with one extra row (`limit() + 1`): drops the
+/// extra row and returns the token of the next page, or `None` on the last page.
+pub fn next_page_token<T>(
+    rows: &mut Vec<T>,
+    page: &PageRequest,
+    cursor: impl Fn(&T) -> Cursor,
+) -> Option<String> {
+    if rows.len() <= page.page_size as usize {
+        return None;
+    }
+    rows.truncate(page.page_size as usize);
+    rows.last().map(|row| cursor(row).encode())
+}
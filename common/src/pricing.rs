@@ -0,0 +1,10 @@
+/// SQL expression of a product's price right now: the sale price while its
+/// sale window is open, the regular price otherwise. A sale never raises the
+/// price. Meant for queries over `products`, e.g. `SELECT {EFFECTIVE_PRICE} FROM products`.
+pub const EFFECTIVE_PRICE: &str = "(CASE
+    WHEN sale_price IS NOT NULL
+     AND (sale_starts_at IS NULL OR sale_starts_at <= NOW())
+     AND (sale_ends_at IS NULL OR sale_ends_at > NOW())
+    THEN LEAST(price, sale_price)
+    ELSE price
+END)";
@@ -0,0 +1,255 @@
+use anyhow::{Result, anyhow};
+use http::uri::PathAndQuery;
+use http::{Request, Response, StatusCode, Uri};
+use std::env;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+
+const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+/// Hashids-style salted shuffle; the same salt always yields the same order.
+fn consistent_shuffle(alphabet: &mut [u8], salt: &[u8]) {
+    if salt.is_empty() {
+        return;
+    }
+
+    let (mut v, mut p) = (0, 0);
+    for i in (1..alphabet.len()).rev() {
+        v %= salt.len();
+        let n = salt[v] as usize;
+        p += n;
+        alphabet.swap(i, (n + v + p) % i);
+        v += 1;
+    }
+}
+
+/// Maps internal ids to short, non-sequential public tokens for URLs and
+/// receipts. This hides ids and their ordering from customers; it is not
+/// encryption and must not be used for access control.
+#[derive(Debug, Clone)]
+pub struct PublicIdCodec {
+    salt: Vec<u8>,
+    alphabet: Vec<u8>,
+    min_length: usize,
+}
+
+impl PublicIdCodec {
+    pub fn new(salt: &str) -> Self {
+        let mut alphabet = ALPHABET.to_vec();
+        consistent_shuffle(&mut alphabet, salt.as_bytes());
+
+        Self {
+            salt: salt.as_bytes().to_vec(),
+            alphabet,
+            min_length: 0,
+        }
+    }
+
+    /// Pads tokens to at least `min_length` characters.
+    pub fn with_min_length(mut self, min_length: usize) -> Self {
+        self.min_length = min_length;
+        self
+    }
+
+    /// Reads `PUBLIC_ID_SALT` (required) and `PUBLIC_ID_MIN_LENGTH`.
+    pub fn from_env() -> Result<Self> {
+        let salt = env::var("PUBLIC_ID_SALT")
+            .ok()
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| anyhow!("PUBLIC_ID_SALT must be set"))?;
+
+        let min_length = match env::var("PUBLIC_ID_MIN_LENGTH") {
+            Ok(v) if !v.is_empty() => v.parse()?,
+            _ => 0,
+        };
+
+        Ok(Self::new(&salt).with_min_length(min_length))
+    }
+
+    /// The per-token alphabet, re-shuffled by the lottery character so that
+    /// consecutive ids don't produce similar tokens.
+    fn token_alphabet(&self, lottery: u8) -> Vec<u8> {
+        let mut salt = Vec::with_capacity(self.salt.len() + 1);
+        salt.push(lottery);
+        salt.extend_from_slice(&self.salt);
+
+        let mut alphabet = self.alphabet.clone();
+        consistent_shuffle(&mut alphabet, &salt);
+        alphabet
+    }
+
+    pub fn encode(&self, id: u128) -> String {
+        let base = self.alphabet.len() as u128;
+        let lottery = self.alphabet[(id % base) as usize];
+        let alphabet = self.token_alphabet(lottery);
+
+        let mut digits = Vec::new();
+        let mut rest = id;
+        loop {
+            digits.push(alphabet[(rest % base) as usize]);
+            rest /= base;
+            if rest == 0 {
+                break;
+            }
+        }
+        // Leading zero digits keep the value and pad to the minimum length
+        while digits.len() + 1 < self.min_length {
+            digits.push(alphabet[0]);
+        }
+        digits.push(lottery);
+        digits.reverse();
+
+        String::from_utf8(digits).unwrap_or_default()
+    }
+
+    pub fn decode(&self, token: &str) -> Option<u128> {
+        let bytes = token.as_bytes();
+        let (&lottery, digits) = bytes.split_first()?;
+        if digits.is_empty() || !self.alphabet.contains(&lottery) {
+            return None;
+        }
+
+        let alphabet = self.token_alphabet(lottery);
+        let base = alphabet.len() as u128;
+        let mut id: u128 = 0;
+        for digit in digits {
+            let value = alphabet.iter().position(|c| c == digit)? as u128;
+            id = id.checked_mul(base)?.checked_add(value)?;
+        }
+
+        // Only the canonical encoding is accepted, so each id has one token
+        (self.encode(id) == token).then_some(id)
+    }
+
+    /// Encodes a UUID string such as an order or product id.
+    pub fn encode_uuid(&self, uuid: &str) -> Option<String> {
+        let hex: String = uuid.chars().filter(|c| *c != '-').collect();
+        if hex.len() != 32 {
+            return None;
+        }
+        u128::from_str_radix(&hex, 16)
+            .ok()
+            .map(|id| self.encode(id))
+    }
+
+    /// Decodes a token back into a hyphenated UUID string.
+    pub fn decode_uuid(&self, token: &str) -> Option<String> {
+        let hex = format!("{:032x}", self.decode(token)?);
+        Some(format!(
+            "{}-{}-{}-{}-{}",
+            &hex[0..8],
+            &hex[8..12],
+            &hex[12..16],
+            &hex[16..20],
+            &hex[20..32]
+        ))
+    }
+}
+
+/// HTTP middleware for customer-facing gateways that decodes public tokens in
+/// the request path back into internal ids before routing. Path segments
+/// starting with one of the configured prefixes (e.g. `o_` for orders) are
+/// decoded; a segment that fails to decode is answered with 404.
+#[derive(Clone)]
+pub struct PublicIdLayer {
+    codec: Arc<PublicIdCodec>,
+    prefixes: Arc<Vec<String>>,
+}
+
+impl PublicIdLayer {
+    pub fn new(codec: Arc<PublicIdCodec>) -> Self {
+        Self {
+            codec,
+            prefixes: Arc::new(Vec::new()),
+        }
+    }
+
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        Arc::make_mut(&mut self.prefixes).push(prefix.into());
+        self
+    }
+}
+
+impl<S> Layer<S> for PublicIdLayer {
+    type Service = PublicIdService<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        PublicIdService {
+            inner: service,
+            codec: self.codec.clone(),
+            prefixes: self.prefixes.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct PublicIdService<S> {
+    inner: S,
+    codec: Arc<PublicIdCodec>,
+    prefixes: Arc<Vec<String>>,
+}
+
+impl<S> PublicIdService<S> {
+    /// Returns the rewritten path, or `None` when a prefixed segment is not a
+    /// valid token.
+    fn decode_path(&self, path: &str) -> Option<String> {
+        let segments: Option<Vec<String>> = path
+            .split('/')
+            .map(|segment| {
+                match self
+                    .prefixes
+                    .iter()
+                    .find_map(|prefix| segment.strip_prefix(prefix.as_str()))
+                {
+                    Some(token) => self.codec.decode_uuid(token),
+                    None => Some(segment.to_string()),
+                }
+            })
+            .collect();
+
+        segments.map(|s| s.join("/"))
+    }
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for PublicIdService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>> + Send + 'static,
+    S::Future: Send + 'static,
+    ResBody: Default + Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<ReqBody>) -> Self::Future {
+        let path = match self.decode_path(req.uri().path()) {
+            Some(path) => path,
+            None => {
+                let mut response = Response::new(ResBody::default());
+                *response.status_mut() = StatusCode::NOT_FOUND;
+                return Box::pin(async move { Ok(response) });
+            }
+        };
+
+        if path != req.uri().path() {
+            let path_and_query = match req.uri().query() {
+                Some(query) => format!("{}?{}", path, query),
+                None => path,
+            };
+            let mut parts = req.uri().clone().into_parts();
+            parts.path_and_query = PathAndQuery::try_from(path_and_query).ok();
+            if let Ok(uri) = Uri::from_parts(parts) {
+                *req.uri_mut() = uri;
+            }
+        }
+
+        Box::pin(self.inner.call(req))
+    }
+}
@@ -1,13 +1,53 @@
+use crate::auth;
+use crate::error;
+use crate::forwarded;
+use crate::health;
+use crate::metrics::Metrics;
+use crate::resilience::RETRY_AFTER;
 use dashmap::DashMap;
+use prometheus::IntGauge;
+use std::collections::HashMap;
+use std::env;
 use std::future:: Future;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 use std::time::{Duration, Instant};
 use tonic::body::BoxBody;
+use tonic::metadata::MetadataValue;
+use tonic::{Code, Status};
 use tower::{Layer, Service};
-use http::{Request, Response, StatusCode};
-use tracing::warn;
+use http::{Request, Response};
+use tracing::{debug, warn};
+
+/// Requests a minute per caller unless configured otherwise.
+const DEFAULT_PER_MINUTE: u32 = 1000;
+
+/// How a client's budget is spent and replenished.
+#[derive(Debug, Clone, Copy)]
+pub enum Algorithm {
+    /// Up to `max_requests` in each window. A client can spend one window's
+    /// budget at its end and the next one's right after, twice the limit.
+    FixedWindow { max_requests: u32, window: Duration },
+    /// A bucket of up to `burst` tokens, each request taking one, refilled
+    /// with a token every `refill_interval`: bursts never exceed `burst` and
+    /// the sustained rate never exceeds one request per interval.
+    TokenBucket {
+        burst: u32,
+        refill_interval: Duration,
+    },
+}
+
+impl Algorithm {
+    /// Bursts of up to `burst` requests, and `rate` requests `per` interval
+    /// sustained.
+    pub fn token_bucket(burst: u32, rate: u32, per: Duration) -> Self {
+        Algorithm::TokenBucket {
+            burst,
+            refill_interval: per / rate.max(1),
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct RateLimitLayer {
@@ -15,26 +55,273 @@ pub struct RateLimitLayer {
 }
 
 impl RateLimitLayer {
+    /// Up to `max_requests` per fixed `window`.
     pub fn new(max_requests: u32, window:  Duration) -> Self {
+        Self::with_algorithm(Algorithm::FixedWindow {
+            max_requests,
+            window,
+        })
+    }
+
+    /// See `Algorithm::token_bucket`.
+    pub fn token_bucket(burst: u32, rate: u32, per: Duration) -> Self {
+        Self::with_algorithm(Algorithm::token_bucket(burst, rate, per))
+    }
+
+    /// A token bucket of `RATE_LIMIT_PER_MINUTE` requests a minute per
+    /// caller, in bursts of up to `RATE_LIMIT_BURST`, by default as many.
+    pub fn from_env() -> Self {
+        let per_minute = env::var("RATE_LIMIT_PER_MINUTE")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_PER_MINUTE);
+        let burst = env::var("RATE_LIMIT_BURST")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(per_minute);
+        Self::token_bucket(burst, per_minute, Duration::from_secs(60))
+    }
+
+    /// Every method limited by `algorithm`, in one budget per caller.
+    pub fn with_algorithm(algorithm: Algorithm) -> Self {
         Self {
             config: Arc::new(RateLimitConfig {
-                max_requests,
-                window,
+                default: algorithm,
+                rules: HashMap::new(),
                 clients: DashMap::new(),
+                tracked_clients: None,
             }),
         }
     }
+
+    /// Limits `method`, e.g. `/user.UserService/Login`, by `algorithm`
+    /// instead, in a budget of its own per caller.
+    pub fn with_rule(mut self, method: &str, algorithm: Algorithm) -> Self {
+        Arc::get_mut(&mut self.config)
+            .expect("Rules are added before the layer is used")
+            .rules
+            .insert(method.to_string(), algorithm);
+        self
+    }
+
+    /// Reports the budgets being tracked as the `ratelimit_tracked_clients`
+    /// gauge, updated by the eviction sweep.
+    pub fn with_metrics(mut self, metrics: &Metrics) -> Self {
+        let gauge = IntGauge::new(
+            "ratelimit_tracked_clients",
+            "Caller budgets the rate limiter holds",
+        )
+        .expect("Valid metric");
+        metrics
+            .registry()
+            .register(Box::new(gauge.clone()))
+            .expect("Metric registered once");
+        Arc::get_mut(&mut self.config)
+            .expect("Metrics are added before the layer is used")
+            .tracked_clients = Some(gauge);
+        self
+    }
+
+    /// Drops, every `interval`, the budgets that have refilled completely:
+    /// a caller coming back starts from a full budget either way, and
+    /// callers that never come back would otherwise be kept forever.
+    pub fn spawn_eviction(&self, interval: Duration) {
+        let config = self.config.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let evicted = config.evict(Instant::now());
+                if evicted > 0 {
+                    debug!(evicted, "Evicted idle rate limit budgets");
+                }
+            }
+        });
+    }
 }
 
 struct RateLimitConfig {
-    max_requests: u32,
-    window: Duration,
+    default: Algorithm,
+    /// Algorithms of the methods with rules of their own, by path.
+    rules: HashMap<String, Algorithm>,
+    /// Budgets by method, or `*` for methods without rules, and caller.
     clients: DashMap<String, ClientState>,
+    tracked_clients: Option<IntGauge>,
+}
+
+enum ClientState {
+    Window { count: u32, window_start: Instant },
+    Bucket { tokens: f64, updated: Instant },
+}
+
+impl ClientState {
+    /// Whether the budget is back to what a new caller starts with.
+    fn is_full(&self, algorithm: Algorithm, now: Instant) -> bool {
+        match (self, algorithm) {
+            (ClientState::Window { window_start, .. }, Algorithm::FixedWindow { window, .. }) => {
+                now.duration_since(*window_start) > window
+            }
+            (
+                ClientState::Bucket { tokens, updated },
+                Algorithm::TokenBucket {
+                    burst,
+                    refill_interval,
+                },
+            ) => {
+                let refilled =
+                    now.duration_since(*updated).as_secs_f64() / refill_interval.as_secs_f64();
+                tokens + refilled >= burst as f64
+            }
+            _ => false,
+        }
+    }
+}
+
+/// A caller's budget after a request was checked against it.
+struct Decision {
+    allowed: bool,
+    /// Requests the budget holds when full.
+    limit: u32,
+    remaining: u32,
+    /// Wait until the next request would be allowed, when this one isn't.
+    retry_after: Duration,
+}
+
+impl Decision {
+    /// The rejection of a request that was not allowed.
+    fn rejection(&self) -> Status {
+        // Rounded up, so a caller waiting this long has the budget back
+        let secs =
+            (self.retry_after.as_secs() + u64::from(self.retry_after.subsec_nanos() > 0)).max(1);
+        let mut status = error::with_reason(
+            Code::ResourceExhausted,
+            "RATE_LIMITED",
+            format!("Rate limit exceeded, retry after {}s", secs),
+        );
+        let metadata = status.metadata_mut();
+        metadata.insert(RETRY_AFTER, MetadataValue::from(secs));
+        metadata.insert("x-ratelimit-limit", MetadataValue::from(self.limit));
+        metadata.insert("x-ratelimit-remaining", MetadataValue::from(self.remaining));
+        status
+    }
+}
+
+impl RateLimitConfig {
+    /// Algorithm of the budget stored under `key`.
+    fn algorithm_of(&self, key: &str) -> Algorithm {
+        let scope = key.split_once(' ').map_or(key, |(scope, _)| scope);
+        self.rules.get(scope).copied().unwrap_or(self.default)
+    }
+
+    /// Drops the budgets that are full again; returns how many.
+    fn evict(&self, now: Instant) -> usize {
+        let before = self.clients.len();
+        self.clients
+            .retain(|key, state| !state.is_full(self.algorithm_of(key), now));
+        let after = self.clients.len();
+        if let Some(gauge) = &self.tracked_clients {
+            gauge.set(after as i64);
+        }
+        before.saturating_sub(after)
+    }
+
+    /// Takes one `path` request from the caller's budget, if it isn't spent.
+    fn acquire(&self, path: &str, caller: &str, now: Instant) -> Decision {
+        let (scope, algorithm) = match self.rules.get(path) {
+            Some(algorithm) => (path, *algorithm),
+            None => ("*", self.default),
+        };
+        let mut state = self
+            .clients
+            .entry(format!("{} {}", scope, caller))
+            .or_insert_with(|| match algorithm {
+                Algorithm::FixedWindow { .. } => ClientState::Window {
+                    count: 0,
+                    window_start: now,
+                },
+                Algorithm::TokenBucket { burst, .. } => ClientState::Bucket {
+                    tokens: burst as f64,
+                    updated: now,
+                },
+            });
+
+        match (&mut *state, algorithm) {
+            (
+                ClientState::Window {
+                    count,
+                    window_start,
+                },
+                Algorithm::FixedWindow {
+                    max_requests,
+                    window,
+                },
+            ) => {
+                if now.duration_since(*window_start) > window {
+                    // Reset window
+                    *count = 0;
+                    *window_start = now;
+                }
+                let allowed = *count < max_requests;
+                if allowed {
+                    *count += 1;
+                }
+                Decision {
+                    allowed,
+                    limit: max_requests,
+                    remaining: max_requests - *count,
+                    retry_after: window.saturating_sub(now.duration_since(*window_start)),
+                }
+            }
+            (
+                ClientState::Bucket { tokens, updated },
+                Algorithm::TokenBucket {
+                    burst,
+                    refill_interval,
+                },
+            ) => {
+                let refilled =
+                    now.duration_since(*updated).as_secs_f64() / refill_interval.as_secs_f64();
+                *tokens = (*tokens + refilled).min(burst as f64);
+                *updated = now;
+                let allowed = *tokens >= 1.0;
+                if allowed {
+                    *tokens -= 1.0;
+                }
+                Decision {
+                    allowed,
+                    limit: burst,
+                    remaining: *tokens as u32,
+                    retry_after: refill_interval.mul_f64((1.0 - *tokens).max(0.0)),
+                }
+            }
+            // A budget's algorithm is fixed, so its state always matches it
+            _ => unreachable!("Client state of another algorithm"),
+        }
+    }
 }
 
-struct ClientState {
-    count: u32,
-    window_start: Instant,
+/// Who a request's budget belongs to: the user of a valid bearer token, or
+/// else the client address, see `forwarded::client_address`. An invalid
+/// token counts as none, so made-up tokens don't each get a budget of their
+/// own. `None` for the platform's own services, which aren't limited: one
+/// service calls on behalf of all its callers, who were limited where their
+/// requests came in.
+fn caller<B>(req: &Request<B>) -> Option<String> {
+    let user = req
+        .headers()
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .and_then(|token| auth::decode_token(token).ok());
+    if let Some(claims) = user {
+        if auth::is_service(&claims) {
+            return None;
+        }
+        return Some(format!("user:{}", claims.sub));
+    }
+
+    let ip = forwarded::client_address(req).unwrap_or_else(|| "unknown".to_string());
+    Some(format!("ip:{}", ip))
 }
 
 impl<S> Layer<S> for RateLimitLayer {
@@ -68,55 +355,145 @@ where
     }
 
     fn call(&mut self, req: Request<BoxBody>) -> Self::Future {
-        // Extract client identifier (IP address)
-        let client_id = req
-            .headers()
-            .get("x-forwarded-for")
-            .and_then(|v| v.to_str().ok())
-            .unwrap_or("unknown")
-            .to_string();
+        // Probes run every few seconds and must never be turned away
+        if req.uri().path().starts_with(health::PATH_PREFIX) {
+            return Box::pin(self.inner.call(req));
+        }
 
-        let now = Instant::now();
-        let mut allowed = false;
+        let Some(client_id) = caller(&req) else {
+            return Box::pin(self.inner.call(req));
+        };
 
-        // Check rate limit
-        self.config.clients
-            .entry(client_id. clone())
-            .and_modify(|state| {
-                if now.duration_since(state. window_start) > self.config.window {
-                    // Reset window
-                    state.count = 1;
-                    state.window_start = now;
-                    allowed = true;
-                } else if state.count < self.config.max_requests {
-                    state.count += 1;
-                    allowed = true;
-                }
-            })
-            .or_insert_with(|| {
-                allowed = true;
-                ClientState {
-                    count: 1,
-                    window_start: now,
-                }
-            });
+        let decision = self
+            .config
+            .acquire(req.uri().path(), &client_id, Instant::now());
 
         let mut inner = self.inner.clone();
 
         Box::pin(async move {
-            if !allowed {
+            if !decision.allowed {
                 warn!("Rate limit exceeded for client: {}", client_id);
-                
-                // Return 429 Too Many Requests
-                let response = Response::builder()
-                    .status(StatusCode::TOO_MANY_REQUESTS)
-                    .body(BoxBody::default())
-                    .unwrap();
-                
-                return Ok(response);
+
+                // A gRPC status clients can read, not a bare HTTP 429
+                return Ok(decision.rejection().into_http());
             }
 
             inner.call(req).await
         })
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::Role;
+
+    const LOGIN: &str = "/user.UserService/Login";
+    const GET_PRODUCT: &str = "/product.ProductService/GetProduct";
+
+    fn bearer(subject: &str, role: Role) -> Request<()> {
+        let token = auth::issue_token(subject, role, None).unwrap();
+        Request::builder()
+            .header("authorization", format!("Bearer {}", token))
+            .body(())
+            .unwrap()
+    }
+
+    #[test]
+    fn a_token_bucket_allows_bursts_and_refills_at_its_rate() {
+        let layer = RateLimitLayer::token_bucket(2, 60, Duration::from_secs(60));
+        let config = &layer.config;
+        let start = Instant::now();
+
+        assert!(config.acquire(GET_PRODUCT, "ann", start).allowed);
+        assert!(config.acquire(GET_PRODUCT, "ann", start).allowed);
+        let refused = config.acquire(GET_PRODUCT, "ann", start);
+        assert!(!refused.allowed);
+        assert_eq!(refused.retry_after, Duration::from_secs(1));
+
+        // A token a second, and never more than the burst
+        let second = start + Duration::from_secs(1);
+        assert!(config.acquire(GET_PRODUCT, "ann", second).allowed);
+        assert!(!config.acquire(GET_PRODUCT, "ann", second).allowed);
+        let later = start + Duration::from_secs(60);
+        assert_eq!(config.acquire(GET_PRODUCT, "ann", later).remaining, 1);
+    }
+
+    #[test]
+    fn callers_have_budgets_of_their_own() {
+        let layer = RateLimitLayer::token_bucket(1, 1, Duration::from_secs(60));
+        let config = &layer.config;
+        let now = Instant::now();
+
+        assert!(config.acquire(GET_PRODUCT, "ann", now).allowed);
+        assert!(!config.acquire(GET_PRODUCT, "ann", now).allowed);
+        assert!(config.acquire(GET_PRODUCT, "bob", now).allowed);
+    }
+
+    #[test]
+    fn methods_with_rules_have_budgets_of_their_own() {
+        let login = Algorithm::token_bucket(1, 1, Duration::from_secs(60));
+        let layer =
+            RateLimitLayer::token_bucket(10, 10, Duration::from_secs(60)).with_rule(LOGIN, login);
+        let config = &layer.config;
+        let now = Instant::now();
+
+        assert!(config.acquire(LOGIN, "ann", now).allowed);
+        let refused = config.acquire(LOGIN, "ann", now);
+        assert!(!refused.allowed);
+        assert_eq!(refused.limit, 1);
+
+        // Other methods draw on the default budget, untouched by logins
+        let allowed = config.acquire(GET_PRODUCT, "ann", now);
+        assert!(allowed.allowed);
+        assert_eq!(allowed.remaining, 9);
+    }
+
+    #[test]
+    fn a_fixed_window_resets_once_it_passes() {
+        let layer = RateLimitLayer::new(1, Duration::from_secs(60));
+        let config = &layer.config;
+        let start = Instant::now();
+
+        assert!(config.acquire(GET_PRODUCT, "ann", start).allowed);
+        assert!(!config.acquire(GET_PRODUCT, "ann", start).allowed);
+        let next_window = start + Duration::from_secs(61);
+        assert!(config.acquire(GET_PRODUCT, "ann", next_window).allowed);
+    }
+
+    #[test]
+    fn services_are_not_limited() {
+        assert_eq!(caller(&bearer("service:order", Role::Admin)), None);
+        assert_eq!(
+            caller(&bearer("ann", Role::Customer)),
+            Some("user:ann".to_string())
+        );
+    }
+
+    #[test]
+    fn callers_without_a_valid_token_are_limited_by_address() {
+        let req = Request::builder()
+            .header("authorization", "Bearer made-up")
+            .header(forwarded::HEADER, "203.0.113.7")
+            .body(())
+            .unwrap();
+        assert_eq!(caller(&req), Some("ip:203.0.113.7".to_string()));
+    }
+
+    #[test]
+    fn evicts_the_budgets_that_have_refilled() {
+        let login = Algorithm::token_bucket(1, 1, Duration::from_secs(60));
+        let layer =
+            RateLimitLayer::token_bucket(2, 60, Duration::from_secs(60)).with_rule(LOGIN, login);
+        let config = &layer.config;
+        let start = Instant::now();
+        config.acquire(GET_PRODUCT, "ann", start);
+        config.acquire(LOGIN, "ann", start);
+
+        assert_eq!(config.evict(start), 0);
+        // The default budget is back after a second, the login one after a minute
+        assert_eq!(config.evict(start + Duration::from_secs(2)), 1);
+        assert_eq!(config.clients.len(), 1);
+        assert_eq!(config.evict(start + Duration::from_secs(61)), 1);
+        assert!(config.clients.is_empty());
+    }
+}
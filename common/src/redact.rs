@@ -0,0 +1,99 @@
+//! Keeps credentials out of the logs. Every line a service logs goes
+//! through `Redacting`, which masks the values of password, secret and token
+//! fields, bearer tokens and JWTs, however they ended up in the line: as a
+//! structured field, in a message or in a quoted error from a dependency.
+
+use regex::{Captures, Regex};
+use std::borrow::Cow;
+use std::io;
+use std::sync::LazyLock;
+use tracing_subscriber::fmt::MakeWriter;
+
+const REDACTED: &str = "[REDACTED]";
+
+/// `key=value`, `key: value` and `"key":"value"`, a JSON string escaped
+/// inside another one too, where the key names a credential.
+static SENSITIVE_FIELD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?i)((?:\\?")?\b[\w-]*(?:password|passwd|secret|token|api[_-]?key|authorization)(?:\\?")?\s*[:=]\s*)(\\"(?:[^"\\]|\\\\\\"|\\\\)*?\\"|"(?:[^"\\]|\\.)*"|[^\s"\\,;&}\]]+)"#,
+    )
+    .expect("Valid regex")
+});
+
+static BEARER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\bbearer\s+[\w\-.~+/]+=*").expect("Valid regex"));
+
+static JWT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\beyJ[\w-]+\.[\w-]+\.[\w-]*").expect("Valid regex"));
+
+/// `text` with credentials masked; borrowed when there were none.
+pub fn redact(text: &str) -> Cow<'_, str> {
+    let mut text = Cow::Borrowed(text);
+    // Tokens first, so `authorization: Bearer …` loses the token, not just
+    // the word Bearer
+    if BEARER.is_match(&text) {
+        text = Cow::Owned(BEARER.replace_all(&text, "Bearer [REDACTED]").into_owned());
+    }
+    if JWT.is_match(&text) {
+        text = Cow::Owned(JWT.replace_all(&text, REDACTED).into_owned());
+    }
+    if SENSITIVE_FIELD.is_match(&text) {
+        text = Cow::Owned(SENSITIVE_FIELD.replace_all(&text, mask_field).into_owned());
+    }
+    text
+}
+
+fn mask_field(caps: &Captures) -> String {
+    let value = &caps[2];
+    // Quoted values stay quoted, so JSON lines stay JSON
+    let quote = if value.starts_with("\\\"") {
+        "\\\""
+    } else if value.starts_with('"') {
+        "\""
+    } else {
+        ""
+    };
+    format!("{}{quote}{REDACTED}{quote}", &caps[1])
+}
+
+/// Makes writers that redact what's written to those `inner` makes. Log
+/// lines are written whole, so each write is redacted as a line.
+pub struct Redacting<M> {
+    inner: M,
+}
+
+impl<M> Redacting<M> {
+    pub fn new(inner: M) -> Self {
+        Self { inner }
+    }
+}
+
+impl<'a, M: MakeWriter<'a>> MakeWriter<'a> for Redacting<M> {
+    type Writer = RedactingWriter<M::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactingWriter {
+            inner: self.inner.make_writer(),
+        }
+    }
+}
+
+pub struct RedactingWriter<W> {
+    inner: W,
+}
+
+impl<W: io::Write> io::Write for RedactingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match std::str::from_utf8(buf) {
+            Ok(line) => {
+                self.inner.write_all(redact(line).as_bytes())?;
+                Ok(buf.len())
+            }
+            Err(_) => self.inner.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
@@ -0,0 +1,115 @@
+//! Request IDs. `RequestIdLayer` keeps the `x-request-id` a caller sent, or
+//! makes one up, logs everything the RPC does under it and echoes it in the
+//! response; `telemetry::PropagateContext` forwards it on calls to other
+//! services, so the logs of one request can be found in all of them.
+
+use http::header::HeaderValue;
+use http::{Request, Response};
+use pin_project::pin_project;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::task::futures::TaskLocalFuture;
+use tonic::body::BoxBody;
+use tower::{Layer, Service};
+use tracing::Instrument;
+use tracing::instrument::Instrumented;
+
+pub const HEADER: &str = "x-request-id";
+
+/// Longest request ID accepted from a caller; longer ones are replaced.
+const MAX_LEN: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// ID of the request being handled, if called while handling one.
+pub fn current() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+/// The caller's ID when it's usable, printable ASCII of a sane length;
+/// a new one otherwise.
+fn from_request<B>(req: &Request<B>) -> String {
+    req.headers()
+        .get(HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|id| !id.is_empty() && id.len() <= MAX_LEN)
+        .map(str::to_string)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
+}
+
+#[derive(Clone, Default)]
+pub struct RequestIdLayer;
+
+impl<S> Layer<S> for RequestIdLayer {
+    type Service = RequestIdMiddleware<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        RequestIdMiddleware { inner: service }
+    }
+}
+
+#[derive(Clone)]
+pub struct RequestIdMiddleware<S> {
+    inner: S,
+}
+
+impl<S> Service<Request<BoxBody>> for RequestIdMiddleware<S>
+where
+    S: Service<Request<BoxBody>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = ResponseFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<BoxBody>) -> Self::Future {
+        let id = from_request(&req);
+        // Handlers reading the metadata see the ID, a generated one too
+        let header = HeaderValue::from_str(&id).expect("Request IDs are printable ASCII");
+        req.headers_mut().insert(HEADER, header.clone());
+
+        let span = tracing::info_span!("request", request_id = %id);
+        let future = {
+            let _entered = span.enter();
+            self.inner.call(req)
+        };
+
+        ResponseFuture {
+            future: REQUEST_ID.scope(id, future.instrument(span)),
+            header,
+        }
+    }
+}
+
+#[pin_project]
+pub struct ResponseFuture<F> {
+    #[pin]
+    future: TaskLocalFuture<String, Instrumented<F>>,
+    header: HeaderValue,
+}
+
+impl<F, E> Future for ResponseFuture<F>
+where
+    F: Future<Output = Result<Response<BoxBody>, E>>,
+{
+    type Output = Result<Response<BoxBody>, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        match this.future.poll(cx) {
+            Poll::Ready(Ok(mut response)) => {
+                response.headers_mut().insert(HEADER, this.header.clone());
+                Poll::Ready(Ok(response))
+            }
+            other => other,
+        }
+    }
+}
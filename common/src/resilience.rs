@@ -0,0 +1,543 @@
+//! Protection against failing downstream services. `CircuitBreakerLayer`
+//! wraps a client's channel: after repeated failures it stops calling the
+//! service for a while and fails calls at once with UNAVAILABLE, so a
+//! service that is down doesn't tie up the caller's requests waiting on it.
+//! `retry` repeats a call that failed transiently, with exponential backoff,
+//! for the calls that are safe to repeat.
+
+use crate::error::{self, AppError};
+use http::{Request, Response};
+use pin_project::pin_project;
+use rand::Rng;
+use std::env;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tonic::body::BoxBody;
+use tonic::metadata::MetadataValue;
+use tonic::{Code, Status};
+use tower::{Layer, Service};
+use tracing::{info, warn};
+
+/// Reason of the status a call fails with while the circuit is open.
+pub const CIRCUIT_OPEN: &str = "CIRCUIT_OPEN";
+
+/// Metadata holding the seconds after which a call may be retried.
+pub const RETRY_AFTER: &str = "retry-after";
+
+const DEFAULT_FAILURE_THRESHOLD: u32 = 5;
+const DEFAULT_OPEN_SECS: u64 = 30;
+
+#[derive(Debug, Clone, Copy)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive failures that open the circuit.
+    pub failure_threshold: u32,
+    /// How long the circuit stays open before a trial call is let through.
+    pub open_duration: Duration,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+            open_duration: Duration::from_secs(DEFAULT_OPEN_SECS),
+        }
+    }
+}
+
+impl CircuitBreakerConfig {
+    /// Reads `CIRCUIT_BREAKER_FAILURES` and `CIRCUIT_BREAKER_OPEN_SECS`.
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        Self {
+            failure_threshold: env::var("CIRCUIT_BREAKER_FAILURES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(default.failure_threshold),
+            open_duration: env::var("CIRCUIT_BREAKER_OPEN_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_secs)
+                .unwrap_or(default.open_duration),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum State {
+    Closed {
+        failures: u32,
+    },
+    Open {
+        until: Instant,
+    },
+    /// A trial call is in flight; its outcome closes or reopens the circuit.
+    HalfOpen,
+}
+
+#[derive(Debug)]
+struct Breaker {
+    service: String,
+    config: CircuitBreakerConfig,
+    state: Mutex<State>,
+}
+
+/// Retry-after hint while a trial call decides whether the circuit closes.
+const TRIAL_RETRY_AFTER: Duration = Duration::from_secs(1);
+
+impl Breaker {
+    /// Whether a call may go through, and if so whether it's the trial of
+    /// a half-open circuit; `Err` holds how long until one may.
+    fn admit(&self) -> Result<bool, Duration> {
+        let mut state = self.state.lock().unwrap();
+        match *state {
+            State::Closed { .. } => Ok(false),
+            State::Open { until } => {
+                let now = Instant::now();
+                if now < until {
+                    Err(until - now)
+                } else {
+                    *state = State::HalfOpen;
+                    Ok(true)
+                }
+            }
+            State::HalfOpen => Err(TRIAL_RETRY_AFTER),
+        }
+    }
+
+    fn record(&self, failed: bool) {
+        let mut state = self.state.lock().unwrap();
+        match (*state, failed) {
+            (State::Closed { .. }, false) => *state = State::Closed { failures: 0 },
+            (State::Closed { failures }, true) if failures + 1 < self.config.failure_threshold => {
+                *state = State::Closed {
+                    failures: failures + 1,
+                }
+            }
+            (State::Closed { .. } | State::HalfOpen, true) => {
+                warn!(
+                    service = %self.service,
+                    "Circuit opened for {}s",
+                    self.config.open_duration.as_secs()
+                );
+                *state = State::Open {
+                    until: Instant::now() + self.config.open_duration,
+                };
+            }
+            (State::HalfOpen, false) => {
+                info!(service = %self.service, "Circuit closed");
+                *state = State::Closed { failures: 0 };
+            }
+            // Calls admitted before the circuit opened
+            (State::Open { .. }, _) => {}
+        }
+    }
+
+    fn open_status(&self, retry_after: Duration) -> Status {
+        // Rounded up, so a caller waiting this long finds the circuit half-open
+        let secs = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+        let mut status = error::with_reason(
+            Code::Unavailable,
+            CIRCUIT_OPEN,
+            format!(
+                "{} service is unavailable, retry after {}s",
+                self.service, secs
+            ),
+        );
+        status
+            .metadata_mut()
+            .insert(RETRY_AFTER, MetadataValue::from(secs));
+        status
+    }
+}
+
+/// Whether a call failed in a way that says the service is unhealthy, as
+/// opposed to refusing the request.
+fn is_failure(code: Code) -> bool {
+    matches!(
+        code,
+        Code::Unavailable | Code::DeadlineExceeded | Code::Internal | Code::Unknown
+    )
+}
+
+fn response_code<B>(response: &Response<B>) -> Code {
+    response
+        .headers()
+        .get("grpc-status")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<i32>().ok())
+        .map(Code::from)
+        .unwrap_or(Code::Ok)
+}
+
+/// Whether a call was failed by an open circuit rather than by the service.
+pub fn is_circuit_open(status: &Status) -> bool {
+    status.code() == Code::Unavailable
+        && error::error_info(status).is_some_and(|info| info.reason == CIRCUIT_OPEN)
+}
+
+/// A failed call to `service` as an error of the caller's own: an open
+/// circuit's failure is kept as it is, retry-after hint included, so the
+/// caller's client backs off too; anything else is internal, its details
+/// logged rather than passed on.
+pub fn downstream_error(service: &str, status: Status) -> Status {
+    if is_circuit_open(&status) {
+        status
+    } else {
+        AppError::Internal(format!("{} service error: {}", service, status)).into()
+    }
+}
+
+/// Circuit breaker for the calls to one service. Clones share the circuit,
+/// so every client wrapped with the same layer opens and closes together.
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerLayer {
+    breaker: Arc<Breaker>,
+}
+
+impl CircuitBreakerLayer {
+    pub fn new(service: impl Into<String>, config: CircuitBreakerConfig) -> Self {
+        Self {
+            breaker: Arc::new(Breaker {
+                service: service.into(),
+                config,
+                state: Mutex::new(State::Closed { failures: 0 }),
+            }),
+        }
+    }
+}
+
+impl<S> Layer<S> for CircuitBreakerLayer {
+    type Service = CircuitBreaker<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        CircuitBreaker {
+            inner: service,
+            breaker: self.breaker.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker<S> {
+    inner: S,
+    breaker: Arc<Breaker>,
+}
+
+impl<S, B> Service<Request<B>> for CircuitBreaker<S>
+where
+    S: Service<Request<B>, Response = Response<BoxBody>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = ResponseFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<B>) -> Self::Future {
+        let kind = match self.breaker.admit() {
+            Ok(trial) => Kind::Called {
+                future: self.inner.call(req),
+                outcome: Outcome {
+                    breaker: self.breaker.clone(),
+                    trial,
+                    recorded: false,
+                },
+            },
+            Err(retry_after) => Kind::Rejected {
+                response: Some(self.breaker.open_status(retry_after).into_http()),
+            },
+        };
+        ResponseFuture { kind }
+    }
+}
+
+/// Reports a call's outcome to its breaker. A trial dropped before it
+/// finished, e.g. cancelled by its caller or its deadline, counts as
+/// failed: the circuit would otherwise stay half-open with no trial left
+/// to close or reopen it.
+struct Outcome {
+    breaker: Arc<Breaker>,
+    trial: bool,
+    recorded: bool,
+}
+
+impl Outcome {
+    fn record(&mut self, failed: bool) {
+        self.recorded = true;
+        self.breaker.record(failed);
+    }
+}
+
+impl Drop for Outcome {
+    fn drop(&mut self) {
+        if self.trial && !self.recorded {
+            self.breaker.record(true);
+        }
+    }
+}
+
+#[pin_project]
+pub struct ResponseFuture<F> {
+    #[pin]
+    kind: Kind<F>,
+}
+
+#[pin_project(project = KindProj)]
+enum Kind<F> {
+    Called {
+        #[pin]
+        future: F,
+        outcome: Outcome,
+    },
+    Rejected {
+        response: Option<Response<BoxBody>>,
+    },
+}
+
+impl<F, E> Future for ResponseFuture<F>
+where
+    F: Future<Output = Result<Response<BoxBody>, E>>,
+{
+    type Output = Result<Response<BoxBody>, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.project().kind.project() {
+            KindProj::Called { future, outcome } => match future.poll(cx) {
+                Poll::Ready(result) => {
+                    let failed = match &result {
+                        Ok(response) => is_failure(response_code(response)),
+                        // The service couldn't be reached
+                        Err(_) => true,
+                    };
+                    outcome.record(failed);
+                    Poll::Ready(result)
+                }
+                Poll::Pending => Poll::Pending,
+            },
+            KindProj::Rejected { response } => Poll::Ready(Ok(response
+                .take()
+                .expect("ResponseFuture polled after completion"))),
+        }
+    }
+}
+
+const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+const DEFAULT_BASE_DELAY_MS: u64 = 50;
+const DEFAULT_MAX_DELAY_MS: u64 = 1000;
+
+/// How often and how far apart a failed call is repeated.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Calls made in all, the first one included.
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled before each one after it.
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// Waits a random share of the delay instead, so callers that failed
+    /// together don't retry together.
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            base_delay: Duration::from_millis(DEFAULT_BASE_DELAY_MS),
+            max_delay: Duration::from_millis(DEFAULT_MAX_DELAY_MS),
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Reads `RETRY_MAX_ATTEMPTS`, `RETRY_BASE_DELAY_MS`, `RETRY_MAX_DELAY_MS`
+    /// and `RETRY_JITTER`.
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        let millis = |var: &str| {
+            env::var(var)
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_millis)
+        };
+        Self {
+            max_attempts: env::var("RETRY_MAX_ATTEMPTS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(default.max_attempts),
+            base_delay: millis("RETRY_BASE_DELAY_MS").unwrap_or(default.base_delay),
+            max_delay: millis("RETRY_MAX_DELAY_MS").unwrap_or(default.max_delay),
+            jitter: env::var("RETRY_JITTER")
+                .map(|v| v != "false")
+                .unwrap_or(default.jitter),
+        }
+    }
+
+    /// Delay before retry number `retry`, counted from 1.
+    fn delay(&self, retry: u32) -> Duration {
+        let delay = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry - 1))
+            .min(self.max_delay);
+        if self.jitter {
+            delay.mul_f64(rand::thread_rng().r#gen::<f64>())
+        } else {
+            delay
+        }
+    }
+}
+
+/// Whether a failure may pass by itself, e.g. a dropped connection. An
+/// open circuit isn't: it says when to come back instead.
+fn is_transient(status: &Status) -> bool {
+    matches!(status.code(), Code::Unavailable | Code::DeadlineExceeded) && !is_circuit_open(status)
+}
+
+/// Runs `call` until it succeeds, fails for good or `policy` runs out of
+/// attempts. Only for calls that are safe to repeat: a call that timed out
+/// may still have been carried out.
+pub async fn retry<T, F, Fut>(policy: &RetryPolicy, method: &str, mut call: F) -> Result<T, Status>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, Status>>,
+{
+    let mut attempt = 1;
+    loop {
+        match call().await {
+            Err(status) if attempt < policy.max_attempts && is_transient(&status) => {
+                let delay = policy.delay(attempt);
+                warn!(
+                    method,
+                    attempt,
+                    "Retrying in {}ms: {}",
+                    delay.as_millis(),
+                    status.message()
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::Infallible;
+    use std::thread::sleep;
+
+    const OPEN: Duration = Duration::from_millis(20);
+
+    fn breaker(failure_threshold: u32) -> Arc<Breaker> {
+        Arc::new(Breaker {
+            service: "test".to_string(),
+            config: CircuitBreakerConfig {
+                failure_threshold,
+                open_duration: OPEN,
+            },
+            state: Mutex::new(State::Closed { failures: 0 }),
+        })
+    }
+
+    /// A breaker whose circuit has been open for `OPEN`, so the next call
+    /// is its trial.
+    fn expired_breaker() -> Arc<Breaker> {
+        let breaker = breaker(1);
+        breaker.record(true);
+        sleep(OPEN);
+        breaker
+    }
+
+    /// A service whose calls never finish.
+    struct Hanging;
+
+    impl Service<Request<()>> for Hanging {
+        type Response = Response<BoxBody>;
+        type Error = Infallible;
+        type Future = std::future::Pending<Result<Response<BoxBody>, Infallible>>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, _req: Request<()>) -> Self::Future {
+            std::future::pending()
+        }
+    }
+
+    #[test]
+    fn opens_at_the_failure_threshold() {
+        let breaker = breaker(3);
+        breaker.record(true);
+        breaker.record(true);
+        assert_eq!(breaker.admit(), Ok(false));
+
+        breaker.record(true);
+        assert!(breaker.admit().is_err());
+    }
+
+    #[test]
+    fn a_success_resets_the_failure_count() {
+        let breaker = breaker(2);
+        breaker.record(true);
+        breaker.record(false);
+        breaker.record(true);
+        assert_eq!(breaker.admit(), Ok(false));
+    }
+
+    #[test]
+    fn lets_one_trial_through_after_the_open_duration() {
+        let breaker = expired_breaker();
+        assert_eq!(breaker.admit(), Ok(true));
+        assert_eq!(breaker.admit(), Err(TRIAL_RETRY_AFTER));
+    }
+
+    #[test]
+    fn a_successful_trial_closes_the_circuit() {
+        let breaker = expired_breaker();
+        assert_eq!(breaker.admit(), Ok(true));
+        breaker.record(false);
+        assert_eq!(breaker.admit(), Ok(false));
+    }
+
+    #[test]
+    fn a_failed_trial_reopens_the_circuit() {
+        let breaker = expired_breaker();
+        assert_eq!(breaker.admit(), Ok(true));
+        breaker.record(true);
+        assert!(breaker.admit().is_err());
+    }
+
+    #[test]
+    fn a_cancelled_trial_reopens_the_circuit() {
+        let breaker = expired_breaker();
+        let mut service = CircuitBreaker {
+            inner: Hanging,
+            breaker: breaker.clone(),
+        };
+        drop(service.call(Request::new(())));
+        assert!(breaker.admit().is_err());
+
+        sleep(OPEN);
+        assert_eq!(breaker.admit(), Ok(true));
+    }
+
+    #[test]
+    fn cancelled_calls_on_a_closed_circuit_are_not_failures() {
+        let breaker = breaker(1);
+        let mut service = CircuitBreaker {
+            inner: Hanging,
+            breaker: breaker.clone(),
+        };
+        drop(service.call(Request::new(())));
+        assert_eq!(breaker.admit(), Ok(false));
+    }
+}
@@ -0,0 +1,245 @@
+use bytes::Bytes;
+use dashmap::DashMap;
+use http::header::AUTHORIZATION;
+use http::{HeaderMap, Request, Response, StatusCode};
+use http_body::Frame;
+use http_body_util::{BodyExt, Full, StreamBody};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tonic::Status;
+use tonic::body::BoxBody;
+use tower::{Layer, Service};
+use tracing::debug;
+
+const DEFAULT_MAX_ENTRIES: usize = 10_000;
+
+#[derive(Clone)]
+struct CachedResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+    trailers: Option<HeaderMap>,
+    expires_at: Instant,
+}
+
+impl CachedResponse {
+    fn to_response(&self) -> Response<BoxBody> {
+        let mut frames = vec![Ok::<_, Status>(Frame::data(self.body.clone()))];
+        if let Some(trailers) = &self.trailers {
+            frames.push(Ok(Frame::trailers(trailers.clone())));
+        }
+
+        let mut response = Response::new(tonic::body::boxed(StreamBody::new(tokio_stream::iter(
+            frames,
+        ))));
+        *response.status_mut() = self.status;
+        *response.headers_mut() = self.headers.clone();
+        response
+    }
+}
+
+/// What a response is cached under: the method, who called it and the
+/// encoded request message. Handlers scope answers to the caller, e.g. a
+/// seller's drafts, so one caller's response is never served to another;
+/// the `authorization` header is kept as a SHA-256 digest, `None` when the
+/// call had none.
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    path: String,
+    caller: Option<[u8; 32]>,
+    message: Bytes,
+}
+
+/// Read-through cache of unary gRPC responses, keyed by `CacheKey`. Only
+/// methods with a TTL rule are cached.
+///
+/// Entries live in this process only: `invalidate_prefix` clears the
+/// replica that made the write, and the other replicas keep serving what
+/// they cached until it expires. The TTLs are what bound staleness across
+/// replicas, so rules should keep them short.
+pub struct ResponseCache {
+    rules: HashMap<String, Duration>,
+    max_entries: usize,
+    entries: DashMap<CacheKey, CachedResponse>,
+}
+
+impl ResponseCache {
+    pub fn new() -> Self {
+        Self {
+            rules: HashMap::new(),
+            max_entries: DEFAULT_MAX_ENTRIES,
+            entries: DashMap::new(),
+        }
+    }
+
+    /// Caches successful responses of `path` (e.g. `/product.ProductService/GetProduct`) for `ttl`.
+    pub fn with_rule(mut self, path: impl Into<String>, ttl: Duration) -> Self {
+        self.rules.insert(path.into(), ttl);
+        self
+    }
+
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    pub fn build(self) -> Arc<Self> {
+        Arc::new(self)
+    }
+
+    /// Drops every response this process cached for methods starting with
+    /// `prefix`, e.g. `/product.ProductService/` after a product write.
+    pub fn invalidate_prefix(&self, prefix: &str) {
+        self.entries.retain(|key, _| !key.path.starts_with(prefix));
+    }
+
+    fn ttl_for(&self, path: &str) -> Option<Duration> {
+        self.rules.get(path).copied()
+    }
+
+    fn get(&self, key: &CacheKey) -> Option<Response<BoxBody>> {
+        let entry = self.entries.get(key)?;
+        if entry.expires_at <= Instant::now() {
+            drop(entry);
+            self.entries.remove(key);
+            return None;
+        }
+        Some(entry.to_response())
+    }
+
+    fn insert(&self, key: CacheKey, value: CachedResponse) {
+        if self.entries.len() >= self.max_entries {
+            let now = Instant::now();
+            self.entries.retain(|_, v| v.expires_at > now);
+            if self.entries.len() >= self.max_entries {
+                return;
+            }
+        }
+        self.entries.insert(key, value);
+    }
+}
+
+impl Default for ResponseCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns true when the response (or its trailers) carries grpc-status 0.
+fn is_grpc_ok(headers: &HeaderMap, trailers: Option<&HeaderMap>) -> bool {
+    let status = headers
+        .get("grpc-status")
+        .or_else(|| trailers.and_then(|t| t.get("grpc-status")));
+    matches!(status.map(|v| v.as_bytes()), Some(b"0"))
+}
+
+#[derive(Clone)]
+pub struct ResponseCacheLayer {
+    cache: Arc<ResponseCache>,
+}
+
+impl ResponseCacheLayer {
+    pub fn new(cache: Arc<ResponseCache>) -> Self {
+        Self { cache }
+    }
+}
+
+impl<S> Layer<S> for ResponseCacheLayer {
+    type Service = ResponseCacheService<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        ResponseCacheService {
+            inner: service,
+            cache: self.cache.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ResponseCacheService<S> {
+    inner: S,
+    cache: Arc<ResponseCache>,
+}
+
+impl<S> Service<Request<BoxBody>> for ResponseCacheService<S>
+where
+    S: Service<Request<BoxBody>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<BoxBody>) -> Self::Future {
+        let path = req.uri().path().to_owned();
+        let mut inner = self.inner.clone();
+
+        let ttl = match self.cache.ttl_for(&path) {
+            Some(ttl) => ttl,
+            None => return Box::pin(inner.call(req)),
+        };
+        let cache = self.cache.clone();
+
+        Box::pin(async move {
+            // Buffer the (unary) request so its encoded message can be part of the key
+            let (parts, body) = req.into_parts();
+            let body = match body.collect().await {
+                Ok(collected) => collected.to_bytes(),
+                Err(status) => return Ok(status.into_http()),
+            };
+
+            let key = CacheKey {
+                path,
+                caller: parts
+                    .headers
+                    .get(AUTHORIZATION)
+                    .map(|value| Sha256::digest(value.as_bytes()).into()),
+                message: body.clone(),
+            };
+            if let Some(response) = cache.get(&key) {
+                debug!(path = %key.path, "Response cache hit");
+                return Ok(response);
+            }
+
+            let req = Request::from_parts(parts, tonic::body::boxed(Full::new(body)));
+            let response = inner.call(req).await?;
+
+            // Trailers-only responses carry a non-OK status in the headers
+            if response.headers().contains_key("grpc-status")
+                && !is_grpc_ok(response.headers(), None)
+            {
+                return Ok(response);
+            }
+
+            let (parts, body) = response.into_parts();
+            let collected = match body.collect().await {
+                Ok(collected) => collected,
+                Err(status) => return Ok(status.into_http()),
+            };
+            let trailers = collected.trailers().cloned();
+            let cached = CachedResponse {
+                status: parts.status,
+                headers: parts.headers,
+                body: collected.to_bytes(),
+                trailers,
+                expires_at: Instant::now() + ttl,
+            };
+
+            let response = cached.to_response();
+            if is_grpc_ok(&cached.headers, cached.trailers.as_ref()) {
+                cache.insert(key, cached);
+            }
+
+            Ok(response)
+        })
+    }
+}
@@ -0,0 +1,45 @@
+//! Developer sandbox: with `SANDBOX_MODE=true` every external provider is
+//! swapped for a deterministic fake, so a deployed environment can run full
+//! checkouts without real side effects. The magic values below are the
+//! contract the fakes share with testers.
+
+use std::env;
+
+/// Captcha token the sandbox verifier accepts; every other token is rejected.
+pub const CAPTCHA_PASS_TOKEN: &str = "sandbox-captcha-pass";
+
+/// Card number the sandbox payment provider always approves.
+pub const CARD_APPROVED: &str = "4242424242424242";
+/// Card number the sandbox payment provider always declines.
+pub const CARD_DECLINED: &str = "4000000000000002";
+/// Card number declined for insufficient funds.
+pub const CARD_INSUFFICIENT_FUNDS: &str = "4000000000009995";
+
+/// Flat tax rate, in percent, charged by the sandbox tax provider.
+pub const TAX_RATE_PERCENT: u32 = 10;
+
+/// Exchange rate the sandbox rates provider quotes for every currency.
+pub const EXCHANGE_RATE: u32 = 2;
+
+/// Outcome the sandbox payment provider reports for a card number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CardOutcome {
+    Approved,
+    Declined,
+    InsufficientFunds,
+}
+
+/// Unknown card numbers are approved, so any test card completes a checkout.
+pub fn card_outcome(card_number: &str) -> CardOutcome {
+    let digits: String = card_number.chars().filter(|c| c.is_ascii_digit()).collect();
+    match digits.as_str() {
+        CARD_DECLINED => CardOutcome::Declined,
+        CARD_INSUFFICIENT_FUNDS => CardOutcome::InsufficientFunds,
+        _ => CardOutcome::Approved,
+    }
+}
+
+/// Whether `SANDBOX_MODE` is set to `true` (or `1`).
+pub fn enabled() -> bool {
+    env::var("SANDBOX_MODE").is_ok_and(|v| matches!(v.to_ascii_lowercase().as_str(), "true" | "1"))
+}
@@ -0,0 +1,136 @@
+//! The middleware stack every service serves its RPCs through, so each one
+//! gets the same logging, metrics, tracing, rate limiting and validation
+//! without wiring the layers itself.
+
+use crate::catch_panic::{CatchPanic, CatchPanicLayer};
+use crate::idempotency::{IdempotencyLayer, IdempotencyService, IdempotencyStore, MemoryStore};
+use crate::logging::{LoggingLayer, LoggingService};
+use crate::metrics::{Metrics, MetricsLayer, MetricsMiddleware};
+use crate::ratelimit::{RateLimitLayer, RateLimitService};
+use crate::request_id::{RequestIdLayer, RequestIdMiddleware};
+use crate::response_cache::{ResponseCache, ResponseCacheLayer, ResponseCacheService};
+use crate::slo::{SloLayer, SloMiddleware, SloTracker};
+use crate::telemetry::{TraceContextLayer, TraceContextMiddleware};
+use crate::tls;
+use crate::validation::{ValidationLayer, ValidationService};
+use std::sync::Arc;
+use std::time::Duration;
+use tonic::transport::Server;
+use tower::Layer;
+use tower::layer::util::{Identity, Stack};
+
+/// Interval of the sweep dropping idle rate limit budgets.
+const RATE_LIMIT_EVICTION_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A server with the standard middleware stack.
+pub struct ServerBuilder {
+    metrics: Arc<Metrics>,
+    slo_tracker: Arc<SloTracker>,
+    rate_limit: Option<RateLimitLayer>,
+    response_cache: Option<Arc<ResponseCache>>,
+    idempotency_store: Option<Arc<dyn IdempotencyStore>>,
+}
+
+impl ServerBuilder {
+    pub fn new(metrics: Arc<Metrics>, slo_tracker: Arc<SloTracker>) -> Self {
+        Self {
+            metrics,
+            slo_tracker,
+            rate_limit: None,
+            response_cache: None,
+            idempotency_store: None,
+        }
+    }
+
+    /// Limits callers by `layer` rather than by `RateLimitLayer::from_env`.
+    pub fn with_rate_limit(mut self, layer: RateLimitLayer) -> Self {
+        self.rate_limit = Some(layer);
+        self
+    }
+
+    pub fn with_response_cache(mut self, cache: Arc<ResponseCache>) -> Self {
+        self.response_cache = Some(cache);
+        self
+    }
+
+    /// Keeps the responses of calls with an idempotency key in `store`
+    /// rather than in memory, see `idempotency`.
+    pub fn with_idempotency_store(mut self, store: Arc<dyn IdempotencyStore>) -> Self {
+        self.idempotency_store = Some(store);
+        self
+    }
+
+    /// The server, over TLS when it's configured, see `tls::server`.
+    pub fn build(self) -> anyhow::Result<Server<Stack<StandardLayer, Identity>>> {
+        let rate_limit = self
+            .rate_limit
+            .unwrap_or_else(RateLimitLayer::from_env)
+            .with_metrics(&self.metrics);
+        rate_limit.spawn_eviction(RATE_LIMIT_EVICTION_INTERVAL);
+
+        let response_cache = self
+            .response_cache
+            .unwrap_or_else(|| ResponseCache::new().build());
+        let idempotency_store = self
+            .idempotency_store
+            .unwrap_or_else(|| Arc::new(MemoryStore::new()));
+
+        Ok(tls::server()?.layer(StandardLayer {
+            catch_panic: CatchPanicLayer::new(&self.metrics),
+            metrics: MetricsLayer::new(self.metrics),
+            rate_limit,
+            idempotency: IdempotencyLayer::new(idempotency_store),
+            slo: SloLayer::new(self.slo_tracker),
+            response_cache: ResponseCacheLayer::new(response_cache),
+        }))
+    }
+}
+
+/// The layers of the standard stack, outermost first: metrics, trace
+/// context, request ID, logging, panic recovery, rate limit, idempotency,
+/// SLO, response cache and validation. Rejected calls are still counted,
+/// traced and logged, as are calls whose handler panicked, and only calls
+/// that passed the rate limit and weren't answered with a stored response
+/// count against the SLO.
+#[derive(Clone)]
+pub struct StandardLayer {
+    metrics: MetricsLayer,
+    catch_panic: CatchPanicLayer,
+    rate_limit: RateLimitLayer,
+    idempotency: IdempotencyLayer,
+    slo: SloLayer,
+    response_cache: ResponseCacheLayer,
+}
+
+type StandardService<S> = MetricsMiddleware<
+    TraceContextMiddleware<
+        RequestIdMiddleware<
+            LoggingService<
+                CatchPanic<
+                    RateLimitService<
+                        IdempotencyService<
+                            SloMiddleware<ResponseCacheService<ValidationService<S>>>,
+                        >,
+                    >,
+                >,
+            >,
+        >,
+    >,
+>;
+
+impl<S> Layer<S> for StandardLayer {
+    type Service = StandardService<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        let service = ValidationLayer.layer(service);
+        let service = self.response_cache.layer(service);
+        let service = self.slo.layer(service);
+        let service = self.idempotency.layer(service);
+        let service = self.rate_limit.layer(service);
+        let service = self.catch_panic.layer(service);
+        let service = LoggingLayer.layer(service);
+        let service = RequestIdLayer.layer(service);
+        let service = TraceContextLayer.layer(service);
+        self.metrics.layer(service)
+    }
+}
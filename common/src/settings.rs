@@ -0,0 +1,371 @@
+use crate::auth::Caller;
+use crate::error::{self, AppError};
+use crate::pagination::{self, PageRequest};
+use proto::settings::{
+    GetSettingsRequest, GetSettingsResponse, ListSettingsAuditRequest, ListSettingsAuditResponse,
+    SettingsChange, StoreSettings, UpdateSettingsRequest, UpdateSettingsResponse,
+    settings_service_server::SettingsService,
+};
+use sqlx::{PgPool, Postgres, QueryBuilder};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tonic::{Request, Response, Status};
+
+/// Settings fields, named as in the `StoreSettings` message.
+pub const STORE_NAME: &str = "store_name";
+pub const SUPPORT_EMAIL: &str = "support_email";
+pub const CURRENCIES: &str = "currencies";
+pub const TAX_INCLUSIVE_DISPLAY: &str = "tax_inclusive_display";
+pub const ORDER_NUMBER_PREFIX: &str = "order_number_prefix";
+pub const CHECKOUT_ENABLED: &str = "checkout_enabled";
+pub const GUEST_CHECKOUT_ENABLED: &str = "guest_checkout_enabled";
+
+const MAX_STORE_NAME_LENGTH: usize = 255;
+const MAX_ORDER_NUMBER_PREFIX_LENGTH: usize = 10;
+
+/// Values used for fields nobody has set yet.
+pub fn defaults() -> StoreSettings {
+    StoreSettings {
+        store_name: "E-Commerce Store".to_string(),
+        support_email: "support@example.com".to_string(),
+        currencies: vec!["USD".to_string()],
+        tax_inclusive_display: false,
+        order_number_prefix: "ORD".to_string(),
+        checkout_enabled: true,
+        guest_checkout_enabled: false,
+    }
+}
+
+/// Stored form of a field; `None` for unknown fields.
+fn field_value(settings: &StoreSettings, field: &str) -> Option<String> {
+    Some(match field {
+        STORE_NAME => settings.store_name.clone(),
+        SUPPORT_EMAIL => settings.support_email.clone(),
+        CURRENCIES => settings.currencies.join(","),
+        TAX_INCLUSIVE_DISPLAY => settings.tax_inclusive_display.to_string(),
+        ORDER_NUMBER_PREFIX => settings.order_number_prefix.clone(),
+        CHECKOUT_ENABLED => settings.checkout_enabled.to_string(),
+        GUEST_CHECKOUT_ENABLED => settings.guest_checkout_enabled.to_string(),
+        _ => return None,
+    })
+}
+
+/// Sets a field from its stored form, rejecting values the field can't hold.
+fn set_field(settings: &mut StoreSettings, field: &str, value: &str) -> Result<(), String> {
+    let flag = || match value {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(format!("{}: must be true or false", field)),
+    };
+
+    match field {
+        STORE_NAME => {
+            if value.trim().is_empty() || value.chars().count() > MAX_STORE_NAME_LENGTH {
+                return Err(format!(
+                    "{}: must be 1 to {} characters",
+                    field, MAX_STORE_NAME_LENGTH
+                ));
+            }
+            settings.store_name = value.trim().to_string();
+        }
+        SUPPORT_EMAIL => {
+            match value.split_once('@') {
+                Some((local, domain)) if !local.is_empty() && domain.contains('.') => {}
+                _ => return Err(format!("{}: must be an email address", field)),
+            }
+            settings.support_email = value.to_string();
+        }
+        CURRENCIES => {
+            let currencies: Vec<String> = value.split(',').map(|c| c.trim().to_string()).collect();
+            if currencies
+                .iter()
+                .any(|c| c.len() != 3 || !c.chars().all(|ch| ch.is_ascii_uppercase()))
+            {
+                return Err(format!(
+                    "{}: must be a non-empty list of ISO 4217 codes such as USD",
+                    field
+                ));
+            }
+            settings.currencies = currencies;
+        }
+        TAX_INCLUSIVE_DISPLAY => settings.tax_inclusive_display = flag()?,
+        ORDER_NUMBER_PREFIX => {
+            if value.is_empty()
+                || value.len() > MAX_ORDER_NUMBER_PREFIX_LENGTH
+                || !value
+                    .chars()
+                    .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+            {
+                return Err(format!(
+                    "{}: must be 1 to {} uppercase letters or digits",
+                    field, MAX_ORDER_NUMBER_PREFIX_LENGTH
+                ));
+            }
+            settings.order_number_prefix = value.to_string();
+        }
+        CHECKOUT_ENABLED => settings.checkout_enabled = flag()?,
+        GUEST_CHECKOUT_ENABLED => settings.guest_checkout_enabled = flag()?,
+        _ => return Err(format!("Unknown setting: {}", field)),
+    }
+    Ok(())
+}
+
+/// Store-wide settings read from the database and cached for `ttl`. Each
+/// process caches on its own, so a change made elsewhere shows up here
+/// within the TTL.
+pub struct SettingsStore {
+    db: PgPool,
+    ttl: Duration,
+    cached: RwLock<Option<(StoreSettings, Instant)>>,
+}
+
+impl SettingsStore {
+    pub fn new(db: PgPool, ttl: Duration) -> Arc<Self> {
+        Arc::new(Self {
+            db,
+            ttl,
+            cached: RwLock::new(None),
+        })
+    }
+
+    /// Current settings; stored values override the defaults.
+    pub async fn get(&self) -> Result<StoreSettings, Status> {
+        if let Some((settings, expires_at)) = self.cached.read().unwrap().as_ref()
+            && *expires_at > Instant::now()
+        {
+            return Ok(settings.clone());
+        }
+
+        let rows: Vec<(String, String)> = sqlx::query_as("SELECT field, value FROM store_settings")
+            .fetch_all(&self.db)
+            .await
+            .map_err(AppError::from)?;
+
+        let mut settings = defaults();
+        for (field, value) in rows {
+            // A value this version can't read keeps the default rather than failing every read
+            if let Err(e) = set_field(&mut settings, &field, &value) {
+                tracing::warn!(field = %field, error = %e, "Ignoring stored setting");
+            }
+        }
+
+        *self.cached.write().unwrap() = Some((settings.clone(), Instant::now() + self.ttl));
+        Ok(settings)
+    }
+
+    /// Drops the cached settings so the next read goes to the database.
+    pub fn invalidate(&self) {
+        *self.cached.write().unwrap() = None;
+    }
+
+    pub async fn store_name(&self) -> Result<String, Status> {
+        Ok(self.get().await?.store_name)
+    }
+
+    pub async fn support_email(&self) -> Result<String, Status> {
+        Ok(self.get().await?.support_email)
+    }
+
+    /// Accepted currencies; the first is the default.
+    pub async fn currencies(&self) -> Result<Vec<String>, Status> {
+        Ok(self.get().await?.currencies)
+    }
+
+    pub async fn tax_inclusive_display(&self) -> Result<bool, Status> {
+        Ok(self.get().await?.tax_inclusive_display)
+    }
+
+    pub async fn order_number_prefix(&self) -> Result<String, Status> {
+        Ok(self.get().await?.order_number_prefix)
+    }
+
+    pub async fn checkout_enabled(&self) -> Result<bool, Status> {
+        Ok(self.get().await?.checkout_enabled)
+    }
+
+    pub async fn guest_checkout_enabled(&self) -> Result<bool, Status> {
+        Ok(self.get().await?.guest_checkout_enabled)
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct DbSettingsChange {
+    id: i64,
+    field: String,
+    old_value: String,
+    new_value: String,
+    actor: String,
+    reason: String,
+    changed_at: i64,
+}
+
+impl DbSettingsChange {
+    fn to_proto(&self) -> SettingsChange {
+        SettingsChange {
+            change_id: self.id,
+            field: self.field.clone(),
+            old_value: self.old_value.clone(),
+            new_value: self.new_value.clone(),
+            actor: self.actor.clone(),
+            reason: self.reason.clone(),
+            changed_at: self.changed_at,
+        }
+    }
+}
+
+fn push_audit_filters(qb: &mut QueryBuilder<'_, Postgres>, req: &ListSettingsAuditRequest) {
+    qb.push(" WHERE TRUE");
+    if !req.field.is_empty() {
+        qb.push(" AND field = ").push_bind(req.field.clone());
+    }
+}
+
+pub struct SettingsServiceImpl {
+    store: Arc<SettingsStore>,
+}
+
+impl SettingsServiceImpl {
+    pub fn new(store: Arc<SettingsStore>) -> Self {
+        Self { store }
+    }
+}
+
+#[tonic::async_trait]
+impl SettingsService for SettingsServiceImpl {
+    async fn get_settings(
+        &self,
+        _request: Request<GetSettingsRequest>,
+    ) -> Result<Response<GetSettingsResponse>, Status> {
+        Ok(Response::new(GetSettingsResponse {
+            settings: Some(self.store.get().await?),
+        }))
+    }
+
+    async fn update_settings(
+        &self,
+        request: Request<UpdateSettingsRequest>,
+    ) -> Result<Response<UpdateSettingsResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        caller.require_platform()?;
+        let actor = caller.actor();
+        let req = request.into_inner();
+
+        // Field rules are checked by the validation layer (see settings.proto)
+        let requested = req.settings.unwrap_or_default();
+        let mut changes = Vec::new();
+        for field in &req.fields {
+            let value = match field_value(&requested, field) {
+                Some(value) => value,
+                None => {
+                    return Err(error::invalid_argument(
+                        "UNKNOWN_SETTING",
+                        format!("Unknown setting: {}", field),
+                    ));
+                }
+            };
+            // Parsing the stored form back checks the value the same way reads do
+            if let Err(e) = set_field(&mut defaults(), field, &value) {
+                return Err(error::invalid_argument("INVALID_SETTING", e));
+            }
+            changes.push((field.as_str(), value));
+        }
+
+        let mut tx = self.store.db.begin().await.map_err(AppError::from)?;
+
+        // Serializes updates so each audit entry's old value is the one it replaced
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext('store_settings'))")
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::from)?;
+
+        let mut changed = 0;
+        for (field, value) in &changes {
+            let stored: Option<String> =
+                sqlx::query_scalar("SELECT value FROM store_settings WHERE field = $1")
+                    .bind(field)
+                    .fetch_optional(&mut *tx)
+                    .await
+                    .map_err(AppError::from)?;
+            let old_value = stored
+                .or_else(|| field_value(&defaults(), field))
+                .unwrap_or_default();
+            if old_value == *value {
+                continue;
+            }
+
+            sqlx::query(
+                "INSERT INTO store_settings (field, value, updated_by) VALUES ($1, $2, $3)
+                 ON CONFLICT (field) DO UPDATE
+                 SET value = EXCLUDED.value, updated_by = EXCLUDED.updated_by, updated_at = CURRENT_TIMESTAMP",
+            )
+            .bind(field)
+            .bind(value)
+            .bind(&actor)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::from)?;
+
+            sqlx::query(
+                "INSERT INTO store_settings_audit (field, old_value, new_value, actor, reason)
+                 VALUES ($1, $2, $3, $4, $5)",
+            )
+            .bind(field)
+            .bind(&old_value)
+            .bind(value)
+            .bind(&actor)
+            .bind(&req.reason)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::from)?;
+
+            changed += 1;
+        }
+
+        tx.commit().await.map_err(AppError::from)?;
+
+        self.store.invalidate();
+
+        Ok(Response::new(UpdateSettingsResponse {
+            success: true,
+            message: format!("Updated {} settings", changed),
+            settings: Some(self.store.get().await?),
+        }))
+    }
+
+    async fn list_settings_audit(
+        &self,
+        request: Request<ListSettingsAuditRequest>,
+    ) -> Result<Response<ListSettingsAuditResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+        let page = PageRequest::new(req.page, req.page_size);
+
+        let mut query = QueryBuilder::<Postgres>::new(
+            "SELECT id, field, old_value, new_value, actor, reason,
+                    EXTRACT(EPOCH FROM created_at)::BIGINT AS changed_at
+             FROM store_settings_audit",
+        );
+        push_audit_filters(&mut query, &req);
+        query.push(" ORDER BY id DESC");
+        page.push_limit_offset(&mut query);
+
+        let changes = query
+            .build_query_as::<DbSettingsChange>()
+            .fetch_all(&self.store.db)
+            .await
+            .map_err(AppError::from)?;
+
+        let mut count_query =
+            QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM store_settings_audit");
+        push_audit_filters(&mut count_query, &req);
+
+        let total_count = pagination::fetch_count(count_query, &self.store.db)
+            .await
+            .map_err(AppError::from)?;
+
+        Ok(Response::new(ListSettingsAuditResponse {
+            changes: changes.iter().map(DbSettingsChange::to_proto).collect(),
+            total_count,
+        }))
+    }
+}
@@ -0,0 +1,374 @@
+use crate::auth::Caller;
+use anyhow::{Result, anyhow};
+use dashmap::DashMap;
+use http::{Request, Response};
+use pin_project::pin_project;
+use proto::slo::{
+    GetSloStatusRequest, GetSloStatusResponse, SloStatus, slo_service_server::SloService,
+};
+use std::collections::HashMap;
+use std::env;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tonic::Code;
+use tonic::body::BoxBody;
+use tower::{Layer, Service};
+
+/// Number of one-minute buckets kept per method; this is the long window.
+const LONG_WINDOW_MINUTES: u64 = 60;
+/// The short window used for fast-burn detection.
+const SHORT_WINDOW_MINUTES: u64 = 5;
+
+const DEFAULT_THRESHOLD_MS: u64 = 500;
+const DEFAULT_OBJECTIVE: f64 = 0.99;
+
+#[derive(Debug, Clone, Copy)]
+pub struct SloTarget {
+    pub threshold: Duration,
+    pub objective: f64,
+}
+
+impl std::str::FromStr for SloTarget {
+    type Err = anyhow::Error;
+
+    /// Parses `<threshold_ms>:<objective>`, e.g. `200:0.99`.
+    fn from_str(s: &str) -> Result<Self> {
+        let (threshold, objective) = s
+            .split_once(':')
+            .ok_or_else(|| anyhow!("Invalid SLO target '{}', expected <ms>:<objective>", s))?;
+
+        let threshold = Duration::from_millis(threshold.trim().parse()?);
+        let objective: f64 = objective.trim().parse()?;
+        if !(0.0..1.0).contains(&objective) {
+            return Err(anyhow!("SLO objective must be in [0, 1): {}", objective));
+        }
+
+        Ok(Self {
+            threshold,
+            objective,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SloConfig {
+    default: SloTarget,
+    per_method: HashMap<String, SloTarget>,
+}
+
+impl SloConfig {
+    pub fn new(default: SloTarget) -> Self {
+        Self {
+            default,
+            per_method: HashMap::new(),
+        }
+    }
+
+    pub fn with_method(mut self, path: impl Into<String>, target: SloTarget) -> Self {
+        self.per_method.insert(path.into(), target);
+        self
+    }
+
+    /// Reads `SLO_DEFAULT` (`<ms>:<objective>`) and `SLO_TARGETS`, a comma
+    /// separated list of `<grpc path>=<ms>:<objective>` entries, e.g.
+    /// `/user.UserService/Login=200:0.99`.
+    pub fn from_env() -> Result<Self> {
+        let default = match env::var("SLO_DEFAULT") {
+            Ok(v) if !v.is_empty() => v.parse()?,
+            _ => SloTarget {
+                threshold: Duration::from_millis(DEFAULT_THRESHOLD_MS),
+                objective: DEFAULT_OBJECTIVE,
+            },
+        };
+
+        let mut config = Self::new(default);
+        if let Ok(targets) = env::var("SLO_TARGETS") {
+            for entry in targets.split(',').filter(|e| !e.trim().is_empty()) {
+                let (path, target) = entry
+                    .split_once('=')
+                    .ok_or_else(|| anyhow!("Invalid SLO_TARGETS entry: {}", entry))?;
+                config = config.with_method(path.trim(), target.parse()?);
+            }
+        }
+
+        Ok(config)
+    }
+
+    fn target_for(&self, path: &str) -> SloTarget {
+        self.per_method.get(path).copied().unwrap_or(self.default)
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+struct Bucket {
+    minute: u64,
+    total: u64,
+    good: u64,
+}
+
+/// Per-method ring of one-minute buckets covering the long window.
+struct RollingWindow {
+    buckets: [Bucket; LONG_WINDOW_MINUTES as usize],
+}
+
+impl RollingWindow {
+    fn new() -> Self {
+        Self {
+            buckets: [Bucket::default(); LONG_WINDOW_MINUTES as usize],
+        }
+    }
+
+    fn record(&mut self, minute: u64, good: bool) {
+        let bucket = &mut self.buckets[(minute % LONG_WINDOW_MINUTES) as usize];
+        if bucket.minute != minute {
+            *bucket = Bucket {
+                minute,
+                total: 0,
+                good: 0,
+            };
+        }
+        bucket.total += 1;
+        if good {
+            bucket.good += 1;
+        }
+    }
+
+    /// Returns (total, good) over the last `minutes` minutes.
+    fn totals(&self, now_minute: u64, minutes: u64) -> (u64, u64) {
+        self.buckets
+            .iter()
+            .filter(|b| b.total > 0 && now_minute - b.minute < minutes)
+            .fold((0, 0), |(total, good), b| (total + b.total, good + b.good))
+    }
+}
+
+/// Tracks request latencies against the configured SLOs.
+pub struct SloTracker {
+    service: String,
+    config: SloConfig,
+    started: Instant,
+    windows: DashMap<String, RollingWindow>,
+}
+
+impl SloTracker {
+    pub fn new(service: impl Into<String>, config: SloConfig) -> Arc<Self> {
+        Arc::new(Self {
+            service: service.into(),
+            config,
+            started: Instant::now(),
+            windows: DashMap::new(),
+        })
+    }
+
+    fn current_minute(&self) -> u64 {
+        self.started.elapsed().as_secs() / 60
+    }
+
+    fn record(&self, path: &str, latency: Duration, succeeded: bool) {
+        let target = self.config.target_for(path);
+        let good = succeeded && latency <= target.threshold;
+        let minute = self.current_minute();
+
+        self.windows
+            .entry(path.to_string())
+            .or_insert_with(RollingWindow::new)
+            .record(minute, good);
+    }
+
+    pub fn statuses(&self) -> Vec<SloStatus> {
+        let now_minute = self.current_minute();
+
+        let mut statuses: Vec<SloStatus> = self
+            .windows
+            .iter()
+            .map(|entry| {
+                let target = self.config.target_for(entry.key());
+                let (total, good) = entry.totals(now_minute, LONG_WINDOW_MINUTES);
+                let (short_total, short_good) = entry.totals(now_minute, SHORT_WINDOW_MINUTES);
+                let compliance = ratio(good, total);
+
+                SloStatus {
+                    method: entry.key().clone(),
+                    threshold_ms: target.threshold.as_millis() as i64,
+                    objective: target.objective,
+                    total_requests: total as i64,
+                    good_requests: good as i64,
+                    compliance,
+                    burn_rate_long: burn_rate(compliance, target.objective),
+                    burn_rate_short: burn_rate(ratio(short_good, short_total), target.objective),
+                    violating: compliance < target.objective,
+                }
+            })
+            .collect();
+
+        statuses.sort_by(|a, b| a.method.cmp(&b.method));
+        statuses
+    }
+}
+
+fn ratio(good: u64, total: u64) -> f64 {
+    if total == 0 {
+        1.0
+    } else {
+        good as f64 / total as f64
+    }
+}
+
+/// How many times faster than allowed the error budget is being consumed;
+/// 1.0 means the budget runs out exactly at the end of the window.
+fn burn_rate(compliance: f64, objective: f64) -> f64 {
+    (1.0 - compliance) / (1.0 - objective)
+}
+
+#[derive(Clone)]
+pub struct SloLayer {
+    tracker: Arc<SloTracker>,
+}
+
+impl SloLayer {
+    pub fn new(tracker: Arc<SloTracker>) -> Self {
+        Self { tracker }
+    }
+}
+
+impl<S> Layer<S> for SloLayer {
+    type Service = SloMiddleware<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        SloMiddleware {
+            inner: service,
+            tracker: self.tracker.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SloMiddleware<S> {
+    inner: S,
+    tracker: Arc<SloTracker>,
+}
+
+impl<S> Service<Request<BoxBody>> for SloMiddleware<S>
+where
+    S: Service<Request<BoxBody>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = ResponseFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<BoxBody>) -> Self::Future {
+        let path = req.uri().path().to_owned();
+
+        ResponseFuture {
+            future: self.inner.call(req),
+            start: Instant::now(),
+            path,
+            tracker: self.tracker.clone(),
+        }
+    }
+}
+
+/// gRPC code of a response. Handlers that fail answer with the status in
+/// the headers, which is where `metrics` reads it too; streams that fail
+/// after their first message aren't seen here.
+fn response_code<B>(response: &Response<B>) -> Code {
+    response
+        .headers()
+        .get("grpc-status")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<i32>().ok())
+        .map(Code::from)
+        .unwrap_or(Code::Ok)
+}
+
+/// Whether a call failed through the service's fault and so spends error
+/// budget. Refusals, e.g. NOT_FOUND or PERMISSION_DENIED, are answers the
+/// service gave as it should.
+fn is_server_failure(code: Code) -> bool {
+    matches!(
+        code,
+        Code::Unknown
+            | Code::Internal
+            | Code::Unavailable
+            | Code::DeadlineExceeded
+            | Code::DataLoss
+    )
+}
+
+#[pin_project]
+pub struct ResponseFuture<F> {
+    #[pin]
+    future: F,
+    start: Instant,
+    path: String,
+    tracker: Arc<SloTracker>,
+}
+
+impl<F, E> Future for ResponseFuture<F>
+where
+    F: Future<Output = Result<Response<BoxBody>, E>>,
+{
+    type Output = Result<Response<BoxBody>, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        match this.future.poll(cx) {
+            Poll::Ready(result) => {
+                let succeeded = match &result {
+                    Ok(response) => !is_server_failure(response_code(response)),
+                    // The connection failed before a status was sent
+                    Err(_) => false,
+                };
+                this.tracker
+                    .record(this.path, this.start.elapsed(), succeeded);
+                Poll::Ready(result)
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// gRPC endpoint exposing the tracker's current state.
+pub struct SloServiceImpl {
+    tracker: Arc<SloTracker>,
+}
+
+impl SloServiceImpl {
+    pub fn new(tracker: Arc<SloTracker>) -> Self {
+        Self { tracker }
+    }
+}
+
+#[tonic::async_trait]
+impl SloService for SloServiceImpl {
+    async fn get_slo_status(
+        &self,
+        request: tonic::Request<GetSloStatusRequest>,
+    ) -> Result<tonic::Response<GetSloStatusResponse>, tonic::Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        let statuses = self
+            .tracker
+            .statuses()
+            .into_iter()
+            .filter(|s| !req.violating_only || s.violating)
+            .collect();
+
+        Ok(tonic::Response::new(GetSloStatusResponse {
+            service: self.tracker.service.clone(),
+            long_window_secs: (LONG_WINDOW_MINUTES * 60) as i64,
+            short_window_secs: (SHORT_WINDOW_MINUTES * 60) as i64,
+            statuses,
+        }))
+    }
+}
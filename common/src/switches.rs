@@ -0,0 +1,33 @@
+//! Ops switches: named kill switches that operators flip through the order
+//! service's OpsService and that any service sharing the database can read.
+
+use crate::error::AppError;
+use sqlx::PgPool;
+use tonic::Status;
+
+/// When active, the shipping service creates no new shipments.
+pub const FULFILLMENT_DRAINING: &str = "fulfillment_draining";
+
+/// Prefix of the per-provider kill switch, e.g. `payment_provider_disabled:stripe`.
+pub const PAYMENT_PROVIDER_DISABLED_PREFIX: &str = "payment_provider_disabled:";
+
+/// Returns whether the named switch is currently engaged. Unknown switches are inactive.
+pub async fn is_switch_active(db: &PgPool, name: &str) -> Result<bool, Status> {
+    let active: Option<bool> =
+        sqlx::query_scalar("SELECT active FROM ops_switches WHERE name = $1")
+            .bind(name)
+            .fetch_optional(db)
+            .await
+            .map_err(AppError::from)?;
+
+    Ok(active.unwrap_or(false))
+}
+
+/// Name of the kill switch of a payment provider.
+pub fn payment_provider_switch(provider: &str) -> String {
+    format!(
+        "{}{}",
+        PAYMENT_PROVIDER_DISABLED_PREFIX,
+        provider.to_ascii_lowercase()
+    )
+}
@@ -0,0 +1,244 @@
+//! Logging and distributed tracing. `init` logs to stdout, credentials
+//! redacted, as JSON lines carrying the service name and version when
+//! `LOG_FORMAT=json`, and, when
+//! `OTEL_EXPORTER_OTLP_ENDPOINT` is set, exports spans over OTLP as well.
+//! `TraceContextLayer` continues the trace of an inbound request from its
+//! `traceparent` header, and `PropagateContext` adds the current one, with
+//! the request ID, to outbound calls, so a request is one trace across the
+//! services it touches.
+
+use crate::redact::Redacting;
+use crate::request_id;
+use http::{HeaderMap, Request, Response};
+use opentelemetry::propagation::{Extractor, Injector};
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::{KeyValue, global};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::runtime;
+use opentelemetry_sdk::trace::TracerProvider;
+use std::env;
+use std::fmt;
+use std::io;
+use std::task::{Context, Poll};
+use tonic::body::BoxBody;
+use tonic::metadata::{MetadataKey, MetadataMap, MetadataValue};
+use tonic::service::Interceptor;
+use tonic::service::interceptor::InterceptedService;
+use tonic::transport::Channel;
+use tonic::{Request as GrpcRequest, Status};
+use tower::{Layer, Service};
+use tracing::instrument::Instrumented;
+use tracing::level_filters::LevelFilter;
+use tracing::{Event, Instrument, Subscriber};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+
+/// Sets up logging and tracing for `service` at `version`, which spans are
+/// exported under. Call once, from within the runtime, before serving.
+///
+/// What gets logged follows `RUST_LOG` (e.g. `info,order=debug`), `info` by
+/// default.
+pub fn init(service: &str, version: &str) -> anyhow::Result<()> {
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
+    let otlp = match env::var("OTEL_EXPORTER_OTLP_ENDPOINT") {
+        Ok(endpoint) if !endpoint.is_empty() => {
+            let exporter = opentelemetry_otlp::SpanExporter::builder()
+                .with_tonic()
+                .with_endpoint(endpoint)
+                .build()?;
+            let provider = TracerProvider::builder()
+                .with_batch_exporter(exporter, runtime::Tokio)
+                .with_resource(Resource::new([
+                    KeyValue::new("service.name", service.to_string()),
+                    KeyValue::new("service.version", version.to_string()),
+                ]))
+                .build();
+            let tracer = provider.tracer(service.to_string());
+            global::set_tracer_provider(provider);
+            Some(tracing_opentelemetry::layer().with_tracer(tracer))
+        }
+        _ => None,
+    };
+
+    let json = env::var("LOG_FORMAT").is_ok_and(|format| format.eq_ignore_ascii_case("json"));
+    let (json, text) = if json {
+        let format = ServiceFields::new(
+            service,
+            version,
+            tracing_subscriber::fmt::format()
+                .json()
+                .with_current_span(true),
+        );
+        let layer = tracing_subscriber::fmt::layer()
+            .json()
+            .event_format(format)
+            .with_writer(Redacting::new(io::stdout));
+        (Some(layer), None)
+    } else {
+        let layer = tracing_subscriber::fmt::layer()
+            .with_target(false)
+            .with_thread_ids(true)
+            .with_line_number(true)
+            .with_writer(Redacting::new(io::stdout));
+        (None, Some(layer))
+    };
+
+    let filter = EnvFilter::builder()
+        .with_default_directive(LevelFilter::INFO.into())
+        .from_env_lossy();
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(json)
+        .with(text)
+        .with(otlp)
+        .try_init()?;
+
+    Ok(())
+}
+
+/// Adds the service's name and version to every JSON log line, so lines
+/// from all services can be told apart once aggregated.
+struct ServiceFields<F> {
+    /// `"service":…,"version":…,`, spliced in after the opening brace.
+    fields: String,
+    inner: F,
+}
+
+impl<F> ServiceFields<F> {
+    fn new(service: &str, version: &str, inner: F) -> Self {
+        let fields = format!(
+            "\"service\":{},\"version\":{},",
+            serde_json::Value::from(service),
+            serde_json::Value::from(version)
+        );
+        Self { fields, inner }
+    }
+}
+
+impl<S, N, F> FormatEvent<S, N> for ServiceFields<F>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+    F: FormatEvent<S, N>,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let mut line = String::new();
+        self.inner
+            .format_event(ctx, Writer::new(&mut line), event)?;
+        match line.strip_prefix('{') {
+            Some(rest) => write!(writer, "{{{}{}", self.fields, rest),
+            None => writer.write_str(&line),
+        }
+    }
+}
+
+/// Exports the spans still buffered; call before the process exits.
+pub fn shutdown() {
+    global::shutdown_tracer_provider();
+}
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|v| v.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|k| k.as_str()).collect()
+    }
+}
+
+struct MetadataInjector<'a>(&'a mut MetadataMap);
+
+impl Injector for MetadataInjector<'_> {
+    fn set(&mut self, key: &str, value: String) {
+        if let (Ok(key), Ok(value)) = (
+            MetadataKey::from_bytes(key.as_bytes()),
+            MetadataValue::try_from(value),
+        ) {
+            self.0.insert(key, value);
+        }
+    }
+}
+
+/// Runs each RPC in a span that continues the caller's trace when the
+/// request carries a `traceparent`, and starts a new one when it doesn't.
+#[derive(Clone, Default)]
+pub struct TraceContextLayer;
+
+impl<S> Layer<S> for TraceContextLayer {
+    type Service = TraceContextMiddleware<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        TraceContextMiddleware { inner: service }
+    }
+}
+
+#[derive(Clone)]
+pub struct TraceContextMiddleware<S> {
+    inner: S,
+}
+
+impl<S> Service<Request<BoxBody>> for TraceContextMiddleware<S>
+where
+    S: Service<Request<BoxBody>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Instrumented<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<BoxBody>) -> Self::Future {
+        let parent =
+            global::get_text_map_propagator(|p| p.extract(&HeaderExtractor(req.headers())));
+        let span = tracing::info_span!(
+            "grpc_request",
+            otel.name = %req.uri().path(),
+            otel.kind = "server",
+        );
+        span.set_parent(parent);
+
+        let _entered = span.enter();
+        self.inner.call(req).in_current_span()
+    }
+}
+
+/// Adds the current trace context and request ID to an outbound call's
+/// metadata.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PropagateContext;
+
+impl Interceptor for PropagateContext {
+    fn call(&mut self, mut request: GrpcRequest<()>) -> Result<GrpcRequest<()>, Status> {
+        let cx = tracing::Span::current().context();
+        let mut injector = MetadataInjector(request.metadata_mut());
+        global::get_text_map_propagator(|p| p.inject_context(&cx, &mut injector));
+        if let Some(id) = request_id::current() {
+            injector.set(request_id::HEADER, id);
+        }
+        Ok(request)
+    }
+}
+
+/// A channel whose calls carry the caller's trace context and request ID, e.g.
+/// `ProductServiceClient::with_interceptor(channel, PropagateContext)`.
+pub type TracedChannel = InterceptedService<Channel, PropagateContext>;
@@ -0,0 +1,65 @@
+//! TLS for service-to-service traffic. Every service serves with the
+//! certificate in `TLS_CERT_PATH`/`TLS_KEY_PATH`; with `TLS_CA_PATH` set as
+//! well, it only accepts clients presenting a certificate that CA signed,
+//! and its own calls to other services present its certificate and verify
+//! theirs against the same CA. Without a certificate configured services
+//! talk plaintext, as in local development.
+
+use anyhow::{Result, anyhow};
+use std::env;
+use std::fs;
+use tonic::transport::{Certificate, ClientTlsConfig, Identity, Server, ServerTlsConfig};
+
+/// Contents of the file named by `var`, if set.
+fn read_pem(var: &str) -> Result<Option<String>> {
+    match env::var(var) {
+        Ok(path) if !path.is_empty() => fs::read_to_string(&path)
+            .map(Some)
+            .map_err(|e| anyhow!("Failed to read {} ({}): {}", var, path, e)),
+        _ => Ok(None),
+    }
+}
+
+/// This service's certificate and key, if configured.
+fn identity() -> Result<Option<Identity>> {
+    match (read_pem("TLS_CERT_PATH")?, read_pem("TLS_KEY_PATH")?) {
+        (Some(cert), Some(key)) => Ok(Some(Identity::from_pem(cert, key))),
+        (None, None) => Ok(None),
+        _ => Err(anyhow!(
+            "TLS_CERT_PATH and TLS_KEY_PATH must be set together"
+        )),
+    }
+}
+
+fn ca() -> Result<Option<Certificate>> {
+    Ok(read_pem("TLS_CA_PATH")?.map(Certificate::from_pem))
+}
+
+/// A server builder that serves over TLS when a certificate is configured,
+/// requiring client certificates when a CA is too.
+pub fn server() -> Result<Server> {
+    let Some(identity) = identity()? else {
+        return Ok(Server::builder());
+    };
+
+    let mut config = ServerTlsConfig::new().identity(identity);
+    if let Some(ca) = ca()? {
+        config = config.client_ca_root(ca);
+    }
+    Ok(Server::builder().tls_config(config)?)
+}
+
+/// TLS for calls to other services, which present this service's
+/// certificate and verify the peer's against `TLS_CA_PATH`; `None` when no
+/// CA is configured.
+pub fn client_config() -> Result<Option<ClientTlsConfig>> {
+    let Some(ca) = ca()? else {
+        return Ok(None);
+    };
+
+    let mut config = ClientTlsConfig::new().ca_certificate(ca);
+    if let Some(identity) = identity()? {
+        config = config.identity(identity);
+    }
+    Ok(Some(config))
+}
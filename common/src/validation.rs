@@ -0,0 +1,84 @@
+use bytes::{Buf, Bytes};
+use http::{Request, Response};
+use http_body_util::{BodyExt, Full};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tonic::Status;
+use tonic::body::BoxBody;
+use tower::{Layer, Service};
+use tracing::debug;
+
+/// Length of the gRPC message prefix: a compression flag and a 4-byte length.
+const FRAME_HEADER_LEN: usize = 5;
+
+/// Checks request messages against the (validate.*) rules declared on their
+/// proto fields before the handler runs. Requests breaking a rule are answered
+/// with INVALID_ARGUMENT; methods without rules pass through untouched.
+#[derive(Clone, Default)]
+pub struct ValidationLayer;
+
+impl<S> Layer<S> for ValidationLayer {
+    type Service = ValidationService<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        ValidationService { inner: service }
+    }
+}
+
+#[derive(Clone)]
+pub struct ValidationService<S> {
+    inner: S,
+}
+
+/// Returns the message of a single uncompressed gRPC frame, or `None` when the
+/// body can't be checked here (compressed or malformed frames are left to tonic).
+fn frame_message(body: &Bytes) -> Option<&[u8]> {
+    if body.len() < FRAME_HEADER_LEN || body[0] != 0 {
+        return None;
+    }
+    let len = (&body[1..FRAME_HEADER_LEN]).get_u32() as usize;
+    body.get(FRAME_HEADER_LEN..FRAME_HEADER_LEN + len)
+}
+
+impl<S> Service<Request<BoxBody>> for ValidationService<S>
+where
+    S: Service<Request<BoxBody>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<BoxBody>) -> Self::Future {
+        let path = req.uri().path().to_owned();
+        let mut inner = self.inner.clone();
+
+        // Only methods with rules pay for buffering the request
+        if !proto::validation::has_rules(&path) {
+            return Box::pin(inner.call(req));
+        }
+
+        Box::pin(async move {
+            let (parts, body) = req.into_parts();
+            let body = match body.collect().await {
+                Ok(collected) => collected.to_bytes(),
+                Err(status) => return Ok(status.into_http()),
+            };
+
+            if let Some(message) = frame_message(&body)
+                && let Some(Err(violation)) = proto::validation::validate_request(&path, message)
+            {
+                debug!(path = %path, violation = %violation, "Request failed validation");
+                return Ok(Status::invalid_argument(violation).into_http());
+            }
+
+            let req = Request::from_parts(parts, tonic::body::boxed(Full::new(body)));
+            inner.call(req).await
+        })
+    }
+}
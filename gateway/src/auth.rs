@@ -0,0 +1,54 @@
+//! Bearer tokens. Endpoints acting for a customer take `Bearer`, which
+//! rejects requests without a valid token before any service is called,
+//! then forwards the token so the services scope the call to its holder.
+//! Calls without one reach the services as anonymous, which only public
+//! reads and sign-up accept.
+
+use crate::error::ApiError;
+use axum::async_trait;
+use axum::extract::FromRequestParts;
+use axum::http::header::AUTHORIZATION;
+use axum::http::request::Parts;
+use common::auth::{self, Claims};
+use tonic::metadata::{Ascii, MetadataValue};
+
+pub struct Bearer {
+    pub claims: Claims,
+    header: MetadataValue<Ascii>,
+}
+
+impl Bearer {
+    /// A request of `message` carrying the token.
+    pub fn request<T>(&self, message: T) -> tonic::Request<T> {
+        let mut request = tonic::Request::new(message);
+        request
+            .metadata_mut()
+            .insert("authorization", self.header.clone());
+        request
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Bearer {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let header = parts
+            .headers
+            .get(AUTHORIZATION)
+            .ok_or_else(|| ApiError::unauthenticated("Sign in first"))?
+            .to_str()
+            .map_err(|_| ApiError::unauthenticated("Malformed authorization header"))?;
+        let token = header
+            .strip_prefix("Bearer ")
+            .ok_or_else(|| ApiError::unauthenticated("Malformed authorization header"))?;
+        let claims = auth::decode_token(token)
+            .map_err(|_| ApiError::unauthenticated("Invalid or expired token"))?;
+
+        Ok(Self {
+            claims,
+            header: MetadataValue::try_from(header)
+                .map_err(|_| ApiError::unauthenticated("Malformed authorization header"))?,
+        })
+    }
+}
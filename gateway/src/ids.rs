@@ -0,0 +1,59 @@
+//! Ids as customers see them. With `PUBLIC_ID_SALT` set, the ids of orders,
+//! products and users go out as public tokens, `o_`, `p_` and `u_` followed
+//! by the id's `PublicIdCodec` encoding, so URLs and responses don't give
+//! away internal ids or how many orders there are. `PublicIdLayer` decodes
+//! tokens in request paths, and handlers the ones in request bodies.
+//! Unset, ids go out as they are.
+
+use anyhow::Result;
+use common::public_id::{PublicIdCodec, PublicIdLayer};
+use std::env;
+use std::sync::Arc;
+
+pub const ORDER: &str = "o_";
+pub const PRODUCT: &str = "p_";
+pub const USER: &str = "u_";
+
+#[derive(Debug, Clone, Default)]
+pub struct PublicIds {
+    codec: Option<Arc<PublicIdCodec>>,
+}
+
+impl PublicIds {
+    pub fn from_env() -> Result<Self> {
+        if env::var("PUBLIC_ID_SALT").unwrap_or_default().is_empty() {
+            return Ok(Self::default());
+        }
+        Ok(Self {
+            codec: Some(Arc::new(PublicIdCodec::from_env()?)),
+        })
+    }
+
+    /// Decodes the order and product tokens in request paths; passes every
+    /// path through as it is when ids aren't encoded.
+    pub fn layer(&self) -> PublicIdLayer {
+        match &self.codec {
+            Some(codec) => PublicIdLayer::new(codec.clone())
+                .with_prefix(ORDER)
+                .with_prefix(PRODUCT),
+            None => PublicIdLayer::new(Arc::new(PublicIdCodec::new(""))),
+        }
+    }
+
+    /// Public form of the id of an entity of kind `prefix`.
+    pub fn encode(&self, prefix: &str, id: &str) -> String {
+        match self.codec.as_ref().and_then(|codec| codec.encode_uuid(id)) {
+            Some(token) => format!("{}{}", prefix, token),
+            None => id.to_string(),
+        }
+    }
+
+    /// Internal id of a public one from a request body, `None` when it's a
+    /// token that doesn't decode. Internal ids are accepted as well.
+    pub fn decode(&self, prefix: &str, id: &str) -> Option<String> {
+        match (&self.codec, id.strip_prefix(prefix)) {
+            (Some(codec), Some(token)) => codec.decode_uuid(token),
+            _ => Some(id.to_string()),
+        }
+    }
+}
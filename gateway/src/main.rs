@@ -0,0 +1,96 @@
+mod auth;
+mod error;
+mod ids;
+mod orders;
+mod products;
+mod routes;
+mod users;
+
+use anyhow::Result;
+use axum::ServiceExt;
+use axum::extract::Request;
+use axum::http::header::{AUTHORIZATION, CONTENT_TYPE};
+use axum::http::{HeaderName, HeaderValue, Method};
+use common::client::ServiceEndpoint;
+use common::idempotency;
+use common::resilience::CircuitBreakerConfig;
+use common::telemetry;
+use common::tls;
+use ids::PublicIds;
+use routes::{AppState, Upstream};
+use std::env;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tower::Layer;
+use tower_http::cors::CorsLayer;
+use tracing::info;
+
+/// Lets browsers call the gateway from the origins in
+/// `GATEWAY_ALLOWED_ORIGINS` (comma-separated); `None` when it's unset, so
+/// only pages the gateway serves itself can.
+fn cors_from_env() -> Result<Option<CorsLayer>> {
+    let origins = env::var("GATEWAY_ALLOWED_ORIGINS")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|origin| !origin.is_empty())
+        .map(HeaderValue::from_str)
+        .collect::<Result<Vec<_>, _>>()?;
+    if origins.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(
+        CorsLayer::new()
+            .allow_origin(origins)
+            .allow_methods([Method::GET, Method::POST])
+            .allow_headers([
+                AUTHORIZATION,
+                CONTENT_TYPE,
+                HeaderName::from_static(idempotency::HEADER),
+            ]),
+    ))
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenvy::dotenv().ok();
+
+    telemetry::init("gateway", env!("CARGO_PKG_VERSION"))?;
+
+    let internal_tls = tls::client_config()?;
+    let circuit_breaker = CircuitBreakerConfig::from_env();
+    let upstream = Upstream {
+        user: ServiceEndpoint::from_env("user", "USER_SERVICE", "http://127.0.0.1:50051")
+            .with_tls(internal_tls.clone())
+            .with_circuit_breaker(circuit_breaker),
+        product: ServiceEndpoint::from_env("product", "PRODUCT_SERVICE", "http://127.0.0.1:50052")
+            .with_tls(internal_tls.clone())
+            .with_circuit_breaker(circuit_breaker),
+        order: ServiceEndpoint::from_env("order", "ORDER_SERVICE", "http://127.0.0.1:50053")
+            .with_tls(internal_tls)
+            .with_circuit_breaker(circuit_breaker),
+    };
+    let ids = PublicIds::from_env()?;
+
+    let mut app = routes::router(AppState {
+        upstream: Arc::new(upstream),
+        ids: ids.clone(),
+    });
+    if let Some(cors) = cors_from_env()? {
+        app = app.layer(cors);
+    }
+    // Tokens in paths are decoded before the request is routed, so the
+    // layer wraps the router rather than its routes
+    let app = ids.layer().layer(app);
+
+    let addr: SocketAddr = env::var("GATEWAY_ADDR")
+        .unwrap_or_else(|_| "0.0.0.0:8080".to_string())
+        .parse()?;
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    info!("Gateway listening on {}", addr);
+    axum::serve(listener, ServiceExt::<Request>::into_make_service(app)).await?;
+
+    telemetry::shutdown();
+    Ok(())
+}
@@ -0,0 +1,216 @@
+use crate::auth::Bearer;
+use crate::error::{ApiError, ErrorBody};
+use crate::ids::{self, PublicIds};
+use crate::routes::{AppState, channel};
+use axum::Json;
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use common::idempotency;
+use common::telemetry::PropagateContext;
+use proto::order::{
+    CreateOrderRequest, GetOrderRequest, Order, OrderItem, OrderItemStatus, OrderStatus,
+    order_service_client::OrderServiceClient,
+};
+use serde::{Deserialize, Serialize};
+use tonic::metadata::MetadataValue;
+use utoipa::ToSchema;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateOrderBody {
+    pub items: Vec<OrderItemBody>,
+    /// Required unless every item is a digital product
+    #[serde(default)]
+    pub shipping_address: String,
+    /// ISO 3166 code of the shipping address, e.g. US-CA; selects the tax
+    #[serde(default)]
+    pub shipping_region: String,
+    /// One of the methods offered for the items and region
+    #[serde(default)]
+    pub shipping_method: String,
+    /// One of the store's accepted currencies; the default one when empty
+    #[serde(default)]
+    pub currency: String,
+    /// ISO 3166 code of the billing address
+    #[serde(default)]
+    pub billing_region: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct OrderItemBody {
+    pub product_id: String,
+    pub quantity: i32,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OrderJson {
+    pub id: String,
+    /// Human-friendly number, e.g. ORD-2024-000123
+    pub order_number: String,
+    /// PENDING, CONFIRMED, PROCESSING, SHIPPED, PARTIALLY_SHIPPED,
+    /// DELIVERED, CANCELLED or ON_HOLD
+    pub status: String,
+    pub items: Vec<OrderItemJson>,
+    /// Decimal strings in `currency`; the total includes tax and shipping
+    pub total_amount: String,
+    pub tax_amount: String,
+    pub shipping_fee: String,
+    pub currency: String,
+    /// The total with its currency, e.g. €12.50
+    pub formatted_total: String,
+    pub shipping_address: String,
+    /// Delivery window, YYYY-MM-DD inclusive, when the order ships
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimated_delivery_from: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimated_delivery_to: Option<String>,
+    /// Unix seconds
+    pub created_at: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OrderItemJson {
+    pub product_id: String,
+    pub product_name: String,
+    pub quantity: i32,
+    pub unit_price: String,
+    pub subtotal: String,
+    /// Filled on restock rather than from stock
+    pub backordered: bool,
+}
+
+impl OrderJson {
+    fn from_proto(order: Order, ids: &PublicIds) -> Self {
+        Self {
+            id: ids.encode(ids::ORDER, &order.order_id),
+            order_number: order.order_number,
+            status: OrderStatus::try_from(order.status)
+                .map(|s| s.as_str_name().to_string())
+                .unwrap_or_default(),
+            items: order
+                .items
+                .into_iter()
+                .map(|item| OrderItemJson {
+                    product_id: ids.encode(ids::PRODUCT, &item.product_id),
+                    product_name: item.product_name,
+                    quantity: item.quantity,
+                    unit_price: item.unit_price,
+                    subtotal: item.subtotal,
+                    backordered: item.status == OrderItemStatus::Backordered as i32,
+                })
+                .collect(),
+            total_amount: order.total_amount,
+            tax_amount: order.tax_amount,
+            shipping_fee: order.shipping_fee,
+            currency: order.currency,
+            formatted_total: order.formatted_total,
+            shipping_address: order.shipping_address,
+            estimated_delivery_from: Some(order.estimated_delivery_from).filter(|d| !d.is_empty()),
+            estimated_delivery_to: Some(order.estimated_delivery_to).filter(|d| !d.is_empty()),
+            created_at: order.created_at,
+        }
+    }
+}
+
+/// Places an order for the signed-in customer. A retry carrying the same
+/// `Idempotency-Key` header gets the first attempt's order rather than a
+/// second one.
+#[utoipa::path(
+    post,
+    path = "/v1/orders",
+    tag = "orders",
+    request_body = CreateOrderBody,
+    params(("Idempotency-Key" = Option<String>, Header, description = "Makes retries safe")),
+    security(("bearer" = [])),
+    responses(
+        (status = 201, body = OrderJson),
+        (status = 400, description = "Invalid order, or out of stock", body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    )
+)]
+pub async fn create_order(
+    State(state): State<AppState>,
+    bearer: Bearer,
+    headers: HeaderMap,
+    Json(body): Json<CreateOrderBody>,
+) -> Result<(StatusCode, Json<OrderJson>), ApiError> {
+    let mut items = Vec::with_capacity(body.items.len());
+    for item in body.items {
+        let product_id = state
+            .ids
+            .decode(ids::PRODUCT, &item.product_id)
+            .ok_or_else(|| {
+                ApiError::invalid_argument(format!("Unknown product: {}", item.product_id))
+            })?;
+        items.push(OrderItem {
+            product_id,
+            quantity: item.quantity,
+            ..Default::default()
+        });
+    }
+
+    // The order is the token holder's, whatever the body says
+    let mut request = bearer.request(CreateOrderRequest {
+        user_id: bearer.claims.sub.clone(),
+        items,
+        shipping_address: body.shipping_address,
+        shipping_region: body.shipping_region,
+        shipping_method: body.shipping_method,
+        currency: body.currency,
+        billing_region: body.billing_region,
+        guest_email: String::new(),
+    });
+    if let Some(key) = headers.get(idempotency::HEADER) {
+        let key = key
+            .to_str()
+            .ok()
+            .and_then(|key| MetadataValue::try_from(key).ok())
+            .ok_or_else(|| ApiError::invalid_argument("Malformed Idempotency-Key header"))?;
+        request.metadata_mut().insert(idempotency::HEADER, key);
+    }
+
+    let order =
+        OrderServiceClient::with_interceptor(channel(&state.upstream.order)?, PropagateContext)
+            .create_order(request)
+            .await?
+            .into_inner()
+            .order
+            .unwrap_or_default();
+
+    Ok((
+        StatusCode::CREATED,
+        Json(OrderJson::from_proto(order, &state.ids)),
+    ))
+}
+
+/// Returns one of the signed-in customer's orders.
+#[utoipa::path(
+    get,
+    path = "/v1/orders/{order_id}",
+    tag = "orders",
+    params(("order_id" = String, Path)),
+    security(("bearer" = [])),
+    responses(
+        (status = 200, body = OrderJson),
+        (status = 401, body = ErrorBody),
+        (status = 403, description = "Someone else's order", body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    )
+)]
+pub async fn get_order(
+    State(state): State<AppState>,
+    bearer: Bearer,
+    Path(order_id): Path<String>,
+) -> Result<Json<OrderJson>, ApiError> {
+    let order =
+        OrderServiceClient::with_interceptor(channel(&state.upstream.order)?, PropagateContext)
+            .get_order(bearer.request(GetOrderRequest {
+                order_id,
+                order_number: String::new(),
+            }))
+            .await?
+            .into_inner()
+            .order
+            .unwrap_or_default();
+
+    Ok(Json(OrderJson::from_proto(order, &state.ids)))
+}
@@ -0,0 +1,185 @@
+use crate::error::{ApiError, ErrorBody};
+use crate::ids::{self, PublicIds};
+use crate::routes::{AppState, channel};
+use axum::Json;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use common::telemetry::PropagateContext;
+use proto::product::{
+    GetProductRequest, ListProductsRequest, Product, ProductSort, ProductStatus,
+    product_service_client::ProductServiceClient,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListProductsQuery {
+    /// 1-based; ignored when page_token is set
+    pub page: Option<i32>,
+    pub page_size: Option<i32>,
+    /// next_page_token of the previous page
+    pub page_token: Option<String>,
+    pub category: Option<String>,
+    /// Comma-separated; only products carrying every one are listed
+    pub tags: Option<String>,
+    /// newest, best_selling, price_asc or price_desc; the category's
+    /// default order when unset
+    pub sort: Option<String>,
+    /// Decimal, inclusive
+    pub min_price: Option<String>,
+    /// Decimal, inclusive
+    pub max_price: Option<String>,
+    pub in_stock: Option<bool>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProductJson {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    /// Decimal string: what it sells for right now, sales included
+    pub price: String,
+    pub regular_price: String,
+    /// Set while a sale runs or is scheduled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sale_price: Option<String>,
+    pub category: String,
+    pub tags: Vec<String>,
+    pub stock_quantity: i32,
+    /// Out of stock: offer a notify-me button instead of add to cart
+    pub notify_me: bool,
+    pub rating_average: f64,
+    pub rating_count: i32,
+    /// Unix seconds
+    pub created_at: i64,
+}
+
+impl ProductJson {
+    fn from_proto(product: Product, ids: &PublicIds) -> Self {
+        Self {
+            id: ids.encode(ids::PRODUCT, &product.product_id),
+            name: product.name,
+            description: product.description,
+            price: product.price,
+            regular_price: product.regular_price,
+            sale_price: Some(product.sale_price).filter(|p| !p.is_empty()),
+            category: product.category,
+            tags: product.tags,
+            stock_quantity: product.stock_quantity,
+            notify_me: product.notify_me,
+            rating_average: product.rating_average,
+            rating_count: product.rating_count,
+            created_at: product.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProductListJson {
+    pub products: Vec<ProductJson>,
+    pub total_count: i32,
+    /// Absent on the last page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_page_token: Option<String>,
+}
+
+/// Lists the published products, a page at a time.
+#[utoipa::path(
+    get,
+    path = "/v1/products",
+    tag = "products",
+    params(ListProductsQuery),
+    responses(
+        (status = 200, body = ProductListJson),
+        (status = 400, body = ErrorBody),
+    )
+)]
+pub async fn list_products(
+    State(state): State<AppState>,
+    Query(query): Query<ListProductsQuery>,
+) -> Result<Json<ProductListJson>, ApiError> {
+    let sort = match query.sort.as_deref().unwrap_or_default() {
+        "" => ProductSort::DefaultSort,
+        "newest" => ProductSort::Newest,
+        "best_selling" => ProductSort::BestSelling,
+        "price_asc" => ProductSort::PriceLowToHigh,
+        "price_desc" => ProductSort::PriceHighToLow,
+        other => {
+            return Err(ApiError::invalid_argument(format!(
+                "Unknown sort: {}",
+                other
+            )));
+        }
+    };
+    let tags = query
+        .tags
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    let response =
+        ProductServiceClient::with_interceptor(channel(&state.upstream.product)?, PropagateContext)
+            .list_products(ListProductsRequest {
+                page: query.page.unwrap_or(1),
+                page_size: query.page_size.unwrap_or_default(),
+                category: query.category.unwrap_or_default(),
+                attribute_filters: Vec::new(),
+                sort: sort as i32,
+                tags,
+                page_token: query.page_token.unwrap_or_default(),
+                min_price: query.min_price.unwrap_or_default(),
+                max_price: query.max_price.unwrap_or_default(),
+                in_stock_only: query.in_stock.unwrap_or_default(),
+            })
+            .await?
+            .into_inner();
+
+    Ok(Json(ProductListJson {
+        products: response
+            .products
+            .into_iter()
+            .map(|product| ProductJson::from_proto(product, &state.ids))
+            .collect(),
+        total_count: response.total_count,
+        next_page_token: Some(response.next_page_token).filter(|t| !t.is_empty()),
+    }))
+}
+
+/// Returns a published product.
+#[utoipa::path(
+    get,
+    path = "/v1/products/{product_id}",
+    tag = "products",
+    params(("product_id" = String, Path)),
+    responses(
+        (status = 200, body = ProductJson),
+        (status = 404, body = ErrorBody),
+    )
+)]
+pub async fn get_product(
+    State(state): State<AppState>,
+    Path(product_id): Path<String>,
+) -> Result<Json<ProductJson>, ApiError> {
+    let product =
+        ProductServiceClient::with_interceptor(channel(&state.upstream.product)?, PropagateContext)
+            .get_product(GetProductRequest { product_id })
+            .await?
+            .into_inner()
+            .product
+            .unwrap_or_default();
+
+    // Drafts and archived products aren't the public's to see
+    if product.status != ProductStatus::Published as i32 {
+        return Err(ApiError::new(
+            StatusCode::NOT_FOUND,
+            "NOT_FOUND",
+            "Product not found",
+        ));
+    }
+    Ok(Json(ProductJson::from_proto(product, &state.ids)))
+}
@@ -0,0 +1,90 @@
+use crate::error::{ApiError, ErrorBody};
+use crate::ids::PublicIds;
+use crate::{orders, products, users};
+use axum::Json;
+use axum::Router;
+use axum::routing::{get, post};
+use common::client::ServiceEndpoint;
+use common::resilience::CircuitBreaker;
+use std::sync::Arc;
+use tonic::transport::Channel;
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+
+/// The services the gateway translates to.
+pub struct Upstream {
+    pub user: ServiceEndpoint,
+    pub product: ServiceEndpoint,
+    pub order: ServiceEndpoint,
+}
+
+#[derive(Clone)]
+pub struct AppState {
+    pub upstream: Arc<Upstream>,
+    pub ids: PublicIds,
+}
+
+/// Channel to `endpoint`, behind its circuit breaker.
+pub fn channel(endpoint: &ServiceEndpoint) -> Result<CircuitBreaker<Channel>, ApiError> {
+    let channel = endpoint.primary_channel().map_err(|e| {
+        ApiError::unavailable(format!("Invalid {} service URL: {}", endpoint.name(), e))
+    })?;
+    Ok(endpoint.guard(channel))
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "e-commerce-rs",
+        description = "REST API of the shop. Endpoints acting for a customer take the \
+                       token of POST /v1/users/login as `Authorization: Bearer <token>`."
+    ),
+    paths(
+        users::login,
+        products::list_products,
+        products::get_product,
+        orders::create_order,
+        orders::get_order,
+    ),
+    components(schemas(ErrorBody)),
+    modifiers(&BearerAuth),
+    tags(
+        (name = "users", description = "Accounts"),
+        (name = "products", description = "The catalog"),
+        (name = "orders", description = "A customer's orders"),
+    )
+)]
+pub struct ApiDoc;
+
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "bearer",
+                SecurityScheme::Http(
+                    HttpBuilder::new()
+                        .scheme(HttpAuthScheme::Bearer)
+                        .bearer_format("JWT")
+                        .build(),
+                ),
+            );
+        }
+    }
+}
+
+async fn openapi() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+pub fn router(state: AppState) -> Router {
+    Router::new()
+        .route("/v1/users/login", post(users::login))
+        .route("/v1/products", get(products::list_products))
+        .route("/v1/products/:product_id", get(products::get_product))
+        .route("/v1/orders", post(orders::create_order))
+        .route("/v1/orders/:order_id", get(orders::get_order))
+        .route("/openapi.json", get(openapi))
+        .with_state(state)
+}
@@ -0,0 +1,75 @@
+use crate::error::{ApiError, ErrorBody};
+use crate::ids::{self, PublicIds};
+use crate::routes::{AppState, channel};
+use axum::Json;
+use axum::extract::State;
+use common::telemetry::PropagateContext;
+use proto::user::{LoginRequest, User, user_service_client::UserServiceClient};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct LoginBody {
+    /// Username or email address of the account
+    pub identifier: String,
+    pub password: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LoginReply {
+    /// Bearer token for the endpoints acting for the customer
+    pub token: String,
+    pub user: UserJson,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UserJson {
+    pub id: String,
+    pub username: String,
+    pub email: String,
+    /// Unix seconds
+    pub created_at: i64,
+}
+
+impl UserJson {
+    fn from_proto(user: User, ids: &PublicIds) -> Self {
+        Self {
+            id: ids.encode(ids::USER, &user.user_id),
+            username: user.username,
+            email: user.email,
+            created_at: user.created_at,
+        }
+    }
+}
+
+/// Signs a customer in.
+#[utoipa::path(
+    post,
+    path = "/v1/users/login",
+    tag = "users",
+    request_body = LoginBody,
+    responses(
+        (status = 200, body = LoginReply),
+        (status = 401, description = "Unknown account or wrong password", body = ErrorBody),
+    )
+)]
+pub async fn login(
+    State(state): State<AppState>,
+    Json(body): Json<LoginBody>,
+) -> Result<Json<LoginReply>, ApiError> {
+    let mut client =
+        UserServiceClient::with_interceptor(channel(&state.upstream.user)?, PropagateContext);
+
+    let response = client
+        .login(LoginRequest {
+            identifier: body.identifier,
+            password: body.password,
+        })
+        .await?
+        .into_inner();
+
+    Ok(Json(LoginReply {
+        token: response.token,
+        user: UserJson::from_proto(response.user.unwrap_or_default(), &state.ids),
+    }))
+}
@@ -0,0 +1,20 @@
+-- Operational switches flipped during incidents
+CREATE TABLE IF NOT EXISTS ops_switches (
+    name VARCHAR(100) PRIMARY KEY,
+    active BOOLEAN NOT NULL DEFAULT FALSE,
+    reason TEXT,
+    updated_by VARCHAR(255),
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Audit trail of every operational action
+CREATE TABLE IF NOT EXISTS ops_audit_log (
+    id VARCHAR(36) PRIMARY KEY,
+    action VARCHAR(100) NOT NULL,
+    target VARCHAR(255) NOT NULL,
+    actor VARCHAR(255) NOT NULL,
+    reason TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_ops_audit_log_created_at ON ops_audit_log(created_at);
@@ -0,0 +1,11 @@
+-- Key-value product specifications, e.g. "RAM: 16GB"
+CREATE TABLE IF NOT EXISTS product_attributes (
+    product_id VARCHAR(36) NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    value VARCHAR(255) NOT NULL,
+    PRIMARY KEY (product_id, name),
+    FOREIGN KEY (product_id) REFERENCES products(id) ON DELETE CASCADE
+);
+
+-- Faceted browsing filters by name/value pairs
+CREATE INDEX idx_product_attributes_name_value ON product_attributes(name, value);
@@ -0,0 +1,15 @@
+-- Expected incoming stock from purchase orders, used for availability timelines
+CREATE TABLE IF NOT EXISTS product_restocks (
+    id VARCHAR(36) PRIMARY KEY,
+    product_id VARCHAR(36) NOT NULL,
+    quantity INTEGER NOT NULL CHECK (quantity > 0),
+    expected_date DATE NOT NULL,
+    reference VARCHAR(100),
+    received_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (product_id) REFERENCES products(id) ON DELETE CASCADE
+);
+
+-- Timelines only look at restocks that haven't arrived yet
+CREATE INDEX idx_product_restocks_pending ON product_restocks(product_id, expected_date)
+    WHERE received_at IS NULL;
@@ -0,0 +1,22 @@
+-- Serial and lot numbers recorded per order item at fulfillment time
+CREATE TABLE IF NOT EXISTS order_item_tracking (
+    id VARCHAR(36) PRIMARY KEY,
+    order_item_id VARCHAR(36) NOT NULL,
+    product_id VARCHAR(36) NOT NULL,
+    serial_number VARCHAR(100),
+    lot_number VARCHAR(100),
+    quantity INT NOT NULL CHECK (quantity > 0),
+    recorded_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK (serial_number IS NOT NULL OR lot_number IS NOT NULL),
+    CHECK (serial_number IS NULL OR quantity = 1),
+    FOREIGN KEY (order_item_id) REFERENCES order_items(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_order_item_tracking_item ON order_item_tracking(order_item_id);
+
+-- Recall lookups
+CREATE INDEX idx_order_item_tracking_lot ON order_item_tracking(lot_number, product_id);
+
+-- A serial number identifies exactly one unit of a product
+CREATE UNIQUE INDEX idx_order_item_tracking_serial ON order_item_tracking(product_id, serial_number)
+    WHERE serial_number IS NOT NULL;
@@ -0,0 +1,31 @@
+-- Quarantined products (lot_number NULL) or individual lots
+CREATE TABLE IF NOT EXISTS product_quarantines (
+    id VARCHAR(36) PRIMARY KEY,
+    product_id VARCHAR(36) NOT NULL,
+    lot_number VARCHAR(100),
+    reason TEXT NOT NULL,
+    created_by VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    released_by VARCHAR(255),
+    released_at TIMESTAMP,
+    FOREIGN KEY (product_id) REFERENCES products(id) ON DELETE CASCADE
+);
+
+-- At most one active quarantine per product or lot
+CREATE UNIQUE INDEX idx_product_quarantines_active
+    ON product_quarantines(product_id, COALESCE(lot_number, ''))
+    WHERE released_at IS NULL;
+
+-- Customer notification batches sent for a quarantine
+CREATE TABLE IF NOT EXISTS recalls (
+    id VARCHAR(36) PRIMARY KEY,
+    quarantine_id VARCHAR(36) NOT NULL,
+    title VARCHAR(255) NOT NULL,
+    body TEXT NOT NULL,
+    created_by VARCHAR(255) NOT NULL,
+    affected_orders INT NOT NULL,
+    notified_users INT NOT NULL,
+    failed_users INT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (quarantine_id) REFERENCES product_quarantines(id) ON DELETE CASCADE
+);
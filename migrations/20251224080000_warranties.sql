@@ -0,0 +1,37 @@
+-- Warranty length offered with a product, 0 for none
+ALTER TABLE products ADD COLUMN IF NOT EXISTS warranty_months INT NOT NULL DEFAULT 0;
+
+-- One warranty per order item; starts_at is set when the order is delivered
+CREATE TABLE IF NOT EXISTS warranties (
+    id VARCHAR(36) PRIMARY KEY,
+    order_id VARCHAR(36) NOT NULL,
+    order_item_id VARCHAR(36) NOT NULL UNIQUE,
+    user_id VARCHAR(36) NOT NULL,
+    product_id VARCHAR(36) NOT NULL,
+    warranty_months INT NOT NULL CHECK (warranty_months > 0),
+    serial_number VARCHAR(100),
+    registered_at TIMESTAMP,
+    starts_at TIMESTAMP,
+    expires_at TIMESTAMP,
+    reminder_sent_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (order_item_id) REFERENCES order_items(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_warranties_user_id ON warranties(user_id);
+CREATE INDEX idx_warranties_expiry_reminder ON warranties(expires_at) WHERE reminder_sent_at IS NULL;
+
+CREATE TABLE IF NOT EXISTS warranty_claims (
+    id VARCHAR(36) PRIMARY KEY,
+    warranty_id VARCHAR(36) NOT NULL,
+    description TEXT NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'OPEN',
+    resolution TEXT,
+    resolved_by VARCHAR(255),
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    resolved_at TIMESTAMP,
+    FOREIGN KEY (warranty_id) REFERENCES warranties(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_warranty_claims_warranty_id ON warranty_claims(warranty_id);
+CREATE INDEX idx_warranty_claims_status ON warranty_claims(status);
@@ -0,0 +1,12 @@
+-- Human-friendly order numbers, formatted by the order service from this sequence
+CREATE SEQUENCE IF NOT EXISTS order_number_seq;
+
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS order_number VARCHAR(32);
+
+UPDATE orders
+SET order_number = 'ORD-' || EXTRACT(YEAR FROM created_at)::INT || '-' || LPAD(nextval('order_number_seq')::TEXT, 6, '0')
+WHERE order_number IS NULL;
+
+ALTER TABLE orders ALTER COLUMN order_number SET NOT NULL;
+
+CREATE UNIQUE INDEX idx_orders_order_number ON orders(order_number);
@@ -0,0 +1,5 @@
+-- Store order timestamps as absolute instants so reports can bucket them in any time zone.
+-- Existing values were written in UTC.
+ALTER TABLE orders
+    ALTER COLUMN created_at TYPE TIMESTAMPTZ USING created_at AT TIME ZONE 'UTC',
+    ALTER COLUMN updated_at TYPE TIMESTAMPTZ USING updated_at AT TIME ZONE 'UTC';
@@ -0,0 +1,4 @@
+-- Deleted products stay in place for the order items that reference them
+ALTER TABLE products ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMP;
+
+CREATE INDEX idx_products_live_created_at ON products(created_at) WHERE deleted_at IS NULL;
@@ -0,0 +1,19 @@
+-- Aggregated rating kept on the product so listings don't scan reviews
+ALTER TABLE products ADD COLUMN IF NOT EXISTS rating_average DECIMAL(3, 2) NOT NULL DEFAULT 0;
+ALTER TABLE products ADD COLUMN IF NOT EXISTS rating_count INT NOT NULL DEFAULT 0;
+
+-- One review per customer and product, backed by a delivered order
+CREATE TABLE IF NOT EXISTS product_reviews (
+    id VARCHAR(36) PRIMARY KEY,
+    product_id VARCHAR(36) NOT NULL,
+    user_id VARCHAR(36) NOT NULL,
+    order_id VARCHAR(36) NOT NULL,
+    rating INT NOT NULL CHECK (rating BETWEEN 1 AND 5),
+    title VARCHAR(255),
+    body TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (product_id) REFERENCES products(id),
+    UNIQUE (product_id, user_id)
+);
+
+CREATE INDEX idx_product_reviews_product_created_at ON product_reviews(product_id, created_at);
@@ -0,0 +1,12 @@
+-- How often two products were bought in the same order, refreshed
+-- periodically by the product service for "customers also bought"
+CREATE MATERIALIZED VIEW IF NOT EXISTS product_co_purchases AS
+SELECT a.product_id, b.product_id AS related_product_id, COUNT(DISTINCT a.order_id)::INT AS order_count
+FROM order_items a
+JOIN order_items b ON b.order_id = a.order_id AND b.product_id <> a.product_id
+JOIN orders o ON o.id = a.order_id
+WHERE o.status <> 'CANCELLED'
+GROUP BY a.product_id, b.product_id;
+
+-- Unique so the view can be refreshed concurrently
+CREATE UNIQUE INDEX idx_product_co_purchases_pair ON product_co_purchases(product_id, related_product_id);
@@ -0,0 +1,21 @@
+-- The product code has always filtered on category; make sure the column exists
+ALTER TABLE products ADD COLUMN IF NOT EXISTS category VARCHAR(100);
+CREATE INDEX IF NOT EXISTS idx_products_category ON products(category) WHERE deleted_at IS NULL;
+
+-- Sort applied to a category listing when the shopper picks none
+CREATE TABLE IF NOT EXISTS category_settings (
+    category VARCHAR(100) PRIMARY KEY,
+    default_sort VARCHAR(20) NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Products pinned to fixed 1-based positions within a category listing
+CREATE TABLE IF NOT EXISTS category_pins (
+    category VARCHAR(100) NOT NULL,
+    product_id VARCHAR(36) NOT NULL,
+    position INT NOT NULL CHECK (position > 0),
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (category, product_id),
+    UNIQUE (category, position),
+    FOREIGN KEY (product_id) REFERENCES products(id) ON DELETE CASCADE
+);
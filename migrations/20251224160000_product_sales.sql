@@ -0,0 +1,4 @@
+-- Scheduled sale price; applies between the two instants, either of which may be open
+ALTER TABLE products ADD COLUMN IF NOT EXISTS sale_price DECIMAL(10, 2);
+ALTER TABLE products ADD COLUMN IF NOT EXISTS sale_starts_at TIMESTAMPTZ;
+ALTER TABLE products ADD COLUMN IF NOT EXISTS sale_ends_at TIMESTAMPTZ;
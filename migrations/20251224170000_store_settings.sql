@@ -0,0 +1,20 @@
+-- Store-wide settings overriding the defaults in common::settings; one row per field
+CREATE TABLE IF NOT EXISTS store_settings (
+    field VARCHAR(100) PRIMARY KEY,
+    value TEXT NOT NULL,
+    updated_by VARCHAR(255) NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Every settings change with the value it replaced
+CREATE TABLE IF NOT EXISTS store_settings_audit (
+    id BIGSERIAL PRIMARY KEY,
+    field VARCHAR(100) NOT NULL,
+    old_value TEXT NOT NULL,
+    new_value TEXT NOT NULL,
+    actor VARCHAR(255) NOT NULL,
+    reason TEXT NOT NULL DEFAULT '',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_store_settings_audit_field ON store_settings_audit(field, created_at);
@@ -0,0 +1,4 @@
+-- Keyset pages seek on (created_at, id), the order listings resume from
+CREATE INDEX idx_orders_created_at_id ON orders(created_at DESC, id DESC);
+CREATE INDEX idx_orders_user_id_created_at_id ON orders(user_id, created_at DESC, id DESC);
+CREATE INDEX idx_products_live_created_at_id ON products(created_at DESC, id DESC) WHERE deleted_at IS NULL;
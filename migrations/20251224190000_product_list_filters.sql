@@ -0,0 +1,5 @@
+-- Price range and in-stock filters of ListProducts, mostly within a category
+CREATE INDEX idx_products_live_category_price ON products(category, price) WHERE deleted_at IS NULL;
+CREATE INDEX idx_products_live_price ON products(price) WHERE deleted_at IS NULL;
+CREATE INDEX idx_products_live_sale_price ON products(sale_price) WHERE sale_price IS NOT NULL AND deleted_at IS NULL;
+CREATE INDEX idx_products_live_in_stock ON products(category) WHERE stock_quantity > 0 AND deleted_at IS NULL;
@@ -0,0 +1,6 @@
+-- Publication lifecycle: DRAFT, PUBLISHED or ARCHIVED. Existing products are
+-- already on sale; new ones start as drafts
+ALTER TABLE products ADD COLUMN IF NOT EXISTS status VARCHAR(20) NOT NULL DEFAULT 'PUBLISHED';
+ALTER TABLE products ALTER COLUMN status SET DEFAULT 'DRAFT';
+
+CREATE INDEX idx_products_published_created_at ON products(created_at) WHERE status = 'PUBLISHED' AND deleted_at IS NULL;
@@ -0,0 +1,2 @@
+-- PHYSICAL or DIGITAL; digital products keep no stock and aren't shipped
+ALTER TABLE products ADD COLUMN IF NOT EXISTS product_type VARCHAR(20) NOT NULL DEFAULT 'PHYSICAL';
@@ -0,0 +1,8 @@
+-- Products may take orders beyond their stock (backorders) or before their
+-- release (preorders); such order items are BACKORDERED and take no stock
+ALTER TABLE products ADD COLUMN IF NOT EXISTS allow_backorder BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE products ADD COLUMN IF NOT EXISTS available_from TIMESTAMPTZ;
+
+ALTER TABLE order_items ADD COLUMN IF NOT EXISTS status VARCHAR(20) NOT NULL DEFAULT 'ALLOCATED';
+
+CREATE INDEX idx_order_items_backordered ON order_items(product_id) WHERE status = 'BACKORDERED';
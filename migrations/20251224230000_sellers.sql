@@ -0,0 +1,15 @@
+-- Marketplace groundwork: products may belong to a seller, whose owning
+-- user signs in with the SELLER role and can only change its own products
+CREATE TABLE IF NOT EXISTS sellers (
+    id VARCHAR(36) PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    user_id VARCHAR(36) NOT NULL UNIQUE,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+ALTER TABLE products ADD COLUMN IF NOT EXISTS seller_id VARCHAR(36) REFERENCES sellers(id);
+CREATE INDEX idx_products_seller_created_at ON products(seller_id, created_at DESC, id DESC) WHERE deleted_at IS NULL;
+
+-- CUSTOMER or ADMIN; sellers are recognised by owning a seller
+ALTER TABLE users ADD COLUMN IF NOT EXISTS role VARCHAR(20) NOT NULL DEFAULT 'CUSTOMER';
@@ -0,0 +1,32 @@
+-- Stock reservations held by the product service for in-flight orders:
+-- PENDING until the order is written, then CONFIRMED or RELEASED
+CREATE TABLE IF NOT EXISTS stock_reservations (
+    id VARCHAR(36) PRIMARY KEY,
+    status VARCHAR(20) NOT NULL DEFAULT 'PENDING',
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS stock_reservation_lines (
+    reservation_id VARCHAR(36) NOT NULL,
+    line INT NOT NULL,
+    product_id VARCHAR(36) NOT NULL,
+    quantity INT NOT NULL,
+    -- TRUE when the units are owed rather than taken from stock
+    backordered BOOLEAN NOT NULL,
+    PRIMARY KEY (reservation_id, line),
+    FOREIGN KEY (reservation_id) REFERENCES stock_reservations(id) ON DELETE CASCADE,
+    FOREIGN KEY (product_id) REFERENCES products(id)
+);
+
+-- Progress of each CreateOrder saga, kept by the order service:
+-- STARTED -> ORDER_CREATED -> COMPLETED, or STARTED -> COMPENSATING -> COMPENSATED
+CREATE TABLE IF NOT EXISTS order_sagas (
+    order_id VARCHAR(36) PRIMARY KEY,
+    state VARCHAR(20) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_order_sagas_in_doubt ON order_sagas(updated_at)
+    WHERE state IN ('STARTED', 'COMPENSATING', 'ORDER_CREATED');
@@ -0,0 +1,35 @@
+-- Payments of orders. Each payment of an order is a numbered attempt, and at
+-- most one of them may be live (not FAILED), so retried or racing calls can't
+-- charge an order twice
+CREATE TABLE IF NOT EXISTS payments (
+    id VARCHAR(36) PRIMARY KEY,
+    order_id VARCHAR(36) NOT NULL,
+    attempt INT NOT NULL,
+    amount DECIMAL(10, 2) NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    -- REQUIRES_CAPTURE, PROCESSING, CAPTURED, FAILED, PARTIALLY_REFUNDED or REFUNDED
+    status VARCHAR(20) NOT NULL,
+    provider VARCHAR(50) NOT NULL,
+    provider_reference VARCHAR(255),
+    refunded_amount DECIMAL(10, 2) NOT NULL DEFAULT 0,
+    failure_reason TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (order_id) REFERENCES orders(id),
+    UNIQUE (order_id, attempt),
+    CHECK (refunded_amount <= amount)
+);
+
+CREATE UNIQUE INDEX idx_payments_live_order ON payments(order_id) WHERE status <> 'FAILED';
+
+CREATE TABLE IF NOT EXISTS payment_refunds (
+    id VARCHAR(36) PRIMARY KEY,
+    payment_id VARCHAR(36) NOT NULL,
+    amount DECIMAL(10, 2) NOT NULL,
+    reason TEXT,
+    provider_reference VARCHAR(255),
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (payment_id) REFERENCES payments(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_payment_refunds_payment_id ON payment_refunds(payment_id);
@@ -0,0 +1,29 @@
+-- Refunds of shipped or delivered orders, made through the payment service.
+-- A refund is PENDING while the payment service is asked, then COMPLETED or
+-- FAILED; the items of refunds that haven't failed count as returned
+CREATE TABLE IF NOT EXISTS order_refunds (
+    id VARCHAR(36) PRIMARY KEY,
+    order_id VARCHAR(36) NOT NULL,
+    payment_id VARCHAR(36) NOT NULL,
+    amount DECIMAL(10, 2) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'PENDING',
+    reason TEXT,
+    -- The payment service's refund id, once it refunded
+    payment_refund_id VARCHAR(36),
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_order_refunds_order_id ON order_refunds(order_id);
+
+CREATE TABLE IF NOT EXISTS order_refund_items (
+    refund_id VARCHAR(36) NOT NULL,
+    order_item_id VARCHAR(36) NOT NULL,
+    quantity INT NOT NULL CHECK (quantity > 0),
+    PRIMARY KEY (refund_id, order_item_id),
+    FOREIGN KEY (refund_id) REFERENCES order_refunds(id) ON DELETE CASCADE,
+    FOREIGN KEY (order_item_id) REFERENCES order_items(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_order_refund_items_order_item_id ON order_refund_items(order_item_id);
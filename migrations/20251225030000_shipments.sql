@@ -0,0 +1,42 @@
+-- Shipments of orders, kept by the shipping service
+CREATE TABLE IF NOT EXISTS shipments (
+    id VARCHAR(36) PRIMARY KEY,
+    order_id VARCHAR(36) NOT NULL,
+    carrier VARCHAR(50) NOT NULL,
+    tracking_number VARCHAR(100) NOT NULL,
+    -- LABEL_CREATED, IN_TRANSIT, OUT_FOR_DELIVERY, DELIVERED, EXCEPTION or CANCELLED
+    status VARCHAR(20) NOT NULL DEFAULT 'LABEL_CREATED',
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE CASCADE,
+    UNIQUE (carrier, tracking_number)
+);
+
+CREATE INDEX idx_shipments_order_id ON shipments(order_id);
+
+-- Order items in each shipment. `active` is cleared when the shipment is
+-- cancelled; an item is in at most one active shipment, so retried or racing
+-- calls can't ship it twice
+CREATE TABLE IF NOT EXISTS shipment_items (
+    shipment_id VARCHAR(36) NOT NULL,
+    order_item_id VARCHAR(36) NOT NULL,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    PRIMARY KEY (shipment_id, order_item_id),
+    FOREIGN KEY (shipment_id) REFERENCES shipments(id) ON DELETE CASCADE,
+    FOREIGN KEY (order_item_id) REFERENCES order_items(id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX idx_shipment_items_active ON shipment_items(order_item_id) WHERE active;
+
+CREATE TABLE IF NOT EXISTS shipment_events (
+    id VARCHAR(36) PRIMARY KEY,
+    shipment_id VARCHAR(36) NOT NULL,
+    status VARCHAR(20) NOT NULL,
+    location VARCHAR(255),
+    description TEXT,
+    occurred_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (shipment_id) REFERENCES shipments(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_shipment_events_shipment_id ON shipment_events(shipment_id);
@@ -0,0 +1,18 @@
+-- Timeline of order mutations, written in the same transaction as the change
+-- itself. Ids increase with every event, so they order the timeline
+CREATE TABLE IF NOT EXISTS order_events (
+    id BIGSERIAL PRIMARY KEY,
+    order_id VARCHAR(36) NOT NULL,
+    -- CREATED, STATUS_CHANGED, CANCELLED, REFUNDED or ADDRESS_CHANGED
+    event_type VARCHAR(30) NOT NULL,
+    from_status VARCHAR(20),
+    to_status VARCHAR(20),
+    -- platform, user:<id>, seller:<id> or system:<service>
+    actor VARCHAR(100) NOT NULL,
+    reason TEXT,
+    details TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_order_events_order_id ON order_events(order_id, id);
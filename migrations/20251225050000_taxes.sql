@@ -0,0 +1,19 @@
+-- Tax classes of products, e.g. standard, reduced or zero; rates are set per class
+ALTER TABLE products ADD COLUMN IF NOT EXISTS tax_class VARCHAR(30) NOT NULL DEFAULT 'standard';
+
+-- Tax rates by region and tax class. Regions are ISO 3166 codes: a
+-- subdivision (US-CA) takes precedence over its country (US)
+CREATE TABLE IF NOT EXISTS tax_rates (
+    region VARCHAR(10) NOT NULL,
+    tax_class VARCHAR(30) NOT NULL,
+    -- percent, e.g. 7.25
+    rate DECIMAL(6, 3) NOT NULL CHECK (rate >= 0),
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (region, tax_class)
+);
+
+-- Tax charged, included in the totals
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS tax_amount DECIMAL(10, 2) NOT NULL DEFAULT 0;
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS shipping_region VARCHAR(10);
+ALTER TABLE order_items ADD COLUMN IF NOT EXISTS tax_amount DECIMAL(10, 2) NOT NULL DEFAULT 0;
@@ -0,0 +1,26 @@
+-- Shipping weight of one unit of a product
+ALTER TABLE products ADD COLUMN IF NOT EXISTS weight_grams INT NOT NULL DEFAULT 0 CHECK (weight_grams >= 0);
+
+-- Shipping methods offered at checkout
+CREATE TABLE IF NOT EXISTS shipping_methods (
+    code VARCHAR(30) PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Price of a method by region and weight bracket: a shipment pays the rate
+-- of the smallest bracket its weight fits in. Regions are ISO 3166 codes; a
+-- subdivision (US-CA) takes precedence over its country (US), and '*' covers
+-- any region
+CREATE TABLE IF NOT EXISTS shipping_rates (
+    method VARCHAR(30) NOT NULL REFERENCES shipping_methods(code) ON DELETE CASCADE,
+    region VARCHAR(10) NOT NULL,
+    max_weight_grams INT NOT NULL CHECK (max_weight_grams > 0),
+    price DECIMAL(10, 2) NOT NULL CHECK (price >= 0),
+    PRIMARY KEY (method, region, max_weight_grams)
+);
+
+-- Shipping chosen at checkout; the fee is included in the order total
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS shipping_method VARCHAR(30);
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS shipping_fee DECIMAL(10, 2) NOT NULL DEFAULT 0;
@@ -0,0 +1,4 @@
+-- Support the ListOrders filters; user and date-range listings use the
+-- keyset pagination indexes
+CREATE INDEX IF NOT EXISTS idx_orders_status_created_at_id ON orders(status, created_at DESC, id DESC);
+CREATE INDEX IF NOT EXISTS idx_orders_total_amount ON orders(total_amount);
@@ -0,0 +1,38 @@
+-- Endpoints order lifecycle events are POSTed to
+CREATE TABLE IF NOT EXISTS webhook_endpoints (
+    id VARCHAR(36) PRIMARY KEY,
+    url TEXT NOT NULL,
+    -- signs every delivery (HMAC-SHA256), so receivers can verify them
+    secret VARCHAR(255) NOT NULL,
+    -- order event types delivered (CREATED, STATUS_CHANGED, CANCELLED, ...)
+    event_types TEXT[] NOT NULL,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- One delivery of an order event to an endpoint. Failed deliveries are
+-- retried with backoff until they run out of attempts and become DEAD
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    id VARCHAR(36) PRIMARY KEY,
+    endpoint_id VARCHAR(36) NOT NULL REFERENCES webhook_endpoints(id) ON DELETE CASCADE,
+    event_id BIGINT NOT NULL REFERENCES order_events(id) ON DELETE CASCADE,
+    -- PENDING, DELIVERED or DEAD
+    status VARCHAR(20) NOT NULL DEFAULT 'PENDING',
+    attempts INT NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_error TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (endpoint_id, event_id)
+);
+
+CREATE INDEX idx_webhook_deliveries_due ON webhook_deliveries(next_attempt_at) WHERE status = 'PENDING';
+CREATE INDEX idx_webhook_deliveries_dead ON webhook_deliveries(updated_at) WHERE status = 'DEAD';
+
+-- Whether an event was fanned out to the endpoints. Events recorded before
+-- webhooks existed count as dispatched, so new endpoints don't get a backlog
+ALTER TABLE order_events ADD COLUMN IF NOT EXISTS webhooks_dispatched BOOLEAN NOT NULL DEFAULT TRUE;
+ALTER TABLE order_events ALTER COLUMN webhooks_dispatched SET DEFAULT FALSE;
+
+CREATE INDEX idx_order_events_undispatched ON order_events(id) WHERE NOT webhooks_dispatched;
@@ -0,0 +1,11 @@
+-- Per-item fulfillment, so an order can ship in several shipments. An order
+-- with some of its items shipped is PARTIALLY_SHIPPED, and SHIPPED once all are
+ALTER TABLE order_items ADD COLUMN IF NOT EXISTS fulfillment_status VARCHAR(20) NOT NULL DEFAULT 'UNFULFILLED';
+-- carrier reference the item shipped under
+ALTER TABLE order_items ADD COLUMN IF NOT EXISTS shipment_tracking VARCHAR(255);
+ALTER TABLE order_items ADD COLUMN IF NOT EXISTS shipped_at TIMESTAMP;
+
+-- Items of orders already shipped count as shipped
+UPDATE order_items oi SET fulfillment_status = 'SHIPPED', shipped_at = o.updated_at
+FROM orders o
+WHERE o.id = oi.order_id AND o.status IN ('SHIPPED', 'DELIVERED');
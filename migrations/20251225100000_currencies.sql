@@ -0,0 +1,12 @@
+-- Exchange rates from the store's default currency (the first accepted one,
+-- which product prices are in), kept current by the order service
+CREATE TABLE IF NOT EXISTS exchange_rates (
+    currency VARCHAR(3) PRIMARY KEY,
+    -- units of the currency one unit of the default currency buys
+    rate DECIMAL(18, 8) NOT NULL CHECK (rate > 0),
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Currency an order is charged in, and the rate its prices were converted at
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS currency VARCHAR(3) NOT NULL DEFAULT 'USD';
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS exchange_rate DECIMAL(18, 8) NOT NULL DEFAULT 1;
@@ -0,0 +1,9 @@
+-- Guest checkout: orders placed without an account belong to a lightweight
+-- guest identity, one per email address, until the customer registers and
+-- claims them. Guests have no password and can't log in.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS is_guest BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- A guest may share an email address with a registered account
+ALTER TABLE users DROP CONSTRAINT IF EXISTS users_email_key;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_users_email_registered ON users (email) WHERE NOT is_guest;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_users_email_guest ON users (LOWER(email)) WHERE is_guest;
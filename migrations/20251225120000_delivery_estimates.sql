@@ -0,0 +1,11 @@
+-- Days a shipment at a rate takes to arrive after the order is placed
+ALTER TABLE shipping_rates ADD COLUMN IF NOT EXISTS min_transit_days INT NOT NULL DEFAULT 3 CHECK (min_transit_days >= 0);
+ALTER TABLE shipping_rates ADD COLUMN IF NOT EXISTS max_transit_days INT NOT NULL DEFAULT 7;
+ALTER TABLE shipping_rates DROP CONSTRAINT IF EXISTS shipping_rates_transit_days_check;
+ALTER TABLE shipping_rates ADD CONSTRAINT shipping_rates_transit_days_check CHECK (max_transit_days >= min_transit_days);
+
+-- Delivery window estimated when an order is placed, from its shipping
+-- method and destination; the shipping service refines it as carriers
+-- report expected delivery dates. Empty for orders that don't ship
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS estimated_delivery_from DATE;
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS estimated_delivery_to DATE;
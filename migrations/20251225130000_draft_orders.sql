@@ -0,0 +1,38 @@
+-- Draft orders: quotes priced like orders but holding no stock. Their
+-- prices hold until expires_at; finalizing one reserves the stock and places
+-- it as order_id. Past expires_at an open draft counts as expired
+CREATE TABLE IF NOT EXISTS draft_orders (
+    id VARCHAR(36) PRIMARY KEY,
+    user_id VARCHAR(36) NOT NULL REFERENCES users(id),
+    status VARCHAR(20) NOT NULL DEFAULT 'OPEN' CHECK (status IN ('OPEN', 'FINALIZED')),
+    total_amount DECIMAL(10, 2) NOT NULL,
+    tax_amount DECIMAL(10, 2) NOT NULL DEFAULT 0,
+    shipping_fee DECIMAL(10, 2) NOT NULL DEFAULT 0,
+    currency VARCHAR(3) NOT NULL,
+    exchange_rate DECIMAL(18, 8) NOT NULL,
+    shipping_address TEXT,
+    shipping_region VARCHAR(10),
+    shipping_method VARCHAR(30),
+    billing_region VARCHAR(10),
+    min_transit_days INT,
+    max_transit_days INT,
+    -- set when finalizing starts, before the order is written
+    order_id VARCHAR(36),
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_draft_orders_user_id ON draft_orders(user_id);
+
+CREATE TABLE IF NOT EXISTS draft_order_items (
+    id VARCHAR(36) PRIMARY KEY,
+    draft_id VARCHAR(36) NOT NULL REFERENCES draft_orders(id) ON DELETE CASCADE,
+    product_id VARCHAR(36) NOT NULL REFERENCES products(id) ON DELETE RESTRICT,
+    quantity INT NOT NULL CHECK (quantity > 0),
+    price DECIMAL(10, 2) NOT NULL,
+    tax_amount DECIMAL(10, 2) NOT NULL DEFAULT 0,
+    position INT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_draft_order_items_draft_id ON draft_order_items(draft_id);
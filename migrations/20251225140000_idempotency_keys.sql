@@ -0,0 +1,15 @@
+-- Idempotency keys of calls made through common::idempotency: the first call
+-- with a key claims it, and its response, once stored, answers repeats of it
+-- until expires_at. A claim without a response is a call still running
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+    -- method, caller and client key
+    key TEXT PRIMARY KEY,
+    -- SHA-256 of the request message, so a key reused for another request is refused
+    fingerprint BYTEA NOT NULL,
+    -- the encoded response, NULL while the call runs
+    response BYTEA,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_idempotency_keys_expires_at ON idempotency_keys(expires_at);
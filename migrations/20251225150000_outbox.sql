@@ -0,0 +1,20 @@
+-- Transactional outbox: domain events written in the transaction making the
+-- change, published to the message broker by common::outbox::OutboxRelay.
+-- Ids increase with every event, so they order each aggregate's events
+CREATE TABLE IF NOT EXISTS outbox (
+    id BIGSERIAL PRIMARY KEY,
+    -- kind of entity changed, e.g. order; the topic published to
+    aggregate_type VARCHAR(50) NOT NULL,
+    aggregate_id VARCHAR(100) NOT NULL,
+    event_type VARCHAR(50) NOT NULL,
+    payload BYTEA NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    -- NULL until the broker accepted the event
+    published_at TIMESTAMPTZ,
+    -- failed publishes, and why the last one failed
+    attempts INT NOT NULL DEFAULT 0,
+    last_error TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_outbox_pending ON outbox(id) WHERE published_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_outbox_published_at ON outbox(published_at);
@@ -0,0 +1,36 @@
+-- One email or SMS sent by the notification service, rendered from a
+-- template when it's queued. Failed sends are retried with backoff until
+-- they run out of attempts and become FAILED
+CREATE TABLE IF NOT EXISTS notification_deliveries (
+    id VARCHAR(36) PRIMARY KEY,
+    -- EMAIL or SMS
+    channel VARCHAR(10) NOT NULL,
+    user_id VARCHAR(36),
+    -- email address or E.164 phone number
+    recipient VARCHAR(255) NOT NULL,
+    template VARCHAR(50) NOT NULL,
+    subject TEXT,
+    body TEXT NOT NULL,
+    html_body TEXT,
+    -- QUEUED, SENT or FAILED
+    status VARCHAR(20) NOT NULL DEFAULT 'QUEUED',
+    -- provider that sent it, and its id of the message, e.g. a Twilio SID
+    provider VARCHAR(20),
+    provider_message_id VARCHAR(255),
+    attempts INT NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_error TEXT,
+    -- bus event it was sent for, so a redelivered event isn't sent twice
+    event_id VARCHAR(64),
+    sent_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (event_id, channel)
+);
+
+CREATE INDEX idx_notification_deliveries_due ON notification_deliveries(next_attempt_at) WHERE status = 'QUEUED';
+CREATE INDEX idx_notification_deliveries_user ON notification_deliveries(user_id, created_at DESC);
+
+-- Bus event an inbox entry was created for, so a redelivered event doesn't
+-- add it twice
+ALTER TABLE notifications ADD COLUMN IF NOT EXISTS event_id VARCHAR(64) UNIQUE;
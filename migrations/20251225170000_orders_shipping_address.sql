@@ -0,0 +1,3 @@
+-- Where an order ships to; empty for orders that don't ship. Orders have
+-- been written with it all along, but no migration created the column
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS shipping_address TEXT;
@@ -0,0 +1,29 @@
+-- Units an order gives back, e.g. on a cancellation or refund, recorded with
+-- the change freeing them and sent to the product service, which owns stock;
+-- see order::stock_return
+CREATE TABLE IF NOT EXISTS order_stock_returns (
+    id VARCHAR(36) PRIMARY KEY,
+    order_id VARCHAR(36) NOT NULL REFERENCES orders(id) ON DELETE CASCADE,
+    -- PENDING until the product service put the stock back, then DONE
+    status VARCHAR(20) NOT NULL DEFAULT 'PENDING',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_order_stock_returns_pending ON order_stock_returns(updated_at) WHERE status = 'PENDING';
+
+CREATE TABLE IF NOT EXISTS order_stock_return_lines (
+    return_id VARCHAR(36) NOT NULL REFERENCES order_stock_returns(id) ON DELETE CASCADE,
+    product_id VARCHAR(36) NOT NULL,
+    quantity INT NOT NULL CHECK (quantity > 0),
+    PRIMARY KEY (return_id, product_id)
+);
+
+-- Returns the product service applied, so one sent again adds nothing
+CREATE TABLE IF NOT EXISTS stock_returns (
+    id VARCHAR(36) PRIMARY KEY,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Every stock write checks what's left; existing rows aren't re-checked
+ALTER TABLE products ADD CONSTRAINT products_stock_quantity_non_negative CHECK (stock_quantity >= 0) NOT VALID;
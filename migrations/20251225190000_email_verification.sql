@@ -0,0 +1,4 @@
+-- When a user proved they receive mail at their address, e.g. with the
+-- token of a guest order's confirmation; cleared when the address changes.
+-- Guest orders are only claimed into an account with a verified address
+ALTER TABLE users ADD COLUMN IF NOT EXISTS email_verified_at TIMESTAMP;
@@ -0,0 +1,17 @@
+-- What an item was sold as: its product's type and warranty when it was
+-- ordered, which the product service owns. Shipping and warranties go by
+-- these rather than by the product as it is now
+ALTER TABLE order_items ADD COLUMN IF NOT EXISTS product_type VARCHAR(20) NOT NULL DEFAULT 'PHYSICAL';
+ALTER TABLE order_items ADD COLUMN IF NOT EXISTS warranty_months INT NOT NULL DEFAULT 0;
+ALTER TABLE draft_order_items ADD COLUMN IF NOT EXISTS product_type VARCHAR(20) NOT NULL DEFAULT 'PHYSICAL';
+ALTER TABLE draft_order_items ADD COLUMN IF NOT EXISTS warranty_months INT NOT NULL DEFAULT 0;
+
+UPDATE order_items oi
+SET product_type = p.product_type, warranty_months = p.warranty_months
+FROM products p
+WHERE p.id = oi.product_id;
+
+UPDATE draft_order_items di
+SET product_type = p.product_type, warranty_months = p.warranty_months
+FROM products p
+WHERE p.id = di.product_id;
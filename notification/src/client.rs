@@ -0,0 +1,97 @@
+use common::auth::ServiceCredentials;
+use proto::notification::{
+    CreateNotificationRequest, GetUnreadCountRequest, ListNotificationsRequest, MarkReadRequest,
+    notification_service_client::NotificationServiceClient,
+};
+use tonic::transport::Channel;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Notifications are created by the platform
+    let channel = Channel::from_static("http://127.0.0.1:50054")
+        .connect()
+        .await?;
+    let mut client = NotificationServiceClient::with_interceptor(
+        channel,
+        ServiceCredentials("notification-client"),
+    );
+
+    println!("Connected to Notification Service");
+    println!("==================================\n");
+
+    // Note: notifications reference an existing user
+    let user_id = "test-user-id".to_string();
+    println!("User ID: {}\n", user_id);
+
+    // Test 1: Create a notification
+    println!("1. Testing Create Notification");
+    let create_request = CreateNotificationRequest {
+        user_id: user_id.clone(),
+        category: "order".to_string(),
+        title: "Your order has shipped".to_string(),
+        body: "Order #123 is on its way.".to_string(),
+        link: "/orders/123".to_string(),
+    };
+
+    let create_response = client.create_notification(create_request).await?;
+    let create_result = create_response.into_inner();
+    println!("Create Notification Response:");
+    println!("  Success: {}", create_result.success);
+    println!("  Message: {}", create_result.message);
+    println!("  Notification ID: {}\n", create_result.notification_id);
+
+    // Test 2: Unread count
+    println!("2. Testing Get Unread Count");
+    let count_response = client
+        .get_unread_count(GetUnreadCountRequest {
+            user_id: user_id.clone(),
+        })
+        .await?;
+    println!(
+        "  Unread Count: {}\n",
+        count_response.into_inner().unread_count
+    );
+
+    // Test 3: List notifications
+    println!("3. Testing List Notifications");
+    let list_request = ListNotificationsRequest {
+        user_id: user_id.clone(),
+        page: 1,
+        page_size: 10,
+        unread_only: false,
+    };
+
+    let list_response = client.list_notifications(list_request).await?;
+    let list_result = list_response.into_inner();
+    println!("List Notifications Response:");
+    println!("  Success: {}", list_result.success);
+    println!("  Total Count: {}", list_result.total_count);
+    println!("  Unread Count: {}", list_result.unread_count);
+    for notification in &list_result.notifications {
+        println!(
+            "  - [{}] {} (read: {})",
+            notification.category, notification.title, notification.read
+        );
+    }
+    println!();
+
+    // Test 4: Mark all as read
+    println!("4. Testing Mark All Read");
+    let mark_request = MarkReadRequest {
+        user_id: user_id.clone(),
+        notification_ids: vec![],
+        all: true,
+    };
+
+    let mark_response = client.mark_read(mark_request).await?;
+    let mark_result = mark_response.into_inner();
+    println!("Mark Read Response:");
+    println!("  Success: {}", mark_result.success);
+    println!("  Message: {}", mark_result.message);
+    println!("  Unread Count: {}\n", mark_result.unread_count);
+
+    println!("==================================");
+    println!("All tests completed!");
+
+    Ok(())
+}
@@ -0,0 +1,273 @@
+//! Emails and SMS are queued as deliveries, already rendered, and sent by
+//! `DeliveryWorker` through the configured providers. Failed sends are
+//! retried with backoff until they run out of attempts and become FAILED.
+
+use crate::provider::{Email, EmailProvider, SmsProvider};
+use common::error::AppError;
+use proto::notification::Delivery;
+use sqlx::{PgExecutor, PgPool};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+use tonic::Status;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+pub const EMAIL: &str = "EMAIL";
+pub const SMS: &str = "SMS";
+
+/// Deliveries sent per sweep.
+const BATCH_SIZE: i64 = 50;
+
+/// How long a claimed delivery is kept from other sweeps while it's sent.
+const CLAIM_SECS: f64 = 120.0;
+
+/// Retry delay after the first failed attempt; it doubles with every
+/// further attempt, up to `MAX_BACKOFF_SECS`.
+const BASE_BACKOFF_SECS: u64 = 30;
+const MAX_BACKOFF_SECS: u64 = 3600;
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct DbDelivery {
+    pub id: String,
+    pub channel: String,
+    pub user_id: Option<String>,
+    pub recipient: String,
+    pub template: String,
+    pub subject: Option<String>,
+    pub body: String,
+    pub html_body: Option<String>,
+    pub status: String,
+    pub provider: Option<String>,
+    pub provider_message_id: Option<String>,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub sent_at: Option<chrono::NaiveDateTime>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+impl DbDelivery {
+    pub fn to_proto(&self) -> Delivery {
+        Delivery {
+            delivery_id: self.id.clone(),
+            channel: self.channel.clone(),
+            user_id: self.user_id.clone().unwrap_or_default(),
+            recipient: self.recipient.clone(),
+            template: self.template.clone(),
+            status: self.status.clone(),
+            provider: self.provider.clone().unwrap_or_default(),
+            provider_message_id: self.provider_message_id.clone().unwrap_or_default(),
+            attempts: self.attempts,
+            last_error: self.last_error.clone().unwrap_or_default(),
+            created_at: self.created_at.and_utc().timestamp(),
+            sent_at: self
+                .sent_at
+                .map(|t| t.and_utc().timestamp())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+pub const DELIVERY_COLUMNS: &str = "id, channel, user_id, recipient, template, subject, body, \
+     html_body, status, provider, provider_message_id, attempts, last_error, sent_at, created_at";
+
+/// A rendered email or SMS to queue.
+#[derive(Debug, Clone, Copy)]
+pub struct NewDelivery<'a> {
+    /// `EMAIL` or `SMS`
+    pub channel: &'a str,
+    pub user_id: Option<&'a str>,
+    pub recipient: &'a str,
+    pub template: &'a str,
+    pub subject: Option<&'a str>,
+    pub body: &'a str,
+    pub html_body: Option<&'a str>,
+    /// Bus event it's sent for; it's queued once per event and channel.
+    pub event_id: Option<&'a str>,
+}
+
+/// Queues `delivery` for the worker to send; `None` when it was already
+/// queued for its event.
+pub async fn queue<'e>(
+    executor: impl PgExecutor<'e>,
+    delivery: &NewDelivery<'_>,
+) -> Result<Option<DbDelivery>, sqlx::Error> {
+    sqlx::query_as::<_, DbDelivery>(&format!(
+        "INSERT INTO notification_deliveries
+             (id, channel, user_id, recipient, template, subject, body, html_body, event_id)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+         ON CONFLICT (event_id, channel) DO NOTHING
+         RETURNING {}",
+        DELIVERY_COLUMNS
+    ))
+    .bind(Uuid::new_v4().to_string())
+    .bind(delivery.channel)
+    .bind(delivery.user_id)
+    .bind(delivery.recipient)
+    .bind(delivery.template)
+    .bind(delivery.subject)
+    .bind(delivery.body)
+    .bind(delivery.html_body)
+    .bind(delivery.event_id)
+    .fetch_optional(executor)
+    .await
+}
+
+/// Delay before retrying a delivery that has failed `attempts` times.
+fn backoff(attempts: i32) -> Duration {
+    let doublings = u32::try_from(attempts.saturating_sub(1))
+        .unwrap_or(0)
+        .min(20);
+    Duration::from_secs((BASE_BACKOFF_SECS << doublings).min(MAX_BACKOFF_SECS))
+}
+
+/// Background worker sending queued deliveries.
+pub struct DeliveryWorker {
+    db: PgPool,
+    email: Arc<dyn EmailProvider>,
+    sms: Arc<dyn SmsProvider>,
+    max_attempts: i32,
+    wake: Arc<Notify>,
+}
+
+impl DeliveryWorker {
+    pub fn new(
+        db: PgPool,
+        email: Arc<dyn EmailProvider>,
+        sms: Arc<dyn SmsProvider>,
+        max_attempts: i32,
+    ) -> Self {
+        Self {
+            db,
+            email,
+            sms,
+            max_attempts: max_attempts.max(1),
+            wake: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Wakes the worker to sweep right away, e.g. after queuing a delivery,
+    /// rather than at its next tick.
+    pub fn waker(&self) -> Arc<Notify> {
+        self.wake.clone()
+    }
+
+    /// Sends the deliveries that are due; returns how many were sent.
+    pub async fn run(&self) -> Result<usize, Status> {
+        // Claiming pushes the next attempt out, so a sweep that dies mid-send
+        // leaves its deliveries to be retried rather than stuck
+        let deliveries = sqlx::query_as::<_, DbDelivery>(&format!(
+            "UPDATE notification_deliveries
+             SET next_attempt_at = CURRENT_TIMESTAMP + make_interval(secs => $1),
+                 updated_at = CURRENT_TIMESTAMP
+             WHERE id IN (
+                 SELECT id FROM notification_deliveries
+                 WHERE status = 'QUEUED' AND next_attempt_at <= CURRENT_TIMESTAMP
+                 ORDER BY next_attempt_at
+                 LIMIT $2
+                 FOR UPDATE SKIP LOCKED
+             )
+             RETURNING {}",
+            DELIVERY_COLUMNS
+        ))
+        .bind(CLAIM_SECS)
+        .bind(BATCH_SIZE)
+        .fetch_all(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        let mut sent = 0;
+        for delivery in deliveries {
+            let (provider, result) = self.send(&delivery).await;
+            match result {
+                Ok(message_id) => {
+                    sqlx::query(
+                        "UPDATE notification_deliveries
+                         SET status = 'SENT', attempts = attempts + 1, last_error = NULL,
+                             provider = $2, provider_message_id = NULLIF($3, ''),
+                             sent_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP
+                         WHERE id = $1",
+                    )
+                    .bind(&delivery.id)
+                    .bind(provider)
+                    .bind(&message_id)
+                    .execute(&self.db)
+                    .await
+                    .map_err(AppError::from)?;
+                    sent += 1;
+                }
+                Err(error) => {
+                    let attempts = delivery.attempts + 1;
+                    let failed = attempts >= self.max_attempts;
+                    if failed {
+                        warn!(
+                            delivery_id = %delivery.id,
+                            channel = %delivery.channel,
+                            template = %delivery.template,
+                            "Notification failed for good after {} attempts: {}",
+                            attempts,
+                            error
+                        );
+                    }
+                    sqlx::query(
+                        "UPDATE notification_deliveries
+                         SET status = $2, attempts = $3, last_error = $4, provider = $5,
+                             next_attempt_at = CURRENT_TIMESTAMP + make_interval(secs => $6),
+                             updated_at = CURRENT_TIMESTAMP
+                         WHERE id = $1",
+                    )
+                    .bind(&delivery.id)
+                    .bind(if failed { "FAILED" } else { "QUEUED" })
+                    .bind(attempts)
+                    .bind(&error)
+                    .bind(provider)
+                    .bind(backoff(attempts).as_secs_f64())
+                    .execute(&self.db)
+                    .await
+                    .map_err(AppError::from)?;
+                }
+            }
+        }
+
+        Ok(sent)
+    }
+
+    /// Sends a delivery through the provider of its channel, which it
+    /// returns along with the provider's message id or why it failed.
+    async fn send(&self, delivery: &DbDelivery) -> (&'static str, Result<String, String>) {
+        let (provider, result) = if delivery.channel == SMS {
+            (
+                self.sms.name(),
+                self.sms.send(&delivery.recipient, &delivery.body).await,
+            )
+        } else {
+            let email = Email {
+                to: &delivery.recipient,
+                subject: delivery.subject.as_deref().unwrap_or_default(),
+                text: &delivery.body,
+                html: delivery.html_body.as_deref(),
+            };
+            (self.email.name(), self.email.send(&email).await)
+        };
+        (provider, result.map_err(|e| e.to_string()))
+    }
+
+    /// Spawns a background task that sweeps every `interval`, and whenever
+    /// it's woken.
+    pub fn spawn(self, interval: Duration) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {}
+                    _ = self.wake.notified() => {}
+                }
+                match self.run().await {
+                    Ok(0) => {}
+                    Ok(sent) => info!("Sent {} notifications", sent),
+                    Err(e) => warn!("Notification sweep failed: {}", e),
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,94 @@
+mod delivery;
+mod notification;
+mod provider;
+mod subscriber;
+mod templates;
+
+use anyhow::Result;
+use common::client::ServiceEndpoint;
+use common::events;
+use common::metrics::{self, Metrics};
+use common::telemetry;
+use common::slo::{SloConfig, SloServiceImpl, SloTracker};
+use common::server::ServerBuilder;
+use common::tls;
+use delivery::DeliveryWorker;
+use notification::NotificationServiceImpl;
+use proto::notification::notification_service_server::NotificationServiceServer;
+use proto::slo::slo_service_server::SloServiceServer;
+use sqlx::postgres::PgPoolOptions;
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+use subscriber::NotificationSubscriber;
+use templates::Templates;
+use tracing::{info};
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenvy::dotenv().ok();
+
+    telemetry::init("notification", env!("CARGO_PKG_VERSION"))?;
+
+    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+
+    // Create database connection pool
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&database_url)
+        .await?;
+
+    info!("Connected to database");
+
+    let addr = "0.0.0.0:50054".parse()?;
+
+    let delivery_interval_secs: u64 = env::var("NOTIFICATION_DELIVERY_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(5);
+    let delivery_max_attempts: i32 = env::var("NOTIFICATION_MAX_ATTEMPTS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(6);
+    let delivery_worker = DeliveryWorker::new(
+        pool.clone(),
+        provider::email_provider_from_env()?,
+        provider::sms_provider_from_env()?,
+        delivery_max_attempts,
+    );
+    let wake = delivery_worker.waker();
+    delivery_worker.spawn(Duration::from_secs(delivery_interval_secs));
+
+    let templates = Arc::new(Templates::new());
+
+    // Customers are notified of what happens to their account and orders
+    // from the events on the bus, when there's one
+    if let Some(consumer) = events::consumer_from_env(subscriber::GROUP, &subscriber::TOPICS).await? {
+        let user_service =
+            ServiceEndpoint::from_env("user", "USER_SERVICE", "http://127.0.0.1:50051")
+                .with_tls(tls::client_config()?);
+        let handler =
+            NotificationSubscriber::new(pool.clone(), templates.clone(), user_service, wake.clone());
+        events::spawn_consumer(consumer, Arc::new(handler));
+    }
+
+    let notification_service = NotificationServiceImpl::new(pool, templates, wake);
+    let slo_tracker = SloTracker::new("notification", SloConfig::from_env()?);
+    let metrics = Metrics::new("notification");
+    let metrics_addr = env::var("METRICS_ADDR")
+        .unwrap_or_else(|_| "0.0.0.0:9104".to_string())
+        .parse()?;
+    metrics::spawn(metrics_addr, metrics.clone());
+
+    info!("Notification service listening on {}", addr);
+
+    ServerBuilder::new(metrics, slo_tracker.clone())
+        .build()?
+        .add_service(NotificationServiceServer::new(notification_service))
+        .add_service(SloServiceServer::new(SloServiceImpl::new(slo_tracker)))
+        .serve(addr)
+        .await?;
+
+    telemetry::shutdown();
+    Ok(())
+}
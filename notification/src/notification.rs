@@ -0,0 +1,394 @@
+use crate::delivery::{self, DELIVERY_COLUMNS, DbDelivery, EMAIL, NewDelivery, SMS};
+use crate::templates::{self, Templates};
+use common::auth::Caller;
+use common::error::{self, AppError};
+use common::pagination::PageRequest;
+use proto::notification::{
+    CreateNotificationRequest, CreateNotificationResponse, GetDeliveryRequest, GetDeliveryResponse,
+    GetUnreadCountRequest, GetUnreadCountResponse, ListNotificationsRequest,
+    ListNotificationsResponse, MarkReadRequest, MarkReadResponse, Notification, SendEmailRequest,
+    SendResponse, SendSmsRequest, notification_service_server::NotificationService,
+};
+use sqlx::PgPool;
+use std::sync::Arc;
+use tokio::sync::Notify;
+use tonic::{Request, Response, Status};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+#[derive(Debug, sqlx::FromRow)]
+struct DbNotification {
+    id: String,
+    user_id: String,
+    category: String,
+    title: String,
+    body: String,
+    link: Option<String>,
+    read_at: Option<chrono::NaiveDateTime>,
+    created_at: chrono::NaiveDateTime,
+}
+
+pub struct NotificationServiceImpl {
+    db: PgPool,
+    templates: Arc<Templates>,
+    /// Wakes the delivery worker once something is queued.
+    wake: Arc<Notify>,
+}
+
+impl NotificationServiceImpl {
+    pub fn new(db: PgPool, templates: Arc<Templates>, wake: Arc<Notify>) -> Self {
+        Self {
+            db,
+            templates,
+            wake,
+        }
+    }
+
+    fn db_notification_to_proto(&self, db_notification: &DbNotification) -> Notification {
+        Notification {
+            notification_id: db_notification.id.clone(),
+            user_id: db_notification.user_id.clone(),
+            category: db_notification.category.clone(),
+            title: db_notification.title.clone(),
+            body: db_notification.body.clone(),
+            link: db_notification.link.clone().unwrap_or_default(),
+            read: db_notification.read_at.is_some(),
+            created_at: db_notification.created_at.and_utc().timestamp(),
+            read_at: db_notification
+                .read_at
+                .map(|t| t.and_utc().timestamp())
+                .unwrap_or_default(),
+        }
+    }
+
+    async fn unread_count(&self, user_id: &str) -> Result<i64, Status> {
+        let count: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM notifications WHERE user_id = $1 AND read_at IS NULL",
+        )
+        .bind(user_id)
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| {
+            error!("Database error counting unread notifications: {}", e);
+            Status::from(AppError::from(e))
+        })?;
+
+        Ok(count.0)
+    }
+
+    /// Queues a rendered delivery and wakes the worker to send it.
+    async fn queue(&self, delivery: &NewDelivery<'_>) -> Result<DbDelivery, Status> {
+        let queued = delivery::queue(&self.db, delivery)
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| Status::internal("Delivery was not queued"))?;
+        self.wake.notify_one();
+
+        info!(
+            "{} queued: {} ({})",
+            queued.channel, queued.id, queued.template
+        );
+        Ok(queued)
+    }
+}
+
+/// Why a template didn't render: there's none of that name, or it refers
+/// to a variable the request didn't pass.
+fn template_error(name: &str, e: minijinja::Error) -> Status {
+    if templates::is_unknown(&e) {
+        error::invalid_argument("UNKNOWN_TEMPLATE", format!("Unknown template: {}", name))
+    } else {
+        error::invalid_argument(
+            "TEMPLATE_RENDER_FAILED",
+            format!("Failed to render template {}: {}", name, e),
+        )
+    }
+}
+
+#[tonic::async_trait]
+impl NotificationService for NotificationServiceImpl {
+    async fn create_notification(
+        &self,
+        request: Request<CreateNotificationRequest>,
+    ) -> Result<Response<CreateNotificationResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        if req.user_id.is_empty() || req.title.is_empty() {
+            return Err(error::invalid_argument(
+                "INVALID_NOTIFICATION",
+                "User ID and title are required",
+            ));
+        }
+
+        let notification_id = Uuid::new_v4().to_string();
+        let category = if req.category.is_empty() {
+            "general".to_string()
+        } else {
+            req.category
+        };
+
+        sqlx::query(
+            "INSERT INTO notifications (id, user_id, category, title, body, link)
+             VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(&notification_id)
+        .bind(&req.user_id)
+        .bind(&category)
+        .bind(&req.title)
+        .bind(&req.body)
+        .bind(if req.link.is_empty() {
+            None
+        } else {
+            Some(&req.link)
+        })
+        .execute(&self.db)
+        .await
+        .map_err(|e| {
+            error!("Database error creating notification: {}", e);
+            Status::from(AppError::from(e))
+        })?;
+
+        info!(
+            "Notification created: {} for user {}",
+            notification_id, req.user_id
+        );
+        Ok(Response::new(CreateNotificationResponse {
+            success: true,
+            message: "Notification created successfully".to_string(),
+            notification_id,
+        }))
+    }
+
+    async fn list_notifications(
+        &self,
+        request: Request<ListNotificationsRequest>,
+    ) -> Result<Response<ListNotificationsResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        let req = request.into_inner();
+
+        if req.user_id.is_empty() {
+            return Err(error::invalid_argument(
+                "MISSING_USER_ID",
+                "User ID is required",
+            ));
+        }
+        caller.require_owner(&req.user_id)?;
+
+        let page = PageRequest::new(req.page, req.page_size);
+
+        let notifications = sqlx::query_as::<_, DbNotification>(
+            "SELECT id, user_id, category, title, body, link, read_at, created_at
+             FROM notifications
+             WHERE user_id = $1 AND ($2 = FALSE OR read_at IS NULL)
+             ORDER BY created_at DESC
+             LIMIT $3 OFFSET $4",
+        )
+        .bind(&req.user_id)
+        .bind(req.unread_only)
+        .bind(page.limit())
+        .bind(page.offset())
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| {
+            error!("Database error listing notifications: {}", e);
+            Status::from(AppError::from(e))
+        })?;
+
+        let count: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM notifications WHERE user_id = $1 AND ($2 = FALSE OR read_at IS NULL)",
+        )
+        .bind(&req.user_id)
+        .bind(req.unread_only)
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| {
+            error!("Database error counting notifications: {}", e);
+            Status::from(AppError::from(e))
+        })?;
+
+        let unread_count = self.unread_count(&req.user_id).await?;
+
+        let proto_notifications: Vec<Notification> = notifications
+            .iter()
+            .map(|n| self.db_notification_to_proto(n))
+            .collect();
+
+        Ok(Response::new(ListNotificationsResponse {
+            success: true,
+            message: format!("Retrieved {} notifications", proto_notifications.len()),
+            notifications: proto_notifications,
+            total_count: count.0 as i32,
+            unread_count: unread_count as i32,
+        }))
+    }
+
+    async fn mark_read(
+        &self,
+        request: Request<MarkReadRequest>,
+    ) -> Result<Response<MarkReadResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        let req = request.into_inner();
+
+        if req.user_id.is_empty() {
+            return Err(error::invalid_argument(
+                "MISSING_USER_ID",
+                "User ID is required",
+            ));
+        }
+        caller.require_owner(&req.user_id)?;
+
+        if !req.all && req.notification_ids.is_empty() {
+            return Err(error::invalid_argument(
+                "MISSING_NOTIFICATION_IDS",
+                "Notification IDs are required unless marking all as read",
+            ));
+        }
+
+        // Scope the update to the user so one user can't mark another user's inbox
+        let result = if req.all {
+            sqlx::query(
+                "UPDATE notifications SET read_at = CURRENT_TIMESTAMP
+                 WHERE user_id = $1 AND read_at IS NULL",
+            )
+            .bind(&req.user_id)
+            .execute(&self.db)
+            .await
+        } else {
+            sqlx::query(
+                "UPDATE notifications SET read_at = CURRENT_TIMESTAMP
+                 WHERE user_id = $1 AND id = ANY($2) AND read_at IS NULL",
+            )
+            .bind(&req.user_id)
+            .bind(&req.notification_ids)
+            .execute(&self.db)
+            .await
+        }
+        .map_err(|e| {
+            error!("Database error marking notifications read: {}", e);
+            Status::from(AppError::from(e))
+        })?;
+
+        let unread_count = self.unread_count(&req.user_id).await?;
+
+        if result.rows_affected() == 0 {
+            warn!("No unread notifications updated for user {}", req.user_id);
+        }
+
+        Ok(Response::new(MarkReadResponse {
+            success: true,
+            message: format!("Marked {} notifications as read", result.rows_affected()),
+            updated_count: result.rows_affected() as i32,
+            unread_count: unread_count as i32,
+        }))
+    }
+
+    async fn get_unread_count(
+        &self,
+        request: Request<GetUnreadCountRequest>,
+    ) -> Result<Response<GetUnreadCountResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        let req = request.into_inner();
+
+        if req.user_id.is_empty() {
+            return Err(error::invalid_argument(
+                "MISSING_USER_ID",
+                "User ID is required",
+            ));
+        }
+        caller.require_owner(&req.user_id)?;
+
+        let unread_count = self.unread_count(&req.user_id).await?;
+
+        Ok(Response::new(GetUnreadCountResponse {
+            unread_count: unread_count as i32,
+        }))
+    }
+
+    async fn send_email(
+        &self,
+        request: Request<SendEmailRequest>,
+    ) -> Result<Response<SendResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        // Field rules are checked by the validation layer (see notification.proto)
+        let email = self
+            .templates
+            .email(&req.template, &req.variables)
+            .map_err(|e| template_error(&req.template, e))?;
+        let delivery = self
+            .queue(&NewDelivery {
+                channel: EMAIL,
+                user_id: Some(req.user_id.as_str()).filter(|id| !id.is_empty()),
+                recipient: &req.to,
+                template: &req.template,
+                subject: Some(&email.subject),
+                body: &email.text,
+                html_body: Some(&email.html),
+                event_id: None,
+            })
+            .await?;
+
+        Ok(Response::new(SendResponse {
+            success: true,
+            message: "Email queued".to_string(),
+            delivery: Some(delivery.to_proto()),
+        }))
+    }
+
+    async fn send_sms(
+        &self,
+        request: Request<SendSmsRequest>,
+    ) -> Result<Response<SendResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        let body = self
+            .templates
+            .sms(&req.template, &req.variables)
+            .map_err(|e| template_error(&req.template, e))?;
+        let delivery = self
+            .queue(&NewDelivery {
+                channel: SMS,
+                user_id: Some(req.user_id.as_str()).filter(|id| !id.is_empty()),
+                recipient: &req.to,
+                template: &req.template,
+                subject: None,
+                body: &body,
+                html_body: None,
+                event_id: None,
+            })
+            .await?;
+
+        Ok(Response::new(SendResponse {
+            success: true,
+            message: "SMS queued".to_string(),
+            delivery: Some(delivery.to_proto()),
+        }))
+    }
+
+    async fn get_delivery(
+        &self,
+        request: Request<GetDeliveryRequest>,
+    ) -> Result<Response<GetDeliveryResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        let delivery = sqlx::query_as::<_, DbDelivery>(&format!(
+            "SELECT {} FROM notification_deliveries WHERE id = $1",
+            DELIVERY_COLUMNS
+        ))
+        .bind(&req.delivery_id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(AppError::from)?
+        .ok_or_else(|| error::not_found("DELIVERY_NOT_FOUND", "Delivery not found"))?;
+
+        Ok(Response::new(GetDeliveryResponse {
+            success: true,
+            message: "Delivery retrieved successfully".to_string(),
+            delivery: Some(delivery.to_proto()),
+        }))
+    }
+}
@@ -0,0 +1,187 @@
+//! Notifies customers of what happens to their account and orders, from the
+//! events on the bus: a welcome in the inbox when they register, and an
+//! order confirmation by email and in the inbox when they place an order.
+//! A guest's confirmation carries the email token they claim the order
+//! with, which only ever goes to their address. Each event adds its
+//! notifications once, however often it's delivered.
+
+use crate::delivery::{self, EMAIL, NewDelivery};
+use crate::templates::{self, Templates};
+use anyhow::{Result, anyhow};
+use common::auth::{self, ServiceCredentials};
+use common::client::ServiceEndpoint;
+use common::events::{self, Delivery, Event, EventHandler};
+use proto::events::{OrderCreated, UserRegistered};
+use proto::user::{GetUserProfileRequest, User, user_service_client::UserServiceClient};
+use sqlx::{PgExecutor, PgPool};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Notify;
+use tonic::Code;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Consumer group of the notification service's instances.
+pub const GROUP: &str = "notification";
+
+/// Topics the service consumes.
+pub const TOPICS: [&str; 2] = [UserRegistered::TOPIC, OrderCreated::TOPIC];
+
+pub struct NotificationSubscriber {
+    db: PgPool,
+    templates: Arc<Templates>,
+    user_service: ServiceEndpoint,
+    wake: Arc<Notify>,
+}
+
+impl NotificationSubscriber {
+    pub fn new(
+        db: PgPool,
+        templates: Arc<Templates>,
+        user_service: ServiceEndpoint,
+        wake: Arc<Notify>,
+    ) -> Self {
+        Self {
+            db,
+            templates,
+            user_service,
+            wake,
+        }
+    }
+
+    async fn user_registered(&self, event_id: &str, event: &UserRegistered) -> Result<()> {
+        add_to_inbox(
+            &self.db,
+            event_id,
+            &event.user_id,
+            "account",
+            &format!("Welcome, {}!", event.username),
+            "Your account is ready. Happy shopping!",
+            None,
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn order_created(&self, event_id: &str, event: &OrderCreated) -> Result<()> {
+        // Guests are users too, so there's an email to confirm to either way
+        let Some(user) = self.user(&event.user_id).await? else {
+            warn!(
+                order_id = %event.order_id,
+                user_id = %event.user_id,
+                "No user to confirm the order to"
+            );
+            return Ok(());
+        };
+
+        let total = format!("{} {}", event.total_amount, event.currency);
+        let item_count: i32 = event.items.iter().map(|item| item.quantity).sum();
+        let mut variables = HashMap::from([
+            ("name".to_string(), user.username.clone()),
+            ("order_number".to_string(), event.order_number.clone()),
+            ("total".to_string(), total.clone()),
+            ("item_count".to_string(), item_count.to_string()),
+        ]);
+        let template = if user.guest {
+            let claim_token = auth::issue_email_token(&user.email.to_lowercase())?;
+            variables.insert("claim_token".to_string(), claim_token);
+            templates::GUEST_ORDER_CONFIRMATION
+        } else {
+            templates::ORDER_CONFIRMATION
+        };
+        let email = self.templates.email(template, &variables)?;
+
+        let mut tx = self.db.begin().await?;
+        add_to_inbox(
+            &mut *tx,
+            event_id,
+            &event.user_id,
+            "order",
+            &format!("Order {} received", event.order_number),
+            &format!("We've received your order, totalling {}.", total),
+            Some(&format!("/orders/{}", event.order_id)),
+        )
+        .await?;
+        let queued = delivery::queue(
+            &mut *tx,
+            &NewDelivery {
+                channel: EMAIL,
+                user_id: Some(&event.user_id),
+                recipient: &user.email,
+                template,
+                subject: Some(&email.subject),
+                body: &email.text,
+                html_body: Some(&email.html),
+                event_id: Some(event_id),
+            },
+        )
+        .await?;
+        tx.commit().await?;
+
+        if let Some(queued) = queued {
+            info!(order_id = %event.order_id, delivery_id = %queued.id, "Queued order confirmation");
+            self.wake.notify_one();
+        }
+        Ok(())
+    }
+
+    /// The user `user_id`, `None` when there's no such user.
+    async fn user(&self, user_id: &str) -> Result<Option<User>> {
+        let channel = self.user_service.primary_channel()?;
+        let mut client =
+            UserServiceClient::with_interceptor(channel, ServiceCredentials("notification"));
+        match client
+            .get_user_profile(GetUserProfileRequest {
+                user_id: user_id.to_string(),
+            })
+            .await
+        {
+            Ok(response) => Ok(response.into_inner().user),
+            Err(status) if status.code() == Code::NotFound => Ok(None),
+            Err(status) => Err(anyhow!("User service failed: {}", status.message())),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl EventHandler for NotificationSubscriber {
+    async fn handle(&self, delivery: &Delivery) -> Result<()> {
+        let envelope = &delivery.envelope;
+        if let Some(event) = events::decode::<UserRegistered>(envelope)? {
+            self.user_registered(&envelope.event_id, &event).await
+        } else if let Some(event) = events::decode::<OrderCreated>(envelope)? {
+            self.order_created(&envelope.event_id, &event).await
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Adds a notification to the inbox of `user_id`, unless event `event_id`
+/// already did.
+async fn add_to_inbox<'e>(
+    executor: impl PgExecutor<'e>,
+    event_id: &str,
+    user_id: &str,
+    category: &str,
+    title: &str,
+    body: &str,
+    link: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO notifications (id, user_id, category, title, body, link, event_id)
+         VALUES ($1, $2, $3, $4, $5, $6, $7)
+         ON CONFLICT (event_id) DO NOTHING",
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(user_id)
+    .bind(category)
+    .bind(title)
+    .bind(body)
+    .bind(link)
+    .bind(event_id)
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,145 @@
+//! Templates emails and SMS are rendered from, in `notification/templates`:
+//! for each name a subject, a text body, an HTML body and an SMS text.
+//! Rendering is strict, so a variable the caller didn't pass fails it
+//! rather than leaving a blank in a customer's inbox.
+
+use minijinja::{Environment, ErrorKind, UndefinedBehavior};
+use std::collections::HashMap;
+
+pub const ORDER_CONFIRMATION: &str = "order_confirmation";
+
+/// The order confirmation of a guest, with the token to claim the order.
+pub const GUEST_ORDER_CONFIRMATION: &str = "guest_order_confirmation";
+
+const TEMPLATES: [(&str, &str); 16] = [
+    (
+        "order_confirmation.subject.txt",
+        include_str!("../templates/order_confirmation.subject.txt"),
+    ),
+    (
+        "order_confirmation.txt",
+        include_str!("../templates/order_confirmation.txt"),
+    ),
+    (
+        "order_confirmation.html",
+        include_str!("../templates/order_confirmation.html"),
+    ),
+    (
+        "order_confirmation.sms.txt",
+        include_str!("../templates/order_confirmation.sms.txt"),
+    ),
+    (
+        "guest_order_confirmation.subject.txt",
+        include_str!("../templates/guest_order_confirmation.subject.txt"),
+    ),
+    (
+        "guest_order_confirmation.txt",
+        include_str!("../templates/guest_order_confirmation.txt"),
+    ),
+    (
+        "guest_order_confirmation.html",
+        include_str!("../templates/guest_order_confirmation.html"),
+    ),
+    (
+        "guest_order_confirmation.sms.txt",
+        include_str!("../templates/guest_order_confirmation.sms.txt"),
+    ),
+    (
+        "password_reset.subject.txt",
+        include_str!("../templates/password_reset.subject.txt"),
+    ),
+    (
+        "password_reset.txt",
+        include_str!("../templates/password_reset.txt"),
+    ),
+    (
+        "password_reset.html",
+        include_str!("../templates/password_reset.html"),
+    ),
+    (
+        "password_reset.sms.txt",
+        include_str!("../templates/password_reset.sms.txt"),
+    ),
+    (
+        "shipment_update.subject.txt",
+        include_str!("../templates/shipment_update.subject.txt"),
+    ),
+    (
+        "shipment_update.txt",
+        include_str!("../templates/shipment_update.txt"),
+    ),
+    (
+        "shipment_update.html",
+        include_str!("../templates/shipment_update.html"),
+    ),
+    (
+        "shipment_update.sms.txt",
+        include_str!("../templates/shipment_update.sms.txt"),
+    ),
+];
+
+/// An email rendered from a template.
+#[derive(Debug, Clone)]
+pub struct RenderedEmail {
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+}
+
+pub struct Templates {
+    env: Environment<'static>,
+}
+
+impl Templates {
+    pub fn new() -> Self {
+        let mut env = Environment::new();
+        env.set_undefined_behavior(UndefinedBehavior::Strict);
+        for (name, source) in TEMPLATES {
+            env.add_template(name, source)
+                .expect("Notification templates are valid");
+        }
+        Self { env }
+    }
+
+    /// Renders the email of template `name`. The HTML body escapes the
+    /// variables, the subject and text body don't.
+    pub fn email(
+        &self,
+        name: &str,
+        variables: &HashMap<String, String>,
+    ) -> Result<RenderedEmail, minijinja::Error> {
+        Ok(RenderedEmail {
+            subject: self.render(&format!("{}.subject.txt", name), variables)?,
+            text: self.render(&format!("{}.txt", name), variables)?,
+            html: self.render(&format!("{}.html", name), variables)?,
+        })
+    }
+
+    /// Renders the SMS text of template `name`.
+    pub fn sms(
+        &self,
+        name: &str,
+        variables: &HashMap<String, String>,
+    ) -> Result<String, minijinja::Error> {
+        self.render(&format!("{}.sms.txt", name), variables)
+    }
+
+    fn render(
+        &self,
+        file: &str,
+        variables: &HashMap<String, String>,
+    ) -> Result<String, minijinja::Error> {
+        self.env.get_template(file)?.render(variables)
+    }
+}
+
+impl Default for Templates {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whether rendering failed because no template has the name asked for.
+pub fn is_unknown(error: &minijinja::Error) -> bool {
+    error.kind() == ErrorKind::TemplateNotFound
+}
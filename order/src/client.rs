@@ -1,12 +1,19 @@
+use common::auth::ServiceCredentials;
 use proto::order::{
     CancelOrderRequest, CreateOrderRequest, GetOrderRequest, GetOrdersByUserRequest,
-    ListOrdersRequest, OrderItem, OrderStatus, UpdateOrderRequest,
-    order_service_client::OrderServiceClient,
+    ItemFulfillmentStatus, ListOrdersRequest, OrderItem, OrderItemStatus, OrderSort, OrderStatus,
+    UpdateOrderRequest, order_service_client::OrderServiceClient,
 };
+use tonic::transport::Channel;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let mut client = OrderServiceClient::connect("http://127.0.0.1:50053").await?;
+    // Runs the operator flows too, so calls are made as the platform
+    let channel = Channel::from_static("http://127.0.0.1:50053")
+        .connect()
+        .await?;
+    let mut client =
+        OrderServiceClient::with_interceptor(channel, ServiceCredentials("order-client"));
 
     println!("Connected to Order Service");
     println!("===========================\n");
@@ -30,18 +37,37 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 product_id: product_id_1.clone(),
                 product_name: String::new(),
                 quantity: 2,
-                unit_price: 0.0, // Will be set by server
-                subtotal: 0.0,   // Will be calculated by server
+                unit_price: String::new(), // Will be set by server
+                subtotal: String::new(),   // Will be calculated by server
+                item_id: String::new(),
+                tracking: vec![],
+                status: OrderItemStatus::Allocated as i32, // Set by server
+                tax_amount: String::new(),
+                fulfillment_status: ItemFulfillmentStatus::ItemUnfulfilled as i32,
+                shipment_tracking: String::new(),
+                shipped_at: 0,
             },
             OrderItem {
                 product_id: product_id_2.clone(),
                 product_name: String::new(),
                 quantity: 1,
-                unit_price: 0.0,
-                subtotal: 0.0,
+                unit_price: String::new(),
+                subtotal: String::new(),
+                item_id: String::new(),
+                tracking: vec![],
+                status: OrderItemStatus::Allocated as i32, // Set by server
+                tax_amount: String::new(),
+                fulfillment_status: ItemFulfillmentStatus::ItemUnfulfilled as i32,
+                shipment_tracking: String::new(),
+                shipped_at: 0,
             },
         ],
         shipping_address: "123 Main St, City, State 12345".to_string(),
+        shipping_region: "US-CA".to_string(),
+        shipping_method: String::new(),
+        currency: String::new(),
+        billing_region: String::new(),
+        guest_email: String::new(),
     };
 
     let create_response = client.create_order(create_request).await?;
@@ -51,12 +77,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("  Message: {}", create_result.message);
     println!("  Order ID: {}", create_result.order_id);
     if let Some(order) = &create_result.order {
-        println!("  Total Amount: ${:.2}", order.total_amount);
+        println!("  Total Amount: ${}", order.total_amount);
+        println!("  Tax: ${}", order.tax_amount);
         println!("  Status: {:?}", OrderStatus::try_from(order.status));
         println!("  Items count: {}", order.items.len());
         for (i, item) in order.items.iter().enumerate() {
             println!(
-                "    Item {}: Product {}, Qty: {}, Price: ${:.2}, Subtotal: ${:.2}",
+                "    Item {}: Product {}, Qty: {}, Price: ${}, Subtotal: ${}",
                 i + 1,
                 item.product_id,
                 item.quantity,
@@ -73,6 +100,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("2. Testing Get Order");
     let get_request = GetOrderRequest {
         order_id: order_id.clone(),
+        order_number: String::new(),
     };
 
     let get_response = client.get_order(get_request).await?;
@@ -82,8 +110,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("  Message: {}", get_result.message);
     if let Some(order) = &get_result.order {
         println!("  Order ID: {}", order.order_id);
+        println!("  Order Number: {}", order.order_number);
         println!("  User ID: {}", order.user_id);
-        println!("  Total: ${:.2}", order.total_amount);
+        println!("  Total: ${}", order.total_amount);
         println!("  Status: {:?}", OrderStatus::try_from(order.status));
         println!("  Shipping Address: {}", order.shipping_address);
     }
@@ -95,6 +124,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         page: 1,
         page_size: 10,
         status: 0, // All statuses
+        page_token: String::new(),
+        created_after: 0,
+        created_before: 0,
+        min_amount: String::new(),
+        max_amount: String::new(),
+        user_id: String::new(),
     };
 
     let list_response = client.list_orders(list_request).await?;
@@ -106,7 +141,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("  Orders in this page:");
     for order in &list_result.orders {
         println!(
-            "    - Order {}: ${:.2} - {:?}",
+            "    - Order {}: ${} - {:?}",
             order.order_id,
             order.total_amount,
             OrderStatus::try_from(order.status)
@@ -120,6 +155,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         user_id: user_id.clone(),
         page: 1,
         page_size: 10,
+        page_token: String::new(),
+        status: 0, // All statuses
+        sort: OrderSort::OldestFirst as i32,
     };
 
     let user_orders_response = client.get_orders_by_user(user_orders_request).await?;
@@ -130,7 +168,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("  Total Count: {}", user_orders_result.total_count);
     println!("  User's orders:");
     for order in &user_orders_result.orders {
-        println!("    - Order {}: ${:.2}", order.order_id, order.total_amount);
+        println!("    - Order {}: ${}", order.order_id, order.total_amount);
     }
     println!();
 
@@ -140,6 +178,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         order_id: order_id.clone(),
         status: OrderStatus::Confirmed as i32,
         shipping_address: "456 Updated St, New City, State 54321".to_string(),
+        reason: "Customer moved".to_string(),
     };
 
     let update_response = client.update_order(update_request).await?;
@@ -162,6 +201,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         order_id: order_id.clone(),
         status: OrderStatus::Processing as i32,
         shipping_address: String::new(), // Keep existing
+        reason: String::new(),
     };
 
     let update_response2 = client.update_order(update_request2).await?;
@@ -173,12 +213,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
     println!();
 
-    // Test 7: List orders by status
-    println!("7. Testing List Orders by Status (Processing)");
+    // Test 7: List the user's orders by status, from $10
+    println!("7. Testing List Orders by Status (Processing) and Filters");
     let list_by_status_request = ListOrdersRequest {
         page: 1,
         page_size: 10,
         status: OrderStatus::Processing as i32,
+        page_token: String::new(),
+        created_after: 0,
+        created_before: 0,
+        min_amount: "10.00".to_string(),
+        max_amount: String::new(),
+        user_id: user_id.clone(),
     };
 
     let list_by_status_response = client.list_orders(list_by_status_request).await?;
@@ -190,7 +236,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     );
     println!("  Processing orders:");
     for order in &list_by_status_result.orders {
-        println!("    - Order {}: ${:.2}", order.order_id, order.total_amount);
+        println!("    - Order {}: ${}", order.order_id, order.total_amount);
     }
     println!();
 
@@ -202,10 +248,22 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             product_id: product_id_1.clone(),
             product_name: String::new(),
             quantity: 1,
-            unit_price: 0.0,
-            subtotal: 0.0,
+            unit_price: String::new(),
+            subtotal: String::new(),
+            item_id: String::new(),
+            tracking: vec![],
+            status: OrderItemStatus::Allocated as i32,
+            tax_amount: String::new(),
+            fulfillment_status: ItemFulfillmentStatus::ItemUnfulfilled as i32,
+            shipment_tracking: String::new(),
+            shipped_at: 0,
         }],
         shipping_address: "789 Test Ave, Test City".to_string(),
+        shipping_region: "US-CA".to_string(),
+        shipping_method: String::new(),
+        currency: String::new(),
+        billing_region: String::new(),
+        guest_email: String::new(),
     };
 
     let create_response2 = client.create_order(create_request2).await?;
@@ -221,6 +279,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cancel_request = CancelOrderRequest {
         order_id: order_id_to_cancel.clone(),
         user_id: user_id.clone(),
+        reason: "Ordered by mistake".to_string(),
     };
 
     let cancel_response = client.cancel_order(cancel_request).await?;
@@ -234,6 +293,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("10. Testing Get Cancelled Order");
     let get_cancelled_request = GetOrderRequest {
         order_id: order_id_to_cancel.clone(),
+        order_number: String::new(),
     };
 
     let get_cancelled_response = client.get_order(get_cancelled_request).await?;
@@ -250,13 +310,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cancel_request2 = CancelOrderRequest {
         order_id: order_id_to_cancel.clone(),
         user_id: user_id.clone(),
+        reason: "Ordered by mistake".to_string(),
     };
 
-    let cancel_response2 = client.cancel_order(cancel_request2).await?;
-    let cancel_result2 = cancel_response2.into_inner();
+    // Cancelling twice fails with FAILED_PRECONDITION
+    let cancel_status2 = client
+        .cancel_order(cancel_request2)
+        .await
+        .expect_err("cancelled order was cancelled again");
     println!("Cancel Already Cancelled Order Response:");
-    println!("  Success: {}", cancel_result2.success);
-    println!("  Message: {}", cancel_result2.message);
+    println!("  Code: {:?}", cancel_status2.code());
+    println!("  Message: {}", cancel_status2.message());
     println!();
 
     println!("===========================");
@@ -0,0 +1,264 @@
+use crate::refund;
+use common::client::ServiceEndpoint;
+use common::error::AppError;
+use common::metrics::Metrics;
+use prometheus::{IntGaugeVec, Opts};
+use proto::ops::{ConsistencyReport, ConsistencyViolation};
+use proto::payment::PaymentStatus;
+use sqlx::PgPool;
+use sqlx::types::Decimal;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tonic::Status;
+use tracing::{error, info, warn};
+
+/// Maximum number of violations reported per check, so a systemic problem
+/// doesn't produce an unbounded report.
+const MAX_VIOLATIONS_PER_CHECK: i64 = 100;
+
+/// Orders whose payment is looked up per run, the most recently changed
+/// first; each one is a call to the payment service.
+const MAX_ORDERS_PER_PAYMENT_CHECK: i64 = 500;
+
+/// Names of the checks, as reported in `ConsistencyViolation.check`.
+const ORDER_TOTAL_MISMATCH: &str = "order_total_mismatch";
+const ORDER_WITHOUT_ITEMS: &str = "order_without_items";
+const CONFIRMED_WITHOUT_PAYMENT: &str = "confirmed_without_payment";
+const RESERVATION_MISMATCH: &str = "reservation_mismatch";
+const CAPTURE_MISMATCH: &str = "capture_mismatch";
+const CHECKS: [&str; 5] = [
+    ORDER_TOTAL_MISMATCH,
+    ORDER_WITHOUT_ITEMS,
+    CONFIRMED_WITHOUT_PAYMENT,
+    RESERVATION_MISMATCH,
+    CAPTURE_MISMATCH,
+];
+
+/// Cross-checks invariants between orders, their payments and the product
+/// catalog's stock and reservations, and keeps the latest report for the
+/// ops RPCs.
+#[derive(Clone)]
+pub struct ConsistencyChecker {
+    db: PgPool,
+    payment_service: Arc<ServiceEndpoint>,
+    last_report: Arc<RwLock<Option<ConsistencyReport>>>,
+    /// Violations the latest run found, by check
+    violations: IntGaugeVec,
+}
+
+impl ConsistencyChecker {
+    pub fn new(db: PgPool, payment_service: Arc<ServiceEndpoint>, metrics: &Metrics) -> Self {
+        let violations = IntGaugeVec::new(
+            Opts::new(
+                "consistency_violations",
+                "Violations found by the latest consistency check",
+            ),
+            &["check"],
+        )
+        .expect("Valid metric");
+        metrics
+            .registry()
+            .register(Box::new(violations.clone()))
+            .expect("Metric registered once");
+
+        Self {
+            db,
+            payment_service,
+            last_report: Arc::new(RwLock::new(None)),
+            violations,
+        }
+    }
+
+    pub async fn last_report(&self) -> Option<ConsistencyReport> {
+        self.last_report.read().await.clone()
+    }
+
+    /// Runs every check and stores the result as the latest report.
+    pub async fn run(&self) -> Result<ConsistencyReport, Status> {
+        let start = Instant::now();
+        let mut violations = Vec::new();
+
+        // Order totals must match their line items, tax and shipping
+        let mismatched: Vec<(String, String, String)> = sqlx::query_as(
+            "SELECT o.id, o.total_amount::TEXT,
+                    (COALESCE(SUM(oi.quantity * oi.price), 0) + o.tax_amount + o.shipping_fee)::TEXT
+             FROM orders o
+             LEFT JOIN order_items oi ON oi.order_id = o.id
+             GROUP BY o.id, o.total_amount, o.tax_amount, o.shipping_fee
+             HAVING o.total_amount <> COALESCE(SUM(oi.quantity * oi.price), 0) + o.tax_amount + o.shipping_fee
+             LIMIT $1",
+        )
+        .bind(MAX_VIOLATIONS_PER_CHECK)
+        .fetch_all(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        violations.extend(mismatched.into_iter().map(|(id, total, items_total)| {
+            ConsistencyViolation {
+                check: ORDER_TOTAL_MISMATCH.to_string(),
+                entity_id: id,
+                detail: format!(
+                    "Order total {} != items, tax and shipping {}",
+                    total, items_total
+                ),
+            }
+        }));
+
+        // Every live order must have at least one item
+        let empty_orders: Vec<String> = sqlx::query_scalar(
+            "SELECT o.id FROM orders o
+             WHERE o.status <> 'CANCELLED'
+               AND NOT EXISTS (SELECT 1 FROM order_items oi WHERE oi.order_id = o.id)
+             LIMIT $1",
+        )
+        .bind(MAX_VIOLATIONS_PER_CHECK)
+        .fetch_all(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        violations.extend(empty_orders.into_iter().map(|id| ConsistencyViolation {
+            check: ORDER_WITHOUT_ITEMS.to_string(),
+            entity_id: id,
+            detail: "Order has no items".to_string(),
+        }));
+
+        // Payments are the payment service's: a confirmed order must have one
+        // that went through, and what was captured must be the order's total
+        let paid: Vec<(String, String, Decimal)> = sqlx::query_as(
+            "SELECT id, status, total_amount FROM orders
+             WHERE status IN ('CONFIRMED', 'PROCESSING', 'PARTIALLY_SHIPPED', 'SHIPPED', 'DELIVERED')
+             ORDER BY updated_at DESC
+             LIMIT $1",
+        )
+        .bind(MAX_ORDERS_PER_PAYMENT_CHECK)
+        .fetch_all(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        let mut unpaid = Vec::new();
+        let mut captures = Vec::new();
+        for (id, status, total) in paid {
+            let payment = refund::order_payment(&self.payment_service, &id).await?;
+            match payment {
+                None if status == "CONFIRMED" => unpaid.push(id),
+                Some(payment)
+                    if matches!(
+                        PaymentStatus::try_from(payment.status),
+                        Ok(PaymentStatus::Captured
+                            | PaymentStatus::PartiallyRefunded
+                            | PaymentStatus::Refunded)
+                    ) && payment.amount.parse::<Decimal>() != Ok(total) =>
+                {
+                    captures.push((id, total, payment.amount));
+                }
+                _ => {}
+            }
+        }
+
+        violations.extend(
+            unpaid
+                .into_iter()
+                .take(MAX_VIOLATIONS_PER_CHECK as usize)
+                .map(|id| ConsistencyViolation {
+                    check: CONFIRMED_WITHOUT_PAYMENT.to_string(),
+                    entity_id: id,
+                    detail: "Confirmed order has no authorized payment".to_string(),
+                }),
+        );
+        violations.extend(
+            captures
+                .into_iter()
+                .take(MAX_VIOLATIONS_PER_CHECK as usize)
+                .map(|(id, total, captured)| ConsistencyViolation {
+                    check: CAPTURE_MISMATCH.to_string(),
+                    entity_id: id,
+                    detail: format!("Order total {} != captured {}", total, captured),
+                }),
+        );
+
+        // Reservations take stock up front, under the order's id, so the stock
+        // they hold is right only while their state follows the order's: open
+        // while its saga runs, confirmed once it's written, released otherwise
+        let reservations: Vec<(String, String, Option<String>, Option<String>)> = sqlx::query_as(
+            "SELECT r.id, r.status, s.state, o.status
+             FROM stock_reservations r
+             LEFT JOIN order_sagas s ON s.order_id = r.id
+             LEFT JOIN orders o ON o.id = r.id
+             WHERE (r.status = 'PENDING'
+                    AND COALESCE(s.state, 'NONE') NOT IN ('STARTED', 'ORDER_CREATED', 'COMPENSATING'))
+                OR (r.status = 'CONFIRMED' AND o.id IS NULL)
+                OR (r.status = 'RELEASED' AND o.status IS NOT NULL AND o.status <> 'CANCELLED')
+             LIMIT $1",
+        )
+        .bind(MAX_VIOLATIONS_PER_CHECK)
+        .fetch_all(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        violations.extend(
+            reservations
+                .into_iter()
+                .map(|(id, status, saga, order_status)| ConsistencyViolation {
+                    check: RESERVATION_MISMATCH.to_string(),
+                    entity_id: id,
+                    detail: format!(
+                        "Reservation is {} while the saga is {} and the order {}",
+                        status,
+                        saga.as_deref().unwrap_or("missing"),
+                        order_status.as_deref().unwrap_or("missing")
+                    ),
+                }),
+        );
+
+        let report = ConsistencyReport {
+            checked_at: chrono::Utc::now().timestamp(),
+            duration_ms: start.elapsed().as_millis() as i64,
+            violations,
+        };
+
+        for check in CHECKS {
+            let found = report
+                .violations
+                .iter()
+                .filter(|v| v.check == check)
+                .count();
+            self.violations
+                .with_label_values(&[check])
+                .set(found as i64);
+        }
+        *self.last_report.write().await = Some(report.clone());
+
+        Ok(report)
+    }
+
+    /// Spawns a background task that runs the checks every `interval`.
+    pub fn spawn(self, interval: Duration) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match self.run().await {
+                    Ok(report) if report.violations.is_empty() => {
+                        info!("Consistency check passed");
+                    }
+                    Ok(report) => {
+                        warn!(
+                            "Consistency check found {} violations",
+                            report.violations.len()
+                        );
+                        for v in &report.violations {
+                            warn!(
+                                check = %v.check,
+                                entity_id = %v.entity_id,
+                                "Consistency violation: {}",
+                                v.detail
+                            );
+                        }
+                    }
+                    Err(e) => error!("Consistency check failed: {}", e),
+                }
+            }
+        });
+    }
+}
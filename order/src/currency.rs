@@ -0,0 +1,219 @@
+//! Orders are charged in any of the store's accepted currencies. Product
+//! prices are kept in the default currency (the first accepted one) and
+//! converted at the rate in `exchange_rates` when an order is priced; the
+//! order keeps that rate, so later rate changes don't touch it.
+
+use common::error::AppError;
+use common::sandbox;
+use common::settings::SettingsStore;
+use sqlx::PgPool;
+use sqlx::types::Decimal;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tonic::Status;
+use tracing::{info, warn};
+
+/// Default currency when the store has none configured.
+const FALLBACK_CURRENCY: &str = "USD";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How amounts in the default currency are converted into an order's.
+#[derive(Debug, Clone)]
+pub struct Conversion {
+    pub currency: String,
+    /// Units of `currency` one unit of the default currency buys.
+    pub rate: Decimal,
+}
+
+impl Conversion {
+    /// The identity conversion into the default currency.
+    pub fn base(currency: &str) -> Self {
+        Self {
+            currency: currency.to_string(),
+            rate: Decimal::ONE,
+        }
+    }
+
+    /// Converts an amount in the default currency, rounded to the currency's
+    /// minor unit.
+    pub fn convert(&self, amount: Decimal) -> Decimal {
+        (amount * self.rate).round_dp(minor_units(&self.currency))
+    }
+}
+
+/// The store's default currency, which product prices are in.
+pub async fn default_currency(settings: &SettingsStore) -> Result<String, Status> {
+    Ok(settings
+        .currencies()
+        .await?
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| FALLBACK_CURRENCY.to_string()))
+}
+
+/// The conversion into `currency`, an ISO 4217 code; empty means the default
+/// currency. `Ok(Err(..))` says why the currency can't be charged.
+pub async fn conversion(
+    db: &PgPool,
+    settings: &SettingsStore,
+    currency: &str,
+) -> Result<Result<Conversion, String>, Status> {
+    let accepted = settings.currencies().await?;
+    let base = accepted
+        .first()
+        .cloned()
+        .unwrap_or_else(|| FALLBACK_CURRENCY.to_string());
+
+    let currency = currency.trim().to_ascii_uppercase();
+    if currency.is_empty() || currency == base {
+        return Ok(Ok(Conversion::base(&base)));
+    }
+    if !accepted.contains(&currency) {
+        return Ok(Err(format!("Currency {} is not accepted", currency)));
+    }
+
+    let rate: Option<Decimal> =
+        sqlx::query_scalar("SELECT rate FROM exchange_rates WHERE currency = $1")
+            .bind(&currency)
+            .fetch_optional(db)
+            .await
+            .map_err(AppError::from)?;
+
+    Ok(match rate {
+        Some(rate) => Ok(Conversion { currency, rate }),
+        None => Err(format!("No exchange rate for {} yet", currency)),
+    })
+}
+
+/// Digits after the decimal point of a currency's amounts.
+fn minor_units(currency: &str) -> u32 {
+    match currency {
+        "JPY" | "KRW" | "VND" | "CLP" | "ISK" => 0,
+        _ => 2,
+    }
+}
+
+/// Formats an amount for display, e.g. "$12.50", "€12.50" or "12.50 CHF".
+pub fn format_amount(amount: Decimal, currency: &str) -> String {
+    let amount = amount.round_dp(minor_units(currency));
+    let amount = format!("{:.*}", minor_units(currency) as usize, amount);
+    match currency {
+        "USD" => format!("${}", amount),
+        "EUR" => format!("€{}", amount),
+        "GBP" => format!("£{}", amount),
+        "JPY" => format!("¥{}", amount),
+        _ => format!("{} {}", amount, currency),
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct RatesResponse {
+    rates: HashMap<String, serde_json::Number>,
+}
+
+/// Keeps `exchange_rates` current for every accepted currency. Rates come
+/// from `GET <url>?base=<default currency>`, answered with
+/// `{"rates": {"EUR": 0.92, ...}}`; in sandbox mode every currency is quoted
+/// at `sandbox::EXCHANGE_RATE` instead.
+pub struct ExchangeRateRefresher {
+    db: PgPool,
+    settings: Arc<SettingsStore>,
+    url: String,
+    client: reqwest::Client,
+}
+
+impl ExchangeRateRefresher {
+    pub fn new(db: PgPool, settings: Arc<SettingsStore>, url: String) -> Self {
+        Self {
+            db,
+            settings,
+            url,
+            client: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Refreshes the rates; returns how many were updated.
+    pub async fn run(&self) -> Result<usize, Status> {
+        let accepted = self.settings.currencies().await?;
+        let Some((base, currencies)) = accepted.split_first() else {
+            return Ok(0);
+        };
+        if currencies.is_empty() {
+            return Ok(0);
+        }
+
+        let rates = if sandbox::enabled() {
+            let rate = Decimal::from(sandbox::EXCHANGE_RATE);
+            currencies.iter().map(|c| (c.clone(), rate)).collect()
+        } else {
+            self.fetch(base).await?
+        };
+
+        let mut updated = 0;
+        for currency in currencies {
+            let Some(rate) = rates.get(currency).filter(|r| **r > Decimal::ZERO) else {
+                warn!(currency = %currency, "No exchange rate quoted for accepted currency");
+                continue;
+            };
+            sqlx::query(
+                "INSERT INTO exchange_rates (currency, rate) VALUES ($1, $2)
+                 ON CONFLICT (currency)
+                 DO UPDATE SET rate = EXCLUDED.rate, updated_at = CURRENT_TIMESTAMP",
+            )
+            .bind(currency)
+            .bind(rate)
+            .execute(&self.db)
+            .await
+            .map_err(AppError::from)?;
+            updated += 1;
+        }
+
+        Ok(updated)
+    }
+
+    async fn fetch(&self, base: &str) -> Result<HashMap<String, Decimal>, Status> {
+        let response: RatesResponse = self
+            .client
+            .get(&self.url)
+            .query(&[("base", base)])
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| Status::unavailable(format!("Exchange rate provider error: {}", e)))?
+            .json()
+            .await
+            .map_err(|e| Status::internal(format!("Invalid exchange rates: {}", e)))?;
+
+        Ok(response
+            .rates
+            .into_iter()
+            .filter_map(|(currency, rate)| {
+                let rate = rate
+                    .to_string()
+                    .parse::<Decimal>()
+                    .or_else(|_| Decimal::from_scientific(&rate.to_string()))
+                    .ok()?;
+                Some((currency.to_ascii_uppercase(), rate.round_dp(8)))
+            })
+            .collect())
+    }
+
+    /// Spawns a background task that refreshes every `interval`.
+    pub fn spawn(self, interval: Duration) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match self.run().await {
+                    Ok(updated) => info!("Refreshed {} exchange rates", updated),
+                    Err(e) => warn!("Exchange rate refresh failed: {}", e),
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,180 @@
+//! Draft orders: quotes a buyer saves, e.g. to get a purchase approved,
+//! priced like orders but holding no stock. FinalizeDraftOrder places an
+//! open draft as an order at the draft's prices, and only then reserves the
+//! stock; drafts not finalized within their validity expire.
+
+use crate::currency;
+use chrono::{DateTime, Utc};
+use common::error::AppError;
+use proto::order::{
+    DraftOrder, DraftOrderStatus, ItemFulfillmentStatus, OrderItem, OrderItemStatus,
+};
+use sqlx::PgPool;
+use sqlx::types::Decimal;
+use tonic::Status;
+
+/// Days a draft stays valid unless the request says otherwise.
+const DEFAULT_VALID_DAYS: i32 = 30;
+const MAX_VALID_DAYS: i32 = 90;
+
+/// Draft columns; an open draft past `expires_at` reads as EXPIRED.
+const DRAFT_COLUMNS: &str = "id, user_id,
+    CASE WHEN status = 'OPEN' AND expires_at <= CURRENT_TIMESTAMP THEN 'EXPIRED' ELSE status END AS status,
+    total_amount, tax_amount, shipping_fee, currency, exchange_rate, shipping_address,
+    shipping_region, shipping_method, billing_region, min_transit_days, max_transit_days,
+    order_id, expires_at, created_at";
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct DbDraftOrder {
+    pub id: String,
+    pub user_id: String,
+    pub status: String,
+    pub total_amount: Decimal,
+    pub tax_amount: Decimal,
+    pub shipping_fee: Decimal,
+    pub currency: String,
+    pub exchange_rate: Decimal,
+    pub shipping_address: Option<String>,
+    pub shipping_region: Option<String>,
+    pub shipping_method: Option<String>,
+    pub billing_region: Option<String>,
+    pub min_transit_days: Option<i32>,
+    pub max_transit_days: Option<i32>,
+    pub order_id: Option<String>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl DbDraftOrder {
+    pub fn to_proto(&self, items: Vec<OrderItem>) -> DraftOrder {
+        let status = match self.status.as_str() {
+            "FINALIZED" => DraftOrderStatus::DraftFinalized,
+            "EXPIRED" => DraftOrderStatus::DraftExpired,
+            _ => DraftOrderStatus::DraftOpen,
+        };
+        DraftOrder {
+            draft_id: self.id.clone(),
+            user_id: self.user_id.clone(),
+            items,
+            total_amount: self.total_amount.to_string(),
+            tax_amount: self.tax_amount.to_string(),
+            shipping_fee: self.shipping_fee.to_string(),
+            currency: self.currency.clone(),
+            formatted_total: currency::format_amount(self.total_amount, &self.currency),
+            status: status as i32,
+            expires_at: self.expires_at.timestamp(),
+            created_at: self.created_at.timestamp(),
+            order_id: self.order_id.clone().unwrap_or_default(),
+            shipping_address: self.shipping_address.clone().unwrap_or_default(),
+            shipping_region: self.shipping_region.clone().unwrap_or_default(),
+            shipping_method: self.shipping_method.clone().unwrap_or_default(),
+            billing_region: self.billing_region.clone().unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct DbDraftItem {
+    pub product_id: String,
+    pub quantity: i32,
+    pub price: Decimal,
+    pub tax_amount: Decimal,
+    pub product_type: String,
+    pub warranty_months: i32,
+}
+
+impl DbDraftItem {
+    pub fn to_proto(&self, product_name: String) -> OrderItem {
+        OrderItem {
+            product_id: self.product_id.clone(),
+            product_name,
+            quantity: self.quantity,
+            unit_price: self.price.to_string(),
+            subtotal: (self.price * Decimal::from(self.quantity)).to_string(),
+            item_id: String::new(),
+            tracking: vec![],
+            status: OrderItemStatus::Allocated as i32,
+            tax_amount: self.tax_amount.to_string(),
+            fulfillment_status: ItemFulfillmentStatus::ItemUnfulfilled as i32,
+            shipment_tracking: String::new(),
+            shipped_at: 0,
+        }
+    }
+}
+
+/// Days a draft is valid for when `requested` were asked for; `Err` says
+/// why that's not allowed.
+pub fn valid_days(requested: i32) -> Result<i32, String> {
+    match requested {
+        0 => Ok(DEFAULT_VALID_DAYS),
+        1..=MAX_VALID_DAYS => Ok(requested),
+        _ => Err(format!(
+            "Drafts can be valid for at most {} days",
+            MAX_VALID_DAYS
+        )),
+    }
+}
+
+/// A draft and its items, in the order they were added.
+pub async fn load(
+    db: &PgPool,
+    draft_id: &str,
+) -> Result<Option<(DbDraftOrder, Vec<DbDraftItem>)>, Status> {
+    let draft = sqlx::query_as::<_, DbDraftOrder>(&format!(
+        "SELECT {} FROM draft_orders WHERE id = $1",
+        DRAFT_COLUMNS
+    ))
+    .bind(draft_id)
+    .fetch_optional(db)
+    .await
+    .map_err(AppError::from)?;
+    let Some(draft) = draft else {
+        return Ok(None);
+    };
+
+    let items = sqlx::query_as::<_, DbDraftItem>(
+        "SELECT product_id, quantity, price, tax_amount, product_type, warranty_months
+         FROM draft_order_items
+         WHERE draft_id = $1 ORDER BY position",
+    )
+    .bind(draft_id)
+    .fetch_all(db)
+    .await
+    .map_err(AppError::from)?;
+
+    Ok(Some((draft, items)))
+}
+
+/// Marks an open, unexpired draft as being placed as `order_id`; `false`
+/// when it's no longer open, e.g. a concurrent call finalized it first.
+pub async fn claim(db: &PgPool, draft_id: &str, order_id: &str) -> Result<bool, Status> {
+    let result = sqlx::query(
+        "UPDATE draft_orders
+         SET status = 'FINALIZED', order_id = $2, updated_at = CURRENT_TIMESTAMP
+         WHERE id = $1 AND status = 'OPEN' AND expires_at > CURRENT_TIMESTAMP",
+    )
+    .bind(draft_id)
+    .bind(order_id)
+    .execute(db)
+    .await
+    .map_err(AppError::from)?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Reopens a draft whose order couldn't be placed, so it can be finalized
+/// again.
+pub async fn release(db: &PgPool, draft_id: &str, order_id: &str) -> Result<(), Status> {
+    sqlx::query(
+        "UPDATE draft_orders
+         SET status = 'OPEN', order_id = NULL, updated_at = CURRENT_TIMESTAMP
+         WHERE id = $1 AND order_id = $2",
+    )
+    .bind(draft_id)
+    .bind(order_id)
+    .execute(db)
+    .await
+    .map_err(AppError::from)?;
+
+    Ok(())
+}
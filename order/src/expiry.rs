@@ -0,0 +1,126 @@
+//! Cancels orders left PENDING, i.e. never paid, for longer than a TTL, so
+//! the stock they hold goes back on sale.
+
+use crate::saga;
+use crate::stock_return;
+use common::client::ServiceEndpoint;
+use common::error::AppError;
+use common::order_events::{self, EventType, OrderEvent};
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+use tonic::Status;
+use tracing::{info, warn};
+
+/// Orders expired per sweep.
+const MAX_EXPIRIES_PER_SWEEP: i64 = 100;
+
+pub struct PendingOrderExpiry {
+    db: PgPool,
+    product_service: Arc<ServiceEndpoint>,
+    ttl: Duration,
+}
+
+impl PendingOrderExpiry {
+    pub fn new(db: PgPool, product_service: Arc<ServiceEndpoint>, ttl: Duration) -> Self {
+        Self {
+            db,
+            product_service,
+            ttl,
+        }
+    }
+
+    /// Cancels the expired orders; returns how many were cancelled.
+    pub async fn run(&self) -> Result<usize, Status> {
+        // Orders whose CreateOrder saga is unfinished are left to the saga
+        // recovery sweep, which still has to settle their reservation. Orders
+        // released from a fraud hold get the full TTL from their approval
+        let expired: Vec<String> = sqlx::query_scalar(
+            "SELECT o.id FROM orders o
+             WHERE o.status = 'PENDING'
+               AND o.created_at < CURRENT_TIMESTAMP - make_interval(secs => $1)
+               AND NOT EXISTS (
+                   SELECT 1 FROM order_sagas s WHERE s.order_id = o.id AND s.state <> $2
+               )
+               AND NOT EXISTS (
+                   SELECT 1 FROM order_events e
+                   WHERE e.order_id = o.id AND e.from_status = 'ON_HOLD' AND e.to_status = 'PENDING'
+                     AND e.created_at >= CURRENT_TIMESTAMP - make_interval(secs => $1)
+               )
+             ORDER BY o.created_at
+             LIMIT $3",
+        )
+        .bind(self.ttl.as_secs_f64())
+        .bind(saga::COMPLETED)
+        .bind(MAX_EXPIRIES_PER_SWEEP)
+        .fetch_all(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        let mut cancelled = 0;
+        for order_id in expired {
+            match self.expire(&order_id).await {
+                Ok(true) => cancelled += 1,
+                Ok(false) => {}
+                Err(e) => warn!(order_id = %order_id, "Failed to expire pending order: {}", e),
+            }
+        }
+
+        Ok(cancelled)
+    }
+
+    /// Cancels an order if it's still pending; `false` when it moved on,
+    /// e.g. it was paid since it was selected.
+    async fn expire(&self, order_id: &str) -> Result<bool, Status> {
+        let mut tx = self.db.begin().await.map_err(AppError::from)?;
+
+        let result = sqlx::query(
+            "UPDATE orders SET status = 'CANCELLED', updated_at = CURRENT_TIMESTAMP
+             WHERE id = $1 AND status = 'PENDING'",
+        )
+        .bind(order_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        let actor = order_events::system_actor("order");
+        let reason = format!(
+            "Not paid within {} minutes",
+            self.ttl.as_secs().div_ceil(60)
+        );
+        order_events::record(
+            &mut *tx,
+            &OrderEvent::new(order_id, EventType::Cancelled, &actor)
+                .status(Some("PENDING"), "CANCELLED")
+                .reason(&reason),
+        )
+        .await?;
+
+        let stock_return = stock_return::record_order(&mut tx, order_id).await?;
+
+        tx.commit().await.map_err(AppError::from)?;
+
+        stock_return::send_recorded(&self.db, &self.product_service, stock_return).await;
+
+        info!(order_id = %order_id, "Cancelled pending order: {}", reason);
+        Ok(true)
+    }
+
+    /// Spawns a background task that sweeps every `interval`.
+    pub fn spawn(self, interval: Duration) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match self.run().await {
+                    Ok(0) => {}
+                    Ok(cancelled) => info!("Cancelled {} expired pending orders", cancelled),
+                    Err(e) => warn!("Pending order expiry sweep failed: {}", e),
+                }
+            }
+        });
+    }
+}
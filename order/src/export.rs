@@ -0,0 +1,191 @@
+//! ExportOrders: orders streamed oldest first as flat rows, one per item.
+//! Every message ends on a whole order and carries the cursor after it, so
+//! an interrupted export resumes without gaps or duplicates.
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use common::error::AppError;
+use common::pagination::{Cursor, SortOrder};
+use proto::order::{ExportOrdersRequest, ExportOrdersResponse, OrderExportRow};
+use sqlx::types::Decimal;
+use sqlx::{PgPool, Postgres, QueryBuilder};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::Status;
+
+const DEFAULT_PAGE_SIZE: i32 = 500;
+const MAX_PAGE_SIZE: i32 = 1000;
+
+#[derive(Debug, sqlx::FromRow)]
+struct DbExportRow {
+    order_id: String,
+    order_number: String,
+    user_id: String,
+    status: String,
+    currency: String,
+    exchange_rate: Decimal,
+    total_amount: Decimal,
+    tax_amount: Decimal,
+    shipping_fee: Decimal,
+    shipping_region: Option<String>,
+    created_at: DateTime<Utc>,
+    item_id: Option<String>,
+    product_id: Option<String>,
+    quantity: Option<i32>,
+    price: Option<Decimal>,
+    item_tax: Option<Decimal>,
+    item_status: Option<String>,
+    fulfillment_status: Option<String>,
+}
+
+impl DbExportRow {
+    fn cursor(&self) -> Cursor {
+        Cursor::new(
+            self.created_at.to_rfc3339_opts(SecondsFormat::Micros, true),
+            self.order_id.clone(),
+        )
+    }
+
+    fn into_proto(self) -> OrderExportRow {
+        let amount = |value: Option<Decimal>| value.map(|v| v.to_string()).unwrap_or_default();
+        OrderExportRow {
+            order_id: self.order_id,
+            order_number: self.order_number,
+            user_id: self.user_id,
+            status: self.status,
+            currency: self.currency,
+            exchange_rate: self.exchange_rate.to_string(),
+            order_total: self.total_amount.to_string(),
+            order_tax: self.tax_amount.to_string(),
+            shipping_fee: self.shipping_fee.to_string(),
+            shipping_region: self.shipping_region.unwrap_or_default(),
+            created_at: self.created_at.timestamp(),
+            item_id: self.item_id.unwrap_or_default(),
+            product_id: self.product_id.unwrap_or_default(),
+            quantity: self.quantity.unwrap_or_default(),
+            unit_price: amount(self.price),
+            item_tax: amount(self.item_tax),
+            item_status: self.item_status.unwrap_or_default(),
+            fulfillment_status: self.fulfillment_status.unwrap_or_default(),
+        }
+    }
+}
+
+/// What an export covers; `None` leaves a bound open.
+pub struct Export {
+    created_after: Option<DateTime<Utc>>,
+    created_before: Option<DateTime<Utc>>,
+    after: Option<Cursor>,
+    page_size: i32,
+}
+
+impl Export {
+    /// Parses a request; `Err` says which field is invalid.
+    pub fn parse(req: &ExportOrdersRequest) -> Result<Self, String> {
+        let time = |seconds: i64, field: &str| match seconds {
+            0 => Ok(None),
+            _ => DateTime::from_timestamp(seconds, 0)
+                .map(Some)
+                .ok_or_else(|| format!("Invalid {}", field)),
+        };
+        let created_after = time(req.created_after, "created_after")?;
+        let created_before = time(req.created_before, "created_before")?;
+        if let (Some(after), Some(before)) = (created_after, created_before)
+            && after >= before
+        {
+            return Err("created_after must be before created_before".to_string());
+        }
+
+        let after = if req.cursor.is_empty() {
+            None
+        } else {
+            Some(Cursor::decode(&req.cursor).ok_or_else(|| "Invalid cursor".to_string())?)
+        };
+
+        Ok(Self {
+            created_after,
+            created_before,
+            after,
+            page_size: if req.page_size <= 0 || req.page_size > MAX_PAGE_SIZE {
+                DEFAULT_PAGE_SIZE
+            } else {
+                req.page_size
+            },
+        })
+    }
+
+    /// Rows of the next `page_size` orders after the cursor, items in id order.
+    async fn page(&self, db: &PgPool) -> Result<Vec<DbExportRow>, Status> {
+        let mut qb: QueryBuilder<'_, Postgres> = QueryBuilder::new(
+            "SELECT o.id AS order_id, o.order_number, o.user_id, o.status, o.currency, o.exchange_rate,
+                    o.total_amount, o.tax_amount, o.shipping_fee, o.shipping_region, o.created_at,
+                    oi.id AS item_id, oi.product_id, oi.quantity, oi.price, oi.tax_amount AS item_tax,
+                    oi.status AS item_status, oi.fulfillment_status
+             FROM (SELECT * FROM orders WHERE TRUE",
+        );
+        if let Some(after) = self.created_after {
+            qb.push(" AND created_at >= ").push_bind(after);
+        }
+        if let Some(before) = self.created_before {
+            qb.push(" AND created_at < ").push_bind(before);
+        }
+        if let Some(cursor) = &self.after {
+            cursor.push_after(&mut qb, "created_at", "TIMESTAMPTZ", "id", SortOrder::Asc);
+        }
+        qb.push(" ORDER BY created_at, id LIMIT ")
+            .push_bind(i64::from(self.page_size))
+            .push(
+                ") o
+                 LEFT JOIN order_items oi ON oi.order_id = o.id
+                 ORDER BY o.created_at, o.id, oi.id",
+            );
+
+        qb.build_query_as()
+            .fetch_all(db)
+            .await
+            .map_err(|e| AppError::from(e).into())
+    }
+
+    /// Streams the export a page of orders per message.
+    pub fn stream(mut self, db: PgPool) -> ReceiverStream<Result<ExportOrdersResponse, Status>> {
+        // A small channel keeps at most a few pages in memory; the producer
+        // waits for the client to catch up.
+        let (tx, rx) = mpsc::channel(4);
+
+        tokio::spawn(async move {
+            loop {
+                let rows = match self.page(&db).await {
+                    Ok(rows) if rows.is_empty() => break,
+                    Ok(rows) => rows,
+                    Err(status) => {
+                        let _ = tx.send(Err(status)).await;
+                        break;
+                    }
+                };
+
+                let mut orders = 0;
+                let mut last_order: Option<&str> = None;
+                for row in &rows {
+                    if last_order != Some(row.order_id.as_str()) {
+                        orders += 1;
+                        last_order = Some(&row.order_id);
+                    }
+                }
+                let is_last_page = orders < self.page_size;
+                let cursor = rows.last().map(DbExportRow::cursor);
+
+                let message = ExportOrdersResponse {
+                    cursor: cursor.as_ref().map(Cursor::encode).unwrap_or_default(),
+                    rows: rows.into_iter().map(DbExportRow::into_proto).collect(),
+                };
+                self.after = cursor;
+
+                // Stop once the client has gone away
+                if tx.send(Ok(message)).await.is_err() || is_last_page {
+                    break;
+                }
+            }
+        });
+
+        ReceiverStream::new(rx)
+    }
+}
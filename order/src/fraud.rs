@@ -0,0 +1,122 @@
+//! Screens new orders for fraud before they're written. Orders a checker
+//! flags are placed ON_HOLD instead of PENDING and can't be paid until an
+//! admin approves them with ApproveOrder, or rejects them with RejectOrder.
+
+use anyhow::{Result, anyhow};
+use common::error::AppError;
+use common::sandbox;
+use sqlx::PgPool;
+use sqlx::types::Decimal;
+use std::env;
+use std::sync::Arc;
+use tonic::Status;
+
+/// What fraud screening sees of an order about to be placed.
+pub struct OrderCandidate<'a> {
+    pub user_id: &'a str,
+    /// Order total converted back into the default currency.
+    pub total: Decimal,
+    /// ISO 3166 codes; empty when not given.
+    pub shipping_region: &'a str,
+    pub billing_region: &'a str,
+}
+
+/// Decides whether an order needs manual review.
+#[tonic::async_trait]
+pub trait FraudChecker: Send + Sync {
+    /// Why the order should be held for review; empty lets it through.
+    async fn check(&self, order: &OrderCandidate<'_>) -> Result<Vec<String>, Status>;
+}
+
+/// The checker for this environment. Sandbox mode holds nothing, so test
+/// orders go straight through.
+pub fn from_env(db: PgPool) -> Result<Arc<dyn FraudChecker>> {
+    if sandbox::enabled() {
+        return Ok(Arc::new(NoopFraudChecker));
+    }
+
+    // 0 disables either rule
+    let max_orders_per_hour: i64 = env::var("FRAUD_MAX_ORDERS_PER_HOUR")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(5);
+    let review_amount: Decimal = match env::var("FRAUD_REVIEW_AMOUNT") {
+        Ok(v) => v
+            .parse()
+            .map_err(|_| anyhow!("Invalid FRAUD_REVIEW_AMOUNT: {}", v))?,
+        Err(_) => Decimal::from(1000),
+    };
+
+    Ok(Arc::new(RuleBasedChecker {
+        db,
+        max_orders_per_hour,
+        review_amount,
+    }))
+}
+
+/// Lets every order through.
+pub struct NoopFraudChecker;
+
+#[tonic::async_trait]
+impl FraudChecker for NoopFraudChecker {
+    async fn check(&self, _order: &OrderCandidate<'_>) -> Result<Vec<String>, Status> {
+        Ok(vec![])
+    }
+}
+
+/// Holds orders of users placing many orders in a short time, large orders,
+/// and orders billed to another country than they ship to.
+pub struct RuleBasedChecker {
+    db: PgPool,
+    /// Orders a user may place within an hour before the next is held.
+    max_orders_per_hour: i64,
+    /// Totals at or above this, in the default currency, are held.
+    review_amount: Decimal,
+}
+
+#[tonic::async_trait]
+impl FraudChecker for RuleBasedChecker {
+    async fn check(&self, order: &OrderCandidate<'_>) -> Result<Vec<String>, Status> {
+        let mut reasons = Vec::new();
+
+        if self.max_orders_per_hour > 0 {
+            let recent: i64 = sqlx::query_scalar(
+                "SELECT COUNT(*) FROM orders
+                 WHERE user_id = $1 AND created_at > CURRENT_TIMESTAMP - INTERVAL '1 hour'",
+            )
+            .bind(order.user_id)
+            .fetch_one(&self.db)
+            .await
+            .map_err(AppError::from)?;
+            if recent >= self.max_orders_per_hour {
+                reasons.push(format!("{} orders placed in the last hour", recent));
+            }
+        }
+
+        if self.review_amount > Decimal::ZERO && order.total >= self.review_amount {
+            reasons.push(format!(
+                "Total {} is at or above the review amount of {}",
+                order.total.round_dp(2),
+                self.review_amount
+            ));
+        }
+
+        let billing = country(order.billing_region);
+        let shipping = country(order.shipping_region);
+        if !billing.is_empty() && !shipping.is_empty() && billing != shipping {
+            reasons.push(format!("Billed to {} but shipped to {}", billing, shipping));
+        }
+
+        Ok(reasons)
+    }
+}
+
+/// Country part of a region code, e.g. " us-ca" -> "US".
+fn country(region: &str) -> String {
+    region
+        .trim()
+        .split('-')
+        .next()
+        .unwrap_or_default()
+        .to_ascii_uppercase()
+}
@@ -0,0 +1,159 @@
+//! Guest checkout. Orders placed without an account belong to a guest
+//! identity, one per email address, which the user service keeps. The
+//! order's confirmation email carries an email token for the address; once
+//! the guest registers with it and confirms it with that token,
+//! ClaimGuestOrders moves the guest's orders to their account.
+
+use common::auth::ServiceCredentials;
+use common::client::ServiceEndpoint;
+use common::error::AppError;
+use common::order_events::{self, EventType, OrderEvent};
+use common::resilience::{self, CircuitBreaker};
+use proto::user::user_service_client::UserServiceClient;
+use proto::user::{GetGuestIdentityRequest, GetUserProfileRequest, User};
+use sqlx::PgPool;
+use tonic::service::interceptor::InterceptedService;
+use tonic::transport::Channel;
+use tonic::{Code, Status};
+
+/// Normalizes an email address, e.g. " Ann@Example.com" -> "ann@example.com".
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+type UserClient =
+    UserServiceClient<InterceptedService<CircuitBreaker<Channel>, ServiceCredentials>>;
+
+async fn connect(endpoint: &ServiceEndpoint) -> Result<UserClient, Status> {
+    let channel = endpoint
+        .primary_channel()
+        .map_err(|e| Status::internal(format!("Invalid user service URL: {}", e)))?;
+    Ok(UserServiceClient::with_interceptor(
+        endpoint.guard(channel),
+        ServiceCredentials("order"),
+    ))
+}
+
+/// Id of the guest identity of `email`, created when there's none and
+/// `create` is set.
+async fn guest_identity(
+    endpoint: &ServiceEndpoint,
+    email: &str,
+    create: bool,
+) -> Result<Option<String>, Status> {
+    let request = GetGuestIdentityRequest {
+        email: normalize_email(email),
+        create,
+    };
+    let client = connect(endpoint).await?;
+    let response = resilience::retry(endpoint.retry_policy(), "GetGuestIdentity", || {
+        let mut client = client.clone();
+        let request = request.clone();
+        async move { client.get_guest_identity(request).await }
+    })
+    .await
+    .map_err(|e| resilience::downstream_error("User", e))?;
+
+    let user_id = response.into_inner().user_id;
+    Ok(Some(user_id).filter(|id| !id.is_empty()))
+}
+
+/// Id of the guest identity of an email address, created on first use.
+pub async fn identity(endpoint: &ServiceEndpoint, email: &str) -> Result<String, Status> {
+    guest_identity(endpoint, email, true)
+        .await?
+        .ok_or_else(|| Status::internal("Guest identity was not created"))
+}
+
+/// The user `user_id`; `None` when there's no such user.
+async fn user(endpoint: &ServiceEndpoint, user_id: &str) -> Result<Option<User>, Status> {
+    let request = GetUserProfileRequest {
+        user_id: user_id.to_string(),
+    };
+    let client = connect(endpoint).await?;
+    let result = resilience::retry(endpoint.retry_policy(), "GetUserProfile", || {
+        let mut client = client.clone();
+        let request = request.clone();
+        async move { client.get_user_profile(request).await }
+    })
+    .await;
+
+    match result {
+        Ok(response) => Ok(response.into_inner().user),
+        Err(status) if status.code() == Code::NotFound => Ok(None),
+        Err(status) => Err(resilience::downstream_error("User", status)),
+    }
+}
+
+/// Moves the orders and warranties of the guest identity of `email` to the
+/// registered user `user_id`, whose verified address it must be, recording
+/// `actor` as having claimed them; returns the ids of the orders moved.
+/// `Ok(Err(..))` says why the user can't claim them.
+pub async fn claim(
+    db: &PgPool,
+    user_service: &ServiceEndpoint,
+    user_id: &str,
+    email: &str,
+    actor: &str,
+) -> Result<Result<Vec<String>, String>, Status> {
+    let email = normalize_email(email);
+
+    let registered = match user(user_service, user_id).await? {
+        Some(user) if !user.guest => user,
+        _ => {
+            return Ok(Err(
+                "Only registered users can claim guest orders".to_string()
+            ));
+        }
+    };
+    if normalize_email(&registered.email) != email {
+        return Ok(Err(
+            "Guest orders can only be claimed by the account registered with their email address"
+                .to_string(),
+        ));
+    }
+    // Anyone can register with any address; only its owner can confirm it
+    if !registered.email_verified {
+        return Ok(Err(
+            "Confirm your email address before claiming guest orders".to_string(),
+        ));
+    }
+
+    let Some(guest_id) = guest_identity(user_service, &email, false).await? else {
+        return Ok(Ok(vec![]));
+    };
+
+    let mut tx = db.begin().await.map_err(AppError::from)?;
+
+    // A claim running alongside finds the orders already moved
+    let order_ids: Vec<String> = sqlx::query_scalar(
+        "UPDATE orders SET user_id = $1, updated_at = CURRENT_TIMESTAMP
+         WHERE user_id = $2
+         RETURNING id",
+    )
+    .bind(user_id)
+    .bind(&guest_id)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(AppError::from)?;
+
+    sqlx::query("UPDATE warranties SET user_id = $1 WHERE user_id = $2")
+        .bind(user_id)
+        .bind(&guest_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+
+    let details = format!("Claimed from guest {}", guest_id);
+    for order_id in &order_ids {
+        order_events::record(
+            &mut *tx,
+            &OrderEvent::new(order_id, EventType::Claimed, actor).details(&details),
+        )
+        .await?;
+    }
+
+    tx.commit().await.map_err(AppError::from)?;
+
+    Ok(Ok(order_ids))
+}
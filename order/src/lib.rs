@@ -0,0 +1,21 @@
+pub mod consistency;
+pub mod currency;
+pub mod draft;
+pub mod expiry;
+pub mod export;
+pub mod fraud;
+pub mod guest;
+pub mod ops;
+pub mod order;
+pub mod order_number;
+pub mod recall;
+pub mod refund;
+pub mod repository;
+pub mod reporting;
+pub mod saga;
+pub mod shipping;
+pub mod stock_return;
+pub mod tax;
+pub mod user_verification;
+pub mod warranty;
+pub mod webhook;
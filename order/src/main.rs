@@ -1,21 +1,88 @@
-mod order;
-
 use anyhow::Result;
-use order::OrderServiceImpl;
+use common::client::ServiceEndpoint;
+use common::health::HealthCheck;
+use common::idempotency;
+use common::outbox::{self, OutboxRelay};
+use common::settings::{SettingsServiceImpl, SettingsStore};
+use common::metrics::{self, Metrics};
+use common::resilience::{CircuitBreakerConfig, RetryPolicy};
+use common::telemetry;
+use common::tls;
+use common::slo::{SloConfig, SloServiceImpl, SloTracker};
+use common::server::ServerBuilder;
+use order::consistency::ConsistencyChecker;
+use order::currency::ExchangeRateRefresher;
+use order::expiry::PendingOrderExpiry;
+use order::fraud;
+use order::ops::OpsServiceImpl;
+use order::order::{Downstream, OrderServiceImpl};
+use order::order_number::OrderNumberFormat;
+use order::user_verification::VerifiedUsers;
+use proto::ops::ops_service_server::OpsServiceServer;
 use proto::order::order_service_server::OrderServiceServer;
+use proto::recall::recall_service_server::RecallServiceServer;
+use proto::reporting::reporting_service_server::ReportingServiceServer;
+use proto::settings::settings_service_server::SettingsServiceServer;
+use proto::slo::slo_service_server::SloServiceServer;
+use proto::tax::tax_service_server::TaxServiceServer;
+use proto::warranty::warranty_service_server::WarrantyServiceServer;
+use proto::webhook::webhook_service_server::WebhookServiceServer;
+use order::recall::RecallServiceImpl;
+use order::reporting::ReportingServiceImpl;
+use order::saga::SagaRecovery;
+use order::stock_return::StockReturns;
 use sqlx::postgres::PgPoolOptions;
 use std::env;
-use tonic::transport::Server;
+use std::sync::Arc;
+use std::time::Duration;
+use order::tax::{self, TaxServiceImpl};
+use tracing::{info, warn};
+use order::warranty::{WarrantyReminders, WarrantyServiceImpl};
+use order::webhook::{WebhookDispatcher, WebhookServiceImpl};
 
 #[tokio::main]
 async fn main() -> Result<()> {
     dotenvy::dotenv().ok();
 
+    telemetry::init("order", env!("CARGO_PKG_VERSION"))?;
+
     let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
-    let user_service_url =
-        env::var("USER_SERVICE_URL").unwrap_or_else(|_| "http://127.0.0.1:50051".to_string());
-    let product_service_url =
-        env::var("PRODUCT_SERVICE_URL").unwrap_or_else(|_| "http://127.0.0.1:50052".to_string());
+    // With a CA configured, calls to other services are mutually authenticated
+    let internal_tls = tls::client_config()?;
+    // Every order calls the user and product services; stop calling one that
+    // keeps failing rather than have each request wait on it, and ride out
+    // blips on the lookups that are safe to repeat
+    let circuit_breaker = CircuitBreakerConfig::from_env();
+    let retry = RetryPolicy::from_env();
+    let user_service = ServiceEndpoint::from_env("user", "USER_SERVICE", "http://127.0.0.1:50051")
+        .with_tls(internal_tls.clone())
+        .with_circuit_breaker(circuit_breaker)
+        .with_retry(retry);
+    let product_service = Arc::new(
+        ServiceEndpoint::from_env("product", "PRODUCT_SERVICE", "http://127.0.0.1:50052")
+            .with_tls(internal_tls.clone())
+            .with_circuit_breaker(circuit_breaker)
+            .with_retry(retry),
+    );
+    let payment_service = Arc::new(
+        ServiceEndpoint::from_env("payment", "PAYMENT_SERVICE", "http://127.0.0.1:50055")
+            .with_tls(internal_tls.clone()),
+    );
+    let shipping_service =
+        ServiceEndpoint::from_env("shipping", "SHIPPING_SERVICE", "http://127.0.0.1:50056")
+            .with_tls(internal_tls.clone());
+    let notification_service = Arc::new(
+        ServiceEndpoint::from_env(
+            "notification",
+            "NOTIFICATION_SERVICE",
+            "http://127.0.0.1:50054",
+        )
+        .with_tls(internal_tls),
+    );
+    // The user and product channels are shared by every request; create them
+    // up front so a malformed URL fails startup
+    user_service.primary_channel()?;
+    product_service.primary_channel()?;
 
     // Create database connection pool
     let pool = PgPoolOptions::new()
@@ -23,21 +90,171 @@ async fn main() -> Result<()> {
         .connect(&database_url)
         .await?;
 
-    println!("Connected to database");
+    info!("Connected to database");
 
     // Run migrations
     sqlx::migrate!("../migrations").run(&pool).await?;
-    println!("Migrations completed");
+    info!("Migrations completed");
 
     let addr = "0.0.0.0:50053".parse()?;
-    let order_service = OrderServiceImpl::new(pool, user_service_url, product_service_url);
+    if common::sandbox::enabled() {
+        warn!("Sandbox mode: external providers are replaced by deterministic fakes");
+    }
+    let metrics = Metrics::new("order");
+    let metrics_addr = env::var("METRICS_ADDR")
+        .unwrap_or_else(|_| "0.0.0.0:9103".to_string())
+        .parse()?;
+    metrics::spawn(metrics_addr, metrics.clone());
+
+    // Orders and payments are what clients retry, from any instance
+    let idempotency_store = idempotency::store_from_env(&pool).await?;
+
+    let consistency_interval_secs: u64 = env::var("CONSISTENCY_CHECK_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(3600);
+    let consistency = ConsistencyChecker::new(pool.clone(), payment_service.clone(), &metrics);
+    consistency
+        .clone()
+        .spawn(Duration::from_secs(consistency_interval_secs));
+
+    let saga_recovery_interval_secs: u64 = env::var("SAGA_RECOVERY_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(60);
+    SagaRecovery::new(pool.clone(), product_service.clone())
+        .spawn(Duration::from_secs(saga_recovery_interval_secs));
+
+    let stock_return_interval_secs: u64 = env::var("STOCK_RETURN_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(60);
+    StockReturns::new(pool.clone(), product_service.clone())
+        .spawn(Duration::from_secs(stock_return_interval_secs));
+
+    // 0 disables auto-cancelling unpaid orders
+    let pending_order_ttl_mins: u64 = env::var("PENDING_ORDER_TTL_MINS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(60);
+    if pending_order_ttl_mins > 0 {
+        PendingOrderExpiry::new(
+            pool.clone(),
+            product_service.clone(),
+            Duration::from_secs(pending_order_ttl_mins * 60),
+        )
+        .spawn(Duration::from_secs(60));
+    }
+
+    let ops_service = OpsServiceImpl::new(pool.clone(), consistency, product_service.clone());
+    let recall_service = RecallServiceImpl::new(pool.clone(), notification_service.clone());
+
+    let warranty_reminder_days: i32 = env::var("WARRANTY_REMINDER_DAYS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(30);
+    WarrantyReminders::new(
+        pool.clone(),
+        notification_service.clone(),
+        warranty_reminder_days,
+    )
+    .spawn(Duration::from_secs(3600));
+    let warranty_service = WarrantyServiceImpl::new(pool.clone(), notification_service);
+
+    let report_time_zone: chrono_tz::Tz = env::var("REPORT_TIME_ZONE")
+        .unwrap_or_else(|_| "UTC".to_string())
+        .parse()
+        .map_err(|e| anyhow::anyhow!("Invalid REPORT_TIME_ZONE: {}", e))?;
+    let reporting_service = ReportingServiceImpl::new(pool.clone(), report_time_zone);
+
+    let settings_ttl_secs: u64 = env::var("SETTINGS_CACHE_TTL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(30);
+    let settings = SettingsStore::new(pool.clone(), Duration::from_secs(settings_ttl_secs));
+    let settings_service = SettingsServiceImpl::new(settings.clone());
+
+    // Without a rates provider, exchange_rates is maintained by hand
+    let exchange_rates_url = env::var("EXCHANGE_RATES_URL").unwrap_or_default();
+    if !exchange_rates_url.is_empty() || common::sandbox::enabled() {
+        let refresh_secs: u64 = env::var("EXCHANGE_RATES_REFRESH_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(3600);
+        ExchangeRateRefresher::new(pool.clone(), settings.clone(), exchange_rates_url)
+            .spawn(Duration::from_secs(refresh_secs));
+    }
+
+    let webhook_interval_secs: u64 = env::var("WEBHOOK_DISPATCH_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(5);
+    let webhook_max_attempts: i32 = env::var("WEBHOOK_MAX_ATTEMPTS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(8);
+    WebhookDispatcher::new(pool.clone(), webhook_max_attempts)
+        .spawn(Duration::from_secs(webhook_interval_secs));
+    let webhook_service = WebhookServiceImpl::new(pool.clone());
+
+    let outbox_interval_secs: u64 = env::var("OUTBOX_RELAY_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(5);
+    OutboxRelay::new(pool.clone(), outbox::publisher_from_env().await?)
+        .spawn(Duration::from_secs(outbox_interval_secs));
+
+    let tax_service = TaxServiceImpl::new(pool.clone());
+    let tax_calculator = tax::from_env(pool.clone());
+    let fraud_checker = fraud::from_env(pool.clone())?;
+
+    // Orders can't be placed without the user and product services, so
+    // they must be serving too
+    let health_interval_secs: u64 = env::var("HEALTH_CHECK_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(5);
+    let health_service = HealthCheck::new(pool.clone())
+        .with_service::<OrderServiceServer<OrderServiceImpl>>()
+        .with_downstream(user_service.name(), user_service.primary_channel()?)
+        .with_downstream(product_service.name(), product_service.primary_channel()?)
+        .start(Duration::from_secs(health_interval_secs))
+        .await;
+
+    let order_service = OrderServiceImpl::new(
+        pool,
+        Downstream {
+            user: user_service,
+            product: product_service,
+            payment: payment_service,
+            shipping: shipping_service,
+        },
+        OrderNumberFormat::from_env()?,
+        settings,
+        tax_calculator,
+        fraud_checker,
+        VerifiedUsers::from_env()?,
+    );
+    let slo_tracker = SloTracker::new("order", SloConfig::from_env()?);
 
-    println!("Order service listening on {}", addr);
+    info!("Order service listening on {}", addr);
 
-    Server::builder()
+    ServerBuilder::new(metrics, slo_tracker.clone())
+        .with_idempotency_store(idempotency_store)
+        .build()?
+        .add_service(health_service)
         .add_service(OrderServiceServer::new(order_service))
+        .add_service(OpsServiceServer::new(ops_service))
+        .add_service(RecallServiceServer::new(recall_service))
+        .add_service(WarrantyServiceServer::new(warranty_service))
+        .add_service(ReportingServiceServer::new(reporting_service))
+        .add_service(SettingsServiceServer::new(settings_service))
+        .add_service(TaxServiceServer::new(tax_service))
+        .add_service(WebhookServiceServer::new(webhook_service))
+        .add_service(SloServiceServer::new(SloServiceImpl::new(slo_tracker)))
         .serve(addr)
         .await?;
 
+    telemetry::shutdown();
     Ok(())
 }
@@ -0,0 +1,420 @@
+use crate::consistency::ConsistencyChecker;
+use crate::saga;
+use common::auth::Caller;
+use common::client::ServiceEndpoint;
+use common::error::{self, AppError};
+use common::pagination::PageRequest;
+pub use common::switches::is_switch_active;
+use common::switches::{FULFILLMENT_DRAINING, payment_provider_switch};
+use proto::ops::{
+    ConsistencyReport, ForceReleaseReservationsRequest, ForceReleaseReservationsResponse,
+    GetConsistencyReportRequest, ListAuditLogRequest, ListAuditLogResponse, ListSwitchesRequest,
+    ListSwitchesResponse, OpsActionRequest, OpsActionResponse, OpsAuditEntry, OpsSwitch,
+    RunConsistencyCheckRequest, SetPaymentProviderEnabledRequest, ops_service_server::OpsService,
+};
+use sqlx::{PgConnection, PgPool};
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use tracing::warn;
+use uuid::Uuid;
+
+/// When active, `create_order` rejects new orders.
+pub const ORDER_INTAKE_PAUSED: &str = "order_intake_paused";
+
+#[derive(Debug, sqlx::FromRow)]
+struct DbSwitch {
+    name: String,
+    active: bool,
+    reason: Option<String>,
+    updated_by: Option<String>,
+    updated_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct DbAuditEntry {
+    id: String,
+    action: String,
+    target: String,
+    actor: String,
+    reason: String,
+    created_at: chrono::NaiveDateTime,
+}
+
+/// Every operational action requires an admin token; `actor` names the
+/// person behind it in the audit log.
+pub struct OpsServiceImpl {
+    db: PgPool,
+    consistency: ConsistencyChecker,
+    product_service: Arc<ServiceEndpoint>,
+}
+
+fn missing_actor() -> Status {
+    error::invalid_argument(
+        "MISSING_ACTOR",
+        "Actor and reason are required for operational actions",
+    )
+}
+
+/// Records an operational action in the audit log.
+async fn audit(
+    conn: &mut PgConnection,
+    action: &str,
+    target: &str,
+    actor: &str,
+    reason: &str,
+) -> Result<(), AppError> {
+    sqlx::query(
+        "INSERT INTO ops_audit_log (id, action, target, actor, reason)
+         VALUES ($1, $2, $3, $4, $5)",
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(action)
+    .bind(target)
+    .bind(actor)
+    .bind(reason)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+impl OpsServiceImpl {
+    pub fn new(
+        db: PgPool,
+        consistency: ConsistencyChecker,
+        product_service: Arc<ServiceEndpoint>,
+    ) -> Self {
+        Self {
+            db,
+            consistency,
+            product_service,
+        }
+    }
+
+    fn db_switch_to_proto(&self, db_switch: &DbSwitch) -> OpsSwitch {
+        OpsSwitch {
+            name: db_switch.name.clone(),
+            active: db_switch.active,
+            reason: db_switch.reason.clone().unwrap_or_default(),
+            updated_by: db_switch.updated_by.clone().unwrap_or_default(),
+            updated_at: db_switch.updated_at.and_utc().timestamp(),
+        }
+    }
+
+    /// Flips a switch and records the action in the audit log in one transaction.
+    async fn set_switch(
+        &self,
+        name: &str,
+        active: bool,
+        action: &str,
+        actor: &str,
+        reason: &str,
+    ) -> Result<OpsActionResponse, Status> {
+        if actor.is_empty() || reason.is_empty() {
+            return Err(missing_actor());
+        }
+
+        let mut tx = self.db.begin().await.map_err(AppError::from)?;
+
+        let db_switch = sqlx::query_as::<_, DbSwitch>(
+            "INSERT INTO ops_switches (name, active, reason, updated_by, updated_at)
+             VALUES ($1, $2, $3, $4, CURRENT_TIMESTAMP)
+             ON CONFLICT (name) DO UPDATE
+             SET active = EXCLUDED.active, reason = EXCLUDED.reason,
+                 updated_by = EXCLUDED.updated_by, updated_at = CURRENT_TIMESTAMP
+             RETURNING name, active, reason, updated_by, updated_at",
+        )
+        .bind(name)
+        .bind(active)
+        .bind(reason)
+        .bind(actor)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+
+        audit(&mut tx, action, name, actor, reason).await?;
+
+        tx.commit().await.map_err(AppError::from)?;
+
+        Ok(OpsActionResponse {
+            success: true,
+            message: format!("{} applied", action),
+            switch: Some(self.db_switch_to_proto(&db_switch)),
+        })
+    }
+}
+
+#[tonic::async_trait]
+impl OpsService for OpsServiceImpl {
+    async fn pause_order_intake(
+        &self,
+        request: Request<OpsActionRequest>,
+    ) -> Result<Response<OpsActionResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        let response = self
+            .set_switch(
+                ORDER_INTAKE_PAUSED,
+                true,
+                "PAUSE_ORDER_INTAKE",
+                &req.actor,
+                &req.reason,
+            )
+            .await?;
+
+        Ok(Response::new(response))
+    }
+
+    async fn resume_order_intake(
+        &self,
+        request: Request<OpsActionRequest>,
+    ) -> Result<Response<OpsActionResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        let response = self
+            .set_switch(
+                ORDER_INTAKE_PAUSED,
+                false,
+                "RESUME_ORDER_INTAKE",
+                &req.actor,
+                &req.reason,
+            )
+            .await?;
+
+        Ok(Response::new(response))
+    }
+
+    async fn drain_fulfillment_queue(
+        &self,
+        request: Request<OpsActionRequest>,
+    ) -> Result<Response<OpsActionResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        let mut response = self
+            .set_switch(
+                FULFILLMENT_DRAINING,
+                true,
+                "DRAIN_FULFILLMENT_QUEUE",
+                &req.actor,
+                &req.reason,
+            )
+            .await?;
+
+        let waiting: i64 = sqlx::query_scalar(
+            "SELECT COUNT(DISTINCT o.id) FROM orders o
+             JOIN order_items oi ON oi.order_id = o.id
+             WHERE o.status IN ('CONFIRMED', 'PROCESSING', 'PARTIALLY_SHIPPED')
+               AND oi.fulfillment_status = 'UNFULFILLED'",
+        )
+        .fetch_one(&self.db)
+        .await
+        .map_err(AppError::from)?;
+        response.message = format!(
+            "Fulfillment queue draining; {} orders are waiting to ship",
+            waiting
+        );
+
+        Ok(Response::new(response))
+    }
+
+    async fn resume_fulfillment_queue(
+        &self,
+        request: Request<OpsActionRequest>,
+    ) -> Result<Response<OpsActionResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        let response = self
+            .set_switch(
+                FULFILLMENT_DRAINING,
+                false,
+                "RESUME_FULFILLMENT_QUEUE",
+                &req.actor,
+                &req.reason,
+            )
+            .await?;
+
+        Ok(Response::new(response))
+    }
+
+    async fn force_release_reservations(
+        &self,
+        request: Request<ForceReleaseReservationsRequest>,
+    ) -> Result<Response<ForceReleaseReservationsResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        if req.actor.is_empty() || req.reason.is_empty() {
+            return Err(missing_actor());
+        }
+        if req.product_id.is_empty() {
+            return Err(error::invalid_argument(
+                "MISSING_PRODUCT_ID",
+                "Product ID is required",
+            ));
+        }
+
+        // Reservations of orders already written are the orders' stock, and
+        // are left to cancellation
+        let reservation_ids: Vec<String> = sqlx::query_scalar(
+            "SELECT DISTINCT r.id FROM stock_reservations r
+             JOIN stock_reservation_lines l ON l.reservation_id = r.id
+             JOIN order_sagas s ON s.order_id = r.id
+             WHERE l.product_id = $1 AND r.status = 'PENDING'
+               AND s.state IN ('STARTED', 'COMPENSATING')",
+        )
+        .bind(&req.product_id)
+        .fetch_all(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        // Compensating the saga, rather than releasing behind its back, fails
+        // an order still being placed instead of writing it without stock
+        let mut released = 0;
+        let mut failed = 0;
+        for reservation_id in &reservation_ids {
+            match saga::compensate(&self.db, &self.product_service, reservation_id).await {
+                Ok(()) => released += 1,
+                Err(e) => {
+                    warn!(reservation_id = %reservation_id, "Forced release failed: {}", e);
+                    failed += 1;
+                }
+            }
+        }
+
+        let mut conn = self.db.acquire().await.map_err(AppError::from)?;
+        audit(
+            &mut conn,
+            "FORCE_RELEASE_RESERVATIONS",
+            &req.product_id,
+            &req.actor,
+            &req.reason,
+        )
+        .await?;
+
+        Ok(Response::new(ForceReleaseReservationsResponse {
+            success: failed == 0,
+            message: format!(
+                "Released {} of {} reservations",
+                released,
+                reservation_ids.len()
+            ),
+            released_count: released,
+            failed_count: failed,
+        }))
+    }
+
+    async fn set_payment_provider_enabled(
+        &self,
+        request: Request<SetPaymentProviderEnabledRequest>,
+    ) -> Result<Response<OpsActionResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        if req.provider.is_empty() {
+            return Err(error::invalid_argument(
+                "MISSING_PROVIDER",
+                "Provider is required",
+            ));
+        }
+
+        let name = payment_provider_switch(&req.provider);
+        let action = if req.enabled {
+            "ENABLE_PAYMENT_PROVIDER"
+        } else {
+            "DISABLE_PAYMENT_PROVIDER"
+        };
+
+        let response = self
+            .set_switch(&name, !req.enabled, action, &req.actor, &req.reason)
+            .await?;
+
+        Ok(Response::new(response))
+    }
+
+    async fn list_switches(
+        &self,
+        request: Request<ListSwitchesRequest>,
+    ) -> Result<Response<ListSwitchesResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let switches = sqlx::query_as::<_, DbSwitch>(
+            "SELECT name, active, reason, updated_by, updated_at FROM ops_switches ORDER BY name",
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        Ok(Response::new(ListSwitchesResponse {
+            switches: switches
+                .iter()
+                .map(|s| self.db_switch_to_proto(s))
+                .collect(),
+        }))
+    }
+
+    async fn list_audit_log(
+        &self,
+        request: Request<ListAuditLogRequest>,
+    ) -> Result<Response<ListAuditLogResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        let page = PageRequest::new(req.page, req.page_size);
+
+        let entries = sqlx::query_as::<_, DbAuditEntry>(
+            "SELECT id, action, target, actor, reason, created_at
+             FROM ops_audit_log
+             ORDER BY created_at DESC
+             LIMIT $1 OFFSET $2",
+        )
+        .bind(page.limit())
+        .bind(page.offset())
+        .fetch_all(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM ops_audit_log")
+            .fetch_one(&self.db)
+            .await
+            .map_err(AppError::from)?;
+
+        let proto_entries: Vec<OpsAuditEntry> = entries
+            .into_iter()
+            .map(|e| OpsAuditEntry {
+                entry_id: e.id,
+                action: e.action,
+                target: e.target,
+                actor: e.actor,
+                reason: e.reason,
+                created_at: e.created_at.and_utc().timestamp(),
+            })
+            .collect();
+
+        Ok(Response::new(ListAuditLogResponse {
+            success: true,
+            message: format!("Retrieved {} audit entries", proto_entries.len()),
+            entries: proto_entries,
+            total_count: count.0 as i32,
+        }))
+    }
+
+    async fn run_consistency_check(
+        &self,
+        request: Request<RunConsistencyCheckRequest>,
+    ) -> Result<Response<ConsistencyReport>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let report = self.consistency.run().await?;
+        Ok(Response::new(report))
+    }
+
+    async fn get_consistency_report(
+        &self,
+        request: Request<GetConsistencyReportRequest>,
+    ) -> Result<Response<ConsistencyReport>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let report = self.consistency.last_report().await.unwrap_or_default();
+        Ok(Response::new(report))
+    }
+}
@@ -1,49 +1,717 @@
+use crate::currency::{self, Conversion};
+use crate::draft::{self, DbDraftItem, DbDraftOrder};
+use crate::export::Export;
+use crate::fraud::{FraudChecker, OrderCandidate};
+use crate::guest;
+use crate::ops;
+use crate::order_number::OrderNumberFormat;
+use crate::recall;
+use crate::refund;
+use crate::repository::{
+    DbItemTracking, DbOrder, DbOrderItem, OrderRepository, PostgresOrderRepository,
+};
+use crate::saga;
+use crate::shipping;
+use crate::stock_return;
+use crate::tax::{self, TaxCalculator, TaxLine};
+use crate::user_verification::VerifiedUsers;
+use crate::warranty;
 use anyhow::Result;
+use chrono::{Datelike, Days, NaiveDate, SecondsFormat};
+use common::auth::{self, Caller, ServiceCredentials};
+use common::client::{ServiceEndpoint, call_with_canary};
+use common::error::{self, AppError};
+use common::events;
+use common::order_events::{self, EventType, OrderEvent};
+use common::pagination::{self, Cursor, PageRequest, SortOrder};
+use common::resilience;
+use common::settings::SettingsStore;
+use proto::events::{OrderCreated, OrderCreatedItem};
 use proto::order::{
-    CancelOrderRequest, CancelOrderResponse, CreateOrderRequest, CreateOrderResponse,
-    GetOrderRequest, GetOrderResponse, GetOrdersByUserRequest, GetOrdersByUserResponse,
-    ListOrdersRequest, ListOrdersResponse, Order, OrderItem, OrderStatus, UpdateOrderRequest,
-    UpdateOrderResponse, order_service_server::OrderService,
+    ApproveOrderRequest, ApproveOrderResponse, CancelOrderItemsRequest, CancelOrderItemsResponse,
+    CancelOrderRequest, CancelOrderResponse, ClaimGuestOrdersRequest, ClaimGuestOrdersResponse,
+    CreateDraftOrderRequest, CreateDraftOrderResponse, CreateOrderRequest, CreateOrderResponse,
+    DraftOrder, ExportOrdersRequest, ExportOrdersResponse, FinalizeDraftOrderRequest,
+    FinalizeDraftOrderResponse, FindOrdersByLotRequest, FindOrdersByLotResponse,
+    GetOrderHistoryRequest, GetOrderHistoryResponse, GetOrderRequest, GetOrderResponse,
+    GetOrdersByIDsRequest, GetOrdersByIDsResponse, GetOrdersByUserRequest, GetOrdersByUserResponse,
+    ItemFulfillmentStatus, ItemTracking, ListOrdersRequest, ListOrdersResponse, ListRefundsRequest,
+    ListRefundsResponse, LotOrderItem, MarkItemsShippedRequest, MarkItemsShippedResponse, Order,
+    OrderEventType, OrderItem, OrderItemStatus, OrderSort, OrderStatus, QuoteOrderRequest,
+    QuoteOrderResponse, RecordItemTrackingRequest, RecordItemTrackingResponse,
+    RecordShipmentEventRequest, RecordShipmentEventResponse, RefundOrderRequest,
+    RefundOrderResponse, RejectOrderRequest, RejectOrderResponse, ShipmentEvent,
+    UpdateDeliveryEstimateRequest, UpdateDeliveryEstimateResponse, UpdateOrderRequest,
+    UpdateOrderResponse, VerifyPurchaseRequest, VerifyPurchaseResponse,
+    order_service_server::OrderService,
 };
+use proto::payment::PaymentStatus;
 use proto::product;
-use proto::product::{CheckAvailabilityRequest, product_service_client::ProductServiceClient};
+use proto::product::ReservationLine;
+use proto::product::product_service_client::ProductServiceClient;
 use proto::user::{VerifyRequest, user_service_client::UserServiceClient};
-use sqlx::PgPool;
-use tonic::{Request, Response, Status};
+use sqlx::types::Decimal;
+use sqlx::{PgExecutor, PgPool, Postgres, QueryBuilder};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Code, Request, Response, Status};
+use tracing::{info, warn};
 use uuid::Uuid;
 
-#[derive(Debug, sqlx::FromRow)]
-struct DbOrder {
-    id: String,
-    user_id: String,
-    total_amount: sqlx::types::Decimal,
-    status: String,
-    shipping_address: Option<String>,
-    created_at: chrono::NaiveDateTime,
-    updated_at: chrono::NaiveDateTime,
+/// Product name shown on order items whose product no longer exists.
+const MISSING_PRODUCT_NAME: &str = "Product no longer available";
+
+impl DbItemTracking {
+    fn to_proto(&self) -> ItemTracking {
+        ItemTracking {
+            serial_number: self.serial_number.clone().unwrap_or_default(),
+            lot_number: self.lot_number.clone().unwrap_or_default(),
+            quantity: self.quantity,
+        }
+    }
 }
 
 #[derive(Debug, sqlx::FromRow)]
-struct DbOrderItem {
-    id: String,
+struct DbLotOrderItem {
     order_id: String,
+    user_id: String,
+    item_id: String,
     product_id: String,
     quantity: i32,
-    price: sqlx::types::Decimal,
+    status: String,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct DbOrderEvent {
+    id: i64,
+    event_type: String,
+    from_status: Option<String>,
+    to_status: Option<String>,
+    actor: String,
+    reason: Option<String>,
+    details: Option<String>,
+    created_at: chrono::NaiveDateTime,
+}
+
+impl DbOrderEvent {
+    fn to_proto(&self) -> proto::order::OrderEvent {
+        let event_type = match self.event_type.as_str() {
+            "STATUS_CHANGED" => OrderEventType::OrderStatusChanged,
+            "CANCELLED" => OrderEventType::OrderCancelled,
+            "REFUNDED" => OrderEventType::OrderRefunded,
+            "ADDRESS_CHANGED" => OrderEventType::OrderAddressChanged,
+            "ITEMS_CANCELLED" => OrderEventType::OrderItemsCancelled,
+            "ITEMS_SHIPPED" => OrderEventType::OrderItemsShipped,
+            "CLAIMED" => OrderEventType::OrderClaimed,
+            "DELIVERY_ESTIMATE_CHANGED" => OrderEventType::OrderDeliveryEstimateChanged,
+            _ => OrderEventType::OrderCreated,
+        };
+
+        proto::order::OrderEvent {
+            event_id: self.id,
+            event_type: event_type as i32,
+            from_status: self.from_status.clone().unwrap_or_default(),
+            to_status: self.to_status.clone().unwrap_or_default(),
+            actor: self.actor.clone(),
+            reason: self.reason.clone().unwrap_or_default(),
+            details: self.details.clone().unwrap_or_default(),
+            created_at: self.created_at.and_utc().timestamp(),
+        }
+    }
+}
+
+/// Filters of a `list_orders` request; `None` leaves a filter off.
+struct ListFilters {
+    status: Option<String>,
+    user_id: Option<String>,
+    created_after: Option<chrono::DateTime<chrono::Utc>>,
+    created_before: Option<chrono::DateTime<chrono::Utc>>,
+    min_amount: Option<Decimal>,
+    max_amount: Option<Decimal>,
+}
+
+impl ListFilters {
+    /// Parses the filters of a request; `Err` says which one is invalid.
+    fn parse(req: &ListOrdersRequest, status: Option<String>) -> Result<Self, String> {
+        let time = |seconds: i64, field: &str| match seconds {
+            0 => Ok(None),
+            _ => chrono::DateTime::from_timestamp(seconds, 0)
+                .map(Some)
+                .ok_or_else(|| format!("Invalid {}", field)),
+        };
+        let amount = |value: &str, field: &str| match value {
+            "" => Ok(None),
+            _ => value
+                .parse::<Decimal>()
+                .map(Some)
+                .map_err(|_| format!("Invalid {}", field)),
+        };
+
+        let filters = Self {
+            status,
+            user_id: (!req.user_id.is_empty()).then(|| req.user_id.clone()),
+            created_after: time(req.created_after, "created_after")?,
+            created_before: time(req.created_before, "created_before")?,
+            min_amount: amount(&req.min_amount, "min_amount")?,
+            max_amount: amount(&req.max_amount, "max_amount")?,
+        };
+
+        if let (Some(after), Some(before)) = (filters.created_after, filters.created_before)
+            && after >= before
+        {
+            return Err("created_after must be before created_before".to_string());
+        }
+        if let (Some(min), Some(max)) = (filters.min_amount, filters.max_amount)
+            && min > max
+        {
+            return Err("min_amount cannot exceed max_amount".to_string());
+        }
+        Ok(filters)
+    }
+}
+
+/// Appends the WHERE clause shared by the list and count queries of `list_orders`.
+fn push_list_filters(qb: &mut QueryBuilder<'_, Postgres>, filters: &ListFilters) {
+    qb.push(" WHERE TRUE");
+    if let Some(status) = &filters.status {
+        qb.push(" AND status = ").push_bind(status.clone());
+    }
+    if let Some(user_id) = &filters.user_id {
+        qb.push(" AND user_id = ").push_bind(user_id.clone());
+    }
+    if let Some(after) = filters.created_after {
+        qb.push(" AND created_at >= ").push_bind(after);
+    }
+    if let Some(before) = filters.created_before {
+        qb.push(" AND created_at < ").push_bind(before);
+    }
+    if let Some(min) = filters.min_amount {
+        qb.push(" AND total_amount >= ").push_bind(min);
+    }
+    if let Some(max) = filters.max_amount {
+        qb.push(" AND total_amount <= ").push_bind(max);
+    }
+}
+
+fn item_status_to_string(status: OrderItemStatus) -> &'static str {
+    match status {
+        OrderItemStatus::Allocated => "ALLOCATED",
+        OrderItemStatus::Backordered => "BACKORDERED",
+    }
+}
+
+fn fulfillment_status_from_string(status: &str) -> ItemFulfillmentStatus {
+    match status {
+        "SHIPPED" => ItemFulfillmentStatus::ItemShipped,
+        _ => ItemFulfillmentStatus::ItemUnfulfilled,
+    }
+}
+
+/// Status an order's shipped items put it in: SHIPPED once every item that
+/// ships has shipped, PARTIALLY_SHIPPED while only some have, `None` before
+/// any has. Digital items don't ship.
+async fn fulfillment_status<'e>(
+    executor: impl PgExecutor<'e>,
+    order_id: &str,
+) -> Result<Option<&'static str>, Status> {
+    let (shipped, unshipped): (i64, i64) = sqlx::query_as(
+        "SELECT COUNT(*) FILTER (WHERE oi.fulfillment_status = 'SHIPPED'),
+                COUNT(*) FILTER (WHERE oi.fulfillment_status <> 'SHIPPED')
+         FROM order_items oi
+         WHERE oi.order_id = $1 AND oi.product_type <> 'DIGITAL'",
+    )
+    .bind(order_id)
+    .fetch_one(executor)
+    .await
+    .map_err(AppError::from)?;
+
+    Ok(match (shipped, unshipped) {
+        (0, _) => None,
+        (_, 0) => Some("SHIPPED"),
+        _ => Some("PARTIALLY_SHIPPED"),
+    })
+}
+
+fn item_status_from_string(status: &str) -> OrderItemStatus {
+    match status {
+        "BACKORDERED" => OrderItemStatus::Backordered,
+        _ => OrderItemStatus::Allocated,
+    }
+}
+
+/// Keyset position of an order in listings, which run by creation time.
+fn order_cursor(order: &DbOrder) -> Cursor {
+    Cursor::new(
+        order
+            .created_at
+            .to_rfc3339_opts(SecondsFormat::Micros, true),
+        order.id.clone(),
+    )
+}
+
+/// Decodes a request's page token; `Ok(None)` when it has none.
+fn decode_page_token(token: &str) -> Result<Option<Cursor>, String> {
+    if token.is_empty() {
+        return Ok(None);
+    }
+    Cursor::decode(token)
+        .map(Some)
+        .ok_or_else(|| "Invalid page token".to_string())
+}
+
+/// Appends the cursor condition, ordering and limit of a page of orders.
+pub(crate) fn push_order_page(
+    qb: &mut QueryBuilder<'_, Postgres>,
+    page: &PageRequest,
+    after: Option<&Cursor>,
+    order: SortOrder,
+) {
+    if let Some(cursor) = after {
+        cursor.push_after(qb, "created_at", "TIMESTAMPTZ", "id", order);
+    }
+    qb.push(match order {
+        SortOrder::Asc => " ORDER BY created_at, id",
+        SortOrder::Desc => " ORDER BY created_at DESC, id DESC",
+    });
+    page.push_lookahead_limit(qb, after.is_some());
+}
+
+/// An order item as priced for CreateOrder and QuoteOrder, with the
+/// product details it's stored with.
+struct PricedItem {
+    item: OrderItem,
+    unit_price: Decimal,
+    tax: Decimal,
+    product_type: String,
+    warranty_months: i32,
+}
+
+/// A product as an order is priced from: its current price, name and tax
+/// class, whether it's digital, i.e. has nothing to ship, and the months of
+/// warranty it carries.
+struct PricedProduct {
+    price: Decimal,
+    name: String,
+    tax_class: String,
+    digital: bool,
+    warranty_months: i32,
+}
+
+/// Amounts charged for an order; `total` includes `tax` and `shipping`.
+struct OrderTotals {
+    total: Decimal,
+    tax: Decimal,
+    shipping: Decimal,
+    /// Whether any item is physical and so has to be shipped.
+    requires_shipping: bool,
+    /// Currency the amounts are in, converted from the default one.
+    conversion: Conversion,
+    /// Days delivery takes with the chosen shipping method; `None` when
+    /// none was chosen.
+    transit_days: Option<(i32, i32)>,
+}
+
+impl OrderTotals {
+    /// Estimated delivery window of an order placed on `placed_on`.
+    fn delivery_window(&self, placed_on: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+        let (min, max) = self.transit_days?;
+        let days = |n: i32| placed_on + Days::new(u64::try_from(n).unwrap_or_default());
+        Some((days(min), days(max)))
+    }
+}
+
+/// Formats an optional date for the API, empty when there's none.
+fn format_date(date: Option<NaiveDate>) -> String {
+    date.map(|d| d.format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+/// Services the order service calls.
+pub struct Downstream {
+    pub user: ServiceEndpoint,
+    pub product: Arc<ServiceEndpoint>,
+    pub payment: Arc<ServiceEndpoint>,
+    pub shipping: ServiceEndpoint,
 }
 
 pub struct OrderServiceImpl {
     db: PgPool,
-    user_service_url: String,
-    product_service_url: String,
+    /// Orders of lookups and listings by user.
+    repository: Arc<dyn OrderRepository>,
+    user_service: ServiceEndpoint,
+    product_service: Arc<ServiceEndpoint>,
+    payment_service: Arc<ServiceEndpoint>,
+    shipping_service: ServiceEndpoint,
+    order_numbers: OrderNumberFormat,
+    settings: Arc<SettingsStore>,
+    tax: Arc<dyn TaxCalculator>,
+    fraud: Arc<dyn FraudChecker>,
+    verified_users: VerifiedUsers,
 }
 
 impl OrderServiceImpl {
-    pub fn new(db: PgPool, user_service_url: String, product_service_url: String) -> Self {
+    pub fn new(
+        db: PgPool,
+        downstream: Downstream,
+        order_numbers: OrderNumberFormat,
+        settings: Arc<SettingsStore>,
+        tax: Arc<dyn TaxCalculator>,
+        fraud: Arc<dyn FraudChecker>,
+        verified_users: VerifiedUsers,
+    ) -> Self {
         Self {
+            repository: Arc::new(PostgresOrderRepository::new(db.clone())),
             db,
-            user_service_url,
-            product_service_url,
+            user_service: downstream.user,
+            product_service: downstream.product,
+            payment_service: downstream.payment,
+            shipping_service: downstream.shipping,
+            order_numbers,
+            settings,
+            tax,
+            fraud,
+            verified_users,
+        }
+    }
+
+    /// Reads orders from `repository` instead of the database, e.g. an
+    /// `InMemoryOrderRepository` in tests.
+    pub fn with_repository(mut self, repository: Arc<dyn OrderRepository>) -> Self {
+        self.repository = repository;
+        self
+    }
+
+    /// Writes an order and its items, with the item statuses of its stock
+    /// reservation, and moves its saga on to ORDER_CREATED in the same
+    /// transaction. Orders fraud screening flags are written ON_HOLD. Returns
+    /// the order and its items as written, less product names.
+    async fn write_order(
+        &self,
+        order_id: &str,
+        req: &CreateOrderRequest,
+        actor: &str,
+        totals: &OrderTotals,
+        items: Vec<PricedItem>,
+        reserved: Vec<ReservationLine>,
+    ) -> Result<(DbOrder, Vec<OrderItem>), Status> {
+        // Held orders keep their stock while they wait for review, but can't
+        // be paid until approved
+        let holds = self
+            .fraud
+            .check(&OrderCandidate {
+                user_id: &req.user_id,
+                total: totals.total / totals.conversion.rate,
+                shipping_region: &req.shipping_region,
+                billing_region: &req.billing_region,
+            })
+            .await?;
+        let status = if holds.is_empty() {
+            "PENDING"
+        } else {
+            "ON_HOLD"
+        };
+        let hold_reason = holds.join("; ");
+
+        let mut tx = self.db.begin().await.map_err(AppError::from)?;
+
+        // The sequence hands out each value once, so numbers are unique even
+        // across concurrent transactions
+        let sequence: i64 = sqlx::query_scalar("SELECT nextval('order_number_seq')")
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(AppError::from)?;
+        let prefix = self.settings.order_number_prefix().await?;
+        let now = chrono::Utc::now();
+        let order_number = self.order_numbers.format(&prefix, sequence, now.year());
+        let delivery = totals.delivery_window(now.date_naive());
+
+        let order = sqlx::query_as::<_, DbOrder>(
+            "INSERT INTO orders (id, order_number, user_id, total_amount, status, shipping_address, tax_amount, shipping_region, shipping_method, shipping_fee, currency, exchange_rate, estimated_delivery_from, estimated_delivery_to) 
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+             RETURNING id, order_number, user_id, total_amount, status, shipping_address, tax_amount, shipping_region, shipping_method, shipping_fee, currency, exchange_rate, estimated_delivery_from, estimated_delivery_to, created_at, updated_at",
+        )
+        .bind(order_id)
+        .bind(&order_number)
+        .bind(&req.user_id)
+        .bind(totals.total)
+        .bind(status)
+        .bind(if req.shipping_address.is_empty() {
+            None
+        } else {
+            Some(&req.shipping_address)
+        })
+        .bind(totals.tax)
+        .bind(if req.shipping_region.is_empty() {
+            None
+        } else {
+            Some(tax::normalize_region(&req.shipping_region))
+        })
+        .bind(if req.shipping_method.is_empty() {
+            None
+        } else {
+            Some(&req.shipping_method)
+        })
+        .bind(totals.shipping)
+        .bind(&totals.conversion.currency)
+        .bind(totals.conversion.rate)
+        .bind(delivery.map(|(from, _)| from))
+        .bind(delivery.map(|(_, to)| to))
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+
+        order_events::record(
+            &mut *tx,
+            &OrderEvent::new(order_id, EventType::Created, actor)
+                .status(None, status)
+                .reason(&hold_reason),
+        )
+        .await?;
+
+        // Items go in as one statement of column arrays, however large the
+        // cart, which keeps the transaction and its locks short
+        let mut written = Vec::with_capacity(items.len());
+        let mut prices = Vec::with_capacity(items.len());
+        let mut taxes = Vec::with_capacity(items.len());
+        let mut statuses = Vec::with_capacity(items.len());
+        let mut product_types = Vec::with_capacity(items.len());
+        let mut warranty_months = Vec::with_capacity(items.len());
+
+        // Reserved lines come back in request order
+        for (priced, line) in items.into_iter().zip(reserved) {
+            let status = if line.backordered {
+                OrderItemStatus::Backordered
+            } else {
+                OrderItemStatus::Allocated
+            };
+            prices.push(priced.unit_price);
+            taxes.push(priced.tax);
+            statuses.push(item_status_to_string(status));
+            product_types.push(priced.product_type);
+            warranty_months.push(priced.warranty_months);
+            written.push(OrderItem {
+                item_id: Uuid::new_v4().to_string(),
+                status: status as i32,
+                ..priced.item
+            });
+        }
+
+        sqlx::query(
+            "INSERT INTO order_items (id, order_id, product_id, quantity, price, status, tax_amount, product_type, warranty_months)
+             SELECT id, $1, product_id, quantity, price, status, tax_amount, product_type, warranty_months
+             FROM UNNEST($2::VARCHAR[], $3::VARCHAR[], $4::INT[], $5::DECIMAL[], $6::VARCHAR[], $7::DECIMAL[], $8::VARCHAR[], $9::INT[])
+                 AS i(id, product_id, quantity, price, status, tax_amount, product_type, warranty_months)",
+        )
+        .bind(order_id)
+        .bind(written.iter().map(|i| i.item_id.clone()).collect::<Vec<_>>())
+        .bind(written.iter().map(|i| i.product_id.clone()).collect::<Vec<_>>())
+        .bind(written.iter().map(|i| i.quantity).collect::<Vec<_>>())
+        .bind(&prices)
+        .bind(&statuses)
+        .bind(&taxes)
+        .bind(&product_types)
+        .bind(&warranty_months)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+
+        events::emit(
+            &mut *tx,
+            &OrderCreated {
+                order_id: order.id.clone(),
+                order_number: order.order_number.clone(),
+                user_id: order.user_id.clone(),
+                status: order.status.clone(),
+                total_amount: order.total_amount.to_string(),
+                currency: order.currency.clone(),
+                items: written
+                    .iter()
+                    .zip(&prices)
+                    .map(|(item, price)| OrderCreatedItem {
+                        product_id: item.product_id.clone(),
+                        quantity: item.quantity,
+                        unit_price: price.to_string(),
+                        backordered: item.status == OrderItemStatus::Backordered as i32,
+                    })
+                    .collect(),
+                created_at: order.created_at.timestamp(),
+            },
+        )
+        .await
+        .map_err(AppError::from)?;
+
+        // The recovery sweep may have released the reservation of a saga
+        // that took too long; the order is then abandoned
+        if !saga::advance(&mut *tx, order_id, &[saga::STARTED], saga::ORDER_CREATED).await? {
+            return Err(Status::aborted(
+                "Order creation took too long and was rolled back",
+            ));
+        }
+
+        tx.commit().await.map_err(AppError::from)?;
+
+        Ok((order, written))
+    }
+
+    /// Reserves an order's stock in the product service under the order id,
+    /// then writes the order and returns it; what a failure leaves reserved
+    /// is released again.
+    async fn place_order(
+        &self,
+        order_id: &str,
+        req: &CreateOrderRequest,
+        actor: &str,
+        totals: &OrderTotals,
+        items: Vec<PricedItem>,
+    ) -> Result<Order, Status> {
+        saga::start(&self.db, order_id).await?;
+        let lines = items
+            .iter()
+            .map(|p| ReservationLine {
+                product_id: p.item.product_id.clone(),
+                quantity: p.item.quantity,
+                backordered: false,
+            })
+            .collect();
+        let reserved = match saga::reserve(&self.product_service, order_id, lines).await {
+            Ok(reserved) => reserved,
+            Err(e) => {
+                self.compensate(order_id).await;
+                return Err(e);
+            }
+        };
+
+        let (order, mut written) = match self
+            .write_order(order_id, req, actor, totals, items, reserved)
+            .await
+        {
+            Ok(written) => written,
+            Err(e) => {
+                self.compensate(order_id).await;
+                return Err(e);
+            }
+        };
+
+        // The order stands from here; a failed confirmation is retried by the
+        // recovery sweep
+        if let Err(e) = saga::complete(&self.db, &self.product_service, order_id).await {
+            warn!(order_id = %order_id, "Failed to confirm stock reservation: {}", e);
+        }
+
+        // The order was just written, so only the product names are looked up
+        let product_ids: Vec<String> = written
+            .iter()
+            .map(|i| i.product_id.clone())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        let product_map = self.get_products_by_ids(product_ids).await?;
+        for item in &mut written {
+            item.product_name = product_map
+                .get(&item.product_id)
+                .map_or(MISSING_PRODUCT_NAME.to_string(), |p| p.name.clone());
+        }
+
+        Ok(self.order_to_proto(&order, written))
+    }
+
+    /// Writes a draft order and its priced items, valid for `valid_days`.
+    async fn write_draft(
+        &self,
+        draft_id: &str,
+        req: &CreateDraftOrderRequest,
+        totals: &OrderTotals,
+        items: &[PricedItem],
+        valid_days: i32,
+    ) -> Result<(), Status> {
+        let optional = |value: &str| {
+            if value.is_empty() {
+                None
+            } else {
+                Some(value.to_string())
+            }
+        };
+
+        let mut tx = self.db.begin().await.map_err(AppError::from)?;
+
+        sqlx::query(
+            "INSERT INTO draft_orders (id, user_id, total_amount, tax_amount, shipping_fee, currency, exchange_rate, shipping_address, shipping_region, shipping_method, billing_region, min_transit_days, max_transit_days, expires_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, CURRENT_TIMESTAMP + make_interval(days => $14))",
+        )
+        .bind(draft_id)
+        .bind(&req.user_id)
+        .bind(totals.total)
+        .bind(totals.tax)
+        .bind(totals.shipping)
+        .bind(&totals.conversion.currency)
+        .bind(totals.conversion.rate)
+        .bind(optional(&req.shipping_address))
+        .bind(optional(&req.shipping_region).map(|r| tax::normalize_region(&r)))
+        .bind(optional(&req.shipping_method))
+        .bind(optional(&req.billing_region))
+        .bind(totals.transit_days.map(|(min, _)| min))
+        .bind(totals.transit_days.map(|(_, max)| max))
+        .bind(valid_days)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+
+        for (position, priced) in items.iter().enumerate() {
+            sqlx::query(
+                "INSERT INTO draft_order_items (id, draft_id, product_id, quantity, price, tax_amount, position, product_type, warranty_months)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(draft_id)
+            .bind(&priced.item.product_id)
+            .bind(priced.item.quantity)
+            .bind(priced.unit_price)
+            .bind(priced.tax)
+            .bind(position as i32)
+            .bind(&priced.product_type)
+            .bind(priced.warranty_months)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::from)?;
+        }
+
+        tx.commit().await.map_err(AppError::from)?;
+
+        Ok(())
+    }
+
+    async fn draft_to_proto(
+        &self,
+        draft: &DbDraftOrder,
+        items: &[DbDraftItem],
+    ) -> Result<DraftOrder, Status> {
+        let product_ids: Vec<String> = items
+            .iter()
+            .map(|i| i.product_id.clone())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        let product_map = self.get_products_by_ids(product_ids).await?;
+
+        let items = items
+            .iter()
+            .map(|item| {
+                item.to_proto(
+                    product_map
+                        .get(&item.product_id)
+                        .map_or(MISSING_PRODUCT_NAME.to_string(), |p| p.name.clone()),
+                )
+            })
+            .collect();
+        Ok(draft.to_proto(items))
+    }
+
+    /// Releases an order's stock reservation; when that fails too, the
+    /// recovery sweep retries it.
+    async fn compensate(&self, order_id: &str) {
+        if let Err(e) = saga::compensate(&self.db, &self.product_service, order_id).await {
+            warn!(order_id = %order_id, "Failed to release stock reservation: {}", e);
         }
     }
 
@@ -52,9 +720,11 @@ impl OrderServiceImpl {
             "PENDING" => OrderStatus::Pending,
             "CONFIRMED" => OrderStatus::Confirmed,
             "PROCESSING" => OrderStatus::Processing,
+            "PARTIALLY_SHIPPED" => OrderStatus::PartiallyShipped,
             "SHIPPED" => OrderStatus::Shipped,
             "DELIVERED" => OrderStatus::Delivered,
             "CANCELLED" => OrderStatus::Cancelled,
+            "ON_HOLD" => OrderStatus::OnHold,
             _ => OrderStatus::Pending,
         }
     }
@@ -64,9 +734,11 @@ impl OrderServiceImpl {
             OrderStatus::Pending => "PENDING",
             OrderStatus::Confirmed => "CONFIRMED",
             OrderStatus::Processing => "PROCESSING",
+            OrderStatus::PartiallyShipped => "PARTIALLY_SHIPPED",
             OrderStatus::Shipped => "SHIPPED",
             OrderStatus::Delivered => "DELIVERED",
             OrderStatus::Cancelled => "CANCELLED",
+            OrderStatus::OnHold => "ON_HOLD",
         }
         .to_string()
     }
@@ -75,22 +747,39 @@ impl OrderServiceImpl {
         &self,
         product_ids: Vec<String>,
     ) -> Result<std::collections::HashMap<String, product::Product>, Status> {
-        let mut product_client = ProductServiceClient::connect(self.product_service_url.clone())
-            .await
-            .map_err(|e| {
-                Status::unavailable(format!("Failed to connect to product service: {}", e))
-            })?;
-
-        let product_request = product::GetProductsByIDsRequest {
-            product_ids: product_ids.clone(),
-        };
+        let product_result =
+            call_with_canary(&self.product_service, "GetProductsByIds", |channel| {
+                let product_request = product::GetProductsByIDsRequest {
+                    product_ids: product_ids.clone(),
+                };
+                let channel = self.product_service.guard(channel);
+                let retry = self.product_service.retry_policy();
+                async move {
+                    let product_client = ProductServiceClient::with_interceptor(
+                        channel,
+                        ServiceCredentials("order"),
+                    );
+
+                    let product_response = resilience::retry(retry, "GetProductsByIds", || {
+                        let mut client = product_client.clone();
+                        let request = product_request.clone();
+                        async move { client.get_products_by_ids(request).await }
+                    })
+                    .await
+                    .map_err(|e| resilience::downstream_error("Product", e))?;
 
-        let product_response = product_client
-            .get_products_by_ids(product_request)
-            .await
-            .map_err(|e| Status::internal(format!("Product service error: {}", e)))?;
+                    Ok(product_response.into_inner())
+                }
+            })
+            .await?;
+
+        if !product_result.missing_ids.is_empty() {
+            warn!(
+                missing_ids = ?product_result.missing_ids,
+                "Product service has no product for some ids"
+            );
+        }
 
-        let product_result = product_response.into_inner();
         let product_map: std::collections::HashMap<String, product::Product> = product_result
             .products
             .into_iter()
@@ -100,118 +789,349 @@ impl OrderServiceImpl {
         Ok(product_map)
     }
 
-    async fn get_order_items(&self, order_id: &str) -> Result<Vec<OrderItem>, Status> {
-        let db_items = sqlx::query_as::<_, DbOrderItem>(
-            "SELECT id, order_id, product_id, quantity, price FROM order_items WHERE order_id = $1",
+    /// Stock of products as an order would find it, by product id; unknown
+    /// products are left out. Only a preview: reserving is the real check.
+    async fn get_availability(
+        &self,
+        product_ids: Vec<String>,
+    ) -> Result<HashMap<String, product::ProductAvailability>, Status> {
+        let channel = self
+            .product_service
+            .primary_channel()
+            .map_err(|e| Status::internal(format!("Invalid product service URL: {}", e)))?;
+        let client = ProductServiceClient::with_interceptor(
+            self.product_service.guard(channel),
+            ServiceCredentials("order"),
+        );
+        let request = product::CheckAvailabilityBatchRequest { product_ids };
+
+        let response = resilience::retry(
+            self.product_service.retry_policy(),
+            "CheckAvailabilityBatch",
+            || {
+                let mut client = client.clone();
+                let request = request.clone();
+                async move { client.check_availability_batch(request).await }
+            },
         )
-        .bind(order_id)
-        .fetch_all(&self.db)
         .await
-        .map_err(|e| Status::internal(format!("Database error: {}", e)))?;
+        .map_err(|e| resilience::downstream_error("Product", e))?;
+
+        Ok(response
+            .into_inner()
+            .products
+            .into_iter()
+            .map(|p| (p.product_id.clone(), p))
+            .collect())
+    }
+
+    /// Items of each order, by order id, resolved with one items query and
+    /// one product lookup for all orders.
+    async fn get_order_items(
+        &self,
+        order_ids: &[String],
+    ) -> Result<HashMap<String, Vec<OrderItem>>, Status> {
+        let db_items = self.repository.items(order_ids).await?;
 
         // collect product ids from db_items, and then call product service get_products_by_ids to get products
         let product_ids: Vec<String> = db_items
             .iter()
             .map(|item| item.product_id.clone())
+            .collect::<HashSet<_>>()
+            .into_iter()
             .collect();
 
         let product_map = self.get_products_by_ids(product_ids).await?;
 
-        let mut items = Vec::new();
+        let item_ids: Vec<String> = db_items.iter().map(|item| item.id.clone()).collect();
+        let mut tracking = self.get_item_tracking(&item_ids).await?;
+
+        let mut items: HashMap<String, Vec<OrderItem>> = HashMap::new();
         for db_item in db_items {
-            let price = db_item.price.to_string().parse::<f64>().unwrap_or(0.0);
-            let subtotal = price * db_item.quantity as f64;
+            let subtotal = db_item.price * Decimal::from(db_item.quantity);
 
-            items.push(OrderItem {
+            items.entry(db_item.order_id).or_default().push(OrderItem {
                 product_id: db_item.product_id.clone(),
                 product_name: product_map
                     .get(&db_item.product_id)
-                    .map_or(String::new(), |p| p.name.clone()),
+                    .map_or(MISSING_PRODUCT_NAME.to_string(), |p| p.name.clone()),
                 quantity: db_item.quantity,
-                unit_price: price,
-                subtotal,
+                unit_price: db_item.price.to_string(),
+                subtotal: subtotal.to_string(),
+                tracking: tracking.remove(&db_item.id).unwrap_or_default(),
+                status: item_status_from_string(&db_item.status) as i32,
+                tax_amount: db_item.tax_amount.to_string(),
+                fulfillment_status: fulfillment_status_from_string(&db_item.fulfillment_status)
+                    as i32,
+                shipment_tracking: db_item.shipment_tracking.unwrap_or_default(),
+                shipped_at: db_item.shipped_at.map_or(0, |t| t.and_utc().timestamp()),
+                item_id: db_item.id,
             });
         }
 
         Ok(items)
     }
 
+    async fn get_item_tracking(
+        &self,
+        item_ids: &[String],
+    ) -> Result<HashMap<String, Vec<ItemTracking>>, Status> {
+        let rows = self.repository.tracking(item_ids).await?;
+
+        let mut tracking: HashMap<String, Vec<ItemTracking>> = HashMap::new();
+        for row in rows {
+            tracking
+                .entry(row.order_item_id.clone())
+                .or_default()
+                .push(row.to_proto());
+        }
+
+        Ok(tracking)
+    }
+
     async fn db_order_to_proto(&self, db_order: &DbOrder) -> Result<Order, Status> {
-        let items = self.get_order_items(&db_order.id).await?;
+        let mut items = self
+            .get_order_items(std::slice::from_ref(&db_order.id))
+            .await?;
+        Ok(self.order_to_proto(db_order, items.remove(&db_order.id).unwrap_or_default()))
+    }
+
+    /// Converts orders, in the same order, resolving all their items at once.
+    async fn db_orders_to_proto(&self, db_orders: &[DbOrder]) -> Result<Vec<Order>, Status> {
+        let order_ids: Vec<String> = db_orders.iter().map(|o| o.id.clone()).collect();
+        let mut items = self.get_order_items(&order_ids).await?;
+
+        Ok(db_orders
+            .iter()
+            .map(|o| self.order_to_proto(o, items.remove(&o.id).unwrap_or_default()))
+            .collect())
+    }
 
-        Ok(Order {
+    fn order_to_proto(&self, db_order: &DbOrder, items: Vec<OrderItem>) -> Order {
+        Order {
             order_id: db_order.id.clone(),
+            order_number: db_order.order_number.clone(),
             user_id: db_order.user_id.clone(),
             items,
-            total_amount: db_order
-                .total_amount
-                .to_string()
-                .parse::<f64>()
-                .unwrap_or(0.0),
+            total_amount: db_order.total_amount.to_string(),
             status: self.status_to_proto(&db_order.status) as i32,
             shipping_address: db_order.shipping_address.clone().unwrap_or_default(),
-            created_at: db_order.created_at.and_utc().timestamp(),
-            updated_at: db_order.updated_at.and_utc().timestamp(),
-        })
+            created_at: db_order.created_at.timestamp(),
+            updated_at: db_order.updated_at.timestamp(),
+            tax_amount: db_order.tax_amount.to_string(),
+            shipping_region: db_order.shipping_region.clone().unwrap_or_default(),
+            shipping_method: db_order.shipping_method.clone().unwrap_or_default(),
+            shipping_fee: db_order.shipping_fee.to_string(),
+            currency: db_order.currency.clone(),
+            exchange_rate: db_order.exchange_rate.to_string(),
+            formatted_total: currency::format_amount(db_order.total_amount, &db_order.currency),
+            estimated_delivery_from: format_date(db_order.estimated_delivery_from),
+            estimated_delivery_to: format_date(db_order.estimated_delivery_to),
+        }
     }
 
     async fn verify_user_by_id(&self, user_id: &str) -> Result<bool, Status> {
-        // Call user service to verify token and get user_id
-        let mut client = UserServiceClient::connect(self.user_service_url.clone())
+        self.verified_users
+            .get_or_verify(user_id, self.call_verify(user_id))
             .await
-            .map_err(|e| {
-                Status::unavailable(format!("Failed to connect to user service: {}", e))
-            })?;
-
-        let verify_request = VerifyRequest {
-            user_id: user_id.to_string(),
-        };
+    }
 
-        let response = client
-            .verify(verify_request)
-            .await
-            .map_err(|e| Status::internal(format!("User service error: {}", e)))?;
+    async fn call_verify(&self, user_id: &str) -> Result<bool, Status> {
+        // Call user service to verify token and get user_id
+        let result = call_with_canary(&self.user_service, "Verify", |channel| {
+            let verify_request = VerifyRequest {
+                user_id: user_id.to_string(),
+            };
+            let channel = self.user_service.guard(channel);
+            let retry = self.user_service.retry_policy();
+            async move {
+                let client =
+                    UserServiceClient::with_interceptor(channel, ServiceCredentials("order"));
+
+                let response = resilience::retry(retry, "Verify", || {
+                    let mut client = client.clone();
+                    let request = verify_request.clone();
+                    async move { client.verify(request).await }
+                })
+                .await
+                .map_err(|e| resilience::downstream_error("User", e))?;
 
-        let result = response.into_inner();
+                Ok(response.into_inner())
+            }
+        })
+        .await?;
 
         if result.valid { Ok(true) } else { Ok(false) }
     }
 
-    async fn check_product_availability(
+    /// Validates and prices order items for CreateOrder and QuoteOrder, with
+    /// the tax and the `shipping_method` fee of shipping them to `region`.
+    /// Problems are collected rather than returned early so that a quote can
+    /// report all of them; CreateOrder rejects with the first one.
+    async fn price_items(
         &self,
-        product_id: &str,
-        quantity: i32,
-    ) -> Result<bool, Status> {
-        // Call product service to check availability
-        let mut client = ProductServiceClient::connect(self.product_service_url.clone())
+        items: &[OrderItem],
+        region: &str,
+        shipping_method: &str,
+        currency: &str,
+    ) -> Result<(Vec<PricedItem>, OrderTotals, Vec<String>), Status> {
+        let mut total_amount = Decimal::ZERO;
+        let mut priced_items = Vec::new();
+        let mut problems = Vec::new();
+
+        // Prices are kept in the default currency and converted into the
+        // order's; an unusable currency still prices in the default one
+        let conversion = match currency::conversion(&self.db, &self.settings, currency).await? {
+            Ok(conversion) => conversion,
+            Err(problem) => {
+                problems.push(problem);
+                Conversion::base(&currency::default_currency(&self.settings).await?)
+            }
+        };
+
+        // Prices, tax classes and product types all come from the product service
+        let product_ids: Vec<String> = items.iter().map(|i| i.product_id.clone()).collect();
+        let products = self.get_product_prices(&product_ids).await?;
+        let mut requires_shipping = false;
+
+        for item in items {
+            if item.quantity <= 0 {
+                problems.push(format!("Invalid quantity for product {}", item.product_id));
+                continue;
+            }
+
+            let Some(product) = products.get(&item.product_id) else {
+                problems.push(format!("Product {} not found", item.product_id));
+                continue;
+            };
+            let price = conversion.convert(product.price);
+            requires_shipping |= !product.digital;
+
+            let subtotal = price * Decimal::from(item.quantity);
+            total_amount += subtotal;
+
+            let priced = OrderItem {
+                product_id: item.product_id.clone(),
+                product_name: product.name.clone(),
+                quantity: item.quantity,
+                unit_price: price.to_string(),
+                subtotal: subtotal.to_string(),
+                item_id: String::new(),
+                tracking: vec![],
+                status: OrderItemStatus::Allocated as i32,
+                tax_amount: String::new(),
+                fulfillment_status: ItemFulfillmentStatus::ItemUnfulfilled as i32,
+                shipment_tracking: String::new(),
+                shipped_at: 0,
+            };
+            priced_items.push(PricedItem {
+                item: priced,
+                unit_price: price,
+                tax: Decimal::ZERO,
+                product_type: if product.digital {
+                    "DIGITAL"
+                } else {
+                    "PHYSICAL"
+                }
+                .to_string(),
+                warranty_months: product.warranty_months,
+            });
+        }
+
+        // Tax is charged per line, on its subtotal
+        let lines: Vec<TaxLine> = priced_items
+            .iter()
+            .map(|p| TaxLine {
+                tax_class: products
+                    .get(&p.item.product_id)
+                    .map(|product| product.tax_class.clone())
+                    .filter(|class| !class.is_empty())
+                    .unwrap_or_else(|| tax::STANDARD_CLASS.to_string()),
+                amount: p.unit_price * Decimal::from(p.item.quantity),
+            })
+            .collect();
+
+        // The shipping service prices the chosen method from the items' weight
+        let shipping_lines: Vec<(String, i32)> = priced_items
+            .iter()
+            .map(|p| (p.item.product_id.clone(), p.item.quantity))
+            .collect();
+        let shipping = async {
+            if shipping_method.is_empty() || shipping_lines.is_empty() {
+                return Ok(Ok(None));
+            }
+            shipping::quote(
+                &self.shipping_service,
+                &shipping_lines,
+                region,
+                shipping_method,
+            )
             .await
-            .map_err(|e| {
-                Status::unavailable(format!("Failed to connect to product service: {}", e))
-            })?;
+            .map(|quote| quote.map(Some))
+        };
 
-        let check_request = CheckAvailabilityRequest {
-            product_id: product_id.to_string(),
-            quantity,
+        let (taxes, shipping) = tokio::try_join!(self.tax.calculate(region, &lines), shipping)?;
+        let (shipping_fee, transit_days) = match shipping {
+            Ok(Some(quote)) => (
+                conversion.convert(quote.fee),
+                Some((quote.min_transit_days, quote.max_transit_days)),
+            ),
+            Ok(None) => (Decimal::ZERO, None),
+            Err(problem) => {
+                problems.push(problem);
+                (Decimal::ZERO, None)
+            }
         };
 
-        let response = client
-            .check_availability(check_request)
-            .await
-            .map_err(|e| Status::internal(format!("Product service error: {}", e)))?;
+        let mut tax_amount = Decimal::ZERO;
+        for (priced, tax) in priced_items.iter_mut().zip(taxes) {
+            priced.tax = tax;
+            priced.item.tax_amount = tax.to_string();
+            tax_amount += tax;
+        }
 
-        let result = response.into_inner();
-        Ok(result.available)
+        let totals = OrderTotals {
+            total: total_amount + tax_amount + shipping_fee,
+            tax: tax_amount,
+            shipping: shipping_fee,
+            requires_shipping,
+            conversion,
+            transit_days,
+        };
+        Ok((priced_items, totals, problems))
     }
 
-    async fn get_product_price(&self, product_id: &str) -> Result<Option<f64>, Status> {
-        let price: Option<sqlx::types::Decimal> =
-            sqlx::query_scalar("SELECT price FROM products WHERE id = $1")
-                .bind(product_id)
-                .fetch_optional(&self.db)
-                .await
-                .map_err(|e| Status::internal(format!("Database error: {}", e)))?;
+    /// Products as the product service currently sells them, by product id;
+    /// unknown products are left out.
+    async fn get_product_prices(
+        &self,
+        product_ids: &[String],
+    ) -> Result<HashMap<String, PricedProduct>, Status> {
+        // The product service applies sale prices as of the moment it's asked
+        let products = self.get_products_by_ids(product_ids.to_vec()).await?;
+
+        let mut prices = HashMap::with_capacity(products.len());
+        for (id, product) in products {
+            let price: Decimal = product.price.parse().map_err(|_| {
+                Status::internal(format!(
+                    "Product service returned an invalid price for product {}",
+                    id
+                ))
+            })?;
+            let digital = product.product_type == product::ProductType::Digital as i32;
+            prices.insert(
+                id,
+                PricedProduct {
+                    price,
+                    name: product.name,
+                    tax_class: product.tax_class,
+                    digital,
+                    warranty_months: product.warranty_months,
+                },
+            );
+        }
 
-        Ok(price.map(|p| p.to_string().parse::<f64>().unwrap_or(0.0)))
+        Ok(prices)
     }
 }
 
@@ -219,510 +1139,1870 @@ impl OrderServiceImpl {
 impl OrderService for OrderServiceImpl {
     async fn create_order(
         &self,
-        request: Request<CreateOrderRequest>,
-    ) -> Result<Response<CreateOrderResponse>, Status> {
+        request: Request<CreateOrderRequest>,
+    ) -> Result<Response<CreateOrderResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        let actor = caller.actor();
+        let mut req = request.into_inner();
+
+        // Field rules are checked by the validation layer (see order.proto)
+        let guest = match (req.user_id.is_empty(), req.guest_email.is_empty()) {
+            (false, true) => {
+                caller.require_owner(&req.user_id)?;
+                false
+            }
+            (true, false) => true,
+            _ => {
+                return Err(error::invalid_argument(
+                    "INVALID_BUYER",
+                    "Either a user ID or a guest email is required, not both".to_string(),
+                ));
+            }
+        };
+        if guest && !self.settings.guest_checkout_enabled().await? {
+            return Err(error::failed_precondition(
+                "GUEST_CHECKOUT_DISABLED",
+                "Guest checkout is not available",
+            ));
+        }
+        if ops::is_switch_active(&self.db, ops::ORDER_INTAKE_PAUSED).await? {
+            return Err(error::failed_precondition(
+                "ORDER_INTAKE_PAUSED",
+                "Order intake is temporarily paused",
+            ));
+        }
+        if !self.settings.checkout_enabled().await? {
+            return Err(error::failed_precondition(
+                "CHECKOUT_DISABLED",
+                "Checkout is currently disabled",
+            ));
+        }
+
+        // Verify the user while the items are validated and the total
+        // calculated; stock is checked when it's taken below
+        let (user_exists, (validated_items, totals, problems)) = tokio::try_join!(
+            async {
+                if guest {
+                    Ok(true)
+                } else {
+                    self.verify_user_by_id(&req.user_id).await
+                }
+            },
+            self.price_items(
+                &req.items,
+                &req.shipping_region,
+                &req.shipping_method,
+                &req.currency,
+            ),
+        )?;
+        if !user_exists {
+            return Err(error::not_found("USER_NOT_FOUND", "User not found"));
+        }
+        if let Some(problem) = problems.into_iter().next() {
+            return Err(error::failed_precondition("ITEM_REJECTED", problem));
+        }
+
+        // Orders of digital products only have nothing to ship
+        if req.shipping_address.is_empty() && totals.requires_shipping {
+            return Err(error::invalid_argument(
+                "MISSING_SHIPPING_ADDRESS",
+                "Shipping address is required for physical products".to_string(),
+            ));
+        }
+
+        // A guest's order belongs to the guest identity of their email
+        // address; the confirmation email sent there carries their token
+        if guest {
+            req.user_id = guest::identity(&self.user_service, &req.guest_email).await?;
+        }
+
+        let order_id = Uuid::new_v4().to_string();
+        let order = self
+            .place_order(&order_id, &req, &actor, &totals, validated_items)
+            .await?;
+
+        let message = if order.status == OrderStatus::OnHold as i32 {
+            "Order created and held for review"
+        } else {
+            "Order created successfully"
+        };
+
+        Ok(Response::new(CreateOrderResponse {
+            success: true,
+            message: message.to_string(),
+            order_id,
+            order: Some(order),
+        }))
+    }
+
+    async fn quote_order(
+        &self,
+        request: Request<QuoteOrderRequest>,
+    ) -> Result<Response<QuoteOrderResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        let req = request.into_inner();
+        let mut problems = Vec::new();
+
+        // Same checks as CreateOrder, but nothing is written or reserved
+        match (req.user_id.is_empty(), req.guest_email.is_empty()) {
+            (false, true) => {
+                caller.require_owner(&req.user_id)?;
+                if !self.verify_user_by_id(&req.user_id).await? {
+                    problems.push("User not found".to_string());
+                }
+            }
+            (true, false) => {
+                if !self.settings.guest_checkout_enabled().await? {
+                    problems.push("Guest checkout is not available".to_string());
+                }
+            }
+            _ => {
+                problems.push("Either a user ID or a guest email is required, not both".to_string())
+            }
+        }
+
+        if req.items.is_empty() {
+            problems.push("Order must contain at least one item".to_string());
+        }
+
+        if ops::is_switch_active(&self.db, ops::ORDER_INTAKE_PAUSED).await? {
+            problems.push("Order intake is temporarily paused".to_string());
+        }
+        if !self.settings.checkout_enabled().await? {
+            problems.push("Checkout is currently disabled".to_string());
+        }
+
+        let (priced_items, totals, item_problems) = self
+            .price_items(
+                &req.items,
+                &req.shipping_region,
+                &req.shipping_method,
+                &req.currency,
+            )
+            .await?;
+        problems.extend(item_problems);
+        let mut items: Vec<OrderItem> = priced_items.into_iter().map(|p| p.item).collect();
+
+        if !items.is_empty() {
+            if req.shipping_address.is_empty() && totals.requires_shipping {
+                problems.push("Shipping address is required for physical products".to_string());
+            }
+
+            let product_ids: Vec<String> = items
+                .iter()
+                .map(|i| i.product_id.clone())
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .collect();
+            let availability = self.get_availability(product_ids).await?;
+            let mut stock: HashMap<String, i32> = availability
+                .iter()
+                .map(|(id, product)| (id.clone(), product.sellable_stock))
+                .collect();
+
+            // Lines take stock in order, as CreateOrder would, so a product on
+            // several lines is checked against what the earlier ones left
+            let mut short = HashSet::new();
+            for item in &mut items {
+                let product = availability.get(&item.product_id);
+                let left = stock.entry(item.product_id.clone()).or_default();
+                if product.is_some_and(|p| p.preorder) {
+                    item.status = OrderItemStatus::Backordered as i32;
+                } else if *left >= item.quantity {
+                    *left -= item.quantity;
+                } else if product.is_some_and(|p| p.allow_backorder) {
+                    item.status = OrderItemStatus::Backordered as i32;
+                } else if short.insert(item.product_id.clone()) {
+                    problems.push(format!(
+                        "Product {} not available in requested quantity",
+                        item.product_id
+                    ));
+                }
+            }
+        }
+
+        let delivery = totals.delivery_window(chrono::Utc::now().date_naive());
+        Ok(Response::new(QuoteOrderResponse {
+            success: problems.is_empty(),
+            message: if problems.is_empty() {
+                "Order can be placed".to_string()
+            } else {
+                format!("Order cannot be placed: {} problem(s)", problems.len())
+            },
+            items,
+            total_amount: totals.total.to_string(),
+            problems,
+            tax_amount: totals.tax.to_string(),
+            shipping_fee: totals.shipping.to_string(),
+            formatted_total: currency::format_amount(totals.total, &totals.conversion.currency),
+            estimated_delivery_from: format_date(delivery.map(|(from, _)| from)),
+            estimated_delivery_to: format_date(delivery.map(|(_, to)| to)),
+            currency: totals.conversion.currency,
+        }))
+    }
+
+    async fn create_draft_order(
+        &self,
+        request: Request<CreateDraftOrderRequest>,
+    ) -> Result<Response<CreateDraftOrderResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        let req = request.into_inner();
+        caller.require_owner(&req.user_id)?;
+
+        // Field rules are checked by the validation layer (see order.proto)
+        let valid_days = draft::valid_days(req.valid_days)
+            .map_err(|message| error::invalid_argument("INVALID_VALIDITY", message))?;
+
+        // Priced and checked like CreateOrder; stock is left alone until the
+        // draft is finalized
+        let (user_exists, (priced_items, totals, problems)) = tokio::try_join!(
+            self.verify_user_by_id(&req.user_id),
+            self.price_items(
+                &req.items,
+                &req.shipping_region,
+                &req.shipping_method,
+                &req.currency,
+            ),
+        )?;
+        if !user_exists {
+            return Err(error::not_found("USER_NOT_FOUND", "User not found"));
+        }
+        if let Some(problem) = problems.into_iter().next() {
+            return Err(error::failed_precondition("ITEM_REJECTED", problem));
+        }
+
+        if req.shipping_address.is_empty() && totals.requires_shipping {
+            return Err(error::invalid_argument(
+                "MISSING_SHIPPING_ADDRESS",
+                "Shipping address is required for physical products".to_string(),
+            ));
+        }
+
+        let draft_id = Uuid::new_v4().to_string();
+        self.write_draft(&draft_id, &req, &totals, &priced_items, valid_days)
+            .await?;
+
+        let (draft, items) = draft::load(&self.db, &draft_id)
+            .await?
+            .ok_or_else(|| Status::internal("Draft order missing after it was written"))?;
+
+        Ok(Response::new(CreateDraftOrderResponse {
+            success: true,
+            message: format!("Draft order saved, valid for {} days", valid_days),
+            draft: Some(self.draft_to_proto(&draft, &items).await?),
+        }))
+    }
+
+    async fn finalize_draft_order(
+        &self,
+        request: Request<FinalizeDraftOrderRequest>,
+    ) -> Result<Response<FinalizeDraftOrderResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        let actor = caller.actor();
+        let req = request.into_inner();
+
+        // Field rules are checked by the validation layer (see order.proto)
+        let Some((draft, items)) = draft::load(&self.db, &req.draft_id).await? else {
+            return Err(error::not_found("DRAFT_NOT_FOUND", "Draft order not found"));
+        };
+        caller.require_owner(&draft.user_id)?;
+        match draft.status.as_str() {
+            "FINALIZED" => {
+                return Err(error::failed_precondition(
+                    "DRAFT_NOT_OPEN",
+                    format!(
+                        "Draft order was already placed as order {}",
+                        draft.order_id.unwrap_or_default()
+                    ),
+                ));
+            }
+            "EXPIRED" => {
+                return Err(error::failed_precondition(
+                    "DRAFT_EXPIRED",
+                    "Draft order has expired",
+                ));
+            }
+            _ => {}
+        }
+        if ops::is_switch_active(&self.db, ops::ORDER_INTAKE_PAUSED).await? {
+            return Err(error::failed_precondition(
+                "ORDER_INTAKE_PAUSED",
+                "Order intake is temporarily paused",
+            ));
+        }
+        if !self.settings.checkout_enabled().await? {
+            return Err(error::failed_precondition(
+                "CHECKOUT_DISABLED",
+                "Checkout is currently disabled",
+            ));
+        }
+
+        // The order is charged what the draft quoted, in the draft's currency
+        // at the draft's rate
+        let priced_items: Vec<PricedItem> = items
+            .iter()
+            .map(|item| PricedItem {
+                item: item.to_proto(String::new()),
+                unit_price: item.price,
+                tax: item.tax_amount,
+                product_type: item.product_type.clone(),
+                warranty_months: item.warranty_months,
+            })
+            .collect();
+        let totals = OrderTotals {
+            total: draft.total_amount,
+            tax: draft.tax_amount,
+            shipping: draft.shipping_fee,
+            // Checked when the draft was written
+            requires_shipping: draft.shipping_address.is_some(),
+            conversion: Conversion {
+                currency: draft.currency.clone(),
+                rate: draft.exchange_rate,
+            },
+            transit_days: draft.min_transit_days.zip(draft.max_transit_days),
+        };
+        let order_req = CreateOrderRequest {
+            user_id: draft.user_id.clone(),
+            items: priced_items.iter().map(|p| p.item.clone()).collect(),
+            shipping_address: draft.shipping_address.clone().unwrap_or_default(),
+            shipping_region: draft.shipping_region.clone().unwrap_or_default(),
+            shipping_method: draft.shipping_method.clone().unwrap_or_default(),
+            currency: draft.currency.clone(),
+            billing_region: draft.billing_region.clone().unwrap_or_default(),
+            guest_email: String::new(),
+        };
+
+        // Claiming the draft first keeps concurrent calls from placing it twice
+        let order_id = Uuid::new_v4().to_string();
+        if !draft::claim(&self.db, &draft.id, &order_id).await? {
+            return Err(error::failed_precondition(
+                "DRAFT_NOT_OPEN",
+                "Draft order is no longer open",
+            ));
+        }
+        let order = match self
+            .place_order(&order_id, &order_req, &actor, &totals, priced_items)
+            .await
+        {
+            Ok(order) => order,
+            Err(e) => {
+                if let Err(release_err) = draft::release(&self.db, &draft.id, &order_id).await {
+                    warn!(draft_id = %draft.id, "Failed to reopen draft order: {}", release_err);
+                }
+                return Err(e);
+            }
+        };
+
+        let message = if order.status == OrderStatus::OnHold as i32 {
+            "Draft order placed and held for review"
+        } else {
+            "Draft order placed"
+        };
+
+        Ok(Response::new(FinalizeDraftOrderResponse {
+            success: true,
+            message: message.to_string(),
+            order_id,
+            order: Some(order),
+        }))
+    }
+
+    async fn update_order(
+        &self,
+        request: Request<UpdateOrderRequest>,
+    ) -> Result<Response<UpdateOrderResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        let actor = caller.actor();
+        let req = request.into_inner();
+
+        if req.order_id.is_empty() {
+            return Err(error::invalid_argument(
+                "MISSING_ORDER_ID",
+                "Order ID is required",
+            ));
+        }
+
+        let status_str = self
+            .status_to_string(OrderStatus::try_from(req.status).unwrap_or(OrderStatus::Pending));
+
+        let mut tx = self.db.begin().await.map_err(AppError::from)?;
+
+        // The row lock keeps the history's "from" values exact under concurrent updates
+        let current: Option<(String, String, Option<String>)> = sqlx::query_as(
+            "SELECT user_id, status, shipping_address FROM orders WHERE id = $1 FOR UPDATE",
+        )
+        .bind(&req.order_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+
+        let Some((user_id, old_status, old_address)) = current else {
+            return Err(error::not_found("ORDER_NOT_FOUND", "Order not found"));
+        };
+        caller.require_owner(&user_id)?;
+        // Owners may change the address; the status moves with fulfillment
+        if caller != Caller::Platform && old_status != status_str {
+            return Err(error::with_reason(
+                Code::PermissionDenied,
+                "ONLY_CANCEL",
+                "Customers can only cancel their orders, with CancelOrder",
+            ));
+        }
+
+        // An empty address keeps the current one
+        let shipping_address = if req.shipping_address.is_empty() {
+            old_address.clone()
+        } else {
+            Some(req.shipping_address.clone())
+        };
+
+        // Holds are placed by fraud screening and lifted by review only
+        if old_status != status_str && (old_status == "ON_HOLD" || status_str == "ON_HOLD") {
+            return Err(error::failed_precondition(
+                "ORDER_ON_HOLD",
+                "Held orders are released with ApproveOrder or RejectOrder",
+            ));
+        }
+
+        let order = sqlx::query_as::<_, DbOrder>(
+            "UPDATE orders SET status = $1, shipping_address = $2, updated_at = CURRENT_TIMESTAMP 
+             WHERE id = $3
+             RETURNING id, order_number, user_id, total_amount, status, shipping_address, tax_amount, shipping_region, shipping_method, shipping_fee, currency, exchange_rate, estimated_delivery_from, estimated_delivery_to, created_at, updated_at",
+        )
+        .bind(&status_str)
+        .bind(&shipping_address)
+        .bind(&req.order_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+
+        if old_status != status_str {
+            order_events::record(
+                &mut *tx,
+                &OrderEvent::new(&req.order_id, EventType::StatusChanged, &actor)
+                    .status(Some(&old_status), &status_str)
+                    .reason(&req.reason),
+            )
+            .await?;
+        }
+        if old_address != shipping_address {
+            order_events::record(
+                &mut *tx,
+                &OrderEvent::new(&req.order_id, EventType::AddressChanged, &actor)
+                    .reason(&req.reason),
+            )
+            .await?;
+        }
+
+        tx.commit().await.map_err(AppError::from)?;
+
+        // Warranties run from the delivery date
+        if status_str == "DELIVERED" {
+            warranty::start_warranties(&self.db, &req.order_id).await?;
+        }
+
+        let proto_order = self.db_order_to_proto(&order).await?;
+
+        Ok(Response::new(UpdateOrderResponse {
+            success: true,
+            message: "Order updated successfully".to_string(),
+            order: Some(proto_order),
+        }))
+    }
+
+    async fn cancel_order(
+        &self,
+        request: Request<CancelOrderRequest>,
+    ) -> Result<Response<CancelOrderResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        let actor = caller.actor();
+        let req = request.into_inner();
+
+        if req.order_id.is_empty() {
+            return Err(error::invalid_argument(
+                "MISSING_ORDER_ID",
+                "Order ID is required",
+            ));
+        }
+
+        // Start transaction to restore inventory
+        let mut tx = self.db.begin().await.map_err(AppError::from)?;
+
+        // Check if order exists and belongs to user. The row lock serializes concurrent
+        // cancellations so a retried cancel can't restore the same stock twice.
+        let order: Option<DbOrder> = sqlx::query_as(
+            "SELECT id, order_number, user_id, total_amount, status, shipping_address, tax_amount, shipping_region, shipping_method, shipping_fee, currency, exchange_rate, estimated_delivery_from, estimated_delivery_to, created_at, updated_at 
+             FROM orders WHERE id = $1 FOR UPDATE",
+        )
+        .bind(&req.order_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+
+        let order = match order {
+            Some(o) => o,
+            None => {
+                tx.rollback().await.map_err(AppError::from)?;
+                return Err(error::not_found("ORDER_NOT_FOUND", "Order not found"));
+            }
+        };
+
+        if !req.user_id.is_empty() && order.user_id != req.user_id {
+            tx.rollback().await.map_err(AppError::from)?;
+            return Err(error::with_reason(
+                Code::PermissionDenied,
+                "NOT_ORDER_OWNER",
+                "Order does not belong to this user",
+            ));
+        }
+        caller.require_owner(&order.user_id)?;
+
+        if order.status == "CANCELLED" {
+            tx.rollback().await.map_err(AppError::from)?;
+            return Err(error::failed_precondition(
+                "ORDER_CANCELLED",
+                "Order is already cancelled",
+            ));
+        }
+
+        if order.status == "DELIVERED" {
+            tx.rollback().await.map_err(AppError::from)?;
+            return Err(error::failed_precondition(
+                "ORDER_DELIVERED",
+                "Cannot cancel delivered order",
+            ));
+        }
+
+        // Shipped units are on their way and come back as a return, not to stock
+        if matches!(order.status.as_str(), "SHIPPED" | "PARTIALLY_SHIPPED") {
+            tx.rollback().await.map_err(AppError::from)?;
+            return Err(error::failed_precondition(
+                "ORDER_SHIPPED",
+                "Cannot cancel an order that has shipped; cancel its unshipped items instead",
+            ));
+        }
+
+        // Update order status, guarded so the transition happens at most once
+        let result = sqlx::query(
+            "UPDATE orders SET status = 'CANCELLED', updated_at = CURRENT_TIMESTAMP
+             WHERE id = $1 AND status NOT IN ('CANCELLED', 'DELIVERED', 'SHIPPED', 'PARTIALLY_SHIPPED')",
+        )
+        .bind(&req.order_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+
+        if result.rows_affected() == 0 {
+            tx.rollback().await.map_err(AppError::from)?;
+            return Err(error::with_reason(
+                Code::Aborted,
+                "CONCURRENT_UPDATE",
+                "Order was modified concurrently, please retry",
+            ));
+        }
+
+        order_events::record(
+            &mut *tx,
+            &OrderEvent::new(&req.order_id, EventType::Cancelled, &actor)
+                .status(Some(&order.status), "CANCELLED")
+                .reason(&req.reason),
+        )
+        .await?;
+
+        let stock_return = stock_return::record_order(&mut tx, &req.order_id).await?;
+
+        tx.commit().await.map_err(AppError::from)?;
+
+        stock_return::send_recorded(&self.db, &self.product_service, stock_return).await;
+
+        Ok(Response::new(CancelOrderResponse {
+            success: true,
+            message: "Order cancelled successfully".to_string(),
+        }))
+    }
+
+    async fn cancel_order_items(
+        &self,
+        request: Request<CancelOrderItemsRequest>,
+    ) -> Result<Response<CancelOrderItemsResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        let actor = caller.actor();
+        let req = request.into_inner();
+
+        // Field rules are checked by the validation layer (see order.proto)
+        let mut tx = self.db.begin().await.map_err(AppError::from)?;
+
+        // The row lock serializes amendments, so stock is restored only once
+        let current: Option<(String, String, Decimal)> = sqlx::query_as(
+            "SELECT user_id, status, total_amount FROM orders WHERE id = $1 FOR UPDATE",
+        )
+        .bind(&req.order_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+
+        let Some((user_id, status, old_total)) = current else {
+            return Err(error::not_found("ORDER_NOT_FOUND", "Order not found"));
+        };
+        caller.require_owner(&user_id)?;
+        if !matches!(
+            status.as_str(),
+            "PENDING" | "CONFIRMED" | "PROCESSING" | "PARTIALLY_SHIPPED"
+        ) {
+            return Err(error::failed_precondition(
+                "ORDER_STATUS_CONFLICT",
+                format!(
+                    "Items of a {} order cannot be cancelled",
+                    status.to_lowercase()
+                ),
+            ));
+        }
+
+        // Units to cancel per item; an item may be named more than once
+        let mut cancelled: HashMap<String, i32> = HashMap::new();
+        for item in &req.items {
+            let units = cancelled.entry(item.item_id.clone()).or_default();
+            *units = units.saturating_add(item.quantity);
+        }
+
+        let items = sqlx::query_as::<_, DbOrderItem>(
+            "SELECT id, order_id, product_id, quantity, price, status, tax_amount, fulfillment_status, shipment_tracking, shipped_at FROM order_items
+             WHERE order_id = $1 ORDER BY id",
+        )
+        .bind(&req.order_id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+
+        if let Some(item_id) = cancelled
+            .keys()
+            .find(|id| !items.iter().any(|item| &item.id == *id))
+        {
+            return Err(error::invalid_argument(
+                "ITEM_NOT_IN_ORDER",
+                format!("Item {} is not part of this order", item_id),
+            ));
+        }
+        let mut remaining = 0;
+        for item in &items {
+            let units = cancelled.get(&item.id).copied().unwrap_or(0);
+            if units > 0 && item.fulfillment_status == "SHIPPED" {
+                return Err(error::failed_precondition(
+                    "ITEM_SHIPPED",
+                    format!("Item {} has already shipped", item.id),
+                ));
+            }
+            if units > item.quantity {
+                return Err(error::failed_precondition(
+                    "INSUFFICIENT_QUANTITY",
+                    format!(
+                        "Item {} has only {} unit(s) to cancel",
+                        item.id, item.quantity
+                    ),
+                ));
+            }
+            remaining += item.quantity - units;
+        }
+        if remaining == 0 {
+            return Err(error::failed_precondition(
+                "CANCELS_WHOLE_ORDER",
+                "Cancelling every item cancels the order; use CancelOrder instead".to_string(),
+            ));
+        }
+
+        // Items already handed to a shipment are past cancelling
+        let shipped = shipping::shipped_items(&self.shipping_service, &req.order_id).await?;
+        if let Some(item_id) = cancelled.keys().find(|id| shipped.contains(*id)) {
+            return Err(error::failed_precondition(
+                "ITEM_SHIPPED",
+                format!("Item {} is already in a shipment", item_id),
+            ));
+        }
+
+        let mut changes = Vec::new();
+        let mut returned = Vec::new();
+        for item in items.iter().filter(|item| cancelled.contains_key(&item.id)) {
+            let units = cancelled[&item.id];
+            if units == item.quantity {
+                sqlx::query("DELETE FROM order_items WHERE id = $1")
+                    .bind(&item.id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(AppError::from)?;
+            } else {
+                // The line's tax shrinks with it
+                sqlx::query(
+                    "UPDATE order_items
+                     SET quantity = quantity - $1, tax_amount = ROUND(tax_amount * (quantity - $1) / quantity, 2)
+                     WHERE id = $2",
+                )
+                .bind(units)
+                .bind(&item.id)
+                .execute(&mut *tx)
+                .await
+                .map_err(AppError::from)?;
+            }
+
+            // Backordered items never took any stock
+            if item.status == "ALLOCATED" {
+                returned.push((item.product_id.clone(), units));
+            }
+
+            changes.push(format!(
+                "{} of {} x product {}",
+                units, item.quantity, item.product_id
+            ));
+        }
+
+        // The shipping fee stays as charged
+        let new_total: Decimal = sqlx::query_scalar(
+            "UPDATE orders o
+             SET tax_amount = i.tax, total_amount = i.subtotal + i.tax + o.shipping_fee,
+                 updated_at = CURRENT_TIMESTAMP
+             FROM (
+                 SELECT COALESCE(SUM(price * quantity), 0) AS subtotal,
+                        COALESCE(SUM(tax_amount), 0) AS tax
+                 FROM order_items WHERE order_id = $1
+             ) i
+             WHERE o.id = $1
+             RETURNING o.total_amount",
+        )
+        .bind(&req.order_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+
+        let details = format!(
+            "Cancelled {}; total {} -> {}",
+            changes.join(", "),
+            old_total,
+            new_total
+        );
+        let mut event = OrderEvent::new(&req.order_id, EventType::ItemsCancelled, &actor)
+            .reason(&req.reason)
+            .details(&details);
+
+        // Cancelling what was left to ship completes a partial shipment
+        if status == "PARTIALLY_SHIPPED"
+            && fulfillment_status(&mut *tx, &req.order_id).await? == Some("SHIPPED")
+        {
+            sqlx::query(
+                "UPDATE orders SET status = 'SHIPPED', updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+            )
+            .bind(&req.order_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::from)?;
+            event = event.status(Some(&status), "SHIPPED");
+        }
+        order_events::record(&mut *tx, &event).await?;
+
+        let stock_return = stock_return::record(&mut tx, &req.order_id, &returned).await?;
+
+        tx.commit().await.map_err(AppError::from)?;
+
+        stock_return::send_recorded(&self.db, &self.product_service, stock_return).await;
+
+        let order = sqlx::query_as::<_, DbOrder>(
+            "SELECT id, order_number, user_id, total_amount, status, shipping_address, tax_amount, shipping_region, shipping_method, shipping_fee, currency, exchange_rate, estimated_delivery_from, estimated_delivery_to, created_at, updated_at 
+             FROM orders WHERE id = $1",
+        )
+        .bind(&req.order_id)
+        .fetch_one(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        Ok(Response::new(CancelOrderItemsResponse {
+            success: true,
+            message: "Order items cancelled".to_string(),
+            order: Some(self.db_order_to_proto(&order).await?),
+        }))
+    }
+
+    async fn get_order(
+        &self,
+        request: Request<GetOrderRequest>,
+    ) -> Result<Response<GetOrderResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        let req = request.into_inner();
+
+        if req.order_id.is_empty() && req.order_number.is_empty() {
+            return Err(error::invalid_argument(
+                "MISSING_ORDER_ID",
+                "Order ID or order number is required",
+            ));
+        }
+
+        let order_result = if req.order_id.is_empty() {
+            self.repository
+                .find_by_number(&self.order_numbers.normalize(&req.order_number))
+                .await?
+        } else {
+            self.repository.find(&req.order_id).await?
+        };
+
+        match order_result {
+            Some(order) => {
+                caller.require_owner(&order.user_id)?;
+                let proto_order = self.db_order_to_proto(&order).await?;
+                Ok(Response::new(GetOrderResponse {
+                    success: true,
+                    message: "Order retrieved successfully".to_string(),
+                    order: Some(proto_order),
+                }))
+            }
+            None => Err(error::not_found("ORDER_NOT_FOUND", "Order not found")),
+        }
+    }
+
+    async fn get_orders_by_ids(
+        &self,
+        request: Request<GetOrdersByIDsRequest>,
+    ) -> Result<Response<GetOrdersByIDsResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        // Field rules are checked by the validation layer (see order.proto)
+        let mut seen = HashSet::new();
+        let order_ids: Vec<String> = req
+            .order_ids
+            .into_iter()
+            .filter(|id| seen.insert(id.clone()))
+            .collect();
+
+        let mut orders: HashMap<String, DbOrder> = self
+            .repository
+            .find_many(&order_ids)
+            .await?
+            .into_iter()
+            .map(|o| (o.id.clone(), o))
+            .collect();
+
+        let mut found = Vec::new();
+        let mut missing_ids = Vec::new();
+        for id in order_ids {
+            match orders.remove(&id) {
+                Some(order) => found.push(order),
+                None => missing_ids.push(id),
+            }
+        }
+
+        let proto_orders = self.db_orders_to_proto(&found).await?;
+
+        Ok(Response::new(GetOrdersByIDsResponse {
+            success: true,
+            message: format!("Retrieved {} orders", proto_orders.len()),
+            orders: proto_orders,
+            missing_ids,
+        }))
+    }
+
+    async fn list_orders(
+        &self,
+        request: Request<ListOrdersRequest>,
+    ) -> Result<Response<ListOrdersResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        let page = PageRequest::new(req.page, req.page_size);
+        let after = decode_page_token(&req.page_token)
+            .map_err(|message| error::invalid_argument("INVALID_PAGE_TOKEN", message))?;
+
+        // Status 0 (PENDING) doubles as "any status"
+        let status = (req.status != 0).then(|| {
+            self.status_to_string(OrderStatus::try_from(req.status).unwrap_or(OrderStatus::Pending))
+        });
+        let filters = ListFilters::parse(&req, status)
+            .map_err(|message| error::invalid_argument("INVALID_FILTER", message))?;
+
+        let mut query = QueryBuilder::<Postgres>::new(
+            "SELECT id, order_number, user_id, total_amount, status, shipping_address, tax_amount, shipping_region, shipping_method, shipping_fee, currency, exchange_rate, estimated_delivery_from, estimated_delivery_to, created_at, updated_at 
+             FROM orders",
+        );
+        push_list_filters(&mut query, &filters);
+        push_order_page(&mut query, &page, after.as_ref(), SortOrder::Desc);
+
+        let mut orders = query
+            .build_query_as::<DbOrder>()
+            .fetch_all(&self.db)
+            .await
+            .map_err(AppError::from)?;
+        let next_page_token = pagination::next_page_token(&mut orders, &page, order_cursor);
+
+        let mut count_query = QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM orders");
+        push_list_filters(&mut count_query, &filters);
+
+        let total_count = pagination::fetch_count(count_query, &self.db)
+            .await
+            .map_err(AppError::from)?;
+
+        let proto_orders = self.db_orders_to_proto(&orders).await?;
+
+        Ok(Response::new(ListOrdersResponse {
+            success: true,
+            message: format!("Retrieved {} orders", proto_orders.len()),
+            orders: proto_orders,
+            total_count,
+            next_page_token: next_page_token.unwrap_or_default(),
+        }))
+    }
+
+    async fn get_orders_by_user(
+        &self,
+        request: Request<GetOrdersByUserRequest>,
+    ) -> Result<Response<GetOrdersByUserResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        let req = request.into_inner();
+
+        if req.user_id.is_empty() {
+            return Err(error::invalid_argument(
+                "MISSING_USER_ID",
+                "User ID is required",
+            ));
+        }
+        caller.require_owner(&req.user_id)?;
+
+        let page = PageRequest::new(req.page, req.page_size);
+        let after = decode_page_token(&req.page_token)
+            .map_err(|message| error::invalid_argument("INVALID_PAGE_TOKEN", message))?;
+
+        // Status 0 (PENDING) doubles as "any status"
+        let status = (req.status != 0).then(|| {
+            self.status_to_string(OrderStatus::try_from(req.status).unwrap_or(OrderStatus::Pending))
+        });
+        let order = match OrderSort::try_from(req.sort).unwrap_or(OrderSort::NewestFirst) {
+            OrderSort::NewestFirst => SortOrder::Desc,
+            OrderSort::OldestFirst => SortOrder::Asc,
+        };
+
+        let mut orders = self
+            .repository
+            .list_by_user(
+                &req.user_id,
+                status.as_deref(),
+                &page,
+                after.as_ref(),
+                order,
+            )
+            .await?;
+        let next_page_token = pagination::next_page_token(&mut orders, &page, order_cursor);
+
+        let count = self
+            .repository
+            .count_by_user(&req.user_id, status.as_deref())
+            .await?;
+
+        let proto_orders = self.db_orders_to_proto(&orders).await?;
+
+        Ok(Response::new(GetOrdersByUserResponse {
+            success: true,
+            message: format!("Retrieved {} orders for user", proto_orders.len()),
+            orders: proto_orders,
+            total_count: count as i32,
+            next_page_token: next_page_token.unwrap_or_default(),
+        }))
+    }
+
+    type ExportOrdersStream = ReceiverStream<Result<ExportOrdersResponse, Status>>;
+
+    async fn export_orders(
+        &self,
+        request: Request<ExportOrdersRequest>,
+    ) -> Result<Response<Self::ExportOrdersStream>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        let export = Export::parse(&req).map_err(Status::invalid_argument)?;
+        Ok(Response::new(export.stream(self.db.clone())))
+    }
+
+    async fn record_item_tracking(
+        &self,
+        request: Request<RecordItemTrackingRequest>,
+    ) -> Result<Response<RecordItemTrackingResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        if req.order_id.is_empty() || req.item_id.is_empty() {
+            return Err(error::invalid_argument(
+                "MISSING_ORDER_ITEM",
+                "Order ID and item ID are required",
+            ));
+        }
+
+        if req.tracking.is_empty() {
+            return Err(error::invalid_argument(
+                "INVALID_TRACKING",
+                "At least one serial or lot number is required",
+            ));
+        }
+
+        let mut entries = Vec::with_capacity(req.tracking.len());
+        for entry in &req.tracking {
+            if entry.serial_number.is_empty() && entry.lot_number.is_empty() {
+                return Err(error::invalid_argument(
+                    "INVALID_TRACKING",
+                    "Each entry needs a serial or lot number",
+                ));
+            }
+            // A serial number always identifies a single unit
+            let quantity = match (entry.serial_number.is_empty(), entry.quantity) {
+                (false, 0 | 1) => 1,
+                (false, _) => {
+                    return Err(error::invalid_argument(
+                        "INVALID_TRACKING",
+                        "A serial number covers exactly one unit",
+                    ));
+                }
+                (true, q) if q > 0 => q,
+                (true, _) => {
+                    return Err(error::invalid_argument(
+                        "INVALID_TRACKING",
+                        "Lot quantity must be positive",
+                    ));
+                }
+            };
+            entries.push((entry, quantity));
+        }
+
+        let mut tx = self.db.begin().await.map_err(AppError::from)?;
+
+        let item: Option<(String, i32, String)> = sqlx::query_as(
+            "SELECT oi.product_id, oi.quantity, o.status
+             FROM order_items oi JOIN orders o ON o.id = oi.order_id
+             WHERE oi.id = $1 AND oi.order_id = $2
+             FOR UPDATE OF oi",
+        )
+        .bind(&req.item_id)
+        .bind(&req.order_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+
+        let (product_id, item_quantity, status) = match item {
+            Some(item) => item,
+            None => {
+                return Err(error::not_found(
+                    "ORDER_ITEM_NOT_FOUND",
+                    "Order item not found",
+                ));
+            }
+        };
+
+        if status == "CANCELLED" {
+            return Err(error::failed_precondition(
+                "ORDER_CANCELLED",
+                "Cannot record tracking for a cancelled order",
+            ));
+        }
+
+        // Quarantined products and lots must not be shipped
+        for (entry, _) in &entries {
+            let lot_number = (!entry.lot_number.is_empty()).then_some(entry.lot_number.as_str());
+            if let Some(reason) =
+                recall::active_quarantine(&self.db, &product_id, lot_number).await?
+            {
+                return Err(error::failed_precondition(
+                    "QUARANTINED",
+                    format!("Product or lot is quarantined: {}", reason),
+                ));
+            }
+        }
+
+        let tracked: i64 = sqlx::query_scalar(
+            "SELECT COALESCE(SUM(quantity), 0)::BIGINT FROM order_item_tracking WHERE order_item_id = $1",
+        )
+        .bind(&req.item_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+
+        let requested: i64 = entries.iter().map(|(_, q)| *q as i64).sum();
+        if tracked + requested > item_quantity as i64 {
+            return Err(error::failed_precondition(
+                "TRACKING_EXCEEDS_QUANTITY",
+                format!(
+                    "Tracking covers {} units but the item only has {} ({} already recorded)",
+                    tracked + requested,
+                    item_quantity,
+                    tracked
+                ),
+            ));
+        }
+
+        let serials: Vec<String> = entries
+            .iter()
+            .filter(|(e, _)| !e.serial_number.is_empty())
+            .map(|(e, _)| e.serial_number.clone())
+            .collect();
+        if !serials.is_empty() {
+            let taken: Vec<String> = sqlx::query_scalar(
+                "SELECT serial_number FROM order_item_tracking
+                 WHERE product_id = $1 AND serial_number = ANY($2)",
+            )
+            .bind(&product_id)
+            .bind(&serials)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(AppError::from)?;
+
+            if !taken.is_empty() {
+                return Err(error::already_exists(
+                    "SERIALS_RECORDED",
+                    format!("Serial numbers already recorded: {}", taken.join(", ")),
+                ));
+            }
+        }
+
+        for (entry, quantity) in &entries {
+            sqlx::query(
+                "INSERT INTO order_item_tracking (id, order_item_id, product_id, serial_number, lot_number, quantity)
+                 VALUES ($1, $2, $3, $4, $5, $6)",
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(&req.item_id)
+            .bind(&product_id)
+            .bind(if entry.serial_number.is_empty() {
+                None
+            } else {
+                Some(&entry.serial_number)
+            })
+            .bind(if entry.lot_number.is_empty() {
+                None
+            } else {
+                Some(&entry.lot_number)
+            })
+            .bind(quantity)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::from)?;
+        }
+
+        tx.commit().await.map_err(AppError::from)?;
+
+        let tracking = self
+            .get_item_tracking(std::slice::from_ref(&req.item_id))
+            .await?
+            .remove(&req.item_id)
+            .unwrap_or_default();
+
+        Ok(Response::new(RecordItemTrackingResponse {
+            success: true,
+            message: format!("Recorded tracking for {} units", requested),
+            tracking,
+        }))
+    }
+
+    async fn find_orders_by_lot(
+        &self,
+        request: Request<FindOrdersByLotRequest>,
+    ) -> Result<Response<FindOrdersByLotResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        if req.lot_number.is_empty() {
+            return Err(error::invalid_argument(
+                "MISSING_LOT_NUMBER",
+                "Lot number is required",
+            ));
+        }
+
+        let rows = sqlx::query_as::<_, DbLotOrderItem>(
+            "SELECT o.id AS order_id, o.user_id, oi.id AS item_id, t.product_id,
+                    SUM(t.quantity)::INT AS quantity, o.status
+             FROM order_item_tracking t
+             JOIN order_items oi ON oi.id = t.order_item_id
+             JOIN orders o ON o.id = oi.order_id
+             WHERE t.lot_number = $1 AND ($2 = '' OR t.product_id = $2)
+             GROUP BY o.id, o.user_id, oi.id, t.product_id, o.status, o.created_at
+             ORDER BY o.created_at",
+        )
+        .bind(&req.lot_number)
+        .bind(&req.product_id)
+        .fetch_all(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        let items: Vec<LotOrderItem> = rows
+            .into_iter()
+            .map(|row| LotOrderItem {
+                status: self.status_to_proto(&row.status) as i32,
+                order_id: row.order_id,
+                user_id: row.user_id,
+                item_id: row.item_id,
+                product_id: row.product_id,
+                quantity: row.quantity,
+            })
+            .collect();
+
+        Ok(Response::new(FindOrdersByLotResponse {
+            success: true,
+            message: format!(
+                "Found {} order items from lot {}",
+                items.len(),
+                req.lot_number
+            ),
+            items,
+        }))
+    }
+
+    async fn verify_purchase(
+        &self,
+        request: Request<VerifyPurchaseRequest>,
+    ) -> Result<Response<VerifyPurchaseResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
         let req = request.into_inner();
 
-        // Validate input
-        if req.user_id.is_empty() {
-            return Ok(Response::new(CreateOrderResponse {
-                success: false,
-                message: "User ID is required".to_string(),
-                order_id: String::new(),
-                order: None,
-            }));
-        }
-
-        if req.items.is_empty() {
-            return Ok(Response::new(CreateOrderResponse {
-                success: false,
-                message: "Order must contain at least one item".to_string(),
-                order_id: String::new(),
-                order: None,
-            }));
+        if req.user_id.is_empty() || req.product_id.is_empty() {
+            return Err(Status::invalid_argument(
+                "User ID and product ID are required",
+            ));
         }
+        caller.require_owner(&req.user_id)?;
+
+        let order_id: Option<String> = sqlx::query_scalar(
+            "SELECT o.id FROM orders o
+             JOIN order_items oi ON oi.order_id = o.id
+             WHERE o.user_id = $1 AND oi.product_id = $2 AND o.status = 'DELIVERED'
+             ORDER BY o.updated_at DESC
+             LIMIT 1",
+        )
+        .bind(&req.user_id)
+        .bind(&req.product_id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(AppError::from)?;
 
-        // Verify user exists
-        if !self.verify_user_by_id(&req.user_id).await? {
-            return Ok(Response::new(CreateOrderResponse {
-                success: false,
-                message: "User not found".to_string(),
-                order_id: String::new(),
-                order: None,
-            }));
-        }
+        Ok(Response::new(VerifyPurchaseResponse {
+            purchased: order_id.is_some(),
+            order_id: order_id.unwrap_or_default(),
+        }))
+    }
 
-        // Check product availability and calculate total
-        let mut total_amount = 0.0;
-        let mut validated_items = Vec::new();
+    async fn refund_order(
+        &self,
+        request: Request<RefundOrderRequest>,
+    ) -> Result<Response<RefundOrderResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        caller.require_platform()?;
+        let actor = caller.actor();
+        let req = request.into_inner();
 
-        for item in &req.items {
-            if item.quantity <= 0 {
-                return Ok(Response::new(CreateOrderResponse {
-                    success: false,
-                    message: format!("Invalid quantity for product {}", item.product_id),
-                    order_id: String::new(),
-                    order: None,
-                }));
+        // Field rules are checked by the validation layer (see order.proto)
+        let status: Option<String> = sqlx::query_scalar("SELECT status FROM orders WHERE id = $1")
+            .bind(&req.order_id)
+            .fetch_optional(&self.db)
+            .await
+            .map_err(AppError::from)?;
+        match status {
+            None => return Err(error::not_found("ORDER_NOT_FOUND", "Order not found")),
+            Some(status) if !refund::refundable(&status) => {
+                return Err(error::failed_precondition(
+                    "ORDER_STATUS_CONFLICT",
+                    "Only shipped or delivered orders can be refunded".to_string(),
+                ));
             }
+            Some(_) => {}
+        }
 
-            // Check availability
-            if !self
-                .check_product_availability(&item.product_id, item.quantity)
+        // A refund whose outcome is unknown goes first, under the same
+        // idempotency key, so its payment can't be refunded twice
+        let pending = match refund::pending(&self.db, &req.order_id).await? {
+            Some(pending) => {
+                info!(refund_id = %pending.id, "Sending pending refund again");
+                pending
+            }
+            None => {
+                let payment = refund::order_payment(&self.payment_service, &req.order_id).await?;
+                let Some(payment) = payment.filter(|p| {
+                    matches!(
+                        PaymentStatus::try_from(p.status),
+                        Ok(PaymentStatus::Captured | PaymentStatus::PartiallyRefunded)
+                    )
+                }) else {
+                    return Err(error::failed_precondition(
+                        "NO_CAPTURED_PAYMENT",
+                        "Order has no captured payment to refund",
+                    ));
+                };
+
+                let amount = req.amount.parse::<Decimal>().ok();
+                match refund::reserve(
+                    &self.db,
+                    &req.order_id,
+                    &payment,
+                    &req.items,
+                    amount,
+                    &req.reason,
+                )
                 .await?
-            {
-                return Ok(Response::new(CreateOrderResponse {
-                    success: false,
-                    message: format!(
-                        "Product {} not available in requested quantity",
-                        item.product_id
-                    ),
-                    order_id: String::new(),
-                    order: None,
-                }));
+                {
+                    Ok(pending) => pending,
+                    Err(message) => {
+                        return Err(error::failed_precondition("REFUND_REFUSED", message));
+                    }
+                }
             }
+        };
 
-            // Get current price
-            let price = match self.get_product_price(&item.product_id).await? {
-                Some(p) => p,
-                None => {
-                    return Ok(Response::new(CreateOrderResponse {
-                        success: false,
-                        message: format!("Product {} not found", item.product_id),
-                        order_id: String::new(),
-                        order: None,
-                    }));
-                }
-            };
+        match refund::refund_payment(&self.payment_service, &pending).await {
+            Ok(Ok(payment_refund_id)) => {
+                let refund = refund::complete(
+                    &self.db,
+                    &self.product_service,
+                    &pending.id,
+                    &payment_refund_id,
+                    &actor,
+                )
+                .await?;
+                Ok(Response::new(RefundOrderResponse {
+                    success: true,
+                    message: format!("Refunded {}", refund.amount),
+                    refund: Some(refund),
+                }))
+            }
+            Ok(Err(message)) => {
+                refund::fail(&self.db, &pending.id).await?;
+                Err(error::failed_precondition("REFUND_DECLINED", message))
+            }
+            Err(e) if error::is_refusal(&e) => {
+                refund::fail(&self.db, &pending.id).await?;
+                Err(e)
+            }
+            Err(e) => {
+                // The payment service may have paid it; it stays pending
+                // until a retry gets the outcome
+                warn!(refund_id = %pending.id, "Payment refund outcome unknown: {}", e);
+                Err(e)
+            }
+        }
+    }
 
-            let subtotal = price * item.quantity as f64;
-            total_amount += subtotal;
+    async fn list_refunds(
+        &self,
+        request: Request<ListRefundsRequest>,
+    ) -> Result<Response<ListRefundsResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        let req = request.into_inner();
 
-            validated_items.push((item, price));
-        }
+        // Field rules are checked by the validation layer (see order.proto)
+        let user_id: Option<String> =
+            sqlx::query_scalar("SELECT user_id FROM orders WHERE id = $1")
+                .bind(&req.order_id)
+                .fetch_optional(&self.db)
+                .await
+                .map_err(AppError::from)?;
+        let Some(user_id) = user_id else {
+            return Err(error::not_found("ORDER_NOT_FOUND", "Order not found"));
+        };
+        caller.require_owner(&user_id)?;
 
-        // Start transaction
-        let mut tx = self
-            .db
-            .begin()
-            .await
-            .map_err(|e| Status::internal(format!("Transaction error: {}", e)))?;
+        let refunds = refund::list(&self.db, &req.order_id).await?;
 
-        let order_id = Uuid::new_v4().to_string();
-        let total_decimal = sqlx::types::Decimal::from_f64_retain(total_amount)
-            .ok_or_else(|| Status::invalid_argument("Invalid total amount"))?;
+        Ok(Response::new(ListRefundsResponse {
+            success: true,
+            message: format!("Retrieved {} refunds", refunds.len()),
+            refunds,
+        }))
+    }
 
-        // Create order
-        sqlx::query(
-            "INSERT INTO orders (id, user_id, total_amount, status, shipping_address) 
-             VALUES ($1, $2, $3, $4, $5)",
-        )
-        .bind(&order_id)
-        .bind(&req.user_id)
-        .bind(total_decimal)
-        .bind("PENDING")
-        .bind(if req.shipping_address.is_empty() {
-            None
-        } else {
-            Some(&req.shipping_address)
-        })
-        .execute(&mut *tx)
-        .await
-        .map_err(|e| Status::internal(format!("Database error: {}", e)))?;
+    async fn record_shipment_event(
+        &self,
+        request: Request<RecordShipmentEventRequest>,
+    ) -> Result<Response<RecordShipmentEventResponse>, Status> {
+        // Sent by the shipping service with its credentials
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+        let actor = order_events::system_actor("shipping");
+
+        // Field rules are checked by the validation layer (see order.proto)
+        let (to, from, reason): (&str, &[&str], &str) = match ShipmentEvent::try_from(req.event) {
+            Ok(ShipmentEvent::ShipmentInTransit) => (
+                "SHIPPED",
+                &["CONFIRMED", "PROCESSING", "PARTIALLY_SHIPPED"],
+                "Shipment on its way",
+            ),
+            Ok(ShipmentEvent::ShipmentDelivered) => (
+                "DELIVERED",
+                &["CONFIRMED", "PROCESSING", "PARTIALLY_SHIPPED", "SHIPPED"],
+                "Every shipment delivered",
+            ),
+            Err(_) => {
+                return Err(error::invalid_argument(
+                    "UNKNOWN_SHIPMENT_EVENT",
+                    "Unknown shipment event",
+                ));
+            }
+        };
 
-        // Create order items and update inventory
-        for (item, price) in validated_items {
-            let item_id = Uuid::new_v4().to_string();
-            let price_decimal = sqlx::types::Decimal::from_f64_retain(price)
-                .ok_or_else(|| Status::invalid_argument("Invalid price"))?;
+        let mut tx = self.db.begin().await.map_err(AppError::from)?;
+
+        let status: Option<String> =
+            sqlx::query_scalar("SELECT status FROM orders WHERE id = $1 FOR UPDATE")
+                .bind(&req.order_id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(AppError::from)?;
+        let Some(status) = status else {
+            return Err(error::not_found("ORDER_NOT_FOUND", "Order not found"));
+        };
 
+        // Only forward moves, so redelivered and out-of-order events can't
+        // move an order backwards
+        let message = if from.contains(&status.as_str()) {
             sqlx::query(
-                "INSERT INTO order_items (id, order_id, product_id, quantity, price) 
-                 VALUES ($1, $2, $3, $4, $5)",
+                "UPDATE orders SET status = $1, updated_at = CURRENT_TIMESTAMP WHERE id = $2",
             )
-            .bind(&item_id)
-            .bind(&order_id)
-            .bind(&item.product_id)
-            .bind(item.quantity)
-            .bind(price_decimal)
+            .bind(to)
+            .bind(&req.order_id)
             .execute(&mut *tx)
             .await
-            .map_err(|e| Status::internal(format!("Database error: {}", e)))?;
+            .map_err(AppError::from)?;
 
-            // Update product inventory
+            // The whole order ships, so whatever items were left ship with it
             sqlx::query(
-                "UPDATE products SET stock_quantity = stock_quantity - $1, updated_at = CURRENT_TIMESTAMP WHERE id = $2",
+                "UPDATE order_items SET fulfillment_status = 'SHIPPED', shipped_at = CURRENT_TIMESTAMP
+                 WHERE order_id = $1 AND fulfillment_status = 'UNFULFILLED'",
             )
-            .bind(item.quantity)
-            .bind(&item.product_id)
+            .bind(&req.order_id)
             .execute(&mut *tx)
             .await
-            .map_err(|e| Status::internal(format!("Database error: {}", e)))?;
-        }
+            .map_err(AppError::from)?;
 
-        tx.commit()
-            .await
-            .map_err(|e| Status::internal(format!("Commit error: {}", e)))?;
+            order_events::record(
+                &mut *tx,
+                &OrderEvent::new(&req.order_id, EventType::StatusChanged, &actor)
+                    .status(Some(&status), to)
+                    .reason(reason),
+            )
+            .await?;
+
+            tx.commit().await.map_err(AppError::from)?;
+
+            // Warranties run from the delivery date
+            if to == "DELIVERED" {
+                warranty::start_warranties(&self.db, &req.order_id).await?;
+            }
+            format!("Order is now {}", to)
+        } else if status == to || (to == "SHIPPED" && status == "DELIVERED") {
+            format!("Order is already {}", status)
+        } else {
+            return Err(error::failed_precondition(
+                "ORDER_STATUS_CONFLICT",
+                format!("Order is {} and can't become {}", status, to),
+            ));
+        };
 
-        // Fetch created order
         let order = sqlx::query_as::<_, DbOrder>(
-            "SELECT id, user_id, total_amount, status, shipping_address, created_at, updated_at 
+            "SELECT id, order_number, user_id, total_amount, status, shipping_address, tax_amount, shipping_region, shipping_method, shipping_fee, currency, exchange_rate, estimated_delivery_from, estimated_delivery_to, created_at, updated_at 
              FROM orders WHERE id = $1",
         )
-        .bind(&order_id)
+        .bind(&req.order_id)
         .fetch_one(&self.db)
         .await
-        .map_err(|e| Status::internal(format!("Database error: {}", e)))?;
+        .map_err(AppError::from)?;
 
         let proto_order = self.db_order_to_proto(&order).await?;
 
-        Ok(Response::new(CreateOrderResponse {
+        Ok(Response::new(RecordShipmentEventResponse {
             success: true,
-            message: "Order created successfully".to_string(),
-            order_id,
+            message,
             order: Some(proto_order),
         }))
     }
 
-    async fn update_order(
+    async fn mark_items_shipped(
         &self,
-        request: Request<UpdateOrderRequest>,
-    ) -> Result<Response<UpdateOrderResponse>, Status> {
+        request: Request<MarkItemsShippedRequest>,
+    ) -> Result<Response<MarkItemsShippedResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        caller.require_platform()?;
+        let actor = caller.actor();
         let req = request.into_inner();
 
-        if req.order_id.is_empty() {
-            return Ok(Response::new(UpdateOrderResponse {
-                success: false,
-                message: "Order ID is required".to_string(),
-                order: None,
-            }));
+        // Field rules are checked by the validation layer (see order.proto)
+        let mut tx = self.db.begin().await.map_err(AppError::from)?;
+
+        let status: Option<String> =
+            sqlx::query_scalar("SELECT status FROM orders WHERE id = $1 FOR UPDATE")
+                .bind(&req.order_id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(AppError::from)?;
+        let Some(status) = status else {
+            return Err(error::not_found("ORDER_NOT_FOUND", "Order not found"));
+        };
+        if !matches!(
+            status.as_str(),
+            "CONFIRMED" | "PROCESSING" | "PARTIALLY_SHIPPED" | "SHIPPED"
+        ) {
+            return Err(error::failed_precondition(
+                "ORDER_STATUS_CONFLICT",
+                format!("Items of a {} order cannot ship", status.to_lowercase()),
+            ));
         }
 
-        let status_str = self
-            .status_to_string(OrderStatus::try_from(req.status).unwrap_or(OrderStatus::Pending));
+        let digital: HashMap<String, bool> = sqlx::query_as(
+            "SELECT id, product_type = 'DIGITAL' FROM order_items WHERE order_id = $1",
+        )
+        .bind(&req.order_id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(AppError::from)?
+        .into_iter()
+        .collect();
+        for item_id in &req.item_ids {
+            match digital.get(item_id) {
+                None => {
+                    return Err(error::invalid_argument(
+                        "ITEM_NOT_IN_ORDER",
+                        format!("Item {} is not part of this order", item_id),
+                    ));
+                }
+                Some(true) => {
+                    return Err(error::failed_precondition(
+                        "ITEM_NOT_SHIPPABLE",
+                        format!("Item {} is a digital product and doesn't ship", item_id),
+                    ));
+                }
+                Some(false) => {}
+            }
+        }
 
-        let result = sqlx::query(
-            "UPDATE orders SET status = $1, shipping_address = $2, updated_at = CURRENT_TIMESTAMP 
-             WHERE id = $3",
+        // Items that shipped before keep their tracking, so retries change nothing
+        let shipped: Vec<String> = sqlx::query_scalar(
+            "UPDATE order_items
+             SET fulfillment_status = 'SHIPPED', shipment_tracking = NULLIF($1, ''),
+                 shipped_at = CURRENT_TIMESTAMP
+             WHERE order_id = $2 AND id = ANY($3) AND fulfillment_status = 'UNFULFILLED'
+             RETURNING id",
         )
-        .bind(&status_str)
-        .bind(if req.shipping_address.is_empty() {
-            None
-        } else {
-            Some(&req.shipping_address)
-        })
+        .bind(&req.tracking)
         .bind(&req.order_id)
-        .execute(&self.db)
+        .bind(&req.item_ids)
+        .fetch_all(&mut *tx)
         .await
-        .map_err(|e| Status::internal(format!("Database error: {}", e)))?;
+        .map_err(AppError::from)?;
 
-        if result.rows_affected() == 0 {
-            return Ok(Response::new(UpdateOrderResponse {
-                success: false,
-                message: "Order not found".to_string(),
-                order: None,
-            }));
-        }
+        let message = if shipped.is_empty() {
+            "Items have already shipped".to_string()
+        } else {
+            let mut details = format!("Shipped items {}", shipped.join(", "));
+            if !req.tracking.is_empty() {
+                details.push_str(&format!(" ({})", req.tracking));
+            }
+
+            // Orders only move forward, e.g. a SHIPPED order stays so
+            let to = fulfillment_status(&mut *tx, &req.order_id)
+                .await?
+                .filter(|to| status != "SHIPPED" && *to != status);
+            let event = match to {
+                Some(to) => {
+                    sqlx::query(
+                        "UPDATE orders SET status = $1, updated_at = CURRENT_TIMESTAMP WHERE id = $2",
+                    )
+                    .bind(to)
+                    .bind(&req.order_id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(AppError::from)?;
+                    OrderEvent::new(&req.order_id, EventType::StatusChanged, &actor)
+                        .status(Some(&status), to)
+                }
+                None => OrderEvent::new(&req.order_id, EventType::ItemsShipped, &actor),
+            };
+            order_events::record(&mut *tx, &event.details(&details)).await?;
+
+            tx.commit().await.map_err(AppError::from)?;
+
+            format!("{} item(s) shipped", shipped.len())
+        };
 
-        // Fetch updated order
         let order = sqlx::query_as::<_, DbOrder>(
-            "SELECT id, user_id, total_amount, status, shipping_address, created_at, updated_at 
+            "SELECT id, order_number, user_id, total_amount, status, shipping_address, tax_amount, shipping_region, shipping_method, shipping_fee, currency, exchange_rate, estimated_delivery_from, estimated_delivery_to, created_at, updated_at 
              FROM orders WHERE id = $1",
         )
         .bind(&req.order_id)
         .fetch_one(&self.db)
         .await
-        .map_err(|e| Status::internal(format!("Database error: {}", e)))?;
-
-        let proto_order = self.db_order_to_proto(&order).await?;
+        .map_err(AppError::from)?;
 
-        Ok(Response::new(UpdateOrderResponse {
+        Ok(Response::new(MarkItemsShippedResponse {
             success: true,
-            message: "Order updated successfully".to_string(),
-            order: Some(proto_order),
+            message,
+            order: Some(self.db_order_to_proto(&order).await?),
         }))
     }
 
-    async fn cancel_order(
+    async fn update_delivery_estimate(
         &self,
-        request: Request<CancelOrderRequest>,
-    ) -> Result<Response<CancelOrderResponse>, Status> {
+        request: Request<UpdateDeliveryEstimateRequest>,
+    ) -> Result<Response<UpdateDeliveryEstimateResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        caller.require_platform()?;
+        let actor = caller.actor();
         let req = request.into_inner();
 
-        if req.order_id.is_empty() {
-            return Ok(Response::new(CancelOrderResponse {
-                success: false,
-                message: "Order ID is required".to_string(),
-            }));
+        // Field rules are checked by the validation layer (see order.proto)
+        let date = |value: &str| NaiveDate::parse_from_str(value, "%Y-%m-%d").ok();
+        let (Some(from), Some(to)) = (date(&req.from_date), date(&req.to_date)) else {
+            return Err(error::invalid_argument(
+                "INVALID_DATE",
+                "Dates must be YYYY-MM-DD",
+            ));
+        };
+        if from > to {
+            return Err(error::invalid_argument(
+                "INVALID_DATE",
+                "from_date must not be after to_date",
+            ));
         }
 
-        // Start transaction to restore inventory
-        let mut tx = self
-            .db
-            .begin()
-            .await
-            .map_err(|e| Status::internal(format!("Transaction error: {}", e)))?;
+        let mut tx = self.db.begin().await.map_err(AppError::from)?;
 
-        // Check if order exists and belongs to user
-        let order: Option<DbOrder> = sqlx::query_as(
-            "SELECT id, user_id, total_amount, status, shipping_address, created_at, updated_at 
-             FROM orders WHERE id = $1",
+        let current: Option<(String, Option<NaiveDate>, Option<NaiveDate>)> = sqlx::query_as(
+            "SELECT status, estimated_delivery_from, estimated_delivery_to
+             FROM orders WHERE id = $1 FOR UPDATE",
         )
         .bind(&req.order_id)
         .fetch_optional(&mut *tx)
         .await
-        .map_err(|e| Status::internal(format!("Database error: {}", e)))?;
-
-        let order = match order {
-            Some(o) => o,
-            None => {
-                tx.rollback()
-                    .await
-                    .map_err(|e| Status::internal(format!("Rollback error: {}", e)))?;
-                return Ok(Response::new(CancelOrderResponse {
-                    success: false,
-                    message: "Order not found".to_string(),
-                }));
-            }
+        .map_err(AppError::from)?;
+        let Some((status, old_from, old_to)) = current else {
+            return Err(error::not_found("ORDER_NOT_FOUND", "Order not found"));
         };
-
-        if !req.user_id.is_empty() && order.user_id != req.user_id {
-            tx.rollback()
-                .await
-                .map_err(|e| Status::internal(format!("Rollback error: {}", e)))?;
-            return Ok(Response::new(CancelOrderResponse {
-                success: false,
-                message: "Order does not belong to this user".to_string(),
-            }));
-        }
-
-        if order.status == "CANCELLED" {
-            tx.rollback()
-                .await
-                .map_err(|e| Status::internal(format!("Rollback error: {}", e)))?;
-            return Ok(Response::new(CancelOrderResponse {
-                success: false,
-                message: "Order is already cancelled".to_string(),
-            }));
-        }
-
-        if order.status == "DELIVERED" {
-            tx.rollback()
-                .await
-                .map_err(|e| Status::internal(format!("Rollback error: {}", e)))?;
-            return Ok(Response::new(CancelOrderResponse {
-                success: false,
-                message: "Cannot cancel delivered order".to_string(),
-            }));
+        if matches!(status.as_str(), "CANCELLED" | "DELIVERED") {
+            return Err(error::failed_precondition(
+                "ORDER_STATUS_CONFLICT",
+                format!(
+                    "Cannot change the delivery estimate of a {} order",
+                    status.to_lowercase()
+                ),
+            ));
         }
 
-        // Restore inventory
-        let items = sqlx::query_as::<_, DbOrderItem>(
-            "SELECT id, order_id, product_id, quantity, price FROM order_items WHERE order_id = $1",
-        )
-        .bind(&req.order_id)
-        .fetch_all(&mut *tx)
-        .await
-        .map_err(|e| Status::internal(format!("Database error: {}", e)))?;
-
-        for item in items {
+        if (old_from, old_to) != (Some(from), Some(to)) {
             sqlx::query(
-                "UPDATE products SET stock_quantity = stock_quantity + $1, updated_at = CURRENT_TIMESTAMP WHERE id = $2",
+                "UPDATE orders
+                 SET estimated_delivery_from = $1, estimated_delivery_to = $2,
+                     updated_at = CURRENT_TIMESTAMP
+                 WHERE id = $3",
             )
-            .bind(item.quantity)
-            .bind(&item.product_id)
+            .bind(from)
+            .bind(to)
+            .bind(&req.order_id)
             .execute(&mut *tx)
             .await
-            .map_err(|e| Status::internal(format!("Database error: {}", e)))?;
+            .map_err(AppError::from)?;
+
+            let window = |from: Option<NaiveDate>, to: Option<NaiveDate>| match (from, to) {
+                (Some(from), Some(to)) => format!("{}..{}", from, to),
+                _ => "none".to_string(),
+            };
+            let details = format!(
+                "Estimated delivery {} -> {}",
+                window(old_from, old_to),
+                window(Some(from), Some(to))
+            );
+            order_events::record(
+                &mut *tx,
+                &OrderEvent::new(&req.order_id, EventType::DeliveryEstimateChanged, &actor)
+                    .reason(&req.reason)
+                    .details(&details),
+            )
+            .await?;
         }
 
-        // Update order status
-        sqlx::query(
-            "UPDATE orders SET status = 'CANCELLED', updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+        tx.commit().await.map_err(AppError::from)?;
+
+        let order = sqlx::query_as::<_, DbOrder>(
+            "SELECT id, order_number, user_id, total_amount, status, shipping_address, tax_amount, shipping_region, shipping_method, shipping_fee, currency, exchange_rate, estimated_delivery_from, estimated_delivery_to, created_at, updated_at 
+             FROM orders WHERE id = $1",
         )
         .bind(&req.order_id)
-        .execute(&mut *tx)
+        .fetch_one(&self.db)
         .await
-        .map_err(|e| Status::internal(format!("Database error: {}", e)))?;
+        .map_err(AppError::from)?;
 
-        tx.commit()
-            .await
-            .map_err(|e| Status::internal(format!("Commit error: {}", e)))?;
+        Ok(Response::new(UpdateDeliveryEstimateResponse {
+            success: true,
+            message: "Delivery estimate updated".to_string(),
+            order: Some(self.db_order_to_proto(&order).await?),
+        }))
+    }
 
-        Ok(Response::new(CancelOrderResponse {
+    async fn claim_guest_orders(
+        &self,
+        request: Request<ClaimGuestOrdersRequest>,
+    ) -> Result<Response<ClaimGuestOrdersResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        let Some(user_id) = caller.user_id() else {
+            return Err(Status::permission_denied(
+                "Only a signed-in customer can claim guest orders",
+            ));
+        };
+        let req = request.into_inner();
+
+        // Field rules are checked by the validation layer (see order.proto)
+        let Ok(email) = auth::decode_email_token(&req.email_token) else {
+            return Err(error::invalid_argument(
+                "INVALID_EMAIL_TOKEN",
+                "Invalid or expired email token",
+            ));
+        };
+        let order_ids = match guest::claim(
+            &self.db,
+            &self.user_service,
+            user_id,
+            &email,
+            &caller.actor(),
+        )
+        .await?
+        {
+            Ok(order_ids) => order_ids,
+            Err(message) => return Err(error::failed_precondition("CLAIM_NOT_ALLOWED", message)),
+        };
+
+        Ok(Response::new(ClaimGuestOrdersResponse {
             success: true,
-            message: "Order cancelled successfully".to_string(),
+            message: format!("Claimed {} guest orders", order_ids.len()),
+            order_ids,
         }))
     }
 
-    async fn get_order(
+    async fn approve_order(
         &self,
-        request: Request<GetOrderRequest>,
-    ) -> Result<Response<GetOrderResponse>, Status> {
+        request: Request<ApproveOrderRequest>,
+    ) -> Result<Response<ApproveOrderResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        caller.require_platform()?;
+        let actor = caller.actor();
         let req = request.into_inner();
 
-        if req.order_id.is_empty() {
-            return Ok(Response::new(GetOrderResponse {
-                success: false,
-                message: "Order ID is required".to_string(),
-                order: None,
-            }));
+        // Field rules are checked by the validation layer (see order.proto)
+        let mut tx = self.db.begin().await.map_err(AppError::from)?;
+
+        let result = sqlx::query(
+            "UPDATE orders SET status = 'PENDING', updated_at = CURRENT_TIMESTAMP
+             WHERE id = $1 AND status = 'ON_HOLD'",
+        )
+        .bind(&req.order_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+        if result.rows_affected() == 0 {
+            return Err(error::failed_precondition(
+                "ORDER_NOT_ON_HOLD",
+                "Order not found or not on hold",
+            ));
         }
 
-        let order_result = sqlx::query_as::<_, DbOrder>(
-            "SELECT id, user_id, total_amount, status, shipping_address, created_at, updated_at 
+        order_events::record(
+            &mut *tx,
+            &OrderEvent::new(&req.order_id, EventType::StatusChanged, &actor)
+                .status(Some("ON_HOLD"), "PENDING")
+                .reason(&req.reason),
+        )
+        .await?;
+
+        tx.commit().await.map_err(AppError::from)?;
+
+        let order = sqlx::query_as::<_, DbOrder>(
+            "SELECT id, order_number, user_id, total_amount, status, shipping_address, tax_amount, shipping_region, shipping_method, shipping_fee, currency, exchange_rate, estimated_delivery_from, estimated_delivery_to, created_at, updated_at 
              FROM orders WHERE id = $1",
         )
         .bind(&req.order_id)
-        .fetch_optional(&self.db)
+        .fetch_one(&self.db)
         .await
-        .map_err(|e| Status::internal(format!("Database error: {}", e)))?;
+        .map_err(AppError::from)?;
 
-        match order_result {
-            Some(order) => {
-                let proto_order = self.db_order_to_proto(&order).await?;
-                Ok(Response::new(GetOrderResponse {
-                    success: true,
-                    message: "Order retrieved successfully".to_string(),
-                    order: Some(proto_order),
-                }))
-            }
-            None => Ok(Response::new(GetOrderResponse {
-                success: false,
-                message: "Order not found".to_string(),
-                order: None,
-            })),
-        }
+        Ok(Response::new(ApproveOrderResponse {
+            success: true,
+            message: "Order approved".to_string(),
+            order: Some(self.db_order_to_proto(&order).await?),
+        }))
     }
 
-    async fn list_orders(
+    async fn reject_order(
         &self,
-        request: Request<ListOrdersRequest>,
-    ) -> Result<Response<ListOrdersResponse>, Status> {
+        request: Request<RejectOrderRequest>,
+    ) -> Result<Response<RejectOrderResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        caller.require_platform()?;
+        let actor = caller.actor();
         let req = request.into_inner();
 
-        let page = if req.page <= 0 { 1 } else { req.page };
-        let page_size = if req.page_size <= 0 || req.page_size > 100 {
-            10
-        } else {
-            req.page_size
-        };
-        let offset = (page - 1) * page_size;
-
-        let status = OrderStatus::try_from(req.status).unwrap_or(OrderStatus::Pending);
-        let status_str = self.status_to_string(status);
-
-        let (orders, total_count) = if req.status == 0 {
-            // List all orders
-            let orders = sqlx::query_as::<_, DbOrder>(
-                "SELECT id, user_id, total_amount, status, shipping_address, created_at, updated_at 
-                 FROM orders 
-                 ORDER BY created_at DESC 
-                 LIMIT $1 OFFSET $2",
-            )
-            .bind(page_size as i64)
-            .bind(offset as i64)
-            .fetch_all(&self.db)
-            .await
-            .map_err(|e| Status::internal(format!("Database error: {}", e)))?;
+        // Field rules are checked by the validation layer (see order.proto)
+        let mut tx = self.db.begin().await.map_err(AppError::from)?;
 
-            let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM orders")
-                .fetch_one(&self.db)
-                .await
-                .map_err(|e| Status::internal(format!("Database error: {}", e)))?;
+        // Guarded so the stock is restored at most once
+        let result = sqlx::query(
+            "UPDATE orders SET status = 'CANCELLED', updated_at = CURRENT_TIMESTAMP
+             WHERE id = $1 AND status = 'ON_HOLD'",
+        )
+        .bind(&req.order_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+        if result.rows_affected() == 0 {
+            return Err(error::failed_precondition(
+                "ORDER_NOT_ON_HOLD",
+                "Order not found or not on hold",
+            ));
+        }
 
-            (orders, count.0)
-        } else {
-            // Filter by status
-            let orders = sqlx::query_as::<_, DbOrder>(
-                "SELECT id, user_id, total_amount, status, shipping_address, created_at, updated_at 
-                 FROM orders 
-                 WHERE status = $1 
-                 ORDER BY created_at DESC 
-                 LIMIT $2 OFFSET $3",
-            )
-            .bind(&status_str)
-            .bind(page_size as i64)
-            .bind(offset as i64)
-            .fetch_all(&self.db)
-            .await
-            .map_err(|e| Status::internal(format!("Database error: {}", e)))?;
+        order_events::record(
+            &mut *tx,
+            &OrderEvent::new(&req.order_id, EventType::Cancelled, &actor)
+                .status(Some("ON_HOLD"), "CANCELLED")
+                .reason(&req.reason),
+        )
+        .await?;
 
-            let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM orders WHERE status = $1")
-                .bind(&status_str)
-                .fetch_one(&self.db)
-                .await
-                .map_err(|e| Status::internal(format!("Database error: {}", e)))?;
+        let stock_return = stock_return::record_order(&mut tx, &req.order_id).await?;
 
-            (orders, count.0)
-        };
+        tx.commit().await.map_err(AppError::from)?;
 
-        let mut proto_orders = Vec::new();
-        for order in orders {
-            proto_orders.push(self.db_order_to_proto(&order).await?);
-        }
+        stock_return::send_recorded(&self.db, &self.product_service, stock_return).await;
 
-        Ok(Response::new(ListOrdersResponse {
+        Ok(Response::new(RejectOrderResponse {
             success: true,
-            message: format!("Retrieved {} orders", proto_orders.len()),
-            orders: proto_orders,
-            total_count: total_count as i32,
+            message: "Order rejected and cancelled".to_string(),
         }))
     }
 
-    async fn get_orders_by_user(
+    async fn get_order_history(
         &self,
-        request: Request<GetOrdersByUserRequest>,
-    ) -> Result<Response<GetOrdersByUserResponse>, Status> {
+        request: Request<GetOrderHistoryRequest>,
+    ) -> Result<Response<GetOrderHistoryResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
         let req = request.into_inner();
 
-        if req.user_id.is_empty() {
-            return Ok(Response::new(GetOrdersByUserResponse {
-                success: false,
-                message: "User ID is required".to_string(),
-                orders: vec![],
-                total_count: 0,
-            }));
-        }
-
-        let page = if req.page <= 0 { 1 } else { req.page };
-        let page_size = if req.page_size <= 0 || req.page_size > 100 {
-            10
-        } else {
-            req.page_size
+        // Field rules are checked by the validation layer (see order.proto)
+        let user_id: Option<String> =
+            sqlx::query_scalar("SELECT user_id FROM orders WHERE id = $1")
+                .bind(&req.order_id)
+                .fetch_optional(&self.db)
+                .await
+                .map_err(AppError::from)?;
+        let Some(user_id) = user_id else {
+            return Err(error::not_found("ORDER_NOT_FOUND", "Order not found"));
         };
-        let offset = (page - 1) * page_size;
+        caller.require_owner(&user_id)?;
 
-        let orders = sqlx::query_as::<_, DbOrder>(
-            "SELECT id, user_id, total_amount, status, shipping_address, created_at, updated_at 
-             FROM orders 
-             WHERE user_id = $1 
-             ORDER BY created_at DESC 
-             LIMIT $2 OFFSET $3",
+        let events = sqlx::query_as::<_, DbOrderEvent>(
+            "SELECT id, event_type, from_status, to_status, actor, reason, details, created_at
+             FROM order_events WHERE order_id = $1 ORDER BY id",
         )
-        .bind(&req.user_id)
-        .bind(page_size as i64)
-        .bind(offset as i64)
+        .bind(&req.order_id)
         .fetch_all(&self.db)
         .await
-        .map_err(|e| Status::internal(format!("Database error: {}", e)))?;
-
-        let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM orders WHERE user_id = $1")
-            .bind(&req.user_id)
-            .fetch_one(&self.db)
-            .await
-            .map_err(|e| Status::internal(format!("Database error: {}", e)))?;
-
-        let mut proto_orders = Vec::new();
-        for order in orders {
-            proto_orders.push(self.db_order_to_proto(&order).await?);
-        }
+        .map_err(AppError::from)?;
 
-        Ok(Response::new(GetOrdersByUserResponse {
+        Ok(Response::new(GetOrderHistoryResponse {
             success: true,
-            message: format!("Retrieved {} orders for user", proto_orders.len()),
-            orders: proto_orders,
-            total_count: count.0 as i32,
+            message: format!("Retrieved {} events", events.len()),
+            events: events.iter().map(DbOrderEvent::to_proto).collect(),
         }))
     }
 }
@@ -0,0 +1,75 @@
+use anyhow::{Result, anyhow};
+use std::env;
+
+/// Crockford's base32 alphabet: no I, L, O or U, so numbers survive being read aloud.
+const CROCKFORD_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// How the human-friendly order number is derived from the order number
+/// sequence. The prefix is the `order_number_prefix` store setting.
+#[derive(Debug, Clone, Copy)]
+pub enum OrderNumberFormat {
+    /// `ORD-2024-000123`
+    Sequential,
+    /// `ORD-00003V`
+    Base32,
+}
+
+impl OrderNumberFormat {
+    /// Reads `ORDER_NUMBER_FORMAT` (`sequential` or `base32`, default `sequential`).
+    pub fn from_env() -> Result<Self> {
+        match env::var("ORDER_NUMBER_FORMAT")
+            .unwrap_or_default()
+            .to_ascii_lowercase()
+            .as_str()
+        {
+            "" | "sequential" => Ok(Self::Sequential),
+            "base32" => Ok(Self::Base32),
+            other => Err(anyhow!("Unknown ORDER_NUMBER_FORMAT: {}", other)),
+        }
+    }
+
+    pub fn format(&self, prefix: &str, sequence: i64, year: i32) -> String {
+        match self {
+            Self::Sequential => format!("{}-{}-{:06}", prefix, year, sequence),
+            Self::Base32 => format!("{}-{}", prefix, crockford_base32(sequence, 6)),
+        }
+    }
+
+    /// Normalizes customer input for lookups: case-insensitive, and for base32
+    /// the commonly confused letters map to the digits they resemble.
+    pub fn normalize(&self, input: &str) -> String {
+        let upper = input.trim().to_ascii_uppercase();
+        match self {
+            Self::Sequential => upper,
+            // Only the code after the prefix is remapped, so numbers issued
+            // under an earlier prefix still normalize correctly
+            Self::Base32 => match upper.split_once('-') {
+                Some((p, code)) => {
+                    let code: String = code
+                        .chars()
+                        .map(|c| match c {
+                            'I' | 'L' => '1',
+                            'O' => '0',
+                            c => c,
+                        })
+                        .collect();
+                    format!("{}-{}", p, code)
+                }
+                None => upper,
+            },
+        }
+    }
+}
+
+fn crockford_base32(mut value: i64, min_len: usize) -> String {
+    let mut digits = Vec::new();
+    while value > 0 {
+        digits.push(CROCKFORD_ALPHABET[(value % 32) as usize]);
+        value /= 32;
+    }
+    while digits.len() < min_len {
+        digits.push(b'0');
+    }
+    digits.reverse();
+    String::from_utf8(digits).unwrap_or_default()
+}
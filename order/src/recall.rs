@@ -0,0 +1,460 @@
+use common::auth::{Caller, ServiceCredentials};
+use common::client::ServiceEndpoint;
+use common::error::{self, AppError};
+use proto::notification::{
+    CreateNotificationRequest, notification_service_client::NotificationServiceClient,
+};
+use proto::recall::{
+    AffectedOrderItem, GetAffectedOrdersRequest, GetAffectedOrdersResponse, ListQuarantinesRequest,
+    ListQuarantinesResponse, Quarantine, QuarantineProductRequest, QuarantineResponse,
+    ReleaseQuarantineRequest, StartRecallRequest, StartRecallResponse,
+    recall_service_server::RecallService,
+};
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+#[derive(Debug, sqlx::FromRow)]
+struct DbQuarantine {
+    id: String,
+    product_id: String,
+    lot_number: Option<String>,
+    reason: String,
+    created_by: String,
+    created_at: chrono::NaiveDateTime,
+    released_by: Option<String>,
+    released_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct DbAffectedItem {
+    order_id: String,
+    user_id: String,
+    item_id: String,
+    product_id: String,
+    quantity: i32,
+    status: String,
+}
+
+const QUARANTINE_COLUMNS: &str =
+    "id, product_id, lot_number, reason, created_by, created_at, released_by, released_at";
+
+/// Returns the reason of the active quarantine covering the product, or the
+/// given lot of it, if any.
+pub async fn active_quarantine(
+    db: &PgPool,
+    product_id: &str,
+    lot_number: Option<&str>,
+) -> Result<Option<String>, Status> {
+    sqlx::query_scalar(
+        "SELECT reason FROM product_quarantines
+         WHERE product_id = $1 AND released_at IS NULL
+           AND (lot_number IS NULL OR lot_number = $2)
+         LIMIT 1",
+    )
+    .bind(product_id)
+    .bind(lot_number)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| AppError::from(e).into())
+}
+
+pub struct RecallServiceImpl {
+    db: PgPool,
+    notification_service: Arc<ServiceEndpoint>,
+}
+
+impl RecallServiceImpl {
+    pub fn new(db: PgPool, notification_service: Arc<ServiceEndpoint>) -> Self {
+        Self {
+            db,
+            notification_service,
+        }
+    }
+
+    fn db_quarantine_to_proto(&self, q: &DbQuarantine) -> Quarantine {
+        Quarantine {
+            quarantine_id: q.id.clone(),
+            product_id: q.product_id.clone(),
+            lot_number: q.lot_number.clone().unwrap_or_default(),
+            reason: q.reason.clone(),
+            created_by: q.created_by.clone(),
+            created_at: q.created_at.and_utc().timestamp(),
+            active: q.released_at.is_none(),
+            released_by: q.released_by.clone().unwrap_or_default(),
+            released_at: q.released_at.map_or(0, |t| t.and_utc().timestamp()),
+        }
+    }
+
+    async fn get_quarantine(&self, quarantine_id: &str) -> Result<Option<DbQuarantine>, Status> {
+        sqlx::query_as::<_, DbQuarantine>(&format!(
+            "SELECT {} FROM product_quarantines WHERE id = $1",
+            QUARANTINE_COLUMNS
+        ))
+        .bind(quarantine_id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| AppError::from(e).into())
+    }
+
+    /// Shipped or delivered order items containing quarantined units. Lot
+    /// quarantines rely on the serial/lot numbers recorded at fulfillment.
+    async fn affected_items(&self, q: &DbQuarantine) -> Result<Vec<DbAffectedItem>, Status> {
+        let query = match &q.lot_number {
+            Some(lot_number) => sqlx::query_as::<_, DbAffectedItem>(
+                "SELECT o.id AS order_id, o.user_id, oi.id AS item_id, oi.product_id,
+                        SUM(t.quantity)::INT AS quantity, o.status
+                 FROM order_item_tracking t
+                 JOIN order_items oi ON oi.id = t.order_item_id
+                 JOIN orders o ON o.id = oi.order_id
+                 WHERE t.product_id = $1 AND t.lot_number = $2
+                   AND o.status IN ('PARTIALLY_SHIPPED', 'SHIPPED', 'DELIVERED')
+                 GROUP BY o.id, o.user_id, oi.id, oi.product_id, o.status
+                 ORDER BY o.id",
+            )
+            .bind(&q.product_id)
+            .bind(lot_number),
+            None => sqlx::query_as::<_, DbAffectedItem>(
+                "SELECT o.id AS order_id, o.user_id, oi.id AS item_id, oi.product_id,
+                        oi.quantity, o.status
+                 FROM order_items oi
+                 JOIN orders o ON o.id = oi.order_id
+                 WHERE oi.product_id = $1
+                   AND (o.status IN ('SHIPPED', 'DELIVERED') OR oi.fulfillment_status = 'SHIPPED')
+                 ORDER BY o.id",
+            )
+            .bind(&q.product_id),
+        };
+
+        query
+            .fetch_all(&self.db)
+            .await
+            .map_err(|e| AppError::from(e).into())
+    }
+}
+
+#[tonic::async_trait]
+impl RecallService for RecallServiceImpl {
+    async fn quarantine_product(
+        &self,
+        request: Request<QuarantineProductRequest>,
+    ) -> Result<Response<QuarantineResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        caller.require_platform()?;
+        let actor = caller.actor();
+        let req = request.into_inner();
+
+        if req.product_id.is_empty() || req.reason.is_empty() {
+            return Err(error::invalid_argument(
+                "INVALID_QUARANTINE",
+                "Product ID and reason are required",
+            ));
+        }
+
+        let lot_number = if req.lot_number.is_empty() {
+            None
+        } else {
+            Some(req.lot_number.clone())
+        };
+
+        let mut tx = self.db.begin().await.map_err(AppError::from)?;
+
+        let quarantine = sqlx::query_as::<_, DbQuarantine>(&format!(
+            "INSERT INTO product_quarantines (id, product_id, lot_number, reason, created_by)
+             VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT DO NOTHING
+             RETURNING {}",
+            QUARANTINE_COLUMNS
+        ))
+        .bind(Uuid::new_v4().to_string())
+        .bind(&req.product_id)
+        .bind(&lot_number)
+        .bind(&req.reason)
+        .bind(&actor)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+
+        let quarantine = match quarantine {
+            Some(q) => q,
+            None => {
+                return Err(error::already_exists(
+                    "QUARANTINE_EXISTS",
+                    "An active quarantine already covers this product or lot",
+                ));
+            }
+        };
+
+        sqlx::query(
+            "INSERT INTO ops_audit_log (id, action, target, actor, reason)
+             VALUES ($1, 'QUARANTINE_PRODUCT', $2, $3, $4)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(match &lot_number {
+            Some(lot) => format!("{}:{}", req.product_id, lot),
+            None => req.product_id.clone(),
+        })
+        .bind(&actor)
+        .bind(&req.reason)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+
+        tx.commit().await.map_err(AppError::from)?;
+
+        warn!(
+            product_id = %req.product_id,
+            lot_number = ?lot_number,
+            actor = %actor,
+            "Product quarantined"
+        );
+
+        Ok(Response::new(QuarantineResponse {
+            success: true,
+            message: "Quarantine created".to_string(),
+            quarantine: Some(self.db_quarantine_to_proto(&quarantine)),
+        }))
+    }
+
+    async fn release_quarantine(
+        &self,
+        request: Request<ReleaseQuarantineRequest>,
+    ) -> Result<Response<QuarantineResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        caller.require_platform()?;
+        let actor = caller.actor();
+        let req = request.into_inner();
+
+        if req.quarantine_id.is_empty() || req.reason.is_empty() {
+            return Err(error::invalid_argument(
+                "INVALID_QUARANTINE",
+                "Quarantine ID and reason are required",
+            ));
+        }
+
+        let mut tx = self.db.begin().await.map_err(AppError::from)?;
+
+        let quarantine = sqlx::query_as::<_, DbQuarantine>(&format!(
+            "UPDATE product_quarantines
+             SET released_at = CURRENT_TIMESTAMP, released_by = $2
+             WHERE id = $1 AND released_at IS NULL
+             RETURNING {}",
+            QUARANTINE_COLUMNS
+        ))
+        .bind(&req.quarantine_id)
+        .bind(&actor)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+
+        let quarantine = match quarantine {
+            Some(q) => q,
+            None => {
+                return Err(error::not_found(
+                    "QUARANTINE_NOT_FOUND",
+                    "Active quarantine not found",
+                ));
+            }
+        };
+
+        sqlx::query(
+            "INSERT INTO ops_audit_log (id, action, target, actor, reason)
+             VALUES ($1, 'RELEASE_QUARANTINE', $2, $3, $4)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(&req.quarantine_id)
+        .bind(&actor)
+        .bind(&req.reason)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+
+        tx.commit().await.map_err(AppError::from)?;
+
+        Ok(Response::new(QuarantineResponse {
+            success: true,
+            message: "Quarantine released".to_string(),
+            quarantine: Some(self.db_quarantine_to_proto(&quarantine)),
+        }))
+    }
+
+    async fn list_quarantines(
+        &self,
+        request: Request<ListQuarantinesRequest>,
+    ) -> Result<Response<ListQuarantinesResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        let quarantines = sqlx::query_as::<_, DbQuarantine>(&format!(
+            "SELECT {} FROM product_quarantines
+             WHERE NOT $1 OR released_at IS NULL
+             ORDER BY created_at DESC",
+            QUARANTINE_COLUMNS
+        ))
+        .bind(req.active_only)
+        .fetch_all(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        Ok(Response::new(ListQuarantinesResponse {
+            quarantines: quarantines
+                .iter()
+                .map(|q| self.db_quarantine_to_proto(q))
+                .collect(),
+        }))
+    }
+
+    async fn get_affected_orders(
+        &self,
+        request: Request<GetAffectedOrdersRequest>,
+    ) -> Result<Response<GetAffectedOrdersResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        let quarantine = match self.get_quarantine(&req.quarantine_id).await? {
+            Some(q) => q,
+            None => {
+                return Err(error::not_found(
+                    "QUARANTINE_NOT_FOUND",
+                    "Quarantine not found",
+                ));
+            }
+        };
+
+        let items: Vec<AffectedOrderItem> = self
+            .affected_items(&quarantine)
+            .await?
+            .into_iter()
+            .map(|i| AffectedOrderItem {
+                order_id: i.order_id,
+                user_id: i.user_id,
+                item_id: i.item_id,
+                product_id: i.product_id,
+                quantity: i.quantity,
+                status: i.status,
+            })
+            .collect();
+
+        Ok(Response::new(GetAffectedOrdersResponse {
+            success: true,
+            message: format!("Found {} affected order items", items.len()),
+            items,
+        }))
+    }
+
+    async fn start_recall(
+        &self,
+        request: Request<StartRecallRequest>,
+    ) -> Result<Response<StartRecallResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        caller.require_platform()?;
+        let actor = caller.actor();
+        let req = request.into_inner();
+
+        if req.quarantine_id.is_empty() || req.title.is_empty() || req.body.is_empty() {
+            return Err(error::invalid_argument(
+                "INVALID_RECALL_NOTICE",
+                "Quarantine ID, title and body are required",
+            ));
+        }
+
+        let quarantine = match self.get_quarantine(&req.quarantine_id).await? {
+            Some(q) if q.released_at.is_none() => q,
+            Some(_) => {
+                return Err(error::failed_precondition(
+                    "QUARANTINE_RELEASED",
+                    "Quarantine has been released",
+                ));
+            }
+            None => {
+                return Err(error::not_found(
+                    "QUARANTINE_NOT_FOUND",
+                    "Quarantine not found",
+                ));
+            }
+        };
+
+        let items = self.affected_items(&quarantine).await?;
+
+        // One notification per customer, linking their first affected order
+        let mut users: HashMap<String, String> = HashMap::new();
+        let mut orders = HashSet::new();
+        for item in &items {
+            orders.insert(item.order_id.clone());
+            users
+                .entry(item.user_id.clone())
+                .or_insert_with(|| item.order_id.clone());
+        }
+
+        let mut notified = 0;
+        let mut failed = 0;
+        if !users.is_empty() {
+            let channel = self.notification_service.primary_channel().map_err(|e| {
+                Status::internal(format!("Invalid notification service URL: {}", e))
+            })?;
+            let mut client =
+                NotificationServiceClient::with_interceptor(channel, ServiceCredentials("order"));
+
+            for (user_id, order_id) in &users {
+                let result = client
+                    .create_notification(CreateNotificationRequest {
+                        user_id: user_id.clone(),
+                        category: "recall".to_string(),
+                        title: req.title.clone(),
+                        body: req.body.clone(),
+                        link: format!("/orders/{}", order_id),
+                    })
+                    .await;
+
+                match result {
+                    Ok(response) if response.get_ref().success => notified += 1,
+                    Ok(response) => {
+                        warn!(user_id = %user_id, "Recall notification rejected: {}", response.get_ref().message);
+                        failed += 1;
+                    }
+                    Err(e) => {
+                        warn!(user_id = %user_id, "Recall notification failed: {}", e);
+                        failed += 1;
+                    }
+                }
+            }
+        }
+
+        let recall_id = Uuid::new_v4().to_string();
+        sqlx::query(
+            "INSERT INTO recalls (id, quarantine_id, title, body, created_by, affected_orders, notified_users, failed_users)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+        )
+        .bind(&recall_id)
+        .bind(&req.quarantine_id)
+        .bind(&req.title)
+        .bind(&req.body)
+        .bind(&actor)
+        .bind(orders.len() as i32)
+        .bind(notified)
+        .bind(failed)
+        .execute(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        info!(
+            recall_id = %recall_id,
+            affected_orders = orders.len(),
+            notified,
+            failed,
+            "Recall notifications sent"
+        );
+
+        Ok(Response::new(StartRecallResponse {
+            success: failed == 0,
+            message: format!("Notified {} customers, {} failed", notified, failed),
+            recall_id,
+            affected_orders: orders.len() as i32,
+            notified_users: notified,
+            failed_users: failed,
+        }))
+    }
+}
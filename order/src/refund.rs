@@ -0,0 +1,432 @@
+//! Refunds of shipped or delivered orders. A refund is written as PENDING,
+//! with the items it returns, before the payment service is asked to refund;
+//! the order row lock makes concurrent refunds see each other's items, so no
+//! more units can be returned than were ordered. The refund then completes,
+//! restocking its items, or fails, which frees them again. A refund the
+//! payment service didn't answer for stays PENDING, since it may have been
+//! paid; the next RefundOrder of the order sends it again under its id as
+//! idempotency key, so the payment is refunded once.
+
+use crate::stock_return;
+use common::auth::ServiceCredentials;
+use common::client::ServiceEndpoint;
+use common::error::{self, AppError};
+use common::idempotency;
+use common::order_events::{self, EventType, OrderEvent};
+use common::resilience::{self, CircuitBreaker};
+use proto::order::{Refund, RefundItem, RefundStatus};
+use proto::payment::payment_service_client::PaymentServiceClient;
+use proto::payment::{GetPaymentRequest, Payment, RefundPaymentRequest};
+use sqlx::PgPool;
+use sqlx::types::Decimal;
+use std::collections::HashMap;
+use tonic::Status;
+use tonic::metadata::MetadataValue;
+use tonic::service::interceptor::InterceptedService;
+use tonic::transport::Channel;
+use tracing::info;
+use uuid::Uuid;
+
+const REFUND_COLUMNS: &str =
+    "id, order_id, payment_id, amount, status, reason, payment_refund_id, created_at";
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct DbRefund {
+    pub id: String,
+    pub order_id: String,
+    payment_id: String,
+    pub amount: Decimal,
+    status: String,
+    reason: Option<String>,
+    payment_refund_id: Option<String>,
+    created_at: chrono::NaiveDateTime,
+}
+
+impl DbRefund {
+    fn to_proto(&self, items: Vec<RefundItem>) -> Refund {
+        Refund {
+            refund_id: self.id.clone(),
+            order_id: self.order_id.clone(),
+            payment_id: self.payment_id.clone(),
+            amount: self.amount.to_string(),
+            status: status_from_string(&self.status) as i32,
+            reason: self.reason.clone().unwrap_or_default(),
+            items,
+            payment_refund_id: self.payment_refund_id.clone().unwrap_or_default(),
+            created_at: self.created_at.and_utc().timestamp(),
+        }
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct DbRefundItem {
+    refund_id: String,
+    order_item_id: String,
+    quantity: i32,
+}
+
+/// An order item with the units that refunds not failed have returned.
+#[derive(Debug, sqlx::FromRow)]
+struct DbReturnableItem {
+    id: String,
+    quantity: i32,
+    price: Decimal,
+    returned: i32,
+}
+
+fn status_from_string(status: &str) -> RefundStatus {
+    match status {
+        "COMPLETED" => RefundStatus::RefundCompleted,
+        "FAILED" => RefundStatus::RefundFailed,
+        _ => RefundStatus::RefundPending,
+    }
+}
+
+/// Whether an order in `status` can be refunded.
+pub fn refundable(status: &str) -> bool {
+    matches!(status, "SHIPPED" | "DELIVERED")
+}
+
+/// Records a pending refund of an order's payment. Without items or amount
+/// the whole order is refunded: every unit not returned yet, and what is
+/// left of the payment. Otherwise the amount defaults to the returned items'
+/// price, up to what is left. `Ok(Err(..))` holds the message refusing it.
+pub async fn reserve(
+    db: &PgPool,
+    order_id: &str,
+    payment: &Payment,
+    items: &[RefundItem],
+    amount: Option<Decimal>,
+    reason: &str,
+) -> Result<Result<DbRefund, String>, Status> {
+    let mut tx = db.begin().await.map_err(AppError::from)?;
+
+    let status: Option<String> =
+        sqlx::query_scalar("SELECT status FROM orders WHERE id = $1 FOR UPDATE")
+            .bind(order_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(AppError::from)?;
+    match status {
+        None => return Ok(Err("Order not found".to_string())),
+        Some(status) if !refundable(&status) => {
+            return Ok(Err(
+                "Only shipped or delivered orders can be refunded".to_string()
+            ));
+        }
+        Some(_) => {}
+    }
+
+    let order_items = sqlx::query_as::<_, DbReturnableItem>(
+        "SELECT oi.id, oi.quantity, oi.price,
+                COALESCE(SUM(ri.quantity) FILTER (WHERE r.status <> 'FAILED'), 0)::INT AS returned
+         FROM order_items oi
+         LEFT JOIN order_refund_items ri ON ri.order_item_id = oi.id
+         LEFT JOIN order_refunds r ON r.id = ri.refund_id
+         WHERE oi.order_id = $1
+         GROUP BY oi.id",
+    )
+    .bind(order_id)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(AppError::from)?;
+    let order_items: HashMap<&str, &DbReturnableItem> =
+        order_items.iter().map(|i| (i.id.as_str(), i)).collect();
+
+    let full_refund = items.is_empty() && amount.is_none();
+    let mut returned: HashMap<&str, i32> = HashMap::new();
+    if full_refund {
+        for item in order_items.values() {
+            if item.quantity > item.returned {
+                returned.insert(&item.id, item.quantity - item.returned);
+            }
+        }
+    } else {
+        for item in items {
+            if !order_items.contains_key(item.item_id.as_str()) {
+                return Ok(Err(format!(
+                    "Item {} is not part of this order",
+                    item.item_id
+                )));
+            }
+            *returned.entry(&item.item_id).or_default() += item.quantity;
+        }
+        for (item_id, quantity) in &returned {
+            let item = order_items[item_id];
+            if *quantity > item.quantity - item.returned {
+                return Ok(Err(format!(
+                    "Only {} unit(s) of item {} can still be returned",
+                    item.quantity - item.returned,
+                    item_id
+                )));
+            }
+        }
+    }
+
+    let paid: Decimal = payment.amount.parse().unwrap_or_default();
+    let refunded: Decimal = payment.refunded_amount.parse().unwrap_or_default();
+    let remaining = paid - refunded;
+    let amount = match amount {
+        Some(amount) => amount,
+        None if full_refund => remaining,
+        None => returned
+            .iter()
+            .map(|(item_id, quantity)| order_items[item_id].price * Decimal::from(*quantity))
+            .sum::<Decimal>()
+            .min(remaining),
+    };
+    if amount <= Decimal::ZERO {
+        return Ok(Err("Payment is already fully refunded".to_string()));
+    }
+    if amount > remaining {
+        return Ok(Err(format!("Refund exceeds the remaining {}", remaining)));
+    }
+
+    let refund = sqlx::query_as::<_, DbRefund>(&format!(
+        "INSERT INTO order_refunds (id, order_id, payment_id, amount, status, reason)
+         VALUES ($1, $2, $3, $4, 'PENDING', $5)
+         RETURNING {}",
+        REFUND_COLUMNS
+    ))
+    .bind(Uuid::new_v4().to_string())
+    .bind(order_id)
+    .bind(&payment.payment_id)
+    .bind(amount)
+    .bind(if reason.is_empty() {
+        None
+    } else {
+        Some(reason)
+    })
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(AppError::from)?;
+
+    for (item_id, quantity) in &returned {
+        sqlx::query(
+            "INSERT INTO order_refund_items (refund_id, order_item_id, quantity)
+             VALUES ($1, $2, $3)",
+        )
+        .bind(&refund.id)
+        .bind(item_id)
+        .bind(quantity)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+    }
+
+    tx.commit().await.map_err(AppError::from)?;
+
+    Ok(Ok(refund))
+}
+
+/// The order's refund left pending by a call the payment service didn't
+/// answer, if any.
+pub async fn pending(db: &PgPool, order_id: &str) -> Result<Option<DbRefund>, Status> {
+    sqlx::query_as::<_, DbRefund>(&format!(
+        "SELECT {} FROM order_refunds
+         WHERE order_id = $1 AND status = 'PENDING'
+         ORDER BY created_at, id
+         LIMIT 1",
+        REFUND_COLUMNS
+    ))
+    .bind(order_id)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| AppError::from(e).into())
+}
+
+/// Marks a pending refund as refunded by the payment service and adds it to
+/// the order history, together, then gives its returned units back to stock.
+pub async fn complete(
+    db: &PgPool,
+    product_service: &ServiceEndpoint,
+    refund_id: &str,
+    payment_refund_id: &str,
+    actor: &str,
+) -> Result<Refund, Status> {
+    let mut tx = db.begin().await.map_err(AppError::from)?;
+
+    let refund = sqlx::query_as::<_, DbRefund>(&format!(
+        "UPDATE order_refunds
+         SET status = 'COMPLETED', payment_refund_id = $1, updated_at = CURRENT_TIMESTAMP
+         WHERE id = $2 AND status = 'PENDING'
+         RETURNING {}",
+        REFUND_COLUMNS
+    ))
+    .bind(payment_refund_id)
+    .bind(refund_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(AppError::from)?;
+
+    // Backordered items never took stock, and digital products have none
+    let returned: Vec<(String, i32)> = sqlx::query_as(
+        "SELECT oi.product_id, SUM(ri.quantity)::INT
+         FROM order_refund_items ri
+         JOIN order_items oi ON oi.id = ri.order_item_id
+         WHERE ri.refund_id = $1 AND oi.status = 'ALLOCATED'
+         GROUP BY oi.product_id",
+    )
+    .bind(refund_id)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(AppError::from)?;
+    let stock_return = stock_return::record(&mut tx, &refund.order_id, &returned).await?;
+
+    let details = format!("Refunded {}", refund.amount);
+    order_events::record(
+        &mut *tx,
+        &OrderEvent::new(&refund.order_id, EventType::Refunded, actor)
+            .reason(refund.reason.as_deref().unwrap_or_default())
+            .details(&details),
+    )
+    .await?;
+
+    let items = fetch_items(&mut *tx, std::slice::from_ref(&refund.id)).await?;
+
+    tx.commit().await.map_err(AppError::from)?;
+
+    stock_return::send_recorded(db, product_service, stock_return).await;
+
+    info!(refund_id = %refund.id, order_id = %refund.order_id, "Order refunded");
+    Ok(refund.to_proto(items.into_values().flatten().collect()))
+}
+
+/// Marks a pending refund as failed, so its items can be returned again.
+pub async fn fail(db: &PgPool, refund_id: &str) -> Result<(), Status> {
+    sqlx::query(
+        "UPDATE order_refunds SET status = 'FAILED', updated_at = CURRENT_TIMESTAMP
+         WHERE id = $1 AND status = 'PENDING'",
+    )
+    .bind(refund_id)
+    .execute(db)
+    .await
+    .map_err(AppError::from)?;
+
+    Ok(())
+}
+
+/// Items of the given refunds, keyed by refund id.
+async fn fetch_items<'e>(
+    executor: impl sqlx::PgExecutor<'e>,
+    refund_ids: &[String],
+) -> Result<HashMap<String, Vec<RefundItem>>, Status> {
+    let rows = sqlx::query_as::<_, DbRefundItem>(
+        "SELECT refund_id, order_item_id, quantity FROM order_refund_items
+         WHERE refund_id = ANY($1)
+         ORDER BY order_item_id",
+    )
+    .bind(refund_ids)
+    .fetch_all(executor)
+    .await
+    .map_err(AppError::from)?;
+
+    let mut items: HashMap<String, Vec<RefundItem>> = HashMap::new();
+    for row in rows {
+        items.entry(row.refund_id).or_default().push(RefundItem {
+            item_id: row.order_item_id,
+            quantity: row.quantity,
+        });
+    }
+    Ok(items)
+}
+
+/// An order's refunds, oldest first.
+pub async fn list(db: &PgPool, order_id: &str) -> Result<Vec<Refund>, Status> {
+    let refunds = sqlx::query_as::<_, DbRefund>(&format!(
+        "SELECT {} FROM order_refunds WHERE order_id = $1 ORDER BY created_at, id",
+        REFUND_COLUMNS
+    ))
+    .bind(order_id)
+    .fetch_all(db)
+    .await
+    .map_err(AppError::from)?;
+
+    let ids: Vec<String> = refunds.iter().map(|r| r.id.clone()).collect();
+    let mut items = fetch_items(db, &ids).await?;
+
+    Ok(refunds
+        .iter()
+        .map(|r| r.to_proto(items.remove(&r.id).unwrap_or_default()))
+        .collect())
+}
+
+type PaymentClient =
+    PaymentServiceClient<InterceptedService<CircuitBreaker<Channel>, ServiceCredentials>>;
+
+async fn connect(endpoint: &ServiceEndpoint) -> Result<PaymentClient, Status> {
+    let channel = endpoint
+        .primary_channel()
+        .map_err(|e| Status::internal(format!("Invalid payment service URL: {}", e)))?;
+    Ok(PaymentServiceClient::with_interceptor(
+        endpoint.guard(channel),
+        ServiceCredentials("order"),
+    ))
+}
+
+/// Keeps a refusal from the payment service as it is, and an open circuit's
+/// fast failure; anything else is an internal error here.
+fn payment_error(status: Status) -> Status {
+    if error::is_refusal(&status) {
+        status
+    } else {
+        resilience::downstream_error("Payment", status)
+    }
+}
+
+/// The order's payment that hasn't failed, if any.
+pub async fn order_payment(
+    endpoint: &ServiceEndpoint,
+    order_id: &str,
+) -> Result<Option<Payment>, Status> {
+    let client = connect(endpoint).await?;
+    let request = GetPaymentRequest {
+        payment_id: String::new(),
+        order_id: order_id.to_string(),
+    };
+    let response = resilience::retry(endpoint.retry_policy(), "GetPayment", || {
+        let mut client = client.clone();
+        let request = request.clone();
+        async move { client.get_payment(request).await }
+    })
+    .await
+    .map_err(|e| resilience::downstream_error("Payment", e))?
+    .into_inner();
+
+    Ok(response.payment.filter(|_| response.success))
+}
+
+/// Asks the payment service to pay a pending refund; returns its refund id.
+/// The refund id is the call's idempotency key, so sending a refund again
+/// can't refund its payment twice. `Ok(Err(..))` holds the payment service's
+/// message refusing the refund.
+pub async fn refund_payment(
+    endpoint: &ServiceEndpoint,
+    refund: &DbRefund,
+) -> Result<Result<String, String>, Status> {
+    let client = connect(endpoint).await?;
+    let key = MetadataValue::try_from(refund.id.as_str())
+        .map_err(|_| Status::internal("Refund ids are valid header values"))?;
+    let request = RefundPaymentRequest {
+        payment_id: refund.payment_id.clone(),
+        amount: refund.amount.to_string(),
+        reason: refund.reason.clone().unwrap_or_default(),
+    };
+    let response = resilience::retry(endpoint.retry_policy(), "RefundPayment", || {
+        let mut client = client.clone();
+        let mut request = tonic::Request::new(request.clone());
+        request
+            .metadata_mut()
+            .insert(idempotency::HEADER, key.clone());
+        async move { client.refund_payment(request).await }
+    })
+    .await
+    .map_err(payment_error)?
+    .into_inner();
+
+    Ok(if response.success {
+        Ok(response.refund_id)
+    } else {
+        Err(response.message)
+    })
+}
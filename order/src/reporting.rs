@@ -0,0 +1,272 @@
+use chrono::{Datelike, Days, NaiveDate, Utc};
+use chrono_tz::Tz;
+use common::auth::Caller;
+use common::error::{self, AppError};
+use proto::reporting::{
+    GetOrderMetricsRequest, GetOrderMetricsResponse, GetSalesReportRequest, GetSalesReportResponse,
+    OrderMetrics, ReportGranularity, SalesBucket, reporting_service_server::ReportingService,
+};
+use sqlx::PgPool;
+use sqlx::types::Decimal;
+use std::collections::HashMap;
+use tonic::{Request, Response, Status};
+
+/// Upper bound of buckets per report.
+const MAX_BUCKETS: u64 = 1000;
+
+pub struct ReportingServiceImpl {
+    db: PgPool,
+    time_zone: Tz,
+}
+
+/// The periods a report covers.
+struct ReportRange {
+    time_zone: Tz,
+    from_date: NaiveDate,
+    to_date: NaiveDate,
+    /// `date_trunc` unit of a period.
+    unit: &'static str,
+    /// Start of the period `from_date` falls in.
+    first_period: NaiveDate,
+    step: u64,
+    periods: u64,
+}
+
+impl ReportRange {
+    fn period_starts(&self) -> impl Iterator<Item = NaiveDate> + '_ {
+        (0..self.periods).map(|i| self.first_period + Days::new(i * self.step))
+    }
+}
+
+impl ReportingServiceImpl {
+    pub fn new(db: PgPool, time_zone: Tz) -> Self {
+        Self { db, time_zone }
+    }
+
+    fn parse_date(value: &str) -> Result<NaiveDate, String> {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .map_err(|_| format!("Invalid date '{}', expected YYYY-MM-DD", value))
+    }
+
+    /// Resolves a report's range from its request fields; `Err` says what's
+    /// wrong with them.
+    fn range(
+        &self,
+        from_date: &str,
+        to_date: &str,
+        granularity: i32,
+        time_zone: &str,
+    ) -> Result<ReportRange, String> {
+        let time_zone = if time_zone.is_empty() {
+            self.time_zone
+        } else {
+            time_zone
+                .parse::<Tz>()
+                .map_err(|_| format!("Unknown time zone '{}'", time_zone))?
+        };
+
+        let to_date = if to_date.is_empty() {
+            Utc::now().with_timezone(&time_zone).date_naive()
+        } else {
+            Self::parse_date(to_date)?
+        };
+        let from_date = if from_date.is_empty() {
+            to_date - Days::new(29)
+        } else {
+            Self::parse_date(from_date)?
+        };
+
+        if from_date > to_date {
+            return Err("from_date must not be after to_date".to_string());
+        }
+
+        let granularity =
+            ReportGranularity::try_from(granularity).unwrap_or(ReportGranularity::Day);
+        let (unit, step, first_period) = match granularity {
+            ReportGranularity::Day => ("day", 1, from_date),
+            ReportGranularity::Week => (
+                "week",
+                7,
+                from_date - Days::new(from_date.weekday().num_days_from_monday() as u64),
+            ),
+        };
+
+        let periods = (to_date - first_period).num_days() as u64 / step + 1;
+        if periods > MAX_BUCKETS {
+            return Err(format!(
+                "Report range too large, at most {} buckets are allowed",
+                MAX_BUCKETS
+            ));
+        }
+
+        Ok(ReportRange {
+            time_zone,
+            from_date,
+            to_date,
+            unit,
+            first_period,
+            step,
+            periods,
+        })
+    }
+}
+
+/// Metrics of `order_count` orders of which `cancelled_count` were cancelled.
+fn order_metrics(
+    period_start: String,
+    order_count: i64,
+    cancelled_count: i64,
+    revenue: Decimal,
+) -> OrderMetrics {
+    let placed = order_count - cancelled_count;
+    let average_order_value = if placed > 0 {
+        revenue / Decimal::from(placed)
+    } else {
+        Decimal::ZERO
+    };
+    let cancellation_rate = if order_count > 0 {
+        cancelled_count as f64 / order_count as f64
+    } else {
+        0.0
+    };
+
+    OrderMetrics {
+        period_start,
+        order_count: order_count as i32,
+        cancelled_count: cancelled_count as i32,
+        revenue: revenue.round_dp(2).to_string(),
+        average_order_value: average_order_value.round_dp(2).to_string(),
+        cancellation_rate,
+    }
+}
+
+#[tonic::async_trait]
+impl ReportingService for ReportingServiceImpl {
+    async fn get_sales_report(
+        &self,
+        request: Request<GetSalesReportRequest>,
+    ) -> Result<Response<GetSalesReportResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        let range = self
+            .range(
+                &req.from_date,
+                &req.to_date,
+                req.granularity,
+                &req.time_zone,
+            )
+            .map_err(|message| error::invalid_argument("INVALID_REPORT_RANGE", message))?;
+
+        // Day boundaries are converted to instants in the report time zone, so
+        // DST changes and non-UTC offsets land orders on the right calendar day.
+        // Totals are converted back at each order's own exchange rate, so
+        // orders in different currencies add up
+        let rows: Vec<(NaiveDate, i64, Decimal)> = sqlx::query_as(
+            "SELECT date_trunc($1, created_at AT TIME ZONE $2)::DATE AS period,
+                    COUNT(*),
+                    COALESCE(SUM(total_amount / exchange_rate), 0)
+             FROM orders
+             WHERE status <> 'CANCELLED'
+               AND created_at >= $3::DATE::TIMESTAMP AT TIME ZONE $2
+               AND created_at < ($4::DATE + 1)::TIMESTAMP AT TIME ZONE $2
+             GROUP BY period",
+        )
+        .bind(range.unit)
+        .bind(range.time_zone.name())
+        .bind(range.from_date)
+        .bind(range.to_date)
+        .fetch_all(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        let totals: HashMap<NaiveDate, (i64, Decimal)> = rows
+            .into_iter()
+            .map(|(period, count, revenue)| (period, (count, revenue)))
+            .collect();
+
+        // Empty periods are reported as zero so charts have no gaps
+        let buckets: Vec<SalesBucket> = range
+            .period_starts()
+            .map(|period| {
+                let (count, revenue) = totals.get(&period).copied().unwrap_or_default();
+                SalesBucket {
+                    period_start: period.format("%Y-%m-%d").to_string(),
+                    order_count: count as i32,
+                    revenue: revenue.round_dp(2).to_string(),
+                }
+            })
+            .collect();
+
+        Ok(Response::new(GetSalesReportResponse {
+            success: true,
+            message: format!("Retrieved {} buckets", buckets.len()),
+            time_zone: range.time_zone.name().to_string(),
+            buckets,
+        }))
+    }
+
+    async fn get_order_metrics(
+        &self,
+        request: Request<GetOrderMetricsRequest>,
+    ) -> Result<Response<GetOrderMetricsResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        let range = self
+            .range(&req.from_date, &req.to_date, req.group_by, &req.time_zone)
+            .map_err(|message| error::invalid_argument("INVALID_REPORT_RANGE", message))?;
+
+        // Totals are converted back at each order's own exchange rate, so
+        // orders in different currencies add up
+        let rows: Vec<(NaiveDate, i64, i64, Decimal)> = sqlx::query_as(
+            "SELECT date_trunc($1, created_at AT TIME ZONE $2)::DATE AS period,
+                    COUNT(*),
+                    COUNT(*) FILTER (WHERE status = 'CANCELLED'),
+                    COALESCE(SUM(total_amount / exchange_rate) FILTER (WHERE status <> 'CANCELLED'), 0)
+             FROM orders
+             WHERE created_at >= $3::DATE::TIMESTAMP AT TIME ZONE $2
+               AND created_at < ($4::DATE + 1)::TIMESTAMP AT TIME ZONE $2
+             GROUP BY period",
+        )
+        .bind(range.unit)
+        .bind(range.time_zone.name())
+        .bind(range.from_date)
+        .bind(range.to_date)
+        .fetch_all(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        let totals: HashMap<NaiveDate, (i64, i64, Decimal)> = rows
+            .into_iter()
+            .map(|(period, count, cancelled, revenue)| (period, (count, cancelled, revenue)))
+            .collect();
+
+        // Empty periods are reported as zero so charts have no gaps
+        let buckets: Vec<OrderMetrics> = range
+            .period_starts()
+            .map(|period| {
+                let (count, cancelled, revenue) = totals.get(&period).copied().unwrap_or_default();
+                order_metrics(
+                    period.format("%Y-%m-%d").to_string(),
+                    count,
+                    cancelled,
+                    revenue,
+                )
+            })
+            .collect();
+
+        let (count, cancelled, revenue) = totals.values().fold(
+            (0, 0, Decimal::ZERO),
+            |(count, cancelled, revenue), (c, x, r)| (count + c, cancelled + x, revenue + r),
+        );
+
+        Ok(Response::new(GetOrderMetricsResponse {
+            success: true,
+            message: format!("Retrieved {} buckets", buckets.len()),
+            time_zone: range.time_zone.name().to_string(),
+            buckets,
+            totals: Some(order_metrics(String::new(), count, cancelled, revenue)),
+        }))
+    }
+}
@@ -0,0 +1,327 @@
+//! Where the order service reads orders from for its lookups and listings
+//! by user. `PostgresOrderRepository` is the production one;
+//! `InMemoryOrderRepository` lets tests run those handlers without a
+//! database.
+
+use crate::order::push_order_page;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use common::error::{self, AppError};
+use common::pagination::{Cursor, PageRequest, SortOrder};
+use sqlx::types::Decimal;
+use sqlx::{PgPool, Postgres, QueryBuilder};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tonic::Status;
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct DbOrder {
+    pub id: String,
+    pub order_number: String,
+    pub user_id: String,
+    pub total_amount: Decimal,
+    pub status: String,
+    pub shipping_address: Option<String>,
+    pub tax_amount: Decimal,
+    pub shipping_region: Option<String>,
+    pub shipping_method: Option<String>,
+    pub shipping_fee: Decimal,
+    pub currency: String,
+    pub exchange_rate: Decimal,
+    pub estimated_delivery_from: Option<NaiveDate>,
+    pub estimated_delivery_to: Option<NaiveDate>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct DbOrderItem {
+    pub id: String,
+    pub order_id: String,
+    pub product_id: String,
+    pub quantity: i32,
+    pub price: Decimal,
+    pub status: String,
+    pub tax_amount: Decimal,
+    pub fulfillment_status: String,
+    pub shipment_tracking: Option<String>,
+    pub shipped_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct DbItemTracking {
+    pub order_item_id: String,
+    pub serial_number: Option<String>,
+    pub lot_number: Option<String>,
+    pub quantity: i32,
+}
+
+#[tonic::async_trait]
+pub trait OrderRepository: Send + Sync {
+    async fn find(&self, order_id: &str) -> Result<Option<DbOrder>, Status>;
+
+    /// The order numbered `order_number`, as normalized by the service.
+    async fn find_by_number(&self, order_number: &str) -> Result<Option<DbOrder>, Status>;
+
+    /// Orders of `order_ids` that exist, in no particular order.
+    async fn find_many(&self, order_ids: &[String]) -> Result<Vec<DbOrder>, Status>;
+
+    /// Items of the orders, in no particular order.
+    async fn items(&self, order_ids: &[String]) -> Result<Vec<DbOrderItem>, Status>;
+
+    /// Serial and lot numbers recorded for the items, oldest first.
+    async fn tracking(&self, item_ids: &[String]) -> Result<Vec<DbItemTracking>, Status>;
+
+    /// A page of a user's orders by creation time, optionally only those
+    /// in `status`, with one order over the page size when there are more;
+    /// see `pagination::next_page_token`.
+    async fn list_by_user(
+        &self,
+        user_id: &str,
+        status: Option<&str>,
+        page: &PageRequest,
+        after: Option<&Cursor>,
+        order: SortOrder,
+    ) -> Result<Vec<DbOrder>, Status>;
+
+    /// How many orders `list_by_user` pages through.
+    async fn count_by_user(&self, user_id: &str, status: Option<&str>) -> Result<i64, Status>;
+}
+
+pub struct PostgresOrderRepository {
+    db: PgPool,
+}
+
+impl PostgresOrderRepository {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    async fn find_by(&self, column: &str, key: &str) -> Result<Option<DbOrder>, Status> {
+        sqlx::query_as::<_, DbOrder>(&format!(
+            "SELECT id, order_number, user_id, total_amount, status, shipping_address, tax_amount, shipping_region, shipping_method, shipping_fee, currency, exchange_rate, estimated_delivery_from, estimated_delivery_to, created_at, updated_at
+             FROM orders WHERE {} = $1",
+            column
+        ))
+        .bind(key)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| AppError::from(e).into())
+    }
+}
+
+#[tonic::async_trait]
+impl OrderRepository for PostgresOrderRepository {
+    async fn find(&self, order_id: &str) -> Result<Option<DbOrder>, Status> {
+        self.find_by("id", order_id).await
+    }
+
+    async fn find_by_number(&self, order_number: &str) -> Result<Option<DbOrder>, Status> {
+        self.find_by("order_number", order_number).await
+    }
+
+    async fn find_many(&self, order_ids: &[String]) -> Result<Vec<DbOrder>, Status> {
+        sqlx::query_as::<_, DbOrder>(
+            "SELECT id, order_number, user_id, total_amount, status, shipping_address, tax_amount, shipping_region, shipping_method, shipping_fee, currency, exchange_rate, estimated_delivery_from, estimated_delivery_to, created_at, updated_at
+             FROM orders WHERE id = ANY($1)",
+        )
+        .bind(order_ids)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::from(e).into())
+    }
+
+    async fn items(&self, order_ids: &[String]) -> Result<Vec<DbOrderItem>, Status> {
+        sqlx::query_as::<_, DbOrderItem>(
+            "SELECT id, order_id, product_id, quantity, price, status, tax_amount, fulfillment_status, shipment_tracking, shipped_at FROM order_items WHERE order_id = ANY($1)",
+        )
+        .bind(order_ids)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::from(e).into())
+    }
+
+    async fn tracking(&self, item_ids: &[String]) -> Result<Vec<DbItemTracking>, Status> {
+        sqlx::query_as::<_, DbItemTracking>(
+            "SELECT order_item_id, serial_number, lot_number, quantity
+             FROM order_item_tracking WHERE order_item_id = ANY($1)
+             ORDER BY recorded_at",
+        )
+        .bind(item_ids)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::from(e).into())
+    }
+
+    async fn list_by_user(
+        &self,
+        user_id: &str,
+        status: Option<&str>,
+        page: &PageRequest,
+        after: Option<&Cursor>,
+        order: SortOrder,
+    ) -> Result<Vec<DbOrder>, Status> {
+        let mut query = QueryBuilder::<Postgres>::new(
+            "SELECT id, order_number, user_id, total_amount, status, shipping_address, tax_amount, shipping_region, shipping_method, shipping_fee, currency, exchange_rate, estimated_delivery_from, estimated_delivery_to, created_at, updated_at
+             FROM orders WHERE user_id = ",
+        );
+        query.push_bind(user_id.to_string());
+        if let Some(status) = status {
+            query.push(" AND status = ").push_bind(status.to_string());
+        }
+        push_order_page(&mut query, page, after, order);
+
+        query
+            .build_query_as::<DbOrder>()
+            .fetch_all(&self.db)
+            .await
+            .map_err(|e| AppError::from(e).into())
+    }
+
+    async fn count_by_user(&self, user_id: &str, status: Option<&str>) -> Result<i64, Status> {
+        sqlx::query_scalar(
+            "SELECT COUNT(*) FROM orders WHERE user_id = $1 AND ($2::TEXT IS NULL OR status = $2)",
+        )
+        .bind(user_id)
+        .bind(status)
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| AppError::from(e).into())
+    }
+}
+
+#[derive(Default)]
+struct Orders {
+    orders: HashMap<String, DbOrder>,
+    items: Vec<DbOrderItem>,
+    tracking: Vec<DbItemTracking>,
+}
+
+/// Orders kept in memory, for tests, which put them in place with
+/// `insert`; the service writes orders through its database only.
+#[derive(Default)]
+pub struct InMemoryOrderRepository {
+    orders: Mutex<Orders>,
+}
+
+impl InMemoryOrderRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an order with its items, replacing any of the same id.
+    pub fn insert(&self, order: DbOrder, items: Vec<DbOrderItem>) {
+        let mut orders = self.orders.lock().unwrap();
+        orders.items.retain(|item| item.order_id != order.id);
+        orders.items.extend(items);
+        orders.orders.insert(order.id.clone(), order);
+    }
+
+    /// Records serial or lot numbers of an item.
+    pub fn insert_tracking(&self, tracking: DbItemTracking) {
+        self.orders.lock().unwrap().tracking.push(tracking);
+    }
+}
+
+#[tonic::async_trait]
+impl OrderRepository for InMemoryOrderRepository {
+    async fn find(&self, order_id: &str) -> Result<Option<DbOrder>, Status> {
+        Ok(self.orders.lock().unwrap().orders.get(order_id).cloned())
+    }
+
+    async fn find_by_number(&self, order_number: &str) -> Result<Option<DbOrder>, Status> {
+        let orders = self.orders.lock().unwrap();
+        Ok(orders
+            .orders
+            .values()
+            .find(|order| order.order_number == order_number)
+            .cloned())
+    }
+
+    async fn find_many(&self, order_ids: &[String]) -> Result<Vec<DbOrder>, Status> {
+        let orders = self.orders.lock().unwrap();
+        Ok(order_ids
+            .iter()
+            .filter_map(|id| orders.orders.get(id).cloned())
+            .collect())
+    }
+
+    async fn items(&self, order_ids: &[String]) -> Result<Vec<DbOrderItem>, Status> {
+        let orders = self.orders.lock().unwrap();
+        Ok(orders
+            .items
+            .iter()
+            .filter(|item| order_ids.contains(&item.order_id))
+            .cloned()
+            .collect())
+    }
+
+    async fn tracking(&self, item_ids: &[String]) -> Result<Vec<DbItemTracking>, Status> {
+        let orders = self.orders.lock().unwrap();
+        Ok(orders
+            .tracking
+            .iter()
+            .filter(|tracking| item_ids.contains(&tracking.order_item_id))
+            .cloned()
+            .collect())
+    }
+
+    async fn list_by_user(
+        &self,
+        user_id: &str,
+        status: Option<&str>,
+        page: &PageRequest,
+        after: Option<&Cursor>,
+        order: SortOrder,
+    ) -> Result<Vec<DbOrder>, Status> {
+        let after = match after {
+            Some(cursor) => match DateTime::parse_from_rfc3339(&cursor.key) {
+                Ok(created_at) => Some((created_at.with_timezone(&Utc), cursor.id.clone())),
+                Err(_) => {
+                    return Err(error::invalid_argument(
+                        "INVALID_PAGE_TOKEN",
+                        "Invalid page token",
+                    ));
+                }
+            },
+            None => None,
+        };
+
+        let orders = self.orders.lock().unwrap();
+        let mut matching: Vec<&DbOrder> = orders
+            .orders
+            .values()
+            .filter(|o| o.user_id == user_id && status.is_none_or(|status| o.status == status))
+            .collect();
+        matching.sort_by(|a, b| (a.created_at, &a.id).cmp(&(b.created_at, &b.id)));
+        if order == SortOrder::Desc {
+            matching.reverse();
+        }
+
+        let skip = match &after {
+            Some(_) => 0,
+            None => page.offset() as usize,
+        };
+        Ok(matching
+            .into_iter()
+            .filter(|o| match &after {
+                Some((created_at, id)) => match order {
+                    SortOrder::Asc => (o.created_at, &o.id) > (*created_at, id),
+                    SortOrder::Desc => (o.created_at, &o.id) < (*created_at, id),
+                },
+                None => true,
+            })
+            .skip(skip)
+            .take(page.limit() as usize + 1)
+            .cloned()
+            .collect())
+    }
+
+    async fn count_by_user(&self, user_id: &str, status: Option<&str>) -> Result<i64, Status> {
+        let orders = self.orders.lock().unwrap();
+        Ok(orders
+            .orders
+            .values()
+            .filter(|o| o.user_id == user_id && status.is_none_or(|status| o.status == status))
+            .count() as i64)
+    }
+}
@@ -0,0 +1,219 @@
+//! CreateOrder runs as a saga across the product service: stock is reserved
+//! there, the order is written here, then the reservation is confirmed. A
+//! failure before the order is written releases the reservation instead.
+//! Every step is logged in `order_sagas`, so a crash at any point leaves a
+//! state the recovery sweep can finish:
+//!
+//! STARTED -> ORDER_CREATED -> COMPLETED, or STARTED -> COMPENSATING -> COMPENSATED
+
+use common::auth::ServiceCredentials;
+use common::client::ServiceEndpoint;
+use common::error::{self, AppError};
+use common::resilience::{self, CircuitBreaker};
+use proto::product::product_service_client::ProductServiceClient;
+use proto::product::{
+    ConfirmReservationRequest, ReleaseReservationRequest, ReservationLine, ReserveStockRequest,
+};
+use sqlx::{PgExecutor, PgPool};
+use std::sync::Arc;
+use std::time::Duration;
+use tonic::Status;
+use tonic::service::interceptor::InterceptedService;
+use tonic::transport::Channel;
+use tracing::{info, warn};
+
+pub const STARTED: &str = "STARTED";
+pub const ORDER_CREATED: &str = "ORDER_CREATED";
+pub const COMPLETED: &str = "COMPLETED";
+pub const COMPENSATING: &str = "COMPENSATING";
+pub const COMPENSATED: &str = "COMPENSATED";
+
+/// How long a saga may stay in progress before the sweep takes it over;
+/// well past the time CreateOrder needs to finish it.
+const IN_DOUBT_AFTER_SECS: i64 = 300;
+
+/// Sagas recovered per sweep.
+const MAX_RECOVERIES_PER_SWEEP: i64 = 100;
+
+/// Logs a new saga, before anything is reserved for the order.
+pub async fn start(db: &PgPool, order_id: &str) -> Result<(), Status> {
+    sqlx::query("INSERT INTO order_sagas (order_id, state) VALUES ($1, $2)")
+        .bind(order_id)
+        .bind(STARTED)
+        .execute(db)
+        .await
+        .map_err(AppError::from)?;
+
+    Ok(())
+}
+
+/// Moves a saga from one of `from` to `to`; `false` when it is in none of them.
+pub async fn advance<'e>(
+    executor: impl PgExecutor<'e>,
+    order_id: &str,
+    from: &[&str],
+    to: &str,
+) -> Result<bool, Status> {
+    let from: Vec<String> = from.iter().map(|s| s.to_string()).collect();
+    let result = sqlx::query(
+        "UPDATE order_sagas SET state = $1, updated_at = CURRENT_TIMESTAMP
+         WHERE order_id = $2 AND state = ANY($3)",
+    )
+    .bind(to)
+    .bind(order_id)
+    .bind(&from)
+    .execute(executor)
+    .await
+    .map_err(AppError::from)?;
+
+    Ok(result.rows_affected() == 1)
+}
+
+type ProductClient =
+    ProductServiceClient<InterceptedService<CircuitBreaker<Channel>, ServiceCredentials>>;
+
+async fn connect(endpoint: &ServiceEndpoint) -> Result<ProductClient, Status> {
+    let channel = endpoint
+        .primary_channel()
+        .map_err(|e| Status::internal(format!("Invalid product service URL: {}", e)))?;
+    Ok(ProductServiceClient::with_interceptor(
+        endpoint.guard(channel),
+        ServiceCredentials("order"),
+    ))
+}
+
+/// Keeps a refusal from the product service, e.g. stock running out, as it
+/// is, and an open circuit's fast failure; anything else is an internal
+/// error here.
+fn product_error(status: Status) -> Status {
+    if error::is_refusal(&status) {
+        status
+    } else {
+        resilience::downstream_error("Product", status)
+    }
+}
+
+/// Reserves stock for an order's lines under the order id. A refused
+/// reservation fails with the product service's FAILED_PRECONDITION.
+pub async fn reserve(
+    endpoint: &ServiceEndpoint,
+    order_id: &str,
+    lines: Vec<ReservationLine>,
+) -> Result<Vec<ReservationLine>, Status> {
+    let response = connect(endpoint)
+        .await?
+        .reserve_stock(ReserveStockRequest {
+            reservation_id: order_id.to_string(),
+            lines,
+        })
+        .await
+        .map_err(product_error)?
+        .into_inner();
+
+    Ok(response.lines)
+}
+
+/// Releases what was reserved for an order and marks its saga compensated.
+/// Does nothing once the order has been written.
+pub async fn compensate(
+    db: &PgPool,
+    endpoint: &ServiceEndpoint,
+    order_id: &str,
+) -> Result<(), Status> {
+    if !advance(db, order_id, &[STARTED, COMPENSATING], COMPENSATING).await? {
+        return Ok(());
+    }
+
+    connect(endpoint)
+        .await?
+        .release_reservation(ReleaseReservationRequest {
+            reservation_id: order_id.to_string(),
+        })
+        .await
+        .map_err(product_error)?;
+
+    advance(db, order_id, &[COMPENSATING], COMPENSATED).await?;
+    Ok(())
+}
+
+/// Confirms the reservation of a written order and marks its saga completed.
+pub async fn complete(
+    db: &PgPool,
+    endpoint: &ServiceEndpoint,
+    order_id: &str,
+) -> Result<(), Status> {
+    connect(endpoint)
+        .await?
+        .confirm_reservation(ConfirmReservationRequest {
+            reservation_id: order_id.to_string(),
+        })
+        .await
+        .map_err(product_error)?;
+
+    advance(db, order_id, &[ORDER_CREATED], COMPLETED).await?;
+    Ok(())
+}
+
+/// Finishes sagas left in doubt by a crash or a failed step: orders that were
+/// written get their reservation confirmed, the others released.
+pub struct SagaRecovery {
+    db: PgPool,
+    product_service: Arc<ServiceEndpoint>,
+}
+
+impl SagaRecovery {
+    pub fn new(db: PgPool, product_service: Arc<ServiceEndpoint>) -> Self {
+        Self {
+            db,
+            product_service,
+        }
+    }
+
+    /// Recovers the sagas in doubt; returns how many were finished.
+    pub async fn run(&self) -> Result<usize, Status> {
+        let in_doubt: Vec<(String, String)> = sqlx::query_as(
+            "SELECT order_id, state FROM order_sagas
+             WHERE state IN ('STARTED', 'COMPENSATING', 'ORDER_CREATED')
+               AND updated_at < CURRENT_TIMESTAMP - make_interval(secs => $1)
+             ORDER BY updated_at
+             LIMIT $2",
+        )
+        .bind(IN_DOUBT_AFTER_SECS as f64)
+        .bind(MAX_RECOVERIES_PER_SWEEP)
+        .fetch_all(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        let mut finished = 0;
+        for (order_id, state) in in_doubt {
+            let result = if state == ORDER_CREATED {
+                complete(&self.db, &self.product_service, &order_id).await
+            } else {
+                compensate(&self.db, &self.product_service, &order_id).await
+            };
+            match result {
+                Ok(()) => finished += 1,
+                Err(e) => {
+                    warn!(order_id = %order_id, state = %state, "Saga recovery failed: {}", e)
+                }
+            }
+        }
+
+        Ok(finished)
+    }
+
+    /// Spawns a background task that sweeps every `interval`.
+    pub fn spawn(self, interval: Duration) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match self.run().await {
+                    Ok(0) => {}
+                    Ok(finished) => info!("Recovered {} order sagas", finished),
+                    Err(e) => warn!("Saga recovery sweep failed: {}", e),
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,110 @@
+use common::auth::ServiceCredentials;
+use common::client::ServiceEndpoint;
+use common::resilience;
+use proto::shipping::shipping_service_client::ShippingServiceClient;
+use proto::shipping::{
+    GetShipmentByOrderRequest, QuoteShippingRequest, ShipmentStatus, ShippingItem,
+};
+use sqlx::types::Decimal;
+use std::collections::HashSet;
+use tonic::Status;
+
+/// Shipping with a method, as quoted by the shipping service.
+#[derive(Debug, Clone, Copy)]
+pub struct Quote {
+    pub fee: Decimal,
+    /// Days delivery takes after the order is placed.
+    pub min_transit_days: i32,
+    pub max_transit_days: i32,
+}
+
+/// Quote for shipping the `(product_id, quantity)` lines to `region` with
+/// `method`. `Ok(Err(..))` says why the method can't be chosen for them.
+pub async fn quote(
+    endpoint: &ServiceEndpoint,
+    items: &[(String, i32)],
+    region: &str,
+    method: &str,
+) -> Result<Result<Quote, String>, Status> {
+    if region.is_empty() {
+        return Ok(Err(
+            "Shipping region is required to choose a shipping method".to_string(),
+        ));
+    }
+
+    let channel = endpoint
+        .primary_channel()
+        .map_err(|e| Status::internal(format!("Invalid shipping service URL: {}", e)))?;
+    let mut client = ShippingServiceClient::new(channel);
+    let response = client
+        .quote_shipping(QuoteShippingRequest {
+            items: items
+                .iter()
+                .map(|(product_id, quantity)| ShippingItem {
+                    product_id: product_id.clone(),
+                    quantity: *quantity,
+                })
+                .collect(),
+            shipping_region: region.to_string(),
+        })
+        .await
+        .map_err(|e| resilience::downstream_error("Shipping", e))?
+        .into_inner();
+
+    if !response.success {
+        return Ok(Err(response.message));
+    }
+    if !response.requires_shipping {
+        return Ok(Err("Order has nothing to ship".to_string()));
+    }
+
+    let Some(option) = response.options.into_iter().find(|o| o.method == method) else {
+        return Ok(Err(format!(
+            "Shipping method {} is not available for this order",
+            method
+        )));
+    };
+    let fee = option
+        .fee
+        .parse::<Decimal>()
+        .map_err(|_| Status::internal(format!("Invalid shipping fee: {}", option.fee)))?;
+    Ok(Ok(Quote {
+        fee,
+        min_transit_days: option.min_transit_days,
+        max_transit_days: option.max_transit_days,
+    }))
+}
+
+/// Items of the order `order_id` in a shipment that isn't cancelled, as the
+/// shipping service has them.
+pub async fn shipped_items(
+    endpoint: &ServiceEndpoint,
+    order_id: &str,
+) -> Result<HashSet<String>, Status> {
+    let channel = endpoint
+        .primary_channel()
+        .map_err(|e| Status::internal(format!("Invalid shipping service URL: {}", e)))?;
+    let client = ShippingServiceClient::with_interceptor(
+        endpoint.guard(channel),
+        ServiceCredentials("order"),
+    );
+    let request = GetShipmentByOrderRequest {
+        order_id: order_id.to_string(),
+    };
+    let response = resilience::retry(endpoint.retry_policy(), "GetShipmentByOrder", || {
+        let mut client = client.clone();
+        let request = request.clone();
+        async move { client.get_shipment_by_order(request).await }
+    })
+    .await
+    .map_err(|e| resilience::downstream_error("Shipping", e))?
+    .into_inner();
+
+    // An order with no shipments comes back unsuccessful, with none
+    Ok(response
+        .shipments
+        .into_iter()
+        .filter(|shipment| shipment.status != ShipmentStatus::Cancelled as i32)
+        .flat_map(|shipment| shipment.item_ids)
+        .collect())
+}
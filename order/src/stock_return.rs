@@ -0,0 +1,222 @@
+//! Units an order gives back, e.g. on a cancellation or a refunded return, go
+//! back on sale through the product service, which owns stock. A return is
+//! recorded in the transaction freeing the units and sent once it commits,
+//! under its id, so a return sent again adds nothing. Returns the product
+//! service didn't take are sent again by the sweep.
+
+use common::auth::ServiceCredentials;
+use common::client::ServiceEndpoint;
+use common::error::{self, AppError};
+use common::resilience::{self, CircuitBreaker};
+use proto::product::product_service_client::ProductServiceClient;
+use proto::product::{ReservationLine, ReturnStockRequest};
+use sqlx::{PgConnection, PgPool};
+use std::sync::Arc;
+use std::time::Duration;
+use tonic::Status;
+use tonic::service::interceptor::InterceptedService;
+use tonic::transport::Channel;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// How long a return may stay pending before the sweep sends it again;
+/// well past the time the send after the commit needs.
+const RESEND_AFTER_SECS: i64 = 60;
+
+/// Returns sent per sweep.
+const MAX_RETURNS_PER_SWEEP: i64 = 100;
+
+/// Records that an order gives back `lines`, as (product id, units) pairs;
+/// `None` when there is nothing to give back.
+pub async fn record(
+    conn: &mut PgConnection,
+    order_id: &str,
+    lines: &[(String, i32)],
+) -> Result<Option<String>, Status> {
+    let lines: Vec<&(String, i32)> = lines.iter().filter(|(_, units)| *units > 0).collect();
+    if lines.is_empty() {
+        return Ok(None);
+    }
+
+    let return_id = Uuid::new_v4().to_string();
+    sqlx::query("INSERT INTO order_stock_returns (id, order_id) VALUES ($1, $2)")
+        .bind(&return_id)
+        .bind(order_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::from)?;
+
+    for (product_id, units) in lines {
+        sqlx::query(
+            "INSERT INTO order_stock_return_lines (return_id, product_id, quantity)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (return_id, product_id)
+             DO UPDATE SET quantity = order_stock_return_lines.quantity + EXCLUDED.quantity",
+        )
+        .bind(&return_id)
+        .bind(product_id)
+        .bind(units)
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::from)?;
+    }
+
+    Ok(Some(return_id))
+}
+
+/// Records that a cancelled order gives back the units its items took;
+/// backordered items took none.
+pub async fn record_order(
+    conn: &mut PgConnection,
+    order_id: &str,
+) -> Result<Option<String>, Status> {
+    let lines: Vec<(String, i32)> = sqlx::query_as(
+        "SELECT product_id, SUM(quantity)::INT FROM order_items
+         WHERE order_id = $1 AND status = 'ALLOCATED'
+         GROUP BY product_id",
+    )
+    .bind(order_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(AppError::from)?;
+
+    record(conn, order_id, &lines).await
+}
+
+type ProductClient =
+    ProductServiceClient<InterceptedService<CircuitBreaker<Channel>, ServiceCredentials>>;
+
+async fn connect(endpoint: &ServiceEndpoint) -> Result<ProductClient, Status> {
+    let channel = endpoint
+        .primary_channel()
+        .map_err(|e| Status::internal(format!("Invalid product service URL: {}", e)))?;
+    Ok(ProductServiceClient::with_interceptor(
+        endpoint.guard(channel),
+        ServiceCredentials("order"),
+    ))
+}
+
+/// Keeps a refusal from the product service as it is, and an open circuit's
+/// fast failure; anything else is an internal error here.
+fn product_error(status: Status) -> Status {
+    if error::is_refusal(&status) {
+        status
+    } else {
+        resilience::downstream_error("Product", status)
+    }
+}
+
+/// Sends a pending return to the product service and marks it done.
+pub async fn send(db: &PgPool, endpoint: &ServiceEndpoint, return_id: &str) -> Result<(), Status> {
+    let lines: Vec<(String, i32)> = sqlx::query_as(
+        "SELECT l.product_id, l.quantity
+         FROM order_stock_return_lines l
+         JOIN order_stock_returns r ON r.id = l.return_id
+         WHERE l.return_id = $1 AND r.status = 'PENDING'
+         ORDER BY l.product_id",
+    )
+    .bind(return_id)
+    .fetch_all(db)
+    .await
+    .map_err(AppError::from)?;
+    if lines.is_empty() {
+        return Ok(());
+    }
+
+    let request = ReturnStockRequest {
+        return_id: return_id.to_string(),
+        lines: lines
+            .into_iter()
+            .map(|(product_id, quantity)| ReservationLine {
+                product_id,
+                quantity,
+                backordered: false,
+            })
+            .collect(),
+    };
+    let client = connect(endpoint).await?;
+    resilience::retry(endpoint.retry_policy(), "ReturnStock", || {
+        let mut client = client.clone();
+        let request = request.clone();
+        async move { client.return_stock(request).await }
+    })
+    .await
+    .map_err(product_error)?;
+
+    sqlx::query(
+        "UPDATE order_stock_returns SET status = 'DONE', updated_at = CURRENT_TIMESTAMP
+         WHERE id = $1 AND status = 'PENDING'",
+    )
+    .bind(return_id)
+    .execute(db)
+    .await
+    .map_err(AppError::from)?;
+
+    Ok(())
+}
+
+/// Sends a return just recorded, if any; one that fails is left to the sweep.
+pub async fn send_recorded(db: &PgPool, endpoint: &ServiceEndpoint, return_id: Option<String>) {
+    let Some(return_id) = return_id else {
+        return;
+    };
+    if let Err(e) = send(db, endpoint, &return_id).await {
+        warn!(return_id = %return_id, "Stock return left to the sweep: {}", e);
+    }
+}
+
+/// Sends again the returns the product service didn't take.
+pub struct StockReturns {
+    db: PgPool,
+    product_service: Arc<ServiceEndpoint>,
+}
+
+impl StockReturns {
+    pub fn new(db: PgPool, product_service: Arc<ServiceEndpoint>) -> Self {
+        Self {
+            db,
+            product_service,
+        }
+    }
+
+    /// Sends the pending returns; returns how many were taken.
+    pub async fn run(&self) -> Result<usize, Status> {
+        let pending: Vec<String> = sqlx::query_scalar(
+            "SELECT id FROM order_stock_returns
+             WHERE status = 'PENDING'
+               AND updated_at < CURRENT_TIMESTAMP - make_interval(secs => $1)
+             ORDER BY updated_at
+             LIMIT $2",
+        )
+        .bind(RESEND_AFTER_SECS as f64)
+        .bind(MAX_RETURNS_PER_SWEEP)
+        .fetch_all(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        let mut sent = 0;
+        for return_id in pending {
+            match send(&self.db, &self.product_service, &return_id).await {
+                Ok(()) => sent += 1,
+                Err(e) => warn!(return_id = %return_id, "Failed to send stock return: {}", e),
+            }
+        }
+
+        Ok(sent)
+    }
+
+    /// Spawns a background task that sweeps every `interval`.
+    pub fn spawn(self, interval: Duration) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match self.run().await {
+                    Ok(0) => {}
+                    Ok(sent) => info!("Sent {} pending stock returns", sent),
+                    Err(e) => warn!("Stock return sweep failed: {}", e),
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,230 @@
+use common::auth::Caller;
+use common::error::{self, AppError};
+use common::sandbox;
+use proto::tax::{
+    DeleteTaxRateRequest, DeleteTaxRateResponse, ListTaxRatesRequest, ListTaxRatesResponse,
+    SetTaxRateRequest, SetTaxRateResponse, TaxRate, tax_service_server::TaxService,
+};
+use sqlx::PgPool;
+use sqlx::types::Decimal;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+/// Tax class of products that don't name one.
+pub const STANDARD_CLASS: &str = "standard";
+
+/// An order line as far as tax is concerned.
+pub struct TaxLine {
+    pub tax_class: String,
+    /// Amount the tax is charged on, i.e. the line subtotal.
+    pub amount: Decimal,
+}
+
+/// Computes the tax charged on order lines shipped to a region.
+#[tonic::async_trait]
+pub trait TaxCalculator: Send + Sync {
+    /// Tax of each line, in line order, rounded to cents. `region` is an ISO
+    /// 3166 code such as US-CA; empty when the order ships nowhere.
+    async fn calculate(&self, region: &str, lines: &[TaxLine]) -> Result<Vec<Decimal>, Status>;
+}
+
+/// The calculator for this environment; sandbox mode always uses the sandbox one.
+pub fn from_env(db: PgPool) -> Arc<dyn TaxCalculator> {
+    if sandbox::enabled() {
+        Arc::new(SandboxTaxCalculator)
+    } else {
+        Arc::new(RateTableCalculator::new(db))
+    }
+}
+
+/// Normalizes a region code, e.g. " us-ca" -> "US-CA".
+pub fn normalize_region(region: &str) -> String {
+    region.trim().to_ascii_uppercase()
+}
+
+fn line_tax(amount: Decimal, rate: Decimal) -> Decimal {
+    (amount * rate / Decimal::ONE_HUNDRED).round_dp(2)
+}
+
+/// Charges the rates configured through TaxService. A subdivision's rate
+/// takes precedence over its country's; lines with no rate are not taxed.
+pub struct RateTableCalculator {
+    db: PgPool,
+}
+
+impl RateTableCalculator {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+}
+
+#[tonic::async_trait]
+impl TaxCalculator for RateTableCalculator {
+    async fn calculate(&self, region: &str, lines: &[TaxLine]) -> Result<Vec<Decimal>, Status> {
+        let region = normalize_region(region);
+        if region.is_empty() {
+            return Ok(vec![Decimal::ZERO; lines.len()]);
+        }
+        let country = region.split('-').next().unwrap_or_default().to_string();
+
+        let rates: HashMap<(String, String), Decimal> =
+            sqlx::query_as::<_, (String, String, Decimal)>(
+                "SELECT region, tax_class, rate FROM tax_rates WHERE region = ANY($1)",
+            )
+            .bind(vec![region.clone(), country.clone()])
+            .fetch_all(&self.db)
+            .await
+            .map_err(AppError::from)?
+            .into_iter()
+            .map(|(region, tax_class, rate)| ((region, tax_class), rate))
+            .collect();
+
+        Ok(lines
+            .iter()
+            .map(|line| {
+                let rate = rates
+                    .get(&(region.clone(), line.tax_class.clone()))
+                    .or_else(|| rates.get(&(country.clone(), line.tax_class.clone())))
+                    .copied()
+                    .unwrap_or(Decimal::ZERO);
+                line_tax(line.amount, rate)
+            })
+            .collect())
+    }
+}
+
+/// Deterministic fake: every line is taxed at `sandbox::TAX_RATE_PERCENT`,
+/// whatever the region or class.
+pub struct SandboxTaxCalculator;
+
+#[tonic::async_trait]
+impl TaxCalculator for SandboxTaxCalculator {
+    async fn calculate(&self, _region: &str, lines: &[TaxLine]) -> Result<Vec<Decimal>, Status> {
+        let rate = Decimal::from(sandbox::TAX_RATE_PERCENT);
+        Ok(lines
+            .iter()
+            .map(|line| line_tax(line.amount, rate))
+            .collect())
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct DbTaxRate {
+    region: String,
+    tax_class: String,
+    rate: Decimal,
+    updated_at: chrono::NaiveDateTime,
+}
+
+impl DbTaxRate {
+    fn to_proto(&self) -> TaxRate {
+        TaxRate {
+            region: self.region.clone(),
+            tax_class: self.tax_class.clone(),
+            rate: self.rate.to_string(),
+            updated_at: self.updated_at.and_utc().timestamp(),
+        }
+    }
+}
+
+/// Admin RPCs over the rate table used by `RateTableCalculator`.
+pub struct TaxServiceImpl {
+    db: PgPool,
+}
+
+impl TaxServiceImpl {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+}
+
+#[tonic::async_trait]
+impl TaxService for TaxServiceImpl {
+    async fn set_tax_rate(
+        &self,
+        request: Request<SetTaxRateRequest>,
+    ) -> Result<Response<SetTaxRateResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        // Field rules are checked by the validation layer (see tax.proto)
+        let rate = req
+            .rate
+            .parse::<Decimal>()
+            .map_err(|_| Status::invalid_argument("Invalid rate value"))?;
+        if rate > Decimal::ONE_HUNDRED {
+            return Err(error::invalid_argument(
+                "INVALID_TAX_RATE",
+                "Rate is a percentage and cannot exceed 100",
+            ));
+        }
+
+        let tax_rate = sqlx::query_as::<_, DbTaxRate>(
+            "INSERT INTO tax_rates (region, tax_class, rate) VALUES ($1, $2, $3)
+             ON CONFLICT (region, tax_class)
+             DO UPDATE SET rate = EXCLUDED.rate, updated_at = CURRENT_TIMESTAMP
+             RETURNING region, tax_class, rate, updated_at",
+        )
+        .bind(normalize_region(&req.region))
+        .bind(req.tax_class.trim().to_lowercase())
+        .bind(rate)
+        .fetch_one(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        Ok(Response::new(SetTaxRateResponse {
+            success: true,
+            message: "Tax rate saved".to_string(),
+            tax_rate: Some(tax_rate.to_proto()),
+        }))
+    }
+
+    async fn delete_tax_rate(
+        &self,
+        request: Request<DeleteTaxRateRequest>,
+    ) -> Result<Response<DeleteTaxRateResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        let result = sqlx::query("DELETE FROM tax_rates WHERE region = $1 AND tax_class = $2")
+            .bind(normalize_region(&req.region))
+            .bind(req.tax_class.trim().to_lowercase())
+            .execute(&self.db)
+            .await
+            .map_err(AppError::from)?;
+
+        if result.rows_affected() == 0 {
+            return Err(error::not_found("TAX_RATE_NOT_FOUND", "Tax rate not found"));
+        }
+
+        Ok(Response::new(DeleteTaxRateResponse {
+            success: true,
+            message: "Tax rate deleted".to_string(),
+        }))
+    }
+
+    async fn list_tax_rates(
+        &self,
+        request: Request<ListTaxRatesRequest>,
+    ) -> Result<Response<ListTaxRatesResponse>, Status> {
+        let req = request.into_inner();
+        let region = normalize_region(&req.region);
+
+        let rates = sqlx::query_as::<_, DbTaxRate>(
+            "SELECT region, tax_class, rate, updated_at FROM tax_rates
+             WHERE $1 = '' OR region = $1
+             ORDER BY region, tax_class",
+        )
+        .bind(&region)
+        .fetch_all(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        Ok(Response::new(ListTaxRatesResponse {
+            success: true,
+            message: format!("Found {} tax rate(s)", rates.len()),
+            tax_rates: rates.iter().map(DbTaxRate::to_proto).collect(),
+        }))
+    }
+}
@@ -0,0 +1,97 @@
+use anyhow::{Context, Result};
+use moka::Expiry;
+use moka::future::Cache;
+use std::env;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tonic::Status;
+
+/// Users cached, at most.
+const MAX_ENTRIES: u64 = 100_000;
+
+const DEFAULT_TTL_SECS: u64 = 60;
+const DEFAULT_NEGATIVE_TTL_SECS: u64 = 10;
+
+/// In-process cache of the user service's answer to whether a user id is
+/// valid, so placing orders doesn't ask it every time. Unknown users are
+/// cached too, for a shorter time, as a user registering right after is
+/// turned away until their entry expires. A user deactivated is still let
+/// through until theirs does.
+pub struct VerifiedUsers {
+    /// `None` when disabled: every lookup goes to the user service.
+    cache: Option<Cache<String, bool>>,
+}
+
+/// Expires valid users after `valid` and unknown ones after `invalid`.
+struct VerificationExpiry {
+    valid: Duration,
+    invalid: Duration,
+}
+
+impl Expiry<String, bool> for VerificationExpiry {
+    fn expire_after_create(
+        &self,
+        _key: &String,
+        valid: &bool,
+        _created_at: Instant,
+    ) -> Option<Duration> {
+        Some(if *valid { self.valid } else { self.invalid })
+    }
+}
+
+impl VerifiedUsers {
+    /// Reads `USER_VERIFY_CACHE_TTL_SECS` (default 60, 0 disables the cache)
+    /// and `USER_VERIFY_NEGATIVE_TTL_SECS` (default 10, 0 doesn't cache
+    /// unknown users).
+    pub fn from_env() -> Result<Self> {
+        let ttl = secs_from_env("USER_VERIFY_CACHE_TTL_SECS", DEFAULT_TTL_SECS)?;
+        let negative_ttl =
+            secs_from_env("USER_VERIFY_NEGATIVE_TTL_SECS", DEFAULT_NEGATIVE_TTL_SECS)?;
+        if ttl.is_zero() {
+            return Ok(Self::disabled());
+        }
+        Ok(Self::new(ttl, negative_ttl))
+    }
+
+    pub fn new(ttl: Duration, negative_ttl: Duration) -> Self {
+        let cache = Cache::builder()
+            .max_capacity(MAX_ENTRIES)
+            .expire_after(VerificationExpiry {
+                valid: ttl,
+                invalid: negative_ttl,
+            })
+            .build();
+        Self { cache: Some(cache) }
+    }
+
+    pub fn disabled() -> Self {
+        Self { cache: None }
+    }
+
+    /// Whether `user_id` is valid, from the cache or else from `verify`.
+    /// Concurrent lookups of one user share a single call, and a call that
+    /// fails isn't cached.
+    pub async fn get_or_verify<F>(&self, user_id: &str, verify: F) -> Result<bool, Status>
+    where
+        F: Future<Output = Result<bool, Status>>,
+    {
+        let Some(cache) = &self.cache else {
+            return verify.await;
+        };
+        cache
+            .try_get_with_by_ref(user_id, verify)
+            .await
+            .map_err(|e: Arc<Status>| Status::clone(&e))
+    }
+}
+
+fn secs_from_env(var: &str, default: u64) -> Result<Duration> {
+    let secs = match env::var(var) {
+        Ok(value) if !value.is_empty() => value
+            .parse()
+            .with_context(|| format!("Invalid {}: {}", var, value))?,
+        _ => default,
+    };
+    Ok(Duration::from_secs(secs))
+}
@@ -0,0 +1,521 @@
+use common::auth::{Caller, ServiceCredentials};
+use common::client::ServiceEndpoint;
+use common::error::{self, AppError};
+use proto::notification::{
+    CreateNotificationRequest, notification_service_client::NotificationServiceClient,
+};
+use proto::warranty::{
+    ClaimResponse, ListClaimsRequest, ListClaimsResponse, ListWarrantiesRequest,
+    ListWarrantiesResponse, RegisterWarrantyRequest, ResolveClaimRequest, SubmitClaimRequest,
+    Warranty, WarrantyClaim, WarrantyResponse, warranty_service_server::WarrantyService,
+};
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+use tonic::{Request, Response, Status};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Upper bound of reminders sent per run, so a backlog is worked off gradually.
+const MAX_REMINDERS_PER_RUN: i64 = 500;
+
+const WARRANTY_COLUMNS: &str = "id, order_id, order_item_id, user_id, product_id, serial_number, \
+     registered_at, starts_at, expires_at";
+
+const CLAIM_COLUMNS: &str =
+    "id, warranty_id, description, status, resolution, resolved_by, created_at, resolved_at";
+
+#[derive(Debug, sqlx::FromRow)]
+struct DbWarranty {
+    id: String,
+    order_id: String,
+    order_item_id: String,
+    user_id: String,
+    product_id: String,
+    serial_number: Option<String>,
+    registered_at: Option<chrono::NaiveDateTime>,
+    starts_at: Option<chrono::NaiveDateTime>,
+    expires_at: Option<chrono::NaiveDateTime>,
+}
+
+impl DbWarranty {
+    fn status(&self) -> &'static str {
+        match self.expires_at {
+            None => "PENDING_DELIVERY",
+            Some(expires_at) if expires_at <= chrono::Utc::now().naive_utc() => "EXPIRED",
+            Some(_) => "ACTIVE",
+        }
+    }
+
+    fn to_proto(&self) -> Warranty {
+        Warranty {
+            warranty_id: self.id.clone(),
+            order_id: self.order_id.clone(),
+            item_id: self.order_item_id.clone(),
+            user_id: self.user_id.clone(),
+            product_id: self.product_id.clone(),
+            serial_number: self.serial_number.clone().unwrap_or_default(),
+            status: self.status().to_string(),
+            registered: self.registered_at.is_some(),
+            starts_at: self.starts_at.map_or(0, |t| t.and_utc().timestamp()),
+            expires_at: self.expires_at.map_or(0, |t| t.and_utc().timestamp()),
+        }
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct DbClaim {
+    id: String,
+    warranty_id: String,
+    description: String,
+    status: String,
+    resolution: Option<String>,
+    resolved_by: Option<String>,
+    created_at: chrono::NaiveDateTime,
+    resolved_at: Option<chrono::NaiveDateTime>,
+}
+
+impl DbClaim {
+    fn to_proto(&self) -> WarrantyClaim {
+        WarrantyClaim {
+            claim_id: self.id.clone(),
+            warranty_id: self.warranty_id.clone(),
+            description: self.description.clone(),
+            status: self.status.clone(),
+            resolution: self.resolution.clone().unwrap_or_default(),
+            resolved_by: self.resolved_by.clone().unwrap_or_default(),
+            created_at: self.created_at.and_utc().timestamp(),
+            resolved_at: self.resolved_at.map_or(0, |t| t.and_utc().timestamp()),
+        }
+    }
+}
+
+/// Starts the warranty of every warranted item of a delivered order. Items
+/// the customer registered before delivery keep their registration.
+pub async fn start_warranties(db: &PgPool, order_id: &str) -> Result<u64, Status> {
+    let items: Vec<(String, String, String, i32)> = sqlx::query_as(
+        "SELECT oi.id, o.user_id, oi.product_id, oi.warranty_months
+         FROM order_items oi
+         JOIN orders o ON o.id = oi.order_id
+         WHERE oi.order_id = $1 AND oi.warranty_months > 0",
+    )
+    .bind(order_id)
+    .fetch_all(db)
+    .await
+    .map_err(AppError::from)?;
+
+    let mut started = 0;
+    for (item_id, user_id, product_id, months) in items {
+        let result = sqlx::query(
+            "INSERT INTO warranties (id, order_id, order_item_id, user_id, product_id, warranty_months, starts_at, expires_at)
+             VALUES ($1, $2, $3, $4, $5, $6, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP + make_interval(months => $6))
+             ON CONFLICT (order_item_id) DO UPDATE
+             SET starts_at = EXCLUDED.starts_at,
+                 expires_at = EXCLUDED.starts_at + make_interval(months => warranties.warranty_months)
+             WHERE warranties.starts_at IS NULL",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(order_id)
+        .bind(&item_id)
+        .bind(&user_id)
+        .bind(&product_id)
+        .bind(months)
+        .execute(db)
+        .await
+        .map_err(AppError::from)?;
+
+        started += result.rows_affected();
+    }
+
+    Ok(started)
+}
+
+async fn notify(
+    endpoint: &ServiceEndpoint,
+    user_id: &str,
+    title: String,
+    body: String,
+) -> Result<(), Status> {
+    let channel = endpoint
+        .primary_channel()
+        .map_err(|e| Status::internal(format!("Invalid notification service URL: {}", e)))?;
+    let mut client =
+        NotificationServiceClient::with_interceptor(channel, ServiceCredentials("order"));
+
+    let response = client
+        .create_notification(CreateNotificationRequest {
+            user_id: user_id.to_string(),
+            category: "warranty".to_string(),
+            title,
+            body,
+            link: "/account/warranties".to_string(),
+        })
+        .await?
+        .into_inner();
+
+    if response.success {
+        Ok(())
+    } else {
+        Err(Status::failed_precondition(response.message))
+    }
+}
+
+pub struct WarrantyServiceImpl {
+    db: PgPool,
+    notification_service: Arc<ServiceEndpoint>,
+}
+
+impl WarrantyServiceImpl {
+    pub fn new(db: PgPool, notification_service: Arc<ServiceEndpoint>) -> Self {
+        Self {
+            db,
+            notification_service,
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl WarrantyService for WarrantyServiceImpl {
+    async fn register_warranty(
+        &self,
+        request: Request<RegisterWarrantyRequest>,
+    ) -> Result<Response<WarrantyResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        let req = request.into_inner();
+
+        if req.order_id.is_empty() || req.item_id.is_empty() || req.user_id.is_empty() {
+            return Err(error::invalid_argument(
+                "MISSING_ORDER_ITEM",
+                "Order ID, item ID and user ID are required",
+            ));
+        }
+        caller.require_owner(&req.user_id)?;
+
+        let item: Option<(String, String, String, i32, chrono::DateTime<chrono::Utc>)> =
+            sqlx::query_as(
+                "SELECT oi.product_id, o.user_id, o.status, oi.warranty_months, o.updated_at
+                 FROM order_items oi
+                 JOIN orders o ON o.id = oi.order_id
+                 WHERE oi.id = $1 AND oi.order_id = $2",
+            )
+            .bind(&req.item_id)
+            .bind(&req.order_id)
+            .fetch_optional(&self.db)
+            .await
+            .map_err(AppError::from)?;
+
+        let (product_id, status, months, updated_at) = match item {
+            Some((product_id, user_id, status, months, updated_at)) if user_id == req.user_id => {
+                (product_id, status, months, updated_at)
+            }
+            _ => {
+                return Err(error::not_found(
+                    "ORDER_ITEM_NOT_FOUND",
+                    "Order item not found",
+                ));
+            }
+        };
+
+        if months <= 0 {
+            return Err(error::failed_precondition(
+                "NO_WARRANTY",
+                "Product does not carry a warranty",
+            ));
+        }
+
+        if status == "CANCELLED" {
+            return Err(error::failed_precondition(
+                "ORDER_CANCELLED",
+                "Cannot register a warranty for a cancelled order",
+            ));
+        }
+
+        // Orders delivered before the warranty row existed start from their last status change
+        let starts_at = (status == "DELIVERED").then_some(updated_at.naive_utc());
+
+        let warranty = sqlx::query_as::<_, DbWarranty>(&format!(
+            "INSERT INTO warranties (id, order_id, order_item_id, user_id, product_id, warranty_months,
+                                     serial_number, registered_at, starts_at, expires_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, CURRENT_TIMESTAMP, $8, $8 + make_interval(months => $6))
+             ON CONFLICT (order_item_id) DO UPDATE
+             SET serial_number = COALESCE(EXCLUDED.serial_number, warranties.serial_number),
+                 registered_at = CURRENT_TIMESTAMP
+             RETURNING {}",
+            WARRANTY_COLUMNS
+        ))
+        .bind(Uuid::new_v4().to_string())
+        .bind(&req.order_id)
+        .bind(&req.item_id)
+        .bind(&req.user_id)
+        .bind(&product_id)
+        .bind(months)
+        .bind(if req.serial_number.is_empty() {
+            None
+        } else {
+            Some(&req.serial_number)
+        })
+        .bind(starts_at)
+        .fetch_one(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        Ok(Response::new(WarrantyResponse {
+            success: true,
+            message: "Warranty registered successfully".to_string(),
+            warranty: Some(warranty.to_proto()),
+        }))
+    }
+
+    async fn list_warranties(
+        &self,
+        request: Request<ListWarrantiesRequest>,
+    ) -> Result<Response<ListWarrantiesResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        let req = request.into_inner();
+        caller.require_owner(&req.user_id)?;
+
+        let warranties = sqlx::query_as::<_, DbWarranty>(&format!(
+            "SELECT {} FROM warranties WHERE user_id = $1 ORDER BY created_at DESC",
+            WARRANTY_COLUMNS
+        ))
+        .bind(&req.user_id)
+        .fetch_all(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        Ok(Response::new(ListWarrantiesResponse {
+            warranties: warranties.iter().map(|w| w.to_proto()).collect(),
+        }))
+    }
+
+    async fn submit_claim(
+        &self,
+        request: Request<SubmitClaimRequest>,
+    ) -> Result<Response<ClaimResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        let req = request.into_inner();
+
+        if req.warranty_id.is_empty() || req.description.is_empty() {
+            return Err(error::invalid_argument(
+                "INVALID_CLAIM",
+                "Warranty ID and description are required",
+            ));
+        }
+        caller.require_owner(&req.user_id)?;
+
+        let warranty = sqlx::query_as::<_, DbWarranty>(&format!(
+            "SELECT {} FROM warranties WHERE id = $1",
+            WARRANTY_COLUMNS
+        ))
+        .bind(&req.warranty_id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        let warranty = match warranty {
+            Some(w) if w.user_id == req.user_id => w,
+            _ => {
+                return Err(error::not_found("WARRANTY_NOT_FOUND", "Warranty not found"));
+            }
+        };
+
+        if warranty.status() != "ACTIVE" {
+            return Err(error::failed_precondition(
+                "WARRANTY_NOT_ACTIVE",
+                format!("Warranty is not active ({})", warranty.status()),
+            ));
+        }
+
+        let claim = sqlx::query_as::<_, DbClaim>(&format!(
+            "INSERT INTO warranty_claims (id, warranty_id, description)
+             VALUES ($1, $2, $3)
+             RETURNING {}",
+            CLAIM_COLUMNS
+        ))
+        .bind(Uuid::new_v4().to_string())
+        .bind(&req.warranty_id)
+        .bind(&req.description)
+        .fetch_one(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        Ok(Response::new(ClaimResponse {
+            success: true,
+            message: "Claim submitted successfully".to_string(),
+            claim: Some(claim.to_proto()),
+        }))
+    }
+
+    async fn resolve_claim(
+        &self,
+        request: Request<ResolveClaimRequest>,
+    ) -> Result<Response<ClaimResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        caller.require_platform()?;
+        let actor = caller.actor();
+        let req = request.into_inner();
+
+        if req.claim_id.is_empty() || req.resolution.is_empty() {
+            return Err(error::invalid_argument(
+                "INVALID_CLAIM_RESOLUTION",
+                "Claim ID and resolution are required",
+            ));
+        }
+
+        let status = if req.approved { "APPROVED" } else { "REJECTED" };
+
+        let claim = sqlx::query_as::<_, DbClaim>(&format!(
+            "UPDATE warranty_claims
+             SET status = $1, resolution = $2, resolved_by = $3, resolved_at = CURRENT_TIMESTAMP
+             WHERE id = $4 AND status = 'OPEN'
+             RETURNING {}",
+            CLAIM_COLUMNS
+        ))
+        .bind(status)
+        .bind(&req.resolution)
+        .bind(&actor)
+        .bind(&req.claim_id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        let claim = match claim {
+            Some(c) => c,
+            None => {
+                return Err(error::not_found("CLAIM_NOT_FOUND", "Open claim not found"));
+            }
+        };
+
+        let user_id: String = sqlx::query_scalar("SELECT user_id FROM warranties WHERE id = $1")
+            .bind(&claim.warranty_id)
+            .fetch_one(&self.db)
+            .await
+            .map_err(AppError::from)?;
+
+        // The resolution is already stored; a failed notification is only logged
+        if let Err(e) = notify(
+            &self.notification_service,
+            &user_id,
+            format!("Your warranty claim was {}", status.to_lowercase()),
+            req.resolution.clone(),
+        )
+        .await
+        {
+            warn!(claim_id = %claim.id, "Failed to notify claim resolution: {}", e);
+        }
+
+        Ok(Response::new(ClaimResponse {
+            success: true,
+            message: format!("Claim {}", status.to_lowercase()),
+            claim: Some(claim.to_proto()),
+        }))
+    }
+
+    async fn list_claims(
+        &self,
+        request: Request<ListClaimsRequest>,
+    ) -> Result<Response<ListClaimsResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        let claims = sqlx::query_as::<_, DbClaim>(&format!(
+            "SELECT {} FROM warranty_claims
+             WHERE ($1 = '' OR warranty_id = $1) AND ($2 = '' OR status = $2)
+             ORDER BY created_at DESC",
+            CLAIM_COLUMNS
+        ))
+        .bind(&req.warranty_id)
+        .bind(req.status.to_ascii_uppercase())
+        .fetch_all(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        Ok(Response::new(ListClaimsResponse {
+            claims: claims.iter().map(|c| c.to_proto()).collect(),
+        }))
+    }
+}
+
+/// Periodically reminds customers of warranties that are about to expire.
+pub struct WarrantyReminders {
+    db: PgPool,
+    notification_service: Arc<ServiceEndpoint>,
+    lead_time_days: i32,
+}
+
+impl WarrantyReminders {
+    pub fn new(
+        db: PgPool,
+        notification_service: Arc<ServiceEndpoint>,
+        lead_time_days: i32,
+    ) -> Self {
+        Self {
+            db,
+            notification_service,
+            lead_time_days,
+        }
+    }
+
+    /// Sends one reminder per warranty expiring within the lead time and
+    /// returns how many were sent.
+    pub async fn run(&self) -> Result<usize, Status> {
+        let due: Vec<(String, String, chrono::NaiveDateTime)> = sqlx::query_as(
+            "SELECT id, user_id, expires_at FROM warranties
+             WHERE reminder_sent_at IS NULL
+               AND expires_at > CURRENT_TIMESTAMP
+               AND expires_at <= CURRENT_TIMESTAMP + make_interval(days => $1)
+             ORDER BY expires_at
+             LIMIT $2",
+        )
+        .bind(self.lead_time_days)
+        .bind(MAX_REMINDERS_PER_RUN)
+        .fetch_all(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        let mut sent = 0;
+        for (warranty_id, user_id, expires_at) in due {
+            let result = notify(
+                &self.notification_service,
+                &user_id,
+                "Your warranty expires soon".to_string(),
+                format!(
+                    "Your product warranty expires on {}.",
+                    expires_at.format("%Y-%m-%d")
+                ),
+            )
+            .await;
+
+            match result {
+                Ok(()) => {
+                    sqlx::query(
+                        "UPDATE warranties SET reminder_sent_at = CURRENT_TIMESTAMP WHERE id = $1",
+                    )
+                    .bind(&warranty_id)
+                    .execute(&self.db)
+                    .await
+                    .map_err(AppError::from)?;
+                    sent += 1;
+                }
+                Err(e) => warn!(warranty_id = %warranty_id, "Warranty reminder failed: {}", e),
+            }
+        }
+
+        Ok(sent)
+    }
+
+    /// Spawns a background task that sends reminders every `interval`.
+    pub fn spawn(self, interval: Duration) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match self.run().await {
+                    Ok(0) => {}
+                    Ok(sent) => info!("Sent {} warranty expiry reminders", sent),
+                    Err(e) => warn!("Warranty reminder run failed: {}", e),
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,549 @@
+//! Outbound webhooks. Order events are fanned out from `order_events` to the
+//! registered endpoints as deliveries, which are POSTed, signed, and retried
+//! with backoff until they succeed or run out of attempts (dead letters).
+
+use common::auth::Caller;
+use common::error::{self, AppError};
+use common::order_events::EventType;
+use hmac::{Hmac, Mac};
+use proto::webhook::{
+    DeadLetter, DeleteWebhookRequest, DeleteWebhookResponse, ListDeadLettersRequest,
+    ListDeadLettersResponse, ListWebhooksRequest, ListWebhooksResponse, RedeliverWebhookRequest,
+    RedeliverWebhookResponse, RegisterWebhookRequest, RegisterWebhookResponse, Webhook,
+    webhook_service_server::WebhookService,
+};
+use sha2::Sha256;
+use sqlx::PgPool;
+use std::time::Duration;
+use tonic::{Request, Response, Status};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Event types an endpoint gets when it doesn't name any.
+const DEFAULT_EVENT_TYPES: [EventType; 3] = [
+    EventType::Created,
+    EventType::StatusChanged,
+    EventType::Cancelled,
+];
+
+const EVENT_TYPES: [EventType; 9] = [
+    EventType::Created,
+    EventType::StatusChanged,
+    EventType::Cancelled,
+    EventType::Refunded,
+    EventType::AddressChanged,
+    EventType::ItemsCancelled,
+    EventType::ItemsShipped,
+    EventType::Claimed,
+    EventType::DeliveryEstimateChanged,
+];
+
+/// Events fanned out, and deliveries attempted, per sweep.
+const BATCH_SIZE: i64 = 50;
+
+/// How long a claimed delivery is kept from other sweeps while it's sent.
+const CLAIM_SECS: f64 = 120.0;
+
+/// Retry delay after the first failed attempt; it doubles with every
+/// further attempt, up to `MAX_BACKOFF_SECS`.
+const BASE_BACKOFF_SECS: u64 = 30;
+const MAX_BACKOFF_SECS: u64 = 6 * 3600;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, sqlx::FromRow)]
+struct DbWebhook {
+    id: String,
+    url: String,
+    event_types: Vec<String>,
+    active: bool,
+    created_at: chrono::NaiveDateTime,
+}
+
+impl DbWebhook {
+    fn to_proto(&self) -> Webhook {
+        Webhook {
+            webhook_id: self.id.clone(),
+            url: self.url.clone(),
+            event_types: self.event_types.clone(),
+            active: self.active,
+            created_at: self.created_at.and_utc().timestamp(),
+        }
+    }
+}
+
+/// A claimed delivery with its endpoint and event.
+#[derive(Debug, sqlx::FromRow)]
+struct DbDelivery {
+    id: String,
+    attempts: i32,
+    url: String,
+    secret: String,
+    event_id: i64,
+    order_id: String,
+    event_type: String,
+    from_status: Option<String>,
+    to_status: Option<String>,
+    actor: String,
+    reason: Option<String>,
+    details: Option<String>,
+    created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct DbDeadLetter {
+    id: String,
+    endpoint_id: String,
+    event_id: i64,
+    order_id: String,
+    event_type: String,
+    attempts: i32,
+    last_error: Option<String>,
+    updated_at: chrono::NaiveDateTime,
+}
+
+impl DbDeadLetter {
+    fn to_proto(&self) -> DeadLetter {
+        DeadLetter {
+            delivery_id: self.id.clone(),
+            webhook_id: self.endpoint_id.clone(),
+            event_id: self.event_id,
+            order_id: self.order_id.clone(),
+            event_type: self.event_type.clone(),
+            attempts: self.attempts,
+            last_error: self.last_error.clone().unwrap_or_default(),
+            failed_at: self.updated_at.and_utc().timestamp(),
+        }
+    }
+}
+
+/// `X-Webhook-Signature` of a body: `t=<timestamp>,v1=<hex HMAC-SHA256 of
+/// "<timestamp>.<body>">`, the scheme Stripe uses, so receivers can reuse
+/// their verification code.
+fn signature(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!(
+        "t={},v1={}",
+        timestamp,
+        hex::encode(mac.finalize().into_bytes())
+    )
+}
+
+/// Delay before retrying a delivery that has failed `attempts` times.
+fn backoff(attempts: i32) -> Duration {
+    let doublings = u32::try_from(attempts.saturating_sub(1))
+        .unwrap_or(0)
+        .min(20);
+    Duration::from_secs((BASE_BACKOFF_SECS << doublings).min(MAX_BACKOFF_SECS))
+}
+
+/// Background worker fanning out order events and sending deliveries.
+pub struct WebhookDispatcher {
+    db: PgPool,
+    client: reqwest::Client,
+    max_attempts: i32,
+}
+
+impl WebhookDispatcher {
+    pub fn new(db: PgPool, max_attempts: i32) -> Self {
+        Self {
+            db,
+            client: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .expect("Failed to build webhook HTTP client"),
+            max_attempts: max_attempts.max(1),
+        }
+    }
+
+    /// Queues a delivery of each new order event to every active endpoint
+    /// subscribed to its type; returns how many events were fanned out.
+    async fn fan_out(&self) -> Result<usize, Status> {
+        let mut tx = self.db.begin().await.map_err(AppError::from)?;
+
+        // Concurrent sweeps skip each other's events
+        let events: Vec<(i64, String)> = sqlx::query_as(
+            "SELECT id, event_type FROM order_events
+             WHERE NOT webhooks_dispatched
+             ORDER BY id
+             LIMIT $1
+             FOR UPDATE SKIP LOCKED",
+        )
+        .bind(BATCH_SIZE)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+        if events.is_empty() {
+            return Ok(0);
+        }
+
+        let endpoints: Vec<(String, Vec<String>)> =
+            sqlx::query_as("SELECT id, event_types FROM webhook_endpoints WHERE active")
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(AppError::from)?;
+
+        for (event_id, event_type) in &events {
+            for (endpoint_id, event_types) in &endpoints {
+                if !event_types.contains(event_type) {
+                    continue;
+                }
+                sqlx::query(
+                    "INSERT INTO webhook_deliveries (id, endpoint_id, event_id)
+                     VALUES ($1, $2, $3)
+                     ON CONFLICT (endpoint_id, event_id) DO NOTHING",
+                )
+                .bind(Uuid::new_v4().to_string())
+                .bind(endpoint_id)
+                .bind(event_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(AppError::from)?;
+            }
+        }
+
+        let ids: Vec<i64> = events.iter().map(|(id, _)| *id).collect();
+        sqlx::query("UPDATE order_events SET webhooks_dispatched = TRUE WHERE id = ANY($1)")
+            .bind(&ids)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::from)?;
+
+        tx.commit().await.map_err(AppError::from)?;
+
+        Ok(events.len())
+    }
+
+    /// Sends the deliveries that are due; returns how many succeeded.
+    async fn deliver_due(&self) -> Result<usize, Status> {
+        // Claiming pushes the next attempt out, so a sweep that dies mid-send
+        // leaves its deliveries to be retried rather than stuck
+        let deliveries = sqlx::query_as::<_, DbDelivery>(
+            "WITH claimed AS (
+                 UPDATE webhook_deliveries
+                 SET next_attempt_at = CURRENT_TIMESTAMP + make_interval(secs => $1),
+                     updated_at = CURRENT_TIMESTAMP
+                 WHERE id IN (
+                     SELECT d.id FROM webhook_deliveries d
+                     JOIN webhook_endpoints w ON w.id = d.endpoint_id
+                     WHERE d.status = 'PENDING' AND w.active
+                       AND d.next_attempt_at <= CURRENT_TIMESTAMP
+                     ORDER BY d.next_attempt_at
+                     LIMIT $2
+                     FOR UPDATE OF d SKIP LOCKED
+                 )
+                 RETURNING id, attempts, endpoint_id, event_id
+             )
+             SELECT c.id, c.attempts, w.url, w.secret, e.id AS event_id, e.order_id,
+                    e.event_type, e.from_status, e.to_status, e.actor, e.reason, e.details,
+                    e.created_at
+             FROM claimed c
+             JOIN webhook_endpoints w ON w.id = c.endpoint_id
+             JOIN order_events e ON e.id = c.event_id
+             ORDER BY e.id",
+        )
+        .bind(CLAIM_SECS)
+        .bind(BATCH_SIZE)
+        .fetch_all(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        let mut delivered = 0;
+        for delivery in deliveries {
+            match self.send(&delivery).await {
+                Ok(()) => {
+                    sqlx::query(
+                        "UPDATE webhook_deliveries
+                         SET status = 'DELIVERED', attempts = attempts + 1, last_error = NULL,
+                             updated_at = CURRENT_TIMESTAMP
+                         WHERE id = $1",
+                    )
+                    .bind(&delivery.id)
+                    .execute(&self.db)
+                    .await
+                    .map_err(AppError::from)?;
+                    delivered += 1;
+                }
+                Err(error) => {
+                    let attempts = delivery.attempts + 1;
+                    let dead = attempts >= self.max_attempts;
+                    if dead {
+                        warn!(
+                            delivery_id = %delivery.id,
+                            url = %delivery.url,
+                            "Webhook delivery failed for good after {} attempts: {}",
+                            attempts,
+                            error
+                        );
+                    }
+                    sqlx::query(
+                        "UPDATE webhook_deliveries
+                         SET status = $2, attempts = $3, last_error = $4,
+                             next_attempt_at = CURRENT_TIMESTAMP + make_interval(secs => $5),
+                             updated_at = CURRENT_TIMESTAMP
+                         WHERE id = $1",
+                    )
+                    .bind(&delivery.id)
+                    .bind(if dead { "DEAD" } else { "PENDING" })
+                    .bind(attempts)
+                    .bind(&error)
+                    .bind(backoff(attempts).as_secs_f64())
+                    .execute(&self.db)
+                    .await
+                    .map_err(AppError::from)?;
+                }
+            }
+        }
+
+        Ok(delivered)
+    }
+
+    /// POSTs a delivery; `Err` describes why it failed.
+    async fn send(&self, delivery: &DbDelivery) -> Result<(), String> {
+        let body = serde_json::json!({
+            "id": delivery.event_id,
+            "type": format!("order.{}", delivery.event_type.to_lowercase()),
+            "created_at": delivery.created_at.and_utc().timestamp(),
+            "data": {
+                "order_id": delivery.order_id,
+                "from_status": delivery.from_status,
+                "to_status": delivery.to_status,
+                "actor": delivery.actor,
+                "reason": delivery.reason,
+                "details": delivery.details,
+            },
+        })
+        .to_string();
+        let timestamp = chrono::Utc::now().timestamp();
+
+        // The delivery id stays the same across retries, so receivers can
+        // drop duplicates
+        let response = self
+            .client
+            .post(&delivery.url)
+            .header("Content-Type", "application/json")
+            .header("X-Webhook-Id", &delivery.id)
+            .header(
+                "X-Webhook-Signature",
+                signature(&delivery.secret, timestamp, body.as_bytes()),
+            )
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| format!("Request failed: {}", e))?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("Endpoint answered {}", response.status()))
+        }
+    }
+
+    /// Fans out new events and sends what's due.
+    pub async fn run(&self) -> Result<(usize, usize), Status> {
+        let fanned_out = self.fan_out().await?;
+        let delivered = self.deliver_due().await?;
+        Ok((fanned_out, delivered))
+    }
+
+    /// Spawns a background task that sweeps every `interval`.
+    pub fn spawn(self, interval: Duration) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match self.run().await {
+                    Ok((_, 0)) => {}
+                    Ok((_, delivered)) => info!("Delivered {} webhooks", delivered),
+                    Err(e) => warn!("Webhook sweep failed: {}", e),
+                }
+            }
+        });
+    }
+}
+
+/// Admin RPCs over webhook endpoints and their dead letters.
+pub struct WebhookServiceImpl {
+    db: PgPool,
+}
+
+impl WebhookServiceImpl {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+}
+
+#[tonic::async_trait]
+impl WebhookService for WebhookServiceImpl {
+    async fn register_webhook(
+        &self,
+        request: Request<RegisterWebhookRequest>,
+    ) -> Result<Response<RegisterWebhookResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        // Field rules are checked by the validation layer (see webhook.proto)
+        let mut event_types: Vec<String> = Vec::new();
+        for event_type in &req.event_types {
+            let event_type = event_type.trim().to_ascii_uppercase();
+            if !EVENT_TYPES.iter().any(|t| t.as_str() == event_type) {
+                return Err(error::invalid_argument(
+                    "UNKNOWN_EVENT_TYPE",
+                    format!("Unknown event type: {}", event_type),
+                ));
+            }
+            if !event_types.contains(&event_type) {
+                event_types.push(event_type);
+            }
+        }
+        if event_types.is_empty() {
+            event_types = DEFAULT_EVENT_TYPES
+                .iter()
+                .map(|t| t.as_str().to_string())
+                .collect();
+        }
+
+        let secret = if req.secret.is_empty() {
+            format!("whsec_{}", Uuid::new_v4().simple())
+        } else {
+            req.secret.clone()
+        };
+
+        let webhook = sqlx::query_as::<_, DbWebhook>(
+            "INSERT INTO webhook_endpoints (id, url, secret, event_types)
+             VALUES ($1, $2, $3, $4)
+             RETURNING id, url, event_types, active, created_at",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(&req.url)
+        .bind(&secret)
+        .bind(&event_types)
+        .fetch_one(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        Ok(Response::new(RegisterWebhookResponse {
+            success: true,
+            message: "Webhook registered".to_string(),
+            webhook: Some(webhook.to_proto()),
+            secret,
+        }))
+    }
+
+    async fn list_webhooks(
+        &self,
+        request: Request<ListWebhooksRequest>,
+    ) -> Result<Response<ListWebhooksResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+
+        let webhooks = sqlx::query_as::<_, DbWebhook>(
+            "SELECT id, url, event_types, active, created_at FROM webhook_endpoints
+             ORDER BY created_at, id",
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        Ok(Response::new(ListWebhooksResponse {
+            success: true,
+            message: format!("Found {} webhooks", webhooks.len()),
+            webhooks: webhooks.iter().map(DbWebhook::to_proto).collect(),
+        }))
+    }
+
+    async fn delete_webhook(
+        &self,
+        request: Request<DeleteWebhookRequest>,
+    ) -> Result<Response<DeleteWebhookResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        // Deactivated rather than removed, so its dead letters stay listed
+        let result = sqlx::query(
+            "UPDATE webhook_endpoints SET active = FALSE, updated_at = CURRENT_TIMESTAMP
+             WHERE id = $1 AND active",
+        )
+        .bind(&req.webhook_id)
+        .execute(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        if result.rows_affected() == 0 {
+            return Err(error::not_found("WEBHOOK_NOT_FOUND", "Webhook not found"));
+        }
+
+        Ok(Response::new(DeleteWebhookResponse {
+            success: true,
+            message: "Webhook deleted".to_string(),
+        }))
+    }
+
+    async fn list_dead_letters(
+        &self,
+        request: Request<ListDeadLettersRequest>,
+    ) -> Result<Response<ListDeadLettersResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        let limit = if req.limit <= 0 {
+            100
+        } else {
+            req.limit.min(500)
+        };
+        let dead_letters = sqlx::query_as::<_, DbDeadLetter>(
+            "SELECT d.id, d.endpoint_id, d.event_id, e.order_id, e.event_type, d.attempts,
+                    d.last_error, d.updated_at
+             FROM webhook_deliveries d
+             JOIN order_events e ON e.id = d.event_id
+             WHERE d.status = 'DEAD' AND ($1 = '' OR d.endpoint_id = $1)
+             ORDER BY d.updated_at DESC, d.id
+             LIMIT $2",
+        )
+        .bind(&req.webhook_id)
+        .bind(i64::from(limit))
+        .fetch_all(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        Ok(Response::new(ListDeadLettersResponse {
+            success: true,
+            message: format!("Found {} dead letters", dead_letters.len()),
+            dead_letters: dead_letters.iter().map(DbDeadLetter::to_proto).collect(),
+        }))
+    }
+
+    async fn redeliver_webhook(
+        &self,
+        request: Request<RedeliverWebhookRequest>,
+    ) -> Result<Response<RedeliverWebhookResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        let result = sqlx::query(
+            "UPDATE webhook_deliveries
+             SET status = 'PENDING', attempts = 0, last_error = NULL,
+                 next_attempt_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP
+             WHERE id = $1 AND status = 'DEAD'",
+        )
+        .bind(&req.delivery_id)
+        .execute(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        if result.rows_affected() == 0 {
+            return Err(error::not_found(
+                "DEAD_LETTER_NOT_FOUND",
+                "Dead letter not found",
+            ));
+        }
+
+        Ok(Response::new(RedeliverWebhookResponse {
+            success: true,
+            message: "Delivery queued again".to_string(),
+        }))
+    }
+}
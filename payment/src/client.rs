@@ -0,0 +1,86 @@
+use common::auth::ServiceCredentials;
+use common::sandbox::CARD_APPROVED;
+use proto::payment::{
+    CapturePaymentRequest, CreatePaymentIntentRequest, GetPaymentRequest, RefundPaymentRequest,
+    payment_service_client::PaymentServiceClient,
+};
+use tonic::transport::Channel;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Captures and refunds are the platform's, so calls are made as it
+    let channel = Channel::from_static("http://127.0.0.1:50055")
+        .connect()
+        .await?;
+    let mut client =
+        PaymentServiceClient::with_interceptor(channel, ServiceCredentials("payment-client"));
+
+    println!("Connected to Payment Service");
+    println!("============================\n");
+
+    // Note: payments reference an existing pending order
+    let order_id = "test-order-id".to_string();
+    println!("Order ID: {}\n", order_id);
+
+    // Test 1: Create a payment intent
+    println!("1. Testing Create Payment Intent");
+    let intent_response = client
+        .create_payment_intent(CreatePaymentIntentRequest {
+            order_id: order_id.clone(),
+        })
+        .await?;
+    let intent_result = intent_response.into_inner();
+    println!("Create Payment Intent Response:");
+    println!("  Success: {}", intent_result.success);
+    println!("  Message: {}", intent_result.message);
+
+    let Some(payment) = intent_result.payment else {
+        return Ok(());
+    };
+    println!("  Payment ID: {}", payment.payment_id);
+    println!("  Amount: {} {}\n", payment.amount, payment.currency);
+
+    // Test 2: Capture it with the sandbox card that is always approved
+    println!("2. Testing Capture Payment");
+    let capture_response = client
+        .capture_payment(CapturePaymentRequest {
+            payment_id: payment.payment_id.clone(),
+            payment_method: CARD_APPROVED.to_string(),
+        })
+        .await?;
+    let capture_result = capture_response.into_inner();
+    println!("Capture Payment Response:");
+    println!("  Success: {}", capture_result.success);
+    println!("  Message: {}\n", capture_result.message);
+
+    // Test 3: Refund part of it
+    println!("3. Testing Refund Payment");
+    let refund_response = client
+        .refund_payment(RefundPaymentRequest {
+            payment_id: payment.payment_id.clone(),
+            amount: "1.00".to_string(),
+            reason: "Damaged packaging".to_string(),
+        })
+        .await?;
+    let refund_result = refund_response.into_inner();
+    println!("Refund Payment Response:");
+    println!("  Success: {}", refund_result.success);
+    println!("  Message: {}", refund_result.message);
+    println!("  Refund ID: {}\n", refund_result.refund_id);
+
+    // Test 4: Get the payment
+    println!("4. Testing Get Payment");
+    let get_response = client
+        .get_payment(GetPaymentRequest {
+            payment_id: payment.payment_id,
+            order_id: String::new(),
+        })
+        .await?;
+    let get_result = get_response.into_inner();
+    if let Some(payment) = get_result.payment {
+        println!("  Status: {:?}", payment.status());
+        println!("  Refunded: {}", payment.refunded_amount);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,82 @@
+mod payment;
+mod provider;
+mod stripe;
+mod webhook;
+
+use anyhow::Result;
+use common::idempotency;
+use common::metrics::{self, Metrics};
+use common::telemetry;
+use common::slo::{SloConfig, SloServiceImpl, SloTracker};
+use common::server::ServerBuilder;
+use payment::PaymentServiceImpl;
+use proto::payment::payment_service_server::PaymentServiceServer;
+use proto::slo::slo_service_server::SloServiceServer;
+use sqlx::postgres::PgPoolOptions;
+use std::env;
+use tracing::{error, info, warn};
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenvy::dotenv().ok();
+
+    telemetry::init("payment", env!("CARGO_PKG_VERSION"))?;
+
+    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+
+    // Create database connection pool
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&database_url)
+        .await?;
+
+    info!("Connected to database");
+
+    let addr = "0.0.0.0:50055".parse()?;
+    if common::sandbox::enabled() {
+        warn!("Sandbox mode: payments are charged by the sandbox provider");
+    }
+    let provider = provider::from_env()?;
+    info!("Payment provider: {}", provider.name());
+
+    // Stripe settles payments needing customer action through its webhook
+    if provider.name() == "stripe" {
+        let secret = env::var("STRIPE_WEBHOOK_SECRET").map_err(|_| {
+            anyhow::anyhow!("STRIPE_WEBHOOK_SECRET must be set for the stripe provider")
+        })?;
+        let webhook_addr = env::var("PAYMENT_WEBHOOK_ADDR")
+            .unwrap_or_else(|_| "0.0.0.0:8085".to_string())
+            .parse()?;
+        let webhook_db = pool.clone();
+        tokio::spawn(async move {
+            if let Err(e) = webhook::serve(webhook_addr, webhook_db, secret).await {
+                error!("Stripe webhook listener failed: {}", e);
+            }
+        });
+    }
+
+    // The order service resends a refund it got no answer for under the same
+    // key, possibly to another instance
+    let idempotency_store = idempotency::store_from_env(&pool).await?;
+
+    let payment_service = PaymentServiceImpl::new(pool, provider);
+    let slo_tracker = SloTracker::new("payment", SloConfig::from_env()?);
+    let metrics = Metrics::new("payment");
+    let metrics_addr = env::var("METRICS_ADDR")
+        .unwrap_or_else(|_| "0.0.0.0:9105".to_string())
+        .parse()?;
+    metrics::spawn(metrics_addr, metrics.clone());
+
+    info!("Payment service listening on {}", addr);
+
+    ServerBuilder::new(metrics, slo_tracker.clone())
+        .with_idempotency_store(idempotency_store)
+        .build()?
+        .add_service(PaymentServiceServer::new(payment_service))
+        .add_service(SloServiceServer::new(SloServiceImpl::new(slo_tracker)))
+        .serve(addr)
+        .await?;
+
+    telemetry::shutdown();
+    Ok(())
+}
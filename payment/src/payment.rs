@@ -0,0 +1,566 @@
+use crate::provider::{Charge, PaymentProvider};
+use common::auth::Caller;
+use common::error::AppError;
+use common::order_events::{self, EventType, OrderEvent};
+use common::switches::{self, payment_provider_switch};
+use proto::payment::{
+    CapturePaymentRequest, CapturePaymentResponse, CreatePaymentIntentRequest,
+    CreatePaymentIntentResponse, GetPaymentRequest, GetPaymentResponse, Payment, PaymentStatus,
+    RefundPaymentRequest, RefundPaymentResponse, payment_service_server::PaymentService,
+};
+use sqlx::PgPool;
+use sqlx::types::Decimal;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+const PAYMENT_COLUMNS: &str = "id, order_id, attempt, amount, currency, status, provider, provider_reference, refunded_amount, failure_reason, created_at, updated_at";
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct DbPayment {
+    id: String,
+    order_id: String,
+    attempt: i32,
+    amount: Decimal,
+    currency: String,
+    status: String,
+    provider: String,
+    provider_reference: Option<String>,
+    refunded_amount: Decimal,
+    failure_reason: Option<String>,
+    created_at: chrono::NaiveDateTime,
+    updated_at: chrono::NaiveDateTime,
+}
+
+impl DbPayment {
+    fn to_proto(&self) -> Payment {
+        Payment {
+            payment_id: self.id.clone(),
+            order_id: self.order_id.clone(),
+            amount: self.amount.to_string(),
+            currency: self.currency.clone(),
+            status: status_from_string(&self.status) as i32,
+            provider: self.provider.clone(),
+            provider_reference: self.provider_reference.clone().unwrap_or_default(),
+            refunded_amount: self.refunded_amount.to_string(),
+            failure_reason: self.failure_reason.clone().unwrap_or_default(),
+            attempt: self.attempt,
+            created_at: self.created_at.and_utc().timestamp(),
+            updated_at: self.updated_at.and_utc().timestamp(),
+        }
+    }
+}
+
+fn status_from_string(status: &str) -> PaymentStatus {
+    match status {
+        "PROCESSING" => PaymentStatus::Processing,
+        "CAPTURED" => PaymentStatus::Captured,
+        "FAILED" => PaymentStatus::Failed,
+        "PARTIALLY_REFUNDED" => PaymentStatus::PartiallyRefunded,
+        "REFUNDED" => PaymentStatus::Refunded,
+        _ => PaymentStatus::RequiresCapture,
+    }
+}
+
+pub struct PaymentServiceImpl {
+    db: PgPool,
+    provider: Arc<dyn PaymentProvider>,
+}
+
+impl PaymentServiceImpl {
+    pub fn new(db: PgPool, provider: Arc<dyn PaymentProvider>) -> Self {
+        Self { db, provider }
+    }
+
+    async fn fetch_payment(&self, payment_id: &str) -> Result<Option<DbPayment>, Status> {
+        sqlx::query_as::<_, DbPayment>(&format!(
+            "SELECT {} FROM payments WHERE id = $1",
+            PAYMENT_COLUMNS
+        ))
+        .bind(payment_id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| AppError::from(e).into())
+    }
+
+    /// The payment of an order that hasn't failed, if any.
+    async fn live_payment(&self, order_id: &str) -> Result<Option<DbPayment>, Status> {
+        sqlx::query_as::<_, DbPayment>(&format!(
+            "SELECT {} FROM payments WHERE order_id = $1 AND status <> 'FAILED'",
+            PAYMENT_COLUMNS
+        ))
+        .bind(order_id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| AppError::from(e).into())
+    }
+
+    /// The customer an order belongs to, if the order exists.
+    async fn order_owner(&self, order_id: &str) -> Result<Option<String>, Status> {
+        sqlx::query_scalar("SELECT user_id FROM orders WHERE id = $1")
+            .bind(order_id)
+            .fetch_optional(&self.db)
+            .await
+            .map_err(|e| AppError::from(e).into())
+    }
+
+    /// Whether operators switched off the payment's provider.
+    async fn provider_disabled(&self, provider: &str) -> Result<bool, Status> {
+        switches::is_switch_active(&self.db, &payment_provider_switch(provider)).await
+    }
+}
+
+/// Records the charge of a processing payment and confirms its pending order,
+/// together. `None` when the payment isn't processing, e.g. when a webhook
+/// repeats what the capture call already recorded.
+pub async fn record_capture(
+    db: &PgPool,
+    payment_id: &str,
+    reference: &str,
+) -> Result<Option<DbPayment>, Status> {
+    let mut tx = db.begin().await.map_err(AppError::from)?;
+
+    let payment = sqlx::query_as::<_, DbPayment>(&format!(
+        "UPDATE payments
+         SET status = 'CAPTURED', provider_reference = $1, updated_at = CURRENT_TIMESTAMP
+         WHERE id = $2 AND status = 'PROCESSING'
+         RETURNING {}",
+        PAYMENT_COLUMNS
+    ))
+    .bind(reference)
+    .bind(payment_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(AppError::from)?;
+    let Some(payment) = payment else {
+        return Ok(None);
+    };
+
+    let confirmed = sqlx::query(
+        "UPDATE orders SET status = 'CONFIRMED', updated_at = CURRENT_TIMESTAMP
+         WHERE id = $1 AND status = 'PENDING'",
+    )
+    .bind(&payment.order_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(AppError::from)?
+    .rows_affected()
+        == 1;
+    if confirmed {
+        let actor = order_events::system_actor("payment");
+        order_events::record(
+            &mut *tx,
+            &OrderEvent::new(&payment.order_id, EventType::StatusChanged, &actor)
+                .status(Some("PENDING"), "CONFIRMED")
+                .reason("Payment captured"),
+        )
+        .await?;
+    }
+
+    tx.commit().await.map_err(AppError::from)?;
+
+    info!(payment_id = %payment.id, order_id = %payment.order_id, "Payment captured");
+    Ok(Some(payment))
+}
+
+/// Records that the provider declined a processing payment; `None` when the
+/// payment isn't processing.
+pub async fn record_decline(
+    db: &PgPool,
+    payment_id: &str,
+    reason: &str,
+) -> Result<Option<DbPayment>, Status> {
+    sqlx::query_as::<_, DbPayment>(&format!(
+        "UPDATE payments
+         SET status = 'FAILED', failure_reason = $1, updated_at = CURRENT_TIMESTAMP
+         WHERE id = $2 AND status = 'PROCESSING'
+         RETURNING {}",
+        PAYMENT_COLUMNS
+    ))
+    .bind(reason)
+    .bind(payment_id)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| AppError::from(e).into())
+}
+
+#[tonic::async_trait]
+impl PaymentService for PaymentServiceImpl {
+    async fn create_payment_intent(
+        &self,
+        request: Request<CreatePaymentIntentRequest>,
+    ) -> Result<Response<CreatePaymentIntentResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        let req = request.into_inner();
+
+        // Field rules are checked by the validation layer (see payment.proto)
+        let fail = |message: &str| {
+            Response::new(CreatePaymentIntentResponse {
+                success: false,
+                message: message.to_string(),
+                payment: None,
+            })
+        };
+
+        // Orders are charged in the currency they were placed in
+        let order: Option<(String, Decimal, String, String)> = sqlx::query_as(
+            "SELECT user_id, total_amount, status, currency FROM orders WHERE id = $1",
+        )
+        .bind(&req.order_id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(AppError::from)?;
+        let Some((user_id, total_amount, order_status, currency)) = order else {
+            return Ok(fail("Order not found"));
+        };
+        caller.require_owner(&user_id)?;
+
+        if let Some(payment) = self.live_payment(&req.order_id).await? {
+            return Ok(Response::new(CreatePaymentIntentResponse {
+                success: true,
+                message: "Order already has a payment".to_string(),
+                payment: Some(payment.to_proto()),
+            }));
+        }
+        if order_status != "PENDING" {
+            return Ok(fail("Only pending orders can be paid"));
+        }
+
+        // Both unique constraints on payments turn a racing call into a conflict
+        let payment = sqlx::query_as::<_, DbPayment>(&format!(
+            "INSERT INTO payments (id, order_id, attempt, amount, currency, status, provider)
+             SELECT $1, $2, COALESCE(MAX(attempt), 0) + 1, $3, $4, 'REQUIRES_CAPTURE', $5
+             FROM payments WHERE order_id = $2
+             ON CONFLICT DO NOTHING
+             RETURNING {}",
+            PAYMENT_COLUMNS
+        ))
+        .bind(Uuid::new_v4().to_string())
+        .bind(&req.order_id)
+        .bind(total_amount)
+        .bind(&currency)
+        .bind(self.provider.name())
+        .fetch_optional(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        let (payment, message) = match payment {
+            Some(payment) => (payment, "Payment intent created"),
+            None => match self.live_payment(&req.order_id).await? {
+                Some(payment) => (payment, "Order already has a payment"),
+                None => return Err(Status::aborted("Concurrent payment attempt, please retry")),
+            },
+        };
+
+        Ok(Response::new(CreatePaymentIntentResponse {
+            success: true,
+            message: message.to_string(),
+            payment: Some(payment.to_proto()),
+        }))
+    }
+
+    async fn capture_payment(
+        &self,
+        request: Request<CapturePaymentRequest>,
+    ) -> Result<Response<CapturePaymentResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        // Field rules are checked by the validation layer (see payment.proto)
+        let fail = |message: String, payment: Option<&DbPayment>| {
+            Response::new(CapturePaymentResponse {
+                success: false,
+                message,
+                payment: payment.map(DbPayment::to_proto),
+            })
+        };
+
+        let Some(payment) = self.fetch_payment(&req.payment_id).await? else {
+            return Ok(fail("Payment not found".to_string(), None));
+        };
+        if self.provider_disabled(&payment.provider).await? {
+            return Ok(fail(
+                format!(
+                    "Payment provider {} is temporarily disabled",
+                    payment.provider
+                ),
+                Some(&payment),
+            ));
+        }
+
+        // Claim the payment, so concurrent captures can't both charge it
+        let claimed = sqlx::query(
+            "UPDATE payments SET status = 'PROCESSING', updated_at = CURRENT_TIMESTAMP
+             WHERE id = $1 AND status = 'REQUIRES_CAPTURE'",
+        )
+        .bind(&payment.id)
+        .execute(&self.db)
+        .await
+        .map_err(AppError::from)?
+        .rows_affected()
+            == 1;
+        if !claimed {
+            return Ok(match payment.status.as_str() {
+                "PROCESSING" => fail(
+                    "Payment capture is already in progress".to_string(),
+                    Some(&payment),
+                ),
+                "FAILED" => fail(
+                    "Payment failed; create a new payment intent".to_string(),
+                    Some(&payment),
+                ),
+                "REQUIRES_CAPTURE" => fail(
+                    "Payment changed concurrently, please retry".to_string(),
+                    Some(&payment),
+                ),
+                _ => Response::new(CapturePaymentResponse {
+                    success: true,
+                    message: "Payment already captured".to_string(),
+                    payment: Some(payment.to_proto()),
+                }),
+            });
+        }
+
+        let charge = self
+            .provider
+            .capture(
+                &payment.id,
+                payment.amount,
+                &payment.currency,
+                &req.payment_method,
+            )
+            .await;
+
+        match charge {
+            Ok(Charge::Captured(reference)) => {
+                let payment = record_capture(&self.db, &payment.id, &reference).await?;
+
+                Ok(match payment {
+                    Some(payment) => Response::new(CapturePaymentResponse {
+                        success: true,
+                        message: "Payment captured".to_string(),
+                        payment: Some(payment.to_proto()),
+                    }),
+                    None => fail("Payment changed concurrently".to_string(), None),
+                })
+            }
+            Ok(Charge::Pending(reference)) => {
+                // The provider's webhook settles the payment
+                let payment = sqlx::query_as::<_, DbPayment>(&format!(
+                    "UPDATE payments SET provider_reference = $1, updated_at = CURRENT_TIMESTAMP
+                     WHERE id = $2
+                     RETURNING {}",
+                    PAYMENT_COLUMNS
+                ))
+                .bind(&reference)
+                .bind(&payment.id)
+                .fetch_one(&self.db)
+                .await
+                .map_err(AppError::from)?;
+
+                Ok(Response::new(CapturePaymentResponse {
+                    success: true,
+                    message: "Payment is processing".to_string(),
+                    payment: Some(payment.to_proto()),
+                }))
+            }
+            Ok(Charge::Declined(reason)) => {
+                let payment = record_decline(&self.db, &payment.id, &reason).await?;
+
+                Ok(fail(
+                    format!("Payment declined: {}", reason),
+                    payment.as_ref(),
+                ))
+            }
+            Err(e) => {
+                // The outcome is unknown; a retry reuses the payment id as
+                // idempotency key, so it can't charge twice
+                warn!(payment_id = %payment.id, "Payment capture failed: {}", e);
+                sqlx::query(
+                    "UPDATE payments SET status = 'REQUIRES_CAPTURE', updated_at = CURRENT_TIMESTAMP
+                     WHERE id = $1 AND status = 'PROCESSING'",
+                )
+                .bind(&payment.id)
+                .execute(&self.db)
+                .await
+                .map_err(AppError::from)?;
+
+                Err(e)
+            }
+        }
+    }
+
+    async fn refund_payment(
+        &self,
+        request: Request<RefundPaymentRequest>,
+    ) -> Result<Response<RefundPaymentResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        // Field rules are checked by the validation layer (see payment.proto)
+        let fail = |message: String, payment: Option<&DbPayment>| {
+            Response::new(RefundPaymentResponse {
+                success: false,
+                message,
+                payment: payment.map(DbPayment::to_proto),
+                refund_id: String::new(),
+            })
+        };
+
+        let Some(payment) = self.fetch_payment(&req.payment_id).await? else {
+            return Ok(fail("Payment not found".to_string(), None));
+        };
+        if !matches!(payment.status.as_str(), "CAPTURED" | "PARTIALLY_REFUNDED") {
+            return Ok(fail(
+                "Only captured payments can be refunded".to_string(),
+                Some(&payment),
+            ));
+        }
+        if self.provider_disabled(&payment.provider).await? {
+            return Ok(fail(
+                format!(
+                    "Payment provider {} is temporarily disabled",
+                    payment.provider
+                ),
+                Some(&payment),
+            ));
+        }
+
+        let remaining = payment.amount - payment.refunded_amount;
+        let amount = match req.amount.parse::<Decimal>() {
+            Ok(amount) => amount,
+            Err(_) => remaining,
+        };
+        if amount <= Decimal::ZERO {
+            return Ok(fail(
+                "Payment is already fully refunded".to_string(),
+                Some(&payment),
+            ));
+        }
+
+        // Take the amount off what is left to refund first, so concurrent
+        // refunds can't add up to more than was charged
+        let reserved = sqlx::query(
+            "UPDATE payments SET refunded_amount = refunded_amount + $1, updated_at = CURRENT_TIMESTAMP
+             WHERE id = $2 AND status IN ('CAPTURED', 'PARTIALLY_REFUNDED')
+               AND refunded_amount + $1 <= amount",
+        )
+        .bind(amount)
+        .bind(&payment.id)
+        .execute(&self.db)
+        .await
+        .map_err(AppError::from)?
+        .rows_affected()
+            == 1;
+        if !reserved {
+            return Ok(fail(
+                format!("Refund exceeds the remaining {}", remaining),
+                Some(&payment),
+            ));
+        }
+
+        let refund_id = Uuid::new_v4().to_string();
+        let charge_reference = payment.provider_reference.clone().unwrap_or_default();
+        let reference = match self
+            .provider
+            .refund(&refund_id, &charge_reference, amount, &payment.currency)
+            .await
+        {
+            Ok(reference) => reference,
+            Err(e) => {
+                warn!(payment_id = %payment.id, "Payment refund failed: {}", e);
+                sqlx::query(
+                    "UPDATE payments SET refunded_amount = refunded_amount - $1, updated_at = CURRENT_TIMESTAMP
+                     WHERE id = $2",
+                )
+                .bind(amount)
+                .bind(&payment.id)
+                .execute(&self.db)
+                .await
+                .map_err(AppError::from)?;
+
+                return Err(e);
+            }
+        };
+
+        let mut tx = self.db.begin().await.map_err(AppError::from)?;
+
+        sqlx::query(
+            "INSERT INTO payment_refunds (id, payment_id, amount, reason, provider_reference)
+             VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(&refund_id)
+        .bind(&payment.id)
+        .bind(amount)
+        .bind(if req.reason.is_empty() {
+            None
+        } else {
+            Some(&req.reason)
+        })
+        .bind(&reference)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+
+        let payment = sqlx::query_as::<_, DbPayment>(&format!(
+            "UPDATE payments
+             SET status = CASE WHEN refunded_amount >= amount THEN 'REFUNDED' ELSE 'PARTIALLY_REFUNDED' END,
+                 updated_at = CURRENT_TIMESTAMP
+             WHERE id = $1
+             RETURNING {}",
+            PAYMENT_COLUMNS
+        ))
+        .bind(&payment.id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+
+        tx.commit().await.map_err(AppError::from)?;
+
+        Ok(Response::new(RefundPaymentResponse {
+            success: true,
+            message: format!("Refunded {} {}", amount, payment.currency),
+            payment: Some(payment.to_proto()),
+            refund_id,
+        }))
+    }
+
+    async fn get_payment(
+        &self,
+        request: Request<GetPaymentRequest>,
+    ) -> Result<Response<GetPaymentResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        let req = request.into_inner();
+
+        let payment = if !req.payment_id.is_empty() {
+            self.fetch_payment(&req.payment_id).await?
+        } else if !req.order_id.is_empty() {
+            self.live_payment(&req.order_id).await?
+        } else {
+            return Ok(Response::new(GetPaymentResponse {
+                success: false,
+                message: "Payment ID or order ID is required".to_string(),
+                payment: None,
+            }));
+        };
+
+        if let Some(payment) = &payment {
+            let owner = self.order_owner(&payment.order_id).await?;
+            caller.require_owner(owner.as_deref().unwrap_or_default())?;
+        }
+
+        Ok(Response::new(match payment {
+            Some(payment) => GetPaymentResponse {
+                success: true,
+                message: "Payment retrieved".to_string(),
+                payment: Some(payment.to_proto()),
+            },
+            None => GetPaymentResponse {
+                success: false,
+                message: "Payment not found".to_string(),
+                payment: None,
+            },
+        }))
+    }
+}
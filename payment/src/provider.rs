@@ -0,0 +1,105 @@
+use crate::stripe::StripeProvider;
+use anyhow::{Result, anyhow};
+use common::sandbox::{self, CardOutcome};
+use sqlx::types::Decimal;
+use std::env;
+use std::sync::Arc;
+use tonic::Status;
+use uuid::Uuid;
+
+/// Result of asking a provider to charge a payment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Charge {
+    /// Charged; the provider's reference for the charge.
+    Captured(String),
+    /// Refused by the provider, with its reason.
+    Declined(String),
+    /// Accepted but not settled yet, e.g. awaiting 3-D Secure; the provider
+    /// reports the outcome through its webhook.
+    Pending(String),
+}
+
+/// A payment provider charging and refunding on the service's behalf. Calls
+/// carry the payment id as idempotency key, so a retried call after an
+/// unknown outcome can't charge twice.
+#[tonic::async_trait]
+pub trait PaymentProvider: Send + Sync {
+    /// Name used in payment rows and in the provider's ops switch.
+    fn name(&self) -> &'static str;
+
+    async fn capture(
+        &self,
+        payment_id: &str,
+        amount: Decimal,
+        currency: &str,
+        payment_method: &str,
+    ) -> Result<Charge, Status>;
+
+    /// Refunds `amount` of a charge; returns the provider's refund reference.
+    async fn refund(
+        &self,
+        refund_id: &str,
+        charge_reference: &str,
+        amount: Decimal,
+        currency: &str,
+    ) -> Result<String, Status>;
+}
+
+/// The provider named by `PAYMENT_PROVIDER`; sandbox mode always uses the
+/// sandbox provider.
+pub fn from_env() -> Result<Arc<dyn PaymentProvider>> {
+    if sandbox::enabled() {
+        return Ok(Arc::new(SandboxProvider));
+    }
+
+    match env::var("PAYMENT_PROVIDER")
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+        .as_str()
+    {
+        "sandbox" => Ok(Arc::new(SandboxProvider)),
+        "stripe" => {
+            let secret_key = env::var("STRIPE_SECRET_KEY")
+                .map_err(|_| anyhow!("STRIPE_SECRET_KEY must be set for the stripe provider"))?;
+            Ok(Arc::new(StripeProvider::new(secret_key)))
+        }
+        "" => Err(anyhow!(
+            "PAYMENT_PROVIDER must be set unless SANDBOX_MODE is on"
+        )),
+        other => Err(anyhow!("Unknown PAYMENT_PROVIDER: {}", other)),
+    }
+}
+
+/// Deterministic fake: card numbers decide the outcome (see `common::sandbox`).
+pub struct SandboxProvider;
+
+#[tonic::async_trait]
+impl PaymentProvider for SandboxProvider {
+    fn name(&self) -> &'static str {
+        "sandbox"
+    }
+
+    async fn capture(
+        &self,
+        _payment_id: &str,
+        _amount: Decimal,
+        _currency: &str,
+        payment_method: &str,
+    ) -> Result<Charge, Status> {
+        Ok(match sandbox::card_outcome(payment_method) {
+            CardOutcome::Approved => Charge::Captured(format!("sandbox_ch_{}", Uuid::new_v4())),
+            CardOutcome::Declined => Charge::Declined("Card declined".to_string()),
+            CardOutcome::InsufficientFunds => Charge::Declined("Insufficient funds".to_string()),
+        })
+    }
+
+    async fn refund(
+        &self,
+        _refund_id: &str,
+        _charge_reference: &str,
+        _amount: Decimal,
+        _currency: &str,
+    ) -> Result<String, Status> {
+        Ok(format!("sandbox_re_{}", Uuid::new_v4()))
+    }
+}
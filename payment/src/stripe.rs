@@ -0,0 +1,179 @@
+use crate::provider::{Charge, PaymentProvider};
+use serde::Deserialize;
+use sqlx::types::Decimal;
+use tonic::Status;
+
+const STRIPE_API_URL: &str = "https://api.stripe.com/v1";
+
+#[derive(Debug, Deserialize)]
+struct PaymentIntent {
+    id: String,
+    status: String,
+    #[serde(default)]
+    last_payment_error: Option<StripeError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Refund {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct StripeError {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    message: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorBody {
+    error: StripeError,
+}
+
+/// Stripe amounts are integers in the currency's minor unit; only
+/// currencies with two decimals are supported.
+fn minor_units(amount: Decimal) -> Option<i64> {
+    let mut cents = amount.checked_mul(Decimal::from(100))?.round();
+    cents.rescale(0);
+    i64::try_from(cents.mantissa()).ok()
+}
+
+/// Charges through Stripe PaymentIntents, confirmed on creation. Payments
+/// needing customer action settle later through the webhook (see webhook.rs).
+pub struct StripeProvider {
+    secret_key: String,
+    client: reqwest::Client,
+}
+
+impl StripeProvider {
+    pub fn new(secret_key: String) -> Self {
+        Self {
+            secret_key,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    async fn post(
+        &self,
+        path: &str,
+        idempotency_key: &str,
+        form: &[(&str, String)],
+    ) -> Result<Result<reqwest::Response, StripeError>, Status> {
+        let response = self
+            .client
+            .post(format!("{}/{}", STRIPE_API_URL, path))
+            .bearer_auth(&self.secret_key)
+            .header("Idempotency-Key", idempotency_key)
+            .form(form)
+            .send()
+            .await
+            .map_err(|e| Status::unavailable(format!("Stripe request failed: {}", e)))?;
+
+        if response.status().is_success() {
+            return Ok(Ok(response));
+        }
+        if response.status().is_server_error() {
+            return Err(Status::unavailable(format!(
+                "Stripe returned {}",
+                response.status()
+            )));
+        }
+
+        let body: ErrorBody = response
+            .json()
+            .await
+            .map_err(|e| Status::internal(format!("Unreadable Stripe error: {}", e)))?;
+        Ok(Err(body.error))
+    }
+}
+
+#[tonic::async_trait]
+impl PaymentProvider for StripeProvider {
+    fn name(&self) -> &'static str {
+        "stripe"
+    }
+
+    async fn capture(
+        &self,
+        payment_id: &str,
+        amount: Decimal,
+        currency: &str,
+        payment_method: &str,
+    ) -> Result<Charge, Status> {
+        let cents = minor_units(amount)
+            .ok_or_else(|| Status::invalid_argument(format!("Amount out of range: {}", amount)))?;
+        let form = [
+            ("amount", cents.to_string()),
+            ("currency", currency.to_ascii_lowercase()),
+            ("payment_method", payment_method.to_string()),
+            ("confirm", "true".to_string()),
+            ("automatic_payment_methods[enabled]", "true".to_string()),
+            (
+                "automatic_payment_methods[allow_redirects]",
+                "never".to_string(),
+            ),
+            // The webhook finds the payment by this
+            ("metadata[payment_id]", payment_id.to_string()),
+        ];
+
+        let intent: PaymentIntent = match self.post("payment_intents", payment_id, &form).await? {
+            Ok(response) => response
+                .json()
+                .await
+                .map_err(|e| Status::internal(format!("Unreadable Stripe response: {}", e)))?,
+            Err(error) if error.kind == "card_error" => {
+                return Ok(Charge::Declined(
+                    error.message.unwrap_or_else(|| "Card declined".to_string()),
+                ));
+            }
+            Err(error) => {
+                return Err(Status::internal(format!(
+                    "Stripe error: {}",
+                    error.message.unwrap_or(error.kind)
+                )));
+            }
+        };
+
+        Ok(match intent.status.as_str() {
+            "succeeded" => Charge::Captured(intent.id),
+            "requires_payment_method" | "canceled" => Charge::Declined(
+                intent
+                    .last_payment_error
+                    .and_then(|e| e.message)
+                    .unwrap_or_else(|| "Payment method declined".to_string()),
+            ),
+            _ => Charge::Pending(intent.id),
+        })
+    }
+
+    async fn refund(
+        &self,
+        refund_id: &str,
+        charge_reference: &str,
+        amount: Decimal,
+        _currency: &str,
+    ) -> Result<String, Status> {
+        let cents = minor_units(amount)
+            .ok_or_else(|| Status::invalid_argument(format!("Amount out of range: {}", amount)))?;
+        let form = [
+            ("payment_intent", charge_reference.to_string()),
+            ("amount", cents.to_string()),
+            ("metadata[refund_id]", refund_id.to_string()),
+        ];
+
+        match self.post("refunds", refund_id, &form).await? {
+            Ok(response) => {
+                let refund: Refund = response
+                    .json()
+                    .await
+                    .map_err(|e| Status::internal(format!("Unreadable Stripe response: {}", e)))?;
+                Ok(refund.id)
+            }
+            Err(error) => Err(Status::failed_precondition(format!(
+                "Stripe refused the refund: {}",
+                error.message.unwrap_or(error.kind)
+            ))),
+        }
+    }
+}
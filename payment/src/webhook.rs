@@ -0,0 +1,160 @@
+//! HTTP listener for Stripe webhooks. Stripe reports the outcome of payments
+//! that didn't settle during CapturePayment here; events are verified against
+//! the endpoint's signing secret before anything is recorded.
+
+use crate::payment::{record_capture, record_decline};
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::Sha256;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// Oldest signature timestamp accepted, against replayed deliveries.
+const SIGNATURE_TOLERANCE_SECS: i64 = 300;
+
+#[derive(Debug, Deserialize)]
+struct Event {
+    #[serde(rename = "type")]
+    kind: String,
+    data: EventData,
+}
+
+#[derive(Debug, Deserialize)]
+struct EventData {
+    object: EventIntent,
+}
+
+#[derive(Debug, Deserialize)]
+struct EventIntent {
+    id: String,
+    #[serde(default)]
+    metadata: HashMap<String, String>,
+    #[serde(default)]
+    last_payment_error: Option<EventError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EventError {
+    #[serde(default)]
+    message: Option<String>,
+}
+
+struct WebhookState {
+    db: PgPool,
+    secret: String,
+}
+
+/// Checks a `Stripe-Signature` header (`t=<timestamp>,v1=<hex hmac>,...`):
+/// one of the v1 signatures must be the HMAC-SHA256 of `<timestamp>.<body>`
+/// under the signing secret, and the timestamp must be recent.
+fn verify_signature(secret: &str, header: &str, body: &[u8], now: i64) -> bool {
+    let mut timestamp = None;
+    let mut signatures = Vec::new();
+    for part in header.split(',') {
+        match part.trim().split_once('=') {
+            Some(("t", value)) => timestamp = value.parse::<i64>().ok(),
+            Some(("v1", value)) => signatures.extend(hex::decode(value).ok()),
+            _ => {}
+        }
+    }
+
+    let Some(timestamp) = timestamp else {
+        return false;
+    };
+    if (now - timestamp).abs() > SIGNATURE_TOLERANCE_SECS {
+        return false;
+    }
+
+    signatures.iter().any(|signature| {
+        let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else {
+            return false;
+        };
+        mac.update(timestamp.to_string().as_bytes());
+        mac.update(b".");
+        mac.update(body);
+        mac.verify_slice(signature).is_ok()
+    })
+}
+
+async fn handle(
+    State(state): State<Arc<WebhookState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> StatusCode {
+    let signature = headers
+        .get("stripe-signature")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    if !verify_signature(
+        &state.secret,
+        signature,
+        &body,
+        chrono::Utc::now().timestamp(),
+    ) {
+        warn!("Rejected Stripe webhook with an invalid signature");
+        return StatusCode::BAD_REQUEST;
+    }
+
+    let event: Event = match serde_json::from_slice(&body) {
+        Ok(event) => event,
+        Err(e) => {
+            warn!("Unreadable Stripe webhook: {}", e);
+            return StatusCode::BAD_REQUEST;
+        }
+    };
+
+    let intent = event.data.object;
+    let Some(payment_id) = intent.metadata.get("payment_id") else {
+        // Not one of ours
+        return StatusCode::OK;
+    };
+
+    let result = match event.kind.as_str() {
+        "payment_intent.succeeded" => record_capture(&state.db, payment_id, &intent.id)
+            .await
+            .map(|_| ()),
+        "payment_intent.payment_failed" => {
+            let reason = intent
+                .last_payment_error
+                .and_then(|e| e.message)
+                .unwrap_or_else(|| "Payment failed".to_string());
+            record_decline(&state.db, payment_id, &reason)
+                .await
+                .map(|_| ())
+        }
+        _ => Ok(()),
+    };
+
+    match result {
+        Ok(()) => {
+            info!(payment_id = %payment_id, event = %event.kind, "Processed Stripe webhook");
+            StatusCode::OK
+        }
+        // Stripe redelivers on errors
+        Err(e) => {
+            warn!(payment_id = %payment_id, "Failed to process Stripe webhook: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+/// Serves `POST /webhooks/stripe` on `addr` until the process exits.
+pub async fn serve(addr: SocketAddr, db: PgPool, secret: String) -> anyhow::Result<()> {
+    let app = Router::new()
+        .route("/webhooks/stripe", post(handle))
+        .with_state(Arc::new(WebhookState { db, secret }));
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    info!("Stripe webhook listener on {}", addr);
+    axum::serve(listener, app).await?;
+
+    Ok(())
+}
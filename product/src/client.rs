@@ -1,12 +1,19 @@
+use common::auth::ServiceCredentials;
 use proto::product::{
     AddProductRequest, CheckAvailabilityRequest, DeleteProductRequest, GetProductRequest,
-    ListProductsRequest, UpdateInventoryRequest, UpdateProductRequest,
-    product_service_client::ProductServiceClient,
+    ListProductsRequest, ProductSort, ProductType, PublishProductRequest, UpdateInventoryRequest,
+    UpdateProductRequest, product_service_client::ProductServiceClient,
 };
+use tonic::transport::Channel;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let mut client = ProductServiceClient::connect("http://127.0.0.1:50052").await?;
+    // Runs the operator flows too, so calls are made as the platform
+    let channel = Channel::from_static("http://127.0.0.1:50052")
+        .connect()
+        .await?;
+    let mut client =
+        ProductServiceClient::with_interceptor(channel, ServiceCredentials("product-client"));
 
     println!("Connected to Product Service");
     println!("=============================\n");
@@ -16,9 +23,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let add_request = AddProductRequest {
         name: "Laptop".to_string(),
         description: "High-performance laptop with 16GB RAM".to_string(),
-        price: 1299.99,
+        price: "1299.99".to_string(),
         stock_quantity: 50,
         category: "Electronics".to_string(),
+        warranty_months: 24,
+        product_type: ProductType::Physical as i32,
+        allow_backorder: false,
+        available_from: 0,
+        seller_id: String::new(),
+        tax_class: String::new(),
+        weight_grams: 0,
     };
 
     let add_response = client.add_product(add_request).await?;
@@ -35,9 +49,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let add_request2 = AddProductRequest {
         name: "Wireless Mouse".to_string(),
         description: "Ergonomic wireless mouse with USB receiver".to_string(),
-        price: 29.99,
+        price: "29.99".to_string(),
         stock_quantity: 150,
         category: "Electronics".to_string(),
+        warranty_months: 0,
+        product_type: ProductType::Physical as i32,
+        allow_backorder: false,
+        available_from: 0,
+        seller_id: String::new(),
+        tax_class: String::new(),
+        weight_grams: 0,
     };
 
     let add_response2 = client.add_product(add_request2).await?;
@@ -49,6 +70,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let product_id2 = add_result2.product_id.clone();
 
+    // New products are drafts; publish both so they're listed and orderable
+    for id in [&product_id, &product_id2] {
+        let publish_result = client
+            .publish_product(PublishProductRequest {
+                product_id: id.clone(),
+            })
+            .await?
+            .into_inner();
+        println!("Publish Product {}: {}", id, publish_result.message);
+    }
+    println!();
+
     // Test 3: Get product by ID
     println!("3. Testing Get Product");
     let get_request = GetProductRequest {
@@ -64,7 +97,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("  Product ID: {}", product.product_id);
         println!("  Name: {}", product.name);
         println!("  Description: {}", product.description);
-        println!("  Price: ${:.2}", product.price);
+        println!("  Price: ${}", product.price);
         println!("  Stock: {}", product.stock_quantity);
         println!("  Category: {}\n", product.category);
     }
@@ -75,6 +108,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         page: 1,
         page_size: 10,
         category: String::new(),
+        attribute_filters: vec![],
+        sort: ProductSort::DefaultSort as i32,
+        tags: vec![],
+        page_token: String::new(),
+        min_price: String::new(),
+        max_price: String::new(),
+        in_stock_only: false,
     };
 
     let list_response = client.list_products(list_request).await?;
@@ -86,7 +126,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("  Products in this page:");
     for product in &list_result.products {
         println!(
-            "    - {} (${:.2}) - Stock: {}",
+            "    - {} (${}) - Stock: {}",
             product.name, product.price, product.stock_quantity
         );
     }
@@ -98,6 +138,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         page: 1,
         page_size: 10,
         category: "Electronics".to_string(),
+        attribute_filters: vec![],
+        sort: ProductSort::DefaultSort as i32,
+        tags: vec![],
+        page_token: String::new(),
+        min_price: String::new(),
+        max_price: String::new(),
+        in_stock_only: false,
     };
 
     let list_by_category_response = client.list_products(list_by_category_request).await?;
@@ -168,9 +215,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         product_id: product_id.clone(),
         name: "Gaming Laptop".to_string(),
         description: "High-performance gaming laptop with RTX GPU and 32GB RAM".to_string(),
-        price: 1899.99,
+        price: "1899.99".to_string(),
         stock_quantity: 65,
         category: "Gaming".to_string(),
+        warranty_months: 24,
+        update_mask: None,
+        allow_backorder: false,
+        available_from: 0,
+        tax_class: String::new(),
+        weight_grams: 0,
     };
 
     let update_response = client.update_product(update_request).await?;
@@ -180,7 +233,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("  Message: {}", update_result.message);
     if let Some(product) = &update_result.product {
         println!("  Updated Name: {}", product.name);
-        println!("  Updated Price: ${:.2}", product.price);
+        println!("  Updated Price: ${}", product.price);
         println!("  Updated Category: {}\n", product.category);
     }
 
@@ -216,11 +269,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         product_id: product_id2.clone(),
     };
 
-    let get_deleted_response = client.get_product(get_deleted_request).await?;
-    let get_deleted_result = get_deleted_response.into_inner();
+    // Deleted products read as missing: NOT_FOUND
+    let get_deleted_status = client
+        .get_product(get_deleted_request)
+        .await
+        .expect_err("deleted product was returned");
     println!("Get Deleted Product Response:");
-    println!("  Success: {}", get_deleted_result.success);
-    println!("  Message: {}\n", get_deleted_result.message);
+    println!("  Code: {:?}", get_deleted_status.code());
+    println!("  Message: {}\n", get_deleted_status.message());
 
     println!("=============================");
     println!("All tests completed!");
@@ -0,0 +1,7 @@
+pub mod merchandising;
+pub mod product;
+pub mod related;
+pub mod repository;
+pub mod reservation;
+pub mod review;
+pub mod stock_badge;
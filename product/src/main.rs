@@ -1,15 +1,31 @@
-mod product;
-
 use anyhow::Result;
-use product::ProductServiceImpl;
+use common::cache::Cache;
+use common::client::ServiceEndpoint;
+use common::health::HealthCheck;
+use common::response_cache::ResponseCache;
+use common::metrics::{self, Metrics};
+use common::server::ServerBuilder;
+use common::telemetry;
+use common::tls;
+use common::slo::{SloConfig, SloServiceImpl, SloTracker};
+use product::merchandising::OutOfStockPolicy;
+use product::product::ProductServiceImpl;
 use proto::product::product_service_server::ProductServiceServer;
+use proto::review::review_service_server::ReviewServiceServer;
+use proto::slo::slo_service_server::SloServiceServer;
+use product::related::CoPurchaseRefresher;
+use product::review::ReviewServiceImpl;
 use sqlx::postgres::PgPoolOptions;
+use product::stock_badge::StockBadgeCache;
 use std::env;
-use tonic::transport::Server;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::info;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     dotenvy::dotenv().ok();
+    telemetry::init("product", env!("CARGO_PKG_VERSION"))?;
 
     let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
 
@@ -19,17 +35,92 @@ async fn main() -> Result<()> {
         .connect(&database_url)
         .await?;
 
-    println!("Connected to database");
+    info!("Connected to database");
 
     let addr = "0.0.0.0:50052".parse()?;
-    let product_service = ProductServiceImpl::new(pool);
+    let response_cache = ResponseCache::new()
+        .with_rule("/product.ProductService/GetProduct", Duration::from_secs(30))
+        .with_rule("/product.ProductService/ListProducts", Duration::from_secs(10))
+        .with_rule("/product.ProductService/GetStockBadge", Duration::from_secs(5))
+        .with_rule(
+            "/product.ProductService/GetRelatedProducts",
+            Duration::from_secs(60),
+        )
+        .with_rule(
+            "/product.ProductService/GetCategoryStats",
+            Duration::from_secs(60),
+        )
+        .build();
+
+    // Badges sit behind two tiers: whole responses above, per-product levels below
+    let badge_ttl_secs: u64 = env::var("STOCK_BADGE_TTL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(30);
+    let low_stock_threshold: i32 = env::var("LOW_STOCK_THRESHOLD")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(5);
+    let stock_badges = StockBadgeCache::new(
+        pool.clone(),
+        Duration::from_secs(badge_ttl_secs),
+        low_stock_threshold,
+    );
+
+    let co_purchase_refresh_secs: u64 = env::var("CO_PURCHASE_REFRESH_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(3600);
+    CoPurchaseRefresher::new(pool.clone()).spawn(Duration::from_secs(co_purchase_refresh_secs));
+
+    // Reviews require a delivered order, verified with the order service
+    let order_service =
+        ServiceEndpoint::from_env("order", "ORDER_SERVICE", "http://127.0.0.1:50053")
+            .with_tls(tls::client_config()?);
+    let product_cache = Arc::new(Cache::from_env("product").await?);
+    let review_service = ReviewServiceImpl::new(
+        pool.clone(),
+        order_service,
+        response_cache.clone(),
+        product_cache.clone(),
+    );
+
+    let health_interval_secs: u64 = env::var("HEALTH_CHECK_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(5);
+    let health_service = HealthCheck::new(pool.clone())
+        .with_service::<ProductServiceServer<ProductServiceImpl>>()
+        .with_service::<ReviewServiceServer<ReviewServiceImpl>>()
+        .start(Duration::from_secs(health_interval_secs))
+        .await;
+
+    let product_service = ProductServiceImpl::new(
+        pool,
+        response_cache.clone(),
+        product_cache,
+        stock_badges,
+        OutOfStockPolicy::from_env()?,
+    );
+    let slo_tracker = SloTracker::new("product", SloConfig::from_env()?);
+    let metrics = Metrics::new("product");
+    let metrics_addr = env::var("METRICS_ADDR")
+        .unwrap_or_else(|_| "0.0.0.0:9102".to_string())
+        .parse()?;
+    metrics::spawn(metrics_addr, metrics.clone());
 
-    println!("Product service listening on {}", addr);
+    info!("Product service listening on {}", addr);
 
-    Server::builder()
+    ServerBuilder::new(metrics, slo_tracker.clone())
+        .with_response_cache(response_cache)
+        .build()?
+        .add_service(health_service)
         .add_service(ProductServiceServer::new(product_service))
+        .add_service(ReviewServiceServer::new(review_service))
+        .add_service(SloServiceServer::new(SloServiceImpl::new(slo_tracker)))
         .serve(addr)
         .await?;
 
+    telemetry::shutdown();
     Ok(())
 }
@@ -0,0 +1,139 @@
+use anyhow::{Result, anyhow};
+use common::error::AppError;
+use common::pagination::SortOrder;
+use common::pricing::EFFECTIVE_PRICE;
+use proto::product::ProductSort;
+use sqlx::PgPool;
+use std::env;
+use tonic::Status;
+
+/// Upper bound of pinned products per category.
+pub const MAX_PINS_PER_CATEGORY: i64 = 50;
+
+/// How ListProducts presents products that are out of stock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutOfStockPolicy {
+    /// Left out of listings and their counts.
+    Hide,
+    /// Listed after every product in stock; pinned products keep their pins.
+    ShowLast,
+    /// Listed in place, flagged for a notify-me call to action.
+    Notify,
+}
+
+impl OutOfStockPolicy {
+    /// Reads `OUT_OF_STOCK_POLICY` (`hide`, `last` or `notify`, default `notify`).
+    pub fn from_env() -> Result<Self> {
+        match env::var("OUT_OF_STOCK_POLICY")
+            .unwrap_or_default()
+            .to_ascii_lowercase()
+            .as_str()
+        {
+            "" | "notify" => Ok(Self::Notify),
+            "hide" => Ok(Self::Hide),
+            "last" => Ok(Self::ShowLast),
+            other => Err(anyhow!("Unknown OUT_OF_STOCK_POLICY: {}", other)),
+        }
+    }
+}
+
+pub fn sort_to_string(sort: ProductSort) -> &'static str {
+    match sort {
+        ProductSort::DefaultSort | ProductSort::Newest => "NEWEST",
+        ProductSort::BestSelling => "BEST_SELLING",
+        ProductSort::PriceLowToHigh => "PRICE_LOW_TO_HIGH",
+        ProductSort::PriceHighToLow => "PRICE_HIGH_TO_LOW",
+        ProductSort::Manual => "MANUAL",
+    }
+}
+
+pub fn sort_from_string(sort: &str) -> ProductSort {
+    match sort {
+        "BEST_SELLING" => ProductSort::BestSelling,
+        "PRICE_LOW_TO_HIGH" => ProductSort::PriceLowToHigh,
+        "PRICE_HIGH_TO_LOW" => ProductSort::PriceHighToLow,
+        "MANUAL" => ProductSort::Manual,
+        _ => ProductSort::Newest,
+    }
+}
+
+/// Sort key of a sort over the `products` table: the expression, its SQL
+/// type and its direction. Listings break ties on the id in the same
+/// direction, so pages are stable and can resume after a cursor.
+pub fn sort_key(sort: ProductSort) -> (String, &'static str, SortOrder) {
+    match sort {
+        ProductSort::DefaultSort | ProductSort::Newest => {
+            ("created_at".to_string(), "TIMESTAMP", SortOrder::Desc)
+        }
+        ProductSort::BestSelling => (
+            "(SELECT COALESCE(SUM(oi.quantity), 0) FROM order_items oi
+               JOIN orders o ON o.id = oi.order_id
+               WHERE oi.product_id = products.id AND o.status <> 'CANCELLED')"
+                .to_string(),
+            "BIGINT",
+            SortOrder::Desc,
+        ),
+        ProductSort::PriceLowToHigh => (EFFECTIVE_PRICE.to_string(), "NUMERIC", SortOrder::Asc),
+        ProductSort::PriceHighToLow => (EFFECTIVE_PRICE.to_string(), "NUMERIC", SortOrder::Desc),
+        ProductSort::Manual => ("name".to_string(), "TEXT", SortOrder::Asc),
+    }
+}
+
+/// ORDER BY expression of a sort over the `products` table.
+pub fn order_by(sort: ProductSort) -> String {
+    let (key, _, order) = sort_key(sort);
+    let direction = match order {
+        SortOrder::Asc => "ASC",
+        SortOrder::Desc => "DESC",
+    };
+    format!("{} {}, id {}", key, direction, direction)
+}
+
+/// The sort a category is listed with when the request asks for none.
+pub async fn default_sort(db: &PgPool, category: &str) -> Result<ProductSort, Status> {
+    let sort: Option<String> =
+        sqlx::query_scalar("SELECT default_sort FROM category_settings WHERE category = $1")
+            .bind(category)
+            .fetch_optional(db)
+            .await
+            .map_err(AppError::from)?;
+
+    Ok(sort.map_or(ProductSort::Newest, |s| sort_from_string(&s)))
+}
+
+/// Pinned product ids of a category with their positions, ordered by position.
+pub async fn pins(db: &PgPool, category: &str) -> Result<Vec<(String, i32)>, Status> {
+    sqlx::query_as(
+        "SELECT product_id, position FROM category_pins WHERE category = $1 ORDER BY position",
+    )
+    .bind(category)
+    .fetch_all(db)
+    .await
+    .map_err(|e| AppError::from(e).into())
+}
+
+/// Merges a page of pinned and unpinned products. `first_position` is the
+/// 1-based listing position of the page's first slot, `pins` holds the
+/// page's pinned items ordered by position and `rest` the unpinned items in
+/// sort order. Pins past the end of the listing follow its last product.
+pub fn merge_pins<T>(first_position: i64, pins: Vec<(i64, T)>, rest: Vec<T>) -> Vec<T> {
+    let mut pins = pins.into_iter().peekable();
+    let mut rest = rest.into_iter();
+    let mut merged = Vec::new();
+    let mut position = first_position;
+
+    loop {
+        let item = match pins.peek() {
+            Some((pin_position, _)) if *pin_position <= position => pins.next().map(|p| p.1),
+            _ => rest.next(),
+        };
+        match item {
+            Some(item) => merged.push(item),
+            None => break,
+        }
+        position += 1;
+    }
+
+    merged.extend(pins.map(|(_, item)| item));
+    merged
+}
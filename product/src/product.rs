@@ -1,47 +1,689 @@
+use crate::merchandising::{self, OutOfStockPolicy};
+use crate::repository::{DbProduct, NewProduct, PostgresProductRepository, ProductRepository};
+use crate::reservation::{
+    self, STOCK_INVENTORY_UPDATE, STOCK_RESTOCK_RECEIVED, emit_stock_changed,
+};
+use crate::stock_badge::StockBadgeCache;
 use anyhow::Result;
+use chrono::{DateTime, Utc};
+use common::auth::Caller;
+use common::cache::Cache;
+use common::error::{self, AppError};
+use common::events;
+use common::inventory::{self, Backorder};
+use common::pagination::{self, Cursor, PageRequest};
+use common::pricing::EFFECTIVE_PRICE;
+use common::response_cache::ResponseCache;
+use proto::events::ProductChanged;
 use proto::product::{
-    AddProductRequest, AddProductResponse, CheckAvailabilityRequest, CheckAvailabilityResponse,
-    DeleteProductRequest, DeleteProductResponse, GetProductRequest, GetProductResponse,
-    GetProductsByIDsRequest, GetProductsByIDsResponse, ListProductsRequest, ListProductsResponse,
-    Product, UpdateInventoryRequest, UpdateInventoryResponse, UpdateProductRequest,
+    AddProductRequest, AddProductResponse, ArchiveProductRequest, ArchiveProductResponse,
+    AvailabilityEntry, AvailabilityState, CancelSaleRequest, CancelSaleResponse, CategoryPin,
+    CategoryStats, CheckAvailabilityBatchRequest, CheckAvailabilityBatchResponse,
+    CheckAvailabilityRequest, CheckAvailabilityResponse, ConfirmReservationRequest,
+    ConfirmReservationResponse, CreateSellerRequest, CreateSellerResponse, DeleteProductRequest,
+    DeleteProductResponse, ExportProductsRequest, ExportProductsResponse,
+    GetAvailabilityTimelineRequest, GetAvailabilityTimelineResponse,
+    GetCategoryMerchandisingRequest, GetCategoryMerchandisingResponse, GetCategoryStatsRequest,
+    GetCategoryStatsResponse, GetProductAttributesRequest, GetProductAttributesResponse,
+    GetProductRequest, GetProductResponse, GetProductsByIDsRequest, GetProductsByIDsResponse,
+    GetRelatedProductsRequest, GetRelatedProductsResponse, GetStockBadgeRequest,
+    GetStockBadgeResponse, ListProductsBySellerRequest, ListProductsBySellerResponse,
+    ListProductsRequest, ListProductsResponse, PinProductRequest, PinProductResponse, Product,
+    ProductAttribute, ProductAvailability, ProductSort, ProductStatus, ProductType,
+    PublishProductRequest, PublishProductResponse, ReceiveRestockRequest, ReceiveRestockResponse,
+    ReleaseReservationRequest, ReleaseReservationResponse, ReserveStockRequest,
+    ReserveStockResponse, RestoreProductRequest, RestoreProductResponse, ReturnStockRequest,
+    ReturnStockResponse, ScheduleRestockRequest, ScheduleRestockResponse, ScheduleSaleRequest,
+    ScheduleSaleResponse, Seller, SetCategorySortRequest, SetCategorySortResponse,
+    SetProductAttributesRequest, SetProductAttributesResponse, StockBadge, TagProductRequest,
+    TagProductResponse, UnpinProductRequest, UnpinProductResponse, UntagProductRequest,
+    UntagProductResponse, UpdateInventoryRequest, UpdateInventoryResponse, UpdateProductRequest,
     UpdateProductResponse, product_service_server::ProductService,
 };
-use sqlx::{PgPool, types::Decimal};
+use sqlx::{PgConnection, PgPool, Postgres, QueryBuilder, types::Decimal};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
 use tonic::{Request, Response, Status};
 use uuid::Uuid;
 
+impl DbProduct {
+    /// How `quantity` units can be ordered at `now`. Quarantines and the
+    /// publication status are checked separately.
+    fn availability(&self, quantity: i32, now: DateTime<Utc>) -> AvailabilityState {
+        if self.available_from.is_some_and(|from| from > now) {
+            AvailabilityState::Preorder
+        } else if self.product_type == "DIGITAL" || self.stock_quantity >= quantity {
+            AvailabilityState::Available
+        } else if self.allow_backorder {
+            AvailabilityState::Backorder
+        } else {
+            AvailabilityState::Unavailable
+        }
+    }
+
+    /// The price charged at `now`; mirrors `common::pricing::EFFECTIVE_PRICE`.
+    fn effective_price(&self, now: DateTime<Utc>) -> sqlx::types::Decimal {
+        match self.sale_price {
+            Some(sale_price)
+                if self.sale_starts_at.is_none_or(|starts| starts <= now)
+                    && self.sale_ends_at.is_none_or(|ends| ends > now) =>
+            {
+                sale_price.min(self.price)
+            }
+            _ => self.price,
+        }
+    }
+}
+
 #[derive(Debug, sqlx::FromRow)]
-struct DbProduct {
+struct DbSeller {
     id: String,
     name: String,
-    description: Option<String>,
-    price: sqlx::types::Decimal,
-    stock_quantity: i32,
-    category: Option<String>,
+    user_id: String,
     created_at: chrono::NaiveDateTime,
-    updated_at: chrono::NaiveDateTime,
 }
 
+impl DbSeller {
+    fn to_proto(&self) -> Seller {
+        Seller {
+            seller_id: self.id.clone(),
+            name: self.name.clone(),
+            user_id: self.user_id.clone(),
+            created_at: self.created_at.and_utc().timestamp(),
+        }
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct DbCategoryStats {
+    category: String,
+    product_count: i64,
+    in_stock_count: i64,
+    min_price: Decimal,
+    max_price: Decimal,
+}
+
+impl DbCategoryStats {
+    fn to_proto(&self) -> CategoryStats {
+        CategoryStats {
+            category: self.category.clone(),
+            product_count: i32::try_from(self.product_count).unwrap_or(i32::MAX),
+            in_stock_count: i32::try_from(self.in_stock_count).unwrap_or(i32::MAX),
+            min_price: self.min_price.to_string(),
+            max_price: self.max_price.to_string(),
+        }
+    }
+}
+
+/// Appends the WHERE clause shared by the list and count queries of `list_products`.
+fn push_list_filters(
+    qb: &mut QueryBuilder<'_, Postgres>,
+    req: &ListProductsRequest,
+    policy: OutOfStockPolicy,
+) {
+    qb.push(" WHERE deleted_at IS NULL AND status = 'PUBLISHED'");
+
+    if policy == OutOfStockPolicy::Hide || req.in_stock_only {
+        qb.push(" AND stock_quantity > 0");
+    }
+
+    // A sale never raises the price, so the bounds also hold for the regular
+    // or sale price alone; those columns are indexed, the effective price isn't
+    if let Ok(min_price) = req.min_price.parse::<Decimal>() {
+        qb.push(" AND price >= ")
+            .push_bind(min_price)
+            .push(format!(" AND {} >= ", EFFECTIVE_PRICE))
+            .push_bind(min_price);
+    }
+    if let Ok(max_price) = req.max_price.parse::<Decimal>() {
+        qb.push(" AND (price <= ")
+            .push_bind(max_price)
+            .push(" OR sale_price <= ")
+            .push_bind(max_price)
+            .push(format!(") AND {} <= ", EFFECTIVE_PRICE))
+            .push_bind(max_price);
+    }
+
+    if !req.category.is_empty() {
+        qb.push(" AND category = ").push_bind(req.category.clone());
+    }
+
+    for filter in &req.attribute_filters {
+        qb.push(
+            " AND EXISTS (SELECT 1 FROM product_attributes pa WHERE pa.product_id = products.id AND pa.name = ",
+        )
+        .push_bind(filter.name.clone())
+        .push(" AND pa.value = ")
+        .push_bind(filter.value.clone())
+        .push(")");
+    }
+
+    for tag in normalize_tags(&req.tags) {
+        qb.push(
+            " AND EXISTS (SELECT 1 FROM product_tags pt JOIN tags t ON t.id = pt.tag_id WHERE pt.product_id = products.id AND t.name = ",
+        )
+        .push_bind(tag)
+        .push(")");
+    }
+}
+
+/// Where a page of a product listing ends, carried to the next page as its
+/// token: the sort the listing started with, the listing slot after the page
+/// and the last unpinned product shown so far, with whether it was out of
+/// stock. The next page's unpinned products continue after that product and
+/// its pins are placed by slot, as on numbered pages.
+struct ListPosition {
+    sort: ProductSort,
+    next_slot: i64,
+    last: Option<(bool, Cursor)>,
+}
+
+impl ListPosition {
+    fn encode(&self) -> String {
+        let (out_of_stock, key, id) = match &self.last {
+            Some((out_of_stock, cursor)) => {
+                (*out_of_stock, cursor.key.as_str(), cursor.id.as_str())
+            }
+            None => (false, "", ""),
+        };
+        let key = format!(
+            "{}:{}:{}:{}",
+            merchandising::sort_to_string(self.sort),
+            self.next_slot,
+            out_of_stock as u8,
+            key
+        );
+        Cursor::new(key, id).encode()
+    }
+
+    fn decode(token: &str) -> Option<Self> {
+        let cursor = Cursor::decode(token)?;
+        let mut parts = cursor.key.splitn(4, ':');
+        let sort = merchandising::sort_from_string(parts.next()?);
+        let next_slot = parts
+            .next()?
+            .parse::<i64>()
+            .ok()
+            .filter(|slot| *slot >= 1)?;
+        let out_of_stock = parts.next()? == "1";
+        let key = parts.next()?;
+        let last =
+            (!cursor.id.is_empty()).then(|| (out_of_stock, Cursor::new(key, cursor.id.clone())));
+
+        Some(Self {
+            sort,
+            next_slot,
+            last,
+        })
+    }
+}
+
+/// Appends the condition selecting the products listed after `cursor`, the
+/// last product of a page. Under `ShowLast` the out-of-stock products follow
+/// the rest whatever the sort.
+fn push_after(
+    qb: &mut QueryBuilder<'_, Postgres>,
+    sort: ProductSort,
+    policy: OutOfStockPolicy,
+    out_of_stock: bool,
+    cursor: &Cursor,
+) {
+    let (key, key_type, order) = merchandising::sort_key(sort);
+    if policy != OutOfStockPolicy::ShowLast {
+        cursor.push_after(qb, &key, key_type, "id", order);
+    } else if out_of_stock {
+        qb.push(" AND stock_quantity <= 0");
+        cursor.push_after(qb, &key, key_type, "id", order);
+    } else {
+        qb.push(" AND (stock_quantity <= 0 OR (stock_quantity > 0");
+        cursor.push_after(qb, &key, key_type, "id", order);
+        qb.push("))");
+    }
+}
+
+/// Fields of `UpdateProductRequest` an update may change, in column order.
+const UPDATABLE_FIELDS: [&str; 10] = [
+    "name",
+    "description",
+    "price",
+    "stock_quantity",
+    "category",
+    "warranty_months",
+    "allow_backorder",
+    "available_from",
+    "tax_class",
+    "weight_grams",
+];
+
+/// Resolves the fields an update changes from its mask. Without a mask only
+/// fields set to a non-default value change, so omitted fields are never
+/// blanked; `*` selects every field.
+fn update_fields(req: &UpdateProductRequest) -> Result<Vec<&'static str>, String> {
+    let paths = req
+        .update_mask
+        .as_ref()
+        .map(|mask| mask.paths.as_slice())
+        .unwrap_or_default();
+
+    if paths.is_empty() {
+        return Ok(UPDATABLE_FIELDS
+            .into_iter()
+            .filter(|field| match *field {
+                "name" => !req.name.is_empty(),
+                "description" => !req.description.is_empty(),
+                "price" => !req.price.is_empty(),
+                "stock_quantity" => req.stock_quantity != 0,
+                "category" => !req.category.is_empty(),
+                "warranty_months" => req.warranty_months != 0,
+                "allow_backorder" => req.allow_backorder,
+                "available_from" => req.available_from != 0,
+                "tax_class" => !req.tax_class.is_empty(),
+                _ => req.weight_grams != 0,
+            })
+            .collect());
+    }
+    if paths.iter().any(|p| p == "*") {
+        return Ok(UPDATABLE_FIELDS.to_vec());
+    }
+
+    for path in paths {
+        if !UPDATABLE_FIELDS.contains(&path.as_str()) {
+            return Err(format!("Unknown field in update_mask: {}", path));
+        }
+    }
+    Ok(UPDATABLE_FIELDS
+        .into_iter()
+        .filter(|field| paths.iter().any(|p| p == field))
+        .collect())
+}
+
+fn status_to_string(status: ProductStatus) -> &'static str {
+    match status {
+        ProductStatus::Draft => "DRAFT",
+        ProductStatus::Published => "PUBLISHED",
+        ProductStatus::Archived => "ARCHIVED",
+    }
+}
+
+fn status_from_string(status: &str) -> ProductStatus {
+    match status {
+        "PUBLISHED" => ProductStatus::Published,
+        "ARCHIVED" => ProductStatus::Archived,
+        _ => ProductStatus::Draft,
+    }
+}
+
+fn type_to_string(product_type: ProductType) -> &'static str {
+    match product_type {
+        ProductType::Physical => "PHYSICAL",
+        ProductType::Digital => "DIGITAL",
+    }
+}
+
+fn type_from_string(product_type: &str) -> ProductType {
+    match product_type {
+        "DIGITAL" => ProductType::Digital,
+        _ => ProductType::Physical,
+    }
+}
+
+/// Release time of a product from unix seconds; 0 means released.
+fn release_time(seconds: i64) -> Option<DateTime<Utc>> {
+    (seconds != 0)
+        .then(|| DateTime::from_timestamp(seconds, 0))
+        .flatten()
+}
+
+/// Tax class of a product; empty means the standard class.
+fn tax_class(value: &str) -> String {
+    match value.trim() {
+        "" => "standard".to_string(),
+        class => class.to_lowercase(),
+    }
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Trims and lowercases tags, dropping blanks and duplicates.
+fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = tags
+        .iter()
+        .map(|t| t.trim().to_lowercase())
+        .filter(|t| !t.is_empty())
+        .collect();
+    normalized.sort();
+    normalized.dedup();
+    normalized
+}
+
+/// Changes a `ProductChanged` event gives.
+pub(crate) const PRODUCT_CREATED: &str = "CREATED";
+const PRODUCT_UPDATED: &str = "UPDATED";
+const PRODUCT_DELETED: &str = "DELETED";
+
+/// Announces a change to a product, in the transaction making it.
+pub(crate) async fn emit_product_changed(
+    conn: &mut PgConnection,
+    product_id: &str,
+    change: &str,
+) -> Result<(), sqlx::Error> {
+    events::emit(
+        conn,
+        &ProductChanged {
+            product_id: product_id.to_string(),
+            change: change.to_string(),
+            changed_at: Utc::now().timestamp(),
+        },
+    )
+    .await
+}
+
+/// Method prefix of every cached product read, invalidated on product writes.
+const PRODUCT_CACHE_PREFIX: &str = "/product.ProductService/";
+
+const DEFAULT_EXPORT_PAGE_SIZE: i32 = 500;
+const MAX_EXPORT_PAGE_SIZE: i32 = 1000;
+
+const MAX_STOCK_BADGES: usize = 100;
+
+const DEFAULT_RELATED_LIMIT: i32 = 10;
+const MAX_RELATED_LIMIT: i32 = 50;
+
+const MAX_TAG_LENGTH: usize = 50;
+
+/// How long a product stays in the shared cache. Stock levels moved by the
+/// order service directly are only seen once the entry expires.
+const PRODUCT_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// How long `product` can be cached: not past the start or end of its sale,
+/// which changes its price.
+fn cache_ttl(product: &Product) -> Duration {
+    let now = Utc::now().timestamp();
+    [product.sale_starts_at, product.sale_ends_at]
+        .into_iter()
+        .filter(|&at| at > now)
+        .map(|at| Duration::from_secs((at - now) as u64))
+        .fold(PRODUCT_CACHE_TTL, Duration::min)
+}
+
+#[derive(Clone)]
 pub struct ProductServiceImpl {
     db: PgPool,
+    /// Product records of lookups, new products and stock updates.
+    repository: Arc<dyn ProductRepository>,
+    cache: Arc<ResponseCache>,
+    /// Products by id, shared by the instances, see `cache_ttl`.
+    products: Arc<Cache>,
+    stock_badges: Arc<StockBadgeCache>,
+    out_of_stock_policy: OutOfStockPolicy,
 }
 
 impl ProductServiceImpl {
-    pub fn new(db: PgPool) -> Self {
-        Self { db }
+    pub fn new(
+        db: PgPool,
+        cache: Arc<ResponseCache>,
+        products: Arc<Cache>,
+        stock_badges: Arc<StockBadgeCache>,
+        out_of_stock_policy: OutOfStockPolicy,
+    ) -> Self {
+        Self {
+            repository: Arc::new(PostgresProductRepository::new(db.clone())),
+            db,
+            cache,
+            products,
+            stock_badges,
+            out_of_stock_policy,
+        }
+    }
+
+    /// Keeps the products of lookups, new products and stock updates in
+    /// `repository` instead of the database, e.g. an
+    /// `InMemoryProductRepository` in tests.
+    pub fn with_repository(mut self, repository: Arc<dyn ProductRepository>) -> Self {
+        self.repository = repository;
+        self
+    }
+
+    /// Drops what's cached of the products a write changed.
+    async fn invalidate(&self, product_ids: &[&str]) {
+        self.cache.invalidate_prefix(PRODUCT_CACHE_PREFIX);
+        self.products.invalidate(product_ids).await;
     }
 
-    fn db_product_to_proto(&self, db_product: &DbProduct) -> Product {
+    fn db_product_to_proto(
+        &self,
+        db_product: &DbProduct,
+        attributes: Vec<ProductAttribute>,
+        tags: Vec<String>,
+    ) -> Product {
         Product {
             product_id: db_product.id.clone(),
             name: db_product.name.clone(),
             description: db_product.description.clone().unwrap_or_default(),
-            price: db_product.price.to_string().parse::<f64>().unwrap_or(0.0),
+            price: db_product.effective_price(Utc::now()).to_string(),
+            regular_price: db_product.price.to_string(),
+            sale_price: db_product
+                .sale_price
+                .map(|p| p.to_string())
+                .unwrap_or_default(),
+            sale_starts_at: db_product.sale_starts_at.map_or(0, |t| t.timestamp()),
+            sale_ends_at: db_product.sale_ends_at.map_or(0, |t| t.timestamp()),
             stock_quantity: db_product.stock_quantity,
             category: db_product.category.clone().unwrap_or_default(),
             created_at: db_product.created_at.and_utc().timestamp(),
             updated_at: db_product.updated_at.and_utc().timestamp(),
+            attributes,
+            warranty_months: db_product.warranty_months,
+            deleted: db_product.deleted_at.is_some(),
+            rating_average: db_product
+                .rating_average
+                .to_string()
+                .parse::<f64>()
+                .unwrap_or(0.0),
+            rating_count: db_product.rating_count,
+            tags,
+            notify_me: false,
+            status: status_from_string(&db_product.status) as i32,
+            product_type: type_from_string(&db_product.product_type) as i32,
+            allow_backorder: db_product.allow_backorder,
+            available_from: db_product.available_from.map_or(0, |t| t.timestamp()),
+            seller_id: db_product.seller_id.clone().unwrap_or_default(),
+            tax_class: db_product.tax_class.clone(),
+            weight_grams: db_product.weight_grams,
+        }
+    }
+
+    /// Keyset position of a listed product under `sort`. The sort key is read
+    /// back as text so it round-trips through the page token exactly.
+    async fn list_cursor(
+        &self,
+        sort: ProductSort,
+        product: &DbProduct,
+    ) -> Result<(bool, Cursor), Status> {
+        let (key, _, _) = merchandising::sort_key(sort);
+        let mut query = QueryBuilder::<Postgres>::new(format!(
+            "SELECT CAST({} AS TEXT) FROM products WHERE id = ",
+            key
+        ));
+        query.push_bind(product.id.clone());
+
+        let value: String = query
+            .build_query_scalar()
+            .fetch_one(&self.db)
+            .await
+            .map_err(AppError::from)?;
+
+        Ok((
+            product.stock_quantity <= 0,
+            Cursor::new(value, product.id.clone()),
+        ))
+    }
+
+    /// Checks the caller may change the product: the platform any product,
+    /// sellers only their own and customers none. Unknown products pass so
+    /// the handler reports them as usual.
+    async fn authorize(&self, caller: &Caller, product_id: &str) -> Result<(), Status> {
+        let seller_id = match caller {
+            Caller::Platform => return Ok(()),
+            Caller::Customer(_) => {
+                return Err(Status::permission_denied(
+                    "Customers cannot change products",
+                ));
+            }
+            Caller::Anonymous => return Err(Status::unauthenticated("Sign in first")),
+            Caller::Seller { seller_id, .. } => seller_id,
+        };
+
+        let owner = self.repository.seller_of(product_id).await?;
+
+        match owner {
+            Some(owner) if owner.as_deref() != Some(seller_id.as_str()) => Err(
+                Status::permission_denied("Product belongs to another seller"),
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    /// Moves a product to `status`. Fails with a message when the product
+    /// doesn't exist or already has that status.
+    async fn set_status(
+        &self,
+        product_id: &str,
+        status: ProductStatus,
+    ) -> Result<DbProduct, Status> {
+        let mut tx = self.db.begin().await.map_err(AppError::from)?;
+
+        let product = sqlx::query_as::<_, DbProduct>(
+            "UPDATE products SET status = $1, updated_at = CURRENT_TIMESTAMP
+             WHERE id = $2 AND deleted_at IS NULL AND status <> $1
+             RETURNING id, name, description, price, stock_quantity, category, warranty_months, rating_average, rating_count, sale_price, sale_starts_at, sale_ends_at, status, product_type, allow_backorder, available_from, seller_id, tax_class, weight_grams, deleted_at, created_at, updated_at",
+        )
+        .bind(status_to_string(status))
+        .bind(product_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+
+        if let Some(product) = product {
+            emit_product_changed(&mut tx, product_id, PRODUCT_UPDATED)
+                .await
+                .map_err(AppError::from)?;
+            tx.commit().await.map_err(AppError::from)?;
+
+            self.invalidate(&[product_id]).await;
+            return Ok(product);
+        }
+
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM products WHERE id = $1 AND deleted_at IS NULL)",
+        )
+        .bind(product_id)
+        .fetch_one(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        Err(if exists {
+            error::failed_precondition(
+                "STATUS_UNCHANGED",
+                format!(
+                    "Product is already {}",
+                    status_to_string(status).to_lowercase()
+                ),
+            )
+        } else {
+            error::not_found("PRODUCT_NOT_FOUND", "Product not found")
+        })
+    }
+
+    /// Converts products to protos, loading their attributes and tags in one
+    /// query each.
+    async fn products_to_proto(&self, products: &[DbProduct]) -> Result<Vec<Product>, Status> {
+        let ids: Vec<String> = products.iter().map(|p| p.id.clone()).collect();
+        let mut attributes = self.repository.attributes(&ids).await?;
+        let mut tags = self.repository.tags(&ids).await?;
+
+        Ok(products
+            .iter()
+            .map(|p| {
+                let attrs = attributes.remove(&p.id).unwrap_or_default();
+                let tags = tags.remove(&p.id).unwrap_or_default();
+                self.db_product_to_proto(p, attrs, tags)
+            })
+            .collect())
+    }
+
+    async fn product_tags(&self, product_id: &str) -> Result<Vec<String>, Status> {
+        Ok(self
+            .repository
+            .tags(&[product_id.to_string()])
+            .await?
+            .remove(product_id)
+            .unwrap_or_default())
+    }
+
+    /// Normalized tags of a tag/untag request, or the message rejecting them.
+    fn request_tags(product_id: &str, tags: &[String]) -> Result<Vec<String>, String> {
+        if product_id.is_empty() {
+            return Err("Product ID is required".to_string());
+        }
+        let tags = normalize_tags(tags);
+        if tags.is_empty() {
+            return Err("At least one tag is required".to_string());
+        }
+        if let Some(tag) = tags.iter().find(|t| t.chars().count() > MAX_TAG_LENGTH) {
+            return Err(format!(
+                "Tag '{}' is longer than {} characters",
+                tag, MAX_TAG_LENGTH
+            ));
+        }
+        Ok(tags)
+    }
+
+    /// Loads the export page following `after_id`. Pages are keyed on the
+    /// product id so rows inserted mid-export can't shift later pages.
+    async fn export_page(
+        &self,
+        category: &str,
+        updated_since: Option<chrono::NaiveDateTime>,
+        after_id: Option<&str>,
+        page_size: i32,
+    ) -> Result<Vec<DbProduct>, Status> {
+        let mut query = QueryBuilder::<Postgres>::new(
+            "SELECT id, name, description, price, stock_quantity, category, warranty_months, rating_average, rating_count, sale_price, sale_starts_at, sale_ends_at, status, product_type, allow_backorder, available_from, seller_id, tax_class, weight_grams, deleted_at, created_at, updated_at 
+             FROM products WHERE deleted_at IS NULL",
+        );
+        if !category.is_empty() {
+            query
+                .push(" AND category = ")
+                .push_bind(category.to_string());
+        }
+        if let Some(updated_since) = updated_since {
+            query.push(" AND updated_at >= ").push_bind(updated_since);
+        }
+        if let Some(after_id) = after_id {
+            query.push(" AND id > ").push_bind(after_id.to_string());
         }
+        query
+            .push(" ORDER BY id LIMIT ")
+            .push_bind(page_size as i64);
+
+        query
+            .build_query_as::<DbProduct>()
+            .fetch_all(&self.db)
+            .await
+            .map_err(|e| AppError::from(e).into())
+    }
+
+    async fn product_to_proto(&self, product: &DbProduct) -> Result<Product, Status> {
+        let mut products = self
+            .products_to_proto(std::slice::from_ref(product))
+            .await?;
+        Ok(products.remove(0))
     }
 }
 
@@ -51,149 +693,207 @@ impl ProductService for ProductServiceImpl {
         &self,
         request: Request<AddProductRequest>,
     ) -> Result<Response<AddProductResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
         let req = request.into_inner();
 
-        // Validate input
-        if req.name.is_empty() {
-            return Ok(Response::new(AddProductResponse {
-                success: false,
-                message: "Product name is required".to_string(),
-                product_id: String::new(),
-            }));
-        }
-
-        if req.price < 0.0 {
-            return Ok(Response::new(AddProductResponse {
-                success: false,
-                message: "Price cannot be negative".to_string(),
-                product_id: String::new(),
-            }));
-        }
-
-        if req.stock_quantity < 0 {
-            return Ok(Response::new(AddProductResponse {
-                success: false,
-                message: "Stock quantity cannot be negative".to_string(),
-                product_id: String::new(),
-            }));
+        // Field rules are checked by the validation layer (see product.proto)
+        let seller_id = match &caller {
+            Caller::Platform => non_empty(&req.seller_id),
+            Caller::Seller { seller_id, .. } => Some(seller_id.clone()),
+            Caller::Customer(_) => {
+                return Err(Status::permission_denied(
+                    "Customers cannot change products",
+                ));
+            }
+            Caller::Anonymous => return Err(Status::unauthenticated("Sign in first")),
+        };
+        if caller == Caller::Platform
+            && let Some(seller_id) = &seller_id
+            && !self.repository.seller_exists(seller_id).await?
+        {
+            return Err(error::not_found("SELLER_NOT_FOUND", "Seller not found"));
         }
 
         let product_id = Uuid::new_v4().to_string();
-        let price_decimal = Decimal::from_f64_retain(req.price)
-            .ok_or_else(|| Status::invalid_argument("Invalid price value"))?;
+        let price_decimal = req
+            .price
+            .parse::<Decimal>()
+            .map_err(|_| Status::invalid_argument("Invalid price value"))?;
 
-        // Insert product into database
-        let result = sqlx::query(
-            "INSERT INTO products (id, name, description, price, stock_quantity, category) 
-             VALUES ($1, $2, $3, $4, $5, $6)",
-        )
-        .bind(&product_id)
-        .bind(&req.name)
-        .bind(if req.description.is_empty() {
-            None
-        } else {
-            Some(&req.description)
-        })
-        .bind(price_decimal)
-        .bind(req.stock_quantity)
-        .bind(if req.category.is_empty() {
-            None
-        } else {
-            Some(&req.category)
-        })
-        .execute(&self.db)
-        .await;
+        self.repository
+            .insert(NewProduct {
+                id: product_id.clone(),
+                name: req.name,
+                description: non_empty(&req.description),
+                price: price_decimal,
+                stock_quantity: req.stock_quantity,
+                category: non_empty(&req.category),
+                warranty_months: req.warranty_months,
+                product_type: type_to_string(
+                    ProductType::try_from(req.product_type).unwrap_or(ProductType::Physical),
+                ),
+                allow_backorder: req.allow_backorder,
+                available_from: release_time(req.available_from),
+                seller_id,
+                tax_class: tax_class(&req.tax_class),
+                weight_grams: req.weight_grams,
+            })
+            .await?;
 
-        match result {
-            Ok(_) => Ok(Response::new(AddProductResponse {
-                success: true,
-                message: "Product added successfully".to_string(),
-                product_id,
-            })),
-            Err(e) => Err(Status::internal(format!("Database error: {}", e))),
-        }
+        self.invalidate(&[&product_id]).await;
+        Ok(Response::new(AddProductResponse {
+            success: true,
+            message: "Product added as a draft".to_string(),
+            product_id,
+        }))
     }
 
     async fn update_product(
         &self,
         request: Request<UpdateProductRequest>,
     ) -> Result<Response<UpdateProductResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
         let req = request.into_inner();
+        self.authorize(&caller, &req.product_id).await?;
 
-        if req.product_id.is_empty() {
-            return Ok(Response::new(UpdateProductResponse {
-                success: false,
-                message: "Product ID is required".to_string(),
-                product: None,
-            }));
-        }
-
-        if req.price < 0.0 {
-            return Ok(Response::new(UpdateProductResponse {
-                success: false,
-                message: "Price cannot be negative".to_string(),
-                product: None,
-            }));
+        // Field rules are checked by the validation layer (see product.proto)
+        let fields = update_fields(&req)
+            .map_err(|message| error::invalid_argument("INVALID_UPDATE_MASK", message))?;
+        if fields.is_empty() {
+            return Err(error::invalid_argument(
+                "EMPTY_UPDATE",
+                "No fields to update",
+            ));
         }
 
-        if req.stock_quantity < 0 {
-            return Ok(Response::new(UpdateProductResponse {
-                success: false,
-                message: "Stock quantity cannot be negative".to_string(),
-                product: None,
-            }));
+        // Only the selected fields are written; the rest keep their values
+        let mut query = QueryBuilder::<Postgres>::new("UPDATE products SET ");
+        let mut set = query.separated(", ");
+        for field in &fields {
+            match *field {
+                "name" => {
+                    if req.name.trim().is_empty() {
+                        return Err(error::invalid_argument(
+                            "INVALID_NAME",
+                            "Name cannot be empty",
+                        ));
+                    }
+                    set.push("name = ").push_bind_unseparated(req.name.clone());
+                }
+                "description" => {
+                    set.push("description = ")
+                        .push_bind_unseparated(non_empty(&req.description));
+                }
+                "price" => {
+                    let price = match req.price.parse::<Decimal>() {
+                        Ok(price) => price,
+                        Err(_) => {
+                            return Err(error::invalid_argument(
+                                "INVALID_PRICE",
+                                "Price is required",
+                            ));
+                        }
+                    };
+                    set.push("price = ").push_bind_unseparated(price);
+                }
+                "stock_quantity" => {
+                    set.push("stock_quantity = ")
+                        .push_bind_unseparated(req.stock_quantity);
+                }
+                "category" => {
+                    set.push("category = ")
+                        .push_bind_unseparated(non_empty(&req.category));
+                }
+                "warranty_months" => {
+                    set.push("warranty_months = ")
+                        .push_bind_unseparated(req.warranty_months);
+                }
+                "allow_backorder" => {
+                    set.push("allow_backorder = ")
+                        .push_bind_unseparated(req.allow_backorder);
+                }
+                "available_from" => {
+                    set.push("available_from = ")
+                        .push_bind_unseparated(release_time(req.available_from));
+                }
+                "tax_class" => {
+                    set.push("tax_class = ")
+                        .push_bind_unseparated(tax_class(&req.tax_class));
+                }
+                "weight_grams" => {
+                    set.push("weight_grams = ")
+                        .push_bind_unseparated(req.weight_grams);
+                }
+                _ => unreachable!("update_fields only returns known fields"),
+            }
         }
+        set.push("updated_at = CURRENT_TIMESTAMP");
+        query
+            .push(" WHERE id = ")
+            .push_bind(req.product_id.clone())
+            .push(" AND deleted_at IS NULL");
 
-        let price_decimal = Decimal::from_f64_retain(req.price)
-            .ok_or_else(|| Status::invalid_argument("Invalid price value"))?;
+        let mut tx = self.db.begin().await.map_err(AppError::from)?;
 
-        // Update product in database
-        let result = sqlx::query(
-            "UPDATE products 
-             SET name = $1, description = $2, price = $3, stock_quantity = $4, 
-                 category = $5, updated_at = CURRENT_TIMESTAMP 
-             WHERE id = $6",
-        )
-        .bind(&req.name)
-        .bind(if req.description.is_empty() {
-            None
+        // A stock level set here is announced like any other stock change
+        let previous_stock: Option<i32> = if fields.contains(&"stock_quantity") {
+            sqlx::query_scalar(
+                "SELECT stock_quantity FROM products WHERE id = $1 AND deleted_at IS NULL FOR UPDATE",
+            )
+            .bind(&req.product_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(AppError::from)?
         } else {
-            Some(&req.description)
-        })
-        .bind(price_decimal)
-        .bind(req.stock_quantity)
-        .bind(if req.category.is_empty() {
             None
-        } else {
-            Some(&req.category)
-        })
-        .bind(&req.product_id)
-        .execute(&self.db)
-        .await
-        .map_err(|e| Status::internal(format!("Database error: {}", e)))?;
+        };
+
+        let result = query
+            .build()
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::from)?;
 
         if result.rows_affected() == 0 {
-            return Ok(Response::new(UpdateProductResponse {
-                success: false,
-                message: "Product not found".to_string(),
-                product: None,
-            }));
+            return Err(error::not_found("PRODUCT_NOT_FOUND", "Product not found"));
+        }
+
+        if let Some(previous) = previous_stock
+            && previous != req.stock_quantity
+        {
+            emit_stock_changed(
+                &mut tx,
+                &req.product_id,
+                req.stock_quantity,
+                req.stock_quantity - previous,
+                STOCK_INVENTORY_UPDATE,
+            )
+            .await
+            .map_err(AppError::from)?;
         }
 
+        emit_product_changed(&mut tx, &req.product_id, PRODUCT_UPDATED)
+            .await
+            .map_err(AppError::from)?;
+
+        tx.commit().await.map_err(AppError::from)?;
+
         // Fetch updated product
         let product = sqlx::query_as::<_, DbProduct>(
-            "SELECT id, name, description, price, stock_quantity, category, created_at, updated_at 
+            "SELECT id, name, description, price, stock_quantity, category, warranty_months, rating_average, rating_count, sale_price, sale_starts_at, sale_ends_at, status, product_type, allow_backorder, available_from, seller_id, tax_class, weight_grams, deleted_at, created_at, updated_at 
              FROM products WHERE id = $1",
         )
         .bind(&req.product_id)
         .fetch_one(&self.db)
         .await
-        .map_err(|e| Status::internal(format!("Database error: {}", e)))?;
+        .map_err(AppError::from)?;
+
+        self.invalidate(&[&req.product_id]).await;
 
         Ok(Response::new(UpdateProductResponse {
             success: true,
             message: "Product updated successfully".to_string(),
-            product: Some(self.db_product_to_proto(&product)),
+            product: Some(self.product_to_proto(&product).await?),
         }))
     }
 
@@ -201,28 +901,41 @@ impl ProductService for ProductServiceImpl {
         &self,
         request: Request<DeleteProductRequest>,
     ) -> Result<Response<DeleteProductResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
         let req = request.into_inner();
+        self.authorize(&caller, &req.product_id).await?;
 
         if req.product_id.is_empty() {
-            return Ok(Response::new(DeleteProductResponse {
-                success: false,
-                message: "Product ID is required".to_string(),
-            }));
+            return Err(error::invalid_argument(
+                "MISSING_PRODUCT_ID",
+                "Product ID is required",
+            ));
         }
 
-        let result = sqlx::query("DELETE FROM products WHERE id = $1")
-            .bind(&req.product_id)
-            .execute(&self.db)
-            .await
-            .map_err(|e| Status::internal(format!("Database error: {}", e)))?;
+        let mut tx = self.db.begin().await.map_err(AppError::from)?;
+
+        // Soft delete: order items keep referencing the row
+        let result = sqlx::query(
+            "UPDATE products SET deleted_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP
+             WHERE id = $1 AND deleted_at IS NULL",
+        )
+        .bind(&req.product_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
 
         if result.rows_affected() == 0 {
-            return Ok(Response::new(DeleteProductResponse {
-                success: false,
-                message: "Product not found".to_string(),
-            }));
+            return Err(error::not_found("PRODUCT_NOT_FOUND", "Product not found"));
         }
 
+        emit_product_changed(&mut tx, &req.product_id, PRODUCT_DELETED)
+            .await
+            .map_err(AppError::from)?;
+
+        tx.commit().await.map_err(AppError::from)?;
+
+        self.invalidate(&[&req.product_id]).await;
+
         Ok(Response::new(DeleteProductResponse {
             success: true,
             message: "Product deleted successfully".to_string(),
@@ -236,33 +949,34 @@ impl ProductService for ProductServiceImpl {
         let req = request.into_inner();
 
         if req.product_id.is_empty() {
-            return Ok(Response::new(GetProductResponse {
-                success: false,
-                message: "Product ID is required".to_string(),
-                product: None,
-            }));
+            return Err(error::invalid_argument(
+                "MISSING_PRODUCT_ID",
+                "Product ID is required",
+            ));
         }
 
-        let product_result = sqlx::query_as::<_, DbProduct>(
-            "SELECT id, name, description, price, stock_quantity, category, created_at, updated_at 
-             FROM products WHERE id = $1",
-        )
-        .bind(&req.product_id)
-        .fetch_optional(&self.db)
-        .await
-        .map_err(|e| Status::internal(format!("Database error: {}", e)))?;
+        // Deleted products are cached too, for get_products_by_ids
+        let product = match self.products.get::<Product>(&req.product_id).await {
+            Some(product) => Some(product),
+            None => match self.repository.find(&req.product_id).await? {
+                Some(product) => {
+                    let product = self.product_to_proto(&product).await?;
+                    self.products
+                        .set(&product.product_id, &product, cache_ttl(&product))
+                        .await;
+                    Some(product)
+                }
+                None => None,
+            },
+        };
 
-        match product_result {
-            Some(product) => Ok(Response::new(GetProductResponse {
+        match product {
+            Some(product) if !product.deleted => Ok(Response::new(GetProductResponse {
                 success: true,
                 message: "Product retrieved successfully".to_string(),
-                product: Some(self.db_product_to_proto(&product)),
-            })),
-            None => Ok(Response::new(GetProductResponse {
-                success: false,
-                message: "Product not found".to_string(),
-                product: None,
+                product: Some(product),
             })),
+            _ => Err(error::not_found("PRODUCT_NOT_FOUND", "Product not found")),
         }
     }
 
@@ -273,25 +987,60 @@ impl ProductService for ProductServiceImpl {
         let req = request.into_inner();
 
         if req.product_ids.is_empty() {
-            return Ok(Response::new(GetProductsByIDsResponse { products: vec![] }));
+            return Ok(Response::new(GetProductsByIDsResponse {
+                products: vec![],
+                missing_ids: vec![],
+            }));
         }
 
-        let products = sqlx::query_as::<_, DbProduct>(
-            "SELECT id, name, description, price, stock_quantity, category, created_at, updated_at 
-             FROM products WHERE id = ANY($1)",
-        )
-        .bind(&req.product_ids)
-        .fetch_all(&self.db)
-        .await
-        .map_err(|e| Status::internal(format!("Database error: {}", e)))?;
+        let mut seen = HashSet::new();
+        let ids: Vec<&str> = req
+            .product_ids
+            .iter()
+            .map(String::as_str)
+            .filter(|id| seen.insert(*id))
+            .collect();
 
-        let proto_products: Vec<Product> = products
+        // Only the products the cache misses are read
+        let cached = self.products.get_many::<Product>(&ids).await;
+        let uncached: Vec<&str> = ids
             .iter()
-            .map(|p| self.db_product_to_proto(p))
+            .zip(&cached)
+            .filter(|(_, product)| product.is_none())
+            .map(|(id, _)| *id)
             .collect();
 
+        let mut by_id: HashMap<String, Product> = if uncached.is_empty() {
+            HashMap::new()
+        } else {
+            // Deleted products are still resolved so historical orders can show them
+            let products = self.repository.find_many(&uncached).await?;
+
+            let products = self.products_to_proto(&products).await?;
+            let entries: Vec<_> = products
+                .iter()
+                .map(|p| (p.product_id.as_str(), p, cache_ttl(p)))
+                .collect();
+            self.products.set_many(&entries).await;
+            products
+                .into_iter()
+                .map(|p| (p.product_id.clone(), p))
+                .collect()
+        };
+
+        // Return products in the requested order, listing ids without a product
+        let mut products = Vec::with_capacity(ids.len());
+        let mut missing_ids = Vec::new();
+        for (id, cached) in ids.into_iter().zip(cached) {
+            match cached.or_else(|| by_id.remove(id)) {
+                Some(product) => products.push(product),
+                None => missing_ids.push(id.to_string()),
+            }
+        }
+
         Ok(Response::new(GetProductsByIDsResponse {
-            products: proto_products,
+            products,
+            missing_ids,
         }))
     }
 
@@ -301,68 +1050,188 @@ impl ProductService for ProductServiceImpl {
     ) -> Result<Response<ListProductsResponse>, Status> {
         let req = request.into_inner();
 
-        let page = if req.page <= 0 { 1 } else { req.page };
-        let page_size = if req.page_size <= 0 || req.page_size > 100 {
-            10
+        // Field rules are checked by the validation layer (see product.proto)
+        if let (Ok(min_price), Ok(max_price)) = (
+            req.min_price.parse::<Decimal>(),
+            req.max_price.parse::<Decimal>(),
+        ) && min_price > max_price
+        {
+            return Err(error::invalid_argument(
+                "INVALID_PRICE_RANGE",
+                "Minimum price cannot exceed maximum price",
+            ));
+        }
+
+        let page = PageRequest::new(req.page, req.page_size);
+        let resume = if req.page_token.is_empty() {
+            None
         } else {
-            req.page_size
+            match ListPosition::decode(&req.page_token) {
+                Some(position) => Some(position),
+                None => {
+                    return Err(error::invalid_argument(
+                        "INVALID_PAGE_TOKEN",
+                        "Invalid page token",
+                    ));
+                }
+            }
         };
-        let offset = (page - 1) * page_size;
-
-        // Build query based on category filter
-        let (products, total_count) = if req.category.is_empty() {
-            let products = sqlx::query_as::<_, DbProduct>(
-                "SELECT id, name, description, price, stock_quantity, category, created_at, updated_at 
-                 FROM products 
-                 ORDER BY created_at DESC 
-                 LIMIT $1 OFFSET $2",
-            )
-            .bind(page_size as i64)
-            .bind(offset as i64)
-            .fetch_all(&self.db)
-            .await
-            .map_err(|e| Status::internal(format!("Database error: {}", e)))?;
 
-            let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM products")
-                .fetch_one(&self.db)
-                .await
-                .map_err(|e| Status::internal(format!("Database error: {}", e)))?;
+        // Pins only hold their positions under the category's default sort
+        let requested = ProductSort::try_from(req.sort).unwrap_or(ProductSort::DefaultSort);
+        let (sort, pins) = match requested {
+            ProductSort::DefaultSort if !req.category.is_empty() => (
+                merchandising::default_sort(&self.db, &req.category).await?,
+                merchandising::pins(&self.db, &req.category).await?,
+            ),
+            sort => (sort, vec![]),
+        };
+        // A listing keeps the sort it started with, even if the category's
+        // default changes while a client pages through it
+        let sort = resume.as_ref().map_or(sort, |position| position.sort);
 
-            (products, count.0)
-        } else {
-            let products = sqlx::query_as::<_, DbProduct>(
-                "SELECT id, name, description, price, stock_quantity, category, created_at, updated_at 
-                 FROM products 
-                 WHERE category = $1 
-                 ORDER BY created_at DESC 
-                 LIMIT $2 OFFSET $3",
-            )
-            .bind(&req.category)
-            .bind(page_size as i64)
-            .bind(offset as i64)
-            .fetch_all(&self.db)
+        let mut count_query = QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM products");
+        push_list_filters(&mut count_query, &req, self.out_of_stock_policy);
+
+        let total_count = pagination::fetch_count(count_query, &self.db)
             .await
-            .map_err(|e| Status::internal(format!("Database error: {}", e)))?;
+            .map_err(AppError::from)?;
 
-            let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM products WHERE category = $1")
-                .bind(&req.category)
-                .fetch_one(&self.db)
+        let pinned_ids: Vec<String> = pins.iter().map(|(id, _)| id.clone()).collect();
+        let mut pinned = Vec::new();
+        if !pins.is_empty() {
+            // Pinned products still have to match the request's filters
+            let mut query = QueryBuilder::<Postgres>::new(
+                "SELECT id, name, description, price, stock_quantity, category, warranty_months, rating_average, rating_count, sale_price, sale_starts_at, sale_ends_at, status, product_type, allow_backorder, available_from, seller_id, tax_class, weight_grams, deleted_at, created_at, updated_at 
+                 FROM products",
+            );
+            push_list_filters(&mut query, &req, self.out_of_stock_policy);
+            query
+                .push(" AND id = ANY(")
+                .push_bind(pinned_ids.clone())
+                .push(")");
+
+            let mut matching: HashMap<String, DbProduct> = query
+                .build_query_as::<DbProduct>()
+                .fetch_all(&self.db)
                 .await
-                .map_err(|e| Status::internal(format!("Database error: {}", e)))?;
+                .map_err(AppError::from)?
+                .into_iter()
+                .map(|p| (p.id.clone(), p))
+                .collect();
 
-            (products, count.0)
-        };
+            let found: Vec<(i32, DbProduct)> = pins
+                .iter()
+                .filter_map(|(id, position)| Some((*position, matching.remove(id)?)))
+                .collect();
+
+            // Pins past the end of the listing move up to fill its last slots
+            let pin_count = found.len() as i64;
+            pinned = found
+                .into_iter()
+                .enumerate()
+                .map(|(i, (position, product))| {
+                    let last_free = total_count as i64 - (pin_count - 1 - i as i64);
+                    ((position as i64).min(last_free), product)
+                })
+                .collect();
+        }
 
-        let proto_products: Vec<Product> = products
+        let first_position = resume
+            .as_ref()
+            .map_or(page.offset() + 1, |position| position.next_slot);
+        let pins_before = pinned
             .iter()
-            .map(|p| self.db_product_to_proto(p))
+            .filter(|(position, _)| *position < first_position)
+            .count() as i64;
+        let page_pins: Vec<(i64, DbProduct)> = pinned
+            .into_iter()
+            .filter(|(position, _)| {
+                *position >= first_position && *position < first_position + page.limit()
+            })
             .collect();
 
+        let mut query = QueryBuilder::<Postgres>::new(
+            "SELECT id, name, description, price, stock_quantity, category, warranty_months, rating_average, rating_count, sale_price, sale_starts_at, sale_ends_at, status, product_type, allow_backorder, available_from, seller_id, tax_class, weight_grams, deleted_at, created_at, updated_at 
+             FROM products",
+        );
+        push_list_filters(&mut query, &req, self.out_of_stock_policy);
+        if !pinned_ids.is_empty() {
+            query
+                .push(" AND id <> ALL(")
+                .push_bind(pinned_ids)
+                .push(")");
+        }
+        if let Some((out_of_stock, cursor)) = resume.as_ref().and_then(|p| p.last.as_ref()) {
+            push_after(
+                &mut query,
+                sort,
+                self.out_of_stock_policy,
+                *out_of_stock,
+                cursor,
+            );
+        }
+        query.push(" ORDER BY ");
+        if self.out_of_stock_policy == OutOfStockPolicy::ShowLast {
+            query.push("stock_quantity <= 0, ");
+        }
+        query.push(merchandising::order_by(sort));
+
+        // A numbered page also reads the unpinned product before it, the
+        // position its next page token resumes from if it shows only pins
+        let unpinned_offset = page.offset() - pins_before;
+        let reads_previous = resume.is_none() && unpinned_offset > 0;
+        query
+            .push(" LIMIT ")
+            .push_bind(page.limit() - page_pins.len() as i64 + reads_previous as i64);
+        if resume.is_none() {
+            query
+                .push(" OFFSET ")
+                .push_bind(unpinned_offset - reads_previous as i64);
+        }
+
+        let mut unpinned = query
+            .build_query_as::<DbProduct>()
+            .fetch_all(&self.db)
+            .await
+            .map_err(AppError::from)?;
+        let previous = if reads_previous && !unpinned.is_empty() {
+            Some(unpinned.remove(0))
+        } else {
+            None
+        };
+
+        let next_slot = first_position + page_pins.len() as i64 + unpinned.len() as i64;
+        let last = match unpinned.last().or(previous.as_ref()) {
+            Some(product) => Some(self.list_cursor(sort, product).await?),
+            None => resume.and_then(|position| position.last),
+        };
+        let next_page_token = if next_slot <= total_count as i64 {
+            ListPosition {
+                sort,
+                next_slot,
+                last,
+            }
+            .encode()
+        } else {
+            String::new()
+        };
+
+        let products = merchandising::merge_pins(first_position, page_pins, unpinned);
+
+        let mut proto_products = self.products_to_proto(&products).await?;
+        if self.out_of_stock_policy == OutOfStockPolicy::Notify {
+            for product in &mut proto_products {
+                product.notify_me = product.stock_quantity <= 0;
+            }
+        }
+
         Ok(Response::new(ListProductsResponse {
             success: true,
             message: format!("Retrieved {} products", proto_products.len()),
             products: proto_products,
-            total_count: total_count as i32,
+            total_count,
+            next_page_token,
         }))
     }
 
@@ -372,126 +1241,1398 @@ impl ProductService for ProductServiceImpl {
     ) -> Result<Response<CheckAvailabilityResponse>, Status> {
         let req = request.into_inner();
 
-        if req.product_id.is_empty() {
-            return Ok(Response::new(CheckAvailabilityResponse {
+        let unavailable = |message: String, current_stock: i32| {
+            Response::new(CheckAvailabilityResponse {
                 available: false,
-                message: "Product ID is required".to_string(),
-                current_stock: 0,
-            }));
+                message,
+                current_stock,
+                state: AvailabilityState::Unavailable as i32,
+            })
+        };
+
+        if req.product_id.is_empty() {
+            return Ok(unavailable("Product ID is required".to_string(), 0));
         }
 
         let product_result = sqlx::query_as::<_, DbProduct>(
-            "SELECT id, name, description, price, stock_quantity, category, created_at, updated_at 
-             FROM products WHERE id = $1",
+            "SELECT id, name, description, price, stock_quantity, category, warranty_months, rating_average, rating_count, sale_price, sale_starts_at, sale_ends_at, status, product_type, allow_backorder, available_from, seller_id, tax_class, weight_grams, deleted_at, created_at, updated_at 
+             FROM products WHERE id = $1 AND deleted_at IS NULL",
         )
         .bind(&req.product_id)
         .fetch_optional(&self.db)
         .await
-        .map_err(|e| Status::internal(format!("Database error: {}", e)))?;
+        .map_err(AppError::from)?;
 
-        match product_result {
-            Some(product) => {
-                let available = product.stock_quantity >= req.quantity;
-                Ok(Response::new(CheckAvailabilityResponse {
-                    available,
-                    message: if available {
-                        "Product is available".to_string()
-                    } else {
-                        format!(
-                            "Insufficient stock. Available: {}, Requested: {}",
-                            product.stock_quantity, req.quantity
-                        )
-                    },
-                    current_stock: product.stock_quantity,
-                }))
-            }
-            None => Ok(Response::new(CheckAvailabilityResponse {
-                available: false,
-                message: "Product not found".to_string(),
-                current_stock: 0,
-            })),
+        // A quarantine of the whole product blocks new sales
+        let quarantine_reason: Option<String> = sqlx::query_scalar(
+            "SELECT reason FROM product_quarantines
+             WHERE product_id = $1 AND lot_number IS NULL AND released_at IS NULL
+             LIMIT 1",
+        )
+        .bind(&req.product_id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        let product = match product_result {
+            Some(product) => product,
+            None => return Ok(unavailable("Product not found".to_string(), 0)),
+        };
+
+        if let Some(reason) = quarantine_reason {
+            return Ok(unavailable(
+                format!("Product is quarantined: {}", reason),
+                product.stock_quantity,
+            ));
+        }
+
+        if product.status != "PUBLISHED" {
+            return Ok(unavailable(
+                "Product is not on sale".to_string(),
+                product.stock_quantity,
+            ));
         }
+
+        let state = product.availability(req.quantity, Utc::now());
+        let message = match state {
+            AvailabilityState::Available if product.product_type == "DIGITAL" => {
+                "Digital product is always available".to_string()
+            }
+            AvailabilityState::Available => "Product is available".to_string(),
+            AvailabilityState::Backorder => format!(
+                "Available on backorder. In stock: {}, Requested: {}",
+                product.stock_quantity, req.quantity
+            ),
+            AvailabilityState::Preorder => "Available for preorder".to_string(),
+            AvailabilityState::Unavailable => format!(
+                "Insufficient stock. Available: {}, Requested: {}",
+                product.stock_quantity, req.quantity
+            ),
+        };
+
+        Ok(Response::new(CheckAvailabilityResponse {
+            available: state != AvailabilityState::Unavailable,
+            message,
+            current_stock: product.stock_quantity,
+            state: state as i32,
+        }))
+    }
+
+    async fn check_availability_batch(
+        &self,
+        request: Request<CheckAvailabilityBatchRequest>,
+    ) -> Result<Response<CheckAvailabilityBatchResponse>, Status> {
+        let req = request.into_inner();
+
+        // Field rules are checked by the validation layer (see product.proto)
+        let stock = inventory::sellable_stock(&self.db, &req.product_ids)
+            .await
+            .map_err(AppError::from)?;
+        let backorders = inventory::backorder_terms(&self.db, &req.product_ids)
+            .await
+            .map_err(AppError::from)?;
+
+        let mut seen = HashSet::new();
+        let products: Vec<ProductAvailability> = req
+            .product_ids
+            .iter()
+            .filter(|id| seen.insert(id.as_str()))
+            .filter_map(|id| {
+                let terms = backorders.get(id).copied();
+                stock.get(id).map(|stock| ProductAvailability {
+                    product_id: id.clone(),
+                    sellable_stock: *stock,
+                    preorder: terms == Some(Backorder::Preorder),
+                    allow_backorder: terms == Some(Backorder::Allowed),
+                })
+            })
+            .collect();
+
+        Ok(Response::new(CheckAvailabilityBatchResponse {
+            success: true,
+            message: format!("Checked {} products", products.len()),
+            products,
+        }))
     }
 
     async fn update_inventory(
         &self,
         request: Request<UpdateInventoryRequest>,
     ) -> Result<Response<UpdateInventoryResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
         let req = request.into_inner();
+        self.authorize(&caller, &req.product_id).await?;
 
         if req.product_id.is_empty() {
-            return Ok(Response::new(UpdateInventoryResponse {
-                success: false,
-                message: "Product ID is required".to_string(),
-                new_stock_quantity: 0,
-            }));
+            return Err(error::invalid_argument(
+                "MISSING_PRODUCT_ID",
+                "Product ID is required",
+            ));
+        }
+
+        let new_stock = self
+            .repository
+            .adjust_stock(&req.product_id, req.quantity_change)
+            .await?;
+
+        self.invalidate(&[&req.product_id]).await;
+
+        Ok(Response::new(UpdateInventoryResponse {
+            success: true,
+            message: "Inventory updated successfully".to_string(),
+            new_stock_quantity: new_stock,
+        }))
+    }
+
+    async fn set_product_attributes(
+        &self,
+        request: Request<SetProductAttributesRequest>,
+    ) -> Result<Response<SetProductAttributesResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        let req = request.into_inner();
+        self.authorize(&caller, &req.product_id).await?;
+
+        if req.product_id.is_empty() {
+            return Err(error::invalid_argument(
+                "MISSING_PRODUCT_ID",
+                "Product ID is required",
+            ));
+        }
+
+        if req.attributes.iter().any(|a| a.name.trim().is_empty()) {
+            return Err(error::invalid_argument(
+                "INVALID_ATTRIBUTE",
+                "Attribute name cannot be empty",
+            ));
+        }
+
+        let mut tx = self.db.begin().await.map_err(AppError::from)?;
+
+        let exists: Option<String> =
+            sqlx::query_scalar("SELECT id FROM products WHERE id = $1 AND deleted_at IS NULL")
+                .bind(&req.product_id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(AppError::from)?;
+
+        if exists.is_none() {
+            tx.rollback().await.map_err(AppError::from)?;
+            return Err(error::not_found("PRODUCT_NOT_FOUND", "Product not found"));
         }
 
-        // Use transaction to ensure atomic update
-        let mut tx = self
-            .db
-            .begin()
+        if !req.remove_names.is_empty() {
+            sqlx::query("DELETE FROM product_attributes WHERE product_id = $1 AND name = ANY($2)")
+                .bind(&req.product_id)
+                .bind(&req.remove_names)
+                .execute(&mut *tx)
+                .await
+                .map_err(AppError::from)?;
+        }
+
+        for attribute in &req.attributes {
+            sqlx::query(
+                "INSERT INTO product_attributes (product_id, name, value) VALUES ($1, $2, $3)
+                 ON CONFLICT (product_id, name) DO UPDATE SET value = EXCLUDED.value",
+            )
+            .bind(&req.product_id)
+            .bind(attribute.name.trim())
+            .bind(&attribute.value)
+            .execute(&mut *tx)
             .await
-            .map_err(|e| Status::internal(format!("Transaction error: {}", e)))?;
+            .map_err(AppError::from)?;
+        }
 
-        // Get current stock
-        let product_result = sqlx::query_as::<_, DbProduct>(
-            "SELECT id, name, description, price, stock_quantity, category, created_at, updated_at 
-             FROM products WHERE id = $1 FOR UPDATE",
+        sqlx::query("UPDATE products SET updated_at = CURRENT_TIMESTAMP WHERE id = $1")
+            .bind(&req.product_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::from)?;
+
+        emit_product_changed(&mut tx, &req.product_id, PRODUCT_UPDATED)
+            .await
+            .map_err(AppError::from)?;
+
+        tx.commit().await.map_err(AppError::from)?;
+
+        self.invalidate(&[&req.product_id]).await;
+
+        let attributes = self
+            .repository
+            .attributes(std::slice::from_ref(&req.product_id))
+            .await?
+            .remove(&req.product_id)
+            .unwrap_or_default();
+
+        Ok(Response::new(SetProductAttributesResponse {
+            success: true,
+            message: "Product attributes updated successfully".to_string(),
+            attributes,
+        }))
+    }
+
+    async fn get_product_attributes(
+        &self,
+        request: Request<GetProductAttributesRequest>,
+    ) -> Result<Response<GetProductAttributesResponse>, Status> {
+        let req = request.into_inner();
+
+        if req.product_id.is_empty() {
+            return Err(error::invalid_argument(
+                "MISSING_PRODUCT_ID",
+                "Product ID is required",
+            ));
+        }
+
+        let attributes = self
+            .repository
+            .attributes(std::slice::from_ref(&req.product_id))
+            .await?
+            .remove(&req.product_id)
+            .unwrap_or_default();
+
+        Ok(Response::new(GetProductAttributesResponse {
+            success: true,
+            message: format!("Retrieved {} attributes", attributes.len()),
+            attributes,
+        }))
+    }
+
+    type ExportProductsStream = ReceiverStream<Result<ExportProductsResponse, Status>>;
+
+    async fn export_products(
+        &self,
+        request: Request<ExportProductsRequest>,
+    ) -> Result<Response<Self::ExportProductsStream>, Status> {
+        // The whole catalog, unpublished products included, is the platform's
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        let page_size = if req.page_size <= 0 || req.page_size > MAX_EXPORT_PAGE_SIZE {
+            DEFAULT_EXPORT_PAGE_SIZE
+        } else {
+            req.page_size
+        };
+        let updated_since = if req.updated_since > 0 {
+            let since = chrono::DateTime::from_timestamp(req.updated_since, 0)
+                .ok_or_else(|| Status::invalid_argument("Invalid updated_since timestamp"))?;
+            Some(since.naive_utc())
+        } else {
+            None
+        };
+
+        // A small channel keeps at most a few pages in memory; the producer
+        // waits for the client to catch up.
+        let (tx, rx) = mpsc::channel(4);
+        let service = self.clone();
+
+        tokio::spawn(async move {
+            let mut last_id: Option<String> = None;
+            loop {
+                let products = match service
+                    .export_page(&req.category, updated_since, last_id.as_deref(), page_size)
+                    .await
+                {
+                    Ok(products) if products.is_empty() => break,
+                    Ok(products) => products,
+                    Err(status) => {
+                        let _ = tx.send(Err(status)).await;
+                        break;
+                    }
+                };
+
+                let is_last_page = products.len() < page_size as usize;
+                last_id = products.last().map(|p| p.id.clone());
+
+                let message = service
+                    .products_to_proto(&products)
+                    .await
+                    .map(|products| ExportProductsResponse { products });
+                let failed = message.is_err();
+
+                // Stop once the client has gone away
+                if tx.send(message).await.is_err() || failed || is_last_page {
+                    break;
+                }
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    async fn schedule_restock(
+        &self,
+        request: Request<ScheduleRestockRequest>,
+    ) -> Result<Response<ScheduleRestockResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        let req = request.into_inner();
+        self.authorize(&caller, &req.product_id).await?;
+
+        if req.product_id.is_empty() || req.quantity <= 0 {
+            return Err(error::invalid_argument(
+                "INVALID_RESTOCK",
+                "Product ID and a positive quantity are required",
+            ));
+        }
+
+        let expected_date = match chrono::NaiveDate::parse_from_str(&req.expected_date, "%Y-%m-%d")
+        {
+            Ok(date) => date,
+            Err(_) => {
+                return Err(error::invalid_argument(
+                    "INVALID_DATE",
+                    "Expected date must be formatted as YYYY-MM-DD",
+                ));
+            }
+        };
+
+        let exists: Option<String> =
+            sqlx::query_scalar("SELECT id FROM products WHERE id = $1 AND deleted_at IS NULL")
+                .bind(&req.product_id)
+                .fetch_optional(&self.db)
+                .await
+                .map_err(AppError::from)?;
+
+        if exists.is_none() {
+            return Err(error::not_found("PRODUCT_NOT_FOUND", "Product not found"));
+        }
+
+        let restock_id = Uuid::new_v4().to_string();
+        let reference = if req.reference.is_empty() {
+            None
+        } else {
+            Some(req.reference)
+        };
+
+        sqlx::query(
+            "INSERT INTO product_restocks (id, product_id, quantity, expected_date, reference)
+             VALUES ($1, $2, $3, $4, $5)",
         )
+        .bind(&restock_id)
         .bind(&req.product_id)
+        .bind(req.quantity)
+        .bind(expected_date)
+        .bind(reference)
+        .execute(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        Ok(Response::new(ScheduleRestockResponse {
+            success: true,
+            message: "Restock scheduled successfully".to_string(),
+            restock_id,
+        }))
+    }
+
+    async fn receive_restock(
+        &self,
+        request: Request<ReceiveRestockRequest>,
+    ) -> Result<Response<ReceiveRestockResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        let req = request.into_inner();
+
+        if req.restock_id.is_empty() {
+            return Err(error::invalid_argument(
+                "MISSING_RESTOCK_ID",
+                "Restock ID is required",
+            ));
+        }
+
+        if caller != Caller::Platform {
+            let product_id: Option<String> =
+                sqlx::query_scalar("SELECT product_id FROM product_restocks WHERE id = $1")
+                    .bind(&req.restock_id)
+                    .fetch_optional(&self.db)
+                    .await
+                    .map_err(AppError::from)?;
+            if let Some(product_id) = product_id {
+                self.authorize(&caller, &product_id).await?;
+            }
+        }
+
+        let mut tx = self.db.begin().await.map_err(AppError::from)?;
+
+        // Marking the restock received first makes a repeated call a no-op
+        let restock: Option<(String, i32)> = sqlx::query_as(
+            "UPDATE product_restocks SET received_at = CURRENT_TIMESTAMP
+             WHERE id = $1 AND received_at IS NULL
+             RETURNING product_id, quantity",
+        )
+        .bind(&req.restock_id)
         .fetch_optional(&mut *tx)
         .await
-        .map_err(|e| Status::internal(format!("Database error: {}", e)))?;
+        .map_err(AppError::from)?;
 
-        let product = match product_result {
-            Some(p) => p,
+        let (product_id, quantity) = match restock {
+            Some(restock) => restock,
             None => {
-                tx.rollback()
-                    .await
-                    .map_err(|e| Status::internal(format!("Rollback error: {}", e)))?;
-                return Ok(Response::new(UpdateInventoryResponse {
-                    success: false,
-                    message: "Product not found".to_string(),
-                    new_stock_quantity: 0,
-                }));
+                tx.rollback().await.map_err(AppError::from)?;
+                return Err(error::not_found(
+                    "RESTOCK_NOT_FOUND",
+                    "Restock not found or already received",
+                ));
             }
         };
 
-        let new_stock = product.stock_quantity + req.quantity_change;
+        let new_stock: i32 = sqlx::query_scalar(
+            "UPDATE products SET stock_quantity = stock_quantity + $1, updated_at = CURRENT_TIMESTAMP
+             WHERE id = $2
+             RETURNING stock_quantity",
+        )
+        .bind(quantity)
+        .bind(&product_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
 
-        if new_stock < 0 {
-            tx.rollback()
-                .await
-                .map_err(|e| Status::internal(format!("Rollback error: {}", e)))?;
-            return Ok(Response::new(UpdateInventoryResponse {
-                success: false,
-                message: format!(
-                    "Insufficient stock. Current: {}, Change: {}",
-                    product.stock_quantity, req.quantity_change
-                ),
-                new_stock_quantity: product.stock_quantity,
-            }));
-        }
+        emit_stock_changed(
+            &mut tx,
+            &product_id,
+            new_stock,
+            quantity,
+            STOCK_RESTOCK_RECEIVED,
+        )
+        .await
+        .map_err(AppError::from)?;
 
-        // Update stock
-        sqlx::query(
-            "UPDATE products SET stock_quantity = $1, updated_at = CURRENT_TIMESTAMP WHERE id = $2",
+        tx.commit().await.map_err(AppError::from)?;
+
+        self.invalidate(&[&product_id]).await;
+
+        Ok(Response::new(ReceiveRestockResponse {
+            success: true,
+            message: format!("Received {} units", quantity),
+            new_stock_quantity: new_stock,
+        }))
+    }
+
+    async fn get_availability_timeline(
+        &self,
+        request: Request<GetAvailabilityTimelineRequest>,
+    ) -> Result<Response<GetAvailabilityTimelineResponse>, Status> {
+        let req = request.into_inner();
+
+        let current_stock: Option<i32> = sqlx::query_scalar(
+            "SELECT stock_quantity FROM products WHERE id = $1 AND deleted_at IS NULL",
         )
-        .bind(new_stock)
         .bind(&req.product_id)
-        .execute(&mut *tx)
+        .fetch_optional(&self.db)
         .await
-        .map_err(|e| Status::internal(format!("Database error: {}", e)))?;
+        .map_err(AppError::from)?;
 
-        tx.commit()
-            .await
-            .map_err(|e| Status::internal(format!("Commit error: {}", e)))?;
+        let current_stock = match current_stock {
+            Some(stock) => stock,
+            None => {
+                return Err(error::not_found("PRODUCT_NOT_FOUND", "Product not found"));
+            }
+        };
 
-        Ok(Response::new(UpdateInventoryResponse {
+        let incoming: Vec<(chrono::NaiveDate, i64)> = sqlx::query_as(
+            "SELECT expected_date, SUM(quantity)::BIGINT FROM product_restocks
+             WHERE product_id = $1 AND received_at IS NULL
+             GROUP BY expected_date
+             ORDER BY expected_date",
+        )
+        .bind(&req.product_id)
+        .fetch_all(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        let mut available = current_stock;
+        let entries: Vec<AvailabilityEntry> = incoming
+            .into_iter()
+            .map(|(date, quantity)| {
+                available += quantity as i32;
+                AvailabilityEntry {
+                    date: date.format("%Y-%m-%d").to_string(),
+                    incoming_quantity: quantity as i32,
+                    available_quantity: available,
+                }
+            })
+            .collect();
+
+        Ok(Response::new(GetAvailabilityTimelineResponse {
             success: true,
-            message: "Inventory updated successfully".to_string(),
-            new_stock_quantity: new_stock,
+            message: format!("{} pending restock dates", entries.len()),
+            current_stock,
+            entries,
         }))
     }
+
+    async fn restore_product(
+        &self,
+        request: Request<RestoreProductRequest>,
+    ) -> Result<Response<RestoreProductResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        let req = request.into_inner();
+        self.authorize(&caller, &req.product_id).await?;
+
+        if req.product_id.is_empty() {
+            return Err(error::invalid_argument(
+                "MISSING_PRODUCT_ID",
+                "Product ID is required",
+            ));
+        }
+
+        let mut tx = self.db.begin().await.map_err(AppError::from)?;
+
+        let product = sqlx::query_as::<_, DbProduct>(
+            "UPDATE products SET deleted_at = NULL, updated_at = CURRENT_TIMESTAMP
+             WHERE id = $1 AND deleted_at IS NOT NULL
+             RETURNING id, name, description, price, stock_quantity, category, warranty_months, rating_average, rating_count, sale_price, sale_starts_at, sale_ends_at, status, product_type, allow_backorder, available_from, seller_id, tax_class, weight_grams, deleted_at, created_at, updated_at",
+        )
+        .bind(&req.product_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+
+        match product {
+            Some(product) => {
+                emit_product_changed(&mut tx, &req.product_id, PRODUCT_UPDATED)
+                    .await
+                    .map_err(AppError::from)?;
+                tx.commit().await.map_err(AppError::from)?;
+
+                self.invalidate(&[&req.product_id]).await;
+                Ok(Response::new(RestoreProductResponse {
+                    success: true,
+                    message: "Product restored successfully".to_string(),
+                    product: Some(self.product_to_proto(&product).await?),
+                }))
+            }
+            None => Err(error::not_found(
+                "PRODUCT_NOT_FOUND",
+                "Deleted product not found",
+            )),
+        }
+    }
+
+    async fn get_stock_badge(
+        &self,
+        request: Request<GetStockBadgeRequest>,
+    ) -> Result<Response<GetStockBadgeResponse>, Status> {
+        let req = request.into_inner();
+
+        if req.product_ids.len() > MAX_STOCK_BADGES {
+            return Err(Status::invalid_argument(format!(
+                "At most {} product IDs can be requested at once",
+                MAX_STOCK_BADGES
+            )));
+        }
+
+        let levels = self.stock_badges.levels(&req.product_ids).await?;
+
+        let badges = req
+            .product_ids
+            .into_iter()
+            .filter_map(|product_id| {
+                let level = *levels.get(&product_id)?;
+                Some(StockBadge {
+                    product_id,
+                    level: level as i32,
+                })
+            })
+            .collect();
+
+        Ok(Response::new(GetStockBadgeResponse { badges }))
+    }
+    async fn get_related_products(
+        &self,
+        request: Request<GetRelatedProductsRequest>,
+    ) -> Result<Response<GetRelatedProductsResponse>, Status> {
+        let req = request.into_inner();
+
+        let limit = if req.limit <= 0 {
+            DEFAULT_RELATED_LIMIT
+        } else {
+            req.limit.min(MAX_RELATED_LIMIT)
+        };
+
+        let category: Option<Option<String>> = sqlx::query_scalar(
+            "SELECT category FROM products WHERE id = $1 AND deleted_at IS NULL",
+        )
+        .bind(&req.product_id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        let category = match category {
+            Some(category) => category,
+            None => {
+                return Err(error::not_found("PRODUCT_NOT_FOUND", "Product not found"));
+            }
+        };
+
+        let mut related = sqlx::query_as::<_, DbProduct>(
+            "SELECT p.id, p.name, p.description, p.price, p.stock_quantity, p.category, p.warranty_months, p.rating_average, p.rating_count, p.sale_price, p.sale_starts_at, p.sale_ends_at, p.status, p.product_type, p.allow_backorder, p.available_from, p.seller_id, p.tax_class, p.weight_grams, p.deleted_at, p.created_at, p.updated_at 
+             FROM product_co_purchases c
+             JOIN products p ON p.id = c.related_product_id
+             WHERE c.product_id = $1 AND p.deleted_at IS NULL AND p.status = 'PUBLISHED'
+             ORDER BY c.order_count DESC, p.id
+             LIMIT $2",
+        )
+        .bind(&req.product_id)
+        .bind(limit as i64)
+        .fetch_all(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        // Products with few orders get same-category suggestions instead
+        if let Some(category) = category
+            && related.len() < limit as usize
+        {
+            let mut exclude: Vec<String> = related.iter().map(|p| p.id.clone()).collect();
+            exclude.push(req.product_id.clone());
+
+            let fallback = sqlx::query_as::<_, DbProduct>(
+                "SELECT id, name, description, price, stock_quantity, category, warranty_months, rating_average, rating_count, sale_price, sale_starts_at, sale_ends_at, status, product_type, allow_backorder, available_from, seller_id, tax_class, weight_grams, deleted_at, created_at, updated_at 
+                 FROM products
+                 WHERE category = $1 AND deleted_at IS NULL AND status = 'PUBLISHED' AND id <> ALL($2)
+                 ORDER BY rating_average DESC, created_at DESC
+                 LIMIT $3",
+            )
+            .bind(&category)
+            .bind(&exclude)
+            .bind((limit as usize - related.len()) as i64)
+            .fetch_all(&self.db)
+            .await
+            .map_err(AppError::from)?;
+
+            related.extend(fallback);
+        }
+
+        let products = self.products_to_proto(&related).await?;
+
+        Ok(Response::new(GetRelatedProductsResponse {
+            success: true,
+            message: format!("Found {} related products", products.len()),
+            products,
+        }))
+    }
+
+    async fn set_category_sort(
+        &self,
+        request: Request<SetCategorySortRequest>,
+    ) -> Result<Response<SetCategorySortResponse>, Status> {
+        // Category merchandising is the platform's, not a seller's
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        if req.category.is_empty() {
+            return Err(error::invalid_argument(
+                "MISSING_CATEGORY",
+                "Category is required",
+            ));
+        }
+
+        let sort = ProductSort::try_from(req.sort).unwrap_or(ProductSort::DefaultSort);
+        if sort == ProductSort::DefaultSort {
+            sqlx::query("DELETE FROM category_settings WHERE category = $1")
+                .bind(&req.category)
+                .execute(&self.db)
+                .await
+                .map_err(AppError::from)?;
+        } else {
+            sqlx::query(
+                "INSERT INTO category_settings (category, default_sort) VALUES ($1, $2)
+                 ON CONFLICT (category) DO UPDATE
+                 SET default_sort = EXCLUDED.default_sort, updated_at = CURRENT_TIMESTAMP",
+            )
+            .bind(&req.category)
+            .bind(merchandising::sort_to_string(sort))
+            .execute(&self.db)
+            .await
+            .map_err(AppError::from)?;
+        }
+
+        self.cache.invalidate_prefix(PRODUCT_CACHE_PREFIX);
+
+        Ok(Response::new(SetCategorySortResponse {
+            success: true,
+            message: format!(
+                "Category {} is now sorted by {}",
+                req.category,
+                merchandising::sort_to_string(sort)
+            ),
+        }))
+    }
+
+    async fn pin_product(
+        &self,
+        request: Request<PinProductRequest>,
+    ) -> Result<Response<PinProductResponse>, Status> {
+        // Category merchandising is the platform's, not a seller's
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        if req.category.is_empty() || req.product_id.is_empty() {
+            return Err(error::invalid_argument(
+                "INVALID_PIN",
+                "Category and product ID are required",
+            ));
+        }
+        if req.position <= 0 {
+            return Err(error::invalid_argument(
+                "INVALID_PIN",
+                "Position must be at least 1",
+            ));
+        }
+
+        let category: Option<Option<String>> = sqlx::query_scalar(
+            "SELECT category FROM products WHERE id = $1 AND deleted_at IS NULL",
+        )
+        .bind(&req.product_id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        match category {
+            None => return Err(error::not_found("PRODUCT_NOT_FOUND", "Product not found")),
+            Some(category) if category.as_deref() != Some(req.category.as_str()) => {
+                return Err(error::failed_precondition(
+                    "NOT_IN_CATEGORY",
+                    format!(
+                        "Product {} is not in category {}",
+                        req.product_id, req.category
+                    ),
+                ));
+            }
+            Some(_) => {}
+        }
+
+        let mut tx = self.db.begin().await.map_err(AppError::from)?;
+
+        // Serializes pin changes per category so the cap and positions hold
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext('category_pins:' || $1))")
+            .bind(&req.category)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::from)?;
+
+        let (pin_count, taken_by): (i64, Option<String>) = sqlx::query_as(
+            "SELECT COUNT(*) FILTER (WHERE product_id <> $2),
+                    MAX(product_id) FILTER (WHERE position = $3 AND product_id <> $2)
+             FROM category_pins WHERE category = $1",
+        )
+        .bind(&req.category)
+        .bind(&req.product_id)
+        .bind(req.position)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+
+        if let Some(other) = taken_by {
+            tx.rollback().await.map_err(AppError::from)?;
+            return Err(error::failed_precondition(
+                "PIN_POSITION_TAKEN",
+                format!(
+                    "Position {} is already taken by product {}",
+                    req.position, other
+                ),
+            ));
+        }
+        if pin_count >= merchandising::MAX_PINS_PER_CATEGORY {
+            tx.rollback().await.map_err(AppError::from)?;
+            return Err(error::failed_precondition(
+                "TOO_MANY_PINS",
+                format!(
+                    "At most {} products can be pinned per category",
+                    merchandising::MAX_PINS_PER_CATEGORY
+                ),
+            ));
+        }
+
+        sqlx::query(
+            "INSERT INTO category_pins (category, product_id, position) VALUES ($1, $2, $3)
+             ON CONFLICT (category, product_id) DO UPDATE SET position = EXCLUDED.position",
+        )
+        .bind(&req.category)
+        .bind(&req.product_id)
+        .bind(req.position)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+
+        tx.commit().await.map_err(AppError::from)?;
+
+        self.cache.invalidate_prefix(PRODUCT_CACHE_PREFIX);
+
+        Ok(Response::new(PinProductResponse {
+            success: true,
+            message: format!(
+                "Product {} pinned at position {} in {}",
+                req.product_id, req.position, req.category
+            ),
+        }))
+    }
+
+    async fn unpin_product(
+        &self,
+        request: Request<UnpinProductRequest>,
+    ) -> Result<Response<UnpinProductResponse>, Status> {
+        // Category merchandising is the platform's, not a seller's
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        let result =
+            sqlx::query("DELETE FROM category_pins WHERE category = $1 AND product_id = $2")
+                .bind(&req.category)
+                .bind(&req.product_id)
+                .execute(&self.db)
+                .await
+                .map_err(AppError::from)?;
+
+        if result.rows_affected() == 0 {
+            return Err(error::failed_precondition(
+                "PRODUCT_NOT_PINNED",
+                "Product is not pinned in this category",
+            ));
+        }
+
+        self.cache.invalidate_prefix(PRODUCT_CACHE_PREFIX);
+
+        Ok(Response::new(UnpinProductResponse {
+            success: true,
+            message: "Product unpinned successfully".to_string(),
+        }))
+    }
+
+    async fn get_category_merchandising(
+        &self,
+        request: Request<GetCategoryMerchandisingRequest>,
+    ) -> Result<Response<GetCategoryMerchandisingResponse>, Status> {
+        let req = request.into_inner();
+
+        let default_sort = merchandising::default_sort(&self.db, &req.category).await?;
+        let pins = merchandising::pins(&self.db, &req.category).await?;
+
+        Ok(Response::new(GetCategoryMerchandisingResponse {
+            default_sort: default_sort as i32,
+            pins: pins
+                .into_iter()
+                .map(|(product_id, position)| CategoryPin {
+                    product_id,
+                    position,
+                })
+                .collect(),
+        }))
+    }
+    async fn tag_product(
+        &self,
+        request: Request<TagProductRequest>,
+    ) -> Result<Response<TagProductResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        let req = request.into_inner();
+        self.authorize(&caller, &req.product_id).await?;
+
+        let tags = Self::request_tags(&req.product_id, &req.tags)
+            .map_err(|message| error::invalid_argument("INVALID_TAGS", message))?;
+
+        let mut tx = self.db.begin().await.map_err(AppError::from)?;
+
+        let exists: Option<String> =
+            sqlx::query_scalar("SELECT id FROM products WHERE id = $1 AND deleted_at IS NULL")
+                .bind(&req.product_id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(AppError::from)?;
+
+        if exists.is_none() {
+            tx.rollback().await.map_err(AppError::from)?;
+            return Err(error::not_found("PRODUCT_NOT_FOUND", "Product not found"));
+        }
+
+        for tag in &tags {
+            sqlx::query(
+                "INSERT INTO tags (id, name) VALUES ($1, $2) ON CONFLICT (name) DO NOTHING",
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(tag)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::from)?;
+        }
+
+        sqlx::query(
+            "INSERT INTO product_tags (product_id, tag_id)
+             SELECT $1, id FROM tags WHERE name = ANY($2)
+             ON CONFLICT DO NOTHING",
+        )
+        .bind(&req.product_id)
+        .bind(&tags)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+
+        sqlx::query("UPDATE products SET updated_at = CURRENT_TIMESTAMP WHERE id = $1")
+            .bind(&req.product_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::from)?;
+
+        emit_product_changed(&mut tx, &req.product_id, PRODUCT_UPDATED)
+            .await
+            .map_err(AppError::from)?;
+
+        tx.commit().await.map_err(AppError::from)?;
+
+        self.invalidate(&[&req.product_id]).await;
+
+        Ok(Response::new(TagProductResponse {
+            success: true,
+            message: "Product tagged successfully".to_string(),
+            tags: self.product_tags(&req.product_id).await?,
+        }))
+    }
+
+    async fn untag_product(
+        &self,
+        request: Request<UntagProductRequest>,
+    ) -> Result<Response<UntagProductResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        let req = request.into_inner();
+        self.authorize(&caller, &req.product_id).await?;
+
+        let tags = Self::request_tags(&req.product_id, &req.tags)
+            .map_err(|message| error::invalid_argument("INVALID_TAGS", message))?;
+
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM products WHERE id = $1 AND deleted_at IS NULL)",
+        )
+        .bind(&req.product_id)
+        .fetch_one(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        if !exists {
+            return Err(error::not_found("PRODUCT_NOT_FOUND", "Product not found"));
+        }
+
+        let mut tx = self.db.begin().await.map_err(AppError::from)?;
+
+        let result = sqlx::query(
+            "DELETE FROM product_tags
+             WHERE product_id = $1 AND tag_id IN (SELECT id FROM tags WHERE name = ANY($2))",
+        )
+        .bind(&req.product_id)
+        .bind(&tags)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+
+        if result.rows_affected() > 0 {
+            sqlx::query("UPDATE products SET updated_at = CURRENT_TIMESTAMP WHERE id = $1")
+                .bind(&req.product_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(AppError::from)?;
+
+            emit_product_changed(&mut tx, &req.product_id, PRODUCT_UPDATED)
+                .await
+                .map_err(AppError::from)?;
+        }
+
+        tx.commit().await.map_err(AppError::from)?;
+
+        if result.rows_affected() > 0 {
+            self.invalidate(&[&req.product_id]).await;
+        }
+
+        Ok(Response::new(UntagProductResponse {
+            success: true,
+            message: format!("Removed {} tags", result.rows_affected()),
+            tags: self.product_tags(&req.product_id).await?,
+        }))
+    }
+    async fn schedule_sale(
+        &self,
+        request: Request<ScheduleSaleRequest>,
+    ) -> Result<Response<ScheduleSaleResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        let req = request.into_inner();
+        self.authorize(&caller, &req.product_id).await?;
+
+        // Field rules are checked by the validation layer (see product.proto)
+        let starts_at = match req.starts_at {
+            0 => None,
+            secs => match DateTime::from_timestamp(secs, 0) {
+                Some(t) => Some(t),
+                None => {
+                    return Err(error::invalid_argument(
+                        "INVALID_SALE_WINDOW",
+                        "Invalid sale start",
+                    ));
+                }
+            },
+        };
+        let ends_at = match req.ends_at {
+            0 => None,
+            secs => match DateTime::from_timestamp(secs, 0) {
+                Some(t) => Some(t),
+                None => {
+                    return Err(error::invalid_argument(
+                        "INVALID_SALE_WINDOW",
+                        "Invalid sale end",
+                    ));
+                }
+            },
+        };
+        if let Some(ends_at) = ends_at {
+            if ends_at <= Utc::now() {
+                return Err(error::invalid_argument(
+                    "INVALID_SALE_WINDOW",
+                    "Sale end must be in the future",
+                ));
+            }
+            if starts_at.is_some_and(|starts_at| ends_at <= starts_at) {
+                return Err(error::invalid_argument(
+                    "INVALID_SALE_WINDOW",
+                    "Sale must end after it starts",
+                ));
+            }
+        }
+
+        let sale_price = req
+            .sale_price
+            .parse::<Decimal>()
+            .map_err(|_| Status::invalid_argument("Invalid sale price value"))?
+            .round_dp(2);
+
+        let regular_price: Option<Decimal> =
+            sqlx::query_scalar("SELECT price FROM products WHERE id = $1 AND deleted_at IS NULL")
+                .bind(&req.product_id)
+                .fetch_optional(&self.db)
+                .await
+                .map_err(AppError::from)?;
+
+        match regular_price {
+            None => return Err(error::not_found("PRODUCT_NOT_FOUND", "Product not found")),
+            Some(price) if sale_price >= price => {
+                return Err(error::failed_precondition(
+                    "SALE_PRICE_TOO_HIGH",
+                    "Sale price must be below the regular price",
+                ));
+            }
+            Some(_) => {}
+        }
+
+        let mut tx = self.db.begin().await.map_err(AppError::from)?;
+
+        let product = sqlx::query_as::<_, DbProduct>(
+            "UPDATE products
+             SET sale_price = $1, sale_starts_at = $2, sale_ends_at = $3, updated_at = CURRENT_TIMESTAMP
+             WHERE id = $4
+             RETURNING id, name, description, price, stock_quantity, category, warranty_months, rating_average, rating_count, sale_price, sale_starts_at, sale_ends_at, status, product_type, allow_backorder, available_from, seller_id, tax_class, weight_grams, deleted_at, created_at, updated_at",
+        )
+        .bind(sale_price)
+        .bind(starts_at)
+        .bind(ends_at)
+        .bind(&req.product_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+
+        emit_product_changed(&mut tx, &req.product_id, PRODUCT_UPDATED)
+            .await
+            .map_err(AppError::from)?;
+
+        tx.commit().await.map_err(AppError::from)?;
+
+        self.invalidate(&[&req.product_id]).await;
+
+        Ok(Response::new(ScheduleSaleResponse {
+            success: true,
+            message: "Sale scheduled successfully".to_string(),
+            product: Some(self.product_to_proto(&product).await?),
+        }))
+    }
+
+    async fn cancel_sale(
+        &self,
+        request: Request<CancelSaleRequest>,
+    ) -> Result<Response<CancelSaleResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        let req = request.into_inner();
+        self.authorize(&caller, &req.product_id).await?;
+
+        let mut tx = self.db.begin().await.map_err(AppError::from)?;
+
+        let product = sqlx::query_as::<_, DbProduct>(
+            "UPDATE products
+             SET sale_price = NULL, sale_starts_at = NULL, sale_ends_at = NULL, updated_at = CURRENT_TIMESTAMP
+             WHERE id = $1 AND deleted_at IS NULL AND sale_price IS NOT NULL
+             RETURNING id, name, description, price, stock_quantity, category, warranty_months, rating_average, rating_count, sale_price, sale_starts_at, sale_ends_at, status, product_type, allow_backorder, available_from, seller_id, tax_class, weight_grams, deleted_at, created_at, updated_at",
+        )
+        .bind(&req.product_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+
+        let product = match product {
+            Some(product) => product,
+            None => {
+                return Err(error::failed_precondition(
+                    "NOT_ON_SALE",
+                    "Product not found or not on sale",
+                ));
+            }
+        };
+
+        emit_product_changed(&mut tx, &req.product_id, PRODUCT_UPDATED)
+            .await
+            .map_err(AppError::from)?;
+
+        tx.commit().await.map_err(AppError::from)?;
+
+        self.invalidate(&[&req.product_id]).await;
+
+        Ok(Response::new(CancelSaleResponse {
+            success: true,
+            message: "Sale cancelled successfully".to_string(),
+            product: Some(self.product_to_proto(&product).await?),
+        }))
+    }
+
+    async fn publish_product(
+        &self,
+        request: Request<PublishProductRequest>,
+    ) -> Result<Response<PublishProductResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        let req = request.into_inner();
+        self.authorize(&caller, &req.product_id).await?;
+
+        let product = self
+            .set_status(&req.product_id, ProductStatus::Published)
+            .await?;
+
+        Ok(Response::new(PublishProductResponse {
+            success: true,
+            message: "Product published successfully".to_string(),
+            product: Some(self.product_to_proto(&product).await?),
+        }))
+    }
+
+    async fn archive_product(
+        &self,
+        request: Request<ArchiveProductRequest>,
+    ) -> Result<Response<ArchiveProductResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        let req = request.into_inner();
+        self.authorize(&caller, &req.product_id).await?;
+
+        let product = self
+            .set_status(&req.product_id, ProductStatus::Archived)
+            .await?;
+
+        Ok(Response::new(ArchiveProductResponse {
+            success: true,
+            message: "Product archived successfully".to_string(),
+            product: Some(self.product_to_proto(&product).await?),
+        }))
+    }
+
+    async fn get_category_stats(
+        &self,
+        _request: Request<GetCategoryStatsRequest>,
+    ) -> Result<Response<GetCategoryStatsResponse>, Status> {
+        let stats = sqlx::query_as::<_, DbCategoryStats>(&format!(
+            "SELECT category,
+                    COUNT(*) AS product_count,
+                    COUNT(*) FILTER (WHERE stock_quantity > 0) AS in_stock_count,
+                    MIN({price}) AS min_price,
+                    MAX({price}) AS max_price
+             FROM products
+             WHERE deleted_at IS NULL AND status = 'PUBLISHED'
+               AND category IS NOT NULL AND category <> ''
+             GROUP BY category
+             ORDER BY category",
+            price = EFFECTIVE_PRICE
+        ))
+        .fetch_all(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        Ok(Response::new(GetCategoryStatsResponse {
+            categories: stats.iter().map(DbCategoryStats::to_proto).collect(),
+        }))
+    }
+
+    async fn create_seller(
+        &self,
+        request: Request<CreateSellerRequest>,
+    ) -> Result<Response<CreateSellerResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        // Field rules are checked by the validation layer (see product.proto)
+        let seller = sqlx::query_as::<_, DbSeller>(
+            "INSERT INTO sellers (id, name, user_id)
+             SELECT $1, $2, id FROM users WHERE id = $3
+             ON CONFLICT (user_id) DO NOTHING
+             RETURNING id, name, user_id, created_at",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(&req.name)
+        .bind(&req.user_id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        match seller {
+            Some(seller) => Ok(Response::new(CreateSellerResponse {
+                success: true,
+                message: "Seller created successfully".to_string(),
+                seller: Some(seller.to_proto()),
+            })),
+            None => Err(error::failed_precondition(
+                "SELLER_NOT_ALLOWED",
+                "User not found or already a seller",
+            )),
+        }
+    }
+
+    async fn list_products_by_seller(
+        &self,
+        request: Request<ListProductsBySellerRequest>,
+    ) -> Result<Response<ListProductsBySellerResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        let req = request.into_inner();
+
+        // Field rules are checked by the validation layer (see product.proto)
+        let page = PageRequest::new(req.page, req.page_size);
+
+        // Sellers and the platform also see drafts and archived products
+        let all_statuses =
+            caller == Caller::Platform || caller.seller_id() == Some(req.seller_id.as_str());
+        let push_filters = |qb: &mut QueryBuilder<'_, Postgres>| {
+            qb.push(" WHERE deleted_at IS NULL AND seller_id = ")
+                .push_bind(req.seller_id.clone());
+            if !all_statuses {
+                qb.push(" AND status = 'PUBLISHED'");
+            }
+        };
+
+        let mut query = QueryBuilder::<Postgres>::new(
+            "SELECT id, name, description, price, stock_quantity, category, warranty_months, rating_average, rating_count, sale_price, sale_starts_at, sale_ends_at, status, product_type, allow_backorder, available_from, seller_id, tax_class, weight_grams, deleted_at, created_at, updated_at 
+             FROM products",
+        );
+        push_filters(&mut query);
+        query.push(" ORDER BY created_at DESC, id DESC");
+        page.push_limit_offset(&mut query);
+
+        let products = query
+            .build_query_as::<DbProduct>()
+            .fetch_all(&self.db)
+            .await
+            .map_err(AppError::from)?;
+
+        let mut count_query = QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM products");
+        push_filters(&mut count_query);
+
+        let total_count = pagination::fetch_count(count_query, &self.db)
+            .await
+            .map_err(AppError::from)?;
+
+        let products = self.products_to_proto(&products).await?;
+
+        Ok(Response::new(ListProductsBySellerResponse {
+            success: true,
+            message: format!("Retrieved {} products", products.len()),
+            products,
+            total_count,
+        }))
+    }
+
+    async fn reserve_stock(
+        &self,
+        request: Request<ReserveStockRequest>,
+    ) -> Result<Response<ReserveStockResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        // Field rules are checked by the validation layer (see product.proto)
+        let reserved = reservation::reserve(&self.db, &req.reservation_id, &req.lines)
+            .await
+            .map_err(AppError::from)?;
+
+        match reserved {
+            Ok(lines) => {
+                let product_ids: Vec<&str> = lines.iter().map(|l| l.product_id.as_str()).collect();
+                self.invalidate(&product_ids).await;
+                Ok(Response::new(ReserveStockResponse {
+                    success: true,
+                    message: "Stock reserved".to_string(),
+                    lines,
+                }))
+            }
+            Err(message) => Err(error::failed_precondition("RESERVATION_REFUSED", message)),
+        }
+    }
+
+    async fn confirm_reservation(
+        &self,
+        request: Request<ConfirmReservationRequest>,
+    ) -> Result<Response<ConfirmReservationResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        let confirmed = reservation::confirm(&self.db, &req.reservation_id)
+            .await
+            .map_err(AppError::from)?;
+
+        confirmed.map_err(|message| error::failed_precondition("RESERVATION_REFUSED", message))?;
+
+        Ok(Response::new(ConfirmReservationResponse {
+            success: true,
+            message: "Reservation confirmed".to_string(),
+        }))
+    }
+
+    async fn release_reservation(
+        &self,
+        request: Request<ReleaseReservationRequest>,
+    ) -> Result<Response<ReleaseReservationResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        let released = reservation::release(&self.db, &req.reservation_id)
+            .await
+            .map_err(AppError::from)?;
+
+        match released {
+            Ok(product_ids) => {
+                let product_ids: Vec<&str> = product_ids.iter().map(String::as_str).collect();
+                self.invalidate(&product_ids).await;
+                Ok(Response::new(ReleaseReservationResponse {
+                    success: true,
+                    message: "Reservation released".to_string(),
+                }))
+            }
+            Err(message) => Err(error::failed_precondition("RESERVATION_REFUSED", message)),
+        }
+    }
+
+    async fn return_stock(
+        &self,
+        request: Request<ReturnStockRequest>,
+    ) -> Result<Response<ReturnStockResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        // Field rules are checked by the validation layer (see product.proto)
+        let returned = reservation::return_stock(&self.db, &req.return_id, &req.lines)
+            .await
+            .map_err(AppError::from)?;
+
+        match returned {
+            Ok(product_ids) => {
+                let product_ids: Vec<&str> = product_ids.iter().map(String::as_str).collect();
+                self.invalidate(&product_ids).await;
+                Ok(Response::new(ReturnStockResponse {
+                    success: true,
+                    message: "Stock returned".to_string(),
+                }))
+            }
+            Err(message) => Err(error::invalid_argument("INVALID_RETURN", message)),
+        }
+    }
 }
@@ -0,0 +1,38 @@
+use sqlx::PgPool;
+use std::time::Duration;
+use tracing::error;
+
+/// Keeps the `product_co_purchases` materialized view current. Co-purchase
+/// counts only drift slowly, so a periodic refresh is enough and spares
+/// GetRelatedProducts from scanning order items.
+pub struct CoPurchaseRefresher {
+    db: PgPool,
+}
+
+impl CoPurchaseRefresher {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn run(&self) -> Result<(), sqlx::Error> {
+        // CONCURRENTLY keeps the view readable while it is rebuilt
+        sqlx::query("REFRESH MATERIALIZED VIEW CONCURRENTLY product_co_purchases")
+            .execute(&self.db)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Spawns a background task that refreshes the view every `interval`.
+    pub fn spawn(self, interval: Duration) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.run().await {
+                    error!("Co-purchase refresh failed: {}", e);
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,413 @@
+//! Where the product service keeps the product records its lookups, new
+//! products and stock updates go through. `PostgresProductRepository` is
+//! the production one; `InMemoryProductRepository` lets tests run those
+//! handlers without a database.
+
+use crate::product::{PRODUCT_CREATED, emit_product_changed};
+use crate::reservation::{STOCK_INVENTORY_UPDATE, emit_stock_changed};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use common::error::{self, AppError};
+use proto::product::ProductAttribute;
+use sqlx::PgPool;
+use sqlx::types::Decimal;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use tonic::Status;
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct DbProduct {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub price: Decimal,
+    pub stock_quantity: i32,
+    pub category: Option<String>,
+    pub warranty_months: i32,
+    pub rating_average: Decimal,
+    pub rating_count: i32,
+    pub sale_price: Option<Decimal>,
+    pub sale_starts_at: Option<DateTime<Utc>>,
+    pub sale_ends_at: Option<DateTime<Utc>>,
+    pub status: String,
+    pub product_type: String,
+    pub allow_backorder: bool,
+    pub available_from: Option<DateTime<Utc>>,
+    pub seller_id: Option<String>,
+    pub tax_class: String,
+    pub weight_grams: i32,
+    pub deleted_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct DbProductAttribute {
+    product_id: String,
+    name: String,
+    value: String,
+}
+
+/// A product about to be written; it starts out a draft.
+pub struct NewProduct {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub price: Decimal,
+    pub stock_quantity: i32,
+    pub category: Option<String>,
+    pub warranty_months: i32,
+    pub product_type: &'static str,
+    pub allow_backorder: bool,
+    pub available_from: Option<DateTime<Utc>>,
+    pub seller_id: Option<String>,
+    pub tax_class: String,
+    pub weight_grams: i32,
+}
+
+#[tonic::async_trait]
+pub trait ProductRepository: Send + Sync {
+    /// The product, deleted or not.
+    async fn find(&self, product_id: &str) -> Result<Option<DbProduct>, Status>;
+
+    /// Products of `product_ids` that exist, deleted ones included, in no
+    /// particular order.
+    async fn find_many(&self, product_ids: &[&str]) -> Result<Vec<DbProduct>, Status>;
+
+    /// Attributes of each product, by product id, ordered by name.
+    async fn attributes(
+        &self,
+        product_ids: &[String],
+    ) -> Result<HashMap<String, Vec<ProductAttribute>>, Status>;
+
+    /// Tag names of each product, by product id, in alphabetical order.
+    async fn tags(&self, product_ids: &[String]) -> Result<HashMap<String, Vec<String>>, Status>;
+
+    /// The seller listing a product, `Some(None)` for the platform's own;
+    /// `None` when there's no such product.
+    async fn seller_of(&self, product_id: &str) -> Result<Option<Option<String>>, Status>;
+
+    async fn seller_exists(&self, seller_id: &str) -> Result<bool, Status>;
+
+    /// Writes `product` and announces it.
+    async fn insert(&self, product: NewProduct) -> Result<(), Status>;
+
+    /// Moves a live product's stock by `change` and announces it; returns
+    /// the new stock. Fails with PRODUCT_NOT_FOUND, or INSUFFICIENT_STOCK
+    /// when it would go negative.
+    async fn adjust_stock(&self, product_id: &str, change: i32) -> Result<i32, Status>;
+}
+
+fn product_not_found() -> Status {
+    error::not_found("PRODUCT_NOT_FOUND", "Product not found")
+}
+
+fn insufficient_stock(stock_quantity: i32, change: i32) -> Status {
+    error::failed_precondition(
+        "INSUFFICIENT_STOCK",
+        format!(
+            "Insufficient stock. Current: {}, Change: {}",
+            stock_quantity, change
+        ),
+    )
+}
+
+pub struct PostgresProductRepository {
+    db: PgPool,
+}
+
+impl PostgresProductRepository {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+}
+
+#[tonic::async_trait]
+impl ProductRepository for PostgresProductRepository {
+    async fn find(&self, product_id: &str) -> Result<Option<DbProduct>, Status> {
+        sqlx::query_as::<_, DbProduct>(
+            "SELECT id, name, description, price, stock_quantity, category, warranty_months, rating_average, rating_count, sale_price, sale_starts_at, sale_ends_at, status, product_type, allow_backorder, available_from, seller_id, tax_class, weight_grams, deleted_at, created_at, updated_at
+             FROM products WHERE id = $1",
+        )
+        .bind(product_id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| AppError::from(e).into())
+    }
+
+    async fn find_many(&self, product_ids: &[&str]) -> Result<Vec<DbProduct>, Status> {
+        sqlx::query_as::<_, DbProduct>(
+            "SELECT id, name, description, price, stock_quantity, category, warranty_months, rating_average, rating_count, sale_price, sale_starts_at, sale_ends_at, status, product_type, allow_backorder, available_from, seller_id, tax_class, weight_grams, deleted_at, created_at, updated_at
+             FROM products WHERE id = ANY($1)",
+        )
+        .bind(product_ids)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| AppError::from(e).into())
+    }
+
+    async fn attributes(
+        &self,
+        product_ids: &[String],
+    ) -> Result<HashMap<String, Vec<ProductAttribute>>, Status> {
+        let rows = sqlx::query_as::<_, DbProductAttribute>(
+            "SELECT product_id, name, value FROM product_attributes
+             WHERE product_id = ANY($1)
+             ORDER BY name",
+        )
+        .bind(product_ids)
+        .fetch_all(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        let mut attributes: HashMap<String, Vec<ProductAttribute>> = HashMap::new();
+        for row in rows {
+            attributes
+                .entry(row.product_id)
+                .or_default()
+                .push(ProductAttribute {
+                    name: row.name,
+                    value: row.value,
+                });
+        }
+
+        Ok(attributes)
+    }
+
+    async fn tags(&self, product_ids: &[String]) -> Result<HashMap<String, Vec<String>>, Status> {
+        let rows: Vec<(String, String)> = sqlx::query_as(
+            "SELECT pt.product_id, t.name FROM product_tags pt
+             JOIN tags t ON t.id = pt.tag_id
+             WHERE pt.product_id = ANY($1)
+             ORDER BY t.name",
+        )
+        .bind(product_ids)
+        .fetch_all(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        let mut tags: HashMap<String, Vec<String>> = HashMap::new();
+        for (product_id, name) in rows {
+            tags.entry(product_id).or_default().push(name);
+        }
+
+        Ok(tags)
+    }
+
+    async fn seller_of(&self, product_id: &str) -> Result<Option<Option<String>>, Status> {
+        sqlx::query_scalar("SELECT seller_id FROM products WHERE id = $1")
+            .bind(product_id)
+            .fetch_optional(&self.db)
+            .await
+            .map_err(|e| AppError::from(e).into())
+    }
+
+    async fn seller_exists(&self, seller_id: &str) -> Result<bool, Status> {
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM sellers WHERE id = $1)")
+            .bind(seller_id)
+            .fetch_one(&self.db)
+            .await
+            .map_err(|e| AppError::from(e).into())
+    }
+
+    async fn insert(&self, product: NewProduct) -> Result<(), Status> {
+        let mut tx = self.db.begin().await.map_err(AppError::from)?;
+
+        sqlx::query(
+            "INSERT INTO products (id, name, description, price, stock_quantity, category, warranty_months, status, product_type, allow_backorder, available_from, seller_id, tax_class, weight_grams)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, 'DRAFT', $8, $9, $10, $11, $12, $13)",
+        )
+        .bind(&product.id)
+        .bind(&product.name)
+        .bind(&product.description)
+        .bind(product.price)
+        .bind(product.stock_quantity)
+        .bind(&product.category)
+        .bind(product.warranty_months)
+        .bind(product.product_type)
+        .bind(product.allow_backorder)
+        .bind(product.available_from)
+        .bind(&product.seller_id)
+        .bind(&product.tax_class)
+        .bind(product.weight_grams)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+
+        emit_product_changed(&mut tx, &product.id, PRODUCT_CREATED)
+            .await
+            .map_err(AppError::from)?;
+
+        tx.commit().await.map_err(AppError::from)?;
+        Ok(())
+    }
+
+    async fn adjust_stock(&self, product_id: &str, change: i32) -> Result<i32, Status> {
+        let mut tx = self.db.begin().await.map_err(AppError::from)?;
+
+        let stock_quantity: Option<i32> = sqlx::query_scalar(
+            "SELECT stock_quantity FROM products WHERE id = $1 AND deleted_at IS NULL FOR UPDATE",
+        )
+        .bind(product_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+
+        let Some(stock_quantity) = stock_quantity else {
+            tx.rollback().await.map_err(AppError::from)?;
+            return Err(product_not_found());
+        };
+
+        let new_stock = stock_quantity + change;
+        if new_stock < 0 {
+            tx.rollback().await.map_err(AppError::from)?;
+            return Err(insufficient_stock(stock_quantity, change));
+        }
+
+        sqlx::query(
+            "UPDATE products SET stock_quantity = $1, updated_at = CURRENT_TIMESTAMP WHERE id = $2",
+        )
+        .bind(new_stock)
+        .bind(product_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+
+        if change != 0 {
+            emit_stock_changed(
+                &mut tx,
+                product_id,
+                new_stock,
+                change,
+                STOCK_INVENTORY_UPDATE,
+            )
+            .await
+            .map_err(AppError::from)?;
+        }
+
+        tx.commit().await.map_err(AppError::from)?;
+        Ok(new_stock)
+    }
+}
+
+#[derive(Default)]
+struct Catalog {
+    products: HashMap<String, DbProduct>,
+    sellers: HashSet<String>,
+}
+
+/// Products kept in memory, for tests. They have no attributes or tags,
+/// and changes aren't announced.
+#[derive(Default)]
+pub struct InMemoryProductRepository {
+    catalog: Mutex<Catalog>,
+}
+
+impl InMemoryProductRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a seller products can be listed by.
+    pub fn with_seller(self, seller_id: &str) -> Self {
+        self.catalog
+            .lock()
+            .unwrap()
+            .sellers
+            .insert(seller_id.to_string());
+        self
+    }
+}
+
+#[tonic::async_trait]
+impl ProductRepository for InMemoryProductRepository {
+    async fn find(&self, product_id: &str) -> Result<Option<DbProduct>, Status> {
+        let catalog = self.catalog.lock().unwrap();
+        Ok(catalog.products.get(product_id).cloned())
+    }
+
+    async fn find_many(&self, product_ids: &[&str]) -> Result<Vec<DbProduct>, Status> {
+        let catalog = self.catalog.lock().unwrap();
+        Ok(product_ids
+            .iter()
+            .filter_map(|id| catalog.products.get(*id).cloned())
+            .collect())
+    }
+
+    async fn attributes(
+        &self,
+        _product_ids: &[String],
+    ) -> Result<HashMap<String, Vec<ProductAttribute>>, Status> {
+        Ok(HashMap::new())
+    }
+
+    async fn tags(&self, _product_ids: &[String]) -> Result<HashMap<String, Vec<String>>, Status> {
+        Ok(HashMap::new())
+    }
+
+    async fn seller_of(&self, product_id: &str) -> Result<Option<Option<String>>, Status> {
+        let catalog = self.catalog.lock().unwrap();
+        Ok(catalog
+            .products
+            .get(product_id)
+            .map(|product| product.seller_id.clone()))
+    }
+
+    async fn seller_exists(&self, seller_id: &str) -> Result<bool, Status> {
+        Ok(self.catalog.lock().unwrap().sellers.contains(seller_id))
+    }
+
+    async fn insert(&self, product: NewProduct) -> Result<(), Status> {
+        let mut catalog = self.catalog.lock().unwrap();
+        if catalog.products.contains_key(&product.id) {
+            return Err(AppError::Conflict("Conflicts with an existing record".to_string()).into());
+        }
+
+        let now = Utc::now().naive_utc();
+        catalog.products.insert(
+            product.id.clone(),
+            DbProduct {
+                id: product.id,
+                name: product.name,
+                description: product.description,
+                price: product.price,
+                stock_quantity: product.stock_quantity,
+                category: product.category,
+                warranty_months: product.warranty_months,
+                rating_average: Decimal::ZERO,
+                rating_count: 0,
+                sale_price: None,
+                sale_starts_at: None,
+                sale_ends_at: None,
+                status: "DRAFT".to_string(),
+                product_type: product.product_type.to_string(),
+                allow_backorder: product.allow_backorder,
+                available_from: product.available_from,
+                seller_id: product.seller_id,
+                tax_class: product.tax_class,
+                weight_grams: product.weight_grams,
+                deleted_at: None,
+                created_at: now,
+                updated_at: now,
+            },
+        );
+        Ok(())
+    }
+
+    async fn adjust_stock(&self, product_id: &str, change: i32) -> Result<i32, Status> {
+        let mut catalog = self.catalog.lock().unwrap();
+        let product = catalog
+            .products
+            .get_mut(product_id)
+            .filter(|product| product.deleted_at.is_none())
+            .ok_or_else(product_not_found)?;
+
+        let new_stock = product.stock_quantity + change;
+        if new_stock < 0 {
+            return Err(insufficient_stock(product.stock_quantity, change));
+        }
+
+        product.stock_quantity = new_stock;
+        product.updated_at = Utc::now().naive_utc();
+        Ok(new_stock)
+    }
+}
@@ -0,0 +1,312 @@
+use common::events;
+use common::inventory::{self, Backorder};
+use proto::events::StockChanged;
+use proto::product::ReservationLine;
+use sqlx::{PgConnection, PgPool};
+use std::collections::BTreeMap;
+
+/// Reasons a `StockChanged` event gives.
+pub const STOCK_INVENTORY_UPDATE: &str = "INVENTORY_UPDATE";
+pub const STOCK_RESTOCK_RECEIVED: &str = "RESTOCK_RECEIVED";
+const STOCK_RESERVED: &str = "RESERVED";
+const STOCK_RELEASED: &str = "RELEASED";
+const STOCK_RETURNED: &str = "RETURNED";
+
+/// Announces that a product's stock moved by `change` to `stock_quantity`,
+/// in the transaction moving it.
+pub async fn emit_stock_changed(
+    conn: &mut PgConnection,
+    product_id: &str,
+    stock_quantity: i32,
+    change: i32,
+    reason: &str,
+) -> Result<(), sqlx::Error> {
+    events::emit(
+        conn,
+        &StockChanged {
+            product_id: product_id.to_string(),
+            stock_quantity,
+            quantity_change: change,
+            reason: reason.to_string(),
+            changed_at: chrono::Utc::now().timestamp(),
+        },
+    )
+    .await
+}
+
+/// Takes stock for every line of a reservation, or for none of them. Lines
+/// follow the same terms as orders: preorders and refused decrements of
+/// backorderable products are owed rather than taken. A retried call returns
+/// the reservation as first made. `Ok(Err(..))` holds the message refusing it.
+pub async fn reserve(
+    db: &PgPool,
+    reservation_id: &str,
+    lines: &[ReservationLine],
+) -> Result<Result<Vec<ReservationLine>, String>, sqlx::Error> {
+    if let Some(line) = lines.iter().find(|l| l.quantity <= 0) {
+        return Ok(Err(format!(
+            "Quantity for product {} must be greater than 0",
+            line.product_id
+        )));
+    }
+
+    let mut tx = db.begin().await?;
+
+    let created =
+        sqlx::query("INSERT INTO stock_reservations (id) VALUES ($1) ON CONFLICT (id) DO NOTHING")
+            .bind(reservation_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected()
+            == 1;
+    if !created {
+        tx.rollback().await?;
+        return existing(db, reservation_id).await;
+    }
+
+    let product_ids: Vec<String> = lines.iter().map(|l| l.product_id.clone()).collect();
+    let backorders = inventory::backorder_terms(db, &product_ids).await?;
+
+    // Lines take stock in product id order, so concurrent reservations lock
+    // rows in the same order; lines of one product keep theirs
+    let mut order: Vec<usize> = (0..lines.len()).collect();
+    order.sort_by(|a, b| lines[*a].product_id.cmp(&lines[*b].product_id));
+
+    let mut reserved = vec![ReservationLine::default(); lines.len()];
+    for line_no in order {
+        let line = &lines[line_no];
+        let terms = backorders.get(&line.product_id).copied();
+        let backordered = if terms == Some(Backorder::Preorder) {
+            true
+        } else {
+            let taken =
+                inventory::decrement_stock(&mut tx, &line.product_id, line.quantity).await?;
+            match (taken, terms) {
+                (Some((stock, tracked)), _) => {
+                    if tracked {
+                        emit_stock_changed(
+                            &mut tx,
+                            &line.product_id,
+                            stock,
+                            -line.quantity,
+                            STOCK_RESERVED,
+                        )
+                        .await?;
+                    }
+                    false
+                }
+                (None, Some(Backorder::Allowed)) => true,
+                (None, _) => {
+                    tx.rollback().await?;
+                    return Ok(Err(format!(
+                        "Product {} not available in requested quantity",
+                        line.product_id
+                    )));
+                }
+            }
+        };
+
+        sqlx::query(
+            "INSERT INTO stock_reservation_lines (reservation_id, line, product_id, quantity, backordered)
+             VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(reservation_id)
+        .bind(line_no as i32)
+        .bind(&line.product_id)
+        .bind(line.quantity)
+        .bind(backordered)
+        .execute(&mut *tx)
+        .await?;
+
+        reserved[line_no] = ReservationLine {
+            product_id: line.product_id.clone(),
+            quantity: line.quantity,
+            backordered,
+        };
+    }
+
+    tx.commit().await?;
+
+    Ok(Ok(reserved))
+}
+
+/// A reservation made by an earlier call, unless it was released since.
+async fn existing(
+    db: &PgPool,
+    reservation_id: &str,
+) -> Result<Result<Vec<ReservationLine>, String>, sqlx::Error> {
+    let status: String = sqlx::query_scalar("SELECT status FROM stock_reservations WHERE id = $1")
+        .bind(reservation_id)
+        .fetch_one(db)
+        .await?;
+    if status == "RELEASED" {
+        return Ok(Err("Reservation was released".to_string()));
+    }
+
+    let lines: Vec<(String, i32, bool)> = sqlx::query_as(
+        "SELECT product_id, quantity, backordered FROM stock_reservation_lines
+         WHERE reservation_id = $1 ORDER BY line",
+    )
+    .bind(reservation_id)
+    .fetch_all(db)
+    .await?;
+
+    Ok(Ok(lines
+        .into_iter()
+        .map(|(product_id, quantity, backordered)| ReservationLine {
+            product_id,
+            quantity,
+            backordered,
+        })
+        .collect()))
+}
+
+/// Keeps a pending reservation's stock for good.
+pub async fn confirm(db: &PgPool, reservation_id: &str) -> Result<Result<(), String>, sqlx::Error> {
+    sqlx::query(
+        "UPDATE stock_reservations SET status = 'CONFIRMED', updated_at = CURRENT_TIMESTAMP
+         WHERE id = $1 AND status = 'PENDING'",
+    )
+    .bind(reservation_id)
+    .execute(db)
+    .await?;
+
+    let status: Option<String> =
+        sqlx::query_scalar("SELECT status FROM stock_reservations WHERE id = $1")
+            .bind(reservation_id)
+            .fetch_optional(db)
+            .await?;
+
+    Ok(match status.as_deref() {
+        Some("CONFIRMED") => Ok(()),
+        Some(_) => Err("Reservation was released".to_string()),
+        None => Err("Reservation not found".to_string()),
+    })
+}
+
+/// Puts a pending reservation's stock back. Releasing an unknown reservation
+/// records it as released, so a ReserveStock call still in flight is refused
+/// when it lands; confirmed reservations can't be released. Returns the
+/// products whose stock was put back.
+pub async fn release(
+    db: &PgPool,
+    reservation_id: &str,
+) -> Result<Result<Vec<String>, String>, sqlx::Error> {
+    let mut tx = db.begin().await?;
+
+    sqlx::query(
+        "INSERT INTO stock_reservations (id, status) VALUES ($1, 'RELEASED')
+         ON CONFLICT (id) DO NOTHING",
+    )
+    .bind(reservation_id)
+    .execute(&mut *tx)
+    .await?;
+
+    let status: String =
+        sqlx::query_scalar("SELECT status FROM stock_reservations WHERE id = $1 FOR UPDATE")
+            .bind(reservation_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+    let mut restocked_ids = Vec::new();
+    match status.as_str() {
+        "CONFIRMED" => {
+            tx.rollback().await?;
+            return Ok(Err("Reservation is already confirmed".to_string()));
+        }
+        "PENDING" => {
+            // Digital products had no stock taken, so none is put back
+            let restocked: Vec<(String, i32, i32)> = sqlx::query_as(
+                "UPDATE products p
+                 SET stock_quantity = p.stock_quantity + r.quantity, updated_at = CURRENT_TIMESTAMP
+                 FROM (
+                     SELECT product_id, SUM(quantity)::INT AS quantity
+                     FROM stock_reservation_lines
+                     WHERE reservation_id = $1 AND NOT backordered
+                     GROUP BY product_id
+                 ) r
+                 WHERE p.id = r.product_id AND p.product_type <> 'DIGITAL'
+                 RETURNING p.id, p.stock_quantity, r.quantity",
+            )
+            .bind(reservation_id)
+            .fetch_all(&mut *tx)
+            .await?;
+            for (product_id, stock, quantity) in restocked {
+                emit_stock_changed(&mut tx, &product_id, stock, quantity, STOCK_RELEASED).await?;
+                restocked_ids.push(product_id);
+            }
+
+            sqlx::query(
+                "UPDATE stock_reservations SET status = 'RELEASED', updated_at = CURRENT_TIMESTAMP
+                 WHERE id = $1",
+            )
+            .bind(reservation_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+        _ => {}
+    }
+
+    tx.commit().await?;
+
+    Ok(Ok(restocked_ids))
+}
+
+/// Puts back the stock of a return's lines, unless the return was made
+/// before; backordered lines and digital products took none. Returns the
+/// products whose stock was put back. `Ok(Err(..))` holds the message
+/// refusing it.
+pub async fn return_stock(
+    db: &PgPool,
+    return_id: &str,
+    lines: &[ReservationLine],
+) -> Result<Result<Vec<String>, String>, sqlx::Error> {
+    if let Some(line) = lines.iter().find(|l| l.quantity <= 0) {
+        return Ok(Err(format!(
+            "Quantity for product {} must be greater than 0",
+            line.product_id
+        )));
+    }
+
+    let mut tx = db.begin().await?;
+
+    let created =
+        sqlx::query("INSERT INTO stock_returns (id) VALUES ($1) ON CONFLICT (id) DO NOTHING")
+            .bind(return_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected()
+            == 1;
+    if !created {
+        tx.rollback().await?;
+        return Ok(Ok(Vec::new()));
+    }
+
+    // Products in id order, so concurrent returns lock them in the same order
+    let mut quantities: BTreeMap<&str, i32> = BTreeMap::new();
+    for line in lines.iter().filter(|l| !l.backordered) {
+        *quantities.entry(&line.product_id).or_default() += line.quantity;
+    }
+
+    let mut restocked_ids = Vec::new();
+    for (product_id, quantity) in quantities {
+        let stock: Option<i32> = sqlx::query_scalar(
+            "UPDATE products
+             SET stock_quantity = stock_quantity + $1, updated_at = CURRENT_TIMESTAMP
+             WHERE id = $2 AND product_type <> 'DIGITAL'
+             RETURNING stock_quantity",
+        )
+        .bind(quantity)
+        .bind(product_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        if let Some(stock) = stock {
+            emit_stock_changed(&mut tx, product_id, stock, quantity, STOCK_RETURNED).await?;
+            restocked_ids.push(product_id.to_string());
+        }
+    }
+
+    tx.commit().await?;
+
+    Ok(Ok(restocked_ids))
+}
@@ -0,0 +1,368 @@
+use common::auth::{Caller, ServiceCredentials};
+use common::cache::Cache;
+use common::client::{ServiceEndpoint, call_with_canary};
+use common::error::{self, AppError};
+use common::pagination::PageRequest;
+use common::resilience;
+use common::response_cache::ResponseCache;
+use proto::order::{VerifyPurchaseRequest, order_service_client::OrderServiceClient};
+use proto::review::{
+    DeleteReviewRequest, DeleteReviewResponse, GetRatingSummaryRequest, GetRatingSummaryResponse,
+    ListReviewsRequest, ListReviewsResponse, RatingCount, Review, SubmitReviewRequest,
+    SubmitReviewResponse, review_service_server::ReviewService,
+};
+use sqlx::{PgConnection, PgPool};
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use uuid::Uuid;
+
+/// Method prefix of the cached product reads that include the aggregated rating.
+const PRODUCT_CACHE_PREFIX: &str = "/product.ProductService/";
+
+const MAX_TITLE_LENGTH: usize = 255;
+const MAX_BODY_LENGTH: usize = 5000;
+
+#[derive(Debug, sqlx::FromRow)]
+struct DbReview {
+    id: String,
+    product_id: String,
+    user_id: String,
+    order_id: String,
+    rating: i32,
+    title: Option<String>,
+    body: Option<String>,
+    created_at: chrono::NaiveDateTime,
+}
+
+impl DbReview {
+    fn to_proto(&self) -> Review {
+        Review {
+            review_id: self.id.clone(),
+            product_id: self.product_id.clone(),
+            user_id: self.user_id.clone(),
+            order_id: self.order_id.clone(),
+            rating: self.rating,
+            title: self.title.clone().unwrap_or_default(),
+            body: self.body.clone().unwrap_or_default(),
+            created_at: self.created_at.and_utc().timestamp(),
+        }
+    }
+}
+
+/// Recomputes the rating aggregate stored on the product. Runs in the same
+/// transaction as the review write so the two can't drift apart.
+async fn refresh_rating(conn: &mut PgConnection, product_id: &str) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE products
+         SET rating_average = COALESCE((SELECT ROUND(AVG(rating), 2) FROM product_reviews WHERE product_id = $1), 0),
+             rating_count = (SELECT COUNT(*) FROM product_reviews WHERE product_id = $1)
+         WHERE id = $1",
+    )
+    .bind(product_id)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+pub struct ReviewServiceImpl {
+    db: PgPool,
+    order_service: ServiceEndpoint,
+    cache: Arc<ResponseCache>,
+    /// Products by id, which carry their rating.
+    products: Arc<Cache>,
+}
+
+impl ReviewServiceImpl {
+    pub fn new(
+        db: PgPool,
+        order_service: ServiceEndpoint,
+        cache: Arc<ResponseCache>,
+        products: Arc<Cache>,
+    ) -> Self {
+        Self {
+            db,
+            order_service,
+            cache,
+            products,
+        }
+    }
+
+    /// Returns the delivered order through which the user received the
+    /// product, if any.
+    async fn verified_order(
+        &self,
+        user_id: &str,
+        product_id: &str,
+    ) -> Result<Option<String>, Status> {
+        let result = call_with_canary(&self.order_service, "VerifyPurchase", |channel| {
+            let verify_request = VerifyPurchaseRequest {
+                user_id: user_id.to_string(),
+                product_id: product_id.to_string(),
+            };
+            async move {
+                let mut client =
+                    OrderServiceClient::with_interceptor(channel, ServiceCredentials("product"));
+
+                let response = client
+                    .verify_purchase(verify_request)
+                    .await
+                    .map_err(|e| resilience::downstream_error("Order", e))?;
+
+                Ok(response.into_inner())
+            }
+        })
+        .await?;
+
+        Ok(result.purchased.then_some(result.order_id))
+    }
+}
+
+#[tonic::async_trait]
+impl ReviewService for ReviewServiceImpl {
+    async fn submit_review(
+        &self,
+        request: Request<SubmitReviewRequest>,
+    ) -> Result<Response<SubmitReviewResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        let req = request.into_inner();
+
+        if req.product_id.is_empty() || req.user_id.is_empty() {
+            return Err(error::invalid_argument(
+                "INVALID_REVIEW",
+                "Product ID and user ID are required",
+            ));
+        }
+        caller.require_owner(&req.user_id)?;
+        if !(1..=5).contains(&req.rating) {
+            return Err(error::invalid_argument(
+                "INVALID_RATING",
+                "Rating must be between 1 and 5",
+            ));
+        }
+        if req.title.chars().count() > MAX_TITLE_LENGTH {
+            return Err(error::invalid_argument(
+                "INVALID_REVIEW",
+                format!("Title must be at most {} characters", MAX_TITLE_LENGTH),
+            ));
+        }
+        if req.body.chars().count() > MAX_BODY_LENGTH {
+            return Err(error::invalid_argument(
+                "INVALID_REVIEW",
+                format!("Review must be at most {} characters", MAX_BODY_LENGTH),
+            ));
+        }
+
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM products WHERE id = $1 AND deleted_at IS NULL)",
+        )
+        .bind(&req.product_id)
+        .fetch_one(&self.db)
+        .await
+        .map_err(AppError::from)?;
+        if !exists {
+            return Err(error::not_found("PRODUCT_NOT_FOUND", "Product not found"));
+        }
+
+        let order_id = match self.verified_order(&req.user_id, &req.product_id).await? {
+            Some(order_id) => order_id,
+            None => {
+                return Err(error::failed_precondition(
+                    "PURCHASE_NOT_VERIFIED",
+                    "Only customers who received this product can review it",
+                ));
+            }
+        };
+
+        let mut tx = self.db.begin().await.map_err(AppError::from)?;
+
+        // The unique (product_id, user_id) constraint settles concurrent submissions
+        let review = sqlx::query_as::<_, DbReview>(
+            "INSERT INTO product_reviews (id, product_id, user_id, order_id, rating, title, body)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             ON CONFLICT (product_id, user_id) DO NOTHING
+             RETURNING id, product_id, user_id, order_id, rating, title, body, created_at",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(&req.product_id)
+        .bind(&req.user_id)
+        .bind(&order_id)
+        .bind(req.rating)
+        .bind(if req.title.is_empty() {
+            None
+        } else {
+            Some(&req.title)
+        })
+        .bind(if req.body.is_empty() {
+            None
+        } else {
+            Some(&req.body)
+        })
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+
+        let review = match review {
+            Some(review) => review,
+            None => {
+                tx.rollback().await.map_err(AppError::from)?;
+                return Err(error::already_exists(
+                    "REVIEW_EXISTS",
+                    "You have already reviewed this product",
+                ));
+            }
+        };
+
+        refresh_rating(&mut tx, &req.product_id)
+            .await
+            .map_err(AppError::from)?;
+
+        tx.commit().await.map_err(AppError::from)?;
+
+        self.cache.invalidate_prefix(PRODUCT_CACHE_PREFIX);
+        self.products.invalidate(&[&req.product_id]).await;
+
+        Ok(Response::new(SubmitReviewResponse {
+            success: true,
+            message: "Review submitted successfully".to_string(),
+            review: Some(review.to_proto()),
+        }))
+    }
+
+    async fn list_reviews(
+        &self,
+        request: Request<ListReviewsRequest>,
+    ) -> Result<Response<ListReviewsResponse>, Status> {
+        let req = request.into_inner();
+
+        if req.product_id.is_empty() {
+            return Err(error::invalid_argument(
+                "MISSING_PRODUCT_ID",
+                "Product ID is required",
+            ));
+        }
+
+        let page = PageRequest::new(req.page, req.page_size);
+
+        let reviews = sqlx::query_as::<_, DbReview>(
+            "SELECT id, product_id, user_id, order_id, rating, title, body, created_at
+             FROM product_reviews WHERE product_id = $1
+             ORDER BY created_at DESC LIMIT $2 OFFSET $3",
+        )
+        .bind(&req.product_id)
+        .bind(page.limit())
+        .bind(page.offset())
+        .fetch_all(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        let total_count: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM product_reviews WHERE product_id = $1")
+                .bind(&req.product_id)
+                .fetch_one(&self.db)
+                .await
+                .map_err(AppError::from)?;
+
+        Ok(Response::new(ListReviewsResponse {
+            success: true,
+            message: format!("Retrieved {} reviews", reviews.len()),
+            reviews: reviews.iter().map(DbReview::to_proto).collect(),
+            total_count: total_count as i32,
+        }))
+    }
+
+    async fn delete_review(
+        &self,
+        request: Request<DeleteReviewRequest>,
+    ) -> Result<Response<DeleteReviewResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        let req = request.into_inner();
+        caller.require_owner(&req.user_id)?;
+
+        let mut tx = self.db.begin().await.map_err(AppError::from)?;
+
+        // The platform may take down any review; anyone else only their own
+        let product_id: Option<String> = sqlx::query_scalar(
+            "DELETE FROM product_reviews
+             WHERE id = $1 AND ($2::TEXT IS NULL OR user_id = $2)
+             RETURNING product_id",
+        )
+        .bind(&req.review_id)
+        .bind(caller.user_id())
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+
+        let product_id = match product_id {
+            Some(product_id) => product_id,
+            None => {
+                tx.rollback().await.map_err(AppError::from)?;
+                return Err(error::not_found("REVIEW_NOT_FOUND", "Review not found"));
+            }
+        };
+
+        refresh_rating(&mut tx, &product_id)
+            .await
+            .map_err(AppError::from)?;
+
+        tx.commit().await.map_err(AppError::from)?;
+
+        self.cache.invalidate_prefix(PRODUCT_CACHE_PREFIX);
+        self.products.invalidate(&[&product_id]).await;
+
+        Ok(Response::new(DeleteReviewResponse {
+            success: true,
+            message: "Review deleted successfully".to_string(),
+        }))
+    }
+
+    async fn get_rating_summary(
+        &self,
+        request: Request<GetRatingSummaryRequest>,
+    ) -> Result<Response<GetRatingSummaryResponse>, Status> {
+        let req = request.into_inner();
+
+        let aggregate: Option<(sqlx::types::Decimal, i32)> = sqlx::query_as(
+            "SELECT rating_average, rating_count FROM products WHERE id = $1 AND deleted_at IS NULL",
+        )
+        .bind(&req.product_id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        let (average, count) = match aggregate {
+            Some(aggregate) => aggregate,
+            None => {
+                return Err(error::not_found("PRODUCT_NOT_FOUND", "Product not found"));
+            }
+        };
+
+        let rows: Vec<(i32, i64)> = sqlx::query_as(
+            "SELECT rating, COUNT(*) FROM product_reviews WHERE product_id = $1 GROUP BY rating",
+        )
+        .bind(&req.product_id)
+        .fetch_all(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        // Every star rating is listed, including those nobody gave
+        let distribution = (1..=5)
+            .rev()
+            .map(|rating| RatingCount {
+                rating,
+                count: rows
+                    .iter()
+                    .find(|(r, _)| *r == rating)
+                    .map_or(0, |(_, c)| *c as i32),
+            })
+            .collect();
+
+        Ok(Response::new(GetRatingSummaryResponse {
+            success: true,
+            message: "Rating summary retrieved successfully".to_string(),
+            average_rating: average.to_string().parse::<f64>().unwrap_or(0.0),
+            review_count: count,
+            distribution,
+        }))
+    }
+}
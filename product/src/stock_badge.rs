@@ -0,0 +1,85 @@
+use common::error::AppError;
+use dashmap::DashMap;
+use proto::product::StockLevel;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tonic::Status;
+
+/// In-process cache of coarse stock levels for listing pages. Entries are
+/// refreshed after `ttl` with a plain read, never a locking one, so badges
+/// may lag real stock by up to the TTL.
+pub struct StockBadgeCache {
+    db: PgPool,
+    ttl: Duration,
+    low_stock_threshold: i32,
+    entries: DashMap<String, (StockLevel, Instant)>,
+}
+
+impl StockBadgeCache {
+    pub fn new(db: PgPool, ttl: Duration, low_stock_threshold: i32) -> Arc<Self> {
+        Arc::new(Self {
+            db,
+            ttl,
+            low_stock_threshold,
+            entries: DashMap::new(),
+        })
+    }
+
+    fn level_for(&self, stock: i32, deleted: bool) -> StockLevel {
+        if deleted || stock <= 0 {
+            StockLevel::OutOfStock
+        } else if stock <= self.low_stock_threshold {
+            StockLevel::LowStock
+        } else {
+            StockLevel::InStock
+        }
+    }
+
+    /// Returns the level of every known product among `product_ids`; only
+    /// cache misses hit the database, in a single query.
+    pub async fn levels(
+        &self,
+        product_ids: &[String],
+    ) -> Result<HashMap<String, StockLevel>, Status> {
+        let now = Instant::now();
+        let mut levels = HashMap::new();
+        let mut misses = Vec::new();
+
+        for id in product_ids {
+            match self.entries.get(id) {
+                Some(entry) if entry.1 > now => {
+                    levels.insert(id.clone(), entry.0);
+                }
+                _ => misses.push(id.clone()),
+            }
+        }
+
+        if misses.is_empty() {
+            return Ok(levels);
+        }
+
+        let rows: Vec<(String, i32, bool)> = sqlx::query_as(
+            "SELECT id, stock_quantity, deleted_at IS NOT NULL FROM products WHERE id = ANY($1)",
+        )
+        .bind(&misses)
+        .fetch_all(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        let expires_at = now + self.ttl;
+        for (id, stock, deleted) in rows {
+            let level = self.level_for(stock, deleted);
+            self.entries.insert(id.clone(), (level, expires_at));
+            levels.insert(id, level);
+        }
+
+        // Bound memory: drop expired entries once the cache grows large
+        if self.entries.len() > 100_000 {
+            self.entries.retain(|_, (_, expires)| *expires > now);
+        }
+
+        Ok(levels)
+    }
+}
@@ -0,0 +1,96 @@
+// This file is @generated by prost-build.
+/// Envelope carries one event on the wire
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Envelope {
+    /// unique per event, increasing in the order events were written
+    #[prost(string, tag = "1")]
+    pub event_id: ::prost::alloc::string::String,
+    /// the event message's name, e.g. UserRegistered
+    #[prost(string, tag = "2")]
+    pub event_type: ::prost::alloc::string::String,
+    /// id of the entity the event is about
+    #[prost(string, tag = "3")]
+    pub key: ::prost::alloc::string::String,
+    /// unix seconds
+    #[prost(int64, tag = "4")]
+    pub occurred_at: i64,
+    /// the encoded event message
+    #[prost(bytes = "vec", tag = "5")]
+    pub payload: ::prost::alloc::vec::Vec<u8>,
+}
+/// UserRegistered is published on "users" when an account is created
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserRegistered {
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub username: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub email: ::prost::alloc::string::String,
+    #[prost(int64, tag = "4")]
+    pub registered_at: i64,
+}
+/// OrderCreated is published on "orders" when an order is placed
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OrderCreated {
+    #[prost(string, tag = "1")]
+    pub order_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub order_number: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub user_id: ::prost::alloc::string::String,
+    /// PENDING, or ON_HOLD for fraud review
+    #[prost(string, tag = "4")]
+    pub status: ::prost::alloc::string::String,
+    /// decimal string, in currency
+    #[prost(string, tag = "5")]
+    pub total_amount: ::prost::alloc::string::String,
+    #[prost(string, tag = "6")]
+    pub currency: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "7")]
+    pub items: ::prost::alloc::vec::Vec<OrderCreatedItem>,
+    #[prost(int64, tag = "8")]
+    pub created_at: i64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OrderCreatedItem {
+    #[prost(string, tag = "1")]
+    pub product_id: ::prost::alloc::string::String,
+    #[prost(int32, tag = "2")]
+    pub quantity: i32,
+    /// decimal string, in the order's currency
+    #[prost(string, tag = "3")]
+    pub unit_price: ::prost::alloc::string::String,
+    #[prost(bool, tag = "4")]
+    pub backordered: bool,
+}
+/// StockChanged is published on "stock" when a product's stock level moves
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StockChanged {
+    #[prost(string, tag = "1")]
+    pub product_id: ::prost::alloc::string::String,
+    #[prost(int32, tag = "2")]
+    pub stock_quantity: i32,
+    /// signed: negative when stock was taken
+    #[prost(int32, tag = "3")]
+    pub quantity_change: i32,
+    /// INVENTORY_UPDATE, RESTOCK_RECEIVED, RESERVED or RELEASED
+    #[prost(string, tag = "4")]
+    pub reason: ::prost::alloc::string::String,
+    #[prost(int64, tag = "5")]
+    pub changed_at: i64,
+}
+/// ProductChanged is published on "products" when a product is added,
+/// edited, published, archived, deleted or restored, or its tags,
+/// attributes or sale change. It carries no snapshot: consumers needing the
+/// product read its current state, which may be newer than the event.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ProductChanged {
+    #[prost(string, tag = "1")]
+    pub product_id: ::prost::alloc::string::String,
+    /// CREATED, UPDATED or DELETED
+    #[prost(string, tag = "2")]
+    pub change: ::prost::alloc::string::String,
+    #[prost(int64, tag = "3")]
+    pub changed_at: i64,
+}
@@ -1,3 +1,19 @@
+pub mod events;
+pub mod notification;
+pub mod ops;
 pub mod order;
+pub mod payment;
 pub mod product;
+pub mod recall;
+pub mod reporting;
+pub mod review;
+pub mod rules;
+pub mod search;
+pub mod settings;
+pub mod shipping;
+pub mod slo;
+pub mod tax;
 pub mod user;
+pub mod validation;
+pub mod warranty;
+pub mod webhook;
@@ -0,0 +1,1154 @@
+// This file is @generated by prost-build.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OpsSwitch {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(bool, tag = "2")]
+    pub active: bool,
+    #[prost(string, tag = "3")]
+    pub reason: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub updated_by: ::prost::alloc::string::String,
+    #[prost(int64, tag = "5")]
+    pub updated_at: i64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OpsAuditEntry {
+    #[prost(string, tag = "1")]
+    pub entry_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub action: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub target: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub actor: ::prost::alloc::string::String,
+    #[prost(string, tag = "5")]
+    pub reason: ::prost::alloc::string::String,
+    #[prost(int64, tag = "6")]
+    pub created_at: i64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OpsActionRequest {
+    #[prost(string, tag = "1")]
+    pub actor: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub reason: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OpsActionResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "3")]
+    pub switch: ::core::option::Option<OpsSwitch>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetPaymentProviderEnabledRequest {
+    #[prost(string, tag = "1")]
+    pub provider: ::prost::alloc::string::String,
+    #[prost(bool, tag = "2")]
+    pub enabled: bool,
+    #[prost(string, tag = "3")]
+    pub actor: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub reason: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ForceReleaseReservationsRequest {
+    #[prost(string, tag = "1")]
+    pub product_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub actor: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub reason: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ForceReleaseReservationsResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(int32, tag = "3")]
+    pub released_count: i32,
+    /// Reservations whose release failed; calling again retries them
+    #[prost(int32, tag = "4")]
+    pub failed_count: i32,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct ListSwitchesRequest {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListSwitchesResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub switches: ::prost::alloc::vec::Vec<OpsSwitch>,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct ListAuditLogRequest {
+    #[prost(int32, tag = "1")]
+    pub page: i32,
+    #[prost(int32, tag = "2")]
+    pub page_size: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListAuditLogResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "3")]
+    pub entries: ::prost::alloc::vec::Vec<OpsAuditEntry>,
+    #[prost(int32, tag = "4")]
+    pub total_count: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConsistencyViolation {
+    #[prost(string, tag = "1")]
+    pub check: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub entity_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub detail: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConsistencyReport {
+    /// 0 when no check has run yet
+    #[prost(int64, tag = "1")]
+    pub checked_at: i64,
+    #[prost(int64, tag = "2")]
+    pub duration_ms: i64,
+    #[prost(message, repeated, tag = "3")]
+    pub violations: ::prost::alloc::vec::Vec<ConsistencyViolation>,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct RunConsistencyCheckRequest {}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetConsistencyReportRequest {}
+/// Generated client implementations.
+pub mod ops_service_client {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value,
+    )]
+    use tonic::codegen::*;
+    use tonic::codegen::http::Uri;
+    /// OpsService exposes audited actions for incident response: reversible
+    /// switches, and forced releases of stuck stock
+    #[derive(Debug, Clone)]
+    pub struct OpsServiceClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl OpsServiceClient<tonic::transport::Channel> {
+        /// Attempt to create a new client by connecting to a given endpoint.
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> OpsServiceClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + std::marker::Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + std::marker::Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> OpsServiceClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+            >>::Error: Into<StdError> + std::marker::Send + std::marker::Sync,
+        {
+            OpsServiceClient::new(InterceptedService::new(inner, interceptor))
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_decoding_message_size(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_encoding_message_size(limit);
+            self
+        }
+        /// PauseOrderIntake rejects new orders until ResumeOrderIntake is called
+        pub async fn pause_order_intake(
+            &mut self,
+            request: impl tonic::IntoRequest<super::OpsActionRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::OpsActionResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/ops.OpsService/PauseOrderIntake",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("ops.OpsService", "PauseOrderIntake"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// ResumeOrderIntake re-enables order creation
+        pub async fn resume_order_intake(
+            &mut self,
+            request: impl tonic::IntoRequest<super::OpsActionRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::OpsActionResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/ops.OpsService/ResumeOrderIntake",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("ops.OpsService", "ResumeOrderIntake"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// DrainFulfillmentQueue stops new shipments from being created for orders
+        /// waiting to ship, while shipments already made go on
+        pub async fn drain_fulfillment_queue(
+            &mut self,
+            request: impl tonic::IntoRequest<super::OpsActionRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::OpsActionResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/ops.OpsService/DrainFulfillmentQueue",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("ops.OpsService", "DrainFulfillmentQueue"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// ResumeFulfillmentQueue lets shipments be created again
+        pub async fn resume_fulfillment_queue(
+            &mut self,
+            request: impl tonic::IntoRequest<super::OpsActionRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::OpsActionResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/ops.OpsService/ResumeFulfillmentQueue",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("ops.OpsService", "ResumeFulfillmentQueue"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// ForceReleaseReservations releases every reservation holding stock of a
+        /// product for an order that isn't written yet: those orders fail and the
+        /// stock goes back. Can't be undone; the customers order again
+        pub async fn force_release_reservations(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ForceReleaseReservationsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ForceReleaseReservationsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/ops.OpsService/ForceReleaseReservations",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("ops.OpsService", "ForceReleaseReservations"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// SetPaymentProviderEnabled globally enables or disables a payment provider
+        pub async fn set_payment_provider_enabled(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SetPaymentProviderEnabledRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::OpsActionResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/ops.OpsService/SetPaymentProviderEnabled",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("ops.OpsService", "SetPaymentProviderEnabled"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// ListSwitches returns the current state of every operational switch
+        pub async fn list_switches(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListSwitchesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListSwitchesResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/ops.OpsService/ListSwitches",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("ops.OpsService", "ListSwitches"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// ListAuditLog returns the history of operational actions, newest first
+        pub async fn list_audit_log(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListAuditLogRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListAuditLogResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/ops.OpsService/ListAuditLog",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("ops.OpsService", "ListAuditLog"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// RunConsistencyCheck runs the cross-service invariant checks immediately
+        pub async fn run_consistency_check(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RunConsistencyCheckRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ConsistencyReport>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/ops.OpsService/RunConsistencyCheck",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("ops.OpsService", "RunConsistencyCheck"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// GetConsistencyReport returns the result of the most recent check
+        pub async fn get_consistency_report(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetConsistencyReportRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ConsistencyReport>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/ops.OpsService/GetConsistencyReport",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("ops.OpsService", "GetConsistencyReport"));
+            self.inner.unary(req, path, codec).await
+        }
+    }
+}
+/// Generated server implementations.
+pub mod ops_service_server {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value,
+    )]
+    use tonic::codegen::*;
+    /// Generated trait containing gRPC methods that should be implemented for use with OpsServiceServer.
+    #[async_trait]
+    pub trait OpsService: std::marker::Send + std::marker::Sync + 'static {
+        /// PauseOrderIntake rejects new orders until ResumeOrderIntake is called
+        async fn pause_order_intake(
+            &self,
+            request: tonic::Request<super::OpsActionRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::OpsActionResponse>,
+            tonic::Status,
+        >;
+        /// ResumeOrderIntake re-enables order creation
+        async fn resume_order_intake(
+            &self,
+            request: tonic::Request<super::OpsActionRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::OpsActionResponse>,
+            tonic::Status,
+        >;
+        /// DrainFulfillmentQueue stops new shipments from being created for orders
+        /// waiting to ship, while shipments already made go on
+        async fn drain_fulfillment_queue(
+            &self,
+            request: tonic::Request<super::OpsActionRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::OpsActionResponse>,
+            tonic::Status,
+        >;
+        /// ResumeFulfillmentQueue lets shipments be created again
+        async fn resume_fulfillment_queue(
+            &self,
+            request: tonic::Request<super::OpsActionRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::OpsActionResponse>,
+            tonic::Status,
+        >;
+        /// ForceReleaseReservations releases every reservation holding stock of a
+        /// product for an order that isn't written yet: those orders fail and the
+        /// stock goes back. Can't be undone; the customers order again
+        async fn force_release_reservations(
+            &self,
+            request: tonic::Request<super::ForceReleaseReservationsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ForceReleaseReservationsResponse>,
+            tonic::Status,
+        >;
+        /// SetPaymentProviderEnabled globally enables or disables a payment provider
+        async fn set_payment_provider_enabled(
+            &self,
+            request: tonic::Request<super::SetPaymentProviderEnabledRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::OpsActionResponse>,
+            tonic::Status,
+        >;
+        /// ListSwitches returns the current state of every operational switch
+        async fn list_switches(
+            &self,
+            request: tonic::Request<super::ListSwitchesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListSwitchesResponse>,
+            tonic::Status,
+        >;
+        /// ListAuditLog returns the history of operational actions, newest first
+        async fn list_audit_log(
+            &self,
+            request: tonic::Request<super::ListAuditLogRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListAuditLogResponse>,
+            tonic::Status,
+        >;
+        /// RunConsistencyCheck runs the cross-service invariant checks immediately
+        async fn run_consistency_check(
+            &self,
+            request: tonic::Request<super::RunConsistencyCheckRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ConsistencyReport>,
+            tonic::Status,
+        >;
+        /// GetConsistencyReport returns the result of the most recent check
+        async fn get_consistency_report(
+            &self,
+            request: tonic::Request<super::GetConsistencyReportRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ConsistencyReport>,
+            tonic::Status,
+        >;
+    }
+    /// OpsService exposes audited actions for incident response: reversible
+    /// switches, and forced releases of stuck stock
+    #[derive(Debug)]
+    pub struct OpsServiceServer<T> {
+        inner: Arc<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+        max_decoding_message_size: Option<usize>,
+        max_encoding_message_size: Option<usize>,
+    }
+    impl<T> OpsServiceServer<T> {
+        pub fn new(inner: T) -> Self {
+            Self::from_arc(Arc::new(inner))
+        }
+        pub fn from_arc(inner: Arc<T>) -> Self {
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+                max_decoding_message_size: None,
+                max_encoding_message_size: None,
+            }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        /// Enable decompressing requests with the given encoding.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.accept_compression_encodings.enable(encoding);
+            self
+        }
+        /// Compress responses with the given encoding, if the client supports it.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.send_compression_encodings.enable(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.max_decoding_message_size = Some(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.max_encoding_message_size = Some(limit);
+            self
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for OpsServiceServer<T>
+    where
+        T: OpsService,
+        B: Body + std::marker::Send + 'static,
+        B::Error: Into<StdError> + std::marker::Send + 'static,
+    {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = std::convert::Infallible;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(
+            &mut self,
+            _cx: &mut Context<'_>,
+        ) -> Poll<std::result::Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            match req.uri().path() {
+                "/ops.OpsService/PauseOrderIntake" => {
+                    #[allow(non_camel_case_types)]
+                    struct PauseOrderIntakeSvc<T: OpsService>(pub Arc<T>);
+                    impl<
+                        T: OpsService,
+                    > tonic::server::UnaryService<super::OpsActionRequest>
+                    for PauseOrderIntakeSvc<T> {
+                        type Response = super::OpsActionResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::OpsActionRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as OpsService>::pause_order_intake(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = PauseOrderIntakeSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/ops.OpsService/ResumeOrderIntake" => {
+                    #[allow(non_camel_case_types)]
+                    struct ResumeOrderIntakeSvc<T: OpsService>(pub Arc<T>);
+                    impl<
+                        T: OpsService,
+                    > tonic::server::UnaryService<super::OpsActionRequest>
+                    for ResumeOrderIntakeSvc<T> {
+                        type Response = super::OpsActionResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::OpsActionRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as OpsService>::resume_order_intake(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ResumeOrderIntakeSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/ops.OpsService/DrainFulfillmentQueue" => {
+                    #[allow(non_camel_case_types)]
+                    struct DrainFulfillmentQueueSvc<T: OpsService>(pub Arc<T>);
+                    impl<
+                        T: OpsService,
+                    > tonic::server::UnaryService<super::OpsActionRequest>
+                    for DrainFulfillmentQueueSvc<T> {
+                        type Response = super::OpsActionResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::OpsActionRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as OpsService>::drain_fulfillment_queue(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = DrainFulfillmentQueueSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/ops.OpsService/ResumeFulfillmentQueue" => {
+                    #[allow(non_camel_case_types)]
+                    struct ResumeFulfillmentQueueSvc<T: OpsService>(pub Arc<T>);
+                    impl<
+                        T: OpsService,
+                    > tonic::server::UnaryService<super::OpsActionRequest>
+                    for ResumeFulfillmentQueueSvc<T> {
+                        type Response = super::OpsActionResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::OpsActionRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as OpsService>::resume_fulfillment_queue(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ResumeFulfillmentQueueSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/ops.OpsService/ForceReleaseReservations" => {
+                    #[allow(non_camel_case_types)]
+                    struct ForceReleaseReservationsSvc<T: OpsService>(pub Arc<T>);
+                    impl<
+                        T: OpsService,
+                    > tonic::server::UnaryService<super::ForceReleaseReservationsRequest>
+                    for ForceReleaseReservationsSvc<T> {
+                        type Response = super::ForceReleaseReservationsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::ForceReleaseReservationsRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as OpsService>::force_release_reservations(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ForceReleaseReservationsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/ops.OpsService/SetPaymentProviderEnabled" => {
+                    #[allow(non_camel_case_types)]
+                    struct SetPaymentProviderEnabledSvc<T: OpsService>(pub Arc<T>);
+                    impl<
+                        T: OpsService,
+                    > tonic::server::UnaryService<
+                        super::SetPaymentProviderEnabledRequest,
+                    > for SetPaymentProviderEnabledSvc<T> {
+                        type Response = super::OpsActionResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::SetPaymentProviderEnabledRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as OpsService>::set_payment_provider_enabled(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = SetPaymentProviderEnabledSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/ops.OpsService/ListSwitches" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListSwitchesSvc<T: OpsService>(pub Arc<T>);
+                    impl<
+                        T: OpsService,
+                    > tonic::server::UnaryService<super::ListSwitchesRequest>
+                    for ListSwitchesSvc<T> {
+                        type Response = super::ListSwitchesResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListSwitchesRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as OpsService>::list_switches(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ListSwitchesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/ops.OpsService/ListAuditLog" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListAuditLogSvc<T: OpsService>(pub Arc<T>);
+                    impl<
+                        T: OpsService,
+                    > tonic::server::UnaryService<super::ListAuditLogRequest>
+                    for ListAuditLogSvc<T> {
+                        type Response = super::ListAuditLogResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListAuditLogRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as OpsService>::list_audit_log(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ListAuditLogSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/ops.OpsService/RunConsistencyCheck" => {
+                    #[allow(non_camel_case_types)]
+                    struct RunConsistencyCheckSvc<T: OpsService>(pub Arc<T>);
+                    impl<
+                        T: OpsService,
+                    > tonic::server::UnaryService<super::RunConsistencyCheckRequest>
+                    for RunConsistencyCheckSvc<T> {
+                        type Response = super::ConsistencyReport;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::RunConsistencyCheckRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as OpsService>::run_consistency_check(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = RunConsistencyCheckSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/ops.OpsService/GetConsistencyReport" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetConsistencyReportSvc<T: OpsService>(pub Arc<T>);
+                    impl<
+                        T: OpsService,
+                    > tonic::server::UnaryService<super::GetConsistencyReportRequest>
+                    for GetConsistencyReportSvc<T> {
+                        type Response = super::ConsistencyReport;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetConsistencyReportRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as OpsService>::get_consistency_report(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetConsistencyReportSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                _ => {
+                    Box::pin(async move {
+                        let mut response = http::Response::new(empty_body());
+                        let headers = response.headers_mut();
+                        headers
+                            .insert(
+                                tonic::Status::GRPC_STATUS,
+                                (tonic::Code::Unimplemented as i32).into(),
+                            );
+                        headers
+                            .insert(
+                                http::header::CONTENT_TYPE,
+                                tonic::metadata::GRPC_CONTENT_TYPE,
+                            );
+                        Ok(response)
+                    })
+                }
+            }
+        }
+    }
+    impl<T> Clone for OpsServiceServer<T> {
+        fn clone(&self) -> Self {
+            let inner = self.inner.clone();
+            Self {
+                inner,
+                accept_compression_encodings: self.accept_compression_encodings,
+                send_compression_encodings: self.send_compression_encodings,
+                max_decoding_message_size: self.max_decoding_message_size,
+                max_encoding_message_size: self.max_encoding_message_size,
+            }
+        }
+    }
+    /// Generated gRPC service name
+    pub const SERVICE_NAME: &str = "ops.OpsService";
+    impl<T> tonic::server::NamedService for OpsServiceServer<T> {
+        const NAME: &'static str = SERVICE_NAME;
+    }
+}
@@ -7,10 +7,39 @@ pub struct OrderItem {
     pub product_name: ::prost::alloc::string::String,
     #[prost(int32, tag = "3")]
     pub quantity: i32,
-    #[prost(double, tag = "4")]
-    pub unit_price: f64,
-    #[prost(double, tag = "5")]
-    pub subtotal: f64,
+    /// decimal string, e.g. "19.99"
+    #[prost(string, tag = "4")]
+    pub unit_price: ::prost::alloc::string::String,
+    #[prost(string, tag = "5")]
+    pub subtotal: ::prost::alloc::string::String,
+    #[prost(string, tag = "6")]
+    pub item_id: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "7")]
+    pub tracking: ::prost::alloc::vec::Vec<ItemTracking>,
+    #[prost(enumeration = "OrderItemStatus", tag = "8")]
+    pub status: i32,
+    /// tax charged on the subtotal
+    #[prost(string, tag = "9")]
+    pub tax_amount: ::prost::alloc::string::String,
+    #[prost(enumeration = "ItemFulfillmentStatus", tag = "10")]
+    pub fulfillment_status: i32,
+    /// carrier reference the item shipped under
+    #[prost(string, tag = "11")]
+    pub shipment_tracking: ::prost::alloc::string::String,
+    /// 0 until shipped
+    #[prost(int64, tag = "12")]
+    pub shipped_at: i64,
+}
+/// ItemTracking identifies the physical units shipped for an order item.
+/// A serial number always covers a single unit.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ItemTracking {
+    #[prost(string, tag = "1")]
+    pub serial_number: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub lot_number: ::prost::alloc::string::String,
+    #[prost(int32, tag = "3")]
+    pub quantity: i32,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Order {
@@ -20,8 +49,9 @@ pub struct Order {
     pub user_id: ::prost::alloc::string::String,
     #[prost(message, repeated, tag = "3")]
     pub items: ::prost::alloc::vec::Vec<OrderItem>,
-    #[prost(double, tag = "4")]
-    pub total_amount: f64,
+    /// decimal string
+    #[prost(string, tag = "4")]
+    pub total_amount: ::prost::alloc::string::String,
     #[prost(enumeration = "OrderStatus", tag = "5")]
     pub status: i32,
     #[prost(string, tag = "6")]
@@ -30,15 +60,65 @@ pub struct Order {
     pub created_at: i64,
     #[prost(int64, tag = "8")]
     pub updated_at: i64,
+    /// human-friendly number, e.g. ORD-2024-000123
+    #[prost(string, tag = "9")]
+    pub order_number: ::prost::alloc::string::String,
+    /// included in total_amount
+    #[prost(string, tag = "10")]
+    pub tax_amount: ::prost::alloc::string::String,
+    #[prost(string, tag = "11")]
+    pub shipping_region: ::prost::alloc::string::String,
+    /// empty when none was chosen
+    #[prost(string, tag = "12")]
+    pub shipping_method: ::prost::alloc::string::String,
+    /// included in total_amount
+    #[prost(string, tag = "13")]
+    pub shipping_fee: ::prost::alloc::string::String,
+    /// ISO 4217 code every amount is in
+    #[prost(string, tag = "14")]
+    pub currency: ::prost::alloc::string::String,
+    /// units of currency per unit of the store's default currency, as of creation
+    #[prost(string, tag = "15")]
+    pub exchange_rate: ::prost::alloc::string::String,
+    /// total_amount with its currency, e.g. €12.50
+    #[prost(string, tag = "16")]
+    pub formatted_total: ::prost::alloc::string::String,
+    /// delivery window, YYYY-MM-DD inclusive; empty when the order doesn't ship
+    #[prost(string, tag = "17")]
+    pub estimated_delivery_from: ::prost::alloc::string::String,
+    #[prost(string, tag = "18")]
+    pub estimated_delivery_to: ::prost::alloc::string::String,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CreateOrderRequest {
+    /// required unless the order is a guest's
     #[prost(string, tag = "1")]
     pub user_id: ::prost::alloc::string::String,
     #[prost(message, repeated, tag = "2")]
     pub items: ::prost::alloc::vec::Vec<OrderItem>,
+    /// required unless every item is a digital product
     #[prost(string, tag = "3")]
     pub shipping_address: ::prost::alloc::string::String,
+    /// ISO 3166 country or subdivision code of the shipping address, e.g. US-CA;
+    /// selects the tax rates. Empty charges no tax
+    #[prost(string, tag = "4")]
+    pub shipping_region: ::prost::alloc::string::String,
+    /// one of the methods QuoteShipping offers for the items and region; its
+    /// fee is added to the total. Empty charges no shipping
+    #[prost(string, tag = "5")]
+    pub shipping_method: ::prost::alloc::string::String,
+    /// one of the store's accepted currencies; prices are converted into it.
+    /// Empty charges the default currency
+    #[prost(string, tag = "6")]
+    pub currency: ::prost::alloc::string::String,
+    /// ISO 3166 code of the billing address; fraud screening holds orders
+    /// billed to another country than they ship to
+    #[prost(string, tag = "7")]
+    pub billing_region: ::prost::alloc::string::String,
+    /// checks out as a guest instead of user_id, when the store allows guest
+    /// checkout; the order belongs to the email address until it's claimed
+    #[prost(string, tag = "8")]
+    pub guest_email: ::prost::alloc::string::String,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CreateOrderResponse {
@@ -52,13 +132,150 @@ pub struct CreateOrderResponse {
     pub order: ::core::option::Option<Order>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QuoteOrderRequest {
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "2")]
+    pub items: ::prost::alloc::vec::Vec<OrderItem>,
+    #[prost(string, tag = "3")]
+    pub shipping_address: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub shipping_region: ::prost::alloc::string::String,
+    #[prost(string, tag = "5")]
+    pub shipping_method: ::prost::alloc::string::String,
+    #[prost(string, tag = "6")]
+    pub currency: ::prost::alloc::string::String,
+    #[prost(string, tag = "7")]
+    pub guest_email: ::prost::alloc::string::String,
+}
+/// QuoteOrderResponse carries the totals CreateOrder would charge. success is
+/// false when any problem would make CreateOrder reject the request.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QuoteOrderResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "3")]
+    pub items: ::prost::alloc::vec::Vec<OrderItem>,
+    #[prost(string, tag = "4")]
+    pub total_amount: ::prost::alloc::string::String,
+    #[prost(string, repeated, tag = "5")]
+    pub problems: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// included in total_amount
+    #[prost(string, tag = "6")]
+    pub tax_amount: ::prost::alloc::string::String,
+    /// included in total_amount
+    #[prost(string, tag = "7")]
+    pub shipping_fee: ::prost::alloc::string::String,
+    #[prost(string, tag = "8")]
+    pub currency: ::prost::alloc::string::String,
+    #[prost(string, tag = "9")]
+    pub formatted_total: ::prost::alloc::string::String,
+    /// delivery window with the chosen shipping method, YYYY-MM-DD inclusive
+    #[prost(string, tag = "10")]
+    pub estimated_delivery_from: ::prost::alloc::string::String,
+    #[prost(string, tag = "11")]
+    pub estimated_delivery_to: ::prost::alloc::string::String,
+}
+/// DraftOrder is a quote saved for later: priced like an order, but holding
+/// no stock until it's finalized.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DraftOrder {
+    #[prost(string, tag = "1")]
+    pub draft_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub user_id: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "3")]
+    pub items: ::prost::alloc::vec::Vec<OrderItem>,
+    #[prost(string, tag = "4")]
+    pub total_amount: ::prost::alloc::string::String,
+    /// included in total_amount
+    #[prost(string, tag = "5")]
+    pub tax_amount: ::prost::alloc::string::String,
+    /// included in total_amount
+    #[prost(string, tag = "6")]
+    pub shipping_fee: ::prost::alloc::string::String,
+    #[prost(string, tag = "7")]
+    pub currency: ::prost::alloc::string::String,
+    #[prost(string, tag = "8")]
+    pub formatted_total: ::prost::alloc::string::String,
+    #[prost(enumeration = "DraftOrderStatus", tag = "9")]
+    pub status: i32,
+    #[prost(int64, tag = "10")]
+    pub expires_at: i64,
+    #[prost(int64, tag = "11")]
+    pub created_at: i64,
+    /// the order placed from it, once finalized
+    #[prost(string, tag = "12")]
+    pub order_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "13")]
+    pub shipping_address: ::prost::alloc::string::String,
+    #[prost(string, tag = "14")]
+    pub shipping_region: ::prost::alloc::string::String,
+    #[prost(string, tag = "15")]
+    pub shipping_method: ::prost::alloc::string::String,
+    #[prost(string, tag = "16")]
+    pub billing_region: ::prost::alloc::string::String,
+}
+/// CreateDraftOrderRequest takes CreateOrder's fields, for a registered user.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateDraftOrderRequest {
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "2")]
+    pub items: ::prost::alloc::vec::Vec<OrderItem>,
+    #[prost(string, tag = "3")]
+    pub shipping_address: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub shipping_region: ::prost::alloc::string::String,
+    #[prost(string, tag = "5")]
+    pub shipping_method: ::prost::alloc::string::String,
+    #[prost(string, tag = "6")]
+    pub currency: ::prost::alloc::string::String,
+    #[prost(string, tag = "7")]
+    pub billing_region: ::prost::alloc::string::String,
+    /// 0 means 30; at most 90
+    #[prost(int32, tag = "8")]
+    pub valid_days: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateDraftOrderResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "3")]
+    pub draft: ::core::option::Option<DraftOrder>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FinalizeDraftOrderRequest {
+    #[prost(string, tag = "1")]
+    pub draft_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FinalizeDraftOrderResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub order_id: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "4")]
+    pub order: ::core::option::Option<Order>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct UpdateOrderRequest {
     #[prost(string, tag = "1")]
     pub order_id: ::prost::alloc::string::String,
     #[prost(enumeration = "OrderStatus", tag = "2")]
     pub status: i32,
+    /// empty keeps the current address
     #[prost(string, tag = "3")]
     pub shipping_address: ::prost::alloc::string::String,
+    /// kept in the order history
+    #[prost(string, tag = "4")]
+    pub reason: ::prost::alloc::string::String,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct UpdateOrderResponse {
@@ -75,6 +292,9 @@ pub struct CancelOrderRequest {
     pub order_id: ::prost::alloc::string::String,
     #[prost(string, tag = "2")]
     pub user_id: ::prost::alloc::string::String,
+    /// kept in the order history
+    #[prost(string, tag = "3")]
+    pub reason: ::prost::alloc::string::String,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CancelOrderResponse {
@@ -84,9 +304,39 @@ pub struct CancelOrderResponse {
     pub message: ::prost::alloc::string::String,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CancelItem {
+    #[prost(string, tag = "1")]
+    pub item_id: ::prost::alloc::string::String,
+    /// units to cancel; all of them removes the item
+    #[prost(int32, tag = "2")]
+    pub quantity: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CancelOrderItemsRequest {
+    #[prost(string, tag = "1")]
+    pub order_id: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "2")]
+    pub items: ::prost::alloc::vec::Vec<CancelItem>,
+    /// kept in the order history
+    #[prost(string, tag = "3")]
+    pub reason: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CancelOrderItemsResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "3")]
+    pub order: ::core::option::Option<Order>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetOrderRequest {
     #[prost(string, tag = "1")]
     pub order_id: ::prost::alloc::string::String,
+    /// used when order_id is empty
+    #[prost(string, tag = "2")]
+    pub order_number: ::prost::alloc::string::String,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetOrderResponse {
@@ -97,7 +347,25 @@ pub struct GetOrderResponse {
     #[prost(message, optional, tag = "3")]
     pub order: ::core::option::Option<Order>,
 }
-#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetOrdersByIDsRequest {
+    #[prost(string, repeated, tag = "1")]
+    pub order_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+/// Orders come back in the order of the request, once per id; ids with no
+/// order are listed in missing_ids.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetOrdersByIDsResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "3")]
+    pub orders: ::prost::alloc::vec::Vec<Order>,
+    #[prost(string, repeated, tag = "4")]
+    pub missing_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ListOrdersRequest {
     #[prost(int32, tag = "1")]
     pub page: i32,
@@ -105,6 +373,25 @@ pub struct ListOrdersRequest {
     pub page_size: i32,
     #[prost(enumeration = "OrderStatus", tag = "3")]
     pub status: i32,
+    /// next_page_token of the previous page; when set, page is ignored
+    #[prost(string, tag = "4")]
+    pub page_token: ::prost::alloc::string::String,
+    /// Optional filters; 0 or empty leaves a bound open
+    ///
+    /// unix seconds, inclusive
+    #[prost(int64, tag = "5")]
+    pub created_after: i64,
+    /// unix seconds, exclusive
+    #[prost(int64, tag = "6")]
+    pub created_before: i64,
+    /// total_amount, inclusive
+    #[prost(string, tag = "7")]
+    pub min_amount: ::prost::alloc::string::String,
+    /// total_amount, inclusive
+    #[prost(string, tag = "8")]
+    pub max_amount: ::prost::alloc::string::String,
+    #[prost(string, tag = "9")]
+    pub user_id: ::prost::alloc::string::String,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ListOrdersResponse {
@@ -116,6 +403,77 @@ pub struct ListOrdersResponse {
     pub orders: ::prost::alloc::vec::Vec<Order>,
     #[prost(int32, tag = "4")]
     pub total_count: i32,
+    /// empty on the last page
+    #[prost(string, tag = "5")]
+    pub next_page_token: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExportOrdersRequest {
+    /// 0 leaves a bound open
+    ///
+    /// unix seconds, inclusive
+    #[prost(int64, tag = "1")]
+    pub created_after: i64,
+    /// unix seconds, exclusive
+    #[prost(int64, tag = "2")]
+    pub created_before: i64,
+    /// cursor of the last message received; the export resumes after it
+    #[prost(string, tag = "3")]
+    pub cursor: ::prost::alloc::string::String,
+    /// orders per streamed message, defaults to 500
+    #[prost(int32, tag = "4")]
+    pub page_size: i32,
+}
+/// An order item with its order's fields repeated; orders without items get
+/// one row with the item fields empty. Amounts are decimal strings in the
+/// order's currency
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OrderExportRow {
+    #[prost(string, tag = "1")]
+    pub order_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub order_number: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub user_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub status: ::prost::alloc::string::String,
+    #[prost(string, tag = "5")]
+    pub currency: ::prost::alloc::string::String,
+    #[prost(string, tag = "6")]
+    pub exchange_rate: ::prost::alloc::string::String,
+    #[prost(string, tag = "7")]
+    pub order_total: ::prost::alloc::string::String,
+    #[prost(string, tag = "8")]
+    pub order_tax: ::prost::alloc::string::String,
+    #[prost(string, tag = "9")]
+    pub shipping_fee: ::prost::alloc::string::String,
+    #[prost(string, tag = "10")]
+    pub shipping_region: ::prost::alloc::string::String,
+    /// unix seconds
+    #[prost(int64, tag = "11")]
+    pub created_at: i64,
+    #[prost(string, tag = "12")]
+    pub item_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "13")]
+    pub product_id: ::prost::alloc::string::String,
+    #[prost(int32, tag = "14")]
+    pub quantity: i32,
+    #[prost(string, tag = "15")]
+    pub unit_price: ::prost::alloc::string::String,
+    #[prost(string, tag = "16")]
+    pub item_tax: ::prost::alloc::string::String,
+    #[prost(string, tag = "17")]
+    pub item_status: ::prost::alloc::string::String,
+    #[prost(string, tag = "18")]
+    pub fulfillment_status: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExportOrdersResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub rows: ::prost::alloc::vec::Vec<OrderExportRow>,
+    /// pass as ExportOrdersRequest.cursor to resume after this message
+    #[prost(string, tag = "2")]
+    pub cursor: ::prost::alloc::string::String,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetOrdersByUserRequest {
@@ -125,6 +483,15 @@ pub struct GetOrdersByUserRequest {
     pub page: i32,
     #[prost(int32, tag = "3")]
     pub page_size: i32,
+    /// next_page_token of the previous page; when set, page is ignored. Pass
+    /// the same status and sort as for the first page
+    #[prost(string, tag = "4")]
+    pub page_token: ::prost::alloc::string::String,
+    /// PENDING (0) returns every status
+    #[prost(enumeration = "OrderStatus", tag = "5")]
+    pub status: i32,
+    #[prost(enumeration = "OrderSort", tag = "6")]
+    pub sort: i32,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetOrdersByUserResponse {
@@ -136,195 +503,652 @@ pub struct GetOrdersByUserResponse {
     pub orders: ::prost::alloc::vec::Vec<Order>,
     #[prost(int32, tag = "4")]
     pub total_count: i32,
+    /// empty on the last page
+    #[prost(string, tag = "5")]
+    pub next_page_token: ::prost::alloc::string::String,
 }
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
-#[repr(i32)]
-pub enum OrderStatus {
-    Pending = 0,
-    Confirmed = 1,
-    Processing = 2,
-    Shipped = 3,
-    Delivered = 4,
-    Cancelled = 5,
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RecordItemTrackingRequest {
+    #[prost(string, tag = "1")]
+    pub order_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub item_id: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "3")]
+    pub tracking: ::prost::alloc::vec::Vec<ItemTracking>,
 }
-impl OrderStatus {
-    /// String value of the enum field names used in the ProtoBuf definition.
-    ///
-    /// The values are not transformed in any way and thus are considered stable
-    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
-    pub fn as_str_name(&self) -> &'static str {
-        match self {
-            Self::Pending => "PENDING",
-            Self::Confirmed => "CONFIRMED",
-            Self::Processing => "PROCESSING",
-            Self::Shipped => "SHIPPED",
-            Self::Delivered => "DELIVERED",
-            Self::Cancelled => "CANCELLED",
-        }
-    }
-    /// Creates an enum from field names used in the ProtoBuf definition.
-    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
-        match value {
-            "PENDING" => Some(Self::Pending),
-            "CONFIRMED" => Some(Self::Confirmed),
-            "PROCESSING" => Some(Self::Processing),
-            "SHIPPED" => Some(Self::Shipped),
-            "DELIVERED" => Some(Self::Delivered),
-            "CANCELLED" => Some(Self::Cancelled),
-            _ => None,
-        }
-    }
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RecordItemTrackingResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "3")]
+    pub tracking: ::prost::alloc::vec::Vec<ItemTracking>,
 }
-/// Generated client implementations.
-pub mod order_service_client {
-    #![allow(
-        unused_variables,
-        dead_code,
-        missing_docs,
-        clippy::wildcard_imports,
-        clippy::let_unit_value,
-    )]
-    use tonic::codegen::*;
-    use tonic::codegen::http::Uri;
-    /// OrderService manages customer orders and related operations
-    #[derive(Debug, Clone)]
-    pub struct OrderServiceClient<T> {
-        inner: tonic::client::Grpc<T>,
-    }
-    impl OrderServiceClient<tonic::transport::Channel> {
-        /// Attempt to create a new client by connecting to a given endpoint.
-        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
-        where
-            D: TryInto<tonic::transport::Endpoint>,
-            D::Error: Into<StdError>,
-        {
-            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
-            Ok(Self::new(conn))
-        }
-    }
-    impl<T> OrderServiceClient<T>
-    where
-        T: tonic::client::GrpcService<tonic::body::BoxBody>,
-        T::Error: Into<StdError>,
-        T::ResponseBody: Body<Data = Bytes> + std::marker::Send + 'static,
-        <T::ResponseBody as Body>::Error: Into<StdError> + std::marker::Send,
-    {
-        pub fn new(inner: T) -> Self {
-            let inner = tonic::client::Grpc::new(inner);
-            Self { inner }
-        }
-        pub fn with_origin(inner: T, origin: Uri) -> Self {
-            let inner = tonic::client::Grpc::with_origin(inner, origin);
-            Self { inner }
-        }
-        pub fn with_interceptor<F>(
-            inner: T,
-            interceptor: F,
-        ) -> OrderServiceClient<InterceptedService<T, F>>
-        where
-            F: tonic::service::Interceptor,
-            T::ResponseBody: Default,
-            T: tonic::codegen::Service<
-                http::Request<tonic::body::BoxBody>,
-                Response = http::Response<
-                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
-                >,
-            >,
-            <T as tonic::codegen::Service<
-                http::Request<tonic::body::BoxBody>,
-            >>::Error: Into<StdError> + std::marker::Send + std::marker::Sync,
-        {
-            OrderServiceClient::new(InterceptedService::new(inner, interceptor))
-        }
-        /// Compress requests with the given encoding.
-        ///
-        /// This requires the server to support it otherwise it might respond with an
-        /// error.
-        #[must_use]
-        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
-            self.inner = self.inner.send_compressed(encoding);
-            self
-        }
-        /// Enable decompressing responses.
-        #[must_use]
-        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
-            self.inner = self.inner.accept_compressed(encoding);
-            self
-        }
-        /// Limits the maximum size of a decoded message.
-        ///
-        /// Default: `4MB`
-        #[must_use]
-        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
-            self.inner = self.inner.max_decoding_message_size(limit);
-            self
-        }
-        /// Limits the maximum size of an encoded message.
-        ///
-        /// Default: `usize::MAX`
-        #[must_use]
-        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
-            self.inner = self.inner.max_encoding_message_size(limit);
-            self
-        }
-        /// Creates a new order
-        pub async fn create_order(
-            &mut self,
-            request: impl tonic::IntoRequest<super::CreateOrderRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::CreateOrderResponse>,
-            tonic::Status,
-        > {
-            self.inner
-                .ready()
-                .await
-                .map_err(|e| {
-                    tonic::Status::unknown(
-                        format!("Service was not ready: {}", e.into()),
-                    )
-                })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static(
-                "/order.OrderService/CreateOrder",
-            );
-            let mut req = request.into_request();
-            req.extensions_mut()
-                .insert(GrpcMethod::new("order.OrderService", "CreateOrder"));
-            self.inner.unary(req, path, codec).await
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FindOrdersByLotRequest {
+    #[prost(string, tag = "1")]
+    pub lot_number: ::prost::alloc::string::String,
+    /// optional, narrows lots shared across products
+    #[prost(string, tag = "2")]
+    pub product_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LotOrderItem {
+    #[prost(string, tag = "1")]
+    pub order_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub user_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub item_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub product_id: ::prost::alloc::string::String,
+    #[prost(int32, tag = "5")]
+    pub quantity: i32,
+    #[prost(enumeration = "OrderStatus", tag = "6")]
+    pub status: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FindOrdersByLotResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "3")]
+    pub items: ::prost::alloc::vec::Vec<LotOrderItem>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VerifyPurchaseRequest {
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub product_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VerifyPurchaseResponse {
+    #[prost(bool, tag = "1")]
+    pub purchased: bool,
+    /// most recent delivered order containing the product
+    #[prost(string, tag = "2")]
+    pub order_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RefundItem {
+    #[prost(string, tag = "1")]
+    pub item_id: ::prost::alloc::string::String,
+    #[prost(int32, tag = "2")]
+    pub quantity: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Refund {
+    #[prost(string, tag = "1")]
+    pub refund_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub order_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub payment_id: ::prost::alloc::string::String,
+    /// decimal string
+    #[prost(string, tag = "4")]
+    pub amount: ::prost::alloc::string::String,
+    #[prost(enumeration = "RefundStatus", tag = "5")]
+    pub status: i32,
+    #[prost(string, tag = "6")]
+    pub reason: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "7")]
+    pub items: ::prost::alloc::vec::Vec<RefundItem>,
+    #[prost(string, tag = "8")]
+    pub payment_refund_id: ::prost::alloc::string::String,
+    #[prost(int64, tag = "9")]
+    pub created_at: i64,
+}
+/// Without items or amount, RefundOrder refunds the whole order: every item
+/// not returned yet comes back, and whatever is left of the payment is refunded.
+/// A refund left pending by an unanswered payment call is sent again first, and
+/// answered instead of the request.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RefundOrderRequest {
+    #[prost(string, tag = "1")]
+    pub order_id: ::prost::alloc::string::String,
+    /// items returned
+    #[prost(message, repeated, tag = "2")]
+    pub items: ::prost::alloc::vec::Vec<RefundItem>,
+    /// decimal string; defaults to the price of the returned items
+    #[prost(string, tag = "3")]
+    pub amount: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub reason: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RefundOrderResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "3")]
+    pub refund: ::core::option::Option<Refund>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListRefundsRequest {
+    #[prost(string, tag = "1")]
+    pub order_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListRefundsResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "3")]
+    pub refunds: ::prost::alloc::vec::Vec<Refund>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RecordShipmentEventRequest {
+    #[prost(string, tag = "1")]
+    pub order_id: ::prost::alloc::string::String,
+    #[prost(enumeration = "ShipmentEvent", tag = "2")]
+    pub event: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RecordShipmentEventResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "3")]
+    pub order: ::core::option::Option<Order>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MarkItemsShippedRequest {
+    #[prost(string, tag = "1")]
+    pub order_id: ::prost::alloc::string::String,
+    #[prost(string, repeated, tag = "2")]
+    pub item_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// e.g. the carrier and tracking number
+    #[prost(string, tag = "3")]
+    pub tracking: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MarkItemsShippedResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "3")]
+    pub order: ::core::option::Option<Order>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateDeliveryEstimateRequest {
+    #[prost(string, tag = "1")]
+    pub order_id: ::prost::alloc::string::String,
+    /// YYYY-MM-DD
+    #[prost(string, tag = "2")]
+    pub from_date: ::prost::alloc::string::String,
+    /// YYYY-MM-DD inclusive, not before from_date
+    #[prost(string, tag = "3")]
+    pub to_date: ::prost::alloc::string::String,
+    /// kept in the order history
+    #[prost(string, tag = "4")]
+    pub reason: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateDeliveryEstimateResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "3")]
+    pub order: ::core::option::Option<Order>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ClaimGuestOrdersRequest {
+    /// the token of the guest order's confirmation email
+    #[prost(string, tag = "1")]
+    pub email_token: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ClaimGuestOrdersResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    /// the orders claimed
+    #[prost(string, repeated, tag = "3")]
+    pub order_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ApproveOrderRequest {
+    #[prost(string, tag = "1")]
+    pub order_id: ::prost::alloc::string::String,
+    /// kept in the order history
+    #[prost(string, tag = "2")]
+    pub reason: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ApproveOrderResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "3")]
+    pub order: ::core::option::Option<Order>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RejectOrderRequest {
+    #[prost(string, tag = "1")]
+    pub order_id: ::prost::alloc::string::String,
+    /// kept in the order history
+    #[prost(string, tag = "2")]
+    pub reason: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RejectOrderResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OrderEvent {
+    #[prost(int64, tag = "1")]
+    pub event_id: i64,
+    #[prost(enumeration = "OrderEventType", tag = "2")]
+    pub event_type: i32,
+    /// empty for events that keep the status
+    #[prost(string, tag = "3")]
+    pub from_status: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub to_status: ::prost::alloc::string::String,
+    /// platform, user:<id>, seller:<id> or system:<service>
+    #[prost(string, tag = "5")]
+    pub actor: ::prost::alloc::string::String,
+    #[prost(string, tag = "6")]
+    pub reason: ::prost::alloc::string::String,
+    #[prost(string, tag = "7")]
+    pub details: ::prost::alloc::string::String,
+    #[prost(int64, tag = "8")]
+    pub created_at: i64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetOrderHistoryRequest {
+    #[prost(string, tag = "1")]
+    pub order_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetOrderHistoryResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "3")]
+    pub events: ::prost::alloc::vec::Vec<OrderEvent>,
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum OrderStatus {
+    Pending = 0,
+    Confirmed = 1,
+    Processing = 2,
+    Shipped = 3,
+    Delivered = 4,
+    Cancelled = 5,
+    /// some of its items have shipped
+    PartiallyShipped = 6,
+    /// flagged by fraud screening, awaiting review
+    OnHold = 7,
+}
+impl OrderStatus {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Pending => "PENDING",
+            Self::Confirmed => "CONFIRMED",
+            Self::Processing => "PROCESSING",
+            Self::Shipped => "SHIPPED",
+            Self::Delivered => "DELIVERED",
+            Self::Cancelled => "CANCELLED",
+            Self::PartiallyShipped => "PARTIALLY_SHIPPED",
+            Self::OnHold => "ON_HOLD",
         }
-        pub async fn update_order(
-            &mut self,
-            request: impl tonic::IntoRequest<super::UpdateOrderRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::UpdateOrderResponse>,
-            tonic::Status,
-        > {
-            self.inner
-                .ready()
-                .await
-                .map_err(|e| {
-                    tonic::Status::unknown(
-                        format!("Service was not ready: {}", e.into()),
-                    )
-                })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static(
-                "/order.OrderService/UpdateOrder",
-            );
-            let mut req = request.into_request();
-            req.extensions_mut()
-                .insert(GrpcMethod::new("order.OrderService", "UpdateOrder"));
-            self.inner.unary(req, path, codec).await
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "PENDING" => Some(Self::Pending),
+            "CONFIRMED" => Some(Self::Confirmed),
+            "PROCESSING" => Some(Self::Processing),
+            "SHIPPED" => Some(Self::Shipped),
+            "DELIVERED" => Some(Self::Delivered),
+            "CANCELLED" => Some(Self::Cancelled),
+            "PARTIALLY_SHIPPED" => Some(Self::PartiallyShipped),
+            "ON_HOLD" => Some(Self::OnHold),
+            _ => None,
         }
-        pub async fn cancel_order(
-            &mut self,
-            request: impl tonic::IntoRequest<super::CancelOrderRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::CancelOrderResponse>,
-            tonic::Status,
-        > {
-            self.inner
-                .ready()
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ItemFulfillmentStatus {
+    ItemUnfulfilled = 0,
+    ItemShipped = 1,
+}
+impl ItemFulfillmentStatus {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::ItemUnfulfilled => "ITEM_UNFULFILLED",
+            Self::ItemShipped => "ITEM_SHIPPED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "ITEM_UNFULFILLED" => Some(Self::ItemUnfulfilled),
+            "ITEM_SHIPPED" => Some(Self::ItemShipped),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum OrderItemStatus {
+    /// taken from stock when the order was placed
+    Allocated = 0,
+    /// backordered or preordered; no stock taken yet
+    Backordered = 1,
+}
+impl OrderItemStatus {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Allocated => "ALLOCATED",
+            Self::Backordered => "BACKORDERED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "ALLOCATED" => Some(Self::Allocated),
+            "BACKORDERED" => Some(Self::Backordered),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum DraftOrderStatus {
+    DraftOpen = 0,
+    /// placed as order_id
+    DraftFinalized = 1,
+    /// not finalized before expires_at
+    DraftExpired = 2,
+}
+impl DraftOrderStatus {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::DraftOpen => "DRAFT_OPEN",
+            Self::DraftFinalized => "DRAFT_FINALIZED",
+            Self::DraftExpired => "DRAFT_EXPIRED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "DRAFT_OPEN" => Some(Self::DraftOpen),
+            "DRAFT_FINALIZED" => Some(Self::DraftFinalized),
+            "DRAFT_EXPIRED" => Some(Self::DraftExpired),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum OrderSort {
+    NewestFirst = 0,
+    OldestFirst = 1,
+}
+impl OrderSort {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::NewestFirst => "NEWEST_FIRST",
+            Self::OldestFirst => "OLDEST_FIRST",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "NEWEST_FIRST" => Some(Self::NewestFirst),
+            "OLDEST_FIRST" => Some(Self::OldestFirst),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum RefundStatus {
+    /// the payment service hasn't answered yet
+    RefundPending = 0,
+    RefundCompleted = 1,
+    RefundFailed = 2,
+}
+impl RefundStatus {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::RefundPending => "REFUND_PENDING",
+            Self::RefundCompleted => "REFUND_COMPLETED",
+            Self::RefundFailed => "REFUND_FAILED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "REFUND_PENDING" => Some(Self::RefundPending),
+            "REFUND_COMPLETED" => Some(Self::RefundCompleted),
+            "REFUND_FAILED" => Some(Self::RefundFailed),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ShipmentEvent {
+    /// the order becomes SHIPPED
+    ShipmentInTransit = 0,
+    /// every shipment delivered; the order becomes DELIVERED
+    ShipmentDelivered = 1,
+}
+impl ShipmentEvent {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::ShipmentInTransit => "SHIPMENT_IN_TRANSIT",
+            Self::ShipmentDelivered => "SHIPMENT_DELIVERED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "SHIPMENT_IN_TRANSIT" => Some(Self::ShipmentInTransit),
+            "SHIPMENT_DELIVERED" => Some(Self::ShipmentDelivered),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum OrderEventType {
+    OrderCreated = 0,
+    OrderStatusChanged = 1,
+    OrderCancelled = 2,
+    OrderRefunded = 3,
+    OrderAddressChanged = 4,
+    OrderItemsCancelled = 5,
+    OrderItemsShipped = 6,
+    /// a guest order moved to the account that claimed it
+    OrderClaimed = 7,
+    OrderDeliveryEstimateChanged = 8,
+}
+impl OrderEventType {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::OrderCreated => "ORDER_CREATED",
+            Self::OrderStatusChanged => "ORDER_STATUS_CHANGED",
+            Self::OrderCancelled => "ORDER_CANCELLED",
+            Self::OrderRefunded => "ORDER_REFUNDED",
+            Self::OrderAddressChanged => "ORDER_ADDRESS_CHANGED",
+            Self::OrderItemsCancelled => "ORDER_ITEMS_CANCELLED",
+            Self::OrderItemsShipped => "ORDER_ITEMS_SHIPPED",
+            Self::OrderClaimed => "ORDER_CLAIMED",
+            Self::OrderDeliveryEstimateChanged => "ORDER_DELIVERY_ESTIMATE_CHANGED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "ORDER_CREATED" => Some(Self::OrderCreated),
+            "ORDER_STATUS_CHANGED" => Some(Self::OrderStatusChanged),
+            "ORDER_CANCELLED" => Some(Self::OrderCancelled),
+            "ORDER_REFUNDED" => Some(Self::OrderRefunded),
+            "ORDER_ADDRESS_CHANGED" => Some(Self::OrderAddressChanged),
+            "ORDER_ITEMS_CANCELLED" => Some(Self::OrderItemsCancelled),
+            "ORDER_ITEMS_SHIPPED" => Some(Self::OrderItemsShipped),
+            "ORDER_CLAIMED" => Some(Self::OrderClaimed),
+            "ORDER_DELIVERY_ESTIMATE_CHANGED" => Some(Self::OrderDeliveryEstimateChanged),
+            _ => None,
+        }
+    }
+}
+/// Generated client implementations.
+pub mod order_service_client {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value,
+    )]
+    use tonic::codegen::*;
+    use tonic::codegen::http::Uri;
+    /// OrderService manages customer orders and related operations. Refused
+    /// requests fail with a gRPC status and a google.rpc.ErrorInfo reason, e.g.
+    /// FAILED_PRECONDITION / ORDER_CANCELLED, rather than success = false.
+    #[derive(Debug, Clone)]
+    pub struct OrderServiceClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl OrderServiceClient<tonic::transport::Channel> {
+        /// Attempt to create a new client by connecting to a given endpoint.
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> OrderServiceClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + std::marker::Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + std::marker::Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> OrderServiceClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+            >>::Error: Into<StdError> + std::marker::Send + std::marker::Sync,
+        {
+            OrderServiceClient::new(InterceptedService::new(inner, interceptor))
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_decoding_message_size(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_encoding_message_size(limit);
+            self
+        }
+        /// Creates a new order
+        pub async fn create_order(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CreateOrderRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CreateOrderResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
                 .await
                 .map_err(|e| {
                     tonic::Status::unknown(
@@ -333,18 +1157,19 @@ pub mod order_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/order.OrderService/CancelOrder",
+                "/order.OrderService/CreateOrder",
             );
             let mut req = request.into_request();
             req.extensions_mut()
-                .insert(GrpcMethod::new("order.OrderService", "CancelOrder"));
+                .insert(GrpcMethod::new("order.OrderService", "CreateOrder"));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn get_order(
+        /// Runs CreateOrder's validation and pricing without placing the order
+        pub async fn quote_order(
             &mut self,
-            request: impl tonic::IntoRequest<super::GetOrderRequest>,
+            request: impl tonic::IntoRequest<super::QuoteOrderRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GetOrderResponse>,
+            tonic::Response<super::QuoteOrderResponse>,
             tonic::Status,
         > {
             self.inner
@@ -357,18 +1182,20 @@ pub mod order_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/order.OrderService/GetOrder",
+                "/order.OrderService/QuoteOrder",
             );
             let mut req = request.into_request();
             req.extensions_mut()
-                .insert(GrpcMethod::new("order.OrderService", "GetOrder"));
+                .insert(GrpcMethod::new("order.OrderService", "QuoteOrder"));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn list_orders(
+        /// Saves a priced quote, e.g. for a B2B buyer to get approved, without
+        /// reserving stock; its prices hold until it expires
+        pub async fn create_draft_order(
             &mut self,
-            request: impl tonic::IntoRequest<super::ListOrdersRequest>,
+            request: impl tonic::IntoRequest<super::CreateDraftOrderRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::ListOrdersResponse>,
+            tonic::Response<super::CreateDraftOrderResponse>,
             tonic::Status,
         > {
             self.inner
@@ -381,18 +1208,20 @@ pub mod order_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/order.OrderService/ListOrders",
+                "/order.OrderService/CreateDraftOrder",
             );
             let mut req = request.into_request();
             req.extensions_mut()
-                .insert(GrpcMethod::new("order.OrderService", "ListOrders"));
+                .insert(GrpcMethod::new("order.OrderService", "CreateDraftOrder"));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn get_orders_by_user(
+        /// Places an open draft as an order at the draft's prices, reserving the
+        /// stock only now
+        pub async fn finalize_draft_order(
             &mut self,
-            request: impl tonic::IntoRequest<super::GetOrdersByUserRequest>,
+            request: impl tonic::IntoRequest<super::FinalizeDraftOrderRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GetOrdersByUserResponse>,
+            tonic::Response<super::FinalizeDraftOrderResponse>,
             tonic::Status,
         > {
             self.inner
@@ -405,168 +1234,1424 @@ pub mod order_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/order.OrderService/GetOrdersByUser",
+                "/order.OrderService/FinalizeDraftOrder",
             );
             let mut req = request.into_request();
             req.extensions_mut()
-                .insert(GrpcMethod::new("order.OrderService", "GetOrdersByUser"));
+                .insert(GrpcMethod::new("order.OrderService", "FinalizeDraftOrder"));
             self.inner.unary(req, path, codec).await
         }
-    }
-}
-/// Generated server implementations.
-pub mod order_service_server {
-    #![allow(
-        unused_variables,
-        dead_code,
-        missing_docs,
-        clippy::wildcard_imports,
-        clippy::let_unit_value,
-    )]
-    use tonic::codegen::*;
-    /// Generated trait containing gRPC methods that should be implemented for use with OrderServiceServer.
-    #[async_trait]
-    pub trait OrderService: std::marker::Send + std::marker::Sync + 'static {
-        /// Creates a new order
-        async fn create_order(
-            &self,
-            request: tonic::Request<super::CreateOrderRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::CreateOrderResponse>,
-            tonic::Status,
-        >;
-        async fn update_order(
-            &self,
-            request: tonic::Request<super::UpdateOrderRequest>,
+        pub async fn update_order(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdateOrderRequest>,
         ) -> std::result::Result<
             tonic::Response<super::UpdateOrderResponse>,
             tonic::Status,
-        >;
-        async fn cancel_order(
-            &self,
-            request: tonic::Request<super::CancelOrderRequest>,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/order.OrderService/UpdateOrder",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("order.OrderService", "UpdateOrder"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn cancel_order(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CancelOrderRequest>,
         ) -> std::result::Result<
             tonic::Response<super::CancelOrderResponse>,
             tonic::Status,
-        >;
-        async fn get_order(
-            &self,
-            request: tonic::Request<super::GetOrderRequest>,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/order.OrderService/CancelOrder",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("order.OrderService", "CancelOrder"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Cancels some units of an order's items before it ships, restocking them
+        /// and recomputing the order's totals
+        pub async fn cancel_order_items(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CancelOrderItemsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CancelOrderItemsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/order.OrderService/CancelOrderItems",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("order.OrderService", "CancelOrderItems"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_order(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetOrderRequest>,
         ) -> std::result::Result<
             tonic::Response<super::GetOrderResponse>,
             tonic::Status,
-        >;
-        async fn list_orders(
-            &self,
-            request: tonic::Request<super::ListOrdersRequest>,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/order.OrderService/GetOrder",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("order.OrderService", "GetOrder"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Fetches many orders at once, e.g. for analytics and admin tools
+        pub async fn get_orders_by_ids(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetOrdersByIDsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetOrdersByIDsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/order.OrderService/GetOrdersByIds",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("order.OrderService", "GetOrdersByIds"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn list_orders(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListOrdersRequest>,
         ) -> std::result::Result<
             tonic::Response<super::ListOrdersResponse>,
             tonic::Status,
-        >;
-        async fn get_orders_by_user(
-            &self,
-            request: tonic::Request<super::GetOrdersByUserRequest>,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/order.OrderService/ListOrders",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("order.OrderService", "ListOrders"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_orders_by_user(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetOrdersByUserRequest>,
         ) -> std::result::Result<
             tonic::Response<super::GetOrdersByUserResponse>,
             tonic::Status,
-        >;
-    }
-    /// OrderService manages customer orders and related operations
-    #[derive(Debug)]
-    pub struct OrderServiceServer<T> {
-        inner: Arc<T>,
-        accept_compression_encodings: EnabledCompressionEncodings,
-        send_compression_encodings: EnabledCompressionEncodings,
-        max_decoding_message_size: Option<usize>,
-        max_encoding_message_size: Option<usize>,
-    }
-    impl<T> OrderServiceServer<T> {
-        pub fn new(inner: T) -> Self {
-            Self::from_arc(Arc::new(inner))
-        }
-        pub fn from_arc(inner: Arc<T>) -> Self {
-            Self {
-                inner,
-                accept_compression_encodings: Default::default(),
-                send_compression_encodings: Default::default(),
-                max_decoding_message_size: None,
-                max_encoding_message_size: None,
-            }
-        }
-        pub fn with_interceptor<F>(
-            inner: T,
-            interceptor: F,
-        ) -> InterceptedService<Self, F>
-        where
-            F: tonic::service::Interceptor,
-        {
-            InterceptedService::new(Self::new(inner), interceptor)
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/order.OrderService/GetOrdersByUser",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("order.OrderService", "GetOrdersByUser"));
+            self.inner.unary(req, path, codec).await
         }
-        /// Enable decompressing requests with the given encoding.
-        #[must_use]
-        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
-            self.accept_compression_encodings.enable(encoding);
-            self
+        /// Streams orders oldest first as flat rows, one per item, for finance and
+        /// reporting ingestion; an interrupted export resumes from its last cursor
+        pub async fn export_orders(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ExportOrdersRequest>,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::ExportOrdersResponse>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/order.OrderService/ExportOrders",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("order.OrderService", "ExportOrders"));
+            self.inner.server_streaming(req, path, codec).await
         }
-        /// Compress responses with the given encoding, if the client supports it.
-        #[must_use]
-        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
-            self.send_compression_encodings.enable(encoding);
-            self
+        /// Records serial/lot numbers of the units shipped for an order item
+        pub async fn record_item_tracking(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RecordItemTrackingRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RecordItemTrackingResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/order.OrderService/RecordItemTracking",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("order.OrderService", "RecordItemTracking"));
+            self.inner.unary(req, path, codec).await
         }
-        /// Limits the maximum size of a decoded message.
-        ///
-        /// Default: `4MB`
-        #[must_use]
-        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
-            self.max_decoding_message_size = Some(limit);
-            self
+        /// Finds the order items that received units from a lot, e.g. for recalls
+        pub async fn find_orders_by_lot(
+            &mut self,
+            request: impl tonic::IntoRequest<super::FindOrdersByLotRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::FindOrdersByLotResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/order.OrderService/FindOrdersByLot",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("order.OrderService", "FindOrdersByLot"));
+            self.inner.unary(req, path, codec).await
         }
-        /// Limits the maximum size of an encoded message.
-        ///
-        /// Default: `usize::MAX`
-        #[must_use]
-        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
-            self.max_encoding_message_size = Some(limit);
-            self
+        /// Checks whether a user has received a product, e.g. for verified reviews;
+        /// the user's own or the platform's
+        pub async fn verify_purchase(
+            &mut self,
+            request: impl tonic::IntoRequest<super::VerifyPurchaseRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::VerifyPurchaseResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/order.OrderService/VerifyPurchase",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("order.OrderService", "VerifyPurchase"));
+            self.inner.unary(req, path, codec).await
         }
-    }
-    impl<T, B> tonic::codegen::Service<http::Request<B>> for OrderServiceServer<T>
-    where
-        T: OrderService,
-        B: Body + std::marker::Send + 'static,
-        B::Error: Into<StdError> + std::marker::Send + 'static,
-    {
-        type Response = http::Response<tonic::body::BoxBody>;
-        type Error = std::convert::Infallible;
-        type Future = BoxFuture<Self::Response, Self::Error>;
-        fn poll_ready(
+        /// Refunds a shipped or delivered order in full or in part through the
+        /// payment service, restocking the returned items
+        pub async fn refund_order(
             &mut self,
-            _cx: &mut Context<'_>,
-        ) -> Poll<std::result::Result<(), Self::Error>> {
-            Poll::Ready(Ok(()))
+            request: impl tonic::IntoRequest<super::RefundOrderRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RefundOrderResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/order.OrderService/RefundOrder",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("order.OrderService", "RefundOrder"));
+            self.inner.unary(req, path, codec).await
         }
-        fn call(&mut self, req: http::Request<B>) -> Self::Future {
-            match req.uri().path() {
-                "/order.OrderService/CreateOrder" => {
+        pub async fn list_refunds(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListRefundsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListRefundsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/order.OrderService/ListRefunds",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("order.OrderService", "ListRefunds"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Moves an order along as its shipments progress; called by the shipping service
+        pub async fn record_shipment_event(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RecordShipmentEventRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RecordShipmentEventResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/order.OrderService/RecordShipmentEvent",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("order.OrderService", "RecordShipmentEvent"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Marks some of an order's items shipped; the order is PARTIALLY_SHIPPED
+        /// until every item that ships has shipped
+        pub async fn mark_items_shipped(
+            &mut self,
+            request: impl tonic::IntoRequest<super::MarkItemsShippedRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::MarkItemsShippedResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/order.OrderService/MarkItemsShipped",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("order.OrderService", "MarkItemsShipped"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Replaces an order's estimated delivery window, e.g. with the date a
+        /// carrier expects to deliver; called by the shipping service
+        pub async fn update_delivery_estimate(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdateDeliveryEstimateRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UpdateDeliveryEstimateResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/order.OrderService/UpdateDeliveryEstimate",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("order.OrderService", "UpdateDeliveryEstimate"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Moves the orders a guest placed with an email address to the calling
+        /// customer's account, registered with that address and verified (see
+        /// user.UserService.ConfirmEmail)
+        pub async fn claim_guest_orders(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ClaimGuestOrdersRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ClaimGuestOrdersResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/order.OrderService/ClaimGuestOrders",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("order.OrderService", "ClaimGuestOrders"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Releases an order held by fraud screening; it's then PENDING payment
+        pub async fn approve_order(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ApproveOrderRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ApproveOrderResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/order.OrderService/ApproveOrder",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("order.OrderService", "ApproveOrder"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Cancels an order held by fraud screening, restocking its items
+        pub async fn reject_order(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RejectOrderRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RejectOrderResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/order.OrderService/RejectOrder",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("order.OrderService", "RejectOrder"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Lists every change made to an order, oldest first, with who made it
+        pub async fn get_order_history(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetOrderHistoryRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetOrderHistoryResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/order.OrderService/GetOrderHistory",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("order.OrderService", "GetOrderHistory"));
+            self.inner.unary(req, path, codec).await
+        }
+    }
+}
+/// Generated server implementations.
+pub mod order_service_server {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value,
+    )]
+    use tonic::codegen::*;
+    /// Generated trait containing gRPC methods that should be implemented for use with OrderServiceServer.
+    #[async_trait]
+    pub trait OrderService: std::marker::Send + std::marker::Sync + 'static {
+        /// Creates a new order
+        async fn create_order(
+            &self,
+            request: tonic::Request<super::CreateOrderRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CreateOrderResponse>,
+            tonic::Status,
+        >;
+        /// Runs CreateOrder's validation and pricing without placing the order
+        async fn quote_order(
+            &self,
+            request: tonic::Request<super::QuoteOrderRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::QuoteOrderResponse>,
+            tonic::Status,
+        >;
+        /// Saves a priced quote, e.g. for a B2B buyer to get approved, without
+        /// reserving stock; its prices hold until it expires
+        async fn create_draft_order(
+            &self,
+            request: tonic::Request<super::CreateDraftOrderRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CreateDraftOrderResponse>,
+            tonic::Status,
+        >;
+        /// Places an open draft as an order at the draft's prices, reserving the
+        /// stock only now
+        async fn finalize_draft_order(
+            &self,
+            request: tonic::Request<super::FinalizeDraftOrderRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::FinalizeDraftOrderResponse>,
+            tonic::Status,
+        >;
+        async fn update_order(
+            &self,
+            request: tonic::Request<super::UpdateOrderRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UpdateOrderResponse>,
+            tonic::Status,
+        >;
+        async fn cancel_order(
+            &self,
+            request: tonic::Request<super::CancelOrderRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CancelOrderResponse>,
+            tonic::Status,
+        >;
+        /// Cancels some units of an order's items before it ships, restocking them
+        /// and recomputing the order's totals
+        async fn cancel_order_items(
+            &self,
+            request: tonic::Request<super::CancelOrderItemsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CancelOrderItemsResponse>,
+            tonic::Status,
+        >;
+        async fn get_order(
+            &self,
+            request: tonic::Request<super::GetOrderRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetOrderResponse>,
+            tonic::Status,
+        >;
+        /// Fetches many orders at once, e.g. for analytics and admin tools
+        async fn get_orders_by_ids(
+            &self,
+            request: tonic::Request<super::GetOrdersByIDsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetOrdersByIDsResponse>,
+            tonic::Status,
+        >;
+        async fn list_orders(
+            &self,
+            request: tonic::Request<super::ListOrdersRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListOrdersResponse>,
+            tonic::Status,
+        >;
+        async fn get_orders_by_user(
+            &self,
+            request: tonic::Request<super::GetOrdersByUserRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetOrdersByUserResponse>,
+            tonic::Status,
+        >;
+        /// Server streaming response type for the ExportOrders method.
+        type ExportOrdersStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<super::ExportOrdersResponse, tonic::Status>,
+            >
+            + std::marker::Send
+            + 'static;
+        /// Streams orders oldest first as flat rows, one per item, for finance and
+        /// reporting ingestion; an interrupted export resumes from its last cursor
+        async fn export_orders(
+            &self,
+            request: tonic::Request<super::ExportOrdersRequest>,
+        ) -> std::result::Result<
+            tonic::Response<Self::ExportOrdersStream>,
+            tonic::Status,
+        >;
+        /// Records serial/lot numbers of the units shipped for an order item
+        async fn record_item_tracking(
+            &self,
+            request: tonic::Request<super::RecordItemTrackingRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RecordItemTrackingResponse>,
+            tonic::Status,
+        >;
+        /// Finds the order items that received units from a lot, e.g. for recalls
+        async fn find_orders_by_lot(
+            &self,
+            request: tonic::Request<super::FindOrdersByLotRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::FindOrdersByLotResponse>,
+            tonic::Status,
+        >;
+        /// Checks whether a user has received a product, e.g. for verified reviews;
+        /// the user's own or the platform's
+        async fn verify_purchase(
+            &self,
+            request: tonic::Request<super::VerifyPurchaseRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::VerifyPurchaseResponse>,
+            tonic::Status,
+        >;
+        /// Refunds a shipped or delivered order in full or in part through the
+        /// payment service, restocking the returned items
+        async fn refund_order(
+            &self,
+            request: tonic::Request<super::RefundOrderRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RefundOrderResponse>,
+            tonic::Status,
+        >;
+        async fn list_refunds(
+            &self,
+            request: tonic::Request<super::ListRefundsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListRefundsResponse>,
+            tonic::Status,
+        >;
+        /// Moves an order along as its shipments progress; called by the shipping service
+        async fn record_shipment_event(
+            &self,
+            request: tonic::Request<super::RecordShipmentEventRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RecordShipmentEventResponse>,
+            tonic::Status,
+        >;
+        /// Marks some of an order's items shipped; the order is PARTIALLY_SHIPPED
+        /// until every item that ships has shipped
+        async fn mark_items_shipped(
+            &self,
+            request: tonic::Request<super::MarkItemsShippedRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::MarkItemsShippedResponse>,
+            tonic::Status,
+        >;
+        /// Replaces an order's estimated delivery window, e.g. with the date a
+        /// carrier expects to deliver; called by the shipping service
+        async fn update_delivery_estimate(
+            &self,
+            request: tonic::Request<super::UpdateDeliveryEstimateRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UpdateDeliveryEstimateResponse>,
+            tonic::Status,
+        >;
+        /// Moves the orders a guest placed with an email address to the calling
+        /// customer's account, registered with that address and verified (see
+        /// user.UserService.ConfirmEmail)
+        async fn claim_guest_orders(
+            &self,
+            request: tonic::Request<super::ClaimGuestOrdersRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ClaimGuestOrdersResponse>,
+            tonic::Status,
+        >;
+        /// Releases an order held by fraud screening; it's then PENDING payment
+        async fn approve_order(
+            &self,
+            request: tonic::Request<super::ApproveOrderRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ApproveOrderResponse>,
+            tonic::Status,
+        >;
+        /// Cancels an order held by fraud screening, restocking its items
+        async fn reject_order(
+            &self,
+            request: tonic::Request<super::RejectOrderRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RejectOrderResponse>,
+            tonic::Status,
+        >;
+        /// Lists every change made to an order, oldest first, with who made it
+        async fn get_order_history(
+            &self,
+            request: tonic::Request<super::GetOrderHistoryRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetOrderHistoryResponse>,
+            tonic::Status,
+        >;
+    }
+    /// OrderService manages customer orders and related operations. Refused
+    /// requests fail with a gRPC status and a google.rpc.ErrorInfo reason, e.g.
+    /// FAILED_PRECONDITION / ORDER_CANCELLED, rather than success = false.
+    #[derive(Debug)]
+    pub struct OrderServiceServer<T> {
+        inner: Arc<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+        max_decoding_message_size: Option<usize>,
+        max_encoding_message_size: Option<usize>,
+    }
+    impl<T> OrderServiceServer<T> {
+        pub fn new(inner: T) -> Self {
+            Self::from_arc(Arc::new(inner))
+        }
+        pub fn from_arc(inner: Arc<T>) -> Self {
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+                max_decoding_message_size: None,
+                max_encoding_message_size: None,
+            }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        /// Enable decompressing requests with the given encoding.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.accept_compression_encodings.enable(encoding);
+            self
+        }
+        /// Compress responses with the given encoding, if the client supports it.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.send_compression_encodings.enable(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.max_decoding_message_size = Some(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.max_encoding_message_size = Some(limit);
+            self
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for OrderServiceServer<T>
+    where
+        T: OrderService,
+        B: Body + std::marker::Send + 'static,
+        B::Error: Into<StdError> + std::marker::Send + 'static,
+    {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = std::convert::Infallible;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(
+            &mut self,
+            _cx: &mut Context<'_>,
+        ) -> Poll<std::result::Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            match req.uri().path() {
+                "/order.OrderService/CreateOrder" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateOrderSvc<T: OrderService>(pub Arc<T>);
+                    impl<
+                        T: OrderService,
+                    > tonic::server::UnaryService<super::CreateOrderRequest>
+                    for CreateOrderSvc<T> {
+                        type Response = super::CreateOrderResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateOrderRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as OrderService>::create_order(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = CreateOrderSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/order.OrderService/QuoteOrder" => {
+                    #[allow(non_camel_case_types)]
+                    struct QuoteOrderSvc<T: OrderService>(pub Arc<T>);
+                    impl<
+                        T: OrderService,
+                    > tonic::server::UnaryService<super::QuoteOrderRequest>
+                    for QuoteOrderSvc<T> {
+                        type Response = super::QuoteOrderResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::QuoteOrderRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as OrderService>::quote_order(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = QuoteOrderSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/order.OrderService/CreateDraftOrder" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateDraftOrderSvc<T: OrderService>(pub Arc<T>);
+                    impl<
+                        T: OrderService,
+                    > tonic::server::UnaryService<super::CreateDraftOrderRequest>
+                    for CreateDraftOrderSvc<T> {
+                        type Response = super::CreateDraftOrderResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateDraftOrderRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as OrderService>::create_draft_order(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = CreateDraftOrderSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/order.OrderService/FinalizeDraftOrder" => {
+                    #[allow(non_camel_case_types)]
+                    struct FinalizeDraftOrderSvc<T: OrderService>(pub Arc<T>);
+                    impl<
+                        T: OrderService,
+                    > tonic::server::UnaryService<super::FinalizeDraftOrderRequest>
+                    for FinalizeDraftOrderSvc<T> {
+                        type Response = super::FinalizeDraftOrderResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::FinalizeDraftOrderRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as OrderService>::finalize_draft_order(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = FinalizeDraftOrderSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/order.OrderService/UpdateOrder" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateOrderSvc<T: OrderService>(pub Arc<T>);
+                    impl<
+                        T: OrderService,
+                    > tonic::server::UnaryService<super::UpdateOrderRequest>
+                    for UpdateOrderSvc<T> {
+                        type Response = super::UpdateOrderResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateOrderRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as OrderService>::update_order(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = UpdateOrderSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/order.OrderService/CancelOrder" => {
+                    #[allow(non_camel_case_types)]
+                    struct CancelOrderSvc<T: OrderService>(pub Arc<T>);
+                    impl<
+                        T: OrderService,
+                    > tonic::server::UnaryService<super::CancelOrderRequest>
+                    for CancelOrderSvc<T> {
+                        type Response = super::CancelOrderResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CancelOrderRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as OrderService>::cancel_order(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = CancelOrderSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/order.OrderService/CancelOrderItems" => {
+                    #[allow(non_camel_case_types)]
+                    struct CancelOrderItemsSvc<T: OrderService>(pub Arc<T>);
+                    impl<
+                        T: OrderService,
+                    > tonic::server::UnaryService<super::CancelOrderItemsRequest>
+                    for CancelOrderItemsSvc<T> {
+                        type Response = super::CancelOrderItemsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CancelOrderItemsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as OrderService>::cancel_order_items(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = CancelOrderItemsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/order.OrderService/GetOrder" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetOrderSvc<T: OrderService>(pub Arc<T>);
+                    impl<
+                        T: OrderService,
+                    > tonic::server::UnaryService<super::GetOrderRequest>
+                    for GetOrderSvc<T> {
+                        type Response = super::GetOrderResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetOrderRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as OrderService>::get_order(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetOrderSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/order.OrderService/GetOrdersByIds" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetOrdersByIdsSvc<T: OrderService>(pub Arc<T>);
+                    impl<
+                        T: OrderService,
+                    > tonic::server::UnaryService<super::GetOrdersByIDsRequest>
+                    for GetOrdersByIdsSvc<T> {
+                        type Response = super::GetOrdersByIDsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetOrdersByIDsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as OrderService>::get_orders_by_ids(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetOrdersByIdsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/order.OrderService/ListOrders" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListOrdersSvc<T: OrderService>(pub Arc<T>);
+                    impl<
+                        T: OrderService,
+                    > tonic::server::UnaryService<super::ListOrdersRequest>
+                    for ListOrdersSvc<T> {
+                        type Response = super::ListOrdersResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListOrdersRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as OrderService>::list_orders(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ListOrdersSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/order.OrderService/GetOrdersByUser" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetOrdersByUserSvc<T: OrderService>(pub Arc<T>);
+                    impl<
+                        T: OrderService,
+                    > tonic::server::UnaryService<super::GetOrdersByUserRequest>
+                    for GetOrdersByUserSvc<T> {
+                        type Response = super::GetOrdersByUserResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetOrdersByUserRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as OrderService>::get_orders_by_user(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetOrdersByUserSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/order.OrderService/ExportOrders" => {
+                    #[allow(non_camel_case_types)]
+                    struct ExportOrdersSvc<T: OrderService>(pub Arc<T>);
+                    impl<
+                        T: OrderService,
+                    > tonic::server::ServerStreamingService<super::ExportOrdersRequest>
+                    for ExportOrdersSvc<T> {
+                        type Response = super::ExportOrdersResponse;
+                        type ResponseStream = T::ExportOrdersStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ExportOrdersRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as OrderService>::export_orders(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ExportOrdersSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/order.OrderService/RecordItemTracking" => {
+                    #[allow(non_camel_case_types)]
+                    struct RecordItemTrackingSvc<T: OrderService>(pub Arc<T>);
+                    impl<
+                        T: OrderService,
+                    > tonic::server::UnaryService<super::RecordItemTrackingRequest>
+                    for RecordItemTrackingSvc<T> {
+                        type Response = super::RecordItemTrackingResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::RecordItemTrackingRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as OrderService>::record_item_tracking(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = RecordItemTrackingSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/order.OrderService/FindOrdersByLot" => {
                     #[allow(non_camel_case_types)]
-                    struct CreateOrderSvc<T: OrderService>(pub Arc<T>);
+                    struct FindOrdersByLotSvc<T: OrderService>(pub Arc<T>);
                     impl<
                         T: OrderService,
-                    > tonic::server::UnaryService<super::CreateOrderRequest>
-                    for CreateOrderSvc<T> {
-                        type Response = super::CreateOrderResponse;
+                    > tonic::server::UnaryService<super::FindOrdersByLotRequest>
+                    for FindOrdersByLotSvc<T> {
+                        type Response = super::FindOrdersByLotResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::CreateOrderRequest>,
+                            request: tonic::Request<super::FindOrdersByLotRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as OrderService>::create_order(&inner, request).await
+                                <T as OrderService>::find_orders_by_lot(&inner, request)
+                                    .await
                             };
                             Box::pin(fut)
                         }
@@ -577,7 +2662,7 @@ pub mod order_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = CreateOrderSvc(inner);
+                        let method = FindOrdersByLotSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -593,25 +2678,25 @@ pub mod order_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/order.OrderService/UpdateOrder" => {
+                "/order.OrderService/VerifyPurchase" => {
                     #[allow(non_camel_case_types)]
-                    struct UpdateOrderSvc<T: OrderService>(pub Arc<T>);
+                    struct VerifyPurchaseSvc<T: OrderService>(pub Arc<T>);
                     impl<
                         T: OrderService,
-                    > tonic::server::UnaryService<super::UpdateOrderRequest>
-                    for UpdateOrderSvc<T> {
-                        type Response = super::UpdateOrderResponse;
+                    > tonic::server::UnaryService<super::VerifyPurchaseRequest>
+                    for VerifyPurchaseSvc<T> {
+                        type Response = super::VerifyPurchaseResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::UpdateOrderRequest>,
+                            request: tonic::Request<super::VerifyPurchaseRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as OrderService>::update_order(&inner, request).await
+                                <T as OrderService>::verify_purchase(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -622,7 +2707,7 @@ pub mod order_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = UpdateOrderSvc(inner);
+                        let method = VerifyPurchaseSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -638,25 +2723,25 @@ pub mod order_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/order.OrderService/CancelOrder" => {
+                "/order.OrderService/RefundOrder" => {
                     #[allow(non_camel_case_types)]
-                    struct CancelOrderSvc<T: OrderService>(pub Arc<T>);
+                    struct RefundOrderSvc<T: OrderService>(pub Arc<T>);
                     impl<
                         T: OrderService,
-                    > tonic::server::UnaryService<super::CancelOrderRequest>
-                    for CancelOrderSvc<T> {
-                        type Response = super::CancelOrderResponse;
+                    > tonic::server::UnaryService<super::RefundOrderRequest>
+                    for RefundOrderSvc<T> {
+                        type Response = super::RefundOrderResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::CancelOrderRequest>,
+                            request: tonic::Request<super::RefundOrderRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as OrderService>::cancel_order(&inner, request).await
+                                <T as OrderService>::refund_order(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -667,7 +2752,7 @@ pub mod order_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = CancelOrderSvc(inner);
+                        let method = RefundOrderSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -683,25 +2768,25 @@ pub mod order_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/order.OrderService/GetOrder" => {
+                "/order.OrderService/ListRefunds" => {
                     #[allow(non_camel_case_types)]
-                    struct GetOrderSvc<T: OrderService>(pub Arc<T>);
+                    struct ListRefundsSvc<T: OrderService>(pub Arc<T>);
                     impl<
                         T: OrderService,
-                    > tonic::server::UnaryService<super::GetOrderRequest>
-                    for GetOrderSvc<T> {
-                        type Response = super::GetOrderResponse;
+                    > tonic::server::UnaryService<super::ListRefundsRequest>
+                    for ListRefundsSvc<T> {
+                        type Response = super::ListRefundsResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetOrderRequest>,
+                            request: tonic::Request<super::ListRefundsRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as OrderService>::get_order(&inner, request).await
+                                <T as OrderService>::list_refunds(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -712,7 +2797,7 @@ pub mod order_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetOrderSvc(inner);
+                        let method = ListRefundsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -728,25 +2813,26 @@ pub mod order_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/order.OrderService/ListOrders" => {
+                "/order.OrderService/RecordShipmentEvent" => {
                     #[allow(non_camel_case_types)]
-                    struct ListOrdersSvc<T: OrderService>(pub Arc<T>);
+                    struct RecordShipmentEventSvc<T: OrderService>(pub Arc<T>);
                     impl<
                         T: OrderService,
-                    > tonic::server::UnaryService<super::ListOrdersRequest>
-                    for ListOrdersSvc<T> {
-                        type Response = super::ListOrdersResponse;
+                    > tonic::server::UnaryService<super::RecordShipmentEventRequest>
+                    for RecordShipmentEventSvc<T> {
+                        type Response = super::RecordShipmentEventResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ListOrdersRequest>,
+                            request: tonic::Request<super::RecordShipmentEventRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as OrderService>::list_orders(&inner, request).await
+                                <T as OrderService>::record_shipment_event(&inner, request)
+                                    .await
                             };
                             Box::pin(fut)
                         }
@@ -757,7 +2843,7 @@ pub mod order_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = ListOrdersSvc(inner);
+                        let method = RecordShipmentEventSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -773,25 +2859,25 @@ pub mod order_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/order.OrderService/GetOrdersByUser" => {
+                "/order.OrderService/MarkItemsShipped" => {
                     #[allow(non_camel_case_types)]
-                    struct GetOrdersByUserSvc<T: OrderService>(pub Arc<T>);
+                    struct MarkItemsShippedSvc<T: OrderService>(pub Arc<T>);
                     impl<
                         T: OrderService,
-                    > tonic::server::UnaryService<super::GetOrdersByUserRequest>
-                    for GetOrdersByUserSvc<T> {
-                        type Response = super::GetOrdersByUserResponse;
+                    > tonic::server::UnaryService<super::MarkItemsShippedRequest>
+                    for MarkItemsShippedSvc<T> {
+                        type Response = super::MarkItemsShippedResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetOrdersByUserRequest>,
+                            request: tonic::Request<super::MarkItemsShippedRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as OrderService>::get_orders_by_user(&inner, request)
+                                <T as OrderService>::mark_items_shipped(&inner, request)
                                     .await
                             };
                             Box::pin(fut)
@@ -803,7 +2889,238 @@ pub mod order_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetOrdersByUserSvc(inner);
+                        let method = MarkItemsShippedSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/order.OrderService/UpdateDeliveryEstimate" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateDeliveryEstimateSvc<T: OrderService>(pub Arc<T>);
+                    impl<
+                        T: OrderService,
+                    > tonic::server::UnaryService<super::UpdateDeliveryEstimateRequest>
+                    for UpdateDeliveryEstimateSvc<T> {
+                        type Response = super::UpdateDeliveryEstimateResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateDeliveryEstimateRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as OrderService>::update_delivery_estimate(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = UpdateDeliveryEstimateSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/order.OrderService/ClaimGuestOrders" => {
+                    #[allow(non_camel_case_types)]
+                    struct ClaimGuestOrdersSvc<T: OrderService>(pub Arc<T>);
+                    impl<
+                        T: OrderService,
+                    > tonic::server::UnaryService<super::ClaimGuestOrdersRequest>
+                    for ClaimGuestOrdersSvc<T> {
+                        type Response = super::ClaimGuestOrdersResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ClaimGuestOrdersRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as OrderService>::claim_guest_orders(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ClaimGuestOrdersSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/order.OrderService/ApproveOrder" => {
+                    #[allow(non_camel_case_types)]
+                    struct ApproveOrderSvc<T: OrderService>(pub Arc<T>);
+                    impl<
+                        T: OrderService,
+                    > tonic::server::UnaryService<super::ApproveOrderRequest>
+                    for ApproveOrderSvc<T> {
+                        type Response = super::ApproveOrderResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ApproveOrderRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as OrderService>::approve_order(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ApproveOrderSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/order.OrderService/RejectOrder" => {
+                    #[allow(non_camel_case_types)]
+                    struct RejectOrderSvc<T: OrderService>(pub Arc<T>);
+                    impl<
+                        T: OrderService,
+                    > tonic::server::UnaryService<super::RejectOrderRequest>
+                    for RejectOrderSvc<T> {
+                        type Response = super::RejectOrderResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::RejectOrderRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as OrderService>::reject_order(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = RejectOrderSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/order.OrderService/GetOrderHistory" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetOrderHistorySvc<T: OrderService>(pub Arc<T>);
+                    impl<
+                        T: OrderService,
+                    > tonic::server::UnaryService<super::GetOrderHistoryRequest>
+                    for GetOrderHistorySvc<T> {
+                        type Response = super::GetOrderHistoryResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetOrderHistoryRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as OrderService>::get_order_history(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetOrderHistorySvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1,4 +1,12 @@
 // This file is @generated by prost-build.
+/// ProductAttribute is a specification entry such as "RAM: 16GB"
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ProductAttribute {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub value: ::prost::alloc::string::String,
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Product {
     #[prost(string, tag = "1")]
@@ -7,8 +15,9 @@ pub struct Product {
     pub name: ::prost::alloc::string::String,
     #[prost(string, tag = "3")]
     pub description: ::prost::alloc::string::String,
-    #[prost(double, tag = "4")]
-    pub price: f64,
+    /// decimal string, e.g. "19.99"
+    #[prost(string, tag = "4")]
+    pub price: ::prost::alloc::string::String,
     #[prost(int32, tag = "5")]
     pub stock_quantity: i32,
     #[prost(string, tag = "6")]
@@ -17,6 +26,55 @@ pub struct Product {
     pub created_at: i64,
     #[prost(int64, tag = "8")]
     pub updated_at: i64,
+    #[prost(message, repeated, tag = "9")]
+    pub attributes: ::prost::alloc::vec::Vec<ProductAttribute>,
+    /// 0 when the product carries no warranty
+    #[prost(int32, tag = "10")]
+    pub warranty_months: i32,
+    /// only returned by GetProductsByIds, for historical orders
+    #[prost(bool, tag = "11")]
+    pub deleted: bool,
+    /// 0 until the product has reviews
+    #[prost(double, tag = "12")]
+    pub rating_average: f64,
+    #[prost(int32, tag = "13")]
+    pub rating_count: i32,
+    #[prost(string, repeated, tag = "14")]
+    pub tags: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// out of stock: offer a notify-me button instead of add to cart
+    #[prost(bool, tag = "15")]
+    pub notify_me: bool,
+    /// price above is what the product sells for right now; these describe the sale behind it
+    #[prost(string, tag = "16")]
+    pub regular_price: ::prost::alloc::string::String,
+    /// empty when no sale is scheduled
+    #[prost(string, tag = "17")]
+    pub sale_price: ::prost::alloc::string::String,
+    /// 0 when the sale has no start
+    #[prost(int64, tag = "18")]
+    pub sale_starts_at: i64,
+    /// 0 when the sale has no end
+    #[prost(int64, tag = "19")]
+    pub sale_ends_at: i64,
+    #[prost(enumeration = "ProductStatus", tag = "20")]
+    pub status: i32,
+    #[prost(enumeration = "ProductType", tag = "21")]
+    pub product_type: i32,
+    /// orders beyond the stock are taken and filled on restock
+    #[prost(bool, tag = "22")]
+    pub allow_backorder: bool,
+    /// release time; orders before it are preorders. 0 when released
+    #[prost(int64, tag = "23")]
+    pub available_from: i64,
+    /// empty for the platform's own products
+    #[prost(string, tag = "24")]
+    pub seller_id: ::prost::alloc::string::String,
+    /// selects the tax rate charged on the product
+    #[prost(string, tag = "25")]
+    pub tax_class: ::prost::alloc::string::String,
+    /// shipping weight of one unit
+    #[prost(int32, tag = "26")]
+    pub weight_grams: i32,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct AddProductRequest {
@@ -24,12 +82,28 @@ pub struct AddProductRequest {
     pub name: ::prost::alloc::string::String,
     #[prost(string, tag = "2")]
     pub description: ::prost::alloc::string::String,
-    #[prost(double, tag = "3")]
-    pub price: f64,
+    #[prost(string, tag = "3")]
+    pub price: ::prost::alloc::string::String,
     #[prost(int32, tag = "4")]
     pub stock_quantity: i32,
     #[prost(string, tag = "5")]
     pub category: ::prost::alloc::string::String,
+    #[prost(int32, tag = "6")]
+    pub warranty_months: i32,
+    #[prost(enumeration = "ProductType", tag = "7")]
+    pub product_type: i32,
+    #[prost(bool, tag = "8")]
+    pub allow_backorder: bool,
+    #[prost(int64, tag = "9")]
+    pub available_from: i64,
+    /// set from the token for sellers; platform calls may name any seller
+    #[prost(string, tag = "10")]
+    pub seller_id: ::prost::alloc::string::String,
+    /// empty means standard
+    #[prost(string, tag = "11")]
+    pub tax_class: ::prost::alloc::string::String,
+    #[prost(int32, tag = "12")]
+    pub weight_grams: i32,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct AddProductResponse {
@@ -48,12 +122,30 @@ pub struct UpdateProductRequest {
     pub name: ::prost::alloc::string::String,
     #[prost(string, tag = "3")]
     pub description: ::prost::alloc::string::String,
-    #[prost(double, tag = "4")]
-    pub price: f64,
+    #[prost(string, tag = "4")]
+    pub price: ::prost::alloc::string::String,
     #[prost(int32, tag = "5")]
     pub stock_quantity: i32,
     #[prost(string, tag = "6")]
     pub category: ::prost::alloc::string::String,
+    #[prost(int32, tag = "7")]
+    pub warranty_months: i32,
+    /// Fields to change: name, description, price, stock_quantity, category,
+    /// warranty_months, allow_backorder, available_from, tax_class,
+    /// weight_grams. Empty changes the fields set to a non-default value; "*"
+    /// replaces every field.
+    #[prost(message, optional, tag = "8")]
+    pub update_mask: ::core::option::Option<::prost_types::FieldMask>,
+    #[prost(bool, tag = "9")]
+    pub allow_backorder: bool,
+    /// 0 clears the release time
+    #[prost(int64, tag = "10")]
+    pub available_from: i64,
+    /// empty resets to standard
+    #[prost(string, tag = "11")]
+    pub tax_class: ::prost::alloc::string::String,
+    #[prost(int32, tag = "12")]
+    pub weight_grams: i32,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct UpdateProductResponse {
@@ -77,6 +169,20 @@ pub struct DeleteProductResponse {
     pub message: ::prost::alloc::string::String,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RestoreProductRequest {
+    #[prost(string, tag = "1")]
+    pub product_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RestoreProductResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "3")]
+    pub product: ::core::option::Option<Product>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetProductRequest {
     #[prost(string, tag = "1")]
     pub product_id: ::prost::alloc::string::String,
@@ -95,10 +201,16 @@ pub struct GetProductsByIDsRequest {
     #[prost(string, repeated, tag = "1")]
     pub product_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
+/// Products come back in the order of the request, once per id; ids with no
+/// product (soft-deleted products still resolve) are listed in missing_ids.
+/// Their price is the one charged right now, sales included; the order
+/// service prices orders from it.
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetProductsByIDsResponse {
     #[prost(message, repeated, tag = "3")]
     pub products: ::prost::alloc::vec::Vec<Product>,
+    #[prost(string, repeated, tag = "4")]
+    pub missing_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ListProductsRequest {
@@ -108,6 +220,24 @@ pub struct ListProductsRequest {
     pub page_size: i32,
     #[prost(string, tag = "3")]
     pub category: ::prost::alloc::string::String,
+    /// only products having every one of these name/value pairs are returned
+    #[prost(message, repeated, tag = "4")]
+    pub attribute_filters: ::prost::alloc::vec::Vec<ProductAttribute>,
+    #[prost(enumeration = "ProductSort", tag = "5")]
+    pub sort: i32,
+    /// only products carrying every one of these tags are returned
+    #[prost(string, repeated, tag = "6")]
+    pub tags: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// next_page_token of the previous page; when set, page is ignored
+    #[prost(string, tag = "7")]
+    pub page_token: ::prost::alloc::string::String,
+    /// inclusive bounds on the price products sell for right now; empty for none
+    #[prost(string, tag = "8")]
+    pub min_price: ::prost::alloc::string::String,
+    #[prost(string, tag = "9")]
+    pub max_price: ::prost::alloc::string::String,
+    #[prost(bool, tag = "10")]
+    pub in_stock_only: bool,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ListProductsResponse {
@@ -119,6 +249,9 @@ pub struct ListProductsResponse {
     pub products: ::prost::alloc::vec::Vec<Product>,
     #[prost(int32, tag = "4")]
     pub total_count: i32,
+    /// empty on the last page
+    #[prost(string, tag = "5")]
+    pub next_page_token: ::prost::alloc::string::String,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CheckAvailabilityRequest {
@@ -135,6 +268,38 @@ pub struct CheckAvailabilityResponse {
     pub message: ::prost::alloc::string::String,
     #[prost(int32, tag = "3")]
     pub current_stock: i32,
+    #[prost(enumeration = "AvailabilityState", tag = "4")]
+    pub state: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CheckAvailabilityBatchRequest {
+    #[prost(string, repeated, tag = "1")]
+    pub product_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+/// Only a preview: ReserveStock remains the authoritative check
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CheckAvailabilityBatchResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    /// unknown ids are left out
+    #[prost(message, repeated, tag = "3")]
+    pub products: ::prost::alloc::vec::Vec<ProductAvailability>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ProductAvailability {
+    #[prost(string, tag = "1")]
+    pub product_id: ::prost::alloc::string::String,
+    /// 0 when not on sale, int32 max for digital products
+    #[prost(int32, tag = "2")]
+    pub sellable_stock: i32,
+    /// not released yet: every unit is ordered ahead
+    #[prost(bool, tag = "3")]
+    pub preorder: bool,
+    /// units past the stock are owed
+    #[prost(bool, tag = "4")]
+    pub allow_backorder: bool,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct UpdateInventoryRequest {
@@ -153,456 +318,3004 @@ pub struct UpdateInventoryResponse {
     #[prost(int32, tag = "3")]
     pub new_stock_quantity: i32,
 }
-/// Generated client implementations.
-pub mod product_service_client {
-    #![allow(
-        unused_variables,
-        dead_code,
-        missing_docs,
-        clippy::wildcard_imports,
-        clippy::let_unit_value,
-    )]
-    use tonic::codegen::*;
-    use tonic::codegen::http::Uri;
-    #[derive(Debug, Clone)]
-    pub struct ProductServiceClient<T> {
-        inner: tonic::client::Grpc<T>,
-    }
-    impl ProductServiceClient<tonic::transport::Channel> {
-        /// Attempt to create a new client by connecting to a given endpoint.
-        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
-        where
-            D: TryInto<tonic::transport::Endpoint>,
-            D::Error: Into<StdError>,
-        {
-            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
-            Ok(Self::new(conn))
-        }
-    }
-    impl<T> ProductServiceClient<T>
-    where
-        T: tonic::client::GrpcService<tonic::body::BoxBody>,
-        T::Error: Into<StdError>,
-        T::ResponseBody: Body<Data = Bytes> + std::marker::Send + 'static,
-        <T::ResponseBody as Body>::Error: Into<StdError> + std::marker::Send,
-    {
-        pub fn new(inner: T) -> Self {
-            let inner = tonic::client::Grpc::new(inner);
-            Self { inner }
-        }
-        pub fn with_origin(inner: T, origin: Uri) -> Self {
-            let inner = tonic::client::Grpc::with_origin(inner, origin);
-            Self { inner }
-        }
-        pub fn with_interceptor<F>(
-            inner: T,
-            interceptor: F,
-        ) -> ProductServiceClient<InterceptedService<T, F>>
-        where
-            F: tonic::service::Interceptor,
-            T::ResponseBody: Default,
-            T: tonic::codegen::Service<
-                http::Request<tonic::body::BoxBody>,
-                Response = http::Response<
-                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
-                >,
-            >,
-            <T as tonic::codegen::Service<
-                http::Request<tonic::body::BoxBody>,
-            >>::Error: Into<StdError> + std::marker::Send + std::marker::Sync,
-        {
-            ProductServiceClient::new(InterceptedService::new(inner, interceptor))
-        }
-        /// Compress requests with the given encoding.
-        ///
-        /// This requires the server to support it otherwise it might respond with an
-        /// error.
-        #[must_use]
-        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
-            self.inner = self.inner.send_compressed(encoding);
-            self
-        }
-        /// Enable decompressing responses.
-        #[must_use]
-        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
-            self.inner = self.inner.accept_compressed(encoding);
-            self
-        }
-        /// Limits the maximum size of a decoded message.
-        ///
-        /// Default: `4MB`
-        #[must_use]
-        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
-            self.inner = self.inner.max_decoding_message_size(limit);
-            self
-        }
-        /// Limits the maximum size of an encoded message.
-        ///
-        /// Default: `usize::MAX`
-        #[must_use]
-        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
-            self.inner = self.inner.max_encoding_message_size(limit);
-            self
-        }
-        pub async fn add_product(
-            &mut self,
-            request: impl tonic::IntoRequest<super::AddProductRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::AddProductResponse>,
-            tonic::Status,
-        > {
-            self.inner
-                .ready()
-                .await
-                .map_err(|e| {
-                    tonic::Status::unknown(
-                        format!("Service was not ready: {}", e.into()),
-                    )
-                })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static(
-                "/product.ProductService/AddProduct",
-            );
-            let mut req = request.into_request();
-            req.extensions_mut()
-                .insert(GrpcMethod::new("product.ProductService", "AddProduct"));
-            self.inner.unary(req, path, codec).await
-        }
-        pub async fn update_product(
-            &mut self,
-            request: impl tonic::IntoRequest<super::UpdateProductRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::UpdateProductResponse>,
-            tonic::Status,
-        > {
-            self.inner
-                .ready()
-                .await
-                .map_err(|e| {
-                    tonic::Status::unknown(
-                        format!("Service was not ready: {}", e.into()),
-                    )
-                })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static(
-                "/product.ProductService/UpdateProduct",
-            );
-            let mut req = request.into_request();
-            req.extensions_mut()
-                .insert(GrpcMethod::new("product.ProductService", "UpdateProduct"));
-            self.inner.unary(req, path, codec).await
-        }
-        pub async fn delete_product(
-            &mut self,
-            request: impl tonic::IntoRequest<super::DeleteProductRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::DeleteProductResponse>,
-            tonic::Status,
-        > {
-            self.inner
-                .ready()
-                .await
-                .map_err(|e| {
-                    tonic::Status::unknown(
-                        format!("Service was not ready: {}", e.into()),
-                    )
-                })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static(
-                "/product.ProductService/DeleteProduct",
-            );
-            let mut req = request.into_request();
-            req.extensions_mut()
-                .insert(GrpcMethod::new("product.ProductService", "DeleteProduct"));
-            self.inner.unary(req, path, codec).await
-        }
-        pub async fn get_product(
-            &mut self,
-            request: impl tonic::IntoRequest<super::GetProductRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::GetProductResponse>,
-            tonic::Status,
-        > {
-            self.inner
-                .ready()
-                .await
-                .map_err(|e| {
-                    tonic::Status::unknown(
-                        format!("Service was not ready: {}", e.into()),
-                    )
-                })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static(
-                "/product.ProductService/GetProduct",
-            );
-            let mut req = request.into_request();
-            req.extensions_mut()
-                .insert(GrpcMethod::new("product.ProductService", "GetProduct"));
-            self.inner.unary(req, path, codec).await
-        }
-        pub async fn get_products_by_ids(
-            &mut self,
-            request: impl tonic::IntoRequest<super::GetProductsByIDsRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::GetProductsByIDsResponse>,
-            tonic::Status,
-        > {
-            self.inner
-                .ready()
-                .await
-                .map_err(|e| {
-                    tonic::Status::unknown(
-                        format!("Service was not ready: {}", e.into()),
-                    )
-                })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static(
-                "/product.ProductService/GetProductsByIds",
-            );
-            let mut req = request.into_request();
-            req.extensions_mut()
-                .insert(GrpcMethod::new("product.ProductService", "GetProductsByIds"));
-            self.inner.unary(req, path, codec).await
-        }
-        pub async fn list_products(
-            &mut self,
-            request: impl tonic::IntoRequest<super::ListProductsRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::ListProductsResponse>,
-            tonic::Status,
-        > {
-            self.inner
-                .ready()
-                .await
-                .map_err(|e| {
-                    tonic::Status::unknown(
-                        format!("Service was not ready: {}", e.into()),
-                    )
-                })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static(
-                "/product.ProductService/ListProducts",
-            );
-            let mut req = request.into_request();
-            req.extensions_mut()
-                .insert(GrpcMethod::new("product.ProductService", "ListProducts"));
-            self.inner.unary(req, path, codec).await
-        }
-        pub async fn check_availability(
-            &mut self,
-            request: impl tonic::IntoRequest<super::CheckAvailabilityRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::CheckAvailabilityResponse>,
-            tonic::Status,
-        > {
-            self.inner
-                .ready()
-                .await
-                .map_err(|e| {
-                    tonic::Status::unknown(
-                        format!("Service was not ready: {}", e.into()),
-                    )
-                })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static(
-                "/product.ProductService/CheckAvailability",
-            );
-            let mut req = request.into_request();
-            req.extensions_mut()
-                .insert(GrpcMethod::new("product.ProductService", "CheckAvailability"));
-            self.inner.unary(req, path, codec).await
-        }
-        pub async fn update_inventory(
-            &mut self,
-            request: impl tonic::IntoRequest<super::UpdateInventoryRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::UpdateInventoryResponse>,
-            tonic::Status,
-        > {
-            self.inner
-                .ready()
-                .await
-                .map_err(|e| {
-                    tonic::Status::unknown(
-                        format!("Service was not ready: {}", e.into()),
-                    )
-                })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static(
-                "/product.ProductService/UpdateInventory",
-            );
-            let mut req = request.into_request();
-            req.extensions_mut()
-                .insert(GrpcMethod::new("product.ProductService", "UpdateInventory"));
-            self.inner.unary(req, path, codec).await
-        }
-    }
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetProductAttributesRequest {
+    #[prost(string, tag = "1")]
+    pub product_id: ::prost::alloc::string::String,
+    /// upserted by name
+    #[prost(message, repeated, tag = "2")]
+    pub attributes: ::prost::alloc::vec::Vec<ProductAttribute>,
+    /// attributes to delete
+    #[prost(string, repeated, tag = "3")]
+    pub remove_names: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
-/// Generated server implementations.
-pub mod product_service_server {
-    #![allow(
-        unused_variables,
-        dead_code,
-        missing_docs,
-        clippy::wildcard_imports,
-        clippy::let_unit_value,
-    )]
-    use tonic::codegen::*;
-    /// Generated trait containing gRPC methods that should be implemented for use with ProductServiceServer.
-    #[async_trait]
-    pub trait ProductService: std::marker::Send + std::marker::Sync + 'static {
-        async fn add_product(
-            &self,
-            request: tonic::Request<super::AddProductRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::AddProductResponse>,
-            tonic::Status,
-        >;
-        async fn update_product(
-            &self,
-            request: tonic::Request<super::UpdateProductRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::UpdateProductResponse>,
-            tonic::Status,
-        >;
-        async fn delete_product(
-            &self,
-            request: tonic::Request<super::DeleteProductRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::DeleteProductResponse>,
-            tonic::Status,
-        >;
-        async fn get_product(
-            &self,
-            request: tonic::Request<super::GetProductRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::GetProductResponse>,
-            tonic::Status,
-        >;
-        async fn get_products_by_ids(
-            &self,
-            request: tonic::Request<super::GetProductsByIDsRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::GetProductsByIDsResponse>,
-            tonic::Status,
-        >;
-        async fn list_products(
-            &self,
-            request: tonic::Request<super::ListProductsRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::ListProductsResponse>,
-            tonic::Status,
-        >;
-        async fn check_availability(
-            &self,
-            request: tonic::Request<super::CheckAvailabilityRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::CheckAvailabilityResponse>,
-            tonic::Status,
-        >;
-        async fn update_inventory(
-            &self,
-            request: tonic::Request<super::UpdateInventoryRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::UpdateInventoryResponse>,
-            tonic::Status,
-        >;
-    }
-    #[derive(Debug)]
-    pub struct ProductServiceServer<T> {
-        inner: Arc<T>,
-        accept_compression_encodings: EnabledCompressionEncodings,
-        send_compression_encodings: EnabledCompressionEncodings,
-        max_decoding_message_size: Option<usize>,
-        max_encoding_message_size: Option<usize>,
-    }
-    impl<T> ProductServiceServer<T> {
-        pub fn new(inner: T) -> Self {
-            Self::from_arc(Arc::new(inner))
-        }
-        pub fn from_arc(inner: Arc<T>) -> Self {
-            Self {
-                inner,
-                accept_compression_encodings: Default::default(),
-                send_compression_encodings: Default::default(),
-                max_decoding_message_size: None,
-                max_encoding_message_size: None,
-            }
-        }
-        pub fn with_interceptor<F>(
-            inner: T,
-            interceptor: F,
-        ) -> InterceptedService<Self, F>
-        where
-            F: tonic::service::Interceptor,
-        {
-            InterceptedService::new(Self::new(inner), interceptor)
-        }
-        /// Enable decompressing requests with the given encoding.
-        #[must_use]
-        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
-            self.accept_compression_encodings.enable(encoding);
-            self
-        }
-        /// Compress responses with the given encoding, if the client supports it.
-        #[must_use]
-        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
-            self.send_compression_encodings.enable(encoding);
-            self
-        }
-        /// Limits the maximum size of a decoded message.
-        ///
-        /// Default: `4MB`
-        #[must_use]
-        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
-            self.max_decoding_message_size = Some(limit);
-            self
-        }
-        /// Limits the maximum size of an encoded message.
-        ///
-        /// Default: `usize::MAX`
-        #[must_use]
-        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
-            self.max_encoding_message_size = Some(limit);
-            self
-        }
-    }
-    impl<T, B> tonic::codegen::Service<http::Request<B>> for ProductServiceServer<T>
-    where
-        T: ProductService,
-        B: Body + std::marker::Send + 'static,
-        B::Error: Into<StdError> + std::marker::Send + 'static,
-    {
-        type Response = http::Response<tonic::body::BoxBody>;
-        type Error = std::convert::Infallible;
-        type Future = BoxFuture<Self::Response, Self::Error>;
-        fn poll_ready(
-            &mut self,
-            _cx: &mut Context<'_>,
-        ) -> Poll<std::result::Result<(), Self::Error>> {
-            Poll::Ready(Ok(()))
-        }
-        fn call(&mut self, req: http::Request<B>) -> Self::Future {
-            match req.uri().path() {
-                "/product.ProductService/AddProduct" => {
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetProductAttributesResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "3")]
+    pub attributes: ::prost::alloc::vec::Vec<ProductAttribute>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetProductAttributesRequest {
+    #[prost(string, tag = "1")]
+    pub product_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetProductAttributesResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "3")]
+    pub attributes: ::prost::alloc::vec::Vec<ProductAttribute>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExportProductsRequest {
+    #[prost(string, tag = "1")]
+    pub category: ::prost::alloc::string::String,
+    /// unix seconds, 0 exports everything
+    #[prost(int64, tag = "2")]
+    pub updated_since: i64,
+    /// products per streamed message, defaults to 500
+    #[prost(int32, tag = "3")]
+    pub page_size: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExportProductsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub products: ::prost::alloc::vec::Vec<Product>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ScheduleRestockRequest {
+    #[prost(string, tag = "1")]
+    pub product_id: ::prost::alloc::string::String,
+    #[prost(int32, tag = "2")]
+    pub quantity: i32,
+    /// YYYY-MM-DD
+    #[prost(string, tag = "3")]
+    pub expected_date: ::prost::alloc::string::String,
+    /// purchase order number
+    #[prost(string, tag = "4")]
+    pub reference: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ScheduleRestockResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub restock_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReceiveRestockRequest {
+    #[prost(string, tag = "1")]
+    pub restock_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReceiveRestockResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(int32, tag = "3")]
+    pub new_stock_quantity: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetAvailabilityTimelineRequest {
+    #[prost(string, tag = "1")]
+    pub product_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AvailabilityEntry {
+    /// YYYY-MM-DD
+    #[prost(string, tag = "1")]
+    pub date: ::prost::alloc::string::String,
+    /// pending restocks expected on this date
+    #[prost(int32, tag = "2")]
+    pub incoming_quantity: i32,
+    /// projected stock once they arrive
+    #[prost(int32, tag = "3")]
+    pub available_quantity: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetAvailabilityTimelineResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(int32, tag = "3")]
+    pub current_stock: i32,
+    #[prost(message, repeated, tag = "4")]
+    pub entries: ::prost::alloc::vec::Vec<AvailabilityEntry>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetStockBadgeRequest {
+    /// at most 100
+    #[prost(string, repeated, tag = "1")]
+    pub product_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StockBadge {
+    #[prost(string, tag = "1")]
+    pub product_id: ::prost::alloc::string::String,
+    #[prost(enumeration = "StockLevel", tag = "2")]
+    pub level: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetStockBadgeResponse {
+    /// unknown products are omitted
+    #[prost(message, repeated, tag = "1")]
+    pub badges: ::prost::alloc::vec::Vec<StockBadge>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetRelatedProductsRequest {
+    #[prost(string, tag = "1")]
+    pub product_id: ::prost::alloc::string::String,
+    /// defaults to 10, at most 50
+    #[prost(int32, tag = "2")]
+    pub limit: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetRelatedProductsResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    /// co-purchased products first, then same-category ones
+    #[prost(message, repeated, tag = "3")]
+    pub products: ::prost::alloc::vec::Vec<Product>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetCategorySortRequest {
+    #[prost(string, tag = "1")]
+    pub category: ::prost::alloc::string::String,
+    /// DEFAULT_SORT resets the category to newest first
+    #[prost(enumeration = "ProductSort", tag = "2")]
+    pub sort: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetCategorySortResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PinProductRequest {
+    #[prost(string, tag = "1")]
+    pub category: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub product_id: ::prost::alloc::string::String,
+    /// 1-based; re-pinning a product moves it
+    #[prost(int32, tag = "3")]
+    pub position: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PinProductResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UnpinProductRequest {
+    #[prost(string, tag = "1")]
+    pub category: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub product_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UnpinProductResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetCategoryMerchandisingRequest {
+    #[prost(string, tag = "1")]
+    pub category: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CategoryPin {
+    #[prost(string, tag = "1")]
+    pub product_id: ::prost::alloc::string::String,
+    #[prost(int32, tag = "2")]
+    pub position: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetCategoryMerchandisingResponse {
+    #[prost(enumeration = "ProductSort", tag = "1")]
+    pub default_sort: i32,
+    /// ordered by position
+    #[prost(message, repeated, tag = "2")]
+    pub pins: ::prost::alloc::vec::Vec<CategoryPin>,
+}
+/// Tags are trimmed and lowercased, so "Gift Idea" and "gift idea" are the same tag
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TagProductRequest {
+    #[prost(string, tag = "1")]
+    pub product_id: ::prost::alloc::string::String,
+    #[prost(string, repeated, tag = "2")]
+    pub tags: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TagProductResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    /// the product's tags after the change
+    #[prost(string, repeated, tag = "3")]
+    pub tags: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UntagProductRequest {
+    #[prost(string, tag = "1")]
+    pub product_id: ::prost::alloc::string::String,
+    #[prost(string, repeated, tag = "2")]
+    pub tags: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UntagProductResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(string, repeated, tag = "3")]
+    pub tags: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ScheduleSaleRequest {
+    #[prost(string, tag = "1")]
+    pub product_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub sale_price: ::prost::alloc::string::String,
+    /// unix seconds; 0 starts the sale right away
+    #[prost(int64, tag = "3")]
+    pub starts_at: i64,
+    /// unix seconds; 0 runs the sale until cancelled
+    #[prost(int64, tag = "4")]
+    pub ends_at: i64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ScheduleSaleResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "3")]
+    pub product: ::core::option::Option<Product>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CancelSaleRequest {
+    #[prost(string, tag = "1")]
+    pub product_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CancelSaleResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "3")]
+    pub product: ::core::option::Option<Product>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PublishProductRequest {
+    #[prost(string, tag = "1")]
+    pub product_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PublishProductResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "3")]
+    pub product: ::core::option::Option<Product>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ArchiveProductRequest {
+    #[prost(string, tag = "1")]
+    pub product_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ArchiveProductResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "3")]
+    pub product: ::core::option::Option<Product>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Seller {
+    #[prost(string, tag = "1")]
+    pub seller_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+    /// the account that signs in as this seller
+    #[prost(string, tag = "3")]
+    pub user_id: ::prost::alloc::string::String,
+    #[prost(int64, tag = "4")]
+    pub created_at: i64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateSellerRequest {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub user_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateSellerResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "3")]
+    pub seller: ::core::option::Option<Seller>,
+}
+/// Published products only, unless the caller is the seller or the platform
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListProductsBySellerRequest {
+    #[prost(string, tag = "1")]
+    pub seller_id: ::prost::alloc::string::String,
+    #[prost(int32, tag = "2")]
+    pub page: i32,
+    #[prost(int32, tag = "3")]
+    pub page_size: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListProductsBySellerResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "3")]
+    pub products: ::prost::alloc::vec::Vec<Product>,
+    #[prost(int32, tag = "4")]
+    pub total_count: i32,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetCategoryStatsRequest {}
+/// Counts over the published products ListProducts shows in a category
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CategoryStats {
+    #[prost(string, tag = "1")]
+    pub category: ::prost::alloc::string::String,
+    #[prost(int32, tag = "2")]
+    pub product_count: i32,
+    #[prost(int32, tag = "3")]
+    pub in_stock_count: i32,
+    /// current prices, sales included
+    #[prost(string, tag = "4")]
+    pub min_price: ::prost::alloc::string::String,
+    #[prost(string, tag = "5")]
+    pub max_price: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetCategoryStatsResponse {
+    /// ordered by category name
+    #[prost(message, repeated, tag = "1")]
+    pub categories: ::prost::alloc::vec::Vec<CategoryStats>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReservationLine {
+    #[prost(string, tag = "1")]
+    pub product_id: ::prost::alloc::string::String,
+    #[prost(int32, tag = "2")]
+    pub quantity: i32,
+    /// set in responses: owed rather than taken from stock
+    #[prost(bool, tag = "3")]
+    pub backordered: bool,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReserveStockRequest {
+    #[prost(string, tag = "1")]
+    pub reservation_id: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "2")]
+    pub lines: ::prost::alloc::vec::Vec<ReservationLine>,
+}
+/// Either every line is reserved or none is
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReserveStockResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    /// in request order
+    #[prost(message, repeated, tag = "3")]
+    pub lines: ::prost::alloc::vec::Vec<ReservationLine>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConfirmReservationRequest {
+    #[prost(string, tag = "1")]
+    pub reservation_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConfirmReservationResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+}
+/// Releasing an unknown reservation succeeds, and a later ReserveStock with
+/// the same id is refused, so a reservation can be released before it lands
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReleaseReservationRequest {
+    #[prost(string, tag = "1")]
+    pub reservation_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReleaseReservationResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+}
+/// Returning an id again returns nothing more
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReturnStockRequest {
+    #[prost(string, tag = "1")]
+    pub return_id: ::prost::alloc::string::String,
+    /// backordered lines took no stock and are skipped
+    #[prost(message, repeated, tag = "2")]
+    pub lines: ::prost::alloc::vec::Vec<ReservationLine>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReturnStockResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+}
+/// Only published products are listed and can be ordered
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ProductStatus {
+    /// new products start as drafts
+    Draft = 0,
+    Published = 1,
+    Archived = 2,
+}
+impl ProductStatus {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Draft => "DRAFT",
+            Self::Published => "PUBLISHED",
+            Self::Archived => "ARCHIVED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "DRAFT" => Some(Self::Draft),
+            "PUBLISHED" => Some(Self::Published),
+            "ARCHIVED" => Some(Self::Archived),
+            _ => None,
+        }
+    }
+}
+/// Digital products have no stock to take and need no shipping address
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ProductType {
+    Physical = 0,
+    Digital = 1,
+}
+impl ProductType {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Physical => "PHYSICAL",
+            Self::Digital => "DIGITAL",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "PHYSICAL" => Some(Self::Physical),
+            "DIGITAL" => Some(Self::Digital),
+            _ => None,
+        }
+    }
+}
+/// ProductSort orders product listings. Pinned products only keep their
+/// positions under the category's default sort.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ProductSort {
+    /// the category's default sort, otherwise newest first
+    DefaultSort = 0,
+    Newest = 1,
+    BestSelling = 2,
+    PriceLowToHigh = 3,
+    PriceHighToLow = 4,
+    /// pinned products, the rest by name
+    Manual = 5,
+}
+impl ProductSort {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::DefaultSort => "DEFAULT_SORT",
+            Self::Newest => "NEWEST",
+            Self::BestSelling => "BEST_SELLING",
+            Self::PriceLowToHigh => "PRICE_LOW_TO_HIGH",
+            Self::PriceHighToLow => "PRICE_HIGH_TO_LOW",
+            Self::Manual => "MANUAL",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "DEFAULT_SORT" => Some(Self::DefaultSort),
+            "NEWEST" => Some(Self::Newest),
+            "BEST_SELLING" => Some(Self::BestSelling),
+            "PRICE_LOW_TO_HIGH" => Some(Self::PriceLowToHigh),
+            "PRICE_HIGH_TO_LOW" => Some(Self::PriceHighToLow),
+            "MANUAL" => Some(Self::Manual),
+            _ => None,
+        }
+    }
+}
+/// How a product can be ordered right now
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum AvailabilityState {
+    Unavailable = 0,
+    /// in stock, or digital
+    Available = 1,
+    /// out of stock, taken and filled on restock
+    Backorder = 2,
+    /// not released yet
+    Preorder = 3,
+}
+impl AvailabilityState {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Unavailable => "UNAVAILABLE",
+            Self::Available => "AVAILABLE",
+            Self::Backorder => "BACKORDER",
+            Self::Preorder => "PREORDER",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "UNAVAILABLE" => Some(Self::Unavailable),
+            "AVAILABLE" => Some(Self::Available),
+            "BACKORDER" => Some(Self::Backorder),
+            "PREORDER" => Some(Self::Preorder),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum StockLevel {
+    InStock = 0,
+    LowStock = 1,
+    OutOfStock = 2,
+}
+impl StockLevel {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::InStock => "IN_STOCK",
+            Self::LowStock => "LOW_STOCK",
+            Self::OutOfStock => "OUT_OF_STOCK",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "IN_STOCK" => Some(Self::InStock),
+            "LOW_STOCK" => Some(Self::LowStock),
+            "OUT_OF_STOCK" => Some(Self::OutOfStock),
+            _ => None,
+        }
+    }
+}
+/// Generated client implementations.
+pub mod product_service_client {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value,
+    )]
+    use tonic::codegen::*;
+    use tonic::codegen::http::Uri;
+    /// ProductService manages the catalog, inventory and stock reservations.
+    /// Refused requests fail with a gRPC status and a google.rpc.ErrorInfo
+    /// reason, e.g. NOT_FOUND / PRODUCT_NOT_FOUND, rather than success = false.
+    #[derive(Debug, Clone)]
+    pub struct ProductServiceClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl ProductServiceClient<tonic::transport::Channel> {
+        /// Attempt to create a new client by connecting to a given endpoint.
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> ProductServiceClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + std::marker::Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + std::marker::Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> ProductServiceClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+            >>::Error: Into<StdError> + std::marker::Send + std::marker::Sync,
+        {
+            ProductServiceClient::new(InterceptedService::new(inner, interceptor))
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_decoding_message_size(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_encoding_message_size(limit);
+            self
+        }
+        pub async fn add_product(
+            &mut self,
+            request: impl tonic::IntoRequest<super::AddProductRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::AddProductResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/product.ProductService/AddProduct",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("product.ProductService", "AddProduct"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn update_product(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdateProductRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UpdateProductResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/product.ProductService/UpdateProduct",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("product.ProductService", "UpdateProduct"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn delete_product(
+            &mut self,
+            request: impl tonic::IntoRequest<super::DeleteProductRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::DeleteProductResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/product.ProductService/DeleteProduct",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("product.ProductService", "DeleteProduct"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn restore_product(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RestoreProductRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RestoreProductResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/product.ProductService/RestoreProduct",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("product.ProductService", "RestoreProduct"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_product(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetProductRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetProductResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/product.ProductService/GetProduct",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("product.ProductService", "GetProduct"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_products_by_ids(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetProductsByIDsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetProductsByIDsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/product.ProductService/GetProductsByIds",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("product.ProductService", "GetProductsByIds"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn list_products(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListProductsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListProductsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/product.ProductService/ListProducts",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("product.ProductService", "ListProducts"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn check_availability(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CheckAvailabilityRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CheckAvailabilityResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/product.ProductService/CheckAvailability",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("product.ProductService", "CheckAvailability"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Stock of several products as an order would find it, for previews
+        pub async fn check_availability_batch(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CheckAvailabilityBatchRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CheckAvailabilityBatchResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/product.ProductService/CheckAvailabilityBatch",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("product.ProductService", "CheckAvailabilityBatch"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn update_inventory(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdateInventoryRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UpdateInventoryResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/product.ProductService/UpdateInventory",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("product.ProductService", "UpdateInventory"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn set_product_attributes(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SetProductAttributesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::SetProductAttributesResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/product.ProductService/SetProductAttributes",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("product.ProductService", "SetProductAttributes"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_product_attributes(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetProductAttributesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetProductAttributesResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/product.ProductService/GetProductAttributes",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("product.ProductService", "GetProductAttributes"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// ExportProducts streams the whole catalog in pages for external catalog
+        /// sync; platform only
+        pub async fn export_products(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ExportProductsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::ExportProductsResponse>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/product.ProductService/ExportProducts",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("product.ProductService", "ExportProducts"));
+            self.inner.server_streaming(req, path, codec).await
+        }
+        pub async fn schedule_restock(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ScheduleRestockRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ScheduleRestockResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/product.ProductService/ScheduleRestock",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("product.ProductService", "ScheduleRestock"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn receive_restock(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ReceiveRestockRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ReceiveRestockResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/product.ProductService/ReceiveRestock",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("product.ProductService", "ReceiveRestock"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// GetAvailabilityTimeline returns current stock plus pending restocks by expected date
+        pub async fn get_availability_timeline(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetAvailabilityTimelineRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetAvailabilityTimelineResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/product.ProductService/GetAvailabilityTimeline",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("product.ProductService", "GetAvailabilityTimeline"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// GetStockBadge returns cached, coarse stock levels for product listing tiles
+        pub async fn get_stock_badge(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetStockBadgeRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetStockBadgeResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/product.ProductService/GetStockBadge",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("product.ProductService", "GetStockBadge"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// GetRelatedProducts returns products often bought together with a product, topped up from its category
+        pub async fn get_related_products(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetRelatedProductsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetRelatedProductsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/product.ProductService/GetRelatedProducts",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("product.ProductService", "GetRelatedProducts"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// SetCategorySort sets the sort ListProducts applies to a category when none is requested
+        pub async fn set_category_sort(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SetCategorySortRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::SetCategorySortResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/product.ProductService/SetCategorySort",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("product.ProductService", "SetCategorySort"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// PinProduct pins a product to a fixed position in its category listing
+        pub async fn pin_product(
+            &mut self,
+            request: impl tonic::IntoRequest<super::PinProductRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::PinProductResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/product.ProductService/PinProduct",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("product.ProductService", "PinProduct"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn unpin_product(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UnpinProductRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UnpinProductResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/product.ProductService/UnpinProduct",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("product.ProductService", "UnpinProduct"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// GetCategoryMerchandising returns a category's default sort and pins
+        pub async fn get_category_merchandising(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetCategoryMerchandisingRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetCategoryMerchandisingResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/product.ProductService/GetCategoryMerchandising",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("product.ProductService", "GetCategoryMerchandising"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// TagProduct adds tags to a product, creating tags that don't exist yet
+        pub async fn tag_product(
+            &mut self,
+            request: impl tonic::IntoRequest<super::TagProductRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::TagProductResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/product.ProductService/TagProduct",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("product.ProductService", "TagProduct"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn untag_product(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UntagProductRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UntagProductResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/product.ProductService/UntagProduct",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("product.ProductService", "UntagProduct"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// ScheduleSale sets a sale price for a time window, replacing any earlier sale
+        pub async fn schedule_sale(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ScheduleSaleRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ScheduleSaleResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/product.ProductService/ScheduleSale",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("product.ProductService", "ScheduleSale"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn cancel_sale(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CancelSaleRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CancelSaleResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/product.ProductService/CancelSale",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("product.ProductService", "CancelSale"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// PublishProduct puts a draft or archived product on sale; ArchiveProduct takes it off
+        pub async fn publish_product(
+            &mut self,
+            request: impl tonic::IntoRequest<super::PublishProductRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::PublishProductResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/product.ProductService/PublishProduct",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("product.ProductService", "PublishProduct"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn archive_product(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ArchiveProductRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ArchiveProductResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/product.ProductService/ArchiveProduct",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("product.ProductService", "ArchiveProduct"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Marketplace: products may belong to a seller. Calls with a seller's bearer
+        /// token can only change that seller's products; customers change none.
+        pub async fn create_seller(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CreateSellerRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CreateSellerResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/product.ProductService/CreateSeller",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("product.ProductService", "CreateSeller"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn list_products_by_seller(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListProductsBySellerRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListProductsBySellerResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/product.ProductService/ListProductsBySeller",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("product.ProductService", "ListProductsBySeller"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// GetCategoryStats returns listing facets for every category with published products
+        pub async fn get_category_stats(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetCategoryStatsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetCategoryStatsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/product.ProductService/GetCategoryStats",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("product.ProductService", "GetCategoryStats"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Order saga: ReserveStock takes stock for an order's lines under a
+        /// reservation id, then ConfirmReservation keeps it or ReleaseReservation
+        /// puts it back. All three are idempotent per reservation id.
+        pub async fn reserve_stock(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ReserveStockRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ReserveStockResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/product.ProductService/ReserveStock",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("product.ProductService", "ReserveStock"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn confirm_reservation(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ConfirmReservationRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ConfirmReservationResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/product.ProductService/ConfirmReservation",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("product.ProductService", "ConfirmReservation"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn release_reservation(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ReleaseReservationRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ReleaseReservationResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/product.ProductService/ReleaseReservation",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("product.ProductService", "ReleaseReservation"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Puts back stock orders took and no longer hold, e.g. cancelled or
+        /// refunded units. Idempotent per return id.
+        pub async fn return_stock(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ReturnStockRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ReturnStockResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/product.ProductService/ReturnStock",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("product.ProductService", "ReturnStock"));
+            self.inner.unary(req, path, codec).await
+        }
+    }
+}
+/// Generated server implementations.
+pub mod product_service_server {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value,
+    )]
+    use tonic::codegen::*;
+    /// Generated trait containing gRPC methods that should be implemented for use with ProductServiceServer.
+    #[async_trait]
+    pub trait ProductService: std::marker::Send + std::marker::Sync + 'static {
+        async fn add_product(
+            &self,
+            request: tonic::Request<super::AddProductRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::AddProductResponse>,
+            tonic::Status,
+        >;
+        async fn update_product(
+            &self,
+            request: tonic::Request<super::UpdateProductRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UpdateProductResponse>,
+            tonic::Status,
+        >;
+        async fn delete_product(
+            &self,
+            request: tonic::Request<super::DeleteProductRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::DeleteProductResponse>,
+            tonic::Status,
+        >;
+        async fn restore_product(
+            &self,
+            request: tonic::Request<super::RestoreProductRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RestoreProductResponse>,
+            tonic::Status,
+        >;
+        async fn get_product(
+            &self,
+            request: tonic::Request<super::GetProductRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetProductResponse>,
+            tonic::Status,
+        >;
+        async fn get_products_by_ids(
+            &self,
+            request: tonic::Request<super::GetProductsByIDsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetProductsByIDsResponse>,
+            tonic::Status,
+        >;
+        async fn list_products(
+            &self,
+            request: tonic::Request<super::ListProductsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListProductsResponse>,
+            tonic::Status,
+        >;
+        async fn check_availability(
+            &self,
+            request: tonic::Request<super::CheckAvailabilityRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CheckAvailabilityResponse>,
+            tonic::Status,
+        >;
+        /// Stock of several products as an order would find it, for previews
+        async fn check_availability_batch(
+            &self,
+            request: tonic::Request<super::CheckAvailabilityBatchRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CheckAvailabilityBatchResponse>,
+            tonic::Status,
+        >;
+        async fn update_inventory(
+            &self,
+            request: tonic::Request<super::UpdateInventoryRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UpdateInventoryResponse>,
+            tonic::Status,
+        >;
+        async fn set_product_attributes(
+            &self,
+            request: tonic::Request<super::SetProductAttributesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::SetProductAttributesResponse>,
+            tonic::Status,
+        >;
+        async fn get_product_attributes(
+            &self,
+            request: tonic::Request<super::GetProductAttributesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetProductAttributesResponse>,
+            tonic::Status,
+        >;
+        /// Server streaming response type for the ExportProducts method.
+        type ExportProductsStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<super::ExportProductsResponse, tonic::Status>,
+            >
+            + std::marker::Send
+            + 'static;
+        /// ExportProducts streams the whole catalog in pages for external catalog
+        /// sync; platform only
+        async fn export_products(
+            &self,
+            request: tonic::Request<super::ExportProductsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<Self::ExportProductsStream>,
+            tonic::Status,
+        >;
+        async fn schedule_restock(
+            &self,
+            request: tonic::Request<super::ScheduleRestockRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ScheduleRestockResponse>,
+            tonic::Status,
+        >;
+        async fn receive_restock(
+            &self,
+            request: tonic::Request<super::ReceiveRestockRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ReceiveRestockResponse>,
+            tonic::Status,
+        >;
+        /// GetAvailabilityTimeline returns current stock plus pending restocks by expected date
+        async fn get_availability_timeline(
+            &self,
+            request: tonic::Request<super::GetAvailabilityTimelineRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetAvailabilityTimelineResponse>,
+            tonic::Status,
+        >;
+        /// GetStockBadge returns cached, coarse stock levels for product listing tiles
+        async fn get_stock_badge(
+            &self,
+            request: tonic::Request<super::GetStockBadgeRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetStockBadgeResponse>,
+            tonic::Status,
+        >;
+        /// GetRelatedProducts returns products often bought together with a product, topped up from its category
+        async fn get_related_products(
+            &self,
+            request: tonic::Request<super::GetRelatedProductsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetRelatedProductsResponse>,
+            tonic::Status,
+        >;
+        /// SetCategorySort sets the sort ListProducts applies to a category when none is requested
+        async fn set_category_sort(
+            &self,
+            request: tonic::Request<super::SetCategorySortRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::SetCategorySortResponse>,
+            tonic::Status,
+        >;
+        /// PinProduct pins a product to a fixed position in its category listing
+        async fn pin_product(
+            &self,
+            request: tonic::Request<super::PinProductRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::PinProductResponse>,
+            tonic::Status,
+        >;
+        async fn unpin_product(
+            &self,
+            request: tonic::Request<super::UnpinProductRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UnpinProductResponse>,
+            tonic::Status,
+        >;
+        /// GetCategoryMerchandising returns a category's default sort and pins
+        async fn get_category_merchandising(
+            &self,
+            request: tonic::Request<super::GetCategoryMerchandisingRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetCategoryMerchandisingResponse>,
+            tonic::Status,
+        >;
+        /// TagProduct adds tags to a product, creating tags that don't exist yet
+        async fn tag_product(
+            &self,
+            request: tonic::Request<super::TagProductRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::TagProductResponse>,
+            tonic::Status,
+        >;
+        async fn untag_product(
+            &self,
+            request: tonic::Request<super::UntagProductRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UntagProductResponse>,
+            tonic::Status,
+        >;
+        /// ScheduleSale sets a sale price for a time window, replacing any earlier sale
+        async fn schedule_sale(
+            &self,
+            request: tonic::Request<super::ScheduleSaleRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ScheduleSaleResponse>,
+            tonic::Status,
+        >;
+        async fn cancel_sale(
+            &self,
+            request: tonic::Request<super::CancelSaleRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CancelSaleResponse>,
+            tonic::Status,
+        >;
+        /// PublishProduct puts a draft or archived product on sale; ArchiveProduct takes it off
+        async fn publish_product(
+            &self,
+            request: tonic::Request<super::PublishProductRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::PublishProductResponse>,
+            tonic::Status,
+        >;
+        async fn archive_product(
+            &self,
+            request: tonic::Request<super::ArchiveProductRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ArchiveProductResponse>,
+            tonic::Status,
+        >;
+        /// Marketplace: products may belong to a seller. Calls with a seller's bearer
+        /// token can only change that seller's products; customers change none.
+        async fn create_seller(
+            &self,
+            request: tonic::Request<super::CreateSellerRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CreateSellerResponse>,
+            tonic::Status,
+        >;
+        async fn list_products_by_seller(
+            &self,
+            request: tonic::Request<super::ListProductsBySellerRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListProductsBySellerResponse>,
+            tonic::Status,
+        >;
+        /// GetCategoryStats returns listing facets for every category with published products
+        async fn get_category_stats(
+            &self,
+            request: tonic::Request<super::GetCategoryStatsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetCategoryStatsResponse>,
+            tonic::Status,
+        >;
+        /// Order saga: ReserveStock takes stock for an order's lines under a
+        /// reservation id, then ConfirmReservation keeps it or ReleaseReservation
+        /// puts it back. All three are idempotent per reservation id.
+        async fn reserve_stock(
+            &self,
+            request: tonic::Request<super::ReserveStockRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ReserveStockResponse>,
+            tonic::Status,
+        >;
+        async fn confirm_reservation(
+            &self,
+            request: tonic::Request<super::ConfirmReservationRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ConfirmReservationResponse>,
+            tonic::Status,
+        >;
+        async fn release_reservation(
+            &self,
+            request: tonic::Request<super::ReleaseReservationRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ReleaseReservationResponse>,
+            tonic::Status,
+        >;
+        /// Puts back stock orders took and no longer hold, e.g. cancelled or
+        /// refunded units. Idempotent per return id.
+        async fn return_stock(
+            &self,
+            request: tonic::Request<super::ReturnStockRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ReturnStockResponse>,
+            tonic::Status,
+        >;
+    }
+    /// ProductService manages the catalog, inventory and stock reservations.
+    /// Refused requests fail with a gRPC status and a google.rpc.ErrorInfo
+    /// reason, e.g. NOT_FOUND / PRODUCT_NOT_FOUND, rather than success = false.
+    #[derive(Debug)]
+    pub struct ProductServiceServer<T> {
+        inner: Arc<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+        max_decoding_message_size: Option<usize>,
+        max_encoding_message_size: Option<usize>,
+    }
+    impl<T> ProductServiceServer<T> {
+        pub fn new(inner: T) -> Self {
+            Self::from_arc(Arc::new(inner))
+        }
+        pub fn from_arc(inner: Arc<T>) -> Self {
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+                max_decoding_message_size: None,
+                max_encoding_message_size: None,
+            }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        /// Enable decompressing requests with the given encoding.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.accept_compression_encodings.enable(encoding);
+            self
+        }
+        /// Compress responses with the given encoding, if the client supports it.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.send_compression_encodings.enable(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.max_decoding_message_size = Some(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.max_encoding_message_size = Some(limit);
+            self
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for ProductServiceServer<T>
+    where
+        T: ProductService,
+        B: Body + std::marker::Send + 'static,
+        B::Error: Into<StdError> + std::marker::Send + 'static,
+    {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = std::convert::Infallible;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(
+            &mut self,
+            _cx: &mut Context<'_>,
+        ) -> Poll<std::result::Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            match req.uri().path() {
+                "/product.ProductService/AddProduct" => {
+                    #[allow(non_camel_case_types)]
+                    struct AddProductSvc<T: ProductService>(pub Arc<T>);
+                    impl<
+                        T: ProductService,
+                    > tonic::server::UnaryService<super::AddProductRequest>
+                    for AddProductSvc<T> {
+                        type Response = super::AddProductResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::AddProductRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ProductService>::add_product(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = AddProductSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/product.ProductService/UpdateProduct" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateProductSvc<T: ProductService>(pub Arc<T>);
+                    impl<
+                        T: ProductService,
+                    > tonic::server::UnaryService<super::UpdateProductRequest>
+                    for UpdateProductSvc<T> {
+                        type Response = super::UpdateProductResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateProductRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ProductService>::update_product(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = UpdateProductSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/product.ProductService/DeleteProduct" => {
+                    #[allow(non_camel_case_types)]
+                    struct DeleteProductSvc<T: ProductService>(pub Arc<T>);
+                    impl<
+                        T: ProductService,
+                    > tonic::server::UnaryService<super::DeleteProductRequest>
+                    for DeleteProductSvc<T> {
+                        type Response = super::DeleteProductResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DeleteProductRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ProductService>::delete_product(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = DeleteProductSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/product.ProductService/RestoreProduct" => {
+                    #[allow(non_camel_case_types)]
+                    struct RestoreProductSvc<T: ProductService>(pub Arc<T>);
+                    impl<
+                        T: ProductService,
+                    > tonic::server::UnaryService<super::RestoreProductRequest>
+                    for RestoreProductSvc<T> {
+                        type Response = super::RestoreProductResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::RestoreProductRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ProductService>::restore_product(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = RestoreProductSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/product.ProductService/GetProduct" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetProductSvc<T: ProductService>(pub Arc<T>);
+                    impl<
+                        T: ProductService,
+                    > tonic::server::UnaryService<super::GetProductRequest>
+                    for GetProductSvc<T> {
+                        type Response = super::GetProductResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetProductRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ProductService>::get_product(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetProductSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/product.ProductService/GetProductsByIds" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetProductsByIdsSvc<T: ProductService>(pub Arc<T>);
+                    impl<
+                        T: ProductService,
+                    > tonic::server::UnaryService<super::GetProductsByIDsRequest>
+                    for GetProductsByIdsSvc<T> {
+                        type Response = super::GetProductsByIDsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetProductsByIDsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ProductService>::get_products_by_ids(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetProductsByIdsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/product.ProductService/ListProducts" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListProductsSvc<T: ProductService>(pub Arc<T>);
+                    impl<
+                        T: ProductService,
+                    > tonic::server::UnaryService<super::ListProductsRequest>
+                    for ListProductsSvc<T> {
+                        type Response = super::ListProductsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListProductsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ProductService>::list_products(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ListProductsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/product.ProductService/CheckAvailability" => {
+                    #[allow(non_camel_case_types)]
+                    struct CheckAvailabilitySvc<T: ProductService>(pub Arc<T>);
+                    impl<
+                        T: ProductService,
+                    > tonic::server::UnaryService<super::CheckAvailabilityRequest>
+                    for CheckAvailabilitySvc<T> {
+                        type Response = super::CheckAvailabilityResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CheckAvailabilityRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ProductService>::check_availability(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = CheckAvailabilitySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/product.ProductService/CheckAvailabilityBatch" => {
+                    #[allow(non_camel_case_types)]
+                    struct CheckAvailabilityBatchSvc<T: ProductService>(pub Arc<T>);
+                    impl<
+                        T: ProductService,
+                    > tonic::server::UnaryService<super::CheckAvailabilityBatchRequest>
+                    for CheckAvailabilityBatchSvc<T> {
+                        type Response = super::CheckAvailabilityBatchResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CheckAvailabilityBatchRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ProductService>::check_availability_batch(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = CheckAvailabilityBatchSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/product.ProductService/UpdateInventory" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateInventorySvc<T: ProductService>(pub Arc<T>);
+                    impl<
+                        T: ProductService,
+                    > tonic::server::UnaryService<super::UpdateInventoryRequest>
+                    for UpdateInventorySvc<T> {
+                        type Response = super::UpdateInventoryResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateInventoryRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ProductService>::update_inventory(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = UpdateInventorySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/product.ProductService/SetProductAttributes" => {
+                    #[allow(non_camel_case_types)]
+                    struct SetProductAttributesSvc<T: ProductService>(pub Arc<T>);
+                    impl<
+                        T: ProductService,
+                    > tonic::server::UnaryService<super::SetProductAttributesRequest>
+                    for SetProductAttributesSvc<T> {
+                        type Response = super::SetProductAttributesResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SetProductAttributesRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ProductService>::set_product_attributes(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = SetProductAttributesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/product.ProductService/GetProductAttributes" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetProductAttributesSvc<T: ProductService>(pub Arc<T>);
+                    impl<
+                        T: ProductService,
+                    > tonic::server::UnaryService<super::GetProductAttributesRequest>
+                    for GetProductAttributesSvc<T> {
+                        type Response = super::GetProductAttributesResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetProductAttributesRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ProductService>::get_product_attributes(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetProductAttributesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/product.ProductService/ExportProducts" => {
+                    #[allow(non_camel_case_types)]
+                    struct ExportProductsSvc<T: ProductService>(pub Arc<T>);
+                    impl<
+                        T: ProductService,
+                    > tonic::server::ServerStreamingService<super::ExportProductsRequest>
+                    for ExportProductsSvc<T> {
+                        type Response = super::ExportProductsResponse;
+                        type ResponseStream = T::ExportProductsStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ExportProductsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ProductService>::export_products(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ExportProductsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/product.ProductService/ScheduleRestock" => {
+                    #[allow(non_camel_case_types)]
+                    struct ScheduleRestockSvc<T: ProductService>(pub Arc<T>);
+                    impl<
+                        T: ProductService,
+                    > tonic::server::UnaryService<super::ScheduleRestockRequest>
+                    for ScheduleRestockSvc<T> {
+                        type Response = super::ScheduleRestockResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ScheduleRestockRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ProductService>::schedule_restock(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ScheduleRestockSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/product.ProductService/ReceiveRestock" => {
+                    #[allow(non_camel_case_types)]
+                    struct ReceiveRestockSvc<T: ProductService>(pub Arc<T>);
+                    impl<
+                        T: ProductService,
+                    > tonic::server::UnaryService<super::ReceiveRestockRequest>
+                    for ReceiveRestockSvc<T> {
+                        type Response = super::ReceiveRestockResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ReceiveRestockRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ProductService>::receive_restock(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ReceiveRestockSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/product.ProductService/GetAvailabilityTimeline" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetAvailabilityTimelineSvc<T: ProductService>(pub Arc<T>);
+                    impl<
+                        T: ProductService,
+                    > tonic::server::UnaryService<super::GetAvailabilityTimelineRequest>
+                    for GetAvailabilityTimelineSvc<T> {
+                        type Response = super::GetAvailabilityTimelineResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::GetAvailabilityTimelineRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ProductService>::get_availability_timeline(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetAvailabilityTimelineSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/product.ProductService/GetStockBadge" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetStockBadgeSvc<T: ProductService>(pub Arc<T>);
+                    impl<
+                        T: ProductService,
+                    > tonic::server::UnaryService<super::GetStockBadgeRequest>
+                    for GetStockBadgeSvc<T> {
+                        type Response = super::GetStockBadgeResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetStockBadgeRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ProductService>::get_stock_badge(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetStockBadgeSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/product.ProductService/GetRelatedProducts" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetRelatedProductsSvc<T: ProductService>(pub Arc<T>);
+                    impl<
+                        T: ProductService,
+                    > tonic::server::UnaryService<super::GetRelatedProductsRequest>
+                    for GetRelatedProductsSvc<T> {
+                        type Response = super::GetRelatedProductsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetRelatedProductsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ProductService>::get_related_products(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetRelatedProductsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/product.ProductService/SetCategorySort" => {
+                    #[allow(non_camel_case_types)]
+                    struct SetCategorySortSvc<T: ProductService>(pub Arc<T>);
+                    impl<
+                        T: ProductService,
+                    > tonic::server::UnaryService<super::SetCategorySortRequest>
+                    for SetCategorySortSvc<T> {
+                        type Response = super::SetCategorySortResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SetCategorySortRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ProductService>::set_category_sort(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = SetCategorySortSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/product.ProductService/PinProduct" => {
+                    #[allow(non_camel_case_types)]
+                    struct PinProductSvc<T: ProductService>(pub Arc<T>);
+                    impl<
+                        T: ProductService,
+                    > tonic::server::UnaryService<super::PinProductRequest>
+                    for PinProductSvc<T> {
+                        type Response = super::PinProductResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::PinProductRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ProductService>::pin_product(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = PinProductSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/product.ProductService/UnpinProduct" => {
+                    #[allow(non_camel_case_types)]
+                    struct UnpinProductSvc<T: ProductService>(pub Arc<T>);
+                    impl<
+                        T: ProductService,
+                    > tonic::server::UnaryService<super::UnpinProductRequest>
+                    for UnpinProductSvc<T> {
+                        type Response = super::UnpinProductResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UnpinProductRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ProductService>::unpin_product(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = UnpinProductSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/product.ProductService/GetCategoryMerchandising" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetCategoryMerchandisingSvc<T: ProductService>(pub Arc<T>);
+                    impl<
+                        T: ProductService,
+                    > tonic::server::UnaryService<super::GetCategoryMerchandisingRequest>
+                    for GetCategoryMerchandisingSvc<T> {
+                        type Response = super::GetCategoryMerchandisingResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::GetCategoryMerchandisingRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ProductService>::get_category_merchandising(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetCategoryMerchandisingSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/product.ProductService/TagProduct" => {
                     #[allow(non_camel_case_types)]
-                    struct AddProductSvc<T: ProductService>(pub Arc<T>);
+                    struct TagProductSvc<T: ProductService>(pub Arc<T>);
                     impl<
                         T: ProductService,
-                    > tonic::server::UnaryService<super::AddProductRequest>
-                    for AddProductSvc<T> {
-                        type Response = super::AddProductResponse;
+                    > tonic::server::UnaryService<super::TagProductRequest>
+                    for TagProductSvc<T> {
+                        type Response = super::TagProductResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::AddProductRequest>,
+                            request: tonic::Request<super::TagProductRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as ProductService>::add_product(&inner, request).await
+                                <T as ProductService>::tag_product(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -613,7 +3326,7 @@ pub mod product_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = AddProductSvc(inner);
+                        let method = TagProductSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -629,25 +3342,25 @@ pub mod product_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/product.ProductService/UpdateProduct" => {
+                "/product.ProductService/UntagProduct" => {
                     #[allow(non_camel_case_types)]
-                    struct UpdateProductSvc<T: ProductService>(pub Arc<T>);
+                    struct UntagProductSvc<T: ProductService>(pub Arc<T>);
                     impl<
                         T: ProductService,
-                    > tonic::server::UnaryService<super::UpdateProductRequest>
-                    for UpdateProductSvc<T> {
-                        type Response = super::UpdateProductResponse;
+                    > tonic::server::UnaryService<super::UntagProductRequest>
+                    for UntagProductSvc<T> {
+                        type Response = super::UntagProductResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::UpdateProductRequest>,
+                            request: tonic::Request<super::UntagProductRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as ProductService>::update_product(&inner, request).await
+                                <T as ProductService>::untag_product(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -658,7 +3371,7 @@ pub mod product_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = UpdateProductSvc(inner);
+                        let method = UntagProductSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -674,25 +3387,25 @@ pub mod product_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/product.ProductService/DeleteProduct" => {
+                "/product.ProductService/ScheduleSale" => {
                     #[allow(non_camel_case_types)]
-                    struct DeleteProductSvc<T: ProductService>(pub Arc<T>);
+                    struct ScheduleSaleSvc<T: ProductService>(pub Arc<T>);
                     impl<
                         T: ProductService,
-                    > tonic::server::UnaryService<super::DeleteProductRequest>
-                    for DeleteProductSvc<T> {
-                        type Response = super::DeleteProductResponse;
+                    > tonic::server::UnaryService<super::ScheduleSaleRequest>
+                    for ScheduleSaleSvc<T> {
+                        type Response = super::ScheduleSaleResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::DeleteProductRequest>,
+                            request: tonic::Request<super::ScheduleSaleRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as ProductService>::delete_product(&inner, request).await
+                                <T as ProductService>::schedule_sale(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -703,7 +3416,7 @@ pub mod product_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = DeleteProductSvc(inner);
+                        let method = ScheduleSaleSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -719,25 +3432,25 @@ pub mod product_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/product.ProductService/GetProduct" => {
+                "/product.ProductService/CancelSale" => {
                     #[allow(non_camel_case_types)]
-                    struct GetProductSvc<T: ProductService>(pub Arc<T>);
+                    struct CancelSaleSvc<T: ProductService>(pub Arc<T>);
                     impl<
                         T: ProductService,
-                    > tonic::server::UnaryService<super::GetProductRequest>
-                    for GetProductSvc<T> {
-                        type Response = super::GetProductResponse;
+                    > tonic::server::UnaryService<super::CancelSaleRequest>
+                    for CancelSaleSvc<T> {
+                        type Response = super::CancelSaleResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetProductRequest>,
+                            request: tonic::Request<super::CancelSaleRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as ProductService>::get_product(&inner, request).await
+                                <T as ProductService>::cancel_sale(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -748,7 +3461,7 @@ pub mod product_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetProductSvc(inner);
+                        let method = CancelSaleSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -764,25 +3477,25 @@ pub mod product_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/product.ProductService/GetProductsByIds" => {
+                "/product.ProductService/PublishProduct" => {
                     #[allow(non_camel_case_types)]
-                    struct GetProductsByIdsSvc<T: ProductService>(pub Arc<T>);
+                    struct PublishProductSvc<T: ProductService>(pub Arc<T>);
                     impl<
                         T: ProductService,
-                    > tonic::server::UnaryService<super::GetProductsByIDsRequest>
-                    for GetProductsByIdsSvc<T> {
-                        type Response = super::GetProductsByIDsResponse;
+                    > tonic::server::UnaryService<super::PublishProductRequest>
+                    for PublishProductSvc<T> {
+                        type Response = super::PublishProductResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetProductsByIDsRequest>,
+                            request: tonic::Request<super::PublishProductRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as ProductService>::get_products_by_ids(&inner, request)
+                                <T as ProductService>::publish_product(&inner, request)
                                     .await
                             };
                             Box::pin(fut)
@@ -794,7 +3507,7 @@ pub mod product_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetProductsByIdsSvc(inner);
+                        let method = PublishProductSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -810,25 +3523,26 @@ pub mod product_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/product.ProductService/ListProducts" => {
+                "/product.ProductService/ArchiveProduct" => {
                     #[allow(non_camel_case_types)]
-                    struct ListProductsSvc<T: ProductService>(pub Arc<T>);
+                    struct ArchiveProductSvc<T: ProductService>(pub Arc<T>);
                     impl<
                         T: ProductService,
-                    > tonic::server::UnaryService<super::ListProductsRequest>
-                    for ListProductsSvc<T> {
-                        type Response = super::ListProductsResponse;
+                    > tonic::server::UnaryService<super::ArchiveProductRequest>
+                    for ArchiveProductSvc<T> {
+                        type Response = super::ArchiveProductResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ListProductsRequest>,
+                            request: tonic::Request<super::ArchiveProductRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as ProductService>::list_products(&inner, request).await
+                                <T as ProductService>::archive_product(&inner, request)
+                                    .await
                             };
                             Box::pin(fut)
                         }
@@ -839,7 +3553,7 @@ pub mod product_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = ListProductsSvc(inner);
+                        let method = ArchiveProductSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -855,25 +3569,73 @@ pub mod product_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/product.ProductService/CheckAvailability" => {
+                "/product.ProductService/CreateSeller" => {
                     #[allow(non_camel_case_types)]
-                    struct CheckAvailabilitySvc<T: ProductService>(pub Arc<T>);
+                    struct CreateSellerSvc<T: ProductService>(pub Arc<T>);
                     impl<
                         T: ProductService,
-                    > tonic::server::UnaryService<super::CheckAvailabilityRequest>
-                    for CheckAvailabilitySvc<T> {
-                        type Response = super::CheckAvailabilityResponse;
+                    > tonic::server::UnaryService<super::CreateSellerRequest>
+                    for CreateSellerSvc<T> {
+                        type Response = super::CreateSellerResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::CheckAvailabilityRequest>,
+                            request: tonic::Request<super::CreateSellerRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as ProductService>::check_availability(&inner, request)
+                                <T as ProductService>::create_seller(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = CreateSellerSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/product.ProductService/ListProductsBySeller" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListProductsBySellerSvc<T: ProductService>(pub Arc<T>);
+                    impl<
+                        T: ProductService,
+                    > tonic::server::UnaryService<super::ListProductsBySellerRequest>
+                    for ListProductsBySellerSvc<T> {
+                        type Response = super::ListProductsBySellerResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListProductsBySellerRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ProductService>::list_products_by_seller(
+                                        &inner,
+                                        request,
+                                    )
                                     .await
                             };
                             Box::pin(fut)
@@ -885,7 +3647,7 @@ pub mod product_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = CheckAvailabilitySvc(inner);
+                        let method = ListProductsBySellerSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -901,25 +3663,25 @@ pub mod product_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/product.ProductService/UpdateInventory" => {
+                "/product.ProductService/GetCategoryStats" => {
                     #[allow(non_camel_case_types)]
-                    struct UpdateInventorySvc<T: ProductService>(pub Arc<T>);
+                    struct GetCategoryStatsSvc<T: ProductService>(pub Arc<T>);
                     impl<
                         T: ProductService,
-                    > tonic::server::UnaryService<super::UpdateInventoryRequest>
-                    for UpdateInventorySvc<T> {
-                        type Response = super::UpdateInventoryResponse;
+                    > tonic::server::UnaryService<super::GetCategoryStatsRequest>
+                    for GetCategoryStatsSvc<T> {
+                        type Response = super::GetCategoryStatsResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::UpdateInventoryRequest>,
+                            request: tonic::Request<super::GetCategoryStatsRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as ProductService>::update_inventory(&inner, request)
+                                <T as ProductService>::get_category_stats(&inner, request)
                                     .await
                             };
                             Box::pin(fut)
@@ -931,7 +3693,189 @@ pub mod product_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = UpdateInventorySvc(inner);
+                        let method = GetCategoryStatsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/product.ProductService/ReserveStock" => {
+                    #[allow(non_camel_case_types)]
+                    struct ReserveStockSvc<T: ProductService>(pub Arc<T>);
+                    impl<
+                        T: ProductService,
+                    > tonic::server::UnaryService<super::ReserveStockRequest>
+                    for ReserveStockSvc<T> {
+                        type Response = super::ReserveStockResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ReserveStockRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ProductService>::reserve_stock(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ReserveStockSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/product.ProductService/ConfirmReservation" => {
+                    #[allow(non_camel_case_types)]
+                    struct ConfirmReservationSvc<T: ProductService>(pub Arc<T>);
+                    impl<
+                        T: ProductService,
+                    > tonic::server::UnaryService<super::ConfirmReservationRequest>
+                    for ConfirmReservationSvc<T> {
+                        type Response = super::ConfirmReservationResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ConfirmReservationRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ProductService>::confirm_reservation(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ConfirmReservationSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/product.ProductService/ReleaseReservation" => {
+                    #[allow(non_camel_case_types)]
+                    struct ReleaseReservationSvc<T: ProductService>(pub Arc<T>);
+                    impl<
+                        T: ProductService,
+                    > tonic::server::UnaryService<super::ReleaseReservationRequest>
+                    for ReleaseReservationSvc<T> {
+                        type Response = super::ReleaseReservationResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ReleaseReservationRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ProductService>::release_reservation(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ReleaseReservationSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/product.ProductService/ReturnStock" => {
+                    #[allow(non_camel_case_types)]
+                    struct ReturnStockSvc<T: ProductService>(pub Arc<T>);
+                    impl<
+                        T: ProductService,
+                    > tonic::server::UnaryService<super::ReturnStockRequest>
+                    for ReturnStockSvc<T> {
+                        type Response = super::ReturnStockResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ReturnStockRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ProductService>::return_stock(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ReturnStockSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
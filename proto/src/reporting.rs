@@ -0,0 +1,496 @@
+// This file is @generated by prost-build.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetSalesReportRequest {
+    /// YYYY-MM-DD in the report time zone, defaults to 29 days before to_date
+    #[prost(string, tag = "1")]
+    pub from_date: ::prost::alloc::string::String,
+    /// YYYY-MM-DD inclusive, defaults to today
+    #[prost(string, tag = "2")]
+    pub to_date: ::prost::alloc::string::String,
+    #[prost(enumeration = "ReportGranularity", tag = "3")]
+    pub granularity: i32,
+    /// IANA name, overrides the configured report time zone
+    #[prost(string, tag = "4")]
+    pub time_zone: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SalesBucket {
+    /// YYYY-MM-DD
+    #[prost(string, tag = "1")]
+    pub period_start: ::prost::alloc::string::String,
+    #[prost(int32, tag = "2")]
+    pub order_count: i32,
+    /// decimal string in the default currency
+    #[prost(string, tag = "3")]
+    pub revenue: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetSalesReportResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub time_zone: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "4")]
+    pub buckets: ::prost::alloc::vec::Vec<SalesBucket>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetOrderMetricsRequest {
+    /// YYYY-MM-DD in the report time zone, defaults to 29 days before to_date
+    #[prost(string, tag = "1")]
+    pub from_date: ::prost::alloc::string::String,
+    /// YYYY-MM-DD inclusive, defaults to today
+    #[prost(string, tag = "2")]
+    pub to_date: ::prost::alloc::string::String,
+    #[prost(enumeration = "ReportGranularity", tag = "3")]
+    pub group_by: i32,
+    /// IANA name, overrides the configured report time zone
+    #[prost(string, tag = "4")]
+    pub time_zone: ::prost::alloc::string::String,
+}
+/// Amounts are decimal strings in the default currency
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OrderMetrics {
+    /// YYYY-MM-DD; empty for the whole range
+    #[prost(string, tag = "1")]
+    pub period_start: ::prost::alloc::string::String,
+    /// every order placed, cancelled ones included
+    #[prost(int32, tag = "2")]
+    pub order_count: i32,
+    #[prost(int32, tag = "3")]
+    pub cancelled_count: i32,
+    /// total of the orders that weren't cancelled
+    #[prost(string, tag = "4")]
+    pub revenue: ::prost::alloc::string::String,
+    #[prost(string, tag = "5")]
+    pub average_order_value: ::prost::alloc::string::String,
+    /// cancelled_count / order_count, 0 without orders
+    #[prost(double, tag = "6")]
+    pub cancellation_rate: f64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetOrderMetricsResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub time_zone: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "4")]
+    pub buckets: ::prost::alloc::vec::Vec<OrderMetrics>,
+    #[prost(message, optional, tag = "5")]
+    pub totals: ::core::option::Option<OrderMetrics>,
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ReportGranularity {
+    Day = 0,
+    /// ISO weeks, starting on Monday
+    Week = 1,
+}
+impl ReportGranularity {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Day => "DAY",
+            Self::Week => "WEEK",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "DAY" => Some(Self::Day),
+            "WEEK" => Some(Self::Week),
+            _ => None,
+        }
+    }
+}
+/// Generated client implementations.
+pub mod reporting_service_client {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value,
+    )]
+    use tonic::codegen::*;
+    use tonic::codegen::http::Uri;
+    /// ReportingService serves sales analytics bucketed in the business's time zone
+    #[derive(Debug, Clone)]
+    pub struct ReportingServiceClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl ReportingServiceClient<tonic::transport::Channel> {
+        /// Attempt to create a new client by connecting to a given endpoint.
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> ReportingServiceClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + std::marker::Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + std::marker::Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> ReportingServiceClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+            >>::Error: Into<StdError> + std::marker::Send + std::marker::Sync,
+        {
+            ReportingServiceClient::new(InterceptedService::new(inner, interceptor))
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_decoding_message_size(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_encoding_message_size(limit);
+            self
+        }
+        /// GetSalesReport returns order counts and revenue per day or week
+        pub async fn get_sales_report(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetSalesReportRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetSalesReportResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/reporting.ReportingService/GetSalesReport",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("reporting.ReportingService", "GetSalesReport"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// GetOrderMetrics returns order counts, revenue, average order value and
+        /// cancellation rate per day or week, for admin dashboards
+        pub async fn get_order_metrics(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetOrderMetricsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetOrderMetricsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/reporting.ReportingService/GetOrderMetrics",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("reporting.ReportingService", "GetOrderMetrics"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+    }
+}
+/// Generated server implementations.
+pub mod reporting_service_server {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value,
+    )]
+    use tonic::codegen::*;
+    /// Generated trait containing gRPC methods that should be implemented for use with ReportingServiceServer.
+    #[async_trait]
+    pub trait ReportingService: std::marker::Send + std::marker::Sync + 'static {
+        /// GetSalesReport returns order counts and revenue per day or week
+        async fn get_sales_report(
+            &self,
+            request: tonic::Request<super::GetSalesReportRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetSalesReportResponse>,
+            tonic::Status,
+        >;
+        /// GetOrderMetrics returns order counts, revenue, average order value and
+        /// cancellation rate per day or week, for admin dashboards
+        async fn get_order_metrics(
+            &self,
+            request: tonic::Request<super::GetOrderMetricsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetOrderMetricsResponse>,
+            tonic::Status,
+        >;
+    }
+    /// ReportingService serves sales analytics bucketed in the business's time zone
+    #[derive(Debug)]
+    pub struct ReportingServiceServer<T> {
+        inner: Arc<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+        max_decoding_message_size: Option<usize>,
+        max_encoding_message_size: Option<usize>,
+    }
+    impl<T> ReportingServiceServer<T> {
+        pub fn new(inner: T) -> Self {
+            Self::from_arc(Arc::new(inner))
+        }
+        pub fn from_arc(inner: Arc<T>) -> Self {
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+                max_decoding_message_size: None,
+                max_encoding_message_size: None,
+            }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        /// Enable decompressing requests with the given encoding.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.accept_compression_encodings.enable(encoding);
+            self
+        }
+        /// Compress responses with the given encoding, if the client supports it.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.send_compression_encodings.enable(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.max_decoding_message_size = Some(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.max_encoding_message_size = Some(limit);
+            self
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for ReportingServiceServer<T>
+    where
+        T: ReportingService,
+        B: Body + std::marker::Send + 'static,
+        B::Error: Into<StdError> + std::marker::Send + 'static,
+    {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = std::convert::Infallible;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(
+            &mut self,
+            _cx: &mut Context<'_>,
+        ) -> Poll<std::result::Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            match req.uri().path() {
+                "/reporting.ReportingService/GetSalesReport" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetSalesReportSvc<T: ReportingService>(pub Arc<T>);
+                    impl<
+                        T: ReportingService,
+                    > tonic::server::UnaryService<super::GetSalesReportRequest>
+                    for GetSalesReportSvc<T> {
+                        type Response = super::GetSalesReportResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetSalesReportRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ReportingService>::get_sales_report(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetSalesReportSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/reporting.ReportingService/GetOrderMetrics" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetOrderMetricsSvc<T: ReportingService>(pub Arc<T>);
+                    impl<
+                        T: ReportingService,
+                    > tonic::server::UnaryService<super::GetOrderMetricsRequest>
+                    for GetOrderMetricsSvc<T> {
+                        type Response = super::GetOrderMetricsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetOrderMetricsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ReportingService>::get_order_metrics(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetOrderMetricsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                _ => {
+                    Box::pin(async move {
+                        let mut response = http::Response::new(empty_body());
+                        let headers = response.headers_mut();
+                        headers
+                            .insert(
+                                tonic::Status::GRPC_STATUS,
+                                (tonic::Code::Unimplemented as i32).into(),
+                            );
+                        headers
+                            .insert(
+                                http::header::CONTENT_TYPE,
+                                tonic::metadata::GRPC_CONTENT_TYPE,
+                            );
+                        Ok(response)
+                    })
+                }
+            }
+        }
+    }
+    impl<T> Clone for ReportingServiceServer<T> {
+        fn clone(&self) -> Self {
+            let inner = self.inner.clone();
+            Self {
+                inner,
+                accept_compression_encodings: self.accept_compression_encodings,
+                send_compression_encodings: self.send_compression_encodings,
+                max_decoding_message_size: self.max_decoding_message_size,
+                max_encoding_message_size: self.max_encoding_message_size,
+            }
+        }
+    }
+    /// Generated gRPC service name
+    pub const SERVICE_NAME: &str = "reporting.ReportingService";
+    impl<T> tonic::server::NamedService for ReportingServiceServer<T> {
+        const NAME: &'static str = SERVICE_NAME;
+    }
+}
@@ -0,0 +1,105 @@
+//! Checks behind the generated `validation` module. Rules are declared on
+//! proto fields with the (validate.*) options from proto/validate.proto.
+
+use prost::Message;
+use regex::Regex;
+use rust_decimal::Decimal;
+
+/// Implemented by the generated code for every message that carries rules.
+pub trait Validate {
+    /// Returns a `<field>: <reason>` message for the first violated rule.
+    fn validate(&self) -> Result<(), String>;
+}
+
+pub fn decode_and_validate<M: Message + Default + Validate>(message: &[u8]) -> Result<(), String> {
+    M::decode(message)
+        .map_err(|e| format!("invalid request message: {}", e))?
+        .validate()
+}
+
+pub fn min_len(field: &str, value: &str, min: u64) -> Result<(), String> {
+    if (value.chars().count() as u64) < min {
+        if min == 1 {
+            return Err(format!("{}: is required", field));
+        }
+        return Err(format!("{}: must be at least {} characters", field, min));
+    }
+    Ok(())
+}
+
+pub fn max_len(field: &str, value: &str, max: u64) -> Result<(), String> {
+    if value.chars().count() as u64 > max {
+        return Err(format!("{}: must be at most {} characters", field, max));
+    }
+    Ok(())
+}
+
+pub fn min_items(field: &str, len: usize, min: u64) -> Result<(), String> {
+    if (len as u64) < min {
+        return Err(format!("{}: must contain at least {} items", field, min));
+    }
+    Ok(())
+}
+
+pub fn max_items(field: &str, len: usize, max: u64) -> Result<(), String> {
+    if len as u64 > max {
+        return Err(format!("{}: must contain at most {} items", field, max));
+    }
+    Ok(())
+}
+
+pub fn pattern(field: &str, value: &str, pattern: &Regex) -> Result<(), String> {
+    if !pattern.is_match(value) {
+        return Err(format!("{}: has an invalid format", field));
+    }
+    Ok(())
+}
+
+pub fn gt(field: &str, value: f64, bound: f64) -> Result<(), String> {
+    if value.is_nan() || value <= bound {
+        return Err(format!("{}: must be greater than {}", field, bound));
+    }
+    Ok(())
+}
+
+pub fn gte(field: &str, value: f64, bound: f64) -> Result<(), String> {
+    if value.is_nan() || value < bound {
+        return Err(format!("{}: must be at least {}", field, bound));
+    }
+    Ok(())
+}
+
+fn parse_decimal(field: &str, value: &str) -> Result<Decimal, String> {
+    value
+        .parse::<Decimal>()
+        .map_err(|_| format!("{}: must be a decimal number", field))
+}
+
+// Empty decimal strings are unset fields; min_len makes them required
+
+pub fn decimal(field: &str, value: &str) -> Result<(), String> {
+    if value.is_empty() {
+        return Ok(());
+    }
+    parse_decimal(field, value).map(|_| ())
+}
+
+pub fn decimal_gt(field: &str, value: &str, bound: &str) -> Result<(), String> {
+    if value.is_empty() {
+        return Ok(());
+    }
+    if parse_decimal(field, value)? <= parse_decimal(field, bound)? {
+        return Err(format!("{}: must be greater than {}", field, bound));
+    }
+    Ok(())
+}
+
+pub fn decimal_gte(field: &str, value: &str, bound: &str) -> Result<(), String> {
+    if value.is_empty() {
+        return Ok(());
+    }
+    if parse_decimal(field, value)? < parse_decimal(field, bound)? {
+        return Err(format!("{}: must be at least {}", field, bound));
+    }
+    Ok(())
+}
@@ -0,0 +1,734 @@
+// This file is @generated by prost-build.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TrackingEvent {
+    #[prost(enumeration = "ShipmentStatus", tag = "1")]
+    pub status: i32,
+    #[prost(string, tag = "2")]
+    pub location: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub description: ::prost::alloc::string::String,
+    #[prost(int64, tag = "4")]
+    pub occurred_at: i64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Shipment {
+    #[prost(string, tag = "1")]
+    pub shipment_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub order_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub carrier: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub tracking_number: ::prost::alloc::string::String,
+    #[prost(enumeration = "ShipmentStatus", tag = "5")]
+    pub status: i32,
+    /// order items in the shipment
+    #[prost(string, repeated, tag = "6")]
+    pub item_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// oldest first
+    #[prost(message, repeated, tag = "7")]
+    pub events: ::prost::alloc::vec::Vec<TrackingEvent>,
+    #[prost(int64, tag = "8")]
+    pub created_at: i64,
+    #[prost(int64, tag = "9")]
+    pub updated_at: i64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateShipmentRequest {
+    #[prost(string, tag = "1")]
+    pub order_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub carrier: ::prost::alloc::string::String,
+    /// required unless SANDBOX_MODE is on, where the sandbox carrier assigns one
+    #[prost(string, tag = "3")]
+    pub tracking_number: ::prost::alloc::string::String,
+    /// empty ships every item not shipped yet, digital products excepted
+    #[prost(string, repeated, tag = "4")]
+    pub item_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateShipmentResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "3")]
+    pub shipment: ::core::option::Option<Shipment>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateTrackingRequest {
+    #[prost(string, tag = "1")]
+    pub shipment_id: ::prost::alloc::string::String,
+    #[prost(enumeration = "ShipmentStatus", tag = "2")]
+    pub status: i32,
+    #[prost(string, tag = "3")]
+    pub location: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub description: ::prost::alloc::string::String,
+    /// unix seconds; 0 means now
+    #[prost(int64, tag = "5")]
+    pub occurred_at: i64,
+    /// YYYY-MM-DD the carrier now expects delivery on, when it reports one;
+    /// becomes the order's delivery estimate
+    #[prost(string, tag = "6")]
+    pub estimated_delivery: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateTrackingResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "3")]
+    pub shipment: ::core::option::Option<Shipment>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetShipmentByOrderRequest {
+    #[prost(string, tag = "1")]
+    pub order_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetShipmentByOrderResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    /// oldest first, cancelled ones included
+    #[prost(message, repeated, tag = "3")]
+    pub shipments: ::prost::alloc::vec::Vec<Shipment>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ShippingItem {
+    #[prost(string, tag = "1")]
+    pub product_id: ::prost::alloc::string::String,
+    #[prost(int32, tag = "2")]
+    pub quantity: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QuoteShippingRequest {
+    #[prost(message, repeated, tag = "1")]
+    pub items: ::prost::alloc::vec::Vec<ShippingItem>,
+    /// ISO 3166 country or subdivision code of the shipping address, e.g. US-CA
+    #[prost(string, tag = "2")]
+    pub shipping_region: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ShippingOption {
+    /// code to pass as the order's shipping_method
+    #[prost(string, tag = "1")]
+    pub method: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+    /// decimal string
+    #[prost(string, tag = "3")]
+    pub fee: ::prost::alloc::string::String,
+    /// days delivery takes after the order is placed
+    #[prost(int32, tag = "4")]
+    pub min_transit_days: i32,
+    #[prost(int32, tag = "5")]
+    pub max_transit_days: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QuoteShippingResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    /// cheapest first; empty when nothing needs shipping
+    #[prost(message, repeated, tag = "3")]
+    pub options: ::prost::alloc::vec::Vec<ShippingOption>,
+    /// total weight of the physical items
+    #[prost(int32, tag = "4")]
+    pub weight_grams: i32,
+    /// false when every item is a digital product
+    #[prost(bool, tag = "5")]
+    pub requires_shipping: bool,
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ShipmentStatus {
+    LabelCreated = 0,
+    InTransit = 1,
+    OutForDelivery = 2,
+    Delivered = 3,
+    /// delayed or undeliverable; the carrier keeps trying
+    Exception = 4,
+    /// its items can ship again
+    Cancelled = 5,
+}
+impl ShipmentStatus {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::LabelCreated => "LABEL_CREATED",
+            Self::InTransit => "IN_TRANSIT",
+            Self::OutForDelivery => "OUT_FOR_DELIVERY",
+            Self::Delivered => "DELIVERED",
+            Self::Exception => "EXCEPTION",
+            Self::Cancelled => "CANCELLED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "LABEL_CREATED" => Some(Self::LabelCreated),
+            "IN_TRANSIT" => Some(Self::InTransit),
+            "OUT_FOR_DELIVERY" => Some(Self::OutForDelivery),
+            "DELIVERED" => Some(Self::Delivered),
+            "EXCEPTION" => Some(Self::Exception),
+            "CANCELLED" => Some(Self::Cancelled),
+            _ => None,
+        }
+    }
+}
+/// Generated client implementations.
+pub mod shipping_service_client {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value,
+    )]
+    use tonic::codegen::*;
+    use tonic::codegen::http::Uri;
+    /// ShippingService ships orders through carriers and tracks their shipments
+    #[derive(Debug, Clone)]
+    pub struct ShippingServiceClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl ShippingServiceClient<tonic::transport::Channel> {
+        /// Attempt to create a new client by connecting to a given endpoint.
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> ShippingServiceClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + std::marker::Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + std::marker::Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> ShippingServiceClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+            >>::Error: Into<StdError> + std::marker::Send + std::marker::Sync,
+        {
+            ShippingServiceClient::new(InterceptedService::new(inner, interceptor))
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_decoding_message_size(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_encoding_message_size(limit);
+            self
+        }
+        /// CreateShipment ships order items with a carrier. An order item ships in
+        /// at most one shipment that isn't cancelled; a repeated call returns it.
+        pub async fn create_shipment(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CreateShipmentRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CreateShipmentResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/shipping.ShippingService/CreateShipment",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("shipping.ShippingService", "CreateShipment"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// UpdateTracking records a carrier event; the order service moves the
+        /// order to SHIPPED, and to DELIVERED once all its shipments are delivered
+        pub async fn update_tracking(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdateTrackingRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UpdateTrackingResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/shipping.ShippingService/UpdateTracking",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("shipping.ShippingService", "UpdateTracking"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_shipment_by_order(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetShipmentByOrderRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetShipmentByOrderResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/shipping.ShippingService/GetShipmentByOrder",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("shipping.ShippingService", "GetShipmentByOrder"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// QuoteShipping prices the shipping methods available for items shipped
+        /// to a region, from the weight of the items and the rate tables
+        pub async fn quote_shipping(
+            &mut self,
+            request: impl tonic::IntoRequest<super::QuoteShippingRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::QuoteShippingResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/shipping.ShippingService/QuoteShipping",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("shipping.ShippingService", "QuoteShipping"));
+            self.inner.unary(req, path, codec).await
+        }
+    }
+}
+/// Generated server implementations.
+pub mod shipping_service_server {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value,
+    )]
+    use tonic::codegen::*;
+    /// Generated trait containing gRPC methods that should be implemented for use with ShippingServiceServer.
+    #[async_trait]
+    pub trait ShippingService: std::marker::Send + std::marker::Sync + 'static {
+        /// CreateShipment ships order items with a carrier. An order item ships in
+        /// at most one shipment that isn't cancelled; a repeated call returns it.
+        async fn create_shipment(
+            &self,
+            request: tonic::Request<super::CreateShipmentRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CreateShipmentResponse>,
+            tonic::Status,
+        >;
+        /// UpdateTracking records a carrier event; the order service moves the
+        /// order to SHIPPED, and to DELIVERED once all its shipments are delivered
+        async fn update_tracking(
+            &self,
+            request: tonic::Request<super::UpdateTrackingRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UpdateTrackingResponse>,
+            tonic::Status,
+        >;
+        async fn get_shipment_by_order(
+            &self,
+            request: tonic::Request<super::GetShipmentByOrderRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetShipmentByOrderResponse>,
+            tonic::Status,
+        >;
+        /// QuoteShipping prices the shipping methods available for items shipped
+        /// to a region, from the weight of the items and the rate tables
+        async fn quote_shipping(
+            &self,
+            request: tonic::Request<super::QuoteShippingRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::QuoteShippingResponse>,
+            tonic::Status,
+        >;
+    }
+    /// ShippingService ships orders through carriers and tracks their shipments
+    #[derive(Debug)]
+    pub struct ShippingServiceServer<T> {
+        inner: Arc<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+        max_decoding_message_size: Option<usize>,
+        max_encoding_message_size: Option<usize>,
+    }
+    impl<T> ShippingServiceServer<T> {
+        pub fn new(inner: T) -> Self {
+            Self::from_arc(Arc::new(inner))
+        }
+        pub fn from_arc(inner: Arc<T>) -> Self {
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+                max_decoding_message_size: None,
+                max_encoding_message_size: None,
+            }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        /// Enable decompressing requests with the given encoding.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.accept_compression_encodings.enable(encoding);
+            self
+        }
+        /// Compress responses with the given encoding, if the client supports it.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.send_compression_encodings.enable(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.max_decoding_message_size = Some(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.max_encoding_message_size = Some(limit);
+            self
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for ShippingServiceServer<T>
+    where
+        T: ShippingService,
+        B: Body + std::marker::Send + 'static,
+        B::Error: Into<StdError> + std::marker::Send + 'static,
+    {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = std::convert::Infallible;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(
+            &mut self,
+            _cx: &mut Context<'_>,
+        ) -> Poll<std::result::Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            match req.uri().path() {
+                "/shipping.ShippingService/CreateShipment" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateShipmentSvc<T: ShippingService>(pub Arc<T>);
+                    impl<
+                        T: ShippingService,
+                    > tonic::server::UnaryService<super::CreateShipmentRequest>
+                    for CreateShipmentSvc<T> {
+                        type Response = super::CreateShipmentResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateShipmentRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ShippingService>::create_shipment(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = CreateShipmentSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/shipping.ShippingService/UpdateTracking" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateTrackingSvc<T: ShippingService>(pub Arc<T>);
+                    impl<
+                        T: ShippingService,
+                    > tonic::server::UnaryService<super::UpdateTrackingRequest>
+                    for UpdateTrackingSvc<T> {
+                        type Response = super::UpdateTrackingResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateTrackingRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ShippingService>::update_tracking(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = UpdateTrackingSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/shipping.ShippingService/GetShipmentByOrder" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetShipmentByOrderSvc<T: ShippingService>(pub Arc<T>);
+                    impl<
+                        T: ShippingService,
+                    > tonic::server::UnaryService<super::GetShipmentByOrderRequest>
+                    for GetShipmentByOrderSvc<T> {
+                        type Response = super::GetShipmentByOrderResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetShipmentByOrderRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ShippingService>::get_shipment_by_order(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetShipmentByOrderSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/shipping.ShippingService/QuoteShipping" => {
+                    #[allow(non_camel_case_types)]
+                    struct QuoteShippingSvc<T: ShippingService>(pub Arc<T>);
+                    impl<
+                        T: ShippingService,
+                    > tonic::server::UnaryService<super::QuoteShippingRequest>
+                    for QuoteShippingSvc<T> {
+                        type Response = super::QuoteShippingResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::QuoteShippingRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ShippingService>::quote_shipping(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = QuoteShippingSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                _ => {
+                    Box::pin(async move {
+                        let mut response = http::Response::new(empty_body());
+                        let headers = response.headers_mut();
+                        headers
+                            .insert(
+                                tonic::Status::GRPC_STATUS,
+                                (tonic::Code::Unimplemented as i32).into(),
+                            );
+                        headers
+                            .insert(
+                                http::header::CONTENT_TYPE,
+                                tonic::metadata::GRPC_CONTENT_TYPE,
+                            );
+                        Ok(response)
+                    })
+                }
+            }
+        }
+    }
+    impl<T> Clone for ShippingServiceServer<T> {
+        fn clone(&self) -> Self {
+            let inner = self.inner.clone();
+            Self {
+                inner,
+                accept_compression_encodings: self.accept_compression_encodings,
+                send_compression_encodings: self.send_compression_encodings,
+                max_decoding_message_size: self.max_decoding_message_size,
+                max_encoding_message_size: self.max_encoding_message_size,
+            }
+        }
+    }
+    /// Generated gRPC service name
+    pub const SERVICE_NAME: &str = "shipping.ShippingService";
+    impl<T> tonic::server::NamedService for ShippingServiceServer<T> {
+        const NAME: &'static str = SERVICE_NAME;
+    }
+}
@@ -15,6 +15,12 @@ pub struct User {
     pub created_at: i64,
     #[prost(int64, tag = "7")]
     pub updated_at: i64,
+    /// whether the user proved they receive mail at email, see ConfirmEmail
+    #[prost(bool, tag = "8")]
+    pub email_verified: bool,
+    /// a guest checkout identity, which never signs in
+    #[prost(bool, tag = "9")]
+    pub guest: bool,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct RegisterRequest {
@@ -28,6 +34,9 @@ pub struct RegisterRequest {
     pub full_name: ::prost::alloc::string::String,
     #[prost(string, tag = "5")]
     pub phone_number: ::prost::alloc::string::String,
+    /// captcha_token is the client-side challenge response, required when captcha is enabled
+    #[prost(string, tag = "6")]
+    pub captcha_token: ::prost::alloc::string::String,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct RegisterResponse {
@@ -41,8 +50,9 @@ pub struct RegisterResponse {
 /// LoginRequest is used to authenticate a user with their credentials
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct LoginRequest {
+    /// identifier is either the username or the email address of the account
     #[prost(string, tag = "1")]
-    pub username: ::prost::alloc::string::String,
+    pub identifier: ::prost::alloc::string::String,
     #[prost(string, tag = "2")]
     pub password: ::prost::alloc::string::String,
 }
@@ -105,6 +115,77 @@ pub struct UpdateUserProfileResponse {
     #[prost(message, optional, tag = "3")]
     pub user: ::core::option::Option<User>,
 }
+/// UserSummary is the subset of User needed to enrich admin and reporting views
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserSummary {
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub username: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub email: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetUsersByIDsRequest {
+    #[prost(string, repeated, tag = "1")]
+    pub user_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetUsersByIDsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub users: ::prost::alloc::vec::Vec<UserSummary>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateAdminRequest {
+    #[prost(string, tag = "1")]
+    pub username: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub email: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub password: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateAdminResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub user_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetGuestIdentityRequest {
+    #[prost(string, tag = "1")]
+    pub email: ::prost::alloc::string::String,
+    #[prost(bool, tag = "2")]
+    pub create: bool,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetGuestIdentityResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    /// empty when there's none and create was false
+    #[prost(string, tag = "3")]
+    pub user_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConfirmEmailRequest {
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub email_token: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConfirmEmailResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "3")]
+    pub user: ::core::option::Option<User>,
+}
 /// Generated client implementations.
 pub mod user_service_client {
     #![allow(
@@ -116,7 +197,10 @@ pub mod user_service_client {
     )]
     use tonic::codegen::*;
     use tonic::codegen::http::Uri;
-    /// UserService provides user authentication and profile management functionality
+    /// UserService provides user authentication and profile management functionality.
+    /// Requests that can't be carried out fail with a gRPC status (NOT_FOUND,
+    /// INVALID_ARGUMENT, ...) carrying a google.rpc.ErrorInfo reason; success is
+    /// true on every OK response.
     #[derive(Debug, Clone)]
     pub struct UserServiceClient<T> {
         inner: tonic::client::Grpc<T>,
@@ -240,7 +324,8 @@ pub mod user_service_client {
             req.extensions_mut().insert(GrpcMethod::new("user.UserService", "Login"));
             self.inner.unary(req, path, codec).await
         }
-        /// Verify checks the validity of a given authentication token
+        /// Verify checks the validity of a given authentication token; platform
+        /// only
         pub async fn verify(
             &mut self,
             request: impl tonic::IntoRequest<super::VerifyRequest>,
@@ -259,7 +344,8 @@ pub mod user_service_client {
             req.extensions_mut().insert(GrpcMethod::new("user.UserService", "Verify"));
             self.inner.unary(req, path, codec).await
         }
-        /// GetUserProfile retrieves the profile information of a user by user ID
+        /// GetUserProfile retrieves the profile information of a user by user ID;
+        /// the user's own or the platform's
         pub async fn get_user_profile(
             &mut self,
             request: impl tonic::IntoRequest<super::GetUserProfileRequest>,
@@ -284,7 +370,8 @@ pub mod user_service_client {
                 .insert(GrpcMethod::new("user.UserService", "GetUserProfile"));
             self.inner.unary(req, path, codec).await
         }
-        /// UpdateUserProfile updates the profile information of a user
+        /// UpdateUserProfile updates the profile information of a user; only the
+        /// user themselves or the platform may
         pub async fn update_user_profile(
             &mut self,
             request: impl tonic::IntoRequest<super::UpdateUserProfileRequest>,
@@ -309,6 +396,111 @@ pub mod user_service_client {
                 .insert(GrpcMethod::new("user.UserService", "UpdateUserProfile"));
             self.inner.unary(req, path, codec).await
         }
+        /// GetUsersByIDs returns lightweight summaries for up to 500 users in one
+        /// call; platform only
+        pub async fn get_users_by_ids(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetUsersByIDsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetUsersByIDsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/user.UserService/GetUsersByIds",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "GetUsersByIds"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// CreateAdmin creates an account that signs in as an admin; platform only
+        pub async fn create_admin(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CreateAdminRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CreateAdminResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/user.UserService/CreateAdmin",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "CreateAdmin"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// GetGuestIdentity returns the guest identity guest checkout files orders
+        /// under for an email address, creating it on first use unless create is
+        /// false; platform only
+        pub async fn get_guest_identity(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetGuestIdentityRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetGuestIdentityResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/user.UserService/GetGuestIdentity",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "GetGuestIdentity"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// ConfirmEmail marks a user's email address verified, given an email
+        /// token for it, e.g. the one of a guest order's confirmation email; only
+        /// the user themselves or the platform may
+        pub async fn confirm_email(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ConfirmEmailRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ConfirmEmailResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/user.UserService/ConfirmEmail",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "ConfirmEmail"));
+            self.inner.unary(req, path, codec).await
+        }
     }
 }
 /// Generated server implementations.
@@ -337,12 +529,14 @@ pub mod user_service_server {
             &self,
             request: tonic::Request<super::LoginRequest>,
         ) -> std::result::Result<tonic::Response<super::LoginResponse>, tonic::Status>;
-        /// Verify checks the validity of a given authentication token
+        /// Verify checks the validity of a given authentication token; platform
+        /// only
         async fn verify(
             &self,
             request: tonic::Request<super::VerifyRequest>,
         ) -> std::result::Result<tonic::Response<super::VerifyResponse>, tonic::Status>;
-        /// GetUserProfile retrieves the profile information of a user by user ID
+        /// GetUserProfile retrieves the profile information of a user by user ID;
+        /// the user's own or the platform's
         async fn get_user_profile(
             &self,
             request: tonic::Request<super::GetUserProfileRequest>,
@@ -350,7 +544,8 @@ pub mod user_service_server {
             tonic::Response<super::GetUserProfileResponse>,
             tonic::Status,
         >;
-        /// UpdateUserProfile updates the profile information of a user
+        /// UpdateUserProfile updates the profile information of a user; only the
+        /// user themselves or the platform may
         async fn update_user_profile(
             &self,
             request: tonic::Request<super::UpdateUserProfileRequest>,
@@ -358,8 +553,48 @@ pub mod user_service_server {
             tonic::Response<super::UpdateUserProfileResponse>,
             tonic::Status,
         >;
+        /// GetUsersByIDs returns lightweight summaries for up to 500 users in one
+        /// call; platform only
+        async fn get_users_by_ids(
+            &self,
+            request: tonic::Request<super::GetUsersByIDsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetUsersByIDsResponse>,
+            tonic::Status,
+        >;
+        /// CreateAdmin creates an account that signs in as an admin; platform only
+        async fn create_admin(
+            &self,
+            request: tonic::Request<super::CreateAdminRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CreateAdminResponse>,
+            tonic::Status,
+        >;
+        /// GetGuestIdentity returns the guest identity guest checkout files orders
+        /// under for an email address, creating it on first use unless create is
+        /// false; platform only
+        async fn get_guest_identity(
+            &self,
+            request: tonic::Request<super::GetGuestIdentityRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetGuestIdentityResponse>,
+            tonic::Status,
+        >;
+        /// ConfirmEmail marks a user's email address verified, given an email
+        /// token for it, e.g. the one of a guest order's confirmation email; only
+        /// the user themselves or the platform may
+        async fn confirm_email(
+            &self,
+            request: tonic::Request<super::ConfirmEmailRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ConfirmEmailResponse>,
+            tonic::Status,
+        >;
     }
-    /// UserService provides user authentication and profile management functionality
+    /// UserService provides user authentication and profile management functionality.
+    /// Requests that can't be carried out fail with a gRPC status (NOT_FOUND,
+    /// INVALID_ARGUMENT, ...) carrying a google.rpc.ErrorInfo reason; success is
+    /// true on every OK response.
     #[derive(Debug)]
     pub struct UserServiceServer<T> {
         inner: Arc<T>,
@@ -660,6 +895,187 @@ pub mod user_service_server {
                     };
                     Box::pin(fut)
                 }
+                "/user.UserService/GetUsersByIds" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetUsersByIdsSvc<T: UserService>(pub Arc<T>);
+                    impl<
+                        T: UserService,
+                    > tonic::server::UnaryService<super::GetUsersByIDsRequest>
+                    for GetUsersByIdsSvc<T> {
+                        type Response = super::GetUsersByIDsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetUsersByIDsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::get_users_by_ids(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetUsersByIdsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/CreateAdmin" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateAdminSvc<T: UserService>(pub Arc<T>);
+                    impl<
+                        T: UserService,
+                    > tonic::server::UnaryService<super::CreateAdminRequest>
+                    for CreateAdminSvc<T> {
+                        type Response = super::CreateAdminResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateAdminRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::create_admin(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = CreateAdminSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/GetGuestIdentity" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetGuestIdentitySvc<T: UserService>(pub Arc<T>);
+                    impl<
+                        T: UserService,
+                    > tonic::server::UnaryService<super::GetGuestIdentityRequest>
+                    for GetGuestIdentitySvc<T> {
+                        type Response = super::GetGuestIdentityResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetGuestIdentityRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::get_guest_identity(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetGuestIdentitySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/ConfirmEmail" => {
+                    #[allow(non_camel_case_types)]
+                    struct ConfirmEmailSvc<T: UserService>(pub Arc<T>);
+                    impl<
+                        T: UserService,
+                    > tonic::server::UnaryService<super::ConfirmEmailRequest>
+                    for ConfirmEmailSvc<T> {
+                        type Response = super::ConfirmEmailResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ConfirmEmailRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::confirm_email(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ConfirmEmailSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => {
                     Box::pin(async move {
                         let mut response = http::Response::new(empty_body());
@@ -0,0 +1,759 @@
+// Generated by common/build.rs from the (validate.*) field options. Do not edit.
+
+use crate::rules::{self, Validate};
+
+impl Validate for crate::notification::GetDeliveryRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("delivery_id", &self.delivery_id, 1)?;
+        Ok(())
+    }
+}
+
+static PATTERN_0: std::sync::LazyLock<regex::Regex> =
+    std::sync::LazyLock::new(|| regex::Regex::new("^[^@\\s]+@[^@\\s]+$").unwrap());
+
+impl Validate for crate::notification::SendEmailRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::max_len("to", &self.to, 255)?;
+        rules::pattern("to", &self.to, &PATTERN_0)?;
+        rules::min_len("template", &self.template, 1)?;
+        Ok(())
+    }
+}
+
+static PATTERN_1: std::sync::LazyLock<regex::Regex> =
+    std::sync::LazyLock::new(|| regex::Regex::new("^\\+[1-9][0-9]{6,14}$").unwrap());
+
+impl Validate for crate::notification::SendSmsRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::pattern("to", &self.to, &PATTERN_1)?;
+        rules::min_len("template", &self.template, 1)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::order::ApproveOrderRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("order_id", &self.order_id, 1)?;
+        rules::max_len("reason", &self.reason, 500)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::order::CancelItem {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("item_id", &self.item_id, 1)?;
+        rules::gt("quantity", self.quantity as f64, 0.0)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::order::CancelOrderItemsRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("order_id", &self.order_id, 1)?;
+        rules::min_items("items", self.items.len(), 1)?;
+        rules::max_items("items", self.items.len(), 100)?;
+        for item in &self.items {
+            item.validate()?;
+        }
+        rules::max_len("reason", &self.reason, 500)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::order::CancelOrderRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::max_len("reason", &self.reason, 500)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::order::ClaimGuestOrdersRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("email_token", &self.email_token, 1)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::order::CreateDraftOrderRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("user_id", &self.user_id, 1)?;
+        rules::min_items("items", self.items.len(), 1)?;
+        rules::max_items("items", self.items.len(), 100)?;
+        rules::max_len("shipping_region", &self.shipping_region, 10)?;
+        rules::max_len("shipping_method", &self.shipping_method, 30)?;
+        rules::max_len("currency", &self.currency, 3)?;
+        rules::max_len("billing_region", &self.billing_region, 10)?;
+        rules::gte("valid_days", self.valid_days as f64, 0.0)?;
+        Ok(())
+    }
+}
+
+static PATTERN_2: std::sync::LazyLock<regex::Regex> =
+    std::sync::LazyLock::new(|| regex::Regex::new("^([^@\\s]+@[^@\\s]+)?$").unwrap());
+
+impl Validate for crate::order::CreateOrderRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_items("items", self.items.len(), 1)?;
+        rules::max_items("items", self.items.len(), 100)?;
+        rules::max_len("shipping_region", &self.shipping_region, 10)?;
+        rules::max_len("shipping_method", &self.shipping_method, 30)?;
+        rules::max_len("currency", &self.currency, 3)?;
+        rules::max_len("billing_region", &self.billing_region, 10)?;
+        rules::max_len("guest_email", &self.guest_email, 255)?;
+        rules::pattern("guest_email", &self.guest_email, &PATTERN_2)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::order::ExportOrdersRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::gte("created_after", self.created_after as f64, 0.0)?;
+        rules::gte("created_before", self.created_before as f64, 0.0)?;
+        rules::gte("page_size", self.page_size as f64, 0.0)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::order::FinalizeDraftOrderRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("draft_id", &self.draft_id, 1)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::order::GetOrderHistoryRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("order_id", &self.order_id, 1)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::order::GetOrdersByIDsRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_items("order_ids", self.order_ids.len(), 1)?;
+        rules::max_items("order_ids", self.order_ids.len(), 100)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::order::ListOrdersRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::gte("created_after", self.created_after as f64, 0.0)?;
+        rules::gte("created_before", self.created_before as f64, 0.0)?;
+        rules::decimal("min_amount", &self.min_amount)?;
+        rules::decimal_gte("min_amount", &self.min_amount, "0")?;
+        rules::decimal("max_amount", &self.max_amount)?;
+        rules::decimal_gte("max_amount", &self.max_amount, "0")?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::order::ListRefundsRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("order_id", &self.order_id, 1)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::order::ListRefundsResponse {
+    fn validate(&self) -> Result<(), String> {
+        for item in &self.refunds {
+            item.validate()?;
+        }
+        Ok(())
+    }
+}
+
+impl Validate for crate::order::MarkItemsShippedRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("order_id", &self.order_id, 1)?;
+        rules::min_items("item_ids", self.item_ids.len(), 1)?;
+        rules::max_items("item_ids", self.item_ids.len(), 100)?;
+        rules::max_len("tracking", &self.tracking, 255)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::order::RecordShipmentEventRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("order_id", &self.order_id, 1)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::order::Refund {
+    fn validate(&self) -> Result<(), String> {
+        for item in &self.items {
+            item.validate()?;
+        }
+        Ok(())
+    }
+}
+
+impl Validate for crate::order::RefundItem {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("item_id", &self.item_id, 1)?;
+        rules::gt("quantity", self.quantity as f64, 0.0)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::order::RefundOrderRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("order_id", &self.order_id, 1)?;
+        rules::max_items("items", self.items.len(), 100)?;
+        for item in &self.items {
+            item.validate()?;
+        }
+        rules::decimal("amount", &self.amount)?;
+        rules::decimal_gt("amount", &self.amount, "0")?;
+        rules::max_len("reason", &self.reason, 500)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::order::RefundOrderResponse {
+    fn validate(&self) -> Result<(), String> {
+        if let Some(value) = &self.refund {
+            value.validate()?;
+        }
+        Ok(())
+    }
+}
+
+impl Validate for crate::order::RejectOrderRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("order_id", &self.order_id, 1)?;
+        rules::max_len("reason", &self.reason, 500)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::order::UpdateDeliveryEstimateRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("order_id", &self.order_id, 1)?;
+        rules::min_len("from_date", &self.from_date, 1)?;
+        rules::min_len("to_date", &self.to_date, 1)?;
+        rules::max_len("reason", &self.reason, 500)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::order::UpdateOrderRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::max_len("reason", &self.reason, 500)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::payment::CapturePaymentRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("payment_id", &self.payment_id, 1)?;
+        rules::min_len("payment_method", &self.payment_method, 1)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::payment::CreatePaymentIntentRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("order_id", &self.order_id, 1)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::payment::RefundPaymentRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("payment_id", &self.payment_id, 1)?;
+        rules::decimal("amount", &self.amount)?;
+        rules::decimal_gt("amount", &self.amount, "0")?;
+        rules::max_len("reason", &self.reason, 500)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::product::AddProductRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("name", &self.name, 1)?;
+        rules::max_len("name", &self.name, 255)?;
+        rules::min_len("price", &self.price, 1)?;
+        rules::decimal("price", &self.price)?;
+        rules::decimal_gte("price", &self.price, "0")?;
+        rules::gte("stock_quantity", self.stock_quantity as f64, 0.0)?;
+        rules::max_len("category", &self.category, 100)?;
+        rules::gte("warranty_months", self.warranty_months as f64, 0.0)?;
+        rules::max_len("tax_class", &self.tax_class, 30)?;
+        rules::gte("weight_grams", self.weight_grams as f64, 0.0)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::product::CheckAvailabilityBatchRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_items("product_ids", self.product_ids.len(), 1)?;
+        rules::max_items("product_ids", self.product_ids.len(), 100)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::product::ConfirmReservationRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("reservation_id", &self.reservation_id, 1)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::product::CreateSellerRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("name", &self.name, 1)?;
+        rules::max_len("name", &self.name, 255)?;
+        rules::min_len("user_id", &self.user_id, 1)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::product::ListProductsBySellerRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("seller_id", &self.seller_id, 1)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::product::ListProductsRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::decimal("min_price", &self.min_price)?;
+        rules::decimal_gte("min_price", &self.min_price, "0")?;
+        rules::decimal("max_price", &self.max_price)?;
+        rules::decimal_gte("max_price", &self.max_price, "0")?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::product::ReleaseReservationRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("reservation_id", &self.reservation_id, 1)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::product::ReserveStockRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("reservation_id", &self.reservation_id, 1)?;
+        rules::min_items("lines", self.lines.len(), 1)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::product::ReturnStockRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("return_id", &self.return_id, 1)?;
+        rules::min_items("lines", self.lines.len(), 1)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::product::ScheduleSaleRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("product_id", &self.product_id, 1)?;
+        rules::min_len("sale_price", &self.sale_price, 1)?;
+        rules::decimal("sale_price", &self.sale_price)?;
+        rules::decimal_gt("sale_price", &self.sale_price, "0")?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::product::UpdateProductRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("product_id", &self.product_id, 1)?;
+        rules::max_len("name", &self.name, 255)?;
+        rules::decimal("price", &self.price)?;
+        rules::decimal_gte("price", &self.price, "0")?;
+        rules::gte("stock_quantity", self.stock_quantity as f64, 0.0)?;
+        rules::max_len("category", &self.category, 100)?;
+        rules::gte("warranty_months", self.warranty_months as f64, 0.0)?;
+        rules::max_len("tax_class", &self.tax_class, 30)?;
+        rules::gte("weight_grams", self.weight_grams as f64, 0.0)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::search::SearchRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::max_len("query", &self.query, 200)?;
+        rules::max_items("categories", self.categories.len(), 50)?;
+        rules::max_items("tags", self.tags.len(), 50)?;
+        rules::decimal("min_price", &self.min_price)?;
+        rules::decimal_gte("min_price", &self.min_price, "0")?;
+        rules::decimal("max_price", &self.max_price)?;
+        rules::decimal_gte("max_price", &self.max_price, "0")?;
+        rules::max_items("facets", self.facets.len(), 4)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::settings::UpdateSettingsRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_items("fields", self.fields.len(), 1)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::shipping::CreateShipmentRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("order_id", &self.order_id, 1)?;
+        rules::min_len("carrier", &self.carrier, 1)?;
+        rules::max_len("carrier", &self.carrier, 50)?;
+        rules::max_len("tracking_number", &self.tracking_number, 100)?;
+        rules::max_items("item_ids", self.item_ids.len(), 100)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::shipping::GetShipmentByOrderRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("order_id", &self.order_id, 1)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::shipping::QuoteShippingRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_items("items", self.items.len(), 1)?;
+        rules::max_items("items", self.items.len(), 100)?;
+        for item in &self.items {
+            item.validate()?;
+        }
+        rules::min_len("shipping_region", &self.shipping_region, 1)?;
+        rules::max_len("shipping_region", &self.shipping_region, 10)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::shipping::ShippingItem {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("product_id", &self.product_id, 1)?;
+        rules::gt("quantity", self.quantity as f64, 0.0)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::shipping::UpdateTrackingRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("shipment_id", &self.shipment_id, 1)?;
+        rules::max_len("location", &self.location, 255)?;
+        rules::max_len("description", &self.description, 500)?;
+        rules::max_len("estimated_delivery", &self.estimated_delivery, 10)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::tax::DeleteTaxRateRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("region", &self.region, 1)?;
+        rules::min_len("tax_class", &self.tax_class, 1)?;
+        Ok(())
+    }
+}
+
+static PATTERN_3: std::sync::LazyLock<regex::Regex> =
+    std::sync::LazyLock::new(|| regex::Regex::new("^[A-Za-z]{2}(-[A-Za-z0-9]{1,3})?$").unwrap());
+
+impl Validate for crate::tax::SetTaxRateRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::pattern("region", &self.region, &PATTERN_3)?;
+        rules::min_len("tax_class", &self.tax_class, 1)?;
+        rules::max_len("tax_class", &self.tax_class, 30)?;
+        rules::min_len("rate", &self.rate, 1)?;
+        rules::decimal("rate", &self.rate)?;
+        rules::decimal_gte("rate", &self.rate, "0")?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::user::ConfirmEmailRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("email_token", &self.email_token, 1)?;
+        Ok(())
+    }
+}
+
+static PATTERN_4: std::sync::LazyLock<regex::Regex> =
+    std::sync::LazyLock::new(|| regex::Regex::new("^[^@\\s]+@[^@\\s]+$").unwrap());
+
+impl Validate for crate::user::CreateAdminRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("username", &self.username, 1)?;
+        rules::max_len("username", &self.username, 255)?;
+        rules::max_len("email", &self.email, 255)?;
+        rules::pattern("email", &self.email, &PATTERN_4)?;
+        rules::min_len("password", &self.password, 12)?;
+        Ok(())
+    }
+}
+
+static PATTERN_5: std::sync::LazyLock<regex::Regex> =
+    std::sync::LazyLock::new(|| regex::Regex::new("^[^@\\s]+@[^@\\s]+$").unwrap());
+
+impl Validate for crate::user::GetGuestIdentityRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::max_len("email", &self.email, 255)?;
+        rules::pattern("email", &self.email, &PATTERN_5)?;
+        Ok(())
+    }
+}
+
+static PATTERN_6: std::sync::LazyLock<regex::Regex> =
+    std::sync::LazyLock::new(|| regex::Regex::new("^[^@\\s]+@[^@\\s]+$").unwrap());
+
+impl Validate for crate::user::RegisterRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("username", &self.username, 1)?;
+        rules::max_len("username", &self.username, 255)?;
+        rules::max_len("email", &self.email, 255)?;
+        rules::pattern("email", &self.email, &PATTERN_6)?;
+        rules::min_len("password", &self.password, 1)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::webhook::DeleteWebhookRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("webhook_id", &self.webhook_id, 1)?;
+        Ok(())
+    }
+}
+
+impl Validate for crate::webhook::RedeliverWebhookRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("delivery_id", &self.delivery_id, 1)?;
+        Ok(())
+    }
+}
+
+static PATTERN_7: std::sync::LazyLock<regex::Regex> =
+    std::sync::LazyLock::new(|| regex::Regex::new("^https?://").unwrap());
+
+impl Validate for crate::webhook::RegisterWebhookRequest {
+    fn validate(&self) -> Result<(), String> {
+        rules::min_len("url", &self.url, 1)?;
+        rules::max_len("url", &self.url, 2048)?;
+        rules::pattern("url", &self.url, &PATTERN_7)?;
+        rules::max_items("event_types", self.event_types.len(), 10)?;
+        rules::max_len("secret", &self.secret, 255)?;
+        Ok(())
+    }
+}
+
+/// Whether the request message of `path` carries rules.
+pub fn has_rules(path: &str) -> bool {
+    matches!(
+        path,
+        "/product.ProductService/AddProduct"
+            | "/product.ProductService/UpdateProduct"
+            | "/product.ProductService/ListProducts"
+            | "/product.ProductService/CheckAvailabilityBatch"
+            | "/product.ProductService/ScheduleSale"
+            | "/product.ProductService/CreateSeller"
+            | "/product.ProductService/ListProductsBySeller"
+            | "/product.ProductService/ReserveStock"
+            | "/product.ProductService/ConfirmReservation"
+            | "/product.ProductService/ReleaseReservation"
+            | "/product.ProductService/ReturnStock"
+            | "/user.UserService/Register"
+            | "/user.UserService/CreateAdmin"
+            | "/user.UserService/GetGuestIdentity"
+            | "/user.UserService/ConfirmEmail"
+            | "/order.OrderService/CreateOrder"
+            | "/order.OrderService/CreateDraftOrder"
+            | "/order.OrderService/FinalizeDraftOrder"
+            | "/order.OrderService/UpdateOrder"
+            | "/order.OrderService/CancelOrder"
+            | "/order.OrderService/CancelOrderItems"
+            | "/order.OrderService/GetOrdersByIds"
+            | "/order.OrderService/ListOrders"
+            | "/order.OrderService/ExportOrders"
+            | "/order.OrderService/RefundOrder"
+            | "/order.OrderService/ListRefunds"
+            | "/order.OrderService/RecordShipmentEvent"
+            | "/order.OrderService/MarkItemsShipped"
+            | "/order.OrderService/UpdateDeliveryEstimate"
+            | "/order.OrderService/ClaimGuestOrders"
+            | "/order.OrderService/ApproveOrder"
+            | "/order.OrderService/RejectOrder"
+            | "/order.OrderService/GetOrderHistory"
+            | "/notification.NotificationService/SendEmail"
+            | "/notification.NotificationService/SendSms"
+            | "/notification.NotificationService/GetDelivery"
+            | "/settings.SettingsService/UpdateSettings"
+            | "/payment.PaymentService/CreatePaymentIntent"
+            | "/payment.PaymentService/CapturePayment"
+            | "/payment.PaymentService/RefundPayment"
+            | "/shipping.ShippingService/CreateShipment"
+            | "/shipping.ShippingService/UpdateTracking"
+            | "/shipping.ShippingService/GetShipmentByOrder"
+            | "/shipping.ShippingService/QuoteShipping"
+            | "/tax.TaxService/SetTaxRate"
+            | "/tax.TaxService/DeleteTaxRate"
+            | "/webhook.WebhookService/RegisterWebhook"
+            | "/webhook.WebhookService/DeleteWebhook"
+            | "/webhook.WebhookService/RedeliverWebhook"
+            | "/search.SearchService/Search"
+    )
+}
+
+/// Decodes and validates the request message of `path`; `None` when the method has no rules.
+pub fn validate_request(path: &str, message: &[u8]) -> Option<Result<(), String>> {
+    match path {
+        "/product.ProductService/AddProduct" => {
+            Some(rules::decode_and_validate::<crate::product::AddProductRequest>(message))
+        }
+        "/product.ProductService/UpdateProduct" => {
+            Some(rules::decode_and_validate::<crate::product::UpdateProductRequest>(message))
+        }
+        "/product.ProductService/ListProducts" => {
+            Some(rules::decode_and_validate::<crate::product::ListProductsRequest>(message))
+        }
+        "/product.ProductService/CheckAvailabilityBatch" => {
+            Some(rules::decode_and_validate::<crate::product::CheckAvailabilityBatchRequest>(message))
+        }
+        "/product.ProductService/ScheduleSale" => {
+            Some(rules::decode_and_validate::<crate::product::ScheduleSaleRequest>(message))
+        }
+        "/product.ProductService/CreateSeller" => {
+            Some(rules::decode_and_validate::<crate::product::CreateSellerRequest>(message))
+        }
+        "/product.ProductService/ListProductsBySeller" => {
+            Some(rules::decode_and_validate::<crate::product::ListProductsBySellerRequest>(message))
+        }
+        "/product.ProductService/ReserveStock" => {
+            Some(rules::decode_and_validate::<crate::product::ReserveStockRequest>(message))
+        }
+        "/product.ProductService/ConfirmReservation" => {
+            Some(rules::decode_and_validate::<crate::product::ConfirmReservationRequest>(message))
+        }
+        "/product.ProductService/ReleaseReservation" => {
+            Some(rules::decode_and_validate::<crate::product::ReleaseReservationRequest>(message))
+        }
+        "/product.ProductService/ReturnStock" => {
+            Some(rules::decode_and_validate::<crate::product::ReturnStockRequest>(message))
+        }
+        "/user.UserService/Register" => {
+            Some(rules::decode_and_validate::<crate::user::RegisterRequest>(message))
+        }
+        "/user.UserService/CreateAdmin" => {
+            Some(rules::decode_and_validate::<crate::user::CreateAdminRequest>(message))
+        }
+        "/user.UserService/GetGuestIdentity" => {
+            Some(rules::decode_and_validate::<crate::user::GetGuestIdentityRequest>(message))
+        }
+        "/user.UserService/ConfirmEmail" => {
+            Some(rules::decode_and_validate::<crate::user::ConfirmEmailRequest>(message))
+        }
+        "/order.OrderService/CreateOrder" => {
+            Some(rules::decode_and_validate::<crate::order::CreateOrderRequest>(message))
+        }
+        "/order.OrderService/CreateDraftOrder" => {
+            Some(rules::decode_and_validate::<crate::order::CreateDraftOrderRequest>(message))
+        }
+        "/order.OrderService/FinalizeDraftOrder" => {
+            Some(rules::decode_and_validate::<crate::order::FinalizeDraftOrderRequest>(message))
+        }
+        "/order.OrderService/UpdateOrder" => {
+            Some(rules::decode_and_validate::<crate::order::UpdateOrderRequest>(message))
+        }
+        "/order.OrderService/CancelOrder" => {
+            Some(rules::decode_and_validate::<crate::order::CancelOrderRequest>(message))
+        }
+        "/order.OrderService/CancelOrderItems" => {
+            Some(rules::decode_and_validate::<crate::order::CancelOrderItemsRequest>(message))
+        }
+        "/order.OrderService/GetOrdersByIds" => {
+            Some(rules::decode_and_validate::<crate::order::GetOrdersByIDsRequest>(message))
+        }
+        "/order.OrderService/ListOrders" => {
+            Some(rules::decode_and_validate::<crate::order::ListOrdersRequest>(message))
+        }
+        "/order.OrderService/ExportOrders" => {
+            Some(rules::decode_and_validate::<crate::order::ExportOrdersRequest>(message))
+        }
+        "/order.OrderService/RefundOrder" => {
+            Some(rules::decode_and_validate::<crate::order::RefundOrderRequest>(message))
+        }
+        "/order.OrderService/ListRefunds" => {
+            Some(rules::decode_and_validate::<crate::order::ListRefundsRequest>(message))
+        }
+        "/order.OrderService/RecordShipmentEvent" => {
+            Some(rules::decode_and_validate::<crate::order::RecordShipmentEventRequest>(message))
+        }
+        "/order.OrderService/MarkItemsShipped" => {
+            Some(rules::decode_and_validate::<crate::order::MarkItemsShippedRequest>(message))
+        }
+        "/order.OrderService/UpdateDeliveryEstimate" => {
+            Some(rules::decode_and_validate::<crate::order::UpdateDeliveryEstimateRequest>(message))
+        }
+        "/order.OrderService/ClaimGuestOrders" => {
+            Some(rules::decode_and_validate::<crate::order::ClaimGuestOrdersRequest>(message))
+        }
+        "/order.OrderService/ApproveOrder" => {
+            Some(rules::decode_and_validate::<crate::order::ApproveOrderRequest>(message))
+        }
+        "/order.OrderService/RejectOrder" => {
+            Some(rules::decode_and_validate::<crate::order::RejectOrderRequest>(message))
+        }
+        "/order.OrderService/GetOrderHistory" => {
+            Some(rules::decode_and_validate::<crate::order::GetOrderHistoryRequest>(message))
+        }
+        "/notification.NotificationService/SendEmail" => {
+            Some(rules::decode_and_validate::<crate::notification::SendEmailRequest>(message))
+        }
+        "/notification.NotificationService/SendSms" => {
+            Some(rules::decode_and_validate::<crate::notification::SendSmsRequest>(message))
+        }
+        "/notification.NotificationService/GetDelivery" => {
+            Some(rules::decode_and_validate::<crate::notification::GetDeliveryRequest>(message))
+        }
+        "/settings.SettingsService/UpdateSettings" => {
+            Some(rules::decode_and_validate::<crate::settings::UpdateSettingsRequest>(message))
+        }
+        "/payment.PaymentService/CreatePaymentIntent" => {
+            Some(rules::decode_and_validate::<crate::payment::CreatePaymentIntentRequest>(message))
+        }
+        "/payment.PaymentService/CapturePayment" => {
+            Some(rules::decode_and_validate::<crate::payment::CapturePaymentRequest>(message))
+        }
+        "/payment.PaymentService/RefundPayment" => {
+            Some(rules::decode_and_validate::<crate::payment::RefundPaymentRequest>(message))
+        }
+        "/shipping.ShippingService/CreateShipment" => {
+            Some(rules::decode_and_validate::<crate::shipping::CreateShipmentRequest>(message))
+        }
+        "/shipping.ShippingService/UpdateTracking" => {
+            Some(rules::decode_and_validate::<crate::shipping::UpdateTrackingRequest>(message))
+        }
+        "/shipping.ShippingService/GetShipmentByOrder" => {
+            Some(rules::decode_and_validate::<crate::shipping::GetShipmentByOrderRequest>(message))
+        }
+        "/shipping.ShippingService/QuoteShipping" => {
+            Some(rules::decode_and_validate::<crate::shipping::QuoteShippingRequest>(message))
+        }
+        "/tax.TaxService/SetTaxRate" => {
+            Some(rules::decode_and_validate::<crate::tax::SetTaxRateRequest>(message))
+        }
+        "/tax.TaxService/DeleteTaxRate" => {
+            Some(rules::decode_and_validate::<crate::tax::DeleteTaxRateRequest>(message))
+        }
+        "/webhook.WebhookService/RegisterWebhook" => {
+            Some(rules::decode_and_validate::<crate::webhook::RegisterWebhookRequest>(message))
+        }
+        "/webhook.WebhookService/DeleteWebhook" => {
+            Some(rules::decode_and_validate::<crate::webhook::DeleteWebhookRequest>(message))
+        }
+        "/webhook.WebhookService/RedeliverWebhook" => {
+            Some(rules::decode_and_validate::<crate::webhook::RedeliverWebhookRequest>(message))
+        }
+        "/search.SearchService/Search" => {
+            Some(rules::decode_and_validate::<crate::search::SearchRequest>(message))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,84 @@
+use common::auth::ServiceCredentials;
+use proto::search::{
+    ReindexRequest, SearchRequest, SearchSort, search_service_client::SearchServiceClient,
+};
+use tonic::transport::Channel;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Runs the operator flows too, so calls are made as the platform
+    let channel = Channel::from_static("http://127.0.0.1:50057")
+        .connect()
+        .await?;
+    let mut client =
+        SearchServiceClient::with_interceptor(channel, ServiceCredentials("search-client"));
+
+    println!("Connected to Search Service");
+    println!("===========================\n");
+
+    // Test 1: Rebuild the index from the products table
+    println!("1. Testing Reindex");
+    let reindex_response = client.reindex(ReindexRequest {}).await?;
+    let reindex_result = reindex_response.into_inner();
+    println!("Reindex Response:");
+    println!("  Success: {}", reindex_result.success);
+    println!("  Message: {}", reindex_result.message);
+    println!("  Indexed: {}\n", reindex_result.indexed);
+
+    // Test 2: Search with a typo, counting the matches by category
+    println!("2. Testing Search");
+    let search_response = client
+        .search(SearchRequest {
+            query: "laptp".to_string(),
+            categories: vec![],
+            tags: vec![],
+            min_price: String::new(),
+            max_price: "2000".to_string(),
+            in_stock_only: true,
+            seller_id: String::new(),
+            facets: vec!["category".to_string(), "tags".to_string()],
+            sort: SearchSort::Relevance as i32,
+            page: 1,
+            page_size: 10,
+        })
+        .await?;
+    let search_result = search_response.into_inner();
+    println!("Search Response:");
+    println!("  Message: {}", search_result.message);
+    for hit in &search_result.hits {
+        println!("  - {} ({}): ${}", hit.name, hit.product_id, hit.price);
+    }
+    for facet in &search_result.facets {
+        println!("  Facet {}:", facet.name);
+        for value in &facet.values {
+            println!("    {}: {}", value.value, value.count);
+        }
+    }
+    println!();
+
+    // Test 3: Browse by filters alone, cheapest first
+    println!("3. Testing Filtered Browse");
+    let browse_response = client
+        .search(SearchRequest {
+            query: String::new(),
+            categories: vec!["Electronics".to_string()],
+            tags: vec![],
+            min_price: "10".to_string(),
+            max_price: String::new(),
+            in_stock_only: false,
+            seller_id: String::new(),
+            facets: vec!["in_stock".to_string()],
+            sort: SearchSort::PriceLowToHigh as i32,
+            page: 1,
+            page_size: 5,
+        })
+        .await?;
+    let browse_result = browse_response.into_inner();
+    println!("Browse Response:");
+    println!("  Total Hits: {}", browse_result.total_hits);
+    for hit in &browse_result.hits {
+        println!("  - {}: ${}", hit.name, hit.price);
+    }
+
+    Ok(())
+}
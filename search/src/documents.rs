@@ -0,0 +1,106 @@
+//! Products as they're indexed, read from the products table. Only
+//! published products that aren't deleted are indexed, as ListProducts
+//! shows them.
+
+use common::pricing::EFFECTIVE_PRICE;
+use proto::search::SearchHit;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgExecutor, PgPool};
+
+/// A product in the index.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ProductDocument {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub category: String,
+    pub tags: Vec<String>,
+    /// What it sells for when indexed, sales included
+    pub price: f64,
+    pub regular_price: f64,
+    pub in_stock: bool,
+    pub rating_average: f64,
+    pub rating_count: i32,
+    pub seller_id: String,
+    /// Unix seconds
+    pub created_at: i64,
+    /// When the document was written, unix milliseconds; a reindex drops
+    /// the documents it didn't write
+    #[sqlx(skip)]
+    pub synced_at: i64,
+}
+
+impl ProductDocument {
+    pub fn to_proto(&self) -> SearchHit {
+        SearchHit {
+            product_id: self.id.clone(),
+            name: self.name.clone(),
+            description: self.description.clone(),
+            category: self.category.clone(),
+            tags: self.tags.clone(),
+            price: format!("{:.2}", self.price),
+            regular_price: format!("{:.2}", self.regular_price),
+            in_stock: self.in_stock,
+            rating_average: self.rating_average,
+            rating_count: self.rating_count,
+            seller_id: self.seller_id.clone(),
+            created_at: self.created_at,
+        }
+    }
+}
+
+fn select_documents() -> String {
+    format!(
+        "SELECT id, name, COALESCE(description, '') AS description,
+                COALESCE(category, '') AS category,
+                ARRAY(SELECT t.name FROM product_tags pt JOIN tags t ON t.id = pt.tag_id
+                      WHERE pt.product_id = products.id ORDER BY t.name) AS tags,
+                {}::float8 AS price, price::float8 AS regular_price,
+                stock_quantity > 0 AS in_stock, rating_average::float8 AS rating_average,
+                rating_count, COALESCE(seller_id, '') AS seller_id,
+                EXTRACT(EPOCH FROM created_at)::int8 AS created_at
+         FROM products
+         WHERE deleted_at IS NULL AND status = 'PUBLISHED'",
+        EFFECTIVE_PRICE
+    )
+}
+
+/// The documents of the products among `ids` that are indexed, stamped
+/// `synced_at`.
+pub async fn load(
+    db: &PgPool,
+    ids: &[String],
+    synced_at: i64,
+) -> Result<Vec<ProductDocument>, sqlx::Error> {
+    let mut documents =
+        sqlx::query_as::<_, ProductDocument>(&format!("{} AND id = ANY($1)", select_documents()))
+            .bind(ids)
+            .fetch_all(db)
+            .await?;
+    for document in &mut documents {
+        document.synced_at = synced_at;
+    }
+    Ok(documents)
+}
+
+/// Up to `limit` documents of the products after `after` in id order,
+/// stamped `synced_at`, for walking the whole table.
+pub async fn load_page<'e>(
+    executor: impl PgExecutor<'e>,
+    after: &str,
+    limit: i64,
+    synced_at: i64,
+) -> Result<Vec<ProductDocument>, sqlx::Error> {
+    let mut documents = sqlx::query_as::<_, ProductDocument>(&format!(
+        "{} AND id > $1 ORDER BY id LIMIT $2",
+        select_documents()
+    ))
+    .bind(after)
+    .bind(limit)
+    .fetch_all(executor)
+    .await?;
+    for document in &mut documents {
+        document.synced_at = synced_at;
+    }
+    Ok(documents)
+}
@@ -0,0 +1,529 @@
+//! Search engines the product index lives in, picked by `SEARCH_ENGINE`
+//! (`meilisearch`, the default, or `elasticsearch`) at `SEARCH_URL`, in the
+//! index `SEARCH_INDEX` (`products` by default). Both tolerate typos:
+//! Meilisearch out of the box, Elasticsearch through fuzzy matching.
+
+use crate::documents::ProductDocument;
+use anyhow::{Context, Result, anyhow, bail};
+use proto::search::SearchSort;
+use reqwest::{Method, RequestBuilder};
+use serde_json::{Value, json};
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Attributes matches can be counted by.
+pub const FACETS: [&str; 4] = ["category", "tags", "in_stock", "seller_id"];
+
+/// Values counted per facet.
+const MAX_FACET_VALUES: usize = 100;
+
+/// A search, in terms every engine understands.
+#[derive(Debug, Clone, Copy)]
+pub struct SearchQuery<'a> {
+    /// Empty matches every document
+    pub text: &'a str,
+    /// Any of them; empty for any category
+    pub categories: &'a [String],
+    /// All of them
+    pub tags: &'a [String],
+    pub min_price: Option<f64>,
+    pub max_price: Option<f64>,
+    pub in_stock_only: bool,
+    pub seller_id: Option<&'a str>,
+    /// Names from `FACETS`
+    pub facets: &'a [String],
+    pub sort: SearchSort,
+    pub offset: i64,
+    pub limit: i64,
+}
+
+#[derive(Debug, Default)]
+pub struct SearchResults {
+    pub hits: Vec<ProductDocument>,
+    pub total_hits: i64,
+    /// Value counts of each facet asked for, most frequent first
+    pub facets: Vec<(String, Vec<(String, i64)>)>,
+}
+
+/// The index of a search engine.
+#[tonic::async_trait]
+pub trait SearchIndex: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Creates the index and its settings when missing.
+    async fn configure(&self) -> Result<()>;
+
+    /// Adds the documents, replacing those with the same ids.
+    async fn upsert(&self, documents: &[ProductDocument]) -> Result<()>;
+
+    /// Removes the documents with these ids; missing ones are skipped.
+    async fn delete(&self, ids: &[String]) -> Result<()>;
+
+    /// Removes the documents last written before `synced_at`.
+    async fn delete_synced_before(&self, synced_at: i64) -> Result<()>;
+
+    async fn search(&self, query: &SearchQuery<'_>) -> Result<SearchResults>;
+}
+
+/// The engine `SEARCH_ENGINE` names.
+pub fn from_env() -> Result<Arc<dyn SearchIndex>> {
+    let index = env::var("SEARCH_INDEX").unwrap_or_else(|_| "products".to_string());
+    let api_key = env::var("SEARCH_API_KEY")
+        .ok()
+        .filter(|key| !key.is_empty());
+    let url = env::var("SEARCH_URL").ok().filter(|url| !url.is_empty());
+
+    let engine = env::var("SEARCH_ENGINE").unwrap_or_default();
+    let search_index: Arc<dyn SearchIndex> = match engine.as_str() {
+        "" | "meilisearch" => Arc::new(Meilisearch::new(
+            url.as_deref().unwrap_or("http://127.0.0.1:7700"),
+            index,
+            api_key,
+        )),
+        "elasticsearch" => Arc::new(Elasticsearch::new(
+            url.as_deref().unwrap_or("http://127.0.0.1:9200"),
+            index,
+            api_key,
+        )),
+        other => bail!(
+            "Unknown SEARCH_ENGINE {:?}, expected meilisearch or elasticsearch",
+            other
+        ),
+    };
+    Ok(search_index)
+}
+
+fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .expect("Failed to build search engine HTTP client")
+}
+
+/// Sends `request`, failing with the engine's answer unless it succeeded.
+async fn send(engine: &str, request: RequestBuilder) -> Result<Value> {
+    let response = request
+        .send()
+        .await
+        .with_context(|| format!("{} unreachable", engine))?;
+    let status = response.status();
+    let body = response.text().await?;
+    if !status.is_success() {
+        return Err(anyhow!("{} answered {}: {}", engine, status, body));
+    }
+    if body.is_empty() {
+        return Ok(Value::Null);
+    }
+    serde_json::from_str(&body).with_context(|| format!("Malformed {} response", engine))
+}
+
+/// Meilisearch (https://www.meilisearch.com), whose writes are queued as
+/// tasks and applied in order.
+pub struct Meilisearch {
+    url: String,
+    index: String,
+    api_key: Option<String>,
+    client: reqwest::Client,
+}
+
+impl Meilisearch {
+    pub fn new(url: &str, index: String, api_key: Option<String>) -> Self {
+        Self {
+            url: url.trim_end_matches('/').to_string(),
+            index,
+            api_key,
+            client: http_client(),
+        }
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let request = self.client.request(
+            method,
+            format!("{}/indexes/{}{}", self.url, self.index, path),
+        );
+        match &self.api_key {
+            Some(key) => request.bearer_auth(key),
+            None => request,
+        }
+    }
+
+    /// A string as a filter expression literal.
+    fn quote(value: &str) -> String {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+
+    fn filters(query: &SearchQuery<'_>) -> Vec<Value> {
+        let mut filters = Vec::new();
+        // An inner array is a disjunction
+        if !query.categories.is_empty() {
+            filters.push(json!(
+                query
+                    .categories
+                    .iter()
+                    .map(|category| format!("category = {}", Self::quote(category)))
+                    .collect::<Vec<_>>()
+            ));
+        }
+        for tag in query.tags {
+            filters.push(json!(format!("tags = {}", Self::quote(tag))));
+        }
+        if let Some(min_price) = query.min_price {
+            filters.push(json!(format!("price >= {}", min_price)));
+        }
+        if let Some(max_price) = query.max_price {
+            filters.push(json!(format!("price <= {}", max_price)));
+        }
+        if query.in_stock_only {
+            filters.push(json!("in_stock = true"));
+        }
+        if let Some(seller_id) = query.seller_id {
+            filters.push(json!(format!("seller_id = {}", Self::quote(seller_id))));
+        }
+        filters
+    }
+}
+
+#[tonic::async_trait]
+impl SearchIndex for Meilisearch {
+    fn name(&self) -> &'static str {
+        "meilisearch"
+    }
+
+    async fn configure(&self) -> Result<()> {
+        // Updating the settings of a missing index creates it
+        send(
+            self.name(),
+            self.request(Method::PATCH, "/settings").json(&json!({
+                "searchableAttributes": ["name", "tags", "category", "description"],
+                "filterableAttributes": ["category", "tags", "price", "in_stock", "seller_id", "synced_at"],
+                "sortableAttributes": ["price", "created_at", "rating_average"],
+                "typoTolerance": { "enabled": true },
+                "faceting": { "maxValuesPerFacet": MAX_FACET_VALUES },
+            })),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn upsert(&self, documents: &[ProductDocument]) -> Result<()> {
+        if documents.is_empty() {
+            return Ok(());
+        }
+        send(
+            self.name(),
+            self.request(Method::POST, "/documents?primaryKey=id")
+                .json(documents),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, ids: &[String]) -> Result<()> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        send(
+            self.name(),
+            self.request(Method::POST, "/documents/delete-batch")
+                .json(ids),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn delete_synced_before(&self, synced_at: i64) -> Result<()> {
+        send(
+            self.name(),
+            self.request(Method::POST, "/documents/delete")
+                .json(&json!({ "filter": format!("synced_at < {}", synced_at) })),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn search(&self, query: &SearchQuery<'_>) -> Result<SearchResults> {
+        let sort: &[&str] = match query.sort {
+            SearchSort::Relevance => &[],
+            SearchSort::PriceLowToHigh => &["price:asc"],
+            SearchSort::PriceHighToLow => &["price:desc"],
+            SearchSort::Newest => &["created_at:desc"],
+            SearchSort::TopRated => &["rating_average:desc"],
+        };
+        let response = send(
+            self.name(),
+            self.request(Method::POST, "/search").json(&json!({
+                "q": query.text,
+                "filter": Self::filters(query),
+                "facets": query.facets,
+                "sort": sort,
+                "offset": query.offset,
+                "limit": query.limit,
+            })),
+        )
+        .await?;
+
+        let hits = serde_json::from_value(response["hits"].clone())
+            .context("Malformed meilisearch hits")?;
+        let facets = query
+            .facets
+            .iter()
+            .map(|facet| {
+                let mut values: Vec<(String, i64)> = response["facetDistribution"][facet]
+                    .as_object()
+                    .map(|counts| {
+                        counts
+                            .iter()
+                            .map(|(value, count)| (value.clone(), count.as_i64().unwrap_or(0)))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                values.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+                (facet.clone(), values)
+            })
+            .collect();
+
+        Ok(SearchResults {
+            hits,
+            total_hits: response["estimatedTotalHits"].as_i64().unwrap_or(0),
+            facets,
+        })
+    }
+}
+
+/// Elasticsearch (https://www.elastic.co/elasticsearch), or OpenSearch.
+/// `SEARCH_API_KEY` is sent as an `ApiKey` authorization.
+pub struct Elasticsearch {
+    url: String,
+    index: String,
+    api_key: Option<String>,
+    client: reqwest::Client,
+}
+
+impl Elasticsearch {
+    pub fn new(url: &str, index: String, api_key: Option<String>) -> Self {
+        Self {
+            url: url.trim_end_matches('/').to_string(),
+            index,
+            api_key,
+            client: http_client(),
+        }
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let request = self.client.request(method, format!("{}{}", self.url, path));
+        match &self.api_key {
+            Some(key) => request.header("authorization", format!("ApiKey {}", key)),
+            None => request,
+        }
+    }
+
+    /// Sends a bulk request, failing when any of its actions failed.
+    async fn bulk(&self, lines: Vec<Value>) -> Result<()> {
+        let mut body = String::new();
+        for line in lines {
+            body.push_str(&line.to_string());
+            body.push('\n');
+        }
+        let response = send(
+            self.name(),
+            self.request(Method::POST, "/_bulk")
+                .header("content-type", "application/x-ndjson")
+                .body(body),
+        )
+        .await?;
+
+        if response["errors"].as_bool() == Some(true) {
+            let failure = response["items"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|item| item.as_object()?.values().next()?.get("error"))
+                .next()
+                .cloned()
+                .unwrap_or_default();
+            return Err(anyhow!("elasticsearch bulk request failed: {}", failure));
+        }
+        Ok(())
+    }
+
+    fn filters(query: &SearchQuery<'_>) -> Vec<Value> {
+        let mut filters = Vec::new();
+        if !query.categories.is_empty() {
+            filters.push(json!({ "terms": { "category": query.categories } }));
+        }
+        for tag in query.tags {
+            filters.push(json!({ "term": { "tags": tag } }));
+        }
+        if let Some(min_price) = query.min_price {
+            filters.push(json!({ "range": { "price": { "gte": min_price } } }));
+        }
+        if let Some(max_price) = query.max_price {
+            filters.push(json!({ "range": { "price": { "lte": max_price } } }));
+        }
+        if query.in_stock_only {
+            filters.push(json!({ "term": { "in_stock": true } }));
+        }
+        if let Some(seller_id) = query.seller_id {
+            filters.push(json!({ "term": { "seller_id": seller_id } }));
+        }
+        filters
+    }
+}
+
+#[tonic::async_trait]
+impl SearchIndex for Elasticsearch {
+    fn name(&self) -> &'static str {
+        "elasticsearch"
+    }
+
+    async fn configure(&self) -> Result<()> {
+        let result = send(
+            self.name(),
+            self.request(Method::PUT, &format!("/{}", self.index))
+                .json(&json!({
+                    "mappings": {
+                        "properties": {
+                            "id": { "type": "keyword" },
+                            "name": { "type": "text" },
+                            "description": { "type": "text" },
+                            "category": { "type": "keyword" },
+                            "tags": { "type": "keyword" },
+                            "price": { "type": "double" },
+                            "regular_price": { "type": "double" },
+                            "in_stock": { "type": "boolean" },
+                            "rating_average": { "type": "double" },
+                            "rating_count": { "type": "integer" },
+                            "seller_id": { "type": "keyword" },
+                            "created_at": { "type": "long" },
+                            "synced_at": { "type": "long" },
+                        }
+                    }
+                })),
+        )
+        .await;
+
+        match result {
+            Err(e) if e.to_string().contains("resource_already_exists_exception") => Ok(()),
+            result => result.map(|_| ()),
+        }
+    }
+
+    async fn upsert(&self, documents: &[ProductDocument]) -> Result<()> {
+        if documents.is_empty() {
+            return Ok(());
+        }
+        let mut lines = Vec::with_capacity(documents.len() * 2);
+        for document in documents {
+            lines.push(json!({ "index": { "_index": self.index, "_id": document.id } }));
+            lines.push(serde_json::to_value(document)?);
+        }
+        self.bulk(lines).await
+    }
+
+    async fn delete(&self, ids: &[String]) -> Result<()> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        // Deleting a missing document isn't an error in a bulk request
+        let lines = ids
+            .iter()
+            .map(|id| json!({ "delete": { "_index": self.index, "_id": id } }))
+            .collect();
+        self.bulk(lines).await
+    }
+
+    async fn delete_synced_before(&self, synced_at: i64) -> Result<()> {
+        send(
+            self.name(),
+            self.request(
+                Method::POST,
+                &format!("/{}/_delete_by_query?conflicts=proceed", self.index),
+            )
+            .json(&json!({ "query": { "range": { "synced_at": { "lt": synced_at } } } })),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn search(&self, query: &SearchQuery<'_>) -> Result<SearchResults> {
+        let text = if query.text.trim().is_empty() {
+            json!({ "match_all": {} })
+        } else {
+            json!({
+                "multi_match": {
+                    "query": query.text,
+                    "fields": ["name^3", "tags^2", "category^2", "description"],
+                    "fuzziness": "AUTO",
+                }
+            })
+        };
+        let sort = match query.sort {
+            SearchSort::Relevance => json!(["_score"]),
+            SearchSort::PriceLowToHigh => json!([{ "price": "asc" }]),
+            SearchSort::PriceHighToLow => json!([{ "price": "desc" }]),
+            SearchSort::Newest => json!([{ "created_at": "desc" }]),
+            SearchSort::TopRated => json!([{ "rating_average": "desc" }]),
+        };
+        let aggregations: serde_json::Map<String, Value> = query
+            .facets
+            .iter()
+            .map(|facet| {
+                (
+                    facet.clone(),
+                    json!({ "terms": { "field": facet, "size": MAX_FACET_VALUES } }),
+                )
+            })
+            .collect();
+
+        let response = send(
+            self.name(),
+            self.request(Method::POST, &format!("/{}/_search", self.index))
+                .json(&json!({
+                    "from": query.offset,
+                    "size": query.limit,
+                    "track_total_hits": true,
+                    "query": { "bool": { "must": [text], "filter": Self::filters(query) } },
+                    "sort": sort,
+                    "aggs": aggregations,
+                })),
+        )
+        .await?;
+
+        let hits = response["hits"]["hits"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|hit| serde_json::from_value(hit["_source"].clone()))
+            .collect::<Result<Vec<ProductDocument>, _>>()
+            .context("Malformed elasticsearch hits")?;
+        let facets = query
+            .facets
+            .iter()
+            .map(|facet| {
+                let values = response["aggregations"][facet]["buckets"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(|bucket| {
+                        // Booleans are bucketed as 1 and 0, named in key_as_string
+                        let value = match (&bucket["key_as_string"], &bucket["key"]) {
+                            (Value::String(name), _) | (_, Value::String(name)) => name.clone(),
+                            (_, key) => key.to_string(),
+                        };
+                        (value, bucket["doc_count"].as_i64().unwrap_or(0))
+                    })
+                    .collect();
+                (facet.clone(), values)
+            })
+            .collect();
+
+        Ok(SearchResults {
+            hits,
+            total_hits: response["hits"]["total"]["value"].as_i64().unwrap_or(0),
+            facets,
+        })
+    }
+}
@@ -0,0 +1,78 @@
+mod documents;
+mod index;
+mod search;
+mod sync;
+
+use anyhow::Result;
+use common::events;
+use common::metrics::{self, Metrics};
+use common::server::ServerBuilder;
+use common::slo::{SloConfig, SloServiceImpl, SloTracker};
+use common::telemetry;
+use proto::search::search_service_server::SearchServiceServer;
+use proto::slo::slo_service_server::SloServiceServer;
+use search::SearchServiceImpl;
+use sqlx::postgres::PgPoolOptions;
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+use sync::IndexSync;
+use tracing::{info, warn};
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenvy::dotenv().ok();
+
+    telemetry::init("search", env!("CARGO_PKG_VERSION"))?;
+
+    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+
+    // Create database connection pool
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&database_url)
+        .await?;
+
+    info!("Connected to database");
+
+    let addr = "0.0.0.0:50057".parse()?;
+
+    let search_index = index::from_env()?;
+    info!("Searching with {}", search_index.name());
+    let index_sync = Arc::new(IndexSync::new(pool.clone(), search_index.clone()));
+
+    // The index is rebuilt from Postgres at startup and then on a schedule;
+    // in between, product and stock events keep it current
+    let reindex_interval_secs: u64 = env::var("SEARCH_REINDEX_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(3600);
+    index_sync
+        .clone()
+        .spawn_reindex(Duration::from_secs(reindex_interval_secs));
+
+    match events::consumer_from_env(sync::GROUP, &sync::TOPICS).await? {
+        Some(consumer) => events::spawn_consumer(consumer, index_sync.clone()),
+        None => warn!("EVENT_BUS is unset: the index only catches up when it's rebuilt"),
+    }
+
+    let search_service = SearchServiceImpl::new(search_index, index_sync);
+    let slo_tracker = SloTracker::new("search", SloConfig::from_env()?);
+    let metrics = Metrics::new("search");
+    let metrics_addr = env::var("METRICS_ADDR")
+        .unwrap_or_else(|_| "0.0.0.0:9107".to_string())
+        .parse()?;
+    metrics::spawn(metrics_addr, metrics.clone());
+
+    info!("Search service listening on {}", addr);
+
+    ServerBuilder::new(metrics, slo_tracker.clone())
+        .build()?
+        .add_service(SearchServiceServer::new(search_service))
+        .add_service(SloServiceServer::new(SloServiceImpl::new(slo_tracker)))
+        .serve(addr)
+        .await?;
+
+    telemetry::shutdown();
+    Ok(())
+}
@@ -0,0 +1,121 @@
+use crate::index::{FACETS, SearchIndex, SearchQuery};
+use crate::sync::IndexSync;
+use common::auth::Caller;
+use common::error;
+use common::pagination::PageRequest;
+use proto::search::{
+    Facet, FacetValue, ReindexRequest, ReindexResponse, SearchRequest, SearchResponse, SearchSort,
+    search_service_server::SearchService,
+};
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use tracing::error;
+
+pub struct SearchServiceImpl {
+    index: Arc<dyn SearchIndex>,
+    sync: Arc<IndexSync>,
+}
+
+impl SearchServiceImpl {
+    pub fn new(index: Arc<dyn SearchIndex>, sync: Arc<IndexSync>) -> Self {
+        Self { index, sync }
+    }
+}
+
+/// A decimal bound, `None` when empty; the validation layer checked its form.
+fn price_bound(value: &str) -> Option<f64> {
+    value.parse().ok()
+}
+
+#[tonic::async_trait]
+impl SearchService for SearchServiceImpl {
+    async fn search(
+        &self,
+        request: Request<SearchRequest>,
+    ) -> Result<Response<SearchResponse>, Status> {
+        let req = request.into_inner();
+
+        // Field rules are checked by the validation layer (see search.proto)
+        if let Some(facet) = req.facets.iter().find(|f| !FACETS.contains(&f.as_str())) {
+            return Err(error::invalid_argument(
+                "UNKNOWN_FACET",
+                format!(
+                    "Unknown facet {:?}, expected one of {}",
+                    facet,
+                    FACETS.join(", ")
+                ),
+            ));
+        }
+        let min_price = price_bound(&req.min_price);
+        let max_price = price_bound(&req.max_price);
+        if let (Some(min_price), Some(max_price)) = (min_price, max_price)
+            && min_price > max_price
+        {
+            return Err(error::invalid_argument(
+                "INVALID_PRICE_RANGE",
+                "Minimum price cannot exceed maximum price",
+            ));
+        }
+
+        let page = PageRequest::new(req.page, req.page_size);
+        let query = SearchQuery {
+            text: req.query.trim(),
+            categories: &req.categories,
+            tags: &req.tags,
+            min_price,
+            max_price,
+            in_stock_only: req.in_stock_only,
+            seller_id: Some(req.seller_id.as_str()).filter(|id| !id.is_empty()),
+            facets: &req.facets,
+            sort: SearchSort::try_from(req.sort).unwrap_or(SearchSort::Relevance),
+            offset: page.offset(),
+            limit: page.limit(),
+        };
+
+        let results = self.index.search(&query).await.map_err(|e| {
+            error!(engine = self.index.name(), "Search failed: {:#}", e);
+            Status::unavailable("Search is unavailable")
+        })?;
+
+        Ok(Response::new(SearchResponse {
+            success: true,
+            message: format!("Found {} products", results.total_hits),
+            hits: results.hits.iter().map(|hit| hit.to_proto()).collect(),
+            total_hits: results.total_hits,
+            facets: results
+                .facets
+                .into_iter()
+                .map(|(name, values)| Facet {
+                    name,
+                    values: values
+                        .into_iter()
+                        .map(|(value, count)| FacetValue { value, count })
+                        .collect(),
+                })
+                .collect(),
+        }))
+    }
+
+    async fn reindex(
+        &self,
+        request: Request<ReindexRequest>,
+    ) -> Result<Response<ReindexResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+
+        match self.sync.reindex().await {
+            Ok(Some(indexed)) => Ok(Response::new(ReindexResponse {
+                success: true,
+                message: format!("Reindexed {} products", indexed),
+                indexed: indexed as i64,
+            })),
+            Ok(None) => Err(error::failed_precondition(
+                "REINDEX_RUNNING",
+                "The index is being rebuilt already",
+            )),
+            Err(e) => {
+                error!(engine = self.index.name(), "Reindex failed: {:#}", e);
+                Err(Status::unavailable("Reindex failed"))
+            }
+        }
+    }
+}
@@ -0,0 +1,126 @@
+//! Keeps the index in step with Postgres. Events only say which product
+//! changed: its document is read from the products table, so a late or
+//! repeated event can't put stale data in the index, and a product no longer
+//! published is taken out. A full reindex on a schedule catches what events
+//! can't: sales starting or ending, new ratings, and events missed while no
+//! instance was consuming.
+
+use crate::documents;
+use crate::index::SearchIndex;
+use anyhow::Result;
+use chrono::Utc;
+use common::events::{self, Delivery, Event, EventHandler};
+use proto::events::{ProductChanged, StockChanged};
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// Consumer group of the search service's instances.
+pub const GROUP: &str = "search";
+
+/// Topics the service consumes.
+pub const TOPICS: [&str; 2] = [ProductChanged::TOPIC, StockChanged::TOPIC];
+
+/// Products read per page while reindexing.
+const REINDEX_PAGE_SIZE: i64 = 500;
+
+/// Advisory lock held while reindexing, so instances take turns.
+const REINDEX_LOCK: i64 = 0x7365_6172_6368;
+
+pub struct IndexSync {
+    db: PgPool,
+    index: Arc<dyn SearchIndex>,
+}
+
+impl IndexSync {
+    pub fn new(db: PgPool, index: Arc<dyn SearchIndex>) -> Self {
+        Self { db, index }
+    }
+
+    /// Writes the current state of the products to the index.
+    pub async fn sync(&self, product_ids: &[String]) -> Result<()> {
+        let documents =
+            documents::load(&self.db, product_ids, Utc::now().timestamp_millis()).await?;
+        let removed: Vec<String> = product_ids
+            .iter()
+            .filter(|id| !documents.iter().any(|document| &document.id == *id))
+            .cloned()
+            .collect();
+
+        self.index.upsert(&documents).await?;
+        self.index.delete(&removed).await?;
+        Ok(())
+    }
+
+    /// Rebuilds the index from the products table and returns how many
+    /// products it holds; `None` when another instance is rebuilding it.
+    pub async fn reindex(&self) -> Result<Option<u64>> {
+        let mut tx = self.db.begin().await?;
+        let (locked,): (bool,) = sqlx::query_as("SELECT pg_try_advisory_xact_lock($1)")
+            .bind(REINDEX_LOCK)
+            .fetch_one(&mut *tx)
+            .await?;
+        if !locked {
+            return Ok(None);
+        }
+
+        self.index.configure().await?;
+
+        let started = Utc::now().timestamp_millis();
+        let mut after = String::new();
+        let mut indexed = 0;
+        loop {
+            let page = documents::load_page(&mut *tx, &after, REINDEX_PAGE_SIZE, started).await?;
+            let Some(last) = page.last() else {
+                break;
+            };
+            after = last.id.clone();
+            self.index.upsert(&page).await?;
+            indexed += page.len() as u64;
+            if (page.len() as i64) < REINDEX_PAGE_SIZE {
+                break;
+            }
+        }
+
+        // Documents the walk didn't rewrite, nor events since it started,
+        // are of products no longer published
+        self.index.delete_synced_before(started).await?;
+        tx.commit().await?;
+
+        Ok(Some(indexed))
+    }
+
+    /// Spawns a background task that rebuilds the index now and every
+    /// `interval`.
+    pub fn spawn_reindex(self: Arc<Self>, interval: Duration) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match self.reindex().await {
+                    Ok(Some(indexed)) => {
+                        info!(engine = self.index.name(), "Reindexed {} products", indexed)
+                    }
+                    Ok(None) => debug!("Another instance is reindexing"),
+                    Err(e) => warn!("Reindex failed: {:#}", e),
+                }
+            }
+        });
+    }
+}
+
+#[tonic::async_trait]
+impl EventHandler for IndexSync {
+    async fn handle(&self, delivery: &Delivery) -> Result<()> {
+        let envelope = &delivery.envelope;
+        let product_id = if let Some(event) = events::decode::<ProductChanged>(envelope)? {
+            event.product_id
+        } else if let Some(event) = events::decode::<StockChanged>(envelope)? {
+            event.product_id
+        } else {
+            return Ok(());
+        };
+        self.sync(&[product_id]).await
+    }
+}
@@ -0,0 +1,54 @@
+use common::sandbox;
+use proto::shipping::ShipmentStatus;
+use std::sync::Arc;
+
+/// A carrier shipments go out with.
+pub trait Carrier: Send + Sync {
+    /// Tracking number of a new shipment, given the one the caller passed
+    /// (empty when none); `None` when the shipment can't get one.
+    fn tracking_number(&self, shipment_id: &str, requested: &str) -> Option<String>;
+
+    /// Tracking events the carrier reports as soon as a shipment is created.
+    fn instant_events(&self) -> &'static [ShipmentStatus];
+}
+
+/// The carrier for this environment; sandbox mode always uses the sandbox one.
+pub fn from_env() -> Arc<dyn Carrier> {
+    if sandbox::enabled() {
+        Arc::new(SandboxCarrier)
+    } else {
+        Arc::new(LabelCarrier)
+    }
+}
+
+/// Carriers whose labels are bought outside this service: the tracking
+/// number comes from the label, and events come in through UpdateTracking.
+pub struct LabelCarrier;
+
+impl Carrier for LabelCarrier {
+    fn tracking_number(&self, _shipment_id: &str, requested: &str) -> Option<String> {
+        (!requested.is_empty()).then(|| requested.to_string())
+    }
+
+    fn instant_events(&self) -> &'static [ShipmentStatus] {
+        &[]
+    }
+}
+
+/// Deterministic fake: assigns tracking numbers from the shipment id and
+/// delivers instantly, so sandbox checkouts run through to DELIVERED.
+pub struct SandboxCarrier;
+
+impl Carrier for SandboxCarrier {
+    fn tracking_number(&self, shipment_id: &str, requested: &str) -> Option<String> {
+        Some(if requested.is_empty() {
+            format!("SANDBOX-{}", shipment_id)
+        } else {
+            requested.to_string()
+        })
+    }
+
+    fn instant_events(&self) -> &'static [ShipmentStatus] {
+        &[ShipmentStatus::InTransit, ShipmentStatus::Delivered]
+    }
+}
@@ -0,0 +1,111 @@
+use common::auth::ServiceCredentials;
+use proto::shipping::{
+    CreateShipmentRequest, GetShipmentByOrderRequest, QuoteShippingRequest, ShipmentStatus,
+    ShippingItem, UpdateTrackingRequest, shipping_service_client::ShippingServiceClient,
+};
+use tonic::transport::Channel;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Shipments are created and tracked by the platform
+    let channel = Channel::from_static("http://127.0.0.1:50056")
+        .connect()
+        .await?;
+    let mut client =
+        ShippingServiceClient::with_interceptor(channel, ServiceCredentials("shipping-client"));
+
+    println!("Connected to Shipping Service");
+    println!("=============================\n");
+
+    // Note: shipments reference an existing confirmed order
+    let order_id = "test-order-id".to_string();
+    let product_id = "test-product-id".to_string();
+    println!("Order ID: {}\n", order_id);
+
+    // Test 0: Price the shipping methods before checkout
+    println!("0. Testing Quote Shipping");
+    let quote_response = client
+        .quote_shipping(QuoteShippingRequest {
+            items: vec![ShippingItem {
+                product_id: product_id.clone(),
+                quantity: 2,
+            }],
+            shipping_region: "US-CA".to_string(),
+        })
+        .await?;
+    let quote_result = quote_response.into_inner();
+    println!("Quote Shipping Response:");
+    println!("  Success: {}", quote_result.success);
+    println!("  Message: {}", quote_result.message);
+    println!("  Weight: {} g", quote_result.weight_grams);
+    for option in &quote_result.options {
+        println!("  - {} ({}): ${}", option.name, option.method, option.fee);
+    }
+    println!();
+
+    // Test 1: Ship every item of the order
+    println!("1. Testing Create Shipment");
+    let create_response = client
+        .create_shipment(CreateShipmentRequest {
+            order_id: order_id.clone(),
+            carrier: "ups".to_string(),
+            tracking_number: "1Z999AA10123456784".to_string(),
+            item_ids: vec![],
+        })
+        .await?;
+    let create_result = create_response.into_inner();
+    println!("Create Shipment Response:");
+    println!("  Success: {}", create_result.success);
+    println!("  Message: {}", create_result.message);
+
+    let Some(shipment) = create_result.shipment else {
+        return Ok(());
+    };
+    println!("  Shipment ID: {}", shipment.shipment_id);
+    println!(
+        "  Carrier: {} ({})\n",
+        shipment.carrier, shipment.tracking_number
+    );
+
+    // Test 2: Report it in transit, then delivered
+    for status in [ShipmentStatus::InTransit, ShipmentStatus::Delivered] {
+        println!("2. Testing Update Tracking ({:?})", status);
+        let update_response = client
+            .update_tracking(UpdateTrackingRequest {
+                shipment_id: shipment.shipment_id.clone(),
+                status: status as i32,
+                location: "Louisville, KY".to_string(),
+                description: String::new(),
+                occurred_at: 0,
+                estimated_delivery: String::new(),
+            })
+            .await?;
+        let update_result = update_response.into_inner();
+        println!("Update Tracking Response:");
+        println!("  Success: {}", update_result.success);
+        println!("  Message: {}\n", update_result.message);
+    }
+
+    // Test 3: Get the order's shipments
+    println!("3. Testing Get Shipment By Order");
+    let get_response = client
+        .get_shipment_by_order(GetShipmentByOrderRequest {
+            order_id: order_id.clone(),
+        })
+        .await?;
+    let get_result = get_response.into_inner();
+    println!("Get Shipment By Order Response:");
+    println!("  Success: {}", get_result.success);
+    println!("  Message: {}", get_result.message);
+    for shipment in &get_result.shipments {
+        println!(
+            "  - {} {}: {:?}, {} event(s)",
+            shipment.carrier,
+            shipment.tracking_number,
+            ShipmentStatus::try_from(shipment.status),
+            shipment.events.len()
+        );
+    }
+
+    Ok(())
+}
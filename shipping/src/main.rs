@@ -0,0 +1,62 @@
+mod carrier;
+mod rates;
+mod shipping;
+
+use anyhow::Result;
+use common::client::ServiceEndpoint;
+use common::metrics::{self, Metrics};
+use common::telemetry;
+use common::tls;
+use common::slo::{SloConfig, SloServiceImpl, SloTracker};
+use common::server::ServerBuilder;
+use proto::shipping::shipping_service_server::ShippingServiceServer;
+use proto::slo::slo_service_server::SloServiceServer;
+use shipping::ShippingServiceImpl;
+use sqlx::postgres::PgPoolOptions;
+use std::env;
+use tracing::{info, warn};
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenvy::dotenv().ok();
+
+    telemetry::init("shipping", env!("CARGO_PKG_VERSION"))?;
+
+    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let order_service =
+        ServiceEndpoint::from_env("order", "ORDER_SERVICE", "http://127.0.0.1:50053")
+            .with_tls(tls::client_config()?);
+
+    // Create database connection pool
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&database_url)
+        .await?;
+
+    info!("Connected to database");
+
+    let addr = "0.0.0.0:50056".parse()?;
+    if common::sandbox::enabled() {
+        warn!("Sandbox mode: shipments are delivered instantly by the sandbox carrier");
+    }
+
+    let shipping_service = ShippingServiceImpl::new(pool, carrier::from_env(), order_service);
+    let slo_tracker = SloTracker::new("shipping", SloConfig::from_env()?);
+    let metrics = Metrics::new("shipping");
+    let metrics_addr = env::var("METRICS_ADDR")
+        .unwrap_or_else(|_| "0.0.0.0:9106".to_string())
+        .parse()?;
+    metrics::spawn(metrics_addr, metrics.clone());
+
+    info!("Shipping service listening on {}", addr);
+
+    ServerBuilder::new(metrics, slo_tracker.clone())
+        .build()?
+        .add_service(ShippingServiceServer::new(shipping_service))
+        .add_service(SloServiceServer::new(SloServiceImpl::new(slo_tracker)))
+        .serve(addr)
+        .await?;
+
+    telemetry::shutdown();
+    Ok(())
+}
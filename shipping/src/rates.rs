@@ -0,0 +1,112 @@
+use common::error::AppError;
+use proto::shipping::{ShippingItem, ShippingOption};
+use sqlx::PgPool;
+use sqlx::types::Decimal;
+use std::collections::HashMap;
+use tonic::Status;
+
+/// Region of rates that apply wherever no more specific rate exists.
+const ANY_REGION: &str = "*";
+
+/// Shipping options for a set of items.
+pub struct Quote {
+    pub options: Vec<ShippingOption>,
+    pub weight_grams: i32,
+    pub requires_shipping: bool,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct DbProductWeight {
+    id: String,
+    product_type: String,
+    weight_grams: i32,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct DbOption {
+    code: String,
+    name: String,
+    price: Decimal,
+    min_transit_days: i32,
+    max_transit_days: i32,
+}
+
+/// Prices every active method for shipping `items` to `region`. Each method
+/// takes the rate, and transit time, of its most specific region
+/// (subdivision, then country, then `*`) and, within it, the smallest weight
+/// bracket the items fit in; methods with no such rate aren't offered. `Ok(Err(..))` names an unknown
+/// product.
+pub async fn quote(
+    db: &PgPool,
+    items: &[ShippingItem],
+    region: &str,
+) -> Result<Result<Quote, String>, Status> {
+    let product_ids: Vec<String> = items.iter().map(|i| i.product_id.clone()).collect();
+    let products: HashMap<String, DbProductWeight> = sqlx::query_as::<_, DbProductWeight>(
+        "SELECT id, product_type, weight_grams FROM products WHERE id = ANY($1)",
+    )
+    .bind(&product_ids)
+    .fetch_all(db)
+    .await
+    .map_err(AppError::from)?
+    .into_iter()
+    .map(|p| (p.id.clone(), p))
+    .collect();
+
+    // Digital products weigh nothing and aren't shipped
+    let mut weight_grams: i64 = 0;
+    let mut requires_shipping = false;
+    for item in items {
+        let Some(product) = products.get(&item.product_id) else {
+            return Ok(Err(format!("Product {} not found", item.product_id)));
+        };
+        if product.product_type != "DIGITAL" {
+            requires_shipping = true;
+            weight_grams += i64::from(product.weight_grams) * i64::from(item.quantity);
+        }
+    }
+    let weight_grams = i32::try_from(weight_grams).unwrap_or(i32::MAX);
+
+    if !requires_shipping {
+        return Ok(Ok(Quote {
+            options: vec![],
+            weight_grams,
+            requires_shipping,
+        }));
+    }
+
+    let region = region.trim().to_ascii_uppercase();
+    let country = region.split('-').next().unwrap_or_default().to_string();
+    let regions = vec![region, country, ANY_REGION.to_string()];
+
+    let options = sqlx::query_as::<_, DbOption>(
+        "SELECT code, name, price, min_transit_days, max_transit_days FROM (
+             SELECT DISTINCT ON (m.code) m.code, m.name, r.price, r.min_transit_days, r.max_transit_days
+             FROM shipping_methods m
+             JOIN shipping_rates r ON r.method = m.code
+             WHERE m.active AND r.region = ANY($1) AND r.max_weight_grams >= $2
+             ORDER BY m.code, array_position($1, r.region::TEXT), r.max_weight_grams
+         ) options
+         ORDER BY price, code",
+    )
+    .bind(&regions)
+    .bind(weight_grams)
+    .fetch_all(db)
+    .await
+    .map_err(AppError::from)?;
+
+    Ok(Ok(Quote {
+        options: options
+            .into_iter()
+            .map(|o| ShippingOption {
+                method: o.code,
+                name: o.name,
+                fee: o.price.to_string(),
+                min_transit_days: o.min_transit_days,
+                max_transit_days: o.max_transit_days,
+            })
+            .collect(),
+        weight_grams,
+        requires_shipping,
+    }))
+}
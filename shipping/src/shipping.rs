@@ -0,0 +1,743 @@
+use crate::carrier::Carrier;
+use crate::rates;
+use common::auth::{Caller, ServiceCredentials};
+use common::client::ServiceEndpoint;
+use common::error::{self, AppError};
+use common::resilience;
+use common::switches;
+use proto::order::order_service_client::OrderServiceClient;
+use proto::order::{
+    MarkItemsShippedRequest, RecordShipmentEventRequest, ShipmentEvent,
+    UpdateDeliveryEstimateRequest,
+};
+use proto::shipping::{
+    CreateShipmentRequest, CreateShipmentResponse, GetShipmentByOrderRequest,
+    GetShipmentByOrderResponse, QuoteShippingRequest, QuoteShippingResponse, Shipment,
+    ShipmentStatus, TrackingEvent, UpdateTrackingRequest, UpdateTrackingResponse,
+    shipping_service_server::ShippingService,
+};
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+const SHIPMENT_COLUMNS: &str =
+    "id, order_id, carrier, tracking_number, status, created_at, updated_at";
+
+#[derive(Debug, sqlx::FromRow)]
+struct DbShipment {
+    id: String,
+    order_id: String,
+    carrier: String,
+    tracking_number: String,
+    status: String,
+    created_at: chrono::NaiveDateTime,
+    updated_at: chrono::NaiveDateTime,
+}
+
+impl DbShipment {
+    fn to_proto(&self, item_ids: Vec<String>, events: Vec<TrackingEvent>) -> Shipment {
+        Shipment {
+            shipment_id: self.id.clone(),
+            order_id: self.order_id.clone(),
+            carrier: self.carrier.clone(),
+            tracking_number: self.tracking_number.clone(),
+            status: status_from_string(&self.status) as i32,
+            item_ids,
+            events,
+            created_at: self.created_at.and_utc().timestamp(),
+            updated_at: self.updated_at.and_utc().timestamp(),
+        }
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct DbShipmentEvent {
+    shipment_id: String,
+    status: String,
+    location: Option<String>,
+    description: Option<String>,
+    occurred_at: chrono::NaiveDateTime,
+}
+
+impl DbShipmentEvent {
+    fn to_proto(&self) -> TrackingEvent {
+        TrackingEvent {
+            status: status_from_string(&self.status) as i32,
+            location: self.location.clone().unwrap_or_default(),
+            description: self.description.clone().unwrap_or_default(),
+            occurred_at: self.occurred_at.and_utc().timestamp(),
+        }
+    }
+}
+
+/// An order item with the active shipment it ships in, if any.
+#[derive(Debug, sqlx::FromRow)]
+struct DbShippableItem {
+    id: String,
+    digital: bool,
+    shipment_id: Option<String>,
+}
+
+fn status_to_string(status: ShipmentStatus) -> &'static str {
+    match status {
+        ShipmentStatus::LabelCreated => "LABEL_CREATED",
+        ShipmentStatus::InTransit => "IN_TRANSIT",
+        ShipmentStatus::OutForDelivery => "OUT_FOR_DELIVERY",
+        ShipmentStatus::Delivered => "DELIVERED",
+        ShipmentStatus::Exception => "EXCEPTION",
+        ShipmentStatus::Cancelled => "CANCELLED",
+    }
+}
+
+fn status_from_string(status: &str) -> ShipmentStatus {
+    match status {
+        "IN_TRANSIT" => ShipmentStatus::InTransit,
+        "OUT_FOR_DELIVERY" => ShipmentStatus::OutForDelivery,
+        "DELIVERED" => ShipmentStatus::Delivered,
+        "EXCEPTION" => ShipmentStatus::Exception,
+        "CANCELLED" => ShipmentStatus::Cancelled,
+        _ => ShipmentStatus::LabelCreated,
+    }
+}
+
+/// How far along a shipment in `status` is. Events reported late, after a
+/// later one, extend the history without moving the shipment back.
+fn progress(status: ShipmentStatus) -> u8 {
+    match status {
+        ShipmentStatus::LabelCreated => 0,
+        ShipmentStatus::InTransit | ShipmentStatus::Exception => 1,
+        ShipmentStatus::OutForDelivery => 2,
+        ShipmentStatus::Delivered | ShipmentStatus::Cancelled => 3,
+    }
+}
+
+pub struct ShippingServiceImpl {
+    db: PgPool,
+    carrier: Arc<dyn Carrier>,
+    order_service: ServiceEndpoint,
+}
+
+impl ShippingServiceImpl {
+    pub fn new(db: PgPool, carrier: Arc<dyn Carrier>, order_service: ServiceEndpoint) -> Self {
+        Self {
+            db,
+            carrier,
+            order_service,
+        }
+    }
+
+    /// Shipments with their items and tracking events.
+    async fn load(&self, shipments: Vec<DbShipment>) -> Result<Vec<Shipment>, Status> {
+        let ids: Vec<String> = shipments.iter().map(|s| s.id.clone()).collect();
+
+        let item_rows: Vec<(String, String)> = sqlx::query_as(
+            "SELECT shipment_id, order_item_id FROM shipment_items
+             WHERE shipment_id = ANY($1)
+             ORDER BY order_item_id",
+        )
+        .bind(&ids)
+        .fetch_all(&self.db)
+        .await
+        .map_err(AppError::from)?;
+        let mut items: HashMap<String, Vec<String>> = HashMap::new();
+        for (shipment_id, item_id) in item_rows {
+            items.entry(shipment_id).or_default().push(item_id);
+        }
+
+        let event_rows = sqlx::query_as::<_, DbShipmentEvent>(
+            "SELECT shipment_id, status, location, description, occurred_at FROM shipment_events
+             WHERE shipment_id = ANY($1)
+             ORDER BY occurred_at, created_at",
+        )
+        .bind(&ids)
+        .fetch_all(&self.db)
+        .await
+        .map_err(AppError::from)?;
+        let mut events: HashMap<String, Vec<TrackingEvent>> = HashMap::new();
+        for row in event_rows {
+            events
+                .entry(row.shipment_id.clone())
+                .or_default()
+                .push(row.to_proto());
+        }
+
+        Ok(shipments
+            .iter()
+            .map(|s| {
+                s.to_proto(
+                    items.remove(&s.id).unwrap_or_default(),
+                    events.remove(&s.id).unwrap_or_default(),
+                )
+            })
+            .collect())
+    }
+
+    async fn load_one(&self, shipment_id: &str) -> Result<Option<Shipment>, Status> {
+        let shipment = sqlx::query_as::<_, DbShipment>(&format!(
+            "SELECT {} FROM shipments WHERE id = $1",
+            SHIPMENT_COLUMNS
+        ))
+        .bind(shipment_id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        Ok(self.load(shipment.into_iter().collect()).await?.pop())
+    }
+
+    /// Records a tracking event and moves the shipment along, then tells the
+    /// order service. Repeating an event records it once but reports it to
+    /// the order service again, so a retry finishes what a failed call
+    /// started. `Ok(Err(..))` holds the message refusing the event.
+    async fn apply_event(
+        &self,
+        shipment_id: &str,
+        status: ShipmentStatus,
+        location: &str,
+        description: &str,
+        occurred_at: chrono::NaiveDateTime,
+    ) -> Result<Result<DbShipment, String>, Status> {
+        let mut tx = self.db.begin().await.map_err(AppError::from)?;
+
+        let shipment = sqlx::query_as::<_, DbShipment>(&format!(
+            "SELECT {} FROM shipments WHERE id = $1 FOR UPDATE",
+            SHIPMENT_COLUMNS
+        ))
+        .bind(shipment_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+        let Some(shipment) = shipment else {
+            return Ok(Err("Shipment not found".to_string()));
+        };
+
+        let current = status_from_string(&shipment.status);
+        if matches!(
+            current,
+            ShipmentStatus::Delivered | ShipmentStatus::Cancelled
+        ) && status != current
+        {
+            return Ok(Err(format!(
+                "Shipment is already {}",
+                shipment.status.to_lowercase()
+            )));
+        }
+
+        let location = (!location.is_empty()).then_some(location);
+        let description = (!description.is_empty()).then_some(description);
+        sqlx::query(
+            "INSERT INTO shipment_events (id, shipment_id, status, location, description, occurred_at)
+             SELECT $1, $2, $3, $4, $5, $6
+             WHERE NOT EXISTS (
+                 SELECT 1 FROM shipment_events
+                 WHERE shipment_id = $2 AND status = $3 AND occurred_at = $6
+                   AND location IS NOT DISTINCT FROM $4
+             )",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(&shipment.id)
+        .bind(status_to_string(status))
+        .bind(location)
+        .bind(description)
+        .bind(occurred_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+
+        let shipment = if status != current && progress(status) >= progress(current) {
+            if status == ShipmentStatus::Cancelled {
+                sqlx::query("UPDATE shipment_items SET active = FALSE WHERE shipment_id = $1")
+                    .bind(&shipment.id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(AppError::from)?;
+            }
+
+            sqlx::query_as::<_, DbShipment>(&format!(
+                "UPDATE shipments SET status = $1, updated_at = CURRENT_TIMESTAMP
+                 WHERE id = $2
+                 RETURNING {}",
+                SHIPMENT_COLUMNS
+            ))
+            .bind(status_to_string(status))
+            .bind(&shipment.id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(AppError::from)?
+        } else {
+            shipment
+        };
+
+        tx.commit().await.map_err(AppError::from)?;
+
+        self.notify_order(&shipment).await?;
+
+        Ok(Ok(shipment))
+    }
+
+    /// Reports a shipment's progress to the order service: its items ship
+    /// once it is on the way, and the order is delivered once every item
+    /// that ships has been delivered.
+    async fn notify_order(&self, shipment: &DbShipment) -> Result<(), Status> {
+        let all_delivered = match status_from_string(&shipment.status) {
+            ShipmentStatus::InTransit | ShipmentStatus::OutForDelivery => false,
+            ShipmentStatus::Delivered => sqlx::query_scalar(
+                "SELECT NOT EXISTS (
+                     SELECT 1 FROM shipments
+                     WHERE order_id = $1 AND status NOT IN ('DELIVERED', 'CANCELLED')
+                 ) AND NOT EXISTS (
+                     SELECT 1 FROM order_items oi
+                     JOIN products p ON p.id = oi.product_id
+                     WHERE oi.order_id = $1 AND p.product_type <> 'DIGITAL'
+                       AND NOT EXISTS (
+                           SELECT 1 FROM shipment_items si
+                           WHERE si.order_item_id = oi.id AND si.active
+                       )
+                 )",
+            )
+            .bind(&shipment.order_id)
+            .fetch_one(&self.db)
+            .await
+            .map_err(AppError::from)?,
+            _ => return Ok(()),
+        };
+
+        let channel = self
+            .order_service
+            .primary_channel()
+            .map_err(|e| Status::internal(format!("Invalid order service URL: {}", e)))?;
+        let mut client =
+            OrderServiceClient::with_interceptor(channel, ServiceCredentials("shipping"));
+        let result = if all_delivered {
+            client
+                .record_shipment_event(RecordShipmentEventRequest {
+                    order_id: shipment.order_id.clone(),
+                    event: ShipmentEvent::ShipmentDelivered as i32,
+                })
+                .await
+                .map(|_| ())
+        } else {
+            let item_ids: Vec<String> = sqlx::query_scalar(
+                "SELECT order_item_id FROM shipment_items WHERE shipment_id = $1 AND active",
+            )
+            .bind(&shipment.id)
+            .fetch_all(&self.db)
+            .await
+            .map_err(AppError::from)?;
+            if item_ids.is_empty() {
+                return Ok(());
+            }
+
+            client
+                .mark_items_shipped(MarkItemsShippedRequest {
+                    order_id: shipment.order_id.clone(),
+                    item_ids,
+                    tracking: format!("{} {}", shipment.carrier, shipment.tracking_number),
+                })
+                .await
+                .map(|_| ())
+        };
+
+        match result {
+            Ok(()) => Ok(()),
+            // E.g. the order was cancelled meanwhile; the shipment is still tracked
+            Err(status) if error::is_refusal(&status) => {
+                warn!(
+                    shipment_id = %shipment.id,
+                    order_id = %shipment.order_id,
+                    "Order service refused shipment event: {}",
+                    status.message()
+                );
+                Ok(())
+            }
+            Err(e) => Err(resilience::downstream_error("Order", e)),
+        }
+    }
+
+    /// Passes a carrier's expected delivery date on to the order as its new
+    /// delivery estimate.
+    async fn update_estimate(
+        &self,
+        shipment: &DbShipment,
+        date: chrono::NaiveDate,
+    ) -> Result<(), Status> {
+        let channel = self
+            .order_service
+            .primary_channel()
+            .map_err(|e| Status::internal(format!("Invalid order service URL: {}", e)))?;
+        let mut client =
+            OrderServiceClient::with_interceptor(channel, ServiceCredentials("shipping"));
+        let date = date.format("%Y-%m-%d").to_string();
+        let result = client
+            .update_delivery_estimate(UpdateDeliveryEstimateRequest {
+                order_id: shipment.order_id.clone(),
+                from_date: date.clone(),
+                to_date: date,
+                reason: format!(
+                    "Expected by {} ({})",
+                    shipment.carrier, shipment.tracking_number
+                ),
+            })
+            .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            // E.g. the order was delivered meanwhile
+            Err(status) if error::is_refusal(&status) => {
+                warn!(
+                    shipment_id = %shipment.id,
+                    order_id = %shipment.order_id,
+                    "Order service refused delivery estimate: {}",
+                    status.message()
+                );
+                Ok(())
+            }
+            Err(e) => Err(resilience::downstream_error("Order", e)),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl ShippingService for ShippingServiceImpl {
+    async fn create_shipment(
+        &self,
+        request: Request<CreateShipmentRequest>,
+    ) -> Result<Response<CreateShipmentResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        // Field rules are checked by the validation layer (see shipping.proto)
+        let fail = |message: String| {
+            Response::new(CreateShipmentResponse {
+                success: false,
+                message,
+                shipment: None,
+            })
+        };
+
+        if switches::is_switch_active(&self.db, switches::FULFILLMENT_DRAINING).await? {
+            return Err(error::failed_precondition(
+                "FULFILLMENT_DRAINING",
+                "Fulfillment is paused while its queue drains",
+            ));
+        }
+
+        let order_status: Option<String> =
+            sqlx::query_scalar("SELECT status FROM orders WHERE id = $1")
+                .bind(&req.order_id)
+                .fetch_optional(&self.db)
+                .await
+                .map_err(AppError::from)?;
+        match order_status.as_deref() {
+            None => return Ok(fail("Order not found".to_string())),
+            Some("CONFIRMED" | "PROCESSING" | "PARTIALLY_SHIPPED" | "SHIPPED") => {}
+            Some(_) => {
+                return Ok(fail("Only confirmed orders can be shipped".to_string()));
+            }
+        }
+
+        let order_items = sqlx::query_as::<_, DbShippableItem>(
+            "SELECT oi.id, p.product_type = 'DIGITAL' AS digital, si.shipment_id
+             FROM order_items oi
+             JOIN products p ON p.id = oi.product_id
+             LEFT JOIN shipment_items si ON si.order_item_id = oi.id AND si.active
+             WHERE oi.order_id = $1",
+        )
+        .bind(&req.order_id)
+        .fetch_all(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        let item_ids: Vec<String> = if req.item_ids.is_empty() {
+            order_items
+                .iter()
+                .filter(|i| !i.digital && i.shipment_id.is_none())
+                .map(|i| i.id.clone())
+                .collect()
+        } else {
+            let by_id: HashMap<&str, &DbShippableItem> =
+                order_items.iter().map(|i| (i.id.as_str(), i)).collect();
+            let mut seen = HashSet::new();
+            let mut shipped_in = HashSet::new();
+            for item_id in &req.item_ids {
+                let Some(item) = by_id.get(item_id.as_str()) else {
+                    return Ok(fail(format!("Item {} is not part of this order", item_id)));
+                };
+                if item.digital {
+                    return Ok(fail(format!(
+                        "Item {} is a digital product and doesn't ship",
+                        item_id
+                    )));
+                }
+                seen.insert(item_id.clone());
+                shipped_in.insert(item.shipment_id.clone());
+            }
+
+            // A retried call finds its items in the shipment it created
+            if shipped_in.len() == 1
+                && let Some(Some(shipment_id)) = shipped_in.iter().next()
+            {
+                return Ok(Response::new(CreateShipmentResponse {
+                    success: true,
+                    message: "Items already ship in this shipment".to_string(),
+                    shipment: self.load_one(shipment_id).await?,
+                }));
+            }
+            if let Some(item) = req.item_ids.iter().find_map(|id| {
+                by_id[id.as_str()]
+                    .shipment_id
+                    .as_ref()
+                    .map(|shipment_id| (id, shipment_id))
+            }) {
+                return Ok(fail(format!(
+                    "Item {} already ships in shipment {}",
+                    item.0, item.1
+                )));
+            }
+
+            seen.into_iter().collect()
+        };
+        if item_ids.is_empty() {
+            return Ok(fail("Order has no items left to ship".to_string()));
+        }
+
+        let shipment_id = Uuid::new_v4().to_string();
+        let Some(tracking_number) = self
+            .carrier
+            .tracking_number(&shipment_id, &req.tracking_number)
+        else {
+            return Ok(fail("Tracking number is required".to_string()));
+        };
+
+        let mut tx = self.db.begin().await.map_err(AppError::from)?;
+
+        let created = sqlx::query(
+            "INSERT INTO shipments (id, order_id, carrier, tracking_number)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (carrier, tracking_number) DO NOTHING",
+        )
+        .bind(&shipment_id)
+        .bind(&req.order_id)
+        .bind(&req.carrier)
+        .bind(&tracking_number)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::from)?
+        .rows_affected()
+            == 1;
+        if !created {
+            return Ok(fail(format!(
+                "Tracking number {} is already used by another {} shipment",
+                tracking_number, req.carrier
+            )));
+        }
+
+        // The partial unique index turns a racing shipment of the same items
+        // into a conflict
+        for item_id in &item_ids {
+            let added = sqlx::query(
+                "INSERT INTO shipment_items (shipment_id, order_item_id) VALUES ($1, $2)
+                 ON CONFLICT (order_item_id) WHERE active DO NOTHING",
+            )
+            .bind(&shipment_id)
+            .bind(item_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::from)?
+            .rows_affected()
+                == 1;
+            if !added {
+                return Err(Status::aborted(
+                    "Concurrent shipment of the same items, please retry",
+                ));
+            }
+        }
+
+        sqlx::query(
+            "INSERT INTO shipment_events (id, shipment_id, status, occurred_at)
+             VALUES ($1, $2, 'LABEL_CREATED', CURRENT_TIMESTAMP)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(&shipment_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::from)?;
+
+        tx.commit().await.map_err(AppError::from)?;
+
+        info!(shipment_id = %shipment_id, order_id = %req.order_id, "Shipment created");
+
+        for status in self.carrier.instant_events() {
+            let now = chrono::Utc::now().naive_utc();
+            if let Err(message) = self
+                .apply_event(&shipment_id, *status, "", "Reported by the carrier", now)
+                .await?
+            {
+                warn!(shipment_id = %shipment_id, "Carrier event refused: {}", message);
+            }
+        }
+
+        Ok(Response::new(CreateShipmentResponse {
+            success: true,
+            message: "Shipment created".to_string(),
+            shipment: self.load_one(&shipment_id).await?,
+        }))
+    }
+
+    async fn update_tracking(
+        &self,
+        request: Request<UpdateTrackingRequest>,
+    ) -> Result<Response<UpdateTrackingResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        // Field rules are checked by the validation layer (see shipping.proto)
+        let fail = |message: String| {
+            Response::new(UpdateTrackingResponse {
+                success: false,
+                message,
+                shipment: None,
+            })
+        };
+
+        let Ok(status) = ShipmentStatus::try_from(req.status) else {
+            return Ok(fail("Unknown shipment status".to_string()));
+        };
+        let occurred_at = if req.occurred_at == 0 {
+            chrono::Utc::now().naive_utc()
+        } else {
+            match chrono::DateTime::from_timestamp(req.occurred_at, 0) {
+                Some(at) => at.naive_utc(),
+                None => return Ok(fail("Invalid event time".to_string())),
+            }
+        };
+        let estimated_delivery = if req.estimated_delivery.is_empty() {
+            None
+        } else {
+            match chrono::NaiveDate::parse_from_str(&req.estimated_delivery, "%Y-%m-%d") {
+                Ok(date) => Some(date),
+                Err(_) => {
+                    return Ok(fail("estimated_delivery must be YYYY-MM-DD".to_string()));
+                }
+            }
+        };
+
+        let shipment = match self
+            .apply_event(
+                &req.shipment_id,
+                status,
+                &req.location,
+                &req.description,
+                occurred_at,
+            )
+            .await?
+        {
+            Ok(shipment) => shipment,
+            Err(message) => return Ok(fail(message)),
+        };
+
+        if let Some(date) = estimated_delivery {
+            self.update_estimate(&shipment, date).await?;
+        }
+
+        Ok(Response::new(UpdateTrackingResponse {
+            success: true,
+            message: "Tracking updated".to_string(),
+            shipment: self.load(vec![shipment]).await?.pop(),
+        }))
+    }
+
+    async fn get_shipment_by_order(
+        &self,
+        request: Request<GetShipmentByOrderRequest>,
+    ) -> Result<Response<GetShipmentByOrderResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        let req = request.into_inner();
+
+        // Field rules are checked by the validation layer (see shipping.proto)
+        let user_id: Option<String> = sqlx::query_scalar("SELECT user_id FROM orders WHERE id = $1")
+            .bind(&req.order_id)
+            .fetch_optional(&self.db)
+            .await
+            .map_err(AppError::from)?;
+        let Some(user_id) = user_id else {
+            return Ok(Response::new(GetShipmentByOrderResponse {
+                success: false,
+                message: "Order not found".to_string(),
+                shipments: vec![],
+            }));
+        };
+        caller.require_owner(&user_id)?;
+
+        let shipments = sqlx::query_as::<_, DbShipment>(&format!(
+            "SELECT {} FROM shipments WHERE order_id = $1 ORDER BY created_at, id",
+            SHIPMENT_COLUMNS
+        ))
+        .bind(&req.order_id)
+        .fetch_all(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        if shipments.is_empty() {
+            return Ok(Response::new(GetShipmentByOrderResponse {
+                success: false,
+                message: "Order has no shipments".to_string(),
+                shipments: vec![],
+            }));
+        }
+
+        let shipments = self.load(shipments).await?;
+
+        Ok(Response::new(GetShipmentByOrderResponse {
+            success: true,
+            message: format!("Retrieved {} shipments", shipments.len()),
+            shipments,
+        }))
+    }
+
+    async fn quote_shipping(
+        &self,
+        request: Request<QuoteShippingRequest>,
+    ) -> Result<Response<QuoteShippingResponse>, Status> {
+        let req = request.into_inner();
+
+        // Field rules are checked by the validation layer (see shipping.proto)
+        let quote = match rates::quote(&self.db, &req.items, &req.shipping_region).await? {
+            Ok(quote) => quote,
+            Err(message) => {
+                return Ok(Response::new(QuoteShippingResponse {
+                    success: false,
+                    message,
+                    options: vec![],
+                    weight_grams: 0,
+                    requires_shipping: false,
+                }));
+            }
+        };
+
+        let (success, message) = if !quote.requires_shipping {
+            (true, "Nothing to ship".to_string())
+        } else if quote.options.is_empty() {
+            (
+                false,
+                format!("No shipping method ships to {}", req.shipping_region),
+            )
+        } else {
+            (
+                true,
+                format!("Found {} shipping options", quote.options.len()),
+            )
+        };
+
+        Ok(Response::new(QuoteShippingResponse {
+            success,
+            message,
+            options: quote.options,
+            weight_grams: quote.weight_grams,
+            requires_shipping: quote.requires_shipping,
+        }))
+    }
+}
@@ -0,0 +1,307 @@
+//! Runs the user, product and order services in-process against a Postgres
+//! of their own, for integration tests that call them the way clients do.
+//!
+//! The database is a fresh one on the server at `TEST_DATABASE_URL` when
+//! it's set, e.g. a CI service container; otherwise a Postgres container is
+//! started through Docker for each `TestApp` and removed with it. Databases
+//! created on a `TEST_DATABASE_URL` server are named `test_<uuid>` and left
+//! behind for inspection.
+
+use anyhow::{Context, Result};
+use common::auth::{self, Role};
+use common::cache::Cache;
+use common::client::ServiceEndpoint;
+use common::metrics::Metrics;
+use common::response_cache::ResponseCache;
+use common::server::{ServerBuilder, StandardLayer};
+use common::settings::SettingsStore;
+use common::slo::{SloConfig, SloTracker};
+use order::fraud::NoopFraudChecker;
+use order::order::{Downstream, OrderServiceImpl};
+use order::order_number::OrderNumberFormat;
+use order::tax::RateTableCalculator;
+use order::user_verification::VerifiedUsers;
+use product::merchandising::OutOfStockPolicy;
+use product::product::ProductServiceImpl;
+use product::stock_badge::StockBadgeCache;
+use proto::order::order_service_client::OrderServiceClient;
+use proto::order::order_service_server::OrderServiceServer;
+use proto::product::product_service_client::ProductServiceClient;
+use proto::product::product_service_server::ProductServiceServer;
+use proto::product::{AddProductRequest, PublishProductRequest};
+use proto::user::user_service_client::UserServiceClient;
+use proto::user::user_service_server::UserServiceServer;
+use proto::user::{LoginRequest, RegisterRequest};
+use sqlx::postgres::PgPoolOptions;
+use sqlx::{Connection, PgConnection, PgPool};
+use std::env;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use testcontainers_modules::postgres::Postgres;
+use testcontainers_modules::testcontainers::runners::AsyncRunner;
+use testcontainers_modules::testcontainers::{ContainerAsync, ImageExt};
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::Request;
+use tonic::metadata::MetadataValue;
+use tonic::transport::{Channel, Endpoint, Server};
+use tower::layer::util::{Identity, Stack};
+use user::repository::PostgresUserRepository;
+use uuid::Uuid;
+
+/// Postgres version of the container, the one the services run against.
+const POSTGRES_TAG: &str = "15-alpine";
+
+/// Password of the users `TestApp::register_user` creates.
+pub const PASSWORD: &str = "correct horse battery staple";
+
+/// The services, serving on ports of their own, and clients of them.
+pub struct TestApp {
+    /// The services' database, to arrange state no RPC can or to check
+    /// what a call wrote.
+    pub db: PgPool,
+    pub user: UserServiceClient<Channel>,
+    pub product: ProductServiceClient<Channel>,
+    pub order: OrderServiceClient<Channel>,
+    /// An operator, for the calls only the platform may make.
+    pub admin: TestUser,
+    /// Where the product service serves, for services a test builds itself.
+    pub product_url: String,
+    // Kept until the app is dropped, which removes the container
+    _container: Option<ContainerAsync<Postgres>>,
+}
+
+/// A registered user and the token of their login.
+pub struct TestUser {
+    pub user_id: String,
+    pub username: String,
+    pub token: String,
+}
+
+impl TestUser {
+    /// A request of `message` made as this user.
+    pub fn request<T>(&self, message: T) -> Request<T> {
+        let mut request = Request::new(message);
+        let header = MetadataValue::try_from(format!("Bearer {}", self.token))
+            .expect("Tokens are valid header values");
+        request.metadata_mut().insert("authorization", header);
+        request
+    }
+}
+
+impl TestApp {
+    /// Migrates a fresh database and starts the services against it.
+    pub async fn spawn() -> Result<Self> {
+        let (database_url, container) = database().await?;
+        let db = PgPoolOptions::new()
+            .max_connections(10)
+            .connect(&database_url)
+            .await?;
+        sqlx::migrate!("../migrations").run(&db).await?;
+
+        let user_addr = serve_user(db.clone()).await?;
+        let product_addr = serve_product(db.clone()).await?;
+        let order_addr = serve_order(db.clone(), user_addr, product_addr).await?;
+
+        Ok(Self {
+            user: UserServiceClient::new(channel(user_addr)?),
+            product: ProductServiceClient::new(channel(product_addr)?),
+            order: OrderServiceClient::new(channel(order_addr)?),
+            product_url: format!("http://{}", product_addr),
+            admin: TestUser {
+                user_id: "admin".to_string(),
+                username: "admin".to_string(),
+                token: auth::issue_token("admin", Role::Admin, None)?,
+            },
+            db,
+            _container: container,
+        })
+    }
+
+    /// Registers `username` and signs them in.
+    pub async fn register_user(&mut self, username: &str) -> Result<TestUser> {
+        let user_id = self
+            .user
+            .register(RegisterRequest {
+                username: username.to_string(),
+                email: format!("{}@example.com", username),
+                password: PASSWORD.to_string(),
+                full_name: String::new(),
+                phone_number: String::new(),
+                captcha_token: String::new(),
+            })
+            .await?
+            .into_inner()
+            .user_id;
+        let token = self
+            .user
+            .login(LoginRequest {
+                identifier: username.to_string(),
+                password: PASSWORD.to_string(),
+            })
+            .await?
+            .into_inner()
+            .token;
+
+        Ok(TestUser {
+            user_id,
+            username: username.to_string(),
+            token,
+        })
+    }
+
+    /// Adds a published product of the platform's own and returns its id.
+    pub async fn add_product(&mut self, name: &str, price: &str, stock: i32) -> Result<String> {
+        let product_id = self
+            .product
+            .add_product(self.admin.request(AddProductRequest {
+                name: name.to_string(),
+                price: price.to_string(),
+                stock_quantity: stock,
+                category: "Testing".to_string(),
+                ..Default::default()
+            }))
+            .await?
+            .into_inner()
+            .product_id;
+        self.product
+            .publish_product(self.admin.request(PublishProductRequest {
+                product_id: product_id.clone(),
+            }))
+            .await?;
+        Ok(product_id)
+    }
+
+    /// Units of a product in stock, as the database has it.
+    pub async fn stock_of(&self, product_id: &str) -> Result<i32> {
+        Ok(
+            sqlx::query_scalar("SELECT stock_quantity FROM products WHERE id = $1")
+                .bind(product_id)
+                .fetch_one(&self.db)
+                .await?,
+        )
+    }
+}
+
+/// URL of a fresh database, and the container serving it if one was started.
+async fn database() -> Result<(String, Option<ContainerAsync<Postgres>>)> {
+    if let Ok(server_url) = env::var("TEST_DATABASE_URL") {
+        let name = format!("test_{}", Uuid::new_v4().simple());
+        let mut server = PgConnection::connect(&server_url)
+            .await
+            .context("Can't connect to TEST_DATABASE_URL")?;
+        sqlx::query(&format!("CREATE DATABASE {}", name))
+            .execute(&mut server)
+            .await?;
+        return Ok((database_url(&server_url, &name), None));
+    }
+
+    let container = Postgres::default()
+        .with_tag(POSTGRES_TAG)
+        .start()
+        .await
+        .context("Can't start a Postgres container; is Docker running? Set TEST_DATABASE_URL to use a server instead")?;
+    let url = format!(
+        "postgres://postgres:postgres@{}:{}/postgres",
+        container.get_host().await?,
+        container.get_host_port_ipv4(5432).await?
+    );
+    Ok((url, Some(container)))
+}
+
+/// `server_url` with its database replaced by `name`; a query string, e.g.
+/// `?host=/tmp`, is kept.
+fn database_url(server_url: &str, name: &str) -> String {
+    let (base, query) = match server_url.split_once('?') {
+        Some((base, query)) => (base, Some(query)),
+        None => (server_url, None),
+    };
+    let authority_end = base.find("://").map_or(0, |i| i + 3);
+    let base = match base[authority_end..].find('/') {
+        Some(i) => &base[..authority_end + i],
+        None => base,
+    };
+    match query {
+        Some(query) => format!("{}/{}?{}", base, name, query),
+        None => format!("{}/{}", base, name),
+    }
+}
+
+fn channel(addr: SocketAddr) -> Result<Channel> {
+    Ok(Endpoint::from_shared(format!("http://{}", addr))?.connect_lazy())
+}
+
+/// The standard server of `service`, as the service's binary builds it.
+fn server(service: &str) -> Result<Server<Stack<StandardLayer, Identity>>> {
+    let slo_tracker = SloTracker::new(service, SloConfig::from_env()?);
+    ServerBuilder::new(Metrics::new(service), slo_tracker).build()
+}
+
+/// Binds a port on localhost for a service to serve on.
+async fn listen() -> Result<(SocketAddr, TcpListenerStream)> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    Ok((listener.local_addr()?, TcpListenerStream::new(listener)))
+}
+
+async fn serve_user(db: PgPool) -> Result<SocketAddr> {
+    let user_service = user::user::UserServiceImpl::new(
+        Arc::new(PostgresUserRepository::new(db)),
+        None,
+        ResponseCache::new().build(),
+        Arc::new(Cache::disabled("user")),
+    );
+
+    let router = server("user")?.add_service(UserServiceServer::new(user_service));
+    let (addr, incoming) = listen().await?;
+    tokio::spawn(router.serve_with_incoming(incoming));
+    Ok(addr)
+}
+
+async fn serve_product(db: PgPool) -> Result<SocketAddr> {
+    let product_service = ProductServiceImpl::new(
+        db.clone(),
+        ResponseCache::new().build(),
+        Arc::new(Cache::disabled("product")),
+        StockBadgeCache::new(db, Duration::from_secs(30), 5),
+        OutOfStockPolicy::Notify,
+    );
+
+    let router = server("product")?.add_service(ProductServiceServer::new(product_service));
+    let (addr, incoming) = listen().await?;
+    tokio::spawn(router.serve_with_incoming(incoming));
+    Ok(addr)
+}
+
+async fn serve_order(
+    db: PgPool,
+    user_addr: SocketAddr,
+    product_addr: SocketAddr,
+) -> Result<SocketAddr> {
+    // Payments and shipping aren't served: flows calling them fail with
+    // UNAVAILABLE
+    let unserved = "http://127.0.0.1:1";
+    let order_service = OrderServiceImpl::new(
+        db.clone(),
+        Downstream {
+            user: ServiceEndpoint::new("user", format!("http://{}", user_addr)),
+            product: Arc::new(ServiceEndpoint::new(
+                "product",
+                format!("http://{}", product_addr),
+            )),
+            payment: Arc::new(ServiceEndpoint::new("payment", unserved)),
+            shipping: ServiceEndpoint::new("shipping", unserved),
+        },
+        OrderNumberFormat::Sequential,
+        SettingsStore::new(db.clone(), Duration::from_secs(30)),
+        Arc::new(RateTableCalculator::new(db)),
+        // Tests place orders faster than customers do
+        Arc::new(NoopFraudChecker),
+        VerifiedUsers::new(Duration::from_secs(60), Duration::from_secs(5)),
+    );
+
+    let router = server("order")?.add_service(OrderServiceServer::new(order_service));
+    let (addr, incoming) = listen().await?;
+    tokio::spawn(router.serve_with_incoming(incoming));
+    Ok(addr)
+}
@@ -0,0 +1,103 @@
+//! Guest checkout and claiming the guest's orders, against services running
+//! in-process.
+
+use common::auth;
+use common::error;
+use proto::order::{ClaimGuestOrdersRequest, CreateOrderRequest, OrderItem};
+use proto::user::ConfirmEmailRequest;
+use testing::TestApp;
+use tonic::{Code, Request};
+
+async fn enable_guest_checkout(app: &TestApp) {
+    sqlx::query(
+        "INSERT INTO store_settings (field, value, updated_by)
+         VALUES ('guest_checkout_enabled', 'true', 'test')",
+    )
+    .execute(&app.db)
+    .await
+    .unwrap();
+}
+
+/// Places an order of one `product_id` as a guest checking out with `email`
+/// and returns its id.
+async fn guest_order(app: &mut TestApp, email: &str, product_id: &str) -> String {
+    app.order
+        .create_order(Request::new(CreateOrderRequest {
+            user_id: String::new(),
+            items: vec![OrderItem {
+                product_id: product_id.to_string(),
+                quantity: 1,
+                ..Default::default()
+            }],
+            shipping_address: "123 Main St, City, State 12345".to_string(),
+            shipping_region: "US-CA".to_string(),
+            shipping_method: String::new(),
+            currency: String::new(),
+            billing_region: String::new(),
+            guest_email: email.to_string(),
+        }))
+        .await
+        .expect("guest order is created")
+        .into_inner()
+        .order_id
+}
+
+#[tokio::test]
+async fn guest_orders_are_claimed_once_the_address_is_confirmed() {
+    let mut app = TestApp::spawn().await.unwrap();
+    enable_guest_checkout(&app).await;
+    let lamp = app.add_product("Desk Lamp", "24.50", 10).await.unwrap();
+    let order_id = guest_order(&mut app, "John_Doe@Example.com", &lamp).await;
+
+    let john = app.register_user("john_doe").await.unwrap();
+    // What the order confirmation email carries
+    let token = auth::issue_email_token("john_doe@example.com").unwrap();
+    let claim = || ClaimGuestOrdersRequest {
+        email_token: token.clone(),
+    };
+
+    let status = app
+        .order
+        .claim_guest_orders(john.request(claim()))
+        .await
+        .expect_err("orders were claimed into an unconfirmed address");
+    assert_eq!(status.code(), Code::FailedPrecondition);
+
+    let user = app
+        .user
+        .confirm_email(john.request(ConfirmEmailRequest {
+            user_id: john.user_id.clone(),
+            email_token: token.clone(),
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .user
+        .expect("user is returned");
+    assert!(user.email_verified);
+
+    let claimed = app
+        .order
+        .claim_guest_orders(john.request(claim()))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(claimed.order_ids, [order_id]);
+}
+
+#[tokio::test]
+async fn an_email_token_confirms_only_its_own_address() {
+    let mut app = TestApp::spawn().await.unwrap();
+    let jane = app.register_user("jane_doe").await.unwrap();
+
+    let status = app
+        .user
+        .confirm_email(jane.request(ConfirmEmailRequest {
+            user_id: jane.user_id.clone(),
+            email_token: auth::issue_email_token("john_doe@example.com").unwrap(),
+        }))
+        .await
+        .expect_err("another address was confirmed");
+    assert_eq!(status.code(), Code::FailedPrecondition);
+    assert_eq!(error::error_info(&status).unwrap().reason, "EMAIL_MISMATCH");
+}
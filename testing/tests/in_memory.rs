@@ -0,0 +1,417 @@
+//! The services' lookups and writes against in-memory repositories, with no
+//! database. Their pools connect lazily and are never used on these paths.
+
+use chrono::Utc;
+use common::auth::{self, Role};
+use common::cache::Cache;
+use common::client::ServiceEndpoint;
+use common::error;
+use common::response_cache::ResponseCache;
+use common::settings::SettingsStore;
+use order::fraud::NoopFraudChecker;
+use order::order::{Downstream, OrderServiceImpl};
+use order::order_number::OrderNumberFormat;
+use order::repository::{DbOrder, DbOrderItem, InMemoryOrderRepository};
+use order::tax::RateTableCalculator;
+use order::user_verification::VerifiedUsers;
+use product::merchandising::OutOfStockPolicy;
+use product::product::ProductServiceImpl;
+use product::repository::InMemoryProductRepository;
+use product::stock_badge::StockBadgeCache;
+use proto::order::order_service_server::OrderService;
+use proto::order::{GetOrderRequest, GetOrdersByUserRequest, OrderSort, OrderStatus};
+use proto::product::product_service_server::{ProductService, ProductServiceServer};
+use proto::product::{AddProductRequest, GetProductRequest, UpdateInventoryRequest};
+use proto::user::user_service_server::UserService;
+use proto::user::{
+    GetUsersByIDsRequest, LoginRequest, RegisterRequest, UpdateUserProfileRequest, VerifyRequest,
+};
+use sqlx::PgPool;
+use sqlx::postgres::PgPoolOptions;
+use sqlx::types::Decimal;
+use std::sync::Arc;
+use std::time::Duration;
+use testing::PASSWORD;
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::transport::Server;
+use tonic::{Code, Request};
+use user::repository::InMemoryUserRepository;
+use user::user::UserServiceImpl;
+
+/// A pool the services are built with, which fails any query made on it.
+fn unused_pool() -> PgPool {
+    PgPoolOptions::new()
+        .acquire_timeout(Duration::from_millis(100))
+        .connect_lazy("postgres://unused@127.0.0.1:1/unused")
+        .unwrap()
+}
+
+fn user_service() -> UserServiceImpl {
+    UserServiceImpl::new(
+        Arc::new(InMemoryUserRepository::new()),
+        None,
+        ResponseCache::new().build(),
+        Arc::new(Cache::disabled("user")),
+    )
+}
+
+fn product_service(repository: InMemoryProductRepository) -> ProductServiceImpl {
+    let db = unused_pool();
+    ProductServiceImpl::new(
+        db.clone(),
+        ResponseCache::new().build(),
+        Arc::new(Cache::disabled("product")),
+        StockBadgeCache::new(db, Duration::from_secs(30), 5),
+        OutOfStockPolicy::Notify,
+    )
+    .with_repository(Arc::new(repository))
+}
+
+/// A request of `message` made with `token`.
+fn with_token<T>(token: &str, message: T) -> Request<T> {
+    let mut request = Request::new(message);
+    let header = format!("Bearer {}", token).parse().unwrap();
+    request.metadata_mut().insert("authorization", header);
+    request
+}
+
+fn as_admin<T>(message: T) -> Request<T> {
+    with_token(
+        &auth::issue_token("admin", Role::Admin, None).unwrap(),
+        message,
+    )
+}
+
+fn register(username: &str) -> Request<RegisterRequest> {
+    Request::new(RegisterRequest {
+        username: username.to_string(),
+        email: format!("{}@example.com", username),
+        password: PASSWORD.to_string(),
+        full_name: String::new(),
+        phone_number: String::new(),
+        captcha_token: String::new(),
+    })
+}
+
+#[tokio::test]
+async fn users_register_sign_in_and_update_their_email() {
+    let users = user_service();
+    let user_id = users
+        .register(register("john_doe"))
+        .await
+        .unwrap()
+        .into_inner()
+        .user_id;
+
+    let status = users
+        .register(register("john_doe"))
+        .await
+        .expect_err("username was registered twice");
+    assert_eq!(status.code(), Code::AlreadyExists);
+    assert_eq!(error::error_info(&status).unwrap().reason, "USER_EXISTS");
+
+    // By email too, in any case
+    let login = users
+        .login(Request::new(LoginRequest {
+            identifier: "John_Doe@Example.com".to_string(),
+            password: PASSWORD.to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(login.user.unwrap().user_id, user_id);
+    assert_eq!(
+        common::auth::decode_token(&login.token).unwrap().sub,
+        user_id
+    );
+
+    let status = users
+        .login(Request::new(LoginRequest {
+            identifier: "john_doe".to_string(),
+            password: "not the password".to_string(),
+        }))
+        .await
+        .expect_err("wrong password signed in");
+    assert_eq!(status.code(), Code::Unauthenticated);
+
+    let update = || UpdateUserProfileRequest {
+        user_id: user_id.clone(),
+        email: "john@example.org".to_string(),
+        full_name: String::new(),
+        phone_number: String::new(),
+    };
+    let status = users
+        .update_user_profile(Request::new(update()))
+        .await
+        .expect_err("profile was updated without signing in");
+    assert_eq!(status.code(), Code::Unauthenticated);
+
+    let updated = users
+        .update_user_profile(with_token(&login.token, update()))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(updated.user.unwrap().email, "john@example.org");
+
+    let lookup = || GetUsersByIDsRequest {
+        user_ids: vec![user_id.clone(), "unknown".to_string()],
+    };
+    let status = users
+        .get_users_by_ids(with_token(&login.token, lookup()))
+        .await
+        .expect_err("a customer looked up other users");
+    assert_eq!(status.code(), Code::PermissionDenied);
+
+    let summaries = users
+        .get_users_by_ids(as_admin(lookup()))
+        .await
+        .unwrap()
+        .into_inner()
+        .users;
+    assert_eq!(summaries.len(), 1);
+    assert_eq!(summaries[0].email, "john@example.org");
+
+    let status = users
+        .verify(with_token(
+            &login.token,
+            VerifyRequest {
+                user_id: "unknown".to_string(),
+            },
+        ))
+        .await
+        .expect_err("a customer checked whether a user exists");
+    assert_eq!(status.code(), Code::PermissionDenied);
+
+    let unknown = users
+        .verify(as_admin(VerifyRequest {
+            user_id: "unknown".to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(!unknown.valid);
+}
+
+#[tokio::test]
+async fn products_are_added_as_drafts_and_their_stock_moves() {
+    let products = product_service(InMemoryProductRepository::new());
+    let product_id = products
+        .add_product(as_admin(AddProductRequest {
+            name: "Desk Lamp".to_string(),
+            price: "24.50".to_string(),
+            stock_quantity: 3,
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .product_id;
+
+    let status = products
+        .add_product(as_admin(AddProductRequest {
+            name: "Mug".to_string(),
+            price: "8.00".to_string(),
+            seller_id: "unknown".to_string(),
+            ..Default::default()
+        }))
+        .await
+        .expect_err("product was listed by an unknown seller");
+    assert_eq!(
+        error::error_info(&status).unwrap().reason,
+        "SELLER_NOT_FOUND"
+    );
+
+    let moved = products
+        .update_inventory(as_admin(UpdateInventoryRequest {
+            product_id: product_id.clone(),
+            quantity_change: -2,
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(moved.new_stock_quantity, 1);
+
+    let status = products
+        .update_inventory(as_admin(UpdateInventoryRequest {
+            product_id: product_id.clone(),
+            quantity_change: -2,
+        }))
+        .await
+        .expect_err("stock went negative");
+    assert_eq!(status.code(), Code::FailedPrecondition);
+    assert_eq!(
+        error::error_info(&status).unwrap().reason,
+        "INSUFFICIENT_STOCK"
+    );
+
+    let product = products
+        .get_product(Request::new(GetProductRequest {
+            product_id: product_id.clone(),
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .product
+        .unwrap();
+    assert_eq!(product.name, "Desk Lamp");
+    assert_eq!(product.price, "24.50");
+    assert_eq!(product.stock_quantity, 1);
+}
+
+fn order(id: &str, user_id: &str, status: &str, age_minutes: i64) -> DbOrder {
+    let created_at = Utc::now() - chrono::Duration::minutes(age_minutes);
+    DbOrder {
+        id: id.to_string(),
+        order_number: format!("ORD-{}", id.to_ascii_uppercase()),
+        user_id: user_id.to_string(),
+        total_amount: Decimal::new(4900, 2),
+        status: status.to_string(),
+        shipping_address: Some("123 Main St".to_string()),
+        tax_amount: Decimal::ZERO,
+        shipping_region: None,
+        shipping_method: None,
+        shipping_fee: Decimal::ZERO,
+        currency: "USD".to_string(),
+        exchange_rate: Decimal::ONE,
+        estimated_delivery_from: None,
+        estimated_delivery_to: None,
+        created_at,
+        updated_at: created_at,
+    }
+}
+
+fn order_item(order_id: &str, product_id: &str) -> DbOrderItem {
+    DbOrderItem {
+        id: format!("{}-1", order_id),
+        order_id: order_id.to_string(),
+        product_id: product_id.to_string(),
+        quantity: 2,
+        price: Decimal::new(2450, 2),
+        status: "ALLOCATED".to_string(),
+        tax_amount: Decimal::ZERO,
+        fulfillment_status: "UNFULFILLED".to_string(),
+        shipment_tracking: None,
+        shipped_at: None,
+    }
+}
+
+/// Order lookups name items through the product service, so one is served
+/// with the products of `products`.
+async fn serve_products(products: ProductServiceImpl) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(
+        Server::builder()
+            .add_service(ProductServiceServer::new(products))
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+    format!("http://{}", addr)
+}
+
+#[tokio::test]
+async fn orders_are_looked_up_and_listed_by_user() {
+    let products = product_service(InMemoryProductRepository::new());
+    let lamp = products
+        .add_product(as_admin(AddProductRequest {
+            name: "Desk Lamp".to_string(),
+            price: "24.50".to_string(),
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .product_id;
+    let product_url = serve_products(products).await;
+
+    let repository = InMemoryOrderRepository::new();
+    repository.insert(
+        order("a", "john", "PENDING", 30),
+        vec![order_item("a", &lamp)],
+    );
+    repository.insert(order("b", "john", "SHIPPED", 20), vec![]);
+    repository.insert(order("c", "john", "PENDING", 10), vec![]);
+    repository.insert(order("d", "jane", "PENDING", 5), vec![]);
+
+    let db = unused_pool();
+    let unserved = "http://127.0.0.1:1";
+    let orders = OrderServiceImpl::new(
+        db.clone(),
+        Downstream {
+            user: ServiceEndpoint::new("user", unserved),
+            product: Arc::new(ServiceEndpoint::new("product", product_url)),
+            payment: Arc::new(ServiceEndpoint::new("payment", unserved)),
+            shipping: ServiceEndpoint::new("shipping", unserved),
+        },
+        OrderNumberFormat::Sequential,
+        SettingsStore::new(db.clone(), Duration::from_secs(30)),
+        Arc::new(RateTableCalculator::new(db)),
+        Arc::new(NoopFraudChecker),
+        VerifiedUsers::disabled(),
+    )
+    .with_repository(Arc::new(repository));
+
+    let found = orders
+        .get_order(as_admin(GetOrderRequest {
+            order_id: String::new(),
+            order_number: "ord-a".to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .order
+        .unwrap();
+    assert_eq!(found.order_id, "a");
+    assert_eq!(found.items.len(), 1);
+    assert_eq!(found.items[0].product_name, "Desk Lamp");
+    assert_eq!(found.items[0].subtotal, "49.00");
+
+    let status = orders
+        .get_order(as_admin(GetOrderRequest {
+            order_id: "unknown".to_string(),
+            order_number: String::new(),
+        }))
+        .await
+        .expect_err("unknown order was found");
+    assert_eq!(status.code(), Code::NotFound);
+
+    let john = auth::issue_token("john", Role::Customer, None).unwrap();
+    let by_user = |page_token: String, status: OrderStatus| {
+        with_token(
+            &john,
+            GetOrdersByUserRequest {
+                user_id: "john".to_string(),
+                page: 1,
+                page_size: 2,
+                page_token,
+                status: status as i32,
+                sort: OrderSort::NewestFirst as i32,
+            },
+        )
+    };
+    let first = orders
+        .get_orders_by_user(by_user(String::new(), OrderStatus::Pending))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(first.total_count, 3);
+    let ids: Vec<&str> = first.orders.iter().map(|o| o.order_id.as_str()).collect();
+    assert_eq!(ids, ["c", "b"]);
+
+    let second = orders
+        .get_orders_by_user(by_user(first.next_page_token, OrderStatus::Pending))
+        .await
+        .unwrap()
+        .into_inner();
+    let ids: Vec<&str> = second.orders.iter().map(|o| o.order_id.as_str()).collect();
+    assert_eq!(ids, ["a"]);
+    assert!(second.next_page_token.is_empty());
+
+    let shipped = orders
+        .get_orders_by_user(by_user(String::new(), OrderStatus::Shipped))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(shipped.total_count, 1);
+    assert_eq!(shipped.orders[0].order_id, "b");
+}
@@ -0,0 +1,131 @@
+//! The ops actions, against services running in-process.
+
+use common::client::ServiceEndpoint;
+use common::metrics::Metrics;
+use order::consistency::ConsistencyChecker;
+use order::ops::OpsServiceImpl;
+use proto::ops::ops_service_server::OpsService;
+use proto::ops::{ForceReleaseReservationsRequest, ListSwitchesRequest, OpsActionRequest};
+use proto::product::{ReservationLine, ReserveStockRequest};
+use std::sync::Arc;
+use testing::TestApp;
+use tonic::{Code, Request};
+use uuid::Uuid;
+
+fn ops_service(app: &TestApp) -> OpsServiceImpl {
+    let metrics = Metrics::new("order");
+    let payment = Arc::new(ServiceEndpoint::new("payment", "http://127.0.0.1:1"));
+    OpsServiceImpl::new(
+        app.db.clone(),
+        ConsistencyChecker::new(app.db.clone(), payment, &metrics),
+        Arc::new(ServiceEndpoint::new("product", app.product_url.clone())),
+    )
+}
+
+fn action(reason: &str) -> OpsActionRequest {
+    OpsActionRequest {
+        actor: "jane.operator".to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+async fn audited_actions(app: &TestApp) -> Vec<String> {
+    sqlx::query_scalar("SELECT action FROM ops_audit_log ORDER BY created_at")
+        .fetch_all(&app.db)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn ops_actions_need_an_admin_token() {
+    let mut app = TestApp::spawn().await.unwrap();
+    let john = app.register_user("john_doe").await.unwrap();
+    let ops = ops_service(&app);
+
+    let status = ops
+        .list_switches(Request::new(ListSwitchesRequest {}))
+        .await
+        .expect_err("an anonymous caller listed the switches");
+    assert_eq!(status.code(), Code::Unauthenticated);
+
+    let status = ops
+        .pause_order_intake(john.request(action("Testing")))
+        .await
+        .expect_err("a customer paused order intake");
+    assert_eq!(status.code(), Code::PermissionDenied);
+    assert!(audited_actions(&app).await.is_empty());
+}
+
+#[tokio::test]
+async fn draining_fulfillment_is_audited_and_reversible() {
+    let app = TestApp::spawn().await.unwrap();
+    let ops = ops_service(&app);
+
+    let drained = ops
+        .drain_fulfillment_queue(app.admin.request(action("Carrier outage")))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(drained.switch.unwrap().active);
+
+    let resumed = ops
+        .resume_fulfillment_queue(app.admin.request(action("Carrier back")))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(!resumed.switch.unwrap().active);
+
+    assert_eq!(
+        audited_actions(&app).await,
+        ["DRAIN_FULFILLMENT_QUEUE", "RESUME_FULFILLMENT_QUEUE"]
+    );
+}
+
+#[tokio::test]
+async fn stuck_reservations_of_a_product_are_released() {
+    let mut app = TestApp::spawn().await.unwrap();
+    let lamp = app.add_product("Desk Lamp", "24.50", 10).await.unwrap();
+
+    // An order being placed when its service went away: stock reserved,
+    // the order never written
+    let order_id = Uuid::new_v4().to_string();
+    sqlx::query("INSERT INTO order_sagas (order_id, state) VALUES ($1, 'STARTED')")
+        .bind(&order_id)
+        .execute(&app.db)
+        .await
+        .unwrap();
+    app.product
+        .reserve_stock(app.admin.request(ReserveStockRequest {
+            reservation_id: order_id.clone(),
+            lines: vec![ReservationLine {
+                product_id: lamp.clone(),
+                quantity: 3,
+                backordered: false,
+            }],
+        }))
+        .await
+        .unwrap();
+    assert_eq!(app.stock_of(&lamp).await.unwrap(), 7);
+
+    let ops = ops_service(&app);
+    let released = ops
+        .force_release_reservations(app.admin.request(ForceReleaseReservationsRequest {
+            product_id: lamp.clone(),
+            actor: "jane.operator".to_string(),
+            reason: "Stuck checkout".to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(released.released_count, 1);
+    assert_eq!(released.failed_count, 0);
+    assert_eq!(app.stock_of(&lamp).await.unwrap(), 10);
+
+    let saga: String = sqlx::query_scalar("SELECT state FROM order_sagas WHERE order_id = $1")
+        .bind(&order_id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(saga, "COMPENSATED");
+    assert_eq!(audited_actions(&app).await, ["FORCE_RELEASE_RESERVATIONS"]);
+}
@@ -0,0 +1,372 @@
+//! The flows of `order/src/client.rs`, against services running in-process.
+
+use common::error;
+use proto::order::{
+    CancelOrderRequest, CreateOrderRequest, GetOrderRequest, GetOrdersByUserRequest,
+    ItemFulfillmentStatus, ListOrdersRequest, Order, OrderItem, OrderItemStatus, OrderSort,
+    OrderStatus, UpdateOrderRequest,
+};
+use testing::{TestApp, TestUser};
+use tonic::Code;
+
+fn item(product_id: &str, quantity: i32) -> OrderItem {
+    OrderItem {
+        product_id: product_id.to_string(),
+        product_name: String::new(),
+        quantity,
+        unit_price: String::new(),
+        subtotal: String::new(),
+        item_id: String::new(),
+        tracking: vec![],
+        status: OrderItemStatus::Allocated as i32,
+        tax_amount: String::new(),
+        fulfillment_status: ItemFulfillmentStatus::ItemUnfulfilled as i32,
+        shipment_tracking: String::new(),
+        shipped_at: 0,
+    }
+}
+
+/// Places an order of `items` as `user`.
+async fn create_order(app: &mut TestApp, user: &TestUser, items: Vec<OrderItem>) -> Order {
+    let response = app
+        .order
+        .create_order(user.request(CreateOrderRequest {
+            user_id: user.user_id.clone(),
+            items,
+            shipping_address: "123 Main St, City, State 12345".to_string(),
+            shipping_region: "US-CA".to_string(),
+            shipping_method: String::new(),
+            currency: String::new(),
+            billing_region: String::new(),
+            guest_email: String::new(),
+        }))
+        .await
+        .expect("order is created")
+        .into_inner();
+
+    assert!(response.success);
+    let order = response.order.expect("order is returned");
+    assert_eq!(order.order_id, response.order_id);
+    order
+}
+
+async fn get_order(app: &mut TestApp, order_id: &str) -> Order {
+    app.order
+        .get_order(app.admin.request(GetOrderRequest {
+            order_id: order_id.to_string(),
+            order_number: String::new(),
+        }))
+        .await
+        .expect("order is found")
+        .into_inner()
+        .order
+        .expect("order is returned")
+}
+
+#[tokio::test]
+async fn create_order_prices_items_and_takes_stock() {
+    let mut app = TestApp::spawn().await.unwrap();
+    let user = app.register_user("john_doe").await.unwrap();
+    let lamp = app.add_product("Desk Lamp", "24.50", 10).await.unwrap();
+    let mug = app.add_product("Mug", "8.00", 5).await.unwrap();
+
+    let order = create_order(&mut app, &user, vec![item(&lamp, 2), item(&mug, 1)]).await;
+
+    assert_eq!(order.user_id, user.user_id);
+    assert_eq!(order.status, OrderStatus::Pending as i32);
+    assert_eq!(order.total_amount, "57.00");
+    assert_eq!(order.items.len(), 2);
+    let lamp_item = order
+        .items
+        .iter()
+        .find(|i| i.product_id == lamp)
+        .expect("lamp is ordered");
+    assert_eq!(lamp_item.unit_price, "24.50");
+    assert_eq!(lamp_item.subtotal, "49.00");
+    assert!(!order.order_number.is_empty());
+
+    assert_eq!(app.stock_of(&lamp).await.unwrap(), 8);
+    assert_eq!(app.stock_of(&mug).await.unwrap(), 4);
+
+    let fetched = get_order(&mut app, &order.order_id).await;
+    assert_eq!(fetched.order_number, order.order_number);
+    assert_eq!(fetched.total_amount, order.total_amount);
+    assert_eq!(fetched.shipping_address, "123 Main St, City, State 12345");
+}
+
+#[tokio::test]
+async fn orders_are_placed_only_for_the_caller() {
+    let mut app = TestApp::spawn().await.unwrap();
+    let john = app.register_user("john_doe").await.unwrap();
+    let jane = app.register_user("jane_doe").await.unwrap();
+    let lamp = app.add_product("Desk Lamp", "24.50", 10).await.unwrap();
+
+    let status = app
+        .order
+        .create_order(jane.request(CreateOrderRequest {
+            user_id: john.user_id.clone(),
+            items: vec![item(&lamp, 1)],
+            shipping_address: "123 Main St, City, State 12345".to_string(),
+            shipping_region: "US-CA".to_string(),
+            shipping_method: String::new(),
+            currency: String::new(),
+            billing_region: String::new(),
+            guest_email: String::new(),
+        }))
+        .await
+        .expect_err("an order was placed for another user");
+    assert_eq!(status.code(), Code::PermissionDenied);
+    assert_eq!(app.stock_of(&lamp).await.unwrap(), 10);
+}
+
+#[tokio::test]
+async fn orders_are_listed_overall_and_by_user() {
+    let mut app = TestApp::spawn().await.unwrap();
+    let john = app.register_user("john_doe").await.unwrap();
+    let jane = app.register_user("jane_doe").await.unwrap();
+    let lamp = app.add_product("Desk Lamp", "24.50", 10).await.unwrap();
+
+    let first = create_order(&mut app, &john, vec![item(&lamp, 1)]).await;
+    let second = create_order(&mut app, &john, vec![item(&lamp, 2)]).await;
+    create_order(&mut app, &jane, vec![item(&lamp, 1)]).await;
+
+    let all = app
+        .order
+        .list_orders(app.admin.request(ListOrdersRequest {
+            page: 1,
+            page_size: 10,
+            status: 0,
+            page_token: String::new(),
+            created_after: 0,
+            created_before: 0,
+            min_amount: String::new(),
+            max_amount: String::new(),
+            user_id: String::new(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(all.total_count, 3);
+    assert_eq!(all.orders.len(), 3);
+
+    let johns = app
+        .order
+        .get_orders_by_user(john.request(GetOrdersByUserRequest {
+            user_id: john.user_id.clone(),
+            page: 1,
+            page_size: 10,
+            page_token: String::new(),
+            status: 0,
+            sort: OrderSort::OldestFirst as i32,
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(johns.total_count, 2);
+    let ids: Vec<&str> = johns.orders.iter().map(|o| o.order_id.as_str()).collect();
+    assert_eq!(ids, [first.order_id.as_str(), second.order_id.as_str()]);
+}
+
+#[tokio::test]
+async fn update_order_moves_status_and_keeps_unset_address() {
+    let mut app = TestApp::spawn().await.unwrap();
+    let user = app.register_user("john_doe").await.unwrap();
+    let lamp = app.add_product("Desk Lamp", "24.50", 10).await.unwrap();
+    let order = create_order(&mut app, &user, vec![item(&lamp, 1)]).await;
+
+    let confirmed = app
+        .order
+        .update_order(app.admin.request(UpdateOrderRequest {
+            order_id: order.order_id.clone(),
+            status: OrderStatus::Confirmed as i32,
+            shipping_address: "456 Updated St, New City, State 54321".to_string(),
+            reason: "Customer moved".to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .order
+        .expect("order is returned");
+    assert_eq!(confirmed.status, OrderStatus::Confirmed as i32);
+    assert_eq!(
+        confirmed.shipping_address,
+        "456 Updated St, New City, State 54321"
+    );
+
+    let processing = app
+        .order
+        .update_order(app.admin.request(UpdateOrderRequest {
+            order_id: order.order_id.clone(),
+            status: OrderStatus::Processing as i32,
+            shipping_address: String::new(),
+            reason: String::new(),
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .order
+        .expect("order is returned");
+    assert_eq!(processing.status, OrderStatus::Processing as i32);
+    assert_eq!(
+        processing.shipping_address,
+        "456 Updated St, New City, State 54321"
+    );
+
+    // The filters narrow the listing to it, and a minimum above its total
+    // leaves it out
+    let list = |min_amount: &str| {
+        app.admin.request(ListOrdersRequest {
+            page: 1,
+            page_size: 10,
+            status: OrderStatus::Processing as i32,
+            page_token: String::new(),
+            created_after: 0,
+            created_before: 0,
+            min_amount: min_amount.to_string(),
+            max_amount: String::new(),
+            user_id: user.user_id.clone(),
+        })
+    };
+    let matching = app.order.list_orders(list("10.00")).await.unwrap();
+    assert_eq!(matching.into_inner().total_count, 1);
+    let too_cheap = app.order.list_orders(list("100.00")).await.unwrap();
+    assert_eq!(too_cheap.into_inner().total_count, 0);
+}
+
+#[tokio::test]
+async fn cancel_order_restores_stock_once() {
+    let mut app = TestApp::spawn().await.unwrap();
+    let user = app.register_user("john_doe").await.unwrap();
+    let lamp = app.add_product("Desk Lamp", "24.50", 10).await.unwrap();
+    let order = create_order(&mut app, &user, vec![item(&lamp, 3)]).await;
+    assert_eq!(app.stock_of(&lamp).await.unwrap(), 7);
+
+    let cancel = || {
+        user.request(CancelOrderRequest {
+            order_id: order.order_id.clone(),
+            user_id: user.user_id.clone(),
+            reason: "Ordered by mistake".to_string(),
+        })
+    };
+    let cancelled = app.order.cancel_order(cancel()).await.unwrap();
+    assert!(cancelled.into_inner().success);
+    assert_eq!(
+        get_order(&mut app, &order.order_id).await.status,
+        OrderStatus::Cancelled as i32
+    );
+    assert_eq!(app.stock_of(&lamp).await.unwrap(), 10);
+
+    let status = app
+        .order
+        .cancel_order(cancel())
+        .await
+        .expect_err("cancelled order was cancelled again");
+    assert_eq!(status.code(), Code::FailedPrecondition);
+    assert_eq!(
+        error::error_info(&status).unwrap().reason,
+        "ORDER_CANCELLED"
+    );
+    assert_eq!(app.stock_of(&lamp).await.unwrap(), 10);
+}
+
+#[tokio::test]
+async fn shipped_orders_are_not_cancelled() {
+    let mut app = TestApp::spawn().await.unwrap();
+    let user = app.register_user("john_doe").await.unwrap();
+    let lamp = app.add_product("Desk Lamp", "24.50", 10).await.unwrap();
+    let order = create_order(&mut app, &user, vec![item(&lamp, 3)]).await;
+
+    // Shipped by the warehouse, as the shipping service would record it
+    sqlx::query("UPDATE orders SET status = 'SHIPPED' WHERE id = $1")
+        .bind(&order.order_id)
+        .execute(&app.db)
+        .await
+        .unwrap();
+
+    let status = app
+        .order
+        .cancel_order(user.request(CancelOrderRequest {
+            order_id: order.order_id.clone(),
+            user_id: user.user_id.clone(),
+            reason: "Changed my mind".to_string(),
+        }))
+        .await
+        .expect_err("a shipped order was cancelled");
+    assert_eq!(status.code(), Code::FailedPrecondition);
+    assert_eq!(error::error_info(&status).unwrap().reason, "ORDER_SHIPPED");
+    assert_eq!(app.stock_of(&lamp).await.unwrap(), 7);
+}
+
+#[tokio::test]
+async fn orders_are_private_to_their_owner() {
+    let mut app = TestApp::spawn().await.unwrap();
+    let john = app.register_user("john_doe").await.unwrap();
+    let jane = app.register_user("jane_doe").await.unwrap();
+    let lamp = app.add_product("Desk Lamp", "24.50", 10).await.unwrap();
+    let order = create_order(&mut app, &john, vec![item(&lamp, 1)]).await;
+
+    let status = app
+        .order
+        .cancel_order(jane.request(CancelOrderRequest {
+            order_id: order.order_id.clone(),
+            user_id: jane.user_id.clone(),
+            reason: String::new(),
+        }))
+        .await
+        .expect_err("another user cancelled the order");
+    assert_eq!(status.code(), Code::PermissionDenied);
+    assert_eq!(
+        get_order(&mut app, &order.order_id).await.status,
+        OrderStatus::Pending as i32
+    );
+}
+
+#[tokio::test]
+async fn orders_need_a_token_and_owners_only_change_the_address() {
+    let mut app = TestApp::spawn().await.unwrap();
+    let john = app.register_user("john_doe").await.unwrap();
+    let lamp = app.add_product("Desk Lamp", "24.50", 10).await.unwrap();
+    let order = create_order(&mut app, &john, vec![item(&lamp, 1)]).await;
+
+    let status = app
+        .order
+        .get_order(GetOrderRequest {
+            order_id: order.order_id.clone(),
+            order_number: String::new(),
+        })
+        .await
+        .expect_err("an anonymous caller read the order");
+    assert_eq!(status.code(), Code::Unauthenticated);
+
+    let update = |status: OrderStatus, shipping_address: &str| UpdateOrderRequest {
+        order_id: order.order_id.clone(),
+        status: status as i32,
+        shipping_address: shipping_address.to_string(),
+        reason: String::new(),
+    };
+    let status = app
+        .order
+        .update_order(update(OrderStatus::Delivered, ""))
+        .await
+        .expect_err("an anonymous caller updated the order");
+    assert_eq!(status.code(), Code::Unauthenticated);
+
+    let status = app
+        .order
+        .update_order(john.request(update(OrderStatus::Delivered, "")))
+        .await
+        .expect_err("the owner moved the order's status");
+    assert_eq!(status.code(), Code::PermissionDenied);
+    assert_eq!(error::error_info(&status).unwrap().reason, "ONLY_CANCEL");
+
+    let moved = app
+        .order
+        .update_order(john.request(update(OrderStatus::Pending, "456 Updated St")))
+        .await
+        .unwrap()
+        .into_inner()
+        .order
+        .expect("order is returned");
+    assert_eq!(moved.status, OrderStatus::Pending as i32);
+    assert_eq!(moved.shipping_address, "456 Updated St");
+}
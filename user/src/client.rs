@@ -1,11 +1,19 @@
+use common::auth::ServiceCredentials;
 use proto::user::{
     GetUserProfileRequest, LoginRequest, RegisterRequest, UpdateUserProfileRequest, VerifyRequest,
     user_service_client::UserServiceClient,
 };
+use tonic::transport::Channel;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let mut client = UserServiceClient::connect("http://127.0.0.1:50051").await?;
+    let channel = Channel::from_static("http://127.0.0.1:50051")
+        .connect()
+        .await?;
+    let mut client = UserServiceClient::new(channel.clone());
+    // Verification is for the platform's services
+    let mut platform_client =
+        UserServiceClient::with_interceptor(channel, ServiceCredentials("user-client"));
 
     println!("Connected to User Service");
     println!("=========================\n");
@@ -18,6 +26,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         password: "securepassword123".to_string(),
         full_name: "John Doe".to_string(),
         phone_number: "+1234567890".to_string(),
+        captcha_token: String::new(),
     };
 
     let register_response = client.register(register_request).await?;
@@ -30,7 +39,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Test 2: Login with the registered user
     println!("2. Testing User Login");
     let login_request = LoginRequest {
-        username: "john_doe".to_string(),
+        identifier: "john_doe".to_string(),
         password: "securepassword123".to_string(),
     };
 
@@ -58,7 +67,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         user_id: user_id.clone(),
     };
 
-    let verify_response = client.verify(verify_request).await?;
+    let verify_response = platform_client.verify(verify_request).await?;
     let verify_result = verify_response.into_inner();
     println!("Verify Response:");
     println!("  Valid: {}", verify_result.valid);
@@ -71,6 +80,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         user_id: user_id.clone(),
     };
 
+    // Users read their own profile, signed in
+    let mut profile_request = tonic::Request::new(profile_request);
+    profile_request.metadata_mut().insert(
+        "authorization",
+        format!("Bearer {}", login_result.token).parse()?,
+    );
     let profile_response = client.get_user_profile(profile_request).await?;
     let profile_result = profile_response.into_inner();
     println!("Get Profile Response:");
@@ -92,6 +107,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         phone_number: "+0987654321".to_string(),
     };
 
+    // Users update their own profile, signed in
+    let mut update_request = tonic::Request::new(update_request);
+    update_request.metadata_mut().insert(
+        "authorization",
+        format!("Bearer {}", login_result.token).parse()?,
+    );
     let update_response = client.update_user_profile(update_request).await?;
     let update_result = update_response.into_inner();
     println!("Update Profile Response:");
@@ -105,15 +126,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Test 6: Try to login with wrong password
     println!("6. Testing Login with Wrong Password");
     let wrong_login_request = LoginRequest {
-        username: "john_doe".to_string(),
+        identifier: "john_doe".to_string(),
         password: "wrongpassword".to_string(),
     };
 
-    let wrong_login_response = client.login(wrong_login_request).await?;
-    let wrong_login_result = wrong_login_response.into_inner();
+    // Refused logins fail with UNAUTHENTICATED
+    let wrong_login_status = client
+        .login(wrong_login_request)
+        .await
+        .expect_err("login with a wrong password succeeded");
     println!("Wrong Login Response:");
-    println!("  Success: {}", wrong_login_result.success);
-    println!("  Message: {}\n", wrong_login_result.message);
+    println!("  Code: {:?}", wrong_login_status.code());
+    println!("  Message: {}\n", wrong_login_status.message());
 
     // Test 7: Verify an invalid token
     println!("7. Testing Invalid Token Verification");
@@ -121,7 +145,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         user_id: "invalid_user_id".to_string(),
     };
 
-    let invalid_verify_response = client.verify(invalid_verify_request).await?;
+    let invalid_verify_response = platform_client.verify(invalid_verify_request).await?;
     let invalid_verify_result = invalid_verify_response.into_inner();
     println!("Invalid Verify Response:");
     println!("  Valid: {}", invalid_verify_result.valid);
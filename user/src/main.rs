@@ -1,29 +1,27 @@
-mod user;
-
 use anyhow::Result;
 use proto::user::user_service_server::UserServiceServer;
 use sqlx::postgres::PgPoolOptions;
 use std::env;
+use std::sync::Arc;
 use std::time::Duration;
-use tonic::transport::Server;
-use tracing::{Level, info};
-use tracing_subscriber::FmtSubscriber;
-use user::UserServiceImpl;
-use common::logging::LoggingLayer;
-use common::ratelimit::RateLimitLayer;
+use tracing::{info, warn};
+use user::repository::PostgresUserRepository;
+use user::user::UserServiceImpl;
+use common::cache::Cache;
+use common::health::HealthCheck;
+use common::ratelimit::{Algorithm, RateLimitLayer};
+use common::server::ServerBuilder;
+use common::response_cache::ResponseCache;
+use common::metrics::{self, Metrics};
+use common::telemetry;
+use common::slo::{SloConfig, SloServiceImpl, SloTracker};
+use proto::slo::slo_service_server::SloServiceServer;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     dotenvy::dotenv().ok();
 
-    // Initialize tracing subscriber
-    let subscriber = FmtSubscriber::builder()
-        .with_max_level(Level::INFO)
-        .with_target(false)
-        .with_thread_ids(true)
-        .with_line_number(true)
-        .finish();
-    tracing::subscriber::set_global_default(subscriber).expect("Failed to set tracing subscriber");
+    telemetry::init("user", env!("CARGO_PKG_VERSION"))?;
 
     let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
 
@@ -36,18 +34,64 @@ async fn main() -> Result<()> {
     info!("Connected to database");
 
     let addr = "0.0.0.0:50051".parse()?;
-    let user_service = UserServiceImpl::new(pool);
+    if common::sandbox::enabled() {
+        warn!("Sandbox mode: external providers are replaced by deterministic fakes");
+    }
+    let captcha = common::captcha::from_env()?;
+    if captcha.is_some() {
+        info!("Captcha verification enabled for registration");
+    }
+    let response_cache = ResponseCache::new()
+        .with_rule("/user.UserService/GetUserProfile", Duration::from_secs(30))
+        .build();
+    let health_interval_secs: u64 = env::var("HEALTH_CHECK_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(5);
+    let health_service = HealthCheck::new(pool.clone())
+        .with_service::<UserServiceServer<UserServiceImpl>>()
+        .start(Duration::from_secs(health_interval_secs))
+        .await;
+    let user_cache = Arc::new(Cache::from_env("user").await?);
+    let user_service = UserServiceImpl::new(
+        Arc::new(PostgresUserRepository::new(pool)),
+        captcha,
+        response_cache.clone(),
+        user_cache,
+    );
 
     info!("User service listening on {}", addr);
 
-    let ratelimiter = RateLimitLayer::new(10, Duration::from_secs(60));
+    let slo_tracker = SloTracker::new("user", SloConfig::from_env()?);
+    let metrics = Metrics::new("user");
+    let metrics_addr = env::var("METRICS_ADDR")
+        .unwrap_or_else(|_| "0.0.0.0:9101".to_string())
+        .parse()?;
+    metrics::spawn(metrics_addr, metrics.clone());
+
+    // Credentials are worth guessing at, so logins and registrations get a
+    // tight budget; everything else, other services' lookups included, a
+    // generous one
+    let ratelimiter = RateLimitLayer::token_bucket(100, 100, Duration::from_secs(60))
+        .with_rule(
+            "/user.UserService/Login",
+            Algorithm::token_bucket(5, 5, Duration::from_secs(60)),
+        )
+        .with_rule(
+            "/user.UserService/Register",
+            Algorithm::token_bucket(5, 5, Duration::from_secs(60)),
+        );
 
-    Server::builder()
-        .layer(LoggingLayer)
-        .layer(ratelimiter)
+    ServerBuilder::new(metrics, slo_tracker.clone())
+        .with_rate_limit(ratelimiter)
+        .with_response_cache(response_cache)
+        .build()?
+        .add_service(health_service)
         .add_service(UserServiceServer::new(user_service))
+        .add_service(SloServiceServer::new(SloServiceImpl::new(slo_tracker)))
         .serve(addr)
         .await?;
 
+    telemetry::shutdown();
     Ok(())
 }
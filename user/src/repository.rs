@@ -0,0 +1,397 @@
+//! Where the user service keeps its users. The service reads and writes
+//! them through `UserRepository` only, so it runs against Postgres in
+//! production and against `InMemoryUserRepository` in tests that have no
+//! database.
+
+use crate::user::is_email;
+use chrono::{NaiveDateTime, Utc};
+use common::auth::Role;
+use common::error::{self, AppError};
+use common::events;
+use proto::events::UserRegistered;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tonic::Status;
+use tracing::{error, warn};
+use uuid::Uuid;
+
+const USER_COLUMNS: &str =
+    "id, username, email, password_hash, email_verified_at, is_guest, created_at, updated_at";
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct DbUser {
+    pub id: String,
+    pub username: String,
+    pub email: String,
+    pub password_hash: String,
+    pub email_verified_at: Option<NaiveDateTime>,
+    pub is_guest: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// A user about to be written, its password already hashed.
+pub struct NewUser<'a> {
+    pub id: &'a str,
+    pub username: &'a str,
+    pub email: &'a str,
+    pub password_hash: &'a str,
+    pub role: Role,
+}
+
+#[tonic::async_trait]
+pub trait UserRepository: Send + Sync {
+    /// The registered user signing in as `identifier`: an email when it
+    /// contains '@', a username otherwise. Guests never sign in.
+    async fn find_by_login(&self, identifier: &str) -> Result<Option<DbUser>, Status>;
+
+    async fn find(&self, user_id: &str) -> Result<Option<DbUser>, Status>;
+
+    /// Users of `user_ids` that exist, in no particular order.
+    async fn find_many(&self, user_ids: &[String]) -> Result<Vec<DbUser>, Status>;
+
+    /// Writes `user` and announces its registration. Fails with USER_EXISTS
+    /// when the username or email is taken.
+    async fn insert(&self, user: NewUser<'_>) -> Result<(), Status>;
+
+    /// Changes a user's email, which then needs verifying again unless it's
+    /// the same address; `None` when there's no such user.
+    async fn update_email(&self, user_id: &str, email: &str) -> Result<Option<DbUser>, Status>;
+
+    /// Marks the email of a registered user verified, provided it's still
+    /// the normalized address `email`; `None` when there's no such user
+    /// with that address.
+    async fn verify_email(&self, user_id: &str, email: &str) -> Result<Option<DbUser>, Status>;
+
+    /// The role a user signs in with, and the seller account they own.
+    async fn role(&self, user_id: &str) -> Result<(Role, Option<String>), Status>;
+
+    /// Id of the guest identity of the normalized address `email`, created
+    /// when there's none and `create` is set.
+    async fn guest_identity(&self, email: &str, create: bool) -> Result<Option<String>, Status>;
+}
+
+fn user_not_found() -> Status {
+    error::not_found("USER_NOT_FOUND", "User not found")
+}
+
+fn user_exists(username: &str) -> Status {
+    warn!(
+        "Registration failed: username or email already exists: {}",
+        username
+    );
+    error::already_exists("USER_EXISTS", "Username or email already exists")
+}
+
+pub struct PostgresUserRepository {
+    db: PgPool,
+}
+
+impl PostgresUserRepository {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+}
+
+#[tonic::async_trait]
+impl UserRepository for PostgresUserRepository {
+    async fn find_by_login(&self, identifier: &str) -> Result<Option<DbUser>, Status> {
+        let query = if is_email(identifier) {
+            format!(
+                "SELECT {} FROM users WHERE LOWER(email) = LOWER($1) AND NOT is_guest",
+                USER_COLUMNS
+            )
+        } else {
+            format!(
+                "SELECT {} FROM users WHERE username = $1 AND NOT is_guest",
+                USER_COLUMNS
+            )
+        };
+
+        sqlx::query_as::<_, DbUser>(&query)
+            .bind(identifier)
+            .fetch_optional(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Database error during login: {}", e);
+                AppError::from(e).into()
+            })
+    }
+
+    async fn find(&self, user_id: &str) -> Result<Option<DbUser>, Status> {
+        sqlx::query_as::<_, DbUser>(&format!("SELECT {} FROM users WHERE id = $1", USER_COLUMNS))
+            .bind(user_id)
+            .fetch_optional(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Database error while fetching user: {}", e);
+                AppError::from(e).into()
+            })
+    }
+
+    async fn find_many(&self, user_ids: &[String]) -> Result<Vec<DbUser>, Status> {
+        sqlx::query_as::<_, DbUser>(&format!(
+            "SELECT {} FROM users WHERE id = ANY($1)",
+            USER_COLUMNS
+        ))
+        .bind(user_ids)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| {
+            error!("Database error while fetching users by ids: {}", e);
+            AppError::from(e).into()
+        })
+    }
+
+    async fn insert(&self, user: NewUser<'_>) -> Result<(), Status> {
+        let mut tx = self.db.begin().await.map_err(AppError::from)?;
+        let result = sqlx::query(
+            "INSERT INTO users (id, username, email, password_hash, role) VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(user.id)
+        .bind(user.username)
+        .bind(user.email)
+        .bind(user.password_hash)
+        .bind(user.role.as_str())
+        .execute(&mut *tx)
+        .await;
+
+        match result {
+            Ok(_) => {
+                events::emit(
+                    &mut *tx,
+                    &UserRegistered {
+                        user_id: user.id.to_string(),
+                        username: user.username.to_string(),
+                        email: user.email.to_string(),
+                        registered_at: Utc::now().timestamp(),
+                    },
+                )
+                .await
+                .map_err(AppError::from)?;
+                tx.commit().await.map_err(AppError::from)?;
+                Ok(())
+            }
+            Err(e) => {
+                if e.to_string().contains("duplicate key") {
+                    Err(user_exists(user.username))
+                } else {
+                    error!("Database error during registration: {}", e);
+                    Err(AppError::from(e).into())
+                }
+            }
+        }
+    }
+
+    async fn update_email(&self, user_id: &str, email: &str) -> Result<Option<DbUser>, Status> {
+        // A new address isn't verified yet
+        sqlx::query_as::<_, DbUser>(&format!(
+            "UPDATE users
+             SET email = $1, updated_at = CURRENT_TIMESTAMP,
+                 email_verified_at = CASE WHEN LOWER(email) = LOWER($1) THEN email_verified_at END
+             WHERE id = $2
+             RETURNING {}",
+            USER_COLUMNS
+        ))
+        .bind(email)
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| {
+            error!("Database error during profile update: {}", e);
+            AppError::from(e).into()
+        })
+    }
+
+    async fn verify_email(&self, user_id: &str, email: &str) -> Result<Option<DbUser>, Status> {
+        sqlx::query_as::<_, DbUser>(&format!(
+            "UPDATE users
+             SET email_verified_at = COALESCE(email_verified_at, CURRENT_TIMESTAMP)
+             WHERE id = $1 AND LOWER(email) = $2 AND NOT is_guest
+             RETURNING {}",
+            USER_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(email)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| AppError::from(e).into())
+    }
+
+    async fn role(&self, user_id: &str) -> Result<(Role, Option<String>), Status> {
+        let row: Option<(String, Option<String>)> = sqlx::query_as(
+            "SELECT u.role, s.id FROM users u
+             LEFT JOIN sellers s ON s.user_id = u.id
+             WHERE u.id = $1",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(AppError::from)?;
+
+        let (role, seller_id) = row.ok_or_else(user_not_found)?;
+        Ok((Role::parse(&role), seller_id))
+    }
+
+    async fn guest_identity(&self, email: &str, create: bool) -> Result<Option<String>, Status> {
+        // Guests can't log in: they have no password, and find_by_login
+        // skips them
+        if create {
+            sqlx::query(
+                "INSERT INTO users (id, username, email, password_hash, is_guest)
+                 VALUES ($1, $2, $3, '', TRUE)
+                 ON CONFLICT (LOWER(email)) WHERE is_guest DO NOTHING",
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(format!("guest-{}", Uuid::new_v4()))
+            .bind(email)
+            .execute(&self.db)
+            .await
+            .map_err(AppError::from)?;
+        }
+
+        sqlx::query_scalar("SELECT id FROM users WHERE LOWER(email) = $1 AND is_guest")
+            .bind(email)
+            .fetch_optional(&self.db)
+            .await
+            .map_err(|e| AppError::from(e).into())
+    }
+}
+
+struct StoredUser {
+    user: DbUser,
+    role: Role,
+    seller_id: Option<String>,
+}
+
+/// Users kept in memory, for tests. Registrations aren't announced.
+#[derive(Default)]
+pub struct InMemoryUserRepository {
+    users: Mutex<HashMap<String, StoredUser>>,
+    /// Guest identities by email.
+    guests: Mutex<HashMap<String, String>>,
+}
+
+impl InMemoryUserRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Makes `user_id` the owner of the seller account `seller_id`; unknown
+    /// users are left as they are.
+    pub fn set_seller(&self, user_id: &str, seller_id: &str) {
+        if let Some(stored) = self.users.lock().unwrap().get_mut(user_id) {
+            stored.seller_id = Some(seller_id.to_string());
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl UserRepository for InMemoryUserRepository {
+    async fn find_by_login(&self, identifier: &str) -> Result<Option<DbUser>, Status> {
+        let users = self.users.lock().unwrap();
+        Ok(users
+            .values()
+            .find(|stored| {
+                if is_email(identifier) {
+                    stored.user.email.eq_ignore_ascii_case(identifier)
+                } else {
+                    stored.user.username == identifier
+                }
+            })
+            .map(|stored| stored.user.clone()))
+    }
+
+    async fn find(&self, user_id: &str) -> Result<Option<DbUser>, Status> {
+        let users = self.users.lock().unwrap();
+        Ok(users.get(user_id).map(|stored| stored.user.clone()))
+    }
+
+    async fn find_many(&self, user_ids: &[String]) -> Result<Vec<DbUser>, Status> {
+        let users = self.users.lock().unwrap();
+        Ok(user_ids
+            .iter()
+            .filter_map(|id| users.get(id))
+            .map(|stored| stored.user.clone())
+            .collect())
+    }
+
+    async fn insert(&self, user: NewUser<'_>) -> Result<(), Status> {
+        let mut users = self.users.lock().unwrap();
+        let taken = users
+            .values()
+            .any(|stored| stored.user.username == user.username || stored.user.email == user.email);
+        if taken || users.contains_key(user.id) {
+            return Err(user_exists(user.username));
+        }
+
+        let now = Utc::now().naive_utc();
+        users.insert(
+            user.id.to_string(),
+            StoredUser {
+                user: DbUser {
+                    id: user.id.to_string(),
+                    username: user.username.to_string(),
+                    email: user.email.to_string(),
+                    password_hash: user.password_hash.to_string(),
+                    email_verified_at: None,
+                    is_guest: false,
+                    created_at: now,
+                    updated_at: now,
+                },
+                role: user.role,
+                seller_id: None,
+            },
+        );
+        Ok(())
+    }
+
+    async fn update_email(&self, user_id: &str, email: &str) -> Result<Option<DbUser>, Status> {
+        let mut users = self.users.lock().unwrap();
+        if users
+            .values()
+            .any(|stored| stored.user.id != user_id && stored.user.email == email)
+        {
+            return Err(AppError::Conflict("Conflicts with an existing record".to_string()).into());
+        }
+        Ok(users.get_mut(user_id).map(|stored| {
+            if !stored.user.email.eq_ignore_ascii_case(email) {
+                stored.user.email_verified_at = None;
+            }
+            stored.user.email = email.to_string();
+            stored.user.updated_at = Utc::now().naive_utc();
+            stored.user.clone()
+        }))
+    }
+
+    async fn verify_email(&self, user_id: &str, email: &str) -> Result<Option<DbUser>, Status> {
+        let mut users = self.users.lock().unwrap();
+        Ok(users
+            .get_mut(user_id)
+            .filter(|stored| stored.user.email.to_lowercase() == email)
+            .map(|stored| {
+                stored
+                    .user
+                    .email_verified_at
+                    .get_or_insert_with(|| Utc::now().naive_utc());
+                stored.user.clone()
+            }))
+    }
+
+    async fn role(&self, user_id: &str) -> Result<(Role, Option<String>), Status> {
+        let users = self.users.lock().unwrap();
+        let stored = users.get(user_id).ok_or_else(user_not_found)?;
+        Ok((stored.role, stored.seller_id.clone()))
+    }
+
+    async fn guest_identity(&self, email: &str, create: bool) -> Result<Option<String>, Status> {
+        let mut guests = self.guests.lock().unwrap();
+        if create {
+            guests
+                .entry(email.to_string())
+                .or_insert_with(|| Uuid::new_v4().to_string());
+        }
+        Ok(guests.get(email).cloned())
+    }
+}
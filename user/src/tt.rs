@@ -29,7 +29,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             };
 
             let mut login_request = Request::new(LoginRequest {
-                username: "john_doe".to_string(),
+                identifier: "john_doe".to_string(),
                 password: "securepassword123".to_string(),
             });
 
@@ -1,71 +1,102 @@
+use crate::repository::{DbUser, NewUser, UserRepository};
 use anyhow::Result;
 use bcrypt::{DEFAULT_COST, hash, verify};
-use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use common::auth::{self, Caller, Role};
+use common::cache::Cache;
+use common::captcha::CaptchaVerifier;
+use common::error::{self, AppError};
+use common::forwarded;
+use common::response_cache::ResponseCache;
 use proto::user::{
-    GetUserProfileRequest, GetUserProfileResponse, LoginRequest, LoginResponse, RegisterRequest,
-    RegisterResponse, UpdateUserProfileRequest, UpdateUserProfileResponse, User, VerifyRequest,
-    VerifyResponse, user_service_server::UserService,
+    ConfirmEmailRequest, ConfirmEmailResponse, CreateAdminRequest, CreateAdminResponse,
+    GetGuestIdentityRequest, GetGuestIdentityResponse, GetUserProfileRequest,
+    GetUserProfileResponse, GetUsersByIDsRequest, GetUsersByIDsResponse, LoginRequest,
+    LoginResponse, RegisterRequest, RegisterResponse, UpdateUserProfileRequest,
+    UpdateUserProfileResponse, User, UserSummary, VerifyRequest, VerifyResponse,
+    user_service_server::UserService,
 };
-use serde::{Deserialize, Serialize};
-use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
 use tonic::{Request, Response, Status};
-use tracing::{error, info, warn};
+use tracing::{info, warn};
 use uuid::Uuid;
 
-const JWT_SECRET: &str = "your-secret-key-change-in-production";
-const TOKEN_EXPIRATION_HOURS: i64 = 24;
+const MAX_USERS_PER_BATCH: usize = 500;
 
-#[derive(Debug, Serialize, Deserialize)]
-struct Claims {
-    sub: String, // user_id
-    exp: i64,    // expiration time
-    iat: i64,    // issued at
+/// How long a verified user stays in the shared cache.
+const USER_CACHE_TTL: Duration = Duration::from_secs(300);
+
+pub(crate) fn is_email(identifier: &str) -> bool {
+    identifier.contains('@')
 }
 
-#[derive(Debug, sqlx::FromRow)]
-struct DbUser {
-    id: String,
-    username: String,
-    email: String,
-    password_hash: String,
-    created_at: chrono::NaiveDateTime,
-    updated_at: chrono::NaiveDateTime,
+/// Normalizes an email address, e.g. " Ann@Example.com" -> "ann@example.com".
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
 }
 
 pub struct UserServiceImpl {
-    db: PgPool,
+    repository: Arc<dyn UserRepository>,
+    captcha: Option<Arc<dyn CaptchaVerifier>>,
+    cache: Arc<ResponseCache>,
+    /// Users by id, shared by the instances, for `verify`.
+    users: Arc<Cache>,
 }
 
 impl UserServiceImpl {
-    pub fn new(db: PgPool) -> Self {
-        Self { db }
+    pub fn new(
+        repository: Arc<dyn UserRepository>,
+        captcha: Option<Arc<dyn CaptchaVerifier>>,
+        cache: Arc<ResponseCache>,
+        users: Arc<Cache>,
+    ) -> Self {
+        Self {
+            repository,
+            captcha,
+            cache,
+            users,
+        }
     }
 
-    fn generate_token(&self, user_id: &str) -> Result<String> {
-        let now = chrono::Utc::now().timestamp();
-        let claims = Claims {
-            sub: user_id.to_string(),
-            exp: now + (TOKEN_EXPIRATION_HOURS * 3600),
-            iat: now,
-        };
+    /// Issues the login token. Users owning a seller account are signed in
+    /// as that seller unless they are admins.
+    async fn generate_token(&self, user_id: &str) -> Result<String> {
+        let (role, seller_id) = self.repository.role(user_id).await?;
 
-        let token = encode(
-            &Header::default(),
-            &claims,
-            &EncodingKey::from_secret(JWT_SECRET.as_bytes()),
-        )?;
+        let role = match role {
+            Role::Customer if seller_id.is_some() => Role::Seller,
+            role => role,
+        };
+        let seller_id = seller_id.filter(|_| role == Role::Seller);
 
-        Ok(token)
+        Ok(auth::issue_token(user_id, role, seller_id.as_deref())?)
     }
 
-    fn verify_token(&self, token: &str) -> Result<String> {
-        let token_data = decode::<Claims>(
-            token,
-            &DecodingKey::from_secret(JWT_SECRET.as_bytes()),
-            &Validation::default(),
-        )?;
+    /// Inserts a user signing in as `role`, announcing it in the same
+    /// transaction, and returns its id.
+    async fn insert_user(
+        &self,
+        username: &str,
+        email: &str,
+        password: &str,
+        role: Role,
+    ) -> Result<String, Status> {
+        // Hash password
+        let password_hash = hash(password, DEFAULT_COST)
+            .map_err(|e| AppError::Internal(format!("Failed to hash password: {}", e)))?;
+
+        let user_id = Uuid::new_v4().to_string();
+        self.repository
+            .insert(NewUser {
+                id: &user_id,
+                username,
+                email,
+                password_hash: &password_hash,
+                role,
+            })
+            .await?;
 
-        Ok(token_data.claims.sub)
+        Ok(user_id)
     }
 
     fn db_user_to_proto(&self, db_user: &DbUser) -> User {
@@ -77,6 +108,8 @@ impl UserServiceImpl {
             phone_number: String::new(), // Not stored in current schema
             created_at: db_user.created_at.and_utc().timestamp(),
             updated_at: db_user.updated_at.and_utc().timestamp(),
+            email_verified: db_user.email_verified_at.is_some(),
+            guest: db_user.is_guest,
         }
     }
 }
@@ -87,66 +120,51 @@ impl UserService for UserServiceImpl {
         &self,
         request: Request<RegisterRequest>,
     ) -> Result<Response<RegisterResponse>, Status> {
+        // The client's own address, not the proxies' it came through
+        let remote_ip = forwarded::grpc_client_address(&request);
         let req = request.into_inner();
 
-        // Validate input
-        if req.username.is_empty() || req.email.is_empty() || req.password.is_empty() {
-            warn!("Register validation failed: missing required fields");
-            return Ok(Response::new(RegisterResponse {
-                success: false,
-                message: "Username, email, and password are required".to_string(),
-                user_id: String::new(),
-            }));
+        // Field rules are checked by the validation layer (see user.proto)
+        if is_email(&req.username) {
+            warn!("Register validation failed: username contains '@'");
+            return Err(error::invalid_argument(
+                "INVALID_USERNAME",
+                "Username cannot contain '@'",
+            ));
         }
 
-        // Hash password
-        let password_hash = hash(&req.password, DEFAULT_COST).map_err(|e| {
-            error!("Failed to hash password: {}", e);
-            Status::internal(format!("Failed to hash password: {}", e))
-        })?;
-
-        let user_id = Uuid::new_v4().to_string();
-
-        // Insert user into database
-        let result = sqlx::query(
-            "INSERT INTO users (id, username, email, password_hash) VALUES ($1, $2, $3, $4)",
-        )
-        .bind(&user_id)
-        .bind(&req.username)
-        .bind(&req.email)
-        .bind(&password_hash)
-        .execute(&self.db)
-        .await;
-
-        match result {
-            Ok(_) => {
-                info!(
-                    "User registered successfully: {} ({})",
-                    req.username, user_id
+        // Verify captcha when enabled
+        if let Some(captcha) = &self.captcha {
+            let passed = captcha
+                .verify(&req.captcha_token, remote_ip.as_deref())
+                .await
+                .map_err(|e| AppError::Unavailable(format!("Captcha verification error: {}", e)))?;
+
+            if !passed {
+                warn!(
+                    "Register rejected: invalid captcha for user: {}",
+                    req.username
                 );
-                Ok(Response::new(RegisterResponse {
-                    success: true,
-                    message: "User registered successfully".to_string(),
-                    user_id,
-                }))
-            }
-            Err(e) => {
-                if e.to_string().contains("duplicate key") {
-                    warn!(
-                        "Registration failed: username or email already exists: {}",
-                        req.username
-                    );
-                    Ok(Response::new(RegisterResponse {
-                        success: false,
-                        message: "Username or email already exists".to_string(),
-                        user_id: String::new(),
-                    }))
-                } else {
-                    error!("Database error during registration: {}", e);
-                    Err(Status::internal(format!("Database error: {}", e)))
-                }
+                return Err(error::failed_precondition(
+                    "CAPTCHA_FAILED",
+                    "Captcha verification failed",
+                ));
             }
         }
+
+        let user_id = self
+            .insert_user(&req.username, &req.email, &req.password, Role::Customer)
+            .await?;
+
+        info!(
+            "User registered successfully: {} ({})",
+            req.username, user_id
+        );
+        Ok(Response::new(RegisterResponse {
+            success: true,
+            message: "User registered successfully".to_string(),
+            user_id,
+        }))
     }
 
     async fn login(
@@ -155,56 +173,54 @@ impl UserService for UserServiceImpl {
     ) -> Result<Response<LoginResponse>, Status> {
         let req = request.into_inner();
 
-        // Fetch user from database
-        let user_result = sqlx::query_as::<_, DbUser>(
-            "SELECT id, username, email, password_hash, created_at, updated_at FROM users WHERE username = $1",
-        )
-        .bind(&req.username)
-        .fetch_optional(&self.db)
-        .await
-        .map_err(|e| {
-            error!("Database error during login: {}", e);
-            Status::internal(format!("Database error: {}", e))
-        })?;
+        if req.identifier.is_empty() || req.password.is_empty() {
+            warn!("Login validation failed: missing credentials");
+            return Err(error::invalid_argument(
+                "MISSING_CREDENTIALS",
+                "Username or email, and password are required",
+            ));
+        }
+
+        // Usernames can't contain '@', so anything that does is treated as an
+        // email. Guest identities have no password and never log in
+        let user_result = self.repository.find_by_login(&req.identifier).await?;
 
         let user = match user_result {
             Some(u) => u,
             None => {
-                warn!("Login failed: user not found: {}", req.username);
-                return Ok(Response::new(LoginResponse {
-                    success: false,
-                    message: "Invalid username or password".to_string(),
-                    token: String::new(),
-                    user: None,
-                }));
+                // The identifier may be an email address, so it isn't logged
+                warn!(
+                    by_email = is_email(&req.identifier),
+                    "Login failed: user not found"
+                );
+                return Err(error::unauthenticated(
+                    "INVALID_CREDENTIALS",
+                    "Invalid username or password",
+                ));
             }
         };
 
         // Verify password
-        let password_valid = verify(&req.password, &user.password_hash).map_err(|e| {
-            error!("Password verification error: {}", e);
-            Status::internal(format!("Password verification error: {}", e))
-        })?;
+        let password_valid = verify(&req.password, &user.password_hash)
+            .map_err(|e| AppError::Internal(format!("Password verification error: {}", e)))?;
 
         if !password_valid {
-            warn!("Login failed: invalid password for user: {}", req.username);
-            return Ok(Response::new(LoginResponse {
-                success: false,
-                message: "Invalid username or password".to_string(),
-                token: String::new(),
-                user: None,
-            }));
+            warn!("Login failed: invalid password for user: {}", user.id);
+            return Err(error::unauthenticated(
+                "INVALID_CREDENTIALS",
+                "Invalid username or password",
+            ));
         }
 
         // Generate JWT token
-        let token = self.generate_token(&user.id).map_err(|e| {
-            error!("Token generation error: {}", e);
-            Status::internal(format!("Token generation error: {}", e))
-        })?;
+        let token = self
+            .generate_token(&user.id)
+            .await
+            .map_err(|e| AppError::Internal(format!("Token generation error: {}", e)))?;
 
         info!(
             "User logged in successfully: {} ({})",
-            req.username, user.id
+            user.username, user.id
         );
         Ok(Response::new(LoginResponse {
             success: true,
@@ -218,24 +234,31 @@ impl UserService for UserServiceImpl {
         &self,
         request: Request<VerifyRequest>,
     ) -> Result<Response<VerifyResponse>, Status> {
+        // Tells whether any user exists: for the other services only
+        Caller::from_metadata(request.metadata())?.require_platform()?;
         let req = request.into_inner();
 
-        let user = self
-            .get_user_profile(Request::new(GetUserProfileRequest {
-                user_id: req.user_id.clone(),
-            }))
-            .await?;
-        let user_result = user.into_inner();
+        // An unknown user is reported invalid rather than failing the call
+        let user = match self.users.get::<User>(&req.user_id).await {
+            Some(user) => Some(user),
+            None => {
+                let user = self
+                    .repository
+                    .find(&req.user_id)
+                    .await?
+                    .map(|user| self.db_user_to_proto(&user));
+                if let Some(user) = &user {
+                    self.users.set(&user.user_id, user, USER_CACHE_TTL).await;
+                }
+                user
+            }
+        };
 
-        if user_result.success && user_result.user.is_some() {
+        if let Some(user) = user {
             info!("User verified successfully: {}", req.user_id);
             Ok(Response::new(VerifyResponse {
                 valid: true,
-                user_id: user_result
-                    .user
-                    .as_ref()
-                    .map(|u| u.user_id.clone())
-                    .unwrap_or_default(),
+                user_id: user.user_id,
                 message: "User is valid".to_string(),
             }))
         } else {
@@ -252,22 +275,15 @@ impl UserService for UserServiceImpl {
         &self,
         request: Request<GetUserProfileRequest>,
     ) -> Result<Response<GetUserProfileResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
         let req = request.into_inner();
+        caller.require_owner(&req.user_id)?;
         info!(
             "Get user profile request received for user_id: {}",
             req.user_id
         );
 
-        let user_result = sqlx::query_as::<_, DbUser>(
-            "SELECT id, username, email, password_hash, created_at, updated_at FROM users WHERE id = $1",
-        )
-        .bind(&req.user_id)
-        .fetch_optional(&self.db)
-        .await
-        .map_err(|e| {
-            error!("Database error while fetching user profile: {}", e);
-            Status::internal(format!("Database error: {}", e))
-        })?;
+        let user_result = self.repository.find(&req.user_id).await?;
 
         match user_result {
             Some(user) => {
@@ -280,11 +296,7 @@ impl UserService for UserServiceImpl {
             }
             None => {
                 warn!("User profile not found: {}", req.user_id);
-                Ok(Response::new(GetUserProfileResponse {
-                    success: false,
-                    message: "User not found".to_string(),
-                    user: None,
-                }))
+                Err(error::not_found("USER_NOT_FOUND", "User not found"))
             }
         }
     }
@@ -293,48 +305,32 @@ impl UserService for UserServiceImpl {
         &self,
         request: Request<UpdateUserProfileRequest>,
     ) -> Result<Response<UpdateUserProfileResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
         let req = request.into_inner();
+        caller.require_owner(&req.user_id)?;
         info!(
             "Update user profile request received for user_id: {}",
             req.user_id
         );
 
-        // Update user in database
-        let result = sqlx::query(
-            "UPDATE users SET email = $1, updated_at = CURRENT_TIMESTAMP WHERE id = $2",
-        )
-        .bind(&req.email)
-        .bind(&req.user_id)
-        .execute(&self.db)
-        .await
-        .map_err(|e| {
-            error!("Database error during profile update: {}", e);
-            Status::internal(format!("Database error: {}", e))
-        })?;
-
-        if result.rows_affected() == 0 {
-            warn!(
-                "User profile update failed: user not found: {}",
-                req.user_id
-            );
-            return Ok(Response::new(UpdateUserProfileResponse {
-                success: false,
-                message: "User not found".to_string(),
-                user: None,
-            }));
-        }
+        let user = match self
+            .repository
+            .update_email(&req.user_id, &req.email)
+            .await?
+        {
+            Some(user) => user,
+            None => {
+                warn!(
+                    "User profile update failed: user not found: {}",
+                    req.user_id
+                );
+                return Err(error::not_found("USER_NOT_FOUND", "User not found"));
+            }
+        };
 
-        // Fetch updated user
-        let user = sqlx::query_as::<_, DbUser>(
-            "SELECT id, username, email, password_hash, created_at, updated_at FROM users WHERE id = $1",
-        )
-        .bind(&req.user_id)
-        .fetch_one(&self.db)
-        .await
-        .map_err(|e| {
-            error!("Database error fetching updated user: {}", e);
-            Status::internal(format!("Database error: {}", e))
-        })?;
+        self.cache
+            .invalidate_prefix("/user.UserService/GetUserProfile");
+        self.users.invalidate(&[&req.user_id]).await;
 
         info!("User profile updated successfully: {}", req.user_id);
         Ok(Response::new(UpdateUserProfileResponse {
@@ -343,4 +339,149 @@ impl UserService for UserServiceImpl {
             user: Some(self.db_user_to_proto(&user)),
         }))
     }
+
+    async fn get_users_by_ids(
+        &self,
+        request: Request<GetUsersByIDsRequest>,
+    ) -> Result<Response<GetUsersByIDsResponse>, Status> {
+        // Email addresses of any user: for the other services only
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let mut req = request.into_inner();
+
+        req.user_ids.sort();
+        req.user_ids.dedup();
+
+        if req.user_ids.is_empty() {
+            return Ok(Response::new(GetUsersByIDsResponse { users: vec![] }));
+        }
+
+        if req.user_ids.len() > MAX_USERS_PER_BATCH {
+            warn!(
+                "GetUsersByIDs rejected: {} ids requested, limit is {}",
+                req.user_ids.len(),
+                MAX_USERS_PER_BATCH
+            );
+            return Err(error::invalid_argument(
+                "TOO_MANY_USER_IDS",
+                format!(
+                    "At most {} user IDs can be requested at once",
+                    MAX_USERS_PER_BATCH
+                ),
+            ));
+        }
+
+        let users = self.repository.find_many(&req.user_ids).await?;
+
+        info!(
+            "Retrieved {} of {} requested users",
+            users.len(),
+            req.user_ids.len()
+        );
+        Ok(Response::new(GetUsersByIDsResponse {
+            users: users
+                .into_iter()
+                .map(|user| UserSummary {
+                    user_id: user.id,
+                    username: user.username,
+                    email: user.email,
+                })
+                .collect(),
+        }))
+    }
+
+    async fn create_admin(
+        &self,
+        request: Request<CreateAdminRequest>,
+    ) -> Result<Response<CreateAdminResponse>, Status> {
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        // Field rules are checked by the validation layer (see user.proto)
+        if is_email(&req.username) {
+            return Err(error::invalid_argument(
+                "INVALID_USERNAME",
+                "Username cannot contain '@'",
+            ));
+        }
+
+        let user_id = self
+            .insert_user(&req.username, &req.email, &req.password, Role::Admin)
+            .await?;
+
+        info!("Admin created: {} ({})", req.username, user_id);
+        Ok(Response::new(CreateAdminResponse {
+            success: true,
+            message: "Admin created successfully".to_string(),
+            user_id,
+        }))
+    }
+
+    async fn get_guest_identity(
+        &self,
+        request: Request<GetGuestIdentityRequest>,
+    ) -> Result<Response<GetGuestIdentityResponse>, Status> {
+        // Guest checkout is the order service's; customers don't see guests
+        Caller::from_metadata(request.metadata())?.require_platform()?;
+        let req = request.into_inner();
+
+        // Field rules are checked by the validation layer (see user.proto)
+        let email = normalize_email(&req.email);
+        let user_id = self
+            .repository
+            .guest_identity(&email, req.create)
+            .await?
+            .unwrap_or_default();
+
+        Ok(Response::new(GetGuestIdentityResponse {
+            success: true,
+            message: if user_id.is_empty() {
+                "No guest identity for this email".to_string()
+            } else {
+                "Guest identity retrieved successfully".to_string()
+            },
+            user_id,
+        }))
+    }
+
+    async fn confirm_email(
+        &self,
+        request: Request<ConfirmEmailRequest>,
+    ) -> Result<Response<ConfirmEmailResponse>, Status> {
+        let caller = Caller::from_metadata(request.metadata())?;
+        let req = request.into_inner();
+        caller.require_owner(&req.user_id)?;
+
+        // Field rules are checked by the validation layer (see user.proto)
+        let Ok(email) = auth::decode_email_token(&req.email_token) else {
+            return Err(error::invalid_argument(
+                "INVALID_EMAIL_TOKEN",
+                "Invalid or expired email token",
+            ));
+        };
+        let user = match self
+            .repository
+            .verify_email(&req.user_id, &normalize_email(&email))
+            .await?
+        {
+            Some(user) => user,
+            None => {
+                warn!("Email confirmation failed for user: {}", req.user_id);
+                return Err(error::failed_precondition(
+                    "EMAIL_MISMATCH",
+                    "The token is not for this user's email address",
+                ));
+            }
+        };
+
+        self.cache
+            .invalidate_prefix("/user.UserService/GetUserProfile");
+        self.users.invalidate(&[&req.user_id]).await;
+
+        info!("Email confirmed for user: {}", req.user_id);
+        Ok(Response::new(ConfirmEmailResponse {
+            success: true,
+            message: "Email address confirmed".to_string(),
+            user: Some(self.db_user_to_proto(&user)),
+        }))
+    }
 }